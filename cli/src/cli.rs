@@ -29,12 +29,18 @@ pub enum Command {
     /// Type-check files without running
     Check(CheckArgs),
 
+    /// Report warnings and errors without running
+    Lint(LintArgs),
+
     /// Format Melbi files
     Fmt(FmtArgs),
 
     /// Start interactive REPL
     Repl(ReplArgs),
 
+    /// Start the language server on stdin and stdout
+    Lsp,
+
     /// Generate shell completions
     Completions(CompletionsArgs),
 
@@ -86,6 +92,26 @@ pub struct CheckArgs {
     /// Suppress all output, only set exit code
     #[arg(short, long)]
     pub quiet: bool,
+
+    /// Print diagnostics as JSON, one line per file
+    #[arg(long)]
+    pub json: bool,
+}
+
+/// Arguments for the `lint` command.
+#[derive(Args, Debug)]
+pub struct LintArgs {
+    /// Melbi files to lint (use '-' for stdin)
+    #[arg(required = true)]
+    pub files: Vec<String>,
+
+    /// Exit with an error if any warnings are reported
+    #[arg(long)]
+    pub deny_warnings: bool,
+
+    /// Print diagnostics as JSON, one line per file
+    #[arg(long)]
+    pub json: bool,
 }
 
 /// Arguments for the `fmt` command.
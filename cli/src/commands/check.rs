@@ -7,6 +7,7 @@ use melbi::{RenderConfig, render_error_to};
 use melbi_core::{analyzer::analyze, parser, types::manager::TypeManager};
 
 use crate::cli::CheckArgs;
+use crate::common::diagnostics::{error_diagnostics, print_json};
 use crate::common::engine::build_stdlib;
 use crate::common::input::read_input;
 
@@ -15,7 +16,7 @@ pub fn run(args: CheckArgs, no_color: bool) -> ExitCode {
     let mut has_errors = false;
 
     for file in &args.files {
-        if !check_file(file, args.quiet, no_color) {
+        if !check_file(file, args.quiet, args.json, no_color) {
             has_errors = true;
         }
     }
@@ -28,7 +29,7 @@ pub fn run(args: CheckArgs, no_color: bool) -> ExitCode {
 }
 
 /// Check a single file. Returns true if OK, false if errors.
-fn check_file(path: &str, quiet: bool, no_color: bool) -> bool {
+fn check_file(path: &str, quiet: bool, json: bool, no_color: bool) -> bool {
     let (content, display_name) = match read_input(path) {
         Ok(c) => c,
         Err(e) => {
@@ -45,7 +46,12 @@ fn check_file(path: &str, quiet: bool, no_color: bool) -> bool {
         ..Default::default()
     };
     let render_err = |e: melbi::Error| {
-        if !quiet {
+        if quiet {
+            return;
+        }
+        if json {
+            print_json(&display_name, &error_diagnostics(e));
+        } else {
             render_error_to(&e, &mut std::io::stderr(), &config).ok();
         }
     };
@@ -70,7 +76,11 @@ fn check_file(path: &str, quiet: bool, no_color: bool) -> bool {
     }
 
     if !quiet {
-        println!("{}: OK", display_name);
+        if json {
+            print_json(&display_name, &[]);
+        } else {
+            println!("{}: OK", display_name);
+        }
     }
     true
 }
@@ -6,6 +6,7 @@ use bumpalo::Bump;
 use melbi::{RenderConfig, render_error_to};
 use melbi_core::{
     analyzer::analyze,
+    api::CompileOptions,
     compiler::BytecodeCompiler,
     parser,
     types::manager::TypeManager,
@@ -97,7 +98,13 @@ fn run_bytecode(args: DebugInputArgs, no_color: bool) -> ExitCode {
         }
     };
 
-    let bytecode = match BytecodeCompiler::compile(type_manager, &arena, globals_values, &typed) {
+    let bytecode = match BytecodeCompiler::compile(
+        type_manager,
+        &arena,
+        globals_values,
+        &typed,
+        &CompileOptions::default(),
+    ) {
         Ok(code) => code,
         Err(e) => {
             render_err(e.into(), no_color);
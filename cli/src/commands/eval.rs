@@ -7,6 +7,7 @@ use bumpalo::Bump;
 use melbi::{RenderConfig, render_error_to};
 use melbi_core::{
     analyzer::analyze,
+    api::CompileOptions,
     compiler::BytecodeCompiler,
     evaluator::{Evaluator, EvaluatorOptions, ExecutionError},
     parser,
@@ -103,8 +104,13 @@ pub fn interpret_input<'types>(
 
     // VM
     if run_vm {
-        let bytecode = match BytecodeCompiler::compile(type_manager, &arena, globals_values, &typed)
-        {
+        let bytecode = match BytecodeCompiler::compile(
+            type_manager,
+            &arena,
+            globals_values,
+            &typed,
+            &CompileOptions::default(),
+        ) {
             Ok(code) => code,
             Err(e) => {
                 render_err(e.into());
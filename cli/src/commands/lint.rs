@@ -0,0 +1,97 @@
+//! The `lint` command - report analyzer warnings and errors without running.
+
+use std::process::ExitCode;
+
+use bumpalo::Bump;
+use melbi::{RenderConfig, render_error_to};
+use melbi_core::{
+    analyzer::{analyze, lint},
+    parser,
+    types::manager::TypeManager,
+};
+
+use crate::cli::LintArgs;
+use crate::common::diagnostics::{error_diagnostics, print_json};
+use crate::common::engine::build_stdlib;
+use crate::common::input::read_input;
+
+/// Run the lint command.
+pub fn run(args: LintArgs, no_color: bool) -> ExitCode {
+    let mut failed = false;
+
+    for file in &args.files {
+        if !lint_file(file, args.deny_warnings, args.json, no_color) {
+            failed = true;
+        }
+    }
+
+    if failed {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+/// Lint a single file. Returns false if it has errors, or warnings when
+/// `deny_warnings` is set.
+fn lint_file(path: &str, deny_warnings: bool, json: bool, no_color: bool) -> bool {
+    let (content, display_name) = match read_input(path) {
+        Ok(c) => c,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            return false;
+        }
+    };
+
+    let config = RenderConfig {
+        color: !no_color,
+        filename: Some(&display_name),
+        ..Default::default()
+    };
+    let render_err = |e: melbi::Error| {
+        if json {
+            print_json(&display_name, &error_diagnostics(e));
+        } else {
+            render_error_to(&e, &mut std::io::stderr(), &config).ok();
+        }
+    };
+
+    let arena = Bump::new();
+    let type_manager = TypeManager::new(&arena);
+    let (globals_types, _globals_values) = build_stdlib(&arena, type_manager);
+
+    // Parse
+    let ast = match parser::parse(&arena, &content) {
+        Ok(ast) => ast,
+        Err(e) => {
+            render_err(e.into());
+            return false;
+        }
+    };
+
+    // Type check
+    let typed = match analyze(type_manager, &arena, ast, globals_types, &[]) {
+        Ok(typed) => typed,
+        Err(e) => {
+            render_err(e.into());
+            return false;
+        }
+    };
+
+    // Lint
+    let warnings = lint(typed);
+    if warnings.is_empty() {
+        if json {
+            print_json(&display_name, &[]);
+        } else {
+            println!("{}: OK", display_name);
+        }
+        return true;
+    }
+    render_err(melbi::Error::Compilation {
+        diagnostics: warnings,
+        source: content.clone(),
+        filename: Some(display_name.clone()),
+    });
+    !deny_warnings
+}
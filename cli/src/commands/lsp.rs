@@ -0,0 +1,9 @@
+//! The `lsp` command - serve editors over the Language Server Protocol.
+
+use std::process::ExitCode;
+
+/// Run the language server until the client exits.
+pub fn run() -> ExitCode {
+    melbi_lsp::server::run_stdio();
+    ExitCode::SUCCESS
+}
@@ -8,5 +8,7 @@ pub mod completions;
 pub mod debug;
 pub mod eval;
 pub mod fmt;
+pub mod lint;
+pub mod lsp;
 pub mod repl;
 pub mod run;
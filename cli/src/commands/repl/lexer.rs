@@ -64,11 +64,7 @@ pub fn calculate_depth(buffer: &str) -> Option<usize> {
 
     for token_res in Token::lexer(buffer) {
         match token_res {
-            Ok(Token::LBrace) | Ok(Token::LBracket) | Ok(Token::LParen) => depth += 1,
-            Ok(Token::RBrace) | Ok(Token::RBracket) | Ok(Token::RParen) => depth -= 1,
-
-            // Valid tokens that don't affect depth
-            Ok(_) => {}
+            Ok(token) => depth += depth_change(&token),
 
             // STRICT BEHAVIOR:
             // If we hit an unclosed string (or any unknown char), abort immediately.
@@ -84,3 +80,64 @@ pub fn calculate_depth(buffer: &str) -> Option<usize> {
         Some(depth as usize)
     }
 }
+
+/// How much `token` changes the nesting depth.
+fn depth_change(token: &Token) -> isize {
+    match token {
+        Token::LBrace | Token::LBracket | Token::LParen => 1,
+        Token::RBrace | Token::RBracket | Token::RParen => -1,
+        // Valid tokens that don't affect depth
+        _ => 0,
+    }
+}
+
+/// Re-indents in-progress input using delimiter depth.
+///
+/// Unlike the formatter, this works on input that doesn't parse yet (e.g. a
+/// half-written `where {` block), so editors can indent as the user types.
+/// Each line is indented by four spaces per enclosing delimiter, and a line
+/// starting with closing delimiters is dedented once for each of them.
+/// Blank lines are emptied, except the last one, which gets the indentation
+/// for the next line to type. Lines that continue a multi-line string are
+/// left unchanged.
+pub fn reformat_incomplete(buffer: &str) -> String {
+    let mut result = String::with_capacity(buffer.len());
+    // The buffer is lexed once, carrying forward what `calculate_depth` would
+    // find for everything before the current line
+    let mut tokens = Token::lexer(buffer).spanned().peekable();
+    let mut depth: isize = 0;
+    let mut lexed = true;
+    let mut lexed_until = 0;
+    let mut line_start = 0;
+    let line_count = buffer.split('\n').count();
+    for (index, line) in buffer.split('\n').enumerate() {
+        if index > 0 {
+            result.push('\n');
+        }
+        while let Some((token_res, span)) = tokens.next_if(|(_, span)| span.start < line_start) {
+            match token_res {
+                Ok(token) => depth += depth_change(&token),
+                Err(_) => lexed = false,
+            }
+            lexed_until = span.end;
+        }
+        // A token running past the start of the line is a multi-line string
+        if lexed && lexed_until <= line_start {
+            let depth = depth.max(0) as usize;
+            let content = line.trim_start();
+            let closing = content
+                .chars()
+                .take_while(|c| matches!(c, '}' | ']' | ')'))
+                .count();
+            if !content.is_empty() || index + 1 == line_count {
+                result.push_str(&"    ".repeat(depth.saturating_sub(closing)));
+            }
+            result.push_str(content);
+        } else {
+            // Inside a string (or after unlexable input): nothing to go by
+            result.push_str(line);
+        }
+        line_start += line.len() + 1;
+    }
+    result
+}
@@ -0,0 +1,44 @@
+//! JSON diagnostic output for the `--json` flag.
+
+use melbi::{Diagnostic, Error, Severity};
+use melbi_core::diagnostics::json::JsonDiagnostic;
+use melbi_core::parser::Span;
+use serde::Serialize;
+
+/// One file's diagnostics, as printed by [`print_json`].
+#[derive(Serialize)]
+struct FileDiagnostics<'a> {
+    file: &'a str,
+    diagnostics: Vec<JsonDiagnostic>,
+}
+
+/// Print one line with a file's diagnostics as JSON:
+/// `{"file": ..., "diagnostics": [...]}`, each diagnostic in the
+/// `melbi_core::diagnostics::json` schema.
+pub fn print_json(file: &str, diagnostics: &[Diagnostic]) {
+    let out = FileDiagnostics {
+        file,
+        diagnostics: diagnostics.iter().map(Diagnostic::to_json).collect(),
+    };
+    println!(
+        "{}",
+        serde_json::to_string(&out).expect("diagnostics serialize to JSON")
+    );
+}
+
+/// The diagnostics carried by `error`; errors without a source location
+/// become a single diagnostic spanning the start of the file.
+pub fn error_diagnostics(error: Error) -> Vec<Diagnostic> {
+    match error {
+        Error::Compilation { diagnostics, .. } => diagnostics,
+        Error::Runtime { diagnostic, .. } => vec![diagnostic],
+        other => vec![Diagnostic {
+            severity: Severity::Error,
+            message: other.to_string(),
+            span: Span::new(0, 0),
+            related: Vec::new(),
+            help: Vec::new(),
+            code: None,
+        }],
+    }
+}
@@ -1,5 +1,6 @@
 //! Common utilities shared across CLI commands.
 
+pub mod diagnostics;
 pub mod engine;
 pub mod input;
 pub mod panic;
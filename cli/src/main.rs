@@ -46,8 +46,10 @@ fn main() -> ExitCode {
         Command::Eval(args) => commands::eval::run(args, cli.no_color),
         Command::Run(args) => commands::run::run(args, cli.no_color),
         Command::Check(args) => commands::check::run(args, cli.no_color),
+        Command::Lint(args) => commands::lint::run(args, cli.no_color),
         Command::Fmt(args) => commands::fmt::run(args, cli.no_color),
         Command::Repl(args) => commands::repl::run(args, cli.no_color),
+        Command::Lsp => commands::lsp::run(),
         Command::Completions(args) => commands::completions::run(args),
         Command::Bug => commands::bug::run(),
         Command::Debug(args) => commands::debug::run(args, cli.no_color),
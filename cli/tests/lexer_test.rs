@@ -1,5 +1,5 @@
 use logos::Logos;
-use melbi_cli::commands::repl::lexer::{Token, calculate_depth};
+use melbi_cli::commands::repl::lexer::{Token, calculate_depth, reformat_incomplete};
 
 #[test]
 fn test_lexer_brackets() {
@@ -148,3 +148,49 @@ fn test_calculate_depth_nested() {
     assert_eq!(calculate_depth("({["), Some(3));
     assert_eq!(calculate_depth(")]}"), Some(0)); // Negative depth at end should be 0
 }
+
+#[test]
+fn test_reformat_incomplete_indents_after_open_where() {
+    assert_eq!(
+        reformat_incomplete("a + b where {\na = 1,\nb = [\n2,\n"),
+        "a + b where {\n    a = 1,\n    b = [\n        2,\n        "
+    );
+}
+
+#[test]
+fn test_reformat_incomplete_dedents_closing_brace() {
+    assert_eq!(
+        reformat_incomplete("x where {\n        x = 1,\n  }"),
+        "x where {\n    x = 1,\n}"
+    );
+    assert_eq!(
+        reformat_incomplete("f({\na = [\n1\n]})"),
+        "f({\n        a = [\n            1\n]})"
+    );
+}
+
+#[test]
+fn test_reformat_incomplete_keeps_multiline_strings() {
+    assert_eq!(
+        reformat_incomplete("{\ns = \"line\n  kept\""),
+        "{\n    s = \"line\n  kept\""
+    );
+}
+
+#[test]
+fn test_reformat_incomplete_resumes_after_multiline_strings() {
+    assert_eq!(
+        reformat_incomplete("{\ns = \"a\nb\",\nt = 1\n}"),
+        "{\n    s = \"a\nb\",\n    t = 1\n}"
+    );
+    // Nothing after unlexable input is reindented
+    assert_eq!(reformat_incomplete("`a b`\n{\nx"), "`a b`\n{\nx");
+}
+
+#[test]
+fn test_reformat_incomplete_long_input() {
+    let lines = 100_000;
+    let input = format!("[\n{}]", "x,\n".repeat(lines));
+    let expected = format!("[\n{}]", "    x,\n".repeat(lines));
+    assert_eq!(reformat_incomplete(&input), expected);
+}
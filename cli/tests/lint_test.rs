@@ -0,0 +1,81 @@
+//! Integration tests for the `lint` command.
+
+mod common;
+
+use common::{melbi, temp_file};
+use predicates::prelude::*;
+
+#[test]
+fn lint_clean_file() {
+    let file = temp_file("x + 1 where { x = 1 }");
+
+    melbi()
+        .args(["lint", file.path().to_str().unwrap()])
+        .assert()
+        .success()
+        .stdout(predicate::str::ends_with(": OK\n"));
+}
+
+#[test]
+fn lint_unused_binding_warns() {
+    let file = temp_file("x where { x = 1, unused = 2 }");
+
+    melbi()
+        .args(["--no-color", "lint", file.path().to_str().unwrap()])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("Warning"))
+        .stderr(predicate::str::contains("Unused binding `unused`"));
+}
+
+#[test]
+fn lint_unused_binding_deny_warnings() {
+    let file = temp_file("x where { x = 1, unused = 2 }");
+
+    melbi()
+        .args([
+            "--no-color",
+            "lint",
+            "--deny-warnings",
+            file.path().to_str().unwrap(),
+        ])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Unused binding `unused`"));
+}
+
+#[test]
+fn lint_type_error() {
+    let file = temp_file("1 + true");
+
+    melbi()
+        .args(["lint", file.path().to_str().unwrap()])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Type mismatch"));
+}
+
+#[test]
+fn lint_json_reports_diagnostics() {
+    let file = temp_file("x where { x = 1, unused = 2 }");
+
+    melbi()
+        .args(["lint", "--json", file.path().to_str().unwrap()])
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with(r#"{"file":"#))
+        .stdout(predicate::str::contains(
+            r#""diagnostics":[{"severity":"warning","message":"Unused binding `unused`","code":"W002","#,
+        ))
+        .stdout(predicate::str::contains(r#""related":[],"suggestions":[]}]}"#));
+}
+
+#[test]
+fn lint_json_clean_file() {
+    melbi()
+        .args(["lint", "--json", "-"])
+        .write_stdin("x + 1 where { x = 1 }")
+        .assert()
+        .success()
+        .stdout(r#"{"file":"<stdin>","diagnostics":[]}"#.to_owned() + "\n");
+}
@@ -0,0 +1,98 @@
+//! Integration tests for the `lsp` command.
+
+use std::io::{BufRead, BufReader, Read, Write};
+use std::process::{ChildStdin, ChildStdout, Command, Stdio};
+
+/// Send one JSON-RPC message with its `Content-Length` header.
+fn send(stdin: &mut ChildStdin, message: &str) {
+    write!(
+        stdin,
+        "Content-Length: {}\r\n\r\n{}",
+        message.len(),
+        message
+    )
+    .unwrap();
+    stdin.flush().unwrap();
+}
+
+/// Read one JSON-RPC message.
+fn receive(stdout: &mut BufReader<ChildStdout>) -> String {
+    let mut length = 0;
+    loop {
+        let mut header = String::new();
+        stdout.read_line(&mut header).unwrap();
+        let header = header.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if let Some(value) = header.strip_prefix("Content-Length: ") {
+            length = value.parse().unwrap();
+        }
+    }
+    let mut body = vec![0; length];
+    stdout.read_exact(&mut body).unwrap();
+    String::from_utf8(body).unwrap()
+}
+
+/// Read messages until one contains `needle`, skipping logs and other
+/// notifications.
+fn receive_until(stdout: &mut BufReader<ChildStdout>, needle: &str) -> String {
+    loop {
+        let message = receive(stdout);
+        if message.contains(needle) {
+            return message;
+        }
+    }
+}
+
+#[test]
+fn lsp_hover_reports_type() {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_melbi"))
+        .arg("lsp")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    let mut stdin = child.stdin.take().unwrap();
+    let mut stdout = BufReader::new(child.stdout.take().unwrap());
+
+    send(
+        &mut stdin,
+        r#"{"jsonrpc":"2.0","id":1,"method":"initialize","params":{"capabilities":{}}}"#,
+    );
+    let initialized = receive_until(&mut stdout, r#""id":1"#);
+    assert!(initialized.contains(r#""hoverProvider":true"#));
+    send(
+        &mut stdin,
+        r#"{"jsonrpc":"2.0","method":"initialized","params":{}}"#,
+    );
+
+    // Wait for the diagnostics so the document is analyzed before hovering
+    send(
+        &mut stdin,
+        r#"{"jsonrpc":"2.0","method":"textDocument/didOpen","params":{"textDocument":{"uri":"file:///test.melbi","languageId":"melbi","version":1,"text":"x where { x = 42 }"}}}"#,
+    );
+    let diagnostics = receive_until(&mut stdout, "textDocument/publishDiagnostics");
+    assert!(diagnostics.contains(r#""diagnostics":[]"#));
+
+    // Hover over the `x` at offset 0
+    send(
+        &mut stdin,
+        r#"{"jsonrpc":"2.0","id":2,"method":"textDocument/hover","params":{"textDocument":{"uri":"file:///test.melbi"},"position":{"line":0,"character":0}}}"#,
+    );
+    let hover = receive_until(&mut stdout, r#""id":2"#);
+    assert!(
+        hover.contains(r#""value":"```melbi\nInt\n```""#),
+        "unexpected hover response: {}",
+        hover
+    );
+
+    send(
+        &mut stdin,
+        r#"{"jsonrpc":"2.0","id":3,"method":"shutdown"}"#,
+    );
+    receive_until(&mut stdout, r#""id":3"#);
+    send(&mut stdin, r#"{"jsonrpc":"2.0","method":"exit"}"#);
+    drop(stdin);
+    assert!(child.wait().unwrap().success());
+}
@@ -0,0 +1,57 @@
+//! Benchmarks for the Melbi type analyzer.
+//!
+//! Run with: `cargo bench --bench analyzer` in the core/ directory.
+//!
+//! Benchmark groups:
+//! 1. where_heavy: Parsing and analysis of deeply nested `where` expressions, each binding
+//!    a lambda, which exercises scope stack growth
+
+use bumpalo::Bump;
+use criterion::{BenchmarkId, Criterion, Throughput, black_box, criterion_group, criterion_main};
+use melbi_core::{analyzer, parser, types::manager::TypeManager};
+use pprof::criterion::{Output, PProfProfiler};
+
+/// Generate `n` nested `where` expressions, each binding a value and a lambda.
+fn generate_where_heavy(n: usize) -> String {
+    let mut expr = String::from("0");
+    for i in 0..n {
+        expr = format!("(f{i}(v{i}) where {{ v{i} = {expr}, f{i} = (x) => x + v{i} }})");
+    }
+    expr
+}
+
+/// Benchmark: Parse + analyze.
+///
+/// Analysis allocates into the parse arena, so each iteration parses into a
+/// fresh arena to keep memory flat; parsing is linear and cheap in comparison.
+fn bench_where_heavy(c: &mut Criterion) {
+    let mut group = c.benchmark_group("where_heavy");
+
+    // Sizes chosen to stay under the parser's default depth limit
+    for size in [10, 20, 40, 80] {
+        group.throughput(Throughput::Elements(size as u64));
+
+        group.bench_with_input(BenchmarkId::from_parameter(size), &size, |b, &size| {
+            let source = generate_where_heavy(size);
+
+            b.iter(|| {
+                let arena = Bump::new();
+                let type_manager = TypeManager::new(&arena);
+                let parsed = parser::parse(&arena, black_box(&source)).expect("Parse failed");
+                let typed = analyzer::analyze(type_manager, &arena, &parsed, &[], &[])
+                    .expect("Analysis failed");
+                black_box(typed);
+            });
+        });
+    }
+
+    group.finish();
+}
+
+// Configure Criterion with profiling support
+criterion_group! {
+    name = benches;
+    config = Criterion::default().with_profiler(PProfProfiler::new(100, Output::Flamegraph(None)));
+    targets = bench_where_heavy
+}
+criterion_main!(benches);
@@ -211,6 +211,8 @@ fn bench_vm_only(c: &mut Criterion) {
                 num_locals: 0,
                 max_stack_size: 2,
                 lambdas: vec![],
+                spans: vec![],
+                record_field_orders: vec![],
             };
 
             // Benchmark: VM execution only
@@ -227,6 +229,47 @@ fn bench_vm_only(c: &mut Criterion) {
     group.finish();
 }
 
+/// Benchmark: VM execution of short programs at various stack depths.
+///
+/// Runs right-nested addition `1 + (1 + (1 + ...))`, which keeps `depth` values
+/// on the stack. Programs are short, so creating the VM (and its stack) is a
+/// large part of each run; depths up to the stack's inline capacity need no
+/// heap allocation for the stack.
+fn bench_vm_stack_depth(c: &mut Criterion) {
+    let mut group = c.benchmark_group("vm_stack_depth");
+
+    for depth in [2, 4, 8, 16, 64] {
+        group.bench_with_input(BenchmarkId::from_parameter(depth), &depth, |b, &depth| {
+            use Instruction::*;
+
+            let mut instructions = vec![ConstInt(1); depth];
+            instructions.extend(vec![IntBinOp(b'+'); depth - 1]);
+            instructions.push(Return);
+
+            let code = Code {
+                constants: vec![],
+                adapters: vec![],
+                generic_adapters: vec![],
+                instructions,
+                num_locals: 0,
+                max_stack_size: depth,
+                lambdas: vec![],
+                spans: vec![],
+                record_field_orders: vec![],
+            };
+
+            let arena = Bump::new();
+            b.iter(|| {
+                let mut vm = VM::new(black_box(&arena), black_box(&code), vec![], &[]);
+                let result = vm.run().expect("VM execution failed");
+                black_box(result.as_int_unchecked())
+            });
+        });
+    }
+
+    group.finish();
+}
+
 /// Benchmark: Raw Rust addition baseline.
 ///
 /// Measures native Rust performance for the same addition chain.
@@ -256,6 +299,6 @@ fn bench_rust_baseline(c: &mut Criterion) {
 criterion_group! {
     name = benches;
     config = Criterion::default().with_profiler(PProfProfiler::new(100, Output::Flamegraph(None)));
-    targets = bench_eval_only, bench_full_pipeline, bench_cel_comparison, bench_cel_full_pipeline, bench_vm_only, bench_rust_baseline
+    targets = bench_eval_only, bench_full_pipeline, bench_cel_comparison, bench_cel_full_pipeline, bench_vm_only, bench_vm_stack_depth, bench_rust_baseline
 }
 criterion_main!(benches);
@@ -0,0 +1,224 @@
+//! Detection of ambiguous types for strict inference.
+//!
+//! By default, a type the expression does not pin down stays a type variable:
+//! `[]` is an `Array[_0]` and evaluates to an empty array all the same. With
+//! `CompileOptions::strict_inference`, such leftovers are rejected so that a
+//! host never depends on a type the compiler had to guess.
+//!
+//! Numeric literals are never ambiguous (`1` is an `Int`, `1.0` a `Float`), so
+//! ambiguity only comes from empty collections, `none`, and values that are
+//! never used at a concrete type.
+//!
+//! One ambiguity is rejected even without strict inference: `sum` of an array
+//! whose element type is unknown, as in `sum([])`, has no zero to return.
+
+use crate::{
+    Box, ToString, Vec,
+    analyzer::{
+        error::{TypeError, TypeErrorKind},
+        typed_expr::{Expr, ExprInner, ExprVisitor, TypedExpr},
+    },
+    format,
+    parser::Span,
+    types::{
+        Type,
+        traits::{ClosureVisitor, TypeVisitor},
+    },
+};
+
+/// Reject an expression whose types are not all fully determined.
+///
+/// Lambdas are skipped: a polymorphic lambda's type variables are resolved at
+/// each call. Other function-typed subexpressions (like `Array.Len`) and
+/// packages of functions (like `Array`) are resolved by their arguments, so
+/// only the arguments are checked, except for the expression's own result
+/// type.
+pub fn check_ambiguity<'types, 'arena>(
+    typed: &TypedExpr<'types, 'arena>,
+) -> Result<(), Box<TypeError>> {
+    let root = typed.expr;
+    let mut finder = AmbiguityFinder { typed, error: None };
+    finder.visit(root);
+    if let Some(error) = finder.error {
+        return Err(Box::new(error));
+    }
+    if has_type_var(root.0) {
+        return Err(Box::new(ambiguous_type_error(typed, root)));
+    }
+    Ok(())
+}
+
+fn ambiguous_type_error<'types, 'arena>(
+    typed: &TypedExpr<'types, 'arena>,
+    expr: &Expr<'types, 'arena>,
+) -> TypeError {
+    TypeError::new(
+        TypeErrorKind::AmbiguousType {
+            ty: format!("{}", expr.0),
+        },
+        typed.ann.source.to_string(),
+        typed.ann.span_of(expr).unwrap_or(Span(0..0)),
+    )
+}
+
+/// Finds the innermost, leftmost subexpression with an ambiguous type outside
+/// of function types.
+struct AmbiguityFinder<'a, 'types, 'arena> {
+    typed: &'a TypedExpr<'types, 'arena>,
+    error: Option<TypeError>,
+}
+
+impl<'a, 'types, 'arena> ExprVisitor<'types, 'arena> for AmbiguityFinder<'a, 'types, 'arena> {
+    fn visit(&mut self, expr: &Expr<'types, 'arena>) {
+        if self.error.is_some() || matches!(expr.1, ExprInner::Lambda { .. }) {
+            return;
+        }
+        self.visit_children(expr);
+        if self.error.is_none() && has_value_type_var(expr.0) {
+            self.error = Some(ambiguous_type_error(self.typed, expr));
+        }
+    }
+}
+
+fn has_type_var(ty: &Type<'_>) -> bool {
+    let mut found = false;
+    ClosureVisitor::new(|ty: &Type<'_>| {
+        if matches!(ty, Type::TypeVar(_)) {
+            found = true;
+        }
+        found
+    })
+    .visit(ty);
+    found
+}
+
+/// Whether `ty` has a type variable outside of function types, as in `[]` but
+/// not in `Array.Len` or in the `Array` package.
+fn has_value_type_var(ty: &Type<'_>) -> bool {
+    let mut found = false;
+    ClosureVisitor::new(|ty: &Type<'_>| match ty {
+        Type::Function { .. } => true,
+        Type::TypeVar(_) => {
+            found = true;
+            true
+        }
+        _ => found,
+    })
+    .visit(ty);
+    found
+}
+
+/// Reject a call to the builtin `sum` on an array whose element type nothing
+/// pins down, like `sum([])`: its result would be the zero of an unknown type.
+///
+/// `is_bound` tells whether a name is bound outside the expression (as a
+/// global or a parameter), shadowing the builtin. Inside a lambda, element
+/// types that appear in the lambda's type are resolved at each call, as in
+/// `(xs) => sum(xs)`, so they are not ambiguous.
+pub fn check_sums<'types, 'arena>(
+    typed: &TypedExpr<'types, 'arena>,
+    is_bound: impl Fn(&str) -> bool,
+) -> Result<(), Box<TypeError>> {
+    let mut checker = SumChecker {
+        typed,
+        is_bound,
+        locals: Vec::new(),
+        lambda_types: Vec::new(),
+        error: None,
+    };
+    checker.visit(typed.expr);
+    match checker.error {
+        Some(error) => Err(Box::new(error)),
+        None => Ok(()),
+    }
+}
+
+struct SumChecker<'a, 'types, 'arena, F> {
+    typed: &'a TypedExpr<'types, 'arena>,
+    is_bound: F,
+    /// Names bound by the enclosing lambdas, `where` bindings, and match arms.
+    locals: Vec<&'arena str>,
+    /// Types of the enclosing lambdas.
+    lambda_types: Vec<&'types Type<'types>>,
+    error: Option<TypeError>,
+}
+
+impl<'a, 'types, 'arena, F: Fn(&str) -> bool> ExprVisitor<'types, 'arena>
+    for SumChecker<'a, 'types, 'arena, F>
+{
+    fn visit(&mut self, expr: &Expr<'types, 'arena>) {
+        if self.error.is_some() {
+            return;
+        }
+        match &expr.1 {
+            ExprInner::Call { callable, args } if self.is_builtin_sum(callable) => {
+                if let [array] = args[..] {
+                    self.check_sum(array);
+                }
+            }
+            ExprInner::Lambda { .. } => {
+                self.lambda_types.push(expr.0);
+                self.visit_children(expr);
+                self.lambda_types.pop();
+                return;
+            }
+            _ => {}
+        }
+        self.visit_children(expr);
+    }
+
+    fn visit_scoped(
+        &mut self,
+        names: impl Iterator<Item = &'arena str>,
+        expr: &Expr<'types, 'arena>,
+    ) {
+        let depth = self.locals.len();
+        self.locals.extend(names);
+        self.visit(expr);
+        self.locals.truncate(depth);
+    }
+}
+
+impl<'a, 'types, 'arena, F: Fn(&str) -> bool> SumChecker<'a, 'types, 'arena, F> {
+    fn is_builtin_sum(&self, callable: &Expr<'types, 'arena>) -> bool {
+        match callable.1 {
+            ExprInner::Ident(name) => {
+                name == "sum" && !self.locals.contains(&name) && !(self.is_bound)(name)
+            }
+            _ => false,
+        }
+    }
+
+    fn check_sum(&mut self, array: &Expr<'types, 'arena>) {
+        let Type::Array(Type::TypeVar(id)) = array.0 else {
+            return;
+        };
+        if self
+            .lambda_types
+            .iter()
+            .any(|lambda_ty| has_the_type_var(lambda_ty, *id))
+        {
+            return;
+        }
+        self.error = Some(TypeError::new(
+            TypeErrorKind::AmbiguousSum {
+                ty: format!("{}", array.0),
+            },
+            self.typed.ann.source.to_string(),
+            self.typed.ann.span_of(array).unwrap_or(Span(0..0)),
+        ));
+    }
+}
+
+/// Whether `ty` mentions the type variable `id`.
+fn has_the_type_var(ty: &Type<'_>, id: u16) -> bool {
+    let mut found = false;
+    ClosureVisitor::new(|ty: &Type<'_>| {
+        if matches!(ty, Type::TypeVar(var) if *var == id) {
+            found = true;
+        }
+        found
+    })
+    .visit(ty);
+    found
+}
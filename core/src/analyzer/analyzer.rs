@@ -1,4 +1,4 @@
-use alloc::collections::BTreeSet;
+use alloc::collections::{BTreeSet, VecDeque};
 use alloc::rc::Rc;
 use alloc::string::ToString;
 use bumpalo::Bump;
@@ -6,19 +6,22 @@ use core::cell::RefCell;
 
 use crate::{
     String, Vec,
+    analyzer::ambiguity,
     analyzer::error::{TypeError, TypeErrorKind},
     analyzer::typed_expr::{self as typed_expr, Expr, ExprInner, LambdaInstantiations, TypedExpr},
     casting, format,
     parser::{self, BinaryOp, ComparisonOp, Span, UnaryOp},
     scope_stack::{self, ScopeStack},
+    stdlib::builtins,
     types::{
         Type, TypeClassResolver, TypeScheme,
         manager::TypeManager,
-        traits::{TypeKind, TypeView},
+        traits::{TypeKind, TypeView, display_type_with_var_names},
         type_expr_to_type,
         unification::Unification,
     },
     values::dynamic::Value,
+    vec,
 };
 use hashbrown::DefaultHashBuilder;
 
@@ -36,56 +39,84 @@ pub fn analyze<'types, 'arena>(
         "Starting type analysis"
     );
 
-    // Create annotation map for typed expressions
-    // We reuse the same source string since both ParsedExpr and TypedExpr are in the same arena
-    let typed_ann = arena.alloc(parser::AnnotatedSource::new(arena, expr.ann.source));
-
-    let mut analyzer = Analyzer {
+    analyze_with_scope_capacity(
         type_manager,
         arena,
-        scope_stack: ScopeStack::new(),
-        unification: Unification::new(type_manager),
-        type_class_resolver: TypeClassResolver::new(),
-        parsed_ann: expr.ann,
-        typed_ann,
-        current_span: None, // Initialize to None
-        env_vars_stack: Vec::new(),
-        polymorphic_lambdas: hashbrown::HashMap::new(),
-        pending_instantiations: hashbrown::HashMap::new(),
-    };
+        expr,
+        globals,
+        variables,
+        expr.expr.scope_depth(),
+        None,
+    )
+}
 
-    // Push globals scope (constants, packages, functions)
-    if !globals.is_empty() {
-        // Wrap each type in a monomorphic TypeScheme
-        // TODO: Accept TypeScheme as an argument.
-        let bindings: Vec<(&'arena str, TypeScheme<'types, 'arena>)> = globals
-            .iter()
-            .map(|(name, ty)| {
-                let empty_quantified = type_manager.alloc_u16_slice(&[]);
-                (*name, TypeScheme::new(empty_quantified, ty))
-            })
-            .collect();
-        let bindings_slice = arena.alloc_slice_fill_iter(bindings.into_iter());
-        analyzer
-            .scope_stack
-            .push(scope_stack::CompleteScope::from_sorted(bindings_slice));
-    }
+/// Types inferred for the identifiers an expression uses without binding.
+#[derive(Debug, Default)]
+pub struct FreeVariables<'types, 'arena> {
+    /// Each free variable with its inferred type, in order of first use.
+    pub variables: Vec<(&'arena str, &'types Type<'types>)>,
+    /// Fields accessed on values whose type is still a type variable, as
+    /// `(type variable id, field name, field type)`. Such a value must be a
+    /// record with at least these fields.
+    pub record_fields: Vec<(u16, &'arena str, &'types Type<'types>)>,
+}
 
-    // Push variables scope (client-provided runtime variables)
-    if !variables.is_empty() {
-        // Wrap each type in a monomorphic TypeScheme
-        // TODO: Accept TypeScheme as an argument.
-        let bindings: Vec<(&'arena str, TypeScheme<'types, 'arena>)> = variables
-            .iter()
-            .map(|(name, ty)| {
-                let empty_quantified = type_manager.alloc_u16_slice(&[]);
-                (*name, TypeScheme::new(empty_quantified, ty))
-            })
-            .collect();
-        let bindings_slice = arena.alloc_slice_fill_iter(bindings.into_iter());
-        analyzer
-            .scope_stack
-            .push(scope_stack::CompleteScope::from_sorted(bindings_slice));
+/// Like [`analyze`], but identifiers missing from `globals` and `variables`
+/// are not an error.
+///
+/// Each such free variable gets a fresh type variable that the rest of the
+/// expression constrains, and is returned with its inferred type. Field
+/// accesses on such values are recorded instead of requiring a concrete
+/// record type.
+pub fn analyze_with_free_variables<'types, 'arena>(
+    type_manager: &'types TypeManager<'types>,
+    arena: &'arena Bump,
+    expr: &'arena parser::ParsedExpr<'arena>,
+    globals: &[(&'arena str, &'types Type<'types>)],
+    variables: &[(&'arena str, &'types Type<'types>)],
+) -> Result<
+    (
+        &'arena TypedExpr<'types, 'arena>,
+        FreeVariables<'types, 'arena>,
+    ),
+    TypeError,
+> {
+    let mut free_variables = FreeVariables::default();
+    let typed_expr = analyze_with_scope_capacity(
+        type_manager,
+        arena,
+        expr,
+        globals,
+        variables,
+        expr.expr.scope_depth(),
+        Some(&mut free_variables),
+    )?;
+    Ok((typed_expr, free_variables))
+}
+
+/// [`analyze`] with the scope stack sized for `scope_capacity` nested scopes
+/// beyond globals and variables. The capacity only affects allocation, never
+/// the result. When `free_variables` is given, unbound identifiers are
+/// inferred into it as in [`analyze_with_free_variables`].
+pub(crate) fn analyze_with_scope_capacity<'types, 'arena>(
+    type_manager: &'types TypeManager<'types>,
+    arena: &'arena Bump,
+    expr: &'arena parser::ParsedExpr<'arena>,
+    globals: &[(&'arena str, &'types Type<'types>)],
+    variables: &[(&'arena str, &'types Type<'types>)],
+    scope_capacity: usize,
+    free_variables: Option<&mut FreeVariables<'types, 'arena>>,
+) -> Result<&'arena TypedExpr<'types, 'arena>, TypeError> {
+    let mut analyzer = Analyzer::new(
+        type_manager,
+        arena,
+        expr.ann,
+        globals,
+        variables,
+        scope_capacity,
+    );
+    if free_variables.is_some() {
+        analyzer.free_variables = Some(Vec::new());
     }
     let result = analyzer.analyze_expr(expr)?;
 
@@ -113,10 +144,30 @@ pub fn analyze<'types, 'arena>(
         ann: result.ann,
         lambda_instantiations,
     });
+    if let Some(free_variables) = free_variables {
+        *free_variables = analyzer.take_free_variables();
+    }
+
+    // Globals and parameters shadow the builtin `sum`
+    ambiguity::check_sums(resolved_result, |name| {
+        globals
+            .iter()
+            .chain(variables)
+            .any(|(bound, _)| *bound == name)
+    })
+    .map_err(|error| *error)?;
 
     Ok(resolved_result)
 }
 
+/// `where` bindings analyzed together.
+struct BindingGroup {
+    /// Binding indices, in source order.
+    members: Vec<usize>,
+    /// Whether the members are lambdas that use each other (or one uses itself).
+    recursive: bool,
+}
+
 struct Analyzer<'types, 'arena> {
     type_manager: &'types TypeManager<'types>,
     arena: &'arena Bump,
@@ -138,9 +189,119 @@ struct Analyzer<'types, 'arena> {
     /// These will be resolved to concrete types after finalize_constraints
     pending_instantiations:
         hashbrown::HashMap<*const Expr<'types, 'arena>, Vec<hashbrown::HashMap<u16, u16>>>,
+    /// Unbound identifiers seen so far, when inferring free variables.
+    /// `None` makes unbound identifiers an error.
+    free_variables: Option<Vec<(&'arena str, &'types Type<'types>)>>,
+    /// Fields accessed on type variables when inferring free variables
+    open_record_fields: Vec<(u16, &'arena str, &'types Type<'types>)>,
+    /// Parameter types for the lambda about to be analyzed, taken from the
+    /// signature of the function it is passed to
+    expected_lambda_params: Option<Vec<&'types Type<'types>>>,
+    /// Number of enclosing `otherwise` primaries, whose failures are handled
+    otherwise_depth: usize,
+    /// Identifiers that refer to `typeof` (not shadowed), whose calls fold to
+    /// a constant once types are resolved
+    typeof_idents: hashbrown::HashSet<*const Expr<'types, 'arena>>,
 }
 
 impl<'types, 'arena> Analyzer<'types, 'arena> {
+    /// The free variables seen so far and the fields accessed on them, with
+    /// their types resolved.
+    fn take_free_variables(&mut self) -> FreeVariables<'types, 'arena> {
+        let unification = &self.unification;
+        let variables = self
+            .free_variables
+            .take()
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(name, ty)| (name, unification.fully_resolve(ty)))
+            .collect();
+        // Type variables may have been unified since the field was accessed
+        let record_fields = self
+            .open_record_fields
+            .iter()
+            .filter_map(|(var_id, field, ty)| {
+                let TypeKind::TypeVar(var_id) = unification.resolve_var(*var_id).view() else {
+                    return None;
+                };
+                Some((var_id, *field, unification.fully_resolve(ty)))
+            })
+            .collect();
+        FreeVariables {
+            variables,
+            record_fields,
+        }
+    }
+
+    fn new(
+        type_manager: &'types TypeManager<'types>,
+        arena: &'arena Bump,
+        parsed_ann: &'arena parser::AnnotatedSource<'arena, parser::Expr<'arena>>,
+        globals: &[(&'arena str, &'types Type<'types>)],
+        variables: &[(&'arena str, &'types Type<'types>)],
+        scope_capacity: usize,
+    ) -> Self {
+        // Create annotation map for typed expressions
+        // We reuse the same source string since both ParsedExpr and TypedExpr are in the same arena
+        let typed_ann = arena.alloc(parser::AnnotatedSource::new(arena, parsed_ann.source));
+
+        let mut analyzer = Analyzer {
+            type_manager,
+            arena,
+            // Globals and variables each take one scope below the nested ones
+            scope_stack: ScopeStack::with_capacity(scope_capacity + 2),
+            unification: Unification::new(type_manager),
+            type_class_resolver: TypeClassResolver::new(),
+            parsed_ann,
+            typed_ann,
+            current_span: None, // Initialize to None
+            env_vars_stack: Vec::new(),
+            polymorphic_lambdas: hashbrown::HashMap::new(),
+            pending_instantiations: hashbrown::HashMap::new(),
+            free_variables: None,
+            open_record_fields: Vec::new(),
+            expected_lambda_params: None,
+            otherwise_depth: 0,
+            typeof_idents: hashbrown::HashSet::new(),
+        };
+
+        // Push globals scope (constants, packages, functions)
+        if !globals.is_empty() {
+            // Wrap each type in a monomorphic TypeScheme
+            // TODO: Accept TypeScheme as an argument.
+            let bindings: Vec<(&'arena str, TypeScheme<'types, 'arena>)> = globals
+                .iter()
+                .map(|(name, ty)| {
+                    let empty_quantified = type_manager.alloc_u16_slice(&[]);
+                    (*name, TypeScheme::new(empty_quantified, ty))
+                })
+                .collect();
+            let bindings_slice = arena.alloc_slice_fill_iter(bindings.into_iter());
+            analyzer
+                .scope_stack
+                .push(scope_stack::CompleteScope::from_sorted(bindings_slice));
+        }
+
+        // Push variables scope (client-provided runtime variables)
+        if !variables.is_empty() {
+            // Wrap each type in a monomorphic TypeScheme
+            // TODO: Accept TypeScheme as an argument.
+            let bindings: Vec<(&'arena str, TypeScheme<'types, 'arena>)> = variables
+                .iter()
+                .map(|(name, ty)| {
+                    let empty_quantified = type_manager.alloc_u16_slice(&[]);
+                    (*name, TypeScheme::new(empty_quantified, ty))
+                })
+                .collect();
+            let bindings_slice = arena.alloc_slice_fill_iter(bindings.into_iter());
+            analyzer
+                .scope_stack
+                .push(scope_stack::CompleteScope::from_sorted(bindings_slice));
+        }
+
+        analyzer
+    }
+
     fn analyze_expr(
         &mut self,
         expr: &parser::ParsedExpr<'arena>,
@@ -270,6 +431,14 @@ impl<'types, 'arena> Analyzer<'types, 'arena> {
         for set in &self.env_vars_stack {
             result.extend(set);
         }
+        // Free variables are monomorphic, like client-provided variables
+        for (_, ty) in self.free_variables.iter().flatten() {
+            result.extend(self.unification.free_type_vars(ty));
+        }
+        for (var_id, _, ty) in &self.open_record_fields {
+            result.insert(*var_id);
+            result.extend(self.unification.free_type_vars(ty));
+        }
         result
     }
 
@@ -302,6 +471,7 @@ impl<'types, 'arena> Analyzer<'types, 'arena> {
             parser::Expr::Otherwise { primary, fallback } => {
                 self.analyze_otherwise(primary, fallback)
             }
+            parser::Expr::Coalesce { option, default } => self.analyze_coalesce(option, default),
             parser::Expr::Option { inner } => self.analyze_option(*inner),
             parser::Expr::Match { expr, arms } => self.analyze_match(expr, arms),
             parser::Expr::Record(items) => self.analyze_record(items),
@@ -463,12 +633,56 @@ impl<'types, 'arena> Analyzer<'types, 'arena> {
     ) -> Result<&'arena mut Expr<'types, 'arena>, TypeError> {
         // 1. Analyze callable and its arguments.
         let callable = self.analyze(callable)?;
-        let args_typed = self
-            .arena
-            .alloc_slice_try_fill_iter(args.iter().map(|arg| self.analyze(arg)))?;
+        let callable_params: Option<Vec<_>> = match self.unification.resolve(callable.0).view() {
+            TypeKind::Function { params, .. } => Some(params.collect()),
+            _ => None,
+        };
+        let mut args_typed: Vec<&'arena Expr<'types, 'arena>> = Vec::with_capacity(args.len());
+        for (index, arg) in args.iter().enumerate() {
+            // A lambda argument takes its parameter types from the callee's
+            // signature, once unified with the preceding arguments. This way
+            // `Array.Map(people, (p) => p.name)` knows `p` is a record.
+            if let parser::Expr::Lambda { params, .. } = arg
+                && let Some(callable_params) = &callable_params
+                && callable_params.len() == args.len()
+            {
+                for (earlier, param_ty) in args_typed.iter().zip(callable_params) {
+                    let arg_ty = self.expected_argument_type(earlier.0, param_ty)?;
+                    self.unification
+                        .unifies_to(param_ty, arg_ty)
+                        .map_err(|err| {
+                            TypeError::from_unification_error(
+                                err,
+                                self.get_span(),
+                                self.get_source(),
+                            )
+                        })?;
+                }
+                if let TypeKind::Function {
+                    params: expected, ..
+                } = self.unification.resolve(callable_params[index]).view()
+                {
+                    let expected: Vec<_> = expected.collect();
+                    if expected.len() == params.len() {
+                        self.expected_lambda_params = Some(expected);
+                    }
+                }
+            }
+            args_typed.push(self.analyze(arg)?);
+        }
 
         // 2. Extract actual argument types.
-        let arg_types: Vec<_> = args_typed.iter().map(|arg| arg.0).collect();
+        let mut arg_types: Vec<_> = args_typed.iter().map(|arg| arg.0).collect();
+        if let Some(callable_params) = &callable_params {
+            for ((arg, arg_ty), param_ty) in args_typed
+                .iter_mut()
+                .zip(arg_types.iter_mut())
+                .zip(callable_params)
+            {
+                *arg_ty = self.expected_argument_type(arg_ty, param_ty)?;
+                *arg = self.project_argument(arg, arg_ty);
+            }
+        }
 
         // 3. Create a fresh type variable for the return type.
         let ret_ty = self.type_manager.fresh_type_var();
@@ -505,13 +719,56 @@ impl<'types, 'arena> Analyzer<'types, 'arena> {
             resolved_ret_ty,
             ExprInner::Call {
                 callable,
-                args: self
-                    .arena
-                    .alloc_slice_fill_iter(args_typed.into_iter().map(|arg| &**arg)),
+                args: self.arena.alloc_slice_copy(&args_typed),
             },
         ))
     }
 
+    /// The type an argument is checked as when passed for `param_ty`.
+    ///
+    /// Records with extra fields are accepted where the callee expects fewer
+    /// (width subtyping), so the expected record type is passed along instead.
+    fn expected_argument_type(
+        &mut self,
+        arg_ty: &'types Type<'types>,
+        param_ty: &'types Type<'types>,
+    ) -> Result<&'types Type<'types>, TypeError> {
+        let param_ty = self.unification.resolve(param_ty);
+        if !matches!(self.unification.resolve(arg_ty).view(), TypeKind::Record(_))
+            || !matches!(param_ty.view(), TypeKind::Record(_))
+        {
+            return Ok(arg_ty);
+        }
+        self.unification
+            .subsumes_to(arg_ty, param_ty)
+            .map_err(|err| {
+                TypeError::from_unification_error(err, self.get_span(), self.get_source())
+            })
+    }
+
+    /// Narrow a record argument to the record type the callee expects.
+    ///
+    /// Records are laid out by their exact fields, so a record with extra
+    /// fields is cast to the parameter's type, which drops the fields the
+    /// callee doesn't know about. Other arguments are returned unchanged.
+    fn project_argument(
+        &mut self,
+        arg: &'arena Expr<'types, 'arena>,
+        expected_ty: &'types Type<'types>,
+    ) -> &'arena Expr<'types, 'arena> {
+        let expected_ty = self.unification.fully_resolve(expected_ty);
+        if !has_extra_fields(self.unification.fully_resolve(arg.0), expected_ty) {
+            return arg;
+        }
+        let cast = self
+            .arena
+            .alloc(Expr(expected_ty, ExprInner::Cast { expr: arg }));
+        if let Some(span) = self.typed_ann.span_of(arg) {
+            self.typed_ann.add_span(cast, span);
+        }
+        cast
+    }
+
     fn analyze_index(
         &mut self,
         value: &'arena parser::Expr<'arena>,
@@ -520,6 +777,8 @@ impl<'types, 'arena> Analyzer<'types, 'arena> {
         let value = self.analyze(value)?;
         let index = self.analyze(index)?;
 
+        self.check_constant_index_in_bounds(value, index)?;
+
         // Determine the result type based on the value type
         let result_ty = match value.0.view() {
             TypeKind::Array(element_ty) => {
@@ -547,6 +806,38 @@ impl<'types, 'arena> Analyzer<'types, 'arena> {
                 )?;
                 self.type_manager.int()
             }
+            TypeKind::Record(fields) => {
+                // A constant key is a plain field access: `r["x"]` is `r.x`
+                if let ExprInner::Constant(key) = &index.1
+                    && let Ok(key) = key.as_str()
+                {
+                    let field_ty = self.record_field_type(fields, key)?;
+                    let field = self.arena.alloc_str(key);
+                    return Ok(self.alloc(field_ty, ExprInner::Field { value, field }));
+                }
+                // Otherwise the record is indexed like a map from field name
+                // to value, which requires all fields to have the same type
+                self.expect_type_to_be(
+                    index,
+                    index.0,
+                    self.type_manager.str(),
+                    "Record index must be Str",
+                )?;
+                let mut field_types = fields.map(|(_, ty)| ty);
+                let Some(first_ty) = field_types.next() else {
+                    return self.error(TypeErrorKind::NonUniformRecordIndex {
+                        ty: format!("{}", value.0),
+                    });
+                };
+                for field_ty in field_types {
+                    if self.unification.unifies_to(field_ty, first_ty).is_err() {
+                        return self.error(TypeErrorKind::NonUniformRecordIndex {
+                            ty: format!("{}", value.0),
+                        });
+                    }
+                }
+                first_ty
+            }
             TypeKind::TypeVar(_) => {
                 // Type variable not yet resolved - add relational Indexable constraint
                 // The constraint tracks: Indexable(container, index, result)
@@ -577,6 +868,46 @@ impl<'types, 'arena> Analyzer<'types, 'arena> {
         Ok(self.alloc(result_ty, ExprInner::Index { value, index }))
     }
 
+    /// Reject a constant index that is out of bounds for an array literal,
+    /// like `[1, 2, 3][5]`, since it would always fail at runtime.
+    ///
+    /// Indexing guarded by `otherwise` is allowed: the fallback handles it.
+    fn check_constant_index_in_bounds(
+        &self,
+        value: &'arena Expr<'types, 'arena>,
+        index: &'arena Expr<'types, 'arena>,
+    ) -> Result<(), TypeError> {
+        if self.otherwise_depth > 0 {
+            return Ok(());
+        }
+        let (ExprInner::Array { elements }, ExprInner::Constant(constant)) = (&value.1, &index.1)
+        else {
+            return Ok(());
+        };
+        let Ok(index_value) = constant.as_int() else {
+            return Ok(());
+        };
+        let length = elements.len();
+        let in_bounds = if index_value < 0 {
+            index_value.unsigned_abs() <= length as u64
+        } else {
+            (index_value as u64) < length as u64
+        };
+        if in_bounds {
+            return Ok(());
+        }
+        Err(TypeError::new(
+            TypeErrorKind::IndexOutOfBounds {
+                index: index_value,
+                length,
+            },
+            self.get_source(),
+            self.typed_ann
+                .span_of(index)
+                .unwrap_or_else(|| self.get_span()),
+        ))
+    }
+
     fn analyze_field(
         &mut self,
         value: &'arena parser::Expr<'arena>,
@@ -586,24 +917,20 @@ impl<'types, 'arena> Analyzer<'types, 'arena> {
 
         // Check that value is a record and get the field type
         let result_ty = match value.0.view() {
-            TypeKind::Record(fields) => {
-                // Clone the iterator to use it twice (once for search, once for error message)
-                let fields_vec: Vec<_> = fields.collect();
-
-                // Look for the field in the record
-                fields_vec
+            TypeKind::Record(fields) => self.record_field_type(fields, field)?,
+            TypeKind::TypeVar(var_id) if self.free_variables.is_some() => {
+                match self
+                    .open_record_fields
                     .iter()
-                    .find(|(name, _)| *name == field)
-                    .map(|(_, ty)| *ty)
-                    .ok_or_else(|| {
-                        self.type_error(TypeErrorKind::UnknownField {
-                            field: field.to_string(),
-                            available_fields: fields_vec
-                                .iter()
-                                .map(|(n, _)| n.to_string())
-                                .collect(),
-                        })
-                    })?
+                    .find(|(other_id, name, _)| *other_id == var_id && *name == field)
+                {
+                    Some((_, _, ty)) => *ty,
+                    None => {
+                        let ty = self.type_manager.fresh_type_var();
+                        self.open_record_fields.push((var_id, field, ty));
+                        ty
+                    }
+                }
             }
             TypeKind::TypeVar(_) => {
                 // Cannot infer record type from field access alone
@@ -624,6 +951,28 @@ impl<'types, 'arena> Analyzer<'types, 'arena> {
         Ok(self.alloc(result_ty, ExprInner::Field { value, field }))
     }
 
+    /// Look up the type of `field` among a record type's fields.
+    fn record_field_type(
+        &self,
+        fields: impl Iterator<Item = (&'types str, &'types Type<'types>)>,
+        field: &str,
+    ) -> Result<&'types Type<'types>, TypeError> {
+        // Collect to use the fields twice (once for search, once for error message)
+        let fields_vec: Vec<_> = fields.collect();
+
+        // Look for the field in the record
+        fields_vec
+            .iter()
+            .find(|(name, _)| *name == field)
+            .map(|(_, ty)| *ty)
+            .ok_or_else(|| {
+                self.type_error(TypeErrorKind::UnknownField {
+                    field: field.to_string(),
+                    available_fields: fields_vec.iter().map(|(n, _)| n.to_string()).collect(),
+                })
+            })
+    }
+
     fn analyze_cast(
         &mut self,
         ty_expr: &'arena parser::TypeExpr<'arena>,
@@ -681,6 +1030,7 @@ impl<'types, 'arena> Analyzer<'types, 'arena> {
         body: &'arena parser::Expr<'arena>,
     ) -> Result<&'arena mut Expr<'types, 'arena>, TypeError> {
         let ty = self.type_manager;
+        let expected_params = self.expected_lambda_params.take();
 
         // Create shared recording vector and push recording scope
         let recorded = Rc::new(RefCell::new(BTreeSet::new()));
@@ -696,10 +1046,13 @@ impl<'types, 'arena> Analyzer<'types, 'arena> {
             })?,
         );
 
-        // Create fresh type variables for parameters
+        // Create fresh type variables for parameters, unless the caller knows them
         let mut param_types: Vec<&'types Type<'types>> = Vec::new();
-        for param in params.iter() {
-            let param_ty = ty.fresh_type_var();
+        for (index, param) in params.iter().enumerate() {
+            let param_ty = match &expected_params {
+                Some(expected) => expected[index],
+                None => ty.fresh_type_var(),
+            };
 
             // Wrap in monomorphic TypeScheme (lambda parameters are not polymorphic)
             let empty_quantified = self.type_manager.alloc_u16_slice(&[]);
@@ -719,7 +1072,12 @@ impl<'types, 'arena> Analyzer<'types, 'arena> {
         }
         self.env_vars_stack.push(param_env_vars);
 
-        let body = self.analyze(body)?;
+        // The body runs when the lambda is called, which may be outside any
+        // enclosing `otherwise`
+        let otherwise_depth = core::mem::take(&mut self.otherwise_depth);
+        let body = self.analyze(body);
+        self.otherwise_depth = otherwise_depth;
+        let body = body?;
 
         // Pop environment variables
         self.env_vars_stack.pop();
@@ -803,29 +1161,19 @@ impl<'types, 'arena> Analyzer<'types, 'arena> {
             })?,
         );
 
-        // Analyze and bind each expression sequentially
-        let mut analyzed_bindings: Vec<(&'arena str, &'arena mut Expr<'types, 'arena>)> =
-            Vec::new();
-        for (name, value_expr) in bindings.iter() {
-            let analyzed = self.analyze(value_expr)?;
-
-            // Generalize the type to a type scheme
-            // Use current environment variables to prevent generalizing over lambda parameters
-            let env_vars = self.get_env_vars();
-            let mut scheme = self.unification.generalize(analyzed.0, &env_vars);
-
-            // Track polymorphic lambdas for instantiation tracking
-            // Store the lambda pointer directly in the TypeScheme
-            if !scheme.is_monomorphic() && matches!(analyzed.1, ExprInner::Lambda { .. }) {
-                let lambda_ptr = analyzed.as_ptr();
-                scheme.lambda_expr = Some(lambda_ptr);
-                self.polymorphic_lambdas.insert(lambda_ptr, scheme);
+        // Analyze and bind each expression after the bindings it uses, so
+        // bindings may reference each other in any order
+        let mut analyzed_bindings: Vec<(&'arena str, &'arena Expr<'types, 'arena>)> = Vec::new();
+        for group in self.where_binding_groups(bindings)? {
+            if group.recursive {
+                let members = self.analyze_recursive_bindings(bindings, &group.members)?;
+                analyzed_bindings.extend(members);
+            } else {
+                let (name, value_expr) = bindings[group.members[0]];
+                let analyzed: &'arena Expr<'types, 'arena> = self.analyze(value_expr)?;
+                self.bind_where_value(name, analyzed)?;
+                analyzed_bindings.push((name, analyzed));
             }
-
-            self.scope_stack
-                .bind_in_current(*name, scheme)
-                .map_err(|e| self.internal_error(format!("Failed to bind in where: {:?}", e)))?;
-            analyzed_bindings.push((*name, analyzed));
         }
 
         let expr_typed = self.analyze(expr)?;
@@ -840,17 +1188,223 @@ impl<'types, 'arena> Analyzer<'types, 'arena> {
                 expr: expr_typed,
                 bindings: self
                     .arena
-                    .alloc_slice_fill_iter(analyzed_bindings.into_iter().map(|(k, v)| (k, &*v))),
+                    .alloc_slice_copy(&analyzed_bindings),
             },
         ))
     }
 
+    /// Generalize the type of a `where` binding and bind it in the current scope.
+    fn bind_where_value(
+        &mut self,
+        name: &'arena str,
+        analyzed: &Expr<'types, 'arena>,
+    ) -> Result<(), TypeError> {
+        // Use current environment variables to prevent generalizing over lambda parameters
+        let env_vars = self.get_env_vars();
+        let mut scheme = self.unification.generalize(analyzed.0, &env_vars);
+
+        // Track polymorphic lambdas for instantiation tracking
+        // Store the lambda pointer directly in the TypeScheme
+        if !scheme.is_monomorphic() && matches!(analyzed.1, ExprInner::Lambda { .. }) {
+            let lambda_ptr = analyzed.as_ptr();
+            scheme.lambda_expr = Some(lambda_ptr);
+            self.polymorphic_lambdas.insert(lambda_ptr, scheme);
+        }
+
+        self.scope_stack
+            .bind_in_current(name, scheme)
+            .map_err(|e| self.internal_error(format!("Failed to bind in where: {:?}", e)))
+    }
+
+    /// Analyze lambdas of a `where` that use each other.
+    ///
+    /// While their bodies are analyzed, the lambdas are in scope with
+    /// monomorphic types; they are generalized once the whole group is known.
+    fn analyze_recursive_bindings(
+        &mut self,
+        bindings: &'arena [(&'arena str, &'arena parser::Expr<'arena>)],
+        members: &[usize],
+    ) -> Result<Vec<(&'arena str, &'arena Expr<'types, 'arena>)>, TypeError> {
+        let member_types: Vec<&'types Type<'types>> = members
+            .iter()
+            .map(|_| self.type_manager.fresh_type_var())
+            .collect();
+
+        let empty_quantified = self.type_manager.alloc_u16_slice(&[]);
+        let mut group_bindings: Vec<(&'arena str, TypeScheme<'types, 'arena>)> = members
+            .iter()
+            .zip(&member_types)
+            .map(|(&index, &ty)| (bindings[index].0, TypeScheme::new(empty_quantified, ty)))
+            .collect();
+        group_bindings.sort_by_key(|(name, _)| *name);
+        self.scope_stack
+            .push(scope_stack::CompleteScope::from_sorted(
+                self.arena.alloc_slice_copy(&group_bindings),
+            ));
+
+        // Nested `where` bindings must not generalize over the group's types
+        let mut group_env_vars = hashbrown::HashSet::new();
+        for member_ty in &member_types {
+            group_env_vars.extend(self.unification.free_type_vars(member_ty));
+        }
+        self.env_vars_stack.push(group_env_vars);
+
+        let mut analyzed_members = Vec::new();
+        for (&index, &member_ty) in members.iter().zip(&member_types) {
+            let (name, value_expr) = bindings[index];
+            let analyzed: &'arena Expr<'types, 'arena> = self.analyze(value_expr)?;
+            self.expect_types_match(analyzed, analyzed.0, member_ty)?;
+            analyzed_members.push((name, analyzed));
+        }
+
+        self.env_vars_stack.pop();
+        self.scope_stack
+            .pop()
+            .map_err(|e| self.internal_error(format!("Failed to pop scope: {:?}", e)))?;
+
+        for (name, analyzed) in analyzed_members.iter() {
+            self.bind_where_value(name, analyzed)?;
+        }
+        Ok(analyzed_members)
+    }
+
+    /// Groups of `where` bindings in analysis order: each group comes after
+    /// the bindings it uses, keeping source order where there is a choice.
+    ///
+    /// A group is a single binding, or lambdas that use each other and are
+    /// analyzed together. Any other cycle is a recursive binding error.
+    fn where_binding_groups(
+        &self,
+        bindings: &'arena [(&'arena str, &'arena parser::Expr<'arena>)],
+    ) -> Result<Vec<BindingGroup>, TypeError> {
+        let count = bindings.len();
+
+        // dependencies[i]: indices of the bindings that binding i uses
+        let dependencies: Vec<Vec<usize>> = bindings
+            .iter()
+            .map(|(_, value)| {
+                value
+                    .free_identifiers()
+                    .into_iter()
+                    .filter_map(|used| bindings.iter().position(|(name, _)| *name == used))
+                    .collect()
+            })
+            .collect();
+
+        // reaches[i][j]: binding i uses binding j, directly or through others
+        let mut reaches = vec![vec![false; count]; count];
+        for (start, reached) in reaches.iter_mut().enumerate() {
+            let mut pending = dependencies[start].clone();
+            while let Some(index) = pending.pop() {
+                if !reached[index] {
+                    reached[index] = true;
+                    pending.extend(&dependencies[index]);
+                }
+            }
+        }
+
+        let mut groups = Vec::new();
+        let mut done = vec![false; count];
+        while let Some(first) = done.iter().position(|&is_done| !is_done) {
+            // The first pending group that only uses finished bindings
+            let members = (first..count)
+                .filter(|&index| !done[index])
+                .map(|index| {
+                    (0..count)
+                        .filter(|&other| {
+                            other == index || (reaches[index][other] && reaches[other][index])
+                        })
+                        .collect::<Vec<usize>>()
+                })
+                .find(|members| {
+                    members.iter().all(|&member| {
+                        dependencies[member]
+                            .iter()
+                            .all(|&used| done[used] || members.contains(&used))
+                    })
+                })
+                .unwrap(); // Safe: groups that use each other are merged, so one is ready.
+
+            let recursive = reaches[members[0]][members[0]];
+            if recursive
+                && let Some(&value) = members
+                    .iter()
+                    .find(|&&member| !matches!(bindings[member].1, parser::Expr::Lambda { .. }))
+            {
+                return Err(self.recursive_binding_error(bindings, &dependencies, &members, value));
+            }
+
+            for &member in &members {
+                done[member] = true;
+            }
+            groups.push(BindingGroup { members, recursive });
+        }
+        Ok(groups)
+    }
+
+    /// Error for the shortest cycle through `start` within `members`.
+    fn recursive_binding_error(
+        &self,
+        bindings: &'arena [(&'arena str, &'arena parser::Expr<'arena>)],
+        dependencies: &[Vec<usize>],
+        members: &[usize],
+        start: usize,
+    ) -> TypeError {
+        // Breadth-first search for the last binding before returning to `start`
+        let mut previous: Vec<Option<usize>> = vec![None; bindings.len()];
+        let mut queue = VecDeque::from([start]);
+        let last = loop {
+            let current = queue.pop_front().unwrap(); // Safe: `start` is on a cycle.
+            if dependencies[current].contains(&start) {
+                break current;
+            }
+            for &used in &dependencies[current] {
+                if members.contains(&used) && used != start && previous[used].is_none() {
+                    previous[used] = Some(current);
+                    queue.push_back(used);
+                }
+            }
+        };
+        let mut cycle = vec![last];
+        while let Some(before) = previous[cycle[cycle.len() - 1]] {
+            cycle.push(before);
+        }
+        cycle.reverse();
+
+        let name_span = |index: usize| {
+            self.parsed_ann
+                .span_of_str(bindings[index].0)
+                .unwrap_or_else(|| self.get_span())
+        };
+        let mut err = TypeError::new(
+            TypeErrorKind::RecursiveBinding {
+                cycle: cycle
+                    .iter()
+                    .map(|&index| bindings[index].0.to_string())
+                    .collect(),
+            },
+            self.get_source(),
+            name_span(cycle[0]),
+        );
+        for &index in &cycle[1..] {
+            err.context
+                .push(crate::diagnostics::context::Context::DefinedHere {
+                    what: format!("binding '{}'", bindings[index].0),
+                    span: name_span(index),
+                });
+        }
+        err
+    }
+
     fn analyze_otherwise(
         &mut self,
         primary: &'arena parser::Expr<'arena>,
         fallback: &'arena parser::Expr<'arena>,
     ) -> Result<&'arena mut Expr<'types, 'arena>, TypeError> {
-        let primary = self.analyze(primary)?;
+        self.otherwise_depth += 1;
+        let primary = self.analyze(primary);
+        self.otherwise_depth -= 1;
+        let primary = primary?;
         let fallback = self.analyze(fallback)?;
 
         // Both expressions must have the same type - point to fallback if mismatch
@@ -859,6 +1413,29 @@ impl<'types, 'arena> Analyzer<'types, 'arena> {
         Ok(self.alloc(result_ty, ExprInner::Otherwise { primary, fallback }))
     }
 
+    fn analyze_coalesce(
+        &mut self,
+        option: &'arena parser::Expr<'arena>,
+        default: &'arena parser::Expr<'arena>,
+    ) -> Result<&'arena mut Expr<'types, 'arena>, TypeError> {
+        let option = self.analyze(option)?;
+        let default = self.analyze(default)?;
+
+        // Left side must be an Option[T] - use option's span for error
+        let inner_ty = self.type_manager.fresh_type_var();
+        self.expect_type_to_be(
+            option,
+            option.0,
+            self.type_manager.option(inner_ty),
+            "Left side of '??' must be an Option",
+        )?;
+
+        // Default must match the option's inner type - point to default if mismatch
+        let result_ty = self.expect_types_match(default, default.0, inner_ty)?;
+
+        Ok(self.alloc(result_ty, ExprInner::Coalesce { option, default }))
+    }
+
     fn analyze_option(
         &mut self,
         inner: Option<&'arena parser::Expr<'arena>>,
@@ -1219,6 +1796,8 @@ impl<'types, 'arena> Analyzer<'types, 'arena> {
         &mut self,
         items: &'arena [(&'arena str, &'arena parser::Expr<'arena>)],
     ) -> Result<&'arena mut Expr<'types, 'arena>, TypeError> {
+        self.check_duplicate_fields(items)?;
+
         let mut fields: Vec<(&'arena str, &'arena mut Expr<'types, 'arena>)> = Vec::new();
         let mut field_types: Vec<(&'arena str, &'types Type<'types>)> = Vec::new();
 
@@ -1240,6 +1819,39 @@ impl<'types, 'arena> Analyzer<'types, 'arena> {
         ))
     }
 
+    /// Reject a record literal that names the same field twice, pointing at
+    /// both occurrences.
+    fn check_duplicate_fields(
+        &self,
+        items: &'arena [(&'arena str, &'arena parser::Expr<'arena>)],
+    ) -> Result<(), TypeError> {
+        for (index, (name, _)) in items.iter().enumerate() {
+            let Some((first, _)) = items[..index].iter().find(|(other, _)| other == name) else {
+                continue;
+            };
+            let span = self
+                .parsed_ann
+                .span_of_str(name)
+                .unwrap_or_else(|| self.get_span());
+            let mut err = TypeError::new(
+                TypeErrorKind::DuplicateField {
+                    name: name.to_string(),
+                },
+                self.get_source(),
+                span,
+            );
+            if let Some(first_span) = self.parsed_ann.span_of_str(first) {
+                err.context
+                    .push(crate::diagnostics::context::Context::DefinedHere {
+                        what: format!("field '{}' first", name),
+                        span: first_span,
+                    });
+            }
+            return Err(err);
+        }
+        Ok(())
+    }
+
     fn analyze_map(
         &mut self,
         items: &'arena [(&'arena parser::Expr<'arena>, &'arena parser::Expr<'arena>)],
@@ -1410,6 +2022,36 @@ impl<'types, 'arena> Analyzer<'types, 'arena> {
                 let value = Value::bytes(self.arena, ty, value);
                 Ok(self.alloc(ty, ExprInner::Constant(value)))
             }
+            #[cfg(feature = "bigint")]
+            parser::Literal::BigInt(text) => {
+                let value = parse_bigint_literal(text);
+                let value = Value::bigint(self.arena, self.type_manager, &value);
+                Ok(self.alloc(value.ty, ExprInner::Constant(value)))
+            }
+            #[cfg(not(feature = "bigint"))]
+            parser::Literal::BigInt(_) => self.error(TypeErrorKind::UnsupportedFeature {
+                feature: "BigInt literals".to_string(),
+                suggestion: "Enable the `bigint` feature of melbi-core to use arbitrary-precision integers".to_string(),
+            }),
+            #[cfg(feature = "decimal")]
+            parser::Literal::Decimal(text) => {
+                let Some(value) = crate::values::decimal::Decimal::parse(&text.replace('_', "")) else {
+                    return self.error(TypeErrorKind::Other {
+                        message: format!(
+                            "Decimal literal {}d exceeds the range or precision of Decimal ({} fractional digits)",
+                            text,
+                            crate::values::decimal::MAX_SCALE
+                        ),
+                    });
+                };
+                let value = Value::decimal(self.arena, self.type_manager, value);
+                Ok(self.alloc(value.ty, ExprInner::Constant(value)))
+            }
+            #[cfg(not(feature = "decimal"))]
+            parser::Literal::Decimal(_) => self.error(TypeErrorKind::UnsupportedFeature {
+                feature: "Decimal literals".to_string(),
+                suggestion: "Enable the `decimal` feature of melbi-core to use fixed-point decimals".to_string(),
+            }),
         }
     }
 
@@ -1451,6 +2093,41 @@ impl<'types, 'arena> Analyzer<'types, 'arena> {
             return Ok(self.alloc(ty, ExprInner::Ident(ident)));
         }
 
+        // Builtins are instantiated per use, so constraints point at this use
+        if let Some(builtin) = builtins::lookup(ident) {
+            let signature = builtin.signature(self.type_manager);
+            let span = self.get_span();
+            for constraint in signature.constraints {
+                match constraint {
+                    builtins::Constraint::Ord(ty) => {
+                        self.type_class_resolver
+                            .add_ord_constraint(ty, span.clone());
+                    }
+                    builtins::Constraint::Numeric(ty) => {
+                        self.type_class_resolver
+                            .add_numeric_constraint(ty, ty, ty, span.clone());
+                    }
+                }
+            }
+            let expr = self.alloc(signature.ty, ExprInner::Ident(ident));
+            if builtin.name == "typeof" {
+                self.typeof_idents.insert(&*expr as *const _);
+            }
+            return Ok(expr);
+        }
+
+        if let Some(free_variables) = &mut self.free_variables {
+            let ty = match free_variables.iter().find(|(name, _)| *name == ident) {
+                Some((_, ty)) => *ty,
+                None => {
+                    let ty = self.type_manager.fresh_type_var();
+                    free_variables.push((ident, ty));
+                    ty
+                }
+            };
+            return Ok(self.alloc(ty, ExprInner::Ident(ident)));
+        }
+
         self.error(TypeErrorKind::UnboundVariable {
             name: ident.to_string(),
         })
@@ -1589,9 +2266,12 @@ impl<'types, 'arena> Analyzer<'types, 'arena> {
                     .iter()
                     .map(|arg| self.resolve_expr_types(arg, ptr_remap))
                     .collect();
-                ExprInner::Call {
-                    callable: resolved_callable,
-                    args: self.arena.alloc_slice_fill_iter(resolved_args.into_iter()),
+                match self.fold_typeof(callable, &resolved_args) {
+                    Some(type_name) => ExprInner::Constant(type_name),
+                    None => ExprInner::Call {
+                        callable: resolved_callable,
+                        args: self.arena.alloc_slice_fill_iter(resolved_args.into_iter()),
+                    },
                 }
             }
             ExprInner::Index { value, index } => ExprInner::Index {
@@ -1643,6 +2323,10 @@ impl<'types, 'arena> Analyzer<'types, 'arena> {
                 primary: self.resolve_expr_types(primary, ptr_remap),
                 fallback: self.resolve_expr_types(fallback, ptr_remap),
             },
+            ExprInner::Coalesce { option, default } => ExprInner::Coalesce {
+                option: self.resolve_expr_types(option, ptr_remap),
+                default: self.resolve_expr_types(default, ptr_remap),
+            },
             ExprInner::Option { inner } => ExprInner::Option {
                 inner: inner.map(|expr| self.resolve_expr_types(expr, ptr_remap)),
             },
@@ -1731,6 +2415,26 @@ impl<'types, 'arena> Analyzer<'types, 'arena> {
         new_expr
     }
 
+    /// The type name of a `typeof` call's argument, if `callable` is `typeof`.
+    ///
+    /// Type variables left in the argument's type (like the parameter type of
+    /// a polymorphic lambda) are named `a`, `b`, ... in order, so the name
+    /// is the same wherever the program is run.
+    fn fold_typeof(
+        &self,
+        callable: &Expr<'types, 'arena>,
+        resolved_args: &[&'arena Expr<'types, 'arena>],
+    ) -> Option<Value<'types, 'arena>> {
+        if !self.typeof_idents.contains(&(callable as *const _)) {
+            return None;
+        }
+        let [argument] = resolved_args else {
+            return None;
+        };
+        let type_name = display_type_with_var_names(argument.0);
+        Some(Value::str(self.arena, self.type_manager.str(), &type_name))
+    }
+
     fn resolve_pattern_types(
         &self,
         pattern: &'arena typed_expr::TypedPattern<'types, 'arena>,
@@ -1759,3 +2463,41 @@ impl<'types, 'arena> Analyzer<'types, 'arena> {
         }
     }
 }
+
+/// Whether `actual` is a record with fields that `expected` lacks, at any
+/// depth. Both must be fully resolved, and `actual` must subsume `expected`.
+fn has_extra_fields(actual: &Type<'_>, expected: &Type<'_>) -> bool {
+    let (TypeKind::Record(actual_fields), TypeKind::Record(expected_fields)) =
+        (actual.view(), expected.view())
+    else {
+        return false;
+    };
+    actual_fields.len() != expected_fields.len()
+        || actual_fields
+            .zip(expected_fields)
+            .any(|((_, actual_ty), (_, expected_ty))| has_extra_fields(actual_ty, expected_ty))
+}
+
+/// Converts the source text of a `123n` literal into a `BigInt`.
+///
+/// The grammar guarantees the digits are valid for their radix.
+#[cfg(feature = "bigint")]
+fn parse_bigint_literal(text: &str) -> num_bigint::BigInt {
+    let digits = text.replace('_', "");
+    let (negative, digits) = match digits.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, digits.as_str()),
+    };
+    let (radix, digits) = if let Some(rest) = digits.strip_prefix("0x") {
+        (16, rest)
+    } else if let Some(rest) = digits.strip_prefix("0o") {
+        (8, rest)
+    } else if let Some(rest) = digits.strip_prefix("0b") {
+        (2, rest)
+    } else {
+        (10, digits)
+    };
+    let magnitude = num_bigint::BigInt::parse_bytes(digits.as_bytes(), radix)
+        .expect("grammar only accepts valid integer digits");
+    if negative { -magnitude } else { magnitude }
+}
@@ -62,6 +62,10 @@ fn collect_lambda_pointers<'types, 'arena>(
         | typed_expr::ExprInner::Otherwise {
             primary: left,
             fallback: right,
+        }
+        | typed_expr::ExprInner::Coalesce {
+            option: left,
+            default: right,
         } => {
             collect_lambda_pointers(left, lambdas);
             collect_lambda_pointers(right, lambdas);
@@ -1086,6 +1090,120 @@ fn test_where_duplicate_binding_fails() {
     assert!(result.is_err());
 }
 
+#[test]
+fn test_where_forward_reference() {
+    let bump = Bump::new();
+    let type_manager = TypeManager::new(&bump);
+
+    // `a` uses `b`, which is bound after it
+    let result = analyze_source("a where { a = b + 1, b = 2 }", &type_manager, &bump).unwrap();
+    assert_eq!(result.expr.0, type_manager.int());
+
+    // Typed bindings are in dependency order
+    let typed_expr::ExprInner::Where { bindings, .. } = &result.expr.1 else {
+        panic!("Expected Where expression");
+    };
+    let names: Vec<&str> = bindings.iter().map(|(name, _)| *name).collect();
+    assert_eq!(names, ["b", "a"]);
+
+    // Lambdas may call bindings defined after them
+    let result = analyze_source(
+        "f(1) where { f = (x) => g(x) * 2, g = (x) => x + c, c = 10 }",
+        &type_manager,
+        &bump,
+    )
+    .unwrap();
+    assert_eq!(result.expr.0, type_manager.int());
+}
+
+#[test]
+fn test_where_cycle_reports_recursive_binding() {
+    let bump = Bump::new();
+    let type_manager = TypeManager::new(&bump);
+
+    let source = "a where { a = b, b = a }";
+    let err = analyze_source(source, &type_manager, &bump).unwrap_err();
+    assert!(
+        matches!(err.kind, TypeErrorKind::RecursiveBinding { ref cycle } if cycle == &["a", "b"])
+    );
+
+    let diagnostic = err.to_diagnostic();
+    assert_eq!(diagnostic.code, Some("E027".to_string()));
+    assert_eq!(diagnostic.message, "Recursive binding: 'a' -> 'b' -> 'a'");
+    // Points at `a`, with `b` as related info
+    assert_eq!(diagnostic.span.0, 10..11);
+    assert_eq!(diagnostic.related.len(), 1);
+    assert_eq!(diagnostic.related[0].span.0, 17..18);
+    assert!(diagnostic.related[0].message.contains("binding 'b'"));
+}
+
+#[test]
+fn test_where_cycle_ignores_bindings_outside_it() {
+    let bump = Bump::new();
+    let type_manager = TypeManager::new(&bump);
+
+    // `c` depends on the cycle but is not part of it
+    let source = "c where { c = a, a = b + 1, b = a }";
+    let err = analyze_source(source, &type_manager, &bump).unwrap_err();
+    assert!(
+        matches!(err.kind, TypeErrorKind::RecursiveBinding { ref cycle } if cycle == &["a", "b"])
+    );
+}
+
+#[test]
+fn test_where_self_reference_is_recursive_binding() {
+    let bump = Bump::new();
+    let type_manager = TypeManager::new(&bump);
+
+    let source = "x where { x = x + 1 }";
+    let err = analyze_source(source, &type_manager, &bump).unwrap_err();
+    let diagnostic = err.to_diagnostic();
+    assert_eq!(diagnostic.message, "Recursive binding: 'x' -> 'x'");
+    assert_eq!(diagnostic.span.0, 10..11);
+    assert!(diagnostic.related.is_empty());
+
+    // A lambda parameter with the same name shadows the binding
+    assert!(analyze_source("f(5) where { f = (f) => f + 1 }", &type_manager, &bump).is_ok());
+}
+
+#[test]
+fn test_where_cycle_through_lambda_is_recursive_binding() {
+    let bump = Bump::new();
+    let type_manager = TypeManager::new(&bump);
+
+    // Only lambdas may use each other: `a` is evaluated before `f` exists
+    let source = "a where { a = f(1), f = (x) => a + x }";
+    let err = analyze_source(source, &type_manager, &bump).unwrap_err();
+    assert!(
+        matches!(err.kind, TypeErrorKind::RecursiveBinding { ref cycle } if cycle == &["a", "f"])
+    );
+}
+
+#[test]
+fn test_where_recursive_lambdas() {
+    let bump = Bump::new();
+    let type_manager = TypeManager::new(&bump);
+
+    let source =
+        "factorial(5) where { factorial = (n) => if n <= 1 then 1 else n * factorial(n - 1) }";
+    let typed = analyze_source(source, &type_manager, &bump).unwrap();
+    assert_eq!(typed.expr.0, type_manager.int());
+
+    let source = "is_even(4) where { \
+        is_even = (n) => if n == 0 then true else is_odd(n - 1), \
+        is_odd = (n) => if n == 0 then false else is_even(n - 1) }";
+    let typed = analyze_source(source, &type_manager, &bump).unwrap();
+    assert_eq!(typed.expr.0, type_manager.bool());
+
+    // A recursive lambda is generalized once its group is analyzed
+    let source = "{a = id(1), b = id(\"s\")} where { id = (x) => if false then id(x) else x }";
+    assert!(analyze_source(source, &type_manager, &bump).is_ok());
+
+    // Inside its own body, it is not generalized yet
+    let source = "id(1) where { id = (x) => if false then id(\"s\") else x }";
+    assert!(analyze_source(source, &type_manager, &bump).is_err());
+}
+
 // ============================================================================
 // Lambdas and Functions
 // ============================================================================
@@ -1326,6 +1444,24 @@ fn test_record_field_access_nonexistent_fails() {
     assert!(result.is_err());
 }
 
+#[test]
+fn test_record_duplicate_field_fails() {
+    let bump = Bump::new();
+    let type_manager = TypeManager::new(&bump);
+
+    let source = "{ x = 1, y = 2, x = 3 }";
+    let err = analyze_source(source, &type_manager, &bump).unwrap_err();
+    assert!(matches!(err.kind, TypeErrorKind::DuplicateField { ref name } if name == "x"));
+
+    let diagnostic = err.to_diagnostic();
+    assert_eq!(diagnostic.code, Some("E023".to_string()));
+    // Points at the second `x`, with the first one as related info
+    assert_eq!(diagnostic.span.0, 16..17);
+    assert_eq!(diagnostic.related.len(), 1);
+    assert_eq!(diagnostic.related[0].span.0, 2..3);
+    assert!(diagnostic.related[0].message.contains("first defined here"));
+}
+
 #[test]
 fn test_field_access_non_record_fails() {
     let bump = Bump::new();
@@ -1335,6 +1471,101 @@ fn test_field_access_non_record_fails() {
     assert!(result.is_err());
 }
 
+#[test]
+fn test_record_index_constant_key_is_field_access() {
+    let bump = Bump::new();
+    let type_manager = TypeManager::new(&bump);
+
+    let result = analyze_source("{ x = 42, y = \"hello\" }[\"y\"]", &type_manager, &bump).unwrap();
+    assert_eq!(result.expr.0, type_manager.str());
+    assert!(matches!(
+        result.expr.1,
+        typed_expr::ExprInner::Field { field: "y", .. }
+    ));
+}
+
+#[test]
+fn test_record_index_constant_key_unknown_fails() {
+    let bump = Bump::new();
+    let type_manager = TypeManager::new(&bump);
+
+    let err = analyze_source("{ x = 42 }[\"y\"]", &type_manager, &bump).unwrap_err();
+    assert!(matches!(err.kind, TypeErrorKind::UnknownField { ref field, .. } if field == "y"));
+}
+
+#[test]
+fn test_record_index_dynamic_key_uniform() {
+    let bump = Bump::new();
+    let type_manager = TypeManager::new(&bump);
+
+    let result = analyze_source(
+        "{ a = 1, b = 2 }[key] where { key = \"a\" }",
+        &type_manager,
+        &bump,
+    );
+    assert_eq!(result.unwrap().expr.0, type_manager.int());
+}
+
+#[test]
+fn test_record_index_dynamic_key_non_uniform_fails() {
+    let bump = Bump::new();
+    let type_manager = TypeManager::new(&bump);
+
+    let err = analyze_source(
+        "{ a = 1, b = \"two\" }[key] where { key = \"a\" }",
+        &type_manager,
+        &bump,
+    )
+    .unwrap_err();
+    assert!(matches!(
+        err.kind,
+        TypeErrorKind::NonUniformRecordIndex { .. }
+    ));
+    assert_eq!(err.to_diagnostic().code, Some("E025".to_string()));
+}
+
+#[test]
+fn test_record_index_non_string_key_fails() {
+    let bump = Bump::new();
+    let type_manager = TypeManager::new(&bump);
+
+    let result = analyze_source("{ a = 1, b = 2 }[0]", &type_manager, &bump);
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_record_width_subtyping_for_globals() {
+    let bump = Bump::new();
+    let type_manager = TypeManager::new(&bump);
+    let str_ty = type_manager.str();
+    let int_ty = type_manager.int();
+    let greet_ty = type_manager.function(&[type_manager.record(vec![("name", str_ty)])], str_ty);
+    let user_ty = type_manager.record(vec![
+        ("age", int_ty),
+        ("extra", type_manager.bool()),
+        ("name", str_ty),
+    ]);
+    let partial_ty = type_manager.record(vec![("age", int_ty)]);
+    let globals = [
+        ("greet", greet_ty),
+        ("partial", partial_ty),
+        ("user", user_ty),
+    ];
+
+    // Extra fields are accepted where fewer are expected
+    let parsed = parser::parse(&bump, "greet(user)").unwrap();
+    let typed = analyze(&type_manager, &bump, parsed, &globals, &[]).unwrap();
+    assert_eq!(typed.expr.0, str_ty);
+
+    // A missing required field is still an error
+    let parsed = parser::parse(&bump, "greet(partial)").unwrap();
+    let err = analyze(&type_manager, &bump, parsed, &globals, &[]).unwrap_err();
+    assert!(matches!(
+        err.kind,
+        TypeErrorKind::MissingField { ref field } if field == "name"
+    ));
+}
+
 // ============================================================================
 // Map Tests
 // ============================================================================
@@ -1456,6 +1687,126 @@ fn test_otherwise_with_array_indexing() {
     assert_eq!(result.unwrap().expr.0, type_manager.str());
 }
 
+#[test]
+fn test_constant_index_out_of_bounds_fails() {
+    let bump = Bump::new();
+    let type_manager = TypeManager::new(&bump);
+
+    for (source, index) in [
+        ("[1, 2, 3][5]", "5"),
+        ("[1, 2, 3][-4]", "-4"),
+        ("[][0]", "0"),
+    ] {
+        let err = analyze_source(source, &type_manager, &bump).unwrap_err();
+        assert!(
+            matches!(err.kind, TypeErrorKind::IndexOutOfBounds { .. }),
+            "{source}: {:?}",
+            err.kind
+        );
+        let diagnostic = err.to_diagnostic();
+        assert_eq!(diagnostic.code, Some("E022".to_string()));
+        assert_eq!(&source[diagnostic.span.0.clone()], index);
+    }
+}
+
+#[test]
+fn test_constant_index_in_bounds_succeeds() {
+    let bump = Bump::new();
+    let type_manager = TypeManager::new(&bump);
+
+    for source in ["[1, 2, 3][2]", "[1, 2, 3][-3]"] {
+        let result = analyze_source(source, &type_manager, &bump);
+        assert!(result.is_ok(), "{source}: {:?}", result.err());
+    }
+}
+
+#[test]
+fn test_constant_index_out_of_bounds_guarded_by_otherwise() {
+    let bump = Bump::new();
+    let type_manager = TypeManager::new(&bump);
+
+    let result = analyze_source("[1, 2, 3][5] otherwise 0", &type_manager, &bump);
+    assert!(result.is_ok(), "{:?}", result.err());
+
+    // A lambda body runs wherever the lambda is called, so it is not guarded
+    let result = analyze_source(
+        "(((x) => [1, 2, 3][5]) otherwise ((x) => x))(0)",
+        &type_manager,
+        &bump,
+    );
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_dynamic_index_is_not_checked() {
+    let bump = Bump::new();
+    let type_manager = TypeManager::new(&bump);
+
+    let result = analyze_source("[1, 2, 3][x] where { x = 5 }", &type_manager, &bump);
+    assert!(result.is_ok(), "{:?}", result.err());
+}
+
+#[test]
+fn test_coalesce_unwraps_option() {
+    let bump = Bump::new();
+    let type_manager = TypeManager::new(&bump);
+
+    let result = analyze_source("some 1 ?? 2", &type_manager, &bump);
+    assert!(result.is_ok());
+    assert_eq!(result.unwrap().expr.0, type_manager.int());
+}
+
+#[test]
+fn test_coalesce_none_takes_default_type() {
+    let bump = Bump::new();
+    let type_manager = TypeManager::new(&bump);
+
+    let result = analyze_source("none ?? \"default\"", &type_manager, &bump);
+    assert!(result.is_ok());
+    assert_eq!(result.unwrap().expr.0, type_manager.str());
+}
+
+#[test]
+fn test_coalesce_non_option_fails() {
+    let bump = Bump::new();
+    let type_manager = TypeManager::new(&bump);
+
+    let result = analyze_source("1 ?? 2", &type_manager, &bump);
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_coalesce_default_type_mismatch_fails() {
+    let bump = Bump::new();
+    let type_manager = TypeManager::new(&bump);
+
+    // Default must match the option's inner type
+    let result = analyze_source("some 1 ?? \"error\"", &type_manager, &bump);
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_optional_field_wraps_field_type() {
+    let bump = Bump::new();
+    let type_manager = TypeManager::new(&bump);
+
+    let result = analyze_source("(some { x = 1 })?.x", &type_manager, &bump);
+    assert!(result.is_ok());
+    assert_eq!(
+        result.unwrap().expr.0,
+        type_manager.option(type_manager.int())
+    );
+}
+
+#[test]
+fn test_optional_field_on_non_option_fails() {
+    let bump = Bump::new();
+    let type_manager = TypeManager::new(&bump);
+
+    let result = analyze_source("{ x = 1 }?.x", &type_manager, &bump);
+    assert!(result.is_err());
+}
+
 // ============================================================================
 // Cast Tests
 // ============================================================================
@@ -1499,6 +1850,51 @@ fn test_integer_suffix_not_supported() {
     }
 }
 
+#[cfg(not(feature = "bigint"))]
+#[test]
+fn test_bigint_literal_requires_feature() {
+    let bump = Bump::new();
+    let type_manager = TypeManager::new(&bump);
+
+    let result = analyze_source("123n", &type_manager, &bump);
+    match result {
+        Err(TypeError {
+            kind: TypeErrorKind::UnsupportedFeature { suggestion, .. },
+            ..
+        }) => assert!(suggestion.contains("bigint")),
+        _ => panic!("Expected UnsupportedFeature error"),
+    }
+}
+
+#[cfg(not(feature = "decimal"))]
+#[test]
+fn test_decimal_literal_requires_feature() {
+    let bump = Bump::new();
+    let type_manager = TypeManager::new(&bump);
+
+    let result = analyze_source("0.1d", &type_manager, &bump);
+    match result {
+        Err(TypeError {
+            kind: TypeErrorKind::UnsupportedFeature { suggestion, .. },
+            ..
+        }) => assert!(suggestion.contains("decimal")),
+        _ => panic!("Expected UnsupportedFeature error"),
+    }
+}
+
+#[cfg(feature = "bigint")]
+#[test]
+fn test_bigint_literal_type() {
+    let bump = Bump::new();
+    let type_manager = TypeManager::new(&bump);
+
+    let expr = analyze_source("1n + 2n", &type_manager, &bump).unwrap();
+    assert_eq!(expr.expr.0, type_manager.bigint());
+
+    // BigInt and Int do not mix without an explicit cast
+    assert!(analyze_source("1n + 2", &type_manager, &bump).is_err());
+}
+
 // ============================================================================
 // Span Tracking
 // ============================================================================
@@ -2997,3 +3393,177 @@ fn test_exhaustiveness_nested_option_with_catch_all() {
         result
     );
 }
+
+/// Build `n` nested `where` expressions, each binding a lambda and a match.
+fn where_heavy_source(n: usize) -> String {
+    let mut source = String::from("0");
+    for i in 0..n {
+        source = format!(
+            "(f{i}(v{i}) where {{ v{i} = {source}, f{i} = (x) => x match {{ 0 -> {i}, y -> y + v{i} }} }})"
+        );
+    }
+    source
+}
+
+#[test]
+fn test_scope_depth_estimate() {
+    let arena = Bump::new();
+    let source = where_heavy_source(3);
+    let parsed = parser::parse(&arena, &source).unwrap();
+    // Three nested `where` scopes; the innermost also holds the lambda's two
+    // scopes and a match arm scope
+    assert_eq!(parsed.expr.scope_depth(), 3 + 3);
+    let parsed = parser::parse(&arena, "[1, 2] == [3]").unwrap();
+    assert_eq!(parsed.expr.scope_depth(), 0);
+}
+
+#[test]
+fn test_scope_capacity_does_not_change_analysis() {
+    let source = where_heavy_source(20);
+    let analyze_with_capacity = |scope_capacity| {
+        let arena = Bump::new();
+        let type_manager = TypeManager::new(&arena);
+        let parsed = parser::parse(&arena, &source).unwrap();
+        let typed = analyzer::analyze_with_scope_capacity(
+            type_manager,
+            &arena,
+            &parsed,
+            &[],
+            &[],
+            scope_capacity,
+            None,
+        )
+        .unwrap();
+        format!("{:?}", typed.expr)
+    };
+
+    let arena = Bump::new();
+    let estimate = parser::parse(&arena, &source).unwrap().expr.scope_depth();
+    assert_eq!(analyze_with_capacity(0), analyze_with_capacity(estimate));
+}
+
+/// The constant string a `typeof` call folded to, if it folded.
+fn folded_typeof(source: &str) -> Option<String> {
+    let arena = Bump::new();
+    let type_manager = TypeManager::new(&arena);
+    let typed = analyze_source(source, type_manager, &arena).unwrap();
+    match &typed.expr.1 {
+        typed_expr::ExprInner::Constant(value) => Some(value.as_str().unwrap().to_string()),
+        _ => None,
+    }
+}
+
+#[test]
+fn test_typeof_folds_to_constant() {
+    assert_eq!(
+        folded_typeof("typeof([1,2])").as_deref(),
+        Some("Array[Int]")
+    );
+    assert_eq!(
+        folded_typeof("typeof(some 1)").as_deref(),
+        Some("Option[Int]")
+    );
+    assert_eq!(
+        folded_typeof(r#"typeof({a = "x"})"#).as_deref(),
+        Some("Record[a: Str]")
+    );
+}
+
+#[test]
+fn test_typeof_in_polymorphic_lambda_folds_to_type_variable() {
+    let arena = Bump::new();
+    let type_manager = TypeManager::new(&arena);
+    let typed = analyze_source(
+        "describe(1) where { describe = (x) => typeof(x) }",
+        type_manager,
+        &arena,
+    )
+    .unwrap();
+    assert_eq!(typed.expr.0, type_manager.str());
+    let typed_expr::ExprInner::Where { bindings, .. } = &typed.expr.1 else {
+        panic!("Expected a where expression");
+    };
+    let typed_expr::ExprInner::Lambda { body, .. } = &bindings[0].1.1 else {
+        panic!("Expected a lambda");
+    };
+    let typed_expr::ExprInner::Constant(value) = &body.1 else {
+        panic!("Expected typeof to fold, got {:?}", body.1);
+    };
+    assert_eq!(value.as_str().unwrap(), "a");
+}
+
+#[test]
+fn test_typeof_names_type_variables_in_order() {
+    assert_eq!(folded_typeof("typeof(none)").as_deref(), Some("Option[a]"));
+    assert_eq!(
+        folded_typeof("typeof((x, y) => {x = x, y = y})").as_deref(),
+        Some("(a, b) => Record[x: a, y: b]")
+    );
+}
+
+#[test]
+fn test_shadowed_typeof_is_not_folded() {
+    assert_eq!(
+        folded_typeof(r#"typeof(1) where { typeof = (x) => "mine" }"#),
+        None
+    );
+}
+
+/// The definition of the identifier at occurrence `skip` (from zero) of
+/// `needle`, as `name@offset`.
+fn definition_of(source: &str, needle: &str, skip: usize) -> Option<String> {
+    let arena = Bump::new();
+    let type_manager = TypeManager::new(&arena);
+    let source = arena.alloc_str(source);
+    let typed = analyze_source(source, type_manager, &arena).unwrap();
+    let offset = source.match_indices(needle).nth(skip).unwrap().0;
+    definition_at(typed, offset).map(|span| format!("{}@{}", &source[span.0.clone()], span.0.start))
+}
+
+#[test]
+fn test_definition_of_where_binding() {
+    let source = "a + 1 where { a = 2 }";
+    assert_eq!(definition_of(source, "a", 0).as_deref(), Some("a@14"));
+    // Bindings may refer to each other
+    let source = "b where { a = 1, b = a * 2 }";
+    assert_eq!(definition_of(source, "a", 1).as_deref(), Some("a@10"));
+}
+
+#[test]
+fn test_definition_of_lambda_param_and_match_var() {
+    let source = "f(1) where { f = (x) => x + 1 }";
+    assert_eq!(definition_of(source, "x", 1).as_deref(), Some("x@18"));
+    let source = "some 1 match { some y -> y, none -> 0 }";
+    assert_eq!(definition_of(source, "y", 1).as_deref(), Some("y@20"));
+}
+
+#[test]
+fn test_definition_prefers_innermost_binding() {
+    let source = "x + ((x) => x)(2) where { x = 1 }";
+    assert_eq!(definition_of(source, "x", 0).as_deref(), Some("x@26"));
+    assert_eq!(definition_of(source, "x", 2).as_deref(), Some("x@6"));
+}
+
+#[test]
+fn test_definition_of_non_local() {
+    // Literals, globals, and names bound outside the expression have none
+    assert_eq!(definition_of("1 + 2", "2", 0), None);
+    assert_eq!(definition_of("max(1, 2)", "max", 0), None);
+}
+
+#[test]
+fn test_definitions_lists_every_local_use() {
+    let arena = Bump::new();
+    let type_manager = TypeManager::new(&arena);
+    let typed = analyze_source(
+        "a + f(a) where { a = 1, f = (x) => x }",
+        type_manager,
+        &arena,
+    )
+    .unwrap();
+    let starts: Vec<_> = definitions(typed)
+        .into_iter()
+        .map(|(identifier, binding)| (identifier.0.start, binding.0.start))
+        .collect();
+    assert_eq!(starts, [(0, 17), (4, 24), (6, 17), (35, 29)]);
+}
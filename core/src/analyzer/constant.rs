@@ -0,0 +1,77 @@
+//! Constant folding of boolean expressions.
+//!
+//! Shared by the linter, which warns about constant conditions, and the
+//! bytecode compiler, which drops the branches they never take.
+
+use core::cmp::Ordering;
+
+use crate::{
+    analyzer::typed_expr::{Expr, ExprInner},
+    parser::{BoolOp, ComparisonOp, UnaryOp},
+};
+
+/// Evaluate a boolean expression built only from literals, if possible.
+///
+/// Short-circuit rules apply, so `x or true` is constant even though `x` is not.
+pub(crate) fn constant_bool(expr: &Expr<'_, '_>) -> Option<bool> {
+    evaluate_constant_bool(expr, false)
+}
+
+/// Like [`constant_bool`], but only succeeds when evaluating `expr` at runtime
+/// would never touch a non-constant operand, so replacing it with the result
+/// can't skip a runtime error. `true or x` folds; `x or true` does not.
+pub(crate) fn fold_constant_bool(expr: &Expr<'_, '_>) -> Option<bool> {
+    evaluate_constant_bool(expr, true)
+}
+
+fn evaluate_constant_bool(expr: &Expr<'_, '_>, respect_evaluation_order: bool) -> Option<bool> {
+    match &expr.1 {
+        ExprInner::Constant(value) => value.as_bool().ok(),
+        ExprInner::Unary {
+            op: UnaryOp::Not,
+            expr: inner,
+        } => evaluate_constant_bool(inner, respect_evaluation_order).map(|value| !value),
+        ExprInner::Boolean { op, left, right } => {
+            let absorbing = matches!(op, BoolOp::Or);
+            let left = evaluate_constant_bool(left, respect_evaluation_order);
+            if left == Some(absorbing) {
+                return Some(absorbing);
+            }
+            if respect_evaluation_order && left.is_none() {
+                return None;
+            }
+            let right = evaluate_constant_bool(right, respect_evaluation_order);
+            if right == Some(absorbing) {
+                Some(absorbing)
+            } else {
+                left.zip(right).map(|_| !absorbing)
+            }
+        }
+        ExprInner::Comparison { op, left, right } => {
+            let (ExprInner::Constant(left), ExprInner::Constant(right)) = (&left.1, &right.1)
+            else {
+                return None;
+            };
+            // Floats follow IEEE semantics at runtime, unlike `Value`'s total order
+            let ordering = match (left.as_float(), right.as_float()) {
+                (Ok(left), Ok(right)) => left.partial_cmp(&right),
+                _ => Some(left.cmp(right)),
+            };
+            match op {
+                ComparisonOp::Eq => Some(ordering == Some(Ordering::Equal)),
+                ComparisonOp::Neq => Some(ordering != Some(Ordering::Equal)),
+                ComparisonOp::Lt => Some(ordering == Some(Ordering::Less)),
+                ComparisonOp::Gt => Some(ordering == Some(Ordering::Greater)),
+                ComparisonOp::Le => {
+                    Some(matches!(ordering, Some(Ordering::Less | Ordering::Equal)))
+                }
+                ComparisonOp::Ge => Some(matches!(
+                    ordering,
+                    Some(Ordering::Greater | Ordering::Equal)
+                )),
+                ComparisonOp::In | ComparisonOp::NotIn => None,
+            }
+        }
+        _ => None,
+    }
+}
@@ -0,0 +1,76 @@
+//! Go-to-definition for names bound inside an expression.
+
+use crate::{
+    Vec,
+    analyzer::typed_expr::{Expr, ExprInner, ExprVisitor, TypedExpr},
+    parser::Span,
+};
+
+/// Span of the binding that the identifier at byte `offset` refers to.
+///
+/// Bindings are `where` bindings, lambda parameters, and match pattern
+/// variables; the innermost one with the identifier's name wins, like in the
+/// analyzer. Returns `None` when there is no identifier at `offset`, when it
+/// names a global or an expression parameter, or when its binding was
+/// synthesized by desugaring and has no place in the source.
+pub fn definition_at<'types, 'arena>(
+    typed: &TypedExpr<'types, 'arena>,
+    offset: usize,
+) -> Option<Span> {
+    definitions(typed)
+        .into_iter()
+        .find(|(identifier, _)| identifier.0.contains(&offset))
+        .map(|(_, binding)| binding)
+}
+
+/// Every identifier bound inside the expression, as pairs of the
+/// identifier's span and its binding's span, in source order.
+///
+/// Identifiers are resolved as described in [`definition_at`].
+pub fn definitions<'types, 'arena>(typed: &TypedExpr<'types, 'arena>) -> Vec<(Span, Span)> {
+    let mut collector = DefinitionCollector {
+        typed,
+        locals: Vec::new(),
+        definitions: Vec::new(),
+    };
+    collector.visit(typed.expr);
+    collector
+        .definitions
+        .sort_by_key(|(identifier, _)| identifier.0.start);
+    collector.definitions
+}
+
+struct DefinitionCollector<'a, 'types, 'arena> {
+    typed: &'a TypedExpr<'types, 'arena>,
+    /// Names bound by the enclosing lambdas, `where` bindings, and match arms.
+    locals: Vec<&'arena str>,
+    definitions: Vec<(Span, Span)>,
+}
+
+impl<'a, 'types, 'arena> ExprVisitor<'types, 'arena> for DefinitionCollector<'a, 'types, 'arena> {
+    fn visit(&mut self, expr: &Expr<'types, 'arena>) {
+        match &expr.1 {
+            ExprInner::Ident(name) => {
+                let Some(identifier) = self.typed.ann.span_of(expr) else {
+                    return;
+                };
+                let binding = self.locals.iter().rev().find(|local| *local == name);
+                if let Some(binding) = binding.and_then(|name| self.typed.ann.span_of_str(name)) {
+                    self.definitions.push((identifier, binding));
+                }
+            }
+            _ => self.visit_children(expr),
+        }
+    }
+
+    fn visit_scoped(
+        &mut self,
+        names: impl Iterator<Item = &'arena str>,
+        expr: &Expr<'types, 'arena>,
+    ) {
+        let depth = self.locals.len();
+        self.locals.extend(names);
+        self.visit(expr);
+        self.locals.truncate(depth);
+    }
+}
@@ -58,10 +58,14 @@ pub enum TypeErrorKind {
     FieldCountMismatch { expected: usize, found: usize },
     /// Field name mismatch in records
     FieldNameMismatch { expected: String, found: String },
+    /// Record lacks a field required by the expected record type
+    MissingField { field: String },
     /// Function parameter count mismatch
     FunctionParamCountMismatch { expected: usize, found: usize },
     /// Cannot index into a non-indexable type
     NotIndexable { ty: String },
+    /// Constant index outside the bounds of an array literal
+    IndexOutOfBounds { index: i64, length: usize },
     /// Field does not exist on record
     UnknownField {
         field: String,
@@ -85,6 +89,19 @@ pub enum TypeErrorKind {
     DuplicateParameter { name: String },
     /// Duplicate binding name in where clause
     DuplicateBinding { name: String },
+    /// Duplicate field name in record literal
+    DuplicateField { name: String },
+    /// Type not fully determined by the expression (strict inference)
+    AmbiguousType { ty: String },
+    /// `sum` of an array whose element type is not determined, like `sum([])`
+    AmbiguousSum { ty: String },
+    /// Record indexed by a non-constant key, but its fields differ in type (or it has none)
+    NonUniformRecordIndex { ty: String },
+    /// Global function not in `CompileOptions::allowed_functions`
+    FunctionNotAllowed { name: String },
+    /// `where` bindings that depend on each other in a cycle that is not made
+    /// of lambdas; each uses the next
+    RecursiveBinding { cycle: Vec<String> },
     /// Type is not formattable in format string
     NotFormattable { ty: String },
     /// Unsupported language feature
@@ -189,6 +206,12 @@ impl TypeError {
                 Some("E007"),
                 vec![],
             ),
+            TypeErrorKind::MissingField { field } => (
+                format!("Record is missing required field '{}'", field),
+                Some("E021"),
+                vec!["Records may have extra fields, but every expected field must be present"
+                    .to_string()],
+            ),
             TypeErrorKind::FunctionParamCountMismatch {
                 expected, found, ..
             } => (
@@ -202,7 +225,26 @@ impl TypeError {
             TypeErrorKind::NotIndexable { ty, .. } => (
                 format!("Cannot index into non-indexable type '{}'", ty),
                 Some("E009"),
-                vec!["Only arrays, maps, and bytes can be indexed".to_string()],
+                vec!["Only arrays, maps, bytes, and records can be indexed".to_string()],
+            ),
+            TypeErrorKind::IndexOutOfBounds { index, length } => (
+                format!(
+                    "Index {} is out of bounds for an array of length {}",
+                    index, length
+                ),
+                Some("E022"),
+                vec![
+                    if *length == 0 {
+                        "The array is empty".to_string()
+                    } else {
+                        format!(
+                            "Valid indices are 0 to {} (or -{} to -1 from the end)",
+                            length - 1,
+                            length
+                        )
+                    },
+                    "Use `otherwise` to provide a fallback if this is intentional".to_string(),
+                ],
             ),
             TypeErrorKind::UnknownField {
                 field,
@@ -263,6 +305,61 @@ impl TypeError {
                 Some("E016"),
                 vec!["Each binding in a where clause must have a unique name".to_string()],
             ),
+            TypeErrorKind::DuplicateField { name, .. } => (
+                format!("Duplicate field name '{}'", name),
+                Some("E023"),
+                vec!["Each field in a record must have a unique name".to_string()],
+            ),
+            TypeErrorKind::AmbiguousType { ty, .. } => (
+                format!("Cannot fully infer type '{}'", ty),
+                Some("E024"),
+                vec![
+                    "Strict inference requires every type to be determined by the expression"
+                        .to_string(),
+                    "Use the value where its type is known, e.g. compare `[]` with `[1]`"
+                        .to_string(),
+                ],
+            ),
+            TypeErrorKind::AmbiguousSum { ty, .. } => (
+                format!("Cannot sum '{}': its element type is unknown", ty),
+                Some("E028"),
+                vec![
+                    "The sum of an empty array is zero, which needs a known numeric type"
+                        .to_string(),
+                    "Use the array where its element type is known, e.g. `sum(if empty then [] else [1])`"
+                        .to_string(),
+                ],
+            ),
+            TypeErrorKind::NonUniformRecordIndex { ty, .. } => (
+                format!("Cannot index record '{}' with a non-constant key", ty),
+                Some("E025"),
+                vec![
+                    "Indexing a record with a computed key requires all fields to have the same type"
+                        .to_string(),
+                    "Use a constant key like `record[\"name\"]`, or a Map instead".to_string(),
+                ],
+            ),
+            TypeErrorKind::FunctionNotAllowed { name, .. } => (
+                format!("Function '{}' is not allowed", name),
+                Some("E026"),
+                vec!["Only functions on the host's allow-list can be used".to_string()],
+            ),
+            TypeErrorKind::RecursiveBinding { cycle, .. } => {
+                // Close the loop: 'a' -> 'b' -> 'a'
+                let path: Vec<String> = cycle
+                    .iter()
+                    .chain(cycle.first())
+                    .map(|name| format!("'{}'", name))
+                    .collect();
+                (
+                    format!("Recursive binding: {}", path.join(" -> ")),
+                    Some("E027"),
+                    vec![
+                        "Only lambdas may call themselves; other bindings cannot depend on themselves, directly or through other bindings in the same `where`"
+                            .to_string(),
+                    ],
+                )
+            }
             TypeErrorKind::NotFormattable { ty, .. } => (
                 format!("Cannot format type '{}' in format string", ty),
                 Some("E017"),
@@ -320,6 +417,7 @@ impl TypeError {
             Error::FieldNameMismatch { expected, found } => {
                 TypeErrorKind::FieldNameMismatch { expected, found }
             }
+            Error::MissingField { field } => TypeErrorKind::MissingField { field },
             Error::FunctionParamCountMismatch { expected, found } => {
                 TypeErrorKind::FunctionParamCountMismatch { expected, found }
             }
@@ -0,0 +1,133 @@
+//! Performance hints over type-checked expressions.
+//!
+//! Hints point out code that is correct but slow. They are advisory
+//! (`Severity::Info`): unlike lints, they are not affected by
+//! `CompileOptions::deny_warnings`.
+
+use crate::{
+    String, ToString, Vec,
+    analyzer::typed_expr::{Expr, ExprInner, ExprVisitor, TypedExpr},
+    api::{Diagnostic, Severity},
+    format,
+    parser::{ComparisonOp, Span},
+    vec,
+};
+
+/// Literals with at least this many elements are large enough to hint about.
+const LARGE_LITERAL_LENGTH: usize = 100;
+
+/// Run all performance hints over a typed expression, in source order.
+pub fn performance_hints<'types, 'arena>(typed: &TypedExpr<'types, 'arena>) -> Vec<Diagnostic> {
+    let mut hinter = Hinter {
+        typed,
+        lambda_depth: 0,
+        diagnostics: Vec::new(),
+    };
+    hinter.visit(typed.expr);
+    hinter
+        .diagnostics
+        .sort_by_key(|diagnostic| diagnostic.span.0.start);
+    hinter.diagnostics
+}
+
+struct Hinter<'a, 'types, 'arena> {
+    typed: &'a TypedExpr<'types, 'arena>,
+    /// Number of lambdas enclosing the current expression.
+    lambda_depth: usize,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl<'a, 'types, 'arena> ExprVisitor<'types, 'arena> for Hinter<'a, 'types, 'arena> {
+    fn visit(&mut self, expr: &Expr<'types, 'arena>) {
+        match &expr.1 {
+            ExprInner::Comparison {
+                op: ComparisonOp::In | ComparisonOp::NotIn,
+                left,
+                right,
+            } => match (&right.1, large_constant_literal_length(right)) {
+                (ExprInner::Array { .. }, Some(length)) => {
+                    // The membership hint covers the literal too
+                    self.hint_linear_membership(right, length);
+                    self.visit(left);
+                }
+                _ => self.visit_children(expr),
+            },
+            ExprInner::Array { .. } | ExprInner::Map { .. } if self.lambda_depth > 0 => {
+                if let Some(length) = large_constant_literal_length(expr) {
+                    self.hint_literal_in_lambda(expr, length);
+                } else {
+                    self.visit_children(expr);
+                }
+            }
+            ExprInner::Lambda { body, .. } => {
+                self.lambda_depth += 1;
+                self.visit(body);
+                self.lambda_depth -= 1;
+            }
+            _ => self.visit_children(expr),
+        }
+    }
+}
+
+impl<'a, 'types, 'arena> Hinter<'a, 'types, 'arena> {
+    fn hint_linear_membership(&mut self, array: &Expr<'types, 'arena>, length: usize) {
+        self.hint(
+            array,
+            format!(
+                "Membership test scans all {} elements of this array",
+                length
+            ),
+            "Use a map with these values as keys, like `x in {1: true, 2: true}`, for a fast lookup"
+                .to_string(),
+            "I001",
+        );
+    }
+
+    fn hint_literal_in_lambda(&mut self, literal: &Expr<'types, 'arena>, length: usize) {
+        let kind = match literal.1 {
+            ExprInner::Map { .. } => "map",
+            _ => "array",
+        };
+        self.hint(
+            literal,
+            format!(
+                "This {} with {} elements is rebuilt every time the lambda is called",
+                kind, length
+            ),
+            "Bind it in a `where` outside the lambda to build it once".to_string(),
+            "I002",
+        );
+    }
+
+    fn hint(&mut self, expr: &Expr<'types, 'arena>, message: String, help: String, code: &str) {
+        let span = self.typed.ann.span_of(expr).unwrap_or(Span(0..0));
+        self.diagnostics.push(Diagnostic {
+            severity: Severity::Info,
+            message,
+            span,
+            related: Vec::new(),
+            help: vec![help],
+            code: Some(String::from(code)),
+        });
+    }
+}
+
+/// Number of elements of an array or map literal made only of constants, if
+/// it is large enough to hint about.
+fn large_constant_literal_length(expr: &Expr<'_, '_>) -> Option<usize> {
+    let is_constant = |expr: &Expr<'_, '_>| matches!(expr.1, ExprInner::Constant(_));
+    let (length, all_constant) = match &expr.1 {
+        ExprInner::Array { elements } => (
+            elements.len(),
+            elements.iter().all(|element| is_constant(element)),
+        ),
+        ExprInner::Map { elements } => (
+            elements.len(),
+            elements
+                .iter()
+                .all(|(key, value)| is_constant(key) && is_constant(value)),
+        ),
+        _ => return None,
+    };
+    (length >= LARGE_LITERAL_LENGTH && all_constant).then_some(length)
+}
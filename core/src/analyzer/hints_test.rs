@@ -0,0 +1,83 @@
+// Tests for the performance hints.
+
+use super::{analyze, performance_hints};
+use crate::{
+    api::{Diagnostic, Severity},
+    parser,
+    types::manager::TypeManager,
+};
+use bumpalo::Bump;
+
+/// Parse, type-check (with an `x: Int` variable) and collect hints for a source string.
+fn hint_source(source: &str) -> Vec<Diagnostic> {
+    let arena = Bump::new();
+    let type_manager = TypeManager::new(&arena);
+    let parsed = parser::parse(&arena, source).expect("parse should succeed");
+    let variables = [("x", type_manager.int())];
+    let typed =
+        analyze(type_manager, &arena, parsed, &[], &variables).expect("analysis should succeed");
+    performance_hints(typed)
+}
+
+/// An array literal `[0, 1, ..., length - 1]`.
+fn int_array(length: usize) -> String {
+    let elements: Vec<String> = (0..length).map(|i| i.to_string()).collect();
+    format!("[{}]", elements.join(", "))
+}
+
+#[test]
+fn test_membership_in_large_constant_array_hints() {
+    let array = int_array(200);
+    let source = format!("x in {}", array);
+    let diagnostics = hint_source(&source);
+
+    assert_eq!(diagnostics.len(), 1);
+    let diagnostic = &diagnostics[0];
+    assert_eq!(diagnostic.severity, Severity::Info);
+    assert_eq!(diagnostic.code.as_deref(), Some("I001"));
+    assert_eq!(
+        diagnostic.message,
+        "Membership test scans all 200 elements of this array"
+    );
+    assert_eq!(diagnostic.span.str_of(&source), array);
+}
+
+#[test]
+fn test_membership_in_small_array_does_not_hint() {
+    assert!(hint_source("x in [1, 2, 3]").is_empty());
+    assert!(hint_source(&format!("x not in {}", int_array(99))).is_empty());
+}
+
+#[test]
+fn test_membership_in_array_with_variables_does_not_hint() {
+    // Not a constant lookup table: the elements change between runs
+    let mut elements: Vec<String> = (0..200).map(|i| i.to_string()).collect();
+    elements[0] = String::from("x");
+    let source = format!("x in [{}]", elements.join(", "));
+    assert!(hint_source(&source).is_empty());
+}
+
+#[test]
+fn test_large_literal_inside_lambda_hints() {
+    let array = int_array(150);
+    let source = format!("f(x) where {{ f = (n) => {}[n] }}", array);
+    let diagnostics = hint_source(&source);
+
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].severity, Severity::Info);
+    assert_eq!(diagnostics[0].code.as_deref(), Some("I002"));
+    assert_eq!(
+        diagnostics[0].message,
+        "This array with 150 elements is rebuilt every time the lambda is called"
+    );
+    assert_eq!(diagnostics[0].span.str_of(&source), array);
+}
+
+#[test]
+fn test_large_literal_outside_lambda_does_not_hint() {
+    let source = format!(
+        "f(x) where {{ table = {}, f = (n) => table[n] }}",
+        int_array(150)
+    );
+    assert!(hint_source(&source).is_empty());
+}
@@ -0,0 +1,225 @@
+//! Lints over type-checked expressions.
+//!
+//! Lints report suspicious but well-typed code as warnings. Callers decide how
+//! to surface the returned diagnostics; `Engine::compile` rejects expressions
+//! with warnings only under `CompileOptions::deny_warnings`.
+
+use crate::{
+    String, ToString, Vec,
+    analyzer::{
+        constant::constant_bool,
+        typed_expr::{Expr, ExprInner, ExprVisitor, TypedExpr},
+    },
+    api::{Diagnostic, RelatedInfo, Severity},
+    format,
+    parser::Span,
+    vec,
+};
+
+/// Run all lints over a typed expression, returning warnings in source order.
+pub fn lint<'types, 'arena>(typed: &TypedExpr<'types, 'arena>) -> Vec<Diagnostic> {
+    let mut linter = Linter {
+        typed,
+        diagnostics: Vec::new(),
+    };
+    linter.visit(typed.expr);
+    linter.diagnostics
+}
+
+struct Linter<'a, 'types, 'arena> {
+    typed: &'a TypedExpr<'types, 'arena>,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl<'a, 'types, 'arena> ExprVisitor<'types, 'arena> for Linter<'a, 'types, 'arena> {
+    fn visit(&mut self, expr: &Expr<'types, 'arena>) {
+        match &expr.1 {
+            ExprInner::If {
+                cond,
+                then_branch,
+                else_branch,
+            } => {
+                if let Some(value) = constant_bool(cond) {
+                    let (dead_branch, dead_branch_name) = if value {
+                        (else_branch, "else")
+                    } else {
+                        (then_branch, "then")
+                    };
+                    let related = self
+                        .span_of(dead_branch)
+                        .map(|span| RelatedInfo {
+                            span,
+                            message: format!(
+                                "the `{}` branch is never evaluated",
+                                dead_branch_name
+                            ),
+                        })
+                        .into_iter()
+                        .collect();
+                    self.warn_constant_condition(cond, value, related);
+                } else {
+                    self.visit(cond);
+                }
+                self.visit(then_branch);
+                self.visit(else_branch);
+            }
+            ExprInner::Boolean { left, right, .. } => {
+                self.visit_operand(left);
+                self.visit_operand(right);
+            }
+            ExprInner::Where {
+                expr: body,
+                bindings,
+            } => {
+                for (index, (name, _)) in bindings.iter().enumerate() {
+                    let used = references(body, name)
+                        || bindings
+                            .iter()
+                            .enumerate()
+                            .any(|(other, (_, value))| other != index && references(value, name));
+                    if !used && !name.starts_with('_') {
+                        self.warn_unused_binding(name);
+                    }
+                }
+                self.visit_children(expr);
+            }
+            ExprInner::Map { elements } => {
+                self.check_duplicate_keys(elements);
+                self.visit_children(expr);
+            }
+            _ => self.visit_children(expr),
+        }
+    }
+}
+
+impl<'a, 'types, 'arena> Linter<'a, 'types, 'arena> {
+    /// An operand of `and`/`or` is itself a condition.
+    fn visit_operand(&mut self, operand: &Expr<'types, 'arena>) {
+        match constant_bool(operand) {
+            Some(value) => self.warn_constant_condition(operand, value, Vec::new()),
+            None => self.visit(operand),
+        }
+    }
+
+    /// Warn on constant keys that repeat an earlier key of the same map
+    /// literal. Keys are compared the way maps compare them at runtime, so
+    /// `{0.0: a, -0.0: b}` is a duplicate too.
+    fn check_duplicate_keys(
+        &mut self,
+        elements: &[(&Expr<'types, 'arena>, &Expr<'types, 'arena>)],
+    ) {
+        for (index, (key, _)) in elements.iter().enumerate() {
+            let ExprInner::Constant(value) = &key.1 else {
+                continue;
+            };
+            let first = elements[..index].iter().find(|(earlier, _)| {
+                matches!(&earlier.1, ExprInner::Constant(earlier) if earlier.cmp(value).is_eq())
+            });
+            if let Some((first, _)) = first {
+                self.warn_duplicate_key(key, first);
+            }
+        }
+    }
+
+    fn warn_constant_condition(
+        &mut self,
+        cond: &Expr<'types, 'arena>,
+        value: bool,
+        related: Vec<RelatedInfo>,
+    ) {
+        // Synthesized nodes may lack a span; there is nothing to point at then
+        let Some(span) = self.span_of(cond) else {
+            return;
+        };
+        self.diagnostics.push(Diagnostic {
+            severity: Severity::Warning,
+            message: format!("Condition is always {}", value),
+            span,
+            related,
+            help: vec![
+                "This condition does not depend on any variable; check for a typo or simplify it"
+                    .to_string(),
+            ],
+            code: Some(String::from("W001")),
+        });
+    }
+
+    fn warn_unused_binding(&mut self, name: &str) {
+        // Names synthesized by desugaring have no place in the source
+        let Some(span) = self.typed.ann.span_of_str(name) else {
+            return;
+        };
+        self.diagnostics.push(Diagnostic {
+            severity: Severity::Warning,
+            message: format!("Unused binding `{}`", name),
+            span,
+            related: Vec::new(),
+            help: vec![format!(
+                "Remove the binding, or rename it to `_{}` to silence this warning",
+                name
+            )],
+            code: Some(String::from("W002")),
+        });
+    }
+
+    fn warn_duplicate_key(&mut self, key: &Expr<'types, 'arena>, first: &Expr<'types, 'arena>) {
+        let Some(span) = self.span_of(key) else {
+            return;
+        };
+        if !matches!(key.1, ExprInner::Constant(_)) {
+            return;
+        }
+        let related = self
+            .span_of(first)
+            .map(|span| RelatedInfo {
+                span,
+                message: String::from("first used here"),
+            })
+            .into_iter()
+            .collect();
+        self.diagnostics.push(Diagnostic {
+            severity: Severity::Warning,
+            message: format!(
+                "Duplicate map key `{}`",
+                &self.typed.ann.source[span.0.start..span.0.end]
+            ),
+            span,
+            related,
+            help: vec![
+                "Only the last value for a key is kept; remove the other entries".to_string(),
+            ],
+            code: Some(String::from("W003")),
+        });
+    }
+
+    fn span_of(&self, expr: &Expr<'types, 'arena>) -> Option<Span> {
+        self.typed.ann.span_of(expr)
+    }
+}
+
+/// Whether `name` appears as an identifier anywhere inside `expr`.
+///
+/// Shadowing is ignored, so this may report a use that actually refers to an
+/// inner binding of the same name; the unused-binding lint then stays silent.
+fn references(expr: &Expr<'_, '_>, name: &str) -> bool {
+    let mut finder = ReferenceFinder { name, found: false };
+    finder.visit(expr);
+    finder.found
+}
+
+struct ReferenceFinder<'a> {
+    name: &'a str,
+    found: bool,
+}
+
+impl<'a, 'types, 'arena> ExprVisitor<'types, 'arena> for ReferenceFinder<'a> {
+    fn visit(&mut self, expr: &Expr<'types, 'arena>) {
+        if self.found {
+            return;
+        }
+        match &expr.1 {
+            ExprInner::Ident(ident) => self.found = *ident == self.name,
+            _ => self.visit_children(expr),
+        }
+    }
+}
@@ -0,0 +1,185 @@
+use super::{analyze, lint};
+use crate::{
+    api::{Diagnostic, Severity},
+    parser,
+    types::manager::TypeManager,
+};
+use bumpalo::Bump;
+
+/// Parse, type-check (with an `x: Bool` variable) and lint a source string.
+fn lint_source(source: &str) -> Vec<Diagnostic> {
+    let arena = Bump::new();
+    let type_manager = TypeManager::new(&arena);
+    let parsed = parser::parse(&arena, source).expect("parse should succeed");
+    let variables = [("x", type_manager.bool())];
+    let typed =
+        analyze(type_manager, &arena, parsed, &[], &variables).expect("analysis should succeed");
+    lint(typed)
+}
+
+fn snippet<'a>(source: &'a str, diagnostic: &Diagnostic) -> &'a str {
+    diagnostic.span.str_of(source)
+}
+
+#[test]
+fn test_constant_if_condition_warns_with_dead_else_branch() {
+    let source = "if true then 1 else 2";
+    let diagnostics = lint_source(source);
+
+    assert_eq!(diagnostics.len(), 1);
+    let diagnostic = &diagnostics[0];
+    assert_eq!(diagnostic.severity, Severity::Warning);
+    assert_eq!(diagnostic.message, "Condition is always true");
+    assert_eq!(snippet(source, diagnostic), "true");
+    assert_eq!(diagnostic.related.len(), 1);
+    assert_eq!(diagnostic.related[0].span.str_of(source), "2");
+    assert!(
+        diagnostic.related[0]
+            .message
+            .contains("`else` branch is never evaluated")
+    );
+}
+
+#[test]
+fn test_constant_false_if_condition_marks_then_branch_dead() {
+    let source = "if 1 == 2 then 10 else 20";
+    let diagnostics = lint_source(source);
+
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].message, "Condition is always false");
+    assert_eq!(snippet(source, &diagnostics[0]), "1 == 2");
+    assert_eq!(diagnostics[0].related[0].span.str_of(source), "10");
+}
+
+#[test]
+fn test_constant_comparison_and_short_circuit_in_if() {
+    // Short-circuiting makes the whole condition constant: one warning, not two
+    let source = "if x or true then 1 else 2";
+    let diagnostics = lint_source(source);
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(snippet(source, &diagnostics[0]), "x or true");
+
+    let diagnostics = lint_source("if not (\"a\" < \"b\") then 1 else 2");
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].message, "Condition is always false");
+}
+
+#[test]
+fn test_constant_boolean_operand_warns() {
+    let source = "x or true";
+    let diagnostics = lint_source(source);
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].message, "Condition is always true");
+    assert_eq!(snippet(source, &diagnostics[0]), "true");
+
+    let source = "(1 > 2) and x";
+    let diagnostics = lint_source(source);
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].message, "Condition is always false");
+    assert_eq!(snippet(source, &diagnostics[0]), "1 > 2");
+}
+
+#[test]
+fn test_nested_constant_condition_warns() {
+    let source = "[if x then (if false then 1 else 2) else 3]";
+    let diagnostics = lint_source(source);
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(snippet(source, &diagnostics[0]), "false");
+}
+
+#[test]
+fn test_variable_conditions_do_not_warn() {
+    for source in [
+        "if x then 1 else 2",
+        "if not x then 1 else 2",
+        "x and not x",
+        "if y > 1 then 1 else 2 where { y = 3 }",
+        "if (x or x) and x then 1 else 2",
+        "(a) => if a == 1 then true else false",
+        "true",
+        "1 == 1",
+    ] {
+        assert!(
+            lint_source(source).is_empty(),
+            "unexpected warning for {:?}",
+            source
+        );
+    }
+}
+
+#[test]
+fn test_unused_where_binding_warns() {
+    let source = "x where { unused = 1 + 2 }";
+    let diagnostics = lint_source(source);
+
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].severity, Severity::Warning);
+    assert_eq!(diagnostics[0].message, "Unused binding `unused`");
+    assert_eq!(snippet(source, &diagnostics[0]), "unused");
+
+    // Points at the name, not at the value
+    let diagnostics = lint_source("y where { x = 1, y = 2 }");
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].span, parser::Span(10..11));
+}
+
+#[test]
+fn test_used_where_bindings_do_not_warn() {
+    for source in [
+        "a + b where { a = 1, b = 2 }",
+        // Used only by another binding
+        "b where { a = 1, b = a + 1 }",
+        // Used only inside a lambda (captured)
+        "f(1) where { n = 2, f = (y) => y * n }",
+        // Leading underscore opts out
+        "1 where { _ignored = 2 }",
+    ] {
+        assert!(
+            lint_source(source).is_empty(),
+            "unexpected warning for {:?}",
+            source
+        );
+    }
+}
+
+#[test]
+fn test_duplicate_map_key_warns_with_first_key() {
+    let source = r#"{"a": 1, "b": 2, "a": 3}"#;
+    let diagnostics = lint_source(source);
+
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].message, r#"Duplicate map key `"a"`"#);
+    assert_eq!(diagnostics[0].code.as_deref(), Some("W003"));
+    assert_eq!(diagnostics[0].span.0, 17..20);
+    assert_eq!(diagnostics[0].related.len(), 1);
+    assert_eq!(diagnostics[0].related[0].span.0, 1..4);
+}
+
+#[test]
+fn test_duplicate_float_map_key_uses_map_equality() {
+    let diagnostics = lint_source("{0.0: 1, -0.0: 2}");
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].message, "Duplicate map key `-0.0`");
+}
+
+#[test]
+fn test_duplicate_map_key_message_quotes_the_source() {
+    let diagnostics = lint_source("{1.5: 1, 1.50: 2}");
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].message, "Duplicate map key `1.50`");
+}
+
+#[test]
+fn test_distinct_or_dynamic_map_keys_do_not_warn() {
+    for source in [
+        "{1: 10, 2: 20, -1: 30}",
+        // Not constant: left to runtime
+        "{a: 1, a: 2} where { a = 1 }",
+    ] {
+        assert!(
+            lint_source(source).is_empty(),
+            "unexpected warning for {:?}",
+            source
+        );
+    }
+}
@@ -1,9 +1,24 @@
 pub mod analyzer;
 pub mod typed_expr;
 pub mod error;
+pub mod lint;
+pub(crate) mod constant;
+pub mod ambiguity;
+pub mod usage;
+pub mod definition;
+pub mod hints;
 
 #[cfg(test)]
 mod analyzer_test;
+#[cfg(test)]
+mod lint_test;
+#[cfg(test)]
+mod hints_test;
 
-pub use analyzer::analyze;
+pub use ambiguity::check_ambiguity;
+pub use definition::{definition_at, definitions};
+pub use analyzer::{FreeVariables, analyze, analyze_with_free_variables};
 pub use error::{TypeError, TypeErrorKind};
+pub use hints::performance_hints;
+pub use lint::lint;
+pub use usage::{function_uses, used_functions};
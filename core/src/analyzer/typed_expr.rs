@@ -64,6 +64,192 @@ impl<'types, 'arena> Expr<'types, 'arena> {
     }
 }
 
+/// Number of `where` bindings from `start` on that are lambdas using each
+/// other, or 0 if `bindings[start]` is not recursive.
+///
+/// The analyzer orders bindings so that each comes after the ones it uses and
+/// keeps recursive lambdas together, so only they use themselves or later
+/// bindings.
+pub fn recursive_group_len(bindings: &[(&str, &Expr<'_, '_>)], start: usize) -> usize {
+    let mut end = start;
+    let mut index = start;
+    loop {
+        if let ExprInner::Lambda { captures, .. } = &bindings[index].1.1 {
+            for capture in captures.iter() {
+                if let Some(position) = bindings[start..]
+                    .iter()
+                    .position(|(name, _)| name == capture)
+                {
+                    end = end.max(start + position + 1);
+                }
+            }
+        }
+        index += 1;
+        if index >= end {
+            return end - start;
+        }
+    }
+}
+
+/// Whether `expr` computes the same value every time, without calling
+/// anything.
+///
+/// Constant expressions use only literals, operators, and names bound inside
+/// them (`where` bindings and match variables): no parameters, no globals, no
+/// calls, and no lambdas. Evaluating one is not necessarily cheap: each
+/// binding can double the length of a string formatted from the previous one.
+pub fn is_constant(expr: &Expr<'_, '_>) -> bool {
+    let mut checker = ConstantChecker {
+        locals: alloc::vec::Vec::new(),
+        constant: true,
+    };
+    checker.visit(expr);
+    checker.constant
+}
+
+struct ConstantChecker<'arena> {
+    /// Names bound by the enclosing `where` bindings and match arms.
+    locals: alloc::vec::Vec<&'arena str>,
+    constant: bool,
+}
+
+impl<'types, 'arena> ExprVisitor<'types, 'arena> for ConstantChecker<'arena> {
+    fn visit(&mut self, expr: &Expr<'types, 'arena>) {
+        if !self.constant {
+            return;
+        }
+        match &expr.1 {
+            ExprInner::Ident(name) => self.constant = self.locals.contains(name),
+            ExprInner::Call { .. } | ExprInner::Lambda { .. } => self.constant = false,
+            _ => self.visit_children(expr),
+        }
+    }
+
+    fn visit_scoped(
+        &mut self,
+        names: impl Iterator<Item = &'arena str>,
+        expr: &Expr<'types, 'arena>,
+    ) {
+        let depth = self.locals.len();
+        self.locals.extend(names);
+        self.visit(expr);
+        self.locals.truncate(depth);
+    }
+}
+
+/// Walks the subexpressions of a typed expression.
+///
+/// Implementors override [`visit`](ExprVisitor::visit) for the expressions
+/// they care about and call [`visit_children`](ExprVisitor::visit_children)
+/// for the rest. Visitors that resolve names also override
+/// [`visit_scoped`](ExprVisitor::visit_scoped) to track the names bound inside
+/// the expression.
+pub trait ExprVisitor<'types, 'arena> {
+    /// Visit `expr`. By default, visits its children.
+    fn visit(&mut self, expr: &Expr<'types, 'arena>) {
+        self.visit_children(expr);
+    }
+
+    /// Visit a subexpression with `names` bound: a lambda's body with its
+    /// parameters, each `where` binding and the body with all binding names
+    /// (like in the analyzer), and a match arm's body with its pattern
+    /// variables. By default, ignores the names.
+    fn visit_scoped(
+        &mut self,
+        _names: impl Iterator<Item = &'arena str>,
+        expr: &Expr<'types, 'arena>,
+    ) {
+        self.visit(expr);
+    }
+
+    /// Visit the direct subexpressions of `expr`, in evaluation order.
+    fn visit_children(&mut self, expr: &Expr<'types, 'arena>) {
+        match &expr.1 {
+            ExprInner::Constant(_) | ExprInner::Ident(_) | ExprInner::Option { inner: None } => {}
+            ExprInner::Binary { left, right, .. }
+            | ExprInner::Boolean { left, right, .. }
+            | ExprInner::Comparison { left, right, .. }
+            | ExprInner::Index {
+                value: left,
+                index: right,
+            }
+            | ExprInner::Otherwise {
+                primary: left,
+                fallback: right,
+            }
+            | ExprInner::Coalesce {
+                option: left,
+                default: right,
+            } => {
+                self.visit(left);
+                self.visit(right);
+            }
+            ExprInner::Unary { expr: inner, .. }
+            | ExprInner::Cast { expr: inner }
+            | ExprInner::Field { value: inner, .. }
+            | ExprInner::Option { inner: Some(inner) } => self.visit(inner),
+            ExprInner::If {
+                cond,
+                then_branch,
+                else_branch,
+            } => {
+                self.visit(cond);
+                self.visit(then_branch);
+                self.visit(else_branch);
+            }
+            ExprInner::Call { callable, args } => {
+                self.visit(callable);
+                for arg in args.iter() {
+                    self.visit(arg);
+                }
+            }
+            ExprInner::Lambda { params, body, .. } => {
+                self.visit_scoped(params.iter().copied(), body);
+            }
+            ExprInner::Where {
+                expr: body,
+                bindings,
+            } => {
+                let names = || bindings.iter().map(|(name, _)| *name);
+                for (_, value) in bindings.iter() {
+                    self.visit_scoped(names(), value);
+                }
+                self.visit_scoped(names(), body);
+            }
+            ExprInner::Match {
+                expr: scrutinee,
+                arms,
+            } => {
+                self.visit(scrutinee);
+                for arm in arms.iter() {
+                    self.visit_scoped(arm.vars.iter().copied(), arm.body);
+                }
+            }
+            ExprInner::Record { fields } => {
+                for (_, value) in fields.iter() {
+                    self.visit(value);
+                }
+            }
+            ExprInner::Map { elements } => {
+                for (key, value) in elements.iter() {
+                    self.visit(key);
+                    self.visit(value);
+                }
+            }
+            ExprInner::Array { elements } => {
+                for element in elements.iter() {
+                    self.visit(element);
+                }
+            }
+            ExprInner::FormatStr { exprs, .. } => {
+                for element in exprs.iter() {
+                    self.visit(element);
+                }
+            }
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum ExprInner<'types, 'arena> {
     Binary {
@@ -118,6 +304,11 @@ pub enum ExprInner<'types, 'arena> {
         primary: &'arena Expr<'types, 'arena>,
         fallback: &'arena Expr<'types, 'arena>,
     },
+    /// Option coalescing: unwraps `option`, or evaluates `default` when none
+    Coalesce {
+        option: &'arena Expr<'types, 'arena>,
+        default: &'arena Expr<'types, 'arena>,
+    },
     /// Option constructor
     Option {
         inner: Option<&'arena Expr<'types, 'arena>>,
@@ -0,0 +1,125 @@
+//! Global functions used by an expression, for auditing.
+//!
+//! Hosts that restrict which functions an expression may call (allow-lists)
+//! can check the result instead of walking the typed AST themselves.
+
+use alloc::collections::BTreeSet;
+
+use crate::{
+    String, ToString, Vec,
+    analyzer::typed_expr::{Expr, ExprInner, ExprVisitor, TypedExpr},
+    format,
+    parser::Span,
+    stdlib::builtins,
+    types::Type,
+};
+
+/// Names of the global functions an expression uses, sorted and deduplicated.
+///
+/// Package members are reported with their package, like `Math.Sqrt`. A
+/// function passed as a value (as in `Array.Map(xs, Math.Sqrt)`) counts as
+/// used, since the callee may call it, and so does every function of a
+/// package used as a value (as in `m.Floor(x) where { m = Math }`). Names
+/// bound inside the expression (lambdas, `where` bindings, match patterns)
+/// are local, never global. The internal builtins that comprehensions desugar
+/// to are not reported.
+///
+/// `is_global` tells whether a name not bound inside the expression refers to
+/// a global, as opposed to an expression parameter.
+pub fn used_functions<'types, 'arena>(
+    typed: &TypedExpr<'types, 'arena>,
+    is_global: impl Fn(&str) -> bool,
+) -> Vec<String> {
+    let names: BTreeSet<String> = function_uses(typed, is_global)
+        .into_iter()
+        .map(|(name, _)| name)
+        .collect();
+    names.into_iter().collect()
+}
+
+/// Every use of a global function, with its span, in source order.
+///
+/// Uses are found as described in [`used_functions`].
+pub fn function_uses<'types, 'arena>(
+    typed: &TypedExpr<'types, 'arena>,
+    is_global: impl Fn(&str) -> bool,
+) -> Vec<(String, Span)> {
+    let mut collector = UsageCollector {
+        typed,
+        is_global,
+        locals: Vec::new(),
+        uses: Vec::new(),
+    };
+    collector.visit(typed.expr);
+    collector.uses.sort_by_key(|(_, span)| span.0.start);
+    collector.uses
+}
+
+struct UsageCollector<'a, 'types, 'arena, F> {
+    typed: &'a TypedExpr<'types, 'arena>,
+    is_global: F,
+    /// Names bound by the enclosing lambdas, `where` bindings, and match arms.
+    locals: Vec<&'arena str>,
+    uses: Vec<(String, Span)>,
+}
+
+impl<'a, 'types, 'arena, F: Fn(&str) -> bool> ExprVisitor<'types, 'arena>
+    for UsageCollector<'a, 'types, 'arena, F>
+{
+    fn visit(&mut self, expr: &Expr<'types, 'arena>) {
+        match &expr.1 {
+            ExprInner::Ident(name) => {
+                // Internal builtins are called by desugared syntax, not by the user
+                let internal = builtins::lookup(name).is_some_and(|builtin| builtin.is_internal());
+                if self.is_global(name) && !internal {
+                    self.add_value_uses(name.to_string(), expr.0, expr);
+                }
+            }
+            ExprInner::Field { value, field } => match value.1 {
+                ExprInner::Ident(package) if self.is_global(package) => {
+                    self.add_value_uses(format!("{}.{}", package, field), expr.0, expr);
+                }
+                _ => self.visit(value),
+            },
+            _ => self.visit_children(expr),
+        }
+    }
+
+    fn visit_scoped(
+        &mut self,
+        names: impl Iterator<Item = &'arena str>,
+        expr: &Expr<'types, 'arena>,
+    ) {
+        let depth = self.locals.len();
+        self.locals.extend(names);
+        self.visit(expr);
+        self.locals.truncate(depth);
+    }
+}
+
+impl<'a, 'types, 'arena, F: Fn(&str) -> bool> UsageCollector<'a, 'types, 'arena, F> {
+    /// Add the functions reachable from a global used as a value.
+    ///
+    /// A package used other than through a field access (bound, passed, or
+    /// chosen by an `if`) may have any of its functions called later.
+    fn add_value_uses(&mut self, name: String, ty: &Type<'types>, expr: &Expr<'types, 'arena>) {
+        match ty {
+            Type::Function { .. } => self.add_use(name, expr),
+            Type::Record(fields) => {
+                for (field, field_ty) in fields.iter() {
+                    self.add_value_uses(format!("{}.{}", name, field), field_ty, expr);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn add_use(&mut self, name: String, expr: &Expr<'types, 'arena>) {
+        let span = self.typed.ann.span_of(expr).unwrap_or(Span(0..0));
+        self.uses.push((name, span));
+    }
+
+    fn is_global(&self, name: &str) -> bool {
+        !self.locals.contains(&name) && (self.is_global)(name)
+    }
+}
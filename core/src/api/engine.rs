@@ -1,10 +1,17 @@
 //! The Melbi compilation engine.
 
-use super::{CompileOptionsOverride, CompiledExpression, EngineOptions, EnvironmentBuilder, Error};
+use super::expression::LazyBytecode;
+use super::{
+    CompileOptionsOverride, CompiledExpression, EngineOptions, EnvironmentBuilder, Error,
+    InputSchema, RuntimeKind,
+};
+use crate::analyzer::{FreeVariables, TypeError, TypeErrorKind};
+use crate::stdlib::builtins;
 use crate::types::{Type, manager::TypeManager};
 use crate::values::binder::Binder;
 use crate::values::dynamic::Value;
-use crate::{Vec, analyzer, parser};
+use crate::values::function::NamedFunction;
+use crate::{ToString, Vec, analyzer, parser};
 use bumpalo::Bump;
 
 /// The Melbi compilation and execution engine.
@@ -96,6 +103,14 @@ impl<'arena> Engine<'arena> {
             .build()
             .expect("Environment should build successfully");
 
+        // Name the functions, so the function call hook reports them by their
+        // global names whatever an expression calls them
+        let environment = &*arena.alloc_slice_fill_iter(
+            environment
+                .iter()
+                .map(|&(name, value)| (name, NamedFunction::name_functions(arena, name, value))),
+        );
+
         // Precompute globals for analyzer (convert Value to Type)
         // TODO: Switch to TypeScheme when generic functions are supported
         let globals: Vec<(&'arena str, &'arena Type<'arena>)> = environment
@@ -143,6 +158,9 @@ impl<'arena> Engine<'arena> {
     /// # Returns
     ///
     /// A compiled expression ready for execution, or a compilation error.
+    /// When the engine's default runtime uses the VM, the bytecode is
+    /// compiled here too, so its limits (such as a jump too far) are reported
+    /// as compilation errors.
     ///
     /// # Example
     ///
@@ -174,33 +192,122 @@ impl<'arena> Engine<'arena> {
         params: &[(&'arena str, &'arena Type<'arena>)],
     ) -> Result<CompiledExpression<'arena>, Error> {
         // Merge compilation options (defaults + provided)
-        let mut _options = self.options.default_compile_options.clone();
-        _options.override_with(&options_override);
-        // TODO: Use merged_options when CompileOptions has fields
+        let mut options = self.options.default_compile_options.clone();
+        options.override_with(&options_override);
 
         // Parse the source
         let parsed = parser::parse(self.arena, source)?;
 
-        // Prepare parameters for analysis - copy to arena
-        // Since params is already (&str, &Type), we can just copy the slice directly
-        let params_slice = self.arena.alloc_slice_copy(params);
-
         // Type check the expression using precomputed globals
-        let typed_expr = analyzer::analyze(
-            self.type_manager,
-            self.arena,
-            &parsed,
-            self.globals_for_analyzer,
-            params_slice,
-        )?;
+        let (typed_expr, free_variables) = if options.infer_free_variables {
+            analyzer::analyze_with_free_variables(
+                self.type_manager,
+                self.arena,
+                parsed,
+                self.globals_for_analyzer,
+                params,
+            )?
+        } else {
+            let typed_expr = analyzer::analyze(
+                self.type_manager,
+                self.arena,
+                parsed,
+                self.globals_for_analyzer,
+                params,
+            )?;
+            (typed_expr, FreeVariables::default())
+        };
+
+        // Copy parameters to the arena, with inferred free variables after
+        // the declared ones
+        let params: Vec<_> = params
+            .iter()
+            .chain(&free_variables.variables)
+            .copied()
+            .collect();
+        let params_slice = self.arena.alloc_slice_copy(&params);
+        let open_record_fields = self.arena.alloc_slice_copy(&free_variables.record_fields);
+
+        if options.strict_inference {
+            analyzer::check_ambiguity(typed_expr).map_err(|error| Error::from(*error))?;
+        }
+
+        // Globals are functions bound in the environment, and builtins.
+        // Parameters shadow globals.
+        let is_global = |name: &str| {
+            !params_slice.iter().any(|(param, _)| *param == name)
+                && (self.environment.iter().any(|(global, _)| *global == name)
+                    || builtins::lookup(name).is_some())
+        };
+
+        if let Some(allowed_functions) = &options.allowed_functions {
+            let diagnostics: Vec<_> = analyzer::function_uses(typed_expr, is_global)
+                .into_iter()
+                .filter(|(name, _)| !allowed_functions.contains(name))
+                .map(|(name, span)| {
+                    TypeError::new(
+                        TypeErrorKind::FunctionNotAllowed { name },
+                        source.to_string(),
+                        span,
+                    )
+                    .to_diagnostic()
+                })
+                .collect();
+            if !diagnostics.is_empty() {
+                return Err(Error::Compilation {
+                    diagnostics,
+                    source: source.to_string(),
+                    filename: None,
+                });
+            }
+        }
+
+        if options.deny_warnings {
+            let warnings = analyzer::lint(typed_expr);
+            if !warnings.is_empty() {
+                return Err(Error::Compilation {
+                    diagnostics: warnings,
+                    source: source.to_string(),
+                    filename: None,
+                });
+            }
+        }
+
+        // Record the global functions used, for auditing
+        let used_functions = analyzer::used_functions(typed_expr, is_global);
+        let used_functions = self.arena.alloc_slice_fill_iter(
+            used_functions
+                .iter()
+                .map(|name| &*self.arena.alloc_str(name)),
+        );
 
         // Create compiled expression with default run options
-        Ok(CompiledExpression::new(
+        let compiled = CompiledExpression::new(
             typed_expr,
             self.type_manager,
-            params_slice,
+            InputSchema::new(params_slice, open_record_fields),
             self.environment,
+            used_functions,
             self.options.default_run_options.clone(),
-        ))
+            LazyBytecode::new(self.arena, options),
+        );
+
+        // Compile the bytecode now if runs use the VM by default, so
+        // bytecode limits are reported here rather than by every run. Field
+        // accesses on inferred inputs of unknown type have no record layout
+        // to compile; such expressions cannot run anyway.
+        let uses_vm = matches!(
+            self.options.default_run_options.runtime,
+            RuntimeKind::Vm | RuntimeKind::Both
+        );
+        if uses_vm && open_record_fields.is_empty() {
+            compiled.bytecode().map_err(|error| Error::Compilation {
+                diagnostics: Vec::from([error.to_diagnostic()]),
+                source: source.to_string(),
+                filename: None,
+            })?;
+        }
+
+        Ok(compiled)
     }
 }
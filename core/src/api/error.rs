@@ -5,6 +5,8 @@
 //!
 //! See docs/design/error-handling.md for the complete design.
 
+use serde::Serialize;
+
 use crate::parser::Span;
 use crate::{String, ToString, Vec, format};
 
@@ -43,6 +45,11 @@ pub enum Error {
 
     /// Resource limits exceeded (e.g., stack overflow, iteration limit).
     ResourceExceeded(String),
+
+    /// The evaluator and the VM disagree ([`RuntimeKind::Both`](super::RuntimeKind::Both)).
+    ///
+    /// Holds each runtime's result: the value, or the error message.
+    RuntimeMismatch { evaluator: String, vm: String },
 }
 
 impl Error {
@@ -71,7 +78,7 @@ impl Error {
                 source,
                 filename,
             },
-            // Api and ResourceExceeded don't have filename context
+            // Api, ResourceExceeded and RuntimeMismatch don't have filename context
             other => other,
         }
     }
@@ -107,12 +114,25 @@ impl fmt::Display for Error {
                     .iter()
                     .filter(|d| d.severity == Severity::Error)
                     .count();
+                if error_count == 0 && !diagnostics.is_empty() {
+                    // Only warnings: compiled with `deny_warnings`
+                    return write!(
+                        f,
+                        "Compilation failed with {} denied warning(s)",
+                        diagnostics.len()
+                    );
+                }
                 write!(f, "Compilation failed with {} error(s)", error_count)
             }
             Error::Runtime { diagnostic, .. } => {
                 write!(f, "Runtime error: {}", diagnostic.message)
             }
             Error::ResourceExceeded(msg) => write!(f, "Resource limit exceeded: {}", msg),
+            Error::RuntimeMismatch { evaluator, vm } => write!(
+                f,
+                "Runtime mismatch: evaluator returned {}, VM returned {}",
+                evaluator, vm
+            ),
         }
     }
 }
@@ -145,7 +165,8 @@ pub struct Diagnostic {
 }
 
 /// Severity level for diagnostics.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
 pub enum Severity {
     /// Error - compilation cannot succeed.
     Error,
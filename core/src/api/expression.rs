@@ -1,12 +1,16 @@
 //! Compiled Melbi expressions.
 
-use super::{Error, RunOptions, RunOptionsOverride};
-use crate::analyzer::typed_expr::TypedExpr;
-use crate::evaluator::{Evaluator, EvaluatorOptions};
+use super::schema::InputSchema;
+use super::{CompileOptions, Error, RunOptions, RunOptionsOverride, RuntimeKind};
+use crate::analyzer::typed_expr::{TypedExpr, is_constant};
+use crate::compiler::{BytecodeCompiler, CompileError};
+use crate::evaluator::{Evaluator, EvaluatorOptions, ExecutionError};
 use crate::types::{Type, manager::TypeManager};
-use crate::values::dynamic::Value;
-use crate::{Vec, format};
+use crate::values::{CallDepth, Fuel, dynamic::Value};
+use crate::vm::{Code, VM};
+use crate::{String, ToString, Vec, format};
 use bumpalo::Bump;
+use core::cell::OnceCell;
 
 /// A compiled Melbi expression ready for execution.
 ///
@@ -57,14 +61,44 @@ pub struct CompiledExpression<'arena> {
     /// Type manager for creating values
     type_manager: &'arena TypeManager<'arena>,
 
-    /// Parameters for validation
-    params: &'arena [(&'arena str, &'arena Type<'arena>)],
+    /// Parameters for validation, with the fields accessed on inferred
+    /// parameters whose type is still unknown
+    inputs: InputSchema<'arena>,
 
     /// Global environment for evaluation
     environment: &'arena [(&'arena str, Value<'arena, 'arena>)],
 
+    /// Global functions the expression uses, sorted
+    used_functions: &'arena [&'arena str],
+
     /// Default run-time options
     default_run_options: RunOptions,
+
+    /// Bytecode for the VM, compiled by `Engine::compile` when the default
+    /// runtime uses the VM, otherwise on the first run that needs it
+    bytecode: LazyBytecode<'arena>,
+
+    /// Result of a constant expression, evaluated on the first request
+    constant: OnceCell<Option<Result<Value<'arena, 'arena>, ExecutionError>>>,
+}
+
+/// Bytecode of an expression, compiled once with the options the expression
+/// was compiled with.
+pub(crate) struct LazyBytecode<'arena> {
+    /// Arena holding the bytecode's constants (the engine's)
+    arena: &'arena Bump,
+    options: CompileOptions,
+    code: OnceCell<Result<Code<'arena>, CompileError>>,
+}
+
+impl<'arena> LazyBytecode<'arena> {
+    pub(crate) fn new(arena: &'arena Bump, options: CompileOptions) -> Self {
+        Self {
+            arena,
+            options,
+            code: OnceCell::new(),
+        }
+    }
 }
 
 impl<'arena> CompiledExpression<'arena> {
@@ -74,16 +108,21 @@ impl<'arena> CompiledExpression<'arena> {
     pub(crate) fn new(
         typed_expr: &'arena TypedExpr<'arena, 'arena>,
         type_manager: &'arena TypeManager<'arena>,
-        params: &'arena [(&'arena str, &'arena Type<'arena>)],
+        inputs: InputSchema<'arena>,
         environment: &'arena [(&'arena str, Value<'arena, 'arena>)],
+        used_functions: &'arena [&'arena str],
         default_run_options: RunOptions,
+        bytecode: LazyBytecode<'arena>,
     ) -> Self {
         Self {
             typed_expr,
             type_manager,
-            params,
+            inputs,
             environment,
+            used_functions,
             default_run_options,
+            bytecode,
+            constant: OnceCell::new(),
         }
     }
 
@@ -146,17 +185,17 @@ impl<'arena> CompiledExpression<'arena> {
         args: &[Value<'arena, 'value_arena>],
     ) -> Result<Value<'arena, 'value_arena>, Error> {
         // Validate argument count
-        if args.len() != self.params.len() {
+        if args.len() != self.inputs.params.len() {
             return Err(Error::Api(format!(
                 "Argument count mismatch: expected {}, got {}",
-                self.params.len(),
+                self.inputs.params.len(),
                 args.len()
             )));
         }
 
         // Validate argument types using pointer equality (types are interned)
         for (i, (arg, (_param_name, expected_ty))) in
-            args.iter().zip(self.params.iter()).enumerate()
+            args.iter().zip(self.inputs.params.iter()).enumerate()
         {
             if !core::ptr::eq(arg.ty, *expected_ty) {
                 return Err(Error::Api(format!(
@@ -179,7 +218,7 @@ impl<'arena> CompiledExpression<'arena> {
     /// # Safety
     ///
     /// The caller must ensure:
-    /// - Argument count matches `self.params().len()`
+    /// - Argument count matches `self.inputs.params().len()`
     /// - Each argument's type matches the corresponding parameter type
     /// - Arguments were created with the same TypeManager as the expression
     ///
@@ -233,26 +272,15 @@ impl<'arena> CompiledExpression<'arena> {
         let mut run_options = self.default_run_options.clone();
         run_options.override_with(&options_override);
 
-        // Create evaluator options from execution options
-        // TODO: EvaluatorOptions should use RunOptions directly or provide a From impl
-        // Note: EvaluatorOptions currently only supports max_depth
-        // When EvaluatorOptions gains more fields, update this conversion
-        let evaluator_opts = EvaluatorOptions {
-            max_depth: run_options.max_depth,
-        };
-
         // Prepare variables for evaluation (params = args)
         // Copy parameter names into the value arena so lifetimes match
         let mut variables = Vec::new();
-        for ((name, _ty), value) in self.params.iter().zip(args.iter()) {
+        for ((name, _ty), value) in self.inputs.params.iter().zip(args.iter()) {
             let name_in_value_arena: &'value_arena str = arena.alloc_str(name);
             variables.push((name_in_value_arena, *value));
         }
         let variables_slice = arena.alloc_slice_copy(&variables);
 
-        let globals: &[(&str, Value<'arena, 'value_arena>)] = self.environment;
-
-        // Evaluate the expression
         // SAFETY: We transmute the expression lifetime to match the evaluator's arena lifetime.
         // This is safe because:
         // 1. The expression is only borrowed for the duration of eval()
@@ -261,29 +289,303 @@ impl<'arena> CompiledExpression<'arena> {
         let expr_for_eval: &'value_arena TypedExpr<'arena, 'value_arena> =
             unsafe { core::mem::transmute(self.typed_expr) };
 
-        // Create evaluator and execute
+        // Execute and convert errors to public Error type
+        match run_options.runtime {
+            RuntimeKind::Evaluator => self
+                .run_evaluator(&run_options, arena, expr_for_eval, variables_slice)
+                .map_err(Error::from),
+            RuntimeKind::Vm => self
+                .run_vm(&run_options, arena, variables_slice)?
+                .map_err(Error::from),
+            RuntimeKind::Both => {
+                let evaluator_result =
+                    self.run_evaluator(&run_options, arena, expr_for_eval, variables_slice);
+                let vm_result = self.run_vm(&run_options, arena, variables_slice)?;
+                check_runtimes_agree(evaluator_result, vm_result)
+            }
+        }
+    }
+
+    /// Execute with the tree-walking evaluator.
+    fn run_evaluator<'value_arena>(
+        &self,
+        run_options: &RunOptions,
+        arena: &'value_arena Bump,
+        expr: &'value_arena TypedExpr<'arena, 'value_arena>,
+        variables: &[(&'value_arena str, Value<'arena, 'value_arena>)],
+    ) -> Result<Value<'arena, 'value_arena>, ExecutionError> {
+        // Create evaluator options from execution options
+        // TODO: EvaluatorOptions should use RunOptions directly or provide a From impl
+        // When EvaluatorOptions gains more fields, update this conversion
+        let evaluator_opts = EvaluatorOptions {
+            max_depth: run_options.max_depth,
+            preserve_record_field_order: run_options.preserve_record_field_order,
+            on_function_call: run_options.on_function_call.clone(),
+        };
+
+        let globals: &[(&str, Value<'arena, 'value_arena>)] = self.environment;
+
         let mut evaluator = Evaluator::new(
             evaluator_opts,
             arena,
             self.type_manager,
-            expr_for_eval,
+            expr,
             globals,
-            variables_slice,
+            variables,
+        );
+        evaluator.set_fuel(
+            run_options
+                .max_fuel
+                .map(|max_fuel| &*arena.alloc(Fuel::new(max_fuel))),
         );
+        evaluator.eval()
+    }
 
-        // Evaluate and convert errors to public Error type
-        evaluator.eval().map_err(Error::from)
+    /// The expression's bytecode, compiled on the first call.
+    ///
+    /// The bytecode reads the arguments from its first locals, so it is
+    /// compiled once and reused by every run.
+    pub(crate) fn bytecode(&self) -> Result<&Code<'arena>, CompileError> {
+        self.bytecode
+            .code
+            .get_or_init(|| {
+                let params: Vec<&'arena str> =
+                    self.inputs.params.iter().map(|(name, _)| *name).collect();
+                BytecodeCompiler::compile_with_params(
+                    self.type_manager,
+                    self.bytecode.arena,
+                    self.environment,
+                    &params,
+                    self.typed_expr,
+                    &self.bytecode.options,
+                )
+            })
+            .as_ref()
+            .map_err(Clone::clone)
+    }
+
+    /// Execute with the VM, compiling the bytecode on first use.
+    ///
+    /// Arguments shadow globals of the same name, as in the evaluator.
+    fn run_vm<'value_arena>(
+        &self,
+        run_options: &RunOptions,
+        arena: &'value_arena Bump,
+        variables: &[(&'value_arena str, Value<'arena, 'value_arena>)],
+    ) -> Result<Result<Value<'arena, 'value_arena>, ExecutionError>, CompileError> {
+        let code = self.bytecode()?;
+        let locals = variables.iter().map(|(_, value)| value.as_raw()).collect();
+        let fuel = run_options
+            .max_fuel
+            .map(|max_fuel| &*arena.alloc(Fuel::new(max_fuel)));
+        let result_type = self.typed_expr.expr.0;
+        let mut vm = VM::new(arena, code, locals, &[])
+            .with_call_depth(CallDepth {
+                depth: 0,
+                max_depth: run_options.max_depth,
+            })
+            .with_fuel(fuel)
+            .with_function_call_hook(run_options.on_function_call.clone())
+            .with_record_field_order(run_options.preserve_record_field_order);
+        Ok(vm
+            .run()
+            .map(|raw| Value::from_raw_unchecked(result_type, raw))
+            .map_err(|error| ExecutionError {
+                source: self.typed_expr.ann.source.to_string(),
+                ..error
+            }))
     }
 
     /// Get the expression's parameters.
     ///
-    /// Returns a slice of (name, type) pairs.
+    /// Returns a slice of (name, type) pairs: the declared parameters, then
+    /// any free variables inferred under
+    /// [`CompileOptions::infer_free_variables`].
     pub fn params(&self) -> &[(&'arena str, &'arena Type<'arena>)] {
-        self.params
+        self.inputs.params
+    }
+
+    /// Describe the inputs the expression expects, for building input forms.
+    ///
+    /// Every parameter, including inferred free variables, is described
+    /// structurally; serializing the result gives a JSON object mapping each
+    /// name, in alphabetical order, to its type descriptor. See
+    /// [`InputSchema`] for the format.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use melbi_core::api::{CompileOptionsOverride, Engine, EngineOptions};
+    /// use bumpalo::Bump;
+    ///
+    /// let arena = Bump::new();
+    /// let engine = Engine::new(EngineOptions::default(), &arena, |_, _, env| env);
+    /// let options = CompileOptionsOverride {
+    ///     infer_free_variables: Some(true),
+    ///     ..Default::default()
+    /// };
+    /// let expr = engine.compile(options, "x * 2", &[]).unwrap();
+    ///
+    /// let schema = serde_json::to_string(&expr.input_schema()).unwrap();
+    /// assert_eq!(schema, r#"{"x":{"type":"Int"}}"#);
+    /// ```
+    pub fn input_schema(&self) -> InputSchema<'arena> {
+        self.inputs
     }
 
     /// Get the expression's return type.
     pub fn return_type(&self) -> &'arena Type<'arena> {
         self.typed_expr.expr.0
     }
+
+    /// Get the global functions the expression uses, sorted by name.
+    ///
+    /// Package members are named with their package, like `Math.Sqrt`.
+    /// Functions defined in the expression (lambdas) are not included, and
+    /// neither are function-typed parameters. Hosts can check the result
+    /// against an allow-list.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use melbi_core::api::{Engine, EngineOptions};
+    /// use melbi_core::stdlib::register_math_package;
+    /// use bumpalo::Bump;
+    ///
+    /// let arena = Bump::new();
+    /// let engine = Engine::new(EngineOptions::default(), &arena, register_math_package);
+    /// let expr = engine
+    ///     .compile(Default::default(), "Math.Floor(Math.Sqrt(x) * Math.PI)", &[
+    ///         ("x", engine.type_manager().float()),
+    ///     ])
+    ///     .unwrap();
+    /// assert_eq!(expr.used_functions(), ["Math.Floor", "Math.Sqrt"]);
+    /// ```
+    pub fn used_functions(&self) -> Vec<&str> {
+        self.used_functions.to_vec()
+    }
+
+    /// The result of the expression, if it is a compile-time constant.
+    ///
+    /// An expression is constant when it uses only literals, operators, and
+    /// its own `where` bindings: no parameters, globals, calls, or lambdas.
+    /// Its result is the same on every run, so hosts caching results can use
+    /// it instead of calling [`run`](Self::run).
+    ///
+    /// Returns `None` for other expressions, without evaluating them. For a
+    /// constant, returns its value, or the error its evaluation fails with,
+    /// like division by zero in `1 / 0`. The result is computed on the first
+    /// call with the evaluator, in the engine's arena and under the
+    /// expression's default run options (so an engine's fuel budget applies),
+    /// and later calls return it without evaluating anything.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use melbi_core::api::{Engine, EngineOptions};
+    /// use bumpalo::Bump;
+    ///
+    /// let arena = Bump::new();
+    /// let engine = Engine::new(EngineOptions::default(), &arena, |_, _, env| env);
+    /// let expr = engine.compile(Default::default(), "2 + 3 * 4", &[]).unwrap();
+    /// let value = expr.constant_fold_result().unwrap().unwrap();
+    /// assert_eq!(value.as_int().unwrap(), 14);
+    ///
+    /// let expr = engine.compile(Default::default(), "1 / 0", &[]).unwrap();
+    /// assert!(expr.constant_fold_result().unwrap().is_err());
+    ///
+    /// let int_ty = engine.type_manager().int();
+    /// let expr = engine.compile(Default::default(), "x + 1", &[("x", int_ty)]).unwrap();
+    /// assert!(expr.constant_fold_result().is_none());
+    /// ```
+    pub fn constant_fold_result(&self) -> Option<Result<Value<'arena, 'arena>, &ExecutionError>> {
+        let result = self.constant.get_or_init(|| {
+            if !is_constant(self.typed_expr.expr) {
+                return None;
+            }
+            Some(self.run_evaluator(
+                &self.default_run_options,
+                self.bytecode.arena,
+                self.typed_expr,
+                &[],
+            ))
+        });
+        result.as_ref().map(|result| result.as_ref().copied())
+    }
+}
+
+/// Check that the evaluator and the VM agree, for [`RuntimeKind::Both`].
+///
+/// Results agree if both runtimes return the same value (see [`same_value`]),
+/// or errors of the same kind. The evaluator's result is returned since its
+/// errors carry source locations.
+fn check_runtimes_agree<'types, 'arena>(
+    evaluator_result: Result<Value<'types, 'arena>, ExecutionError>,
+    vm_result: Result<Value<'types, 'arena>, ExecutionError>,
+) -> Result<Value<'types, 'arena>, Error> {
+    let agree = match (&evaluator_result, &vm_result) {
+        (Ok(evaluator_value), Ok(vm_value)) => same_value(evaluator_value, vm_value),
+        (Err(evaluator_error), Err(vm_error)) => evaluator_error.kind == vm_error.kind,
+        _ => false,
+    };
+    if !agree {
+        return Err(Error::RuntimeMismatch {
+            evaluator: describe_result(&evaluator_result),
+            vm: describe_result(&vm_result),
+        });
+    }
+    evaluator_result.map_err(Error::from)
+}
+
+/// Whether two runtimes computed the same value.
+///
+/// Unlike `==`, a NaN is the same as any other NaN, so `0.0 / 0.0` agrees
+/// with itself. Functions are the same if they have the same type: each
+/// runtime builds its own closures, so they can't be compared by identity.
+fn same_value(left: &Value<'_, '_>, right: &Value<'_, '_>) -> bool {
+    if left.ty != right.ty {
+        return false;
+    }
+    match left.ty {
+        Type::Float => {
+            let (left, right) = (left.as_float().unwrap(), right.as_float().unwrap());
+            left == right || (left.is_nan() && right.is_nan())
+        }
+        Type::Function { .. } => true,
+        Type::Array(_) => {
+            let (left, right) = (left.as_array().unwrap(), right.as_array().unwrap());
+            left.len() == right.len()
+                && left
+                    .iter()
+                    .zip(right.iter())
+                    .all(|(left, right)| same_value(&left, &right))
+        }
+        Type::Record(_) => {
+            let (left, right) = (left.as_record().unwrap(), right.as_record().unwrap());
+            left.iter()
+                .zip(right.iter())
+                .all(|((_, left), (_, right))| same_value(&left, &right))
+        }
+        Type::Map(_, _) => {
+            let (left, right) = (left.as_map().unwrap(), right.as_map().unwrap());
+            left.len() == right.len()
+                && left.iter().zip(right.iter()).all(
+                    |((left_key, left_value), (right_key, right_value))| {
+                        same_value(&left_key, &right_key) && same_value(&left_value, &right_value)
+                    },
+                )
+        }
+        Type::Option(_) => match (left.as_option().unwrap(), right.as_option().unwrap()) {
+            (Some(left), Some(right)) => same_value(&left, &right),
+            (left, right) => left.is_none() && right.is_none(),
+        },
+        _ => left == right,
+    }
+}
+
+fn describe_result(result: &Result<Value, ExecutionError>) -> String {
+    match result {
+        Ok(value) => value.to_string(),
+        Err(error) => error.kind.to_string(),
+    }
 }
@@ -36,6 +36,8 @@ mod environment;
 mod error;
 mod expression;
 mod options;
+mod schema;
+mod value_arena;
 
 pub use engine::Engine;
 pub use environment::EnvironmentBuilder;
@@ -43,4 +45,7 @@ pub use error::{Diagnostic, Error, RelatedInfo, Severity};
 pub use expression::CompiledExpression;
 pub use options::{
     CompileOptions, CompileOptionsOverride, EngineOptions, RunOptions, RunOptionsOverride,
+    RuntimeKind,
 };
+pub use schema::InputSchema;
+pub use value_arena::ReusableValueArena;
@@ -1,5 +1,9 @@
 //! Configuration options for the Melbi engine.
 
+use hashbrown::HashSet;
+
+use crate::{String, evaluator::DEFAULT_MAX_DEPTH, values::FunctionCallHook};
+
 /// Configuration options for the Melbi engine.
 ///
 /// These options set the defaults for compilation and execution,
@@ -8,13 +12,15 @@
 /// # Example
 ///
 /// ```
-/// use melbi_core::api::{EngineOptions, CompileOptions, RunOptions};
+/// use melbi_core::api::{EngineOptions, CompileOptions, RunOptions, RuntimeKind};
 ///
 /// let options = EngineOptions {
 ///     default_compile_options: CompileOptions::default(),
 ///     default_run_options: RunOptions {
 ///         max_depth: 500,
-///         max_iterations: Some(10_000),
+///         runtime: RuntimeKind::Evaluator,
+///         max_fuel: Some(10_000),
+///         ..RunOptions::default()
 ///     },
 /// };
 /// ```
@@ -53,7 +59,56 @@ impl Default for EngineOptions {
 /// ```
 #[derive(Debug, Clone)]
 pub struct CompileOptions {
-    // Future: optimization level, type checking strictness, etc.
+    /// Apply bytecode optimizations (e.g., dropping branches of `if`
+    /// expressions whose condition is a compile-time constant).
+    ///
+    /// Applies to runs on [`RuntimeKind::Vm`]; the evaluator runs the
+    /// expression as written. Optimizations never skip type checking.
+    /// Defaults to `false`.
+    pub optimize: bool,
+
+    /// Reject expressions that produce any warning diagnostic.
+    ///
+    /// When set, analyzer lints (e.g., unused bindings, constant conditions,
+    /// duplicate map keys) make `Engine::compile()` return
+    /// `Error::Compilation`. Defaults to `false`.
+    pub deny_warnings: bool,
+
+    /// Reject expressions whose types are not fully determined.
+    ///
+    /// Without this, a type the expression does not pin down (such as the
+    /// element type of `[]` or the inner type of `none`) is left generic, and
+    /// the value works at any type. When set, `Engine::compile()` returns
+    /// `Error::Compilation` instead, so hosts never rely on a guessed type.
+    /// Numeric literals are unaffected: `1` is always `Int` and `1.0` is
+    /// always `Float`. Defaults to `false`.
+    pub strict_inference: bool,
+
+    /// Accept identifiers that are neither parameters nor globals, inferring
+    /// their types from how the expression uses them.
+    ///
+    /// Each such free variable becomes an extra parameter, after the declared
+    /// ones in order of first use, and
+    /// `CompiledExpression::input_schema()` describes what it must be (such
+    /// as a record with at least an `age: Int` field for `user.age + 1`).
+    /// Running the expression needs an argument of exactly the inferred type,
+    /// so one that is only partly known (like that record) cannot run.
+    /// Defaults to `false`.
+    pub infer_free_variables: bool,
+
+    /// Global functions the expression may use, or `None` to allow all.
+    ///
+    /// Names are as reported by `CompiledExpression::used_functions()`:
+    /// package members with their package (`Math.Sqrt`), other functions by
+    /// name (`max`). When set, `Engine::compile()` returns
+    /// `Error::Compilation` with a diagnostic for each use of a function not
+    /// in the set, so hosts can restrict untrusted expressions to a safe
+    /// subset. Functions defined by the expression itself are always allowed.
+    ///
+    /// This is an engine-wide policy: `CompileOptionsOverride` cannot change
+    /// it. Defaults to `None`.
+    pub allowed_functions: Option<HashSet<String>>,
+    // Future: optimization level, etc.
 }
 
 impl CompileOptions {
@@ -61,17 +116,41 @@ impl CompileOptions {
     ///
     /// For each field, if `other` specifies a value (is `Some`), use it.
     /// Otherwise, keep the value from `self`.
-    pub fn override_with(&mut self, _other: &CompileOptionsOverride) {}
+    pub fn override_with(&mut self, other: &CompileOptionsOverride) {
+        if let Some(optimize) = other.optimize {
+            self.optimize = optimize;
+        }
+        if let Some(deny_warnings) = other.deny_warnings {
+            self.deny_warnings = deny_warnings;
+        }
+        if let Some(strict_inference) = other.strict_inference {
+            self.strict_inference = strict_inference;
+        }
+        if let Some(infer_free_variables) = other.infer_free_variables {
+            self.infer_free_variables = infer_free_variables;
+        }
+    }
 }
 
 impl Default for CompileOptions {
     fn default() -> Self {
-        Self {}
+        Self {
+            optimize: false,
+            deny_warnings: false,
+            strict_inference: false,
+            infer_free_variables: false,
+            allowed_functions: None,
+        }
     }
 }
 
 #[derive(Debug, Clone, Copy, Default)]
-pub struct CompileOptionsOverride {}
+pub struct CompileOptionsOverride {
+    pub optimize: Option<bool>,
+    pub deny_warnings: Option<bool>,
+    pub strict_inference: Option<bool>,
+    pub infer_free_variables: Option<bool>,
+}
 
 /// Configuration options for expression execution.
 ///
@@ -82,30 +161,68 @@ pub struct CompileOptionsOverride {}
 /// # Example
 ///
 /// ```
-/// use melbi_core::api::RunOptions;
+/// use melbi_core::api::{RunOptions, RuntimeKind};
 ///
-/// // Specify only max_depth, use default for max_iterations
 /// let options = RunOptions {
 ///     max_depth: 500,
-///     max_iterations: None,
+///     runtime: RuntimeKind::Evaluator,
+///     ..RunOptions::default()
 /// };
 /// ```
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone)]
 pub struct RunOptions {
-    /// Maximum evaluation stack depth (for recursion protection).
+    /// Maximum number of nested lambda calls (for recursion protection).
     ///
-    /// `None` means not specified (use default: 1000).
+    /// Both runtimes count the same way: a call nested `max_depth` calls deep
+    /// fails with `Error::ResourceExceeded`. Each nested call uses native
+    /// stack (a few KB in release builds, more in debug builds), so run on a
+    /// thread whose stack can hold `max_depth` calls. Defaults to
+    /// [`DEFAULT_MAX_DEPTH`](crate::evaluator::DEFAULT_MAX_DEPTH) (1000).
     pub max_depth: usize,
 
-    /// Maximum number of iterations in loops.
-    ///
-    /// - `None` = not specified, use default (unlimited)
-    /// - `Some(None)` = explicitly unlimited
-    /// - `Some(Some(n))` = explicitly limited to n iterations
+    /// Ignored: Melbi has no loops, and `max_fuel` bounds the work of a run.
     ///
-    /// TODO: Consider using a custom enum like `IterationLimit { Unlimited, Limited(usize) }`
-    /// instead of nested Option for better ergonomics.
+    /// Kept so existing code that sets it still compiles. Defaults to `None`.
+    #[deprecated(note = "ignored by both runtimes; use `max_fuel` to bound a run")]
     pub max_iterations: Option<usize>,
+
+    /// Display record fields in the order they were written in the source.
+    ///
+    /// Records are always stored sorted by field name, so field access,
+    /// equality and hashing are unaffected; only how a record is rendered
+    /// changes. Defaults to `false` (fields are displayed sorted).
+    pub preserve_record_field_order: bool,
+
+    /// Runtime that executes the expression. Defaults to [`RuntimeKind::Vm`].
+    pub runtime: RuntimeKind,
+
+    /// Hook called before each native function call, for audit logs or rate
+    /// limiting.
+    ///
+    /// It receives the function's global name, as reported by
+    /// `CompiledExpression::used_functions()` (`max`, `Math.Sqrt`), even when
+    /// the expression calls it by another name (`f(x) where { f = Math.Sqrt }`),
+    /// and the number of arguments. Returning an error rejects the call and
+    /// fails the run with `Error::ResourceExceeded`, which `otherwise` does not
+    /// catch. Calls made by lambdas and by native functions (like `Math.Sqrt`
+    /// in `Array.Map(xs, Math.Sqrt)`) are reported too; calls to lambdas are
+    /// not, nor calls to functions with no global name (like those passed in
+    /// as arguments). Both runtimes report the same
+    /// calls; with [`RuntimeKind::Both`], the hook sees each call twice, once
+    /// per runtime. When unset, calls cost nothing extra. Defaults to `None`.
+    pub on_function_call: Option<FunctionCallHook>,
+
+    /// Fuel budget of a run, or `None` for unlimited.
+    ///
+    /// The evaluator charges one unit per evaluated expression, and the VM one
+    /// unit per instruction of each block of code it runs (lambda bodies and
+    /// the expression itself), so the two runtimes use similar but not equal
+    /// amounts. Standard library functions charge extra in proportion to
+    /// their work (like the length of an array they copy, or `n log n` to sort
+    /// one), so a cheap-looking call over large data cannot run unbounded. A
+    /// run that uses up its budget fails with `Error::ResourceExceeded`, which
+    /// `otherwise` does not catch. Defaults to `None`.
+    pub max_fuel: Option<u64>,
 }
 
 impl RunOptions {
@@ -113,6 +230,7 @@ impl RunOptions {
     ///
     /// For each field, if `other` specifies a value (is `Some`), use it.
     /// Otherwise, keep the value from `self`.
+    #[allow(deprecated)]
     pub fn override_with(&mut self, other: &RunOptionsOverride) {
         if let Some(max_depth) = other.max_depth {
             self.max_depth = max_depth;
@@ -120,20 +238,65 @@ impl RunOptions {
         if let Some(max_iterations) = other.max_iterations {
             self.max_iterations = max_iterations;
         }
+        if let Some(preserve_record_field_order) = other.preserve_record_field_order {
+            self.preserve_record_field_order = preserve_record_field_order;
+        }
+        if let Some(runtime) = other.runtime {
+            self.runtime = runtime;
+        }
+        if let Some(on_function_call) = &other.on_function_call {
+            self.on_function_call = on_function_call.clone();
+        }
+        if let Some(max_fuel) = other.max_fuel {
+            self.max_fuel = max_fuel;
+        }
     }
 }
 
 impl Default for RunOptions {
+    #[allow(deprecated)]
     fn default() -> Self {
         Self {
-            max_depth: 1000,
-            max_iterations: None, // Unlimited by default
+            max_depth: DEFAULT_MAX_DEPTH,
+            max_iterations: None,
+            preserve_record_field_order: false,
+            runtime: RuntimeKind::default(),
+            on_function_call: None,
+            max_fuel: None,
         }
     }
 }
 
-#[derive(Debug, Clone, Copy, Default)]
+#[derive(Debug, Clone, Default)]
 pub struct RunOptionsOverride {
     pub max_depth: Option<usize>,
+    /// Ignored, like [`RunOptions::max_iterations`].
+    #[deprecated(note = "ignored by both runtimes; use `max_fuel` to bound a run")]
     pub max_iterations: Option<Option<usize>>,
+    pub preserve_record_field_order: Option<bool>,
+    pub runtime: Option<RuntimeKind>,
+    pub on_function_call: Option<Option<FunctionCallHook>>,
+    pub max_fuel: Option<Option<u64>>,
+}
+
+/// Runtime that executes a compiled expression.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RuntimeKind {
+    /// Tree-walking evaluator.
+    Evaluator,
+    /// Bytecode VM.
+    ///
+    /// The expression is compiled to bytecode on its first run on the VM, with
+    /// the options it was compiled with, and later runs reuse the bytecode.
+    #[default]
+    Vm,
+    /// Run both and check that they agree.
+    ///
+    /// Intended for differential testing: returns the result when both
+    /// runtimes produce the same value (or the same error), and
+    /// [`Error::RuntimeMismatch`](super::Error::RuntimeMismatch) otherwise.
+    /// Values compare structurally, with NaN the same as NaN and functions
+    /// the same if their types are. The evaluator runs first, then the VM,
+    /// so the function call hook is called for each call in both runs.
+    Both,
 }
@@ -0,0 +1,184 @@
+//! JSON descriptions of the inputs an expression expects.
+//!
+//! Each input is described structurally, so host tooling can build input
+//! forms without understanding Melbi's type syntax:
+//!
+//! ```text
+//! {"user": {"type": "Record", "open": true, "fields": {"age": {"type": "Int"}}}}
+//! ```
+//!
+//! A record is `open` when it was only inferred from field accesses: the value
+//! must have at least the listed fields. Unconstrained values are `Any`.
+
+use serde::ser::{Serialize, SerializeMap, Serializer};
+
+use crate::{
+    Vec,
+    types::{
+        Type,
+        traits::{TypeKind, TypeView},
+    },
+};
+
+/// Fields accessed on values whose type is still a type variable, as
+/// `(type variable id, field name, field type)`.
+pub(super) type OpenRecordFields<'a> = [(u16, &'a str, &'a Type<'a>)];
+
+/// The inputs of a compiled expression, serialized as a JSON object mapping
+/// each input name, in alphabetical order, to a structural type descriptor.
+///
+/// Returned by `CompiledExpression::input_schema()`; pass it to any serde
+/// serializer (such as `serde_json::to_string`).
+#[derive(Clone, Copy)]
+pub struct InputSchema<'a> {
+    pub(super) params: &'a [(&'a str, &'a Type<'a>)],
+    open_record_fields: &'a OpenRecordFields<'a>,
+}
+
+impl<'a> InputSchema<'a> {
+    pub(super) fn new(
+        params: &'a [(&'a str, &'a Type<'a>)],
+        open_record_fields: &'a OpenRecordFields<'a>,
+    ) -> Self {
+        Self {
+            params,
+            open_record_fields,
+        }
+    }
+
+    fn describe(&self, ty: &'a Type<'a>) -> TypeSchema<'a> {
+        TypeSchema {
+            ty,
+            open_record_fields: self.open_record_fields,
+        }
+    }
+}
+
+impl Serialize for InputSchema<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut params = self.params.to_vec();
+        params.sort_by_key(|(name, _)| *name);
+        serializer.collect_map(
+            params
+                .into_iter()
+                .map(|(name, ty)| (name, self.describe(ty))),
+        )
+    }
+}
+
+/// Structural descriptor of one type.
+struct TypeSchema<'a> {
+    ty: &'a Type<'a>,
+    open_record_fields: &'a OpenRecordFields<'a>,
+}
+
+impl<'a> TypeSchema<'a> {
+    fn describe(&self, ty: &'a Type<'a>) -> Self {
+        Self {
+            ty,
+            open_record_fields: self.open_record_fields,
+        }
+    }
+
+    fn serialize_record<S: Serializer>(
+        &self,
+        serializer: S,
+        fields: impl Iterator<Item = (&'a str, &'a Type<'a>)>,
+        open: bool,
+    ) -> Result<S::Ok, S::Error> {
+        let fields = Fields {
+            schema: self,
+            fields: fields.collect(),
+        };
+        let mut map = serializer.serialize_map(Some(3))?;
+        map.serialize_entry("type", "Record")?;
+        map.serialize_entry("open", &open)?;
+        map.serialize_entry("fields", &fields)?;
+        map.end()
+    }
+}
+
+impl Serialize for TypeSchema<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self.ty.view() {
+            TypeKind::TypeVar(var_id) => {
+                let mut fields: Vec<(&str, &Type<'_>)> = self
+                    .open_record_fields
+                    .iter()
+                    .filter(|(other_id, _, _)| *other_id == var_id)
+                    .map(|(_, name, field_ty)| (*name, *field_ty))
+                    .collect();
+                if fields.is_empty() {
+                    scalar(serializer, "Any")
+                } else {
+                    fields.sort_by_key(|(name, _)| *name);
+                    self.serialize_record(serializer, fields.into_iter(), true)
+                }
+            }
+            TypeKind::Int => scalar(serializer, "Int"),
+            TypeKind::Float => scalar(serializer, "Float"),
+            TypeKind::Bool => scalar(serializer, "Bool"),
+            TypeKind::Str => scalar(serializer, "Str"),
+            TypeKind::Bytes => scalar(serializer, "Bytes"),
+            TypeKind::BigInt => scalar(serializer, "BigInt"),
+            TypeKind::Decimal => scalar(serializer, "Decimal"),
+            TypeKind::Array(element) => {
+                let mut map = serializer.serialize_map(Some(2))?;
+                map.serialize_entry("type", "Array")?;
+                map.serialize_entry("element", &self.describe(element))?;
+                map.end()
+            }
+            TypeKind::Map(key, value) => {
+                let mut map = serializer.serialize_map(Some(3))?;
+                map.serialize_entry("type", "Map")?;
+                map.serialize_entry("key", &self.describe(key))?;
+                map.serialize_entry("value", &self.describe(value))?;
+                map.end()
+            }
+            TypeKind::Option(inner) => {
+                let mut map = serializer.serialize_map(Some(2))?;
+                map.serialize_entry("type", "Option")?;
+                map.serialize_entry("inner", &self.describe(inner))?;
+                map.end()
+            }
+            TypeKind::Record(fields) => self.serialize_record(serializer, fields, false),
+            TypeKind::Function { params, ret } => {
+                let params: Vec<_> = params.map(|param| self.describe(param)).collect();
+                let mut map = serializer.serialize_map(Some(3))?;
+                map.serialize_entry("type", "Function")?;
+                map.serialize_entry("params", &params)?;
+                map.serialize_entry("result", &self.describe(ret))?;
+                map.end()
+            }
+            TypeKind::Symbol(parts) => {
+                let parts: Vec<&str> = parts.collect();
+                let mut map = serializer.serialize_map(Some(2))?;
+                map.serialize_entry("type", "Symbol")?;
+                map.serialize_entry("values", &parts)?;
+                map.end()
+            }
+        }
+    }
+}
+
+fn scalar<S: Serializer>(serializer: S, name: &str) -> Result<S::Ok, S::Error> {
+    let mut map = serializer.serialize_map(Some(1))?;
+    map.serialize_entry("type", name)?;
+    map.end()
+}
+
+/// The fields of a record, serialized as an object in field order.
+struct Fields<'s, 'a> {
+    schema: &'s TypeSchema<'a>,
+    fields: Vec<(&'a str, &'a Type<'a>)>,
+}
+
+impl Serialize for Fields<'_, '_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_map(
+            self.fields
+                .iter()
+                .map(|(name, ty)| (*name, self.schema.describe(ty))),
+        )
+    }
+}
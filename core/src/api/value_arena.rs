@@ -0,0 +1,72 @@
+//! Reusable arena for repeated evaluation.
+
+use bumpalo::Bump;
+
+/// An arena for run results that is reused across runs.
+///
+/// Allocating a fresh `Bump` for every `CompiledExpression::run()` works, but
+/// each one allocates its memory from scratch. `ReusableValueArena` keeps the
+/// memory between runs: [`reset`](Self::reset) frees everything allocated by
+/// the previous run and hands out the arena for the next one.
+///
+/// Resetting is safe because it borrows the arena mutably: values from the
+/// previous run borrow the arena too, so the compiler rejects any use of them
+/// after the next `reset()`.
+///
+/// # Example
+///
+/// ```
+/// use melbi_core::api::{Engine, EngineOptions, ReusableValueArena};
+/// use bumpalo::Bump;
+///
+/// let arena = Bump::new();
+/// let engine = Engine::new(EngineOptions::default(), &arena, |_, _, env| env);
+/// let expr = engine.compile(Default::default(), "[1, 2, 3]", &[]).unwrap();
+///
+/// let mut values = ReusableValueArena::new();
+/// for _ in 0..3 {
+///     let result = expr.run(Default::default(), values.reset(), &[]).unwrap();
+///     assert_eq!(result.as_array().unwrap().len(), 3);
+/// }
+/// ```
+///
+/// Using a value after the arena has been reset does not compile:
+///
+/// ```compile_fail
+/// use melbi_core::api::{Engine, EngineOptions, ReusableValueArena};
+/// use bumpalo::Bump;
+///
+/// let arena = Bump::new();
+/// let engine = Engine::new(EngineOptions::default(), &arena, |_, _, env| env);
+/// let expr = engine.compile(Default::default(), "[1, 2, 3]", &[]).unwrap();
+///
+/// let mut values = ReusableValueArena::new();
+/// let first = expr.run(Default::default(), values.reset(), &[]).unwrap();
+/// let _second = expr.run(Default::default(), values.reset(), &[]).unwrap();
+/// assert_eq!(first.as_array().unwrap().len(), 3);
+/// ```
+#[derive(Debug, Default)]
+pub struct ReusableValueArena {
+    bump: Bump,
+}
+
+impl ReusableValueArena {
+    /// Create an empty arena.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Free all values from the previous run and return the arena for the next.
+    ///
+    /// The memory is kept, so runs of similar size stop allocating once the
+    /// arena has grown to fit them.
+    pub fn reset(&mut self) -> &Bump {
+        self.bump.reset();
+        &self.bump
+    }
+
+    /// Total bytes of memory held by the arena, including unused capacity.
+    pub fn allocated_bytes(&self) -> usize {
+        self.bump.allocated_bytes()
+    }
+}
@@ -8,11 +8,19 @@
 //! ## Numeric Conversions
 //! - **Int → Float**: Infallible widening conversion
 //! - **Float → Int**: Truncates toward zero, wraps on overflow, NaN→0, Inf→MAX/MIN
+//! - **Int → BigInt**: Infallible widening conversion (`bigint` feature)
+//! - **BigInt → Int**: Fallible, fails when the value is outside the `Int` range
+//! - **Int → Decimal**: Infallible, exact (`decimal` feature)
+//! - **Decimal → Int**: Truncates toward zero, fails when outside the `Int` range
 //!
 //! ## Bytes ↔ String (UTF-8)
 //! - **Str → Bytes**: Infallible UTF-8 encoding
 //! - **Bytes → Str**: Fallible UTF-8 decoding (can fail on invalid UTF-8)
 //!
+//! ## Records
+//! - **Record → record with fewer fields**: Not written with `as`. The analyzer
+//!   inserts it where a call passes a record with extra fields, dropping them.
+//!
 //! # NOT Supported (Use Alternatives)
 //!
 //! - **Numeric → Str**: Use format strings: `f"{x}"`
@@ -27,6 +35,7 @@
 //! - Unit conversions: e.g., `10`MB` as `bytes``
 
 use crate::String;
+use crate::Vec;
 use crate::types::Type;
 use crate::types::manager::TypeManager;
 use crate::types::traits::{TypeKind, TypeView};
@@ -43,6 +52,8 @@ use crate::values::dynamic::Value;
 /// - Float → Int (infallible, truncates)
 /// - Str → Bytes (infallible, UTF-8 encoding)
 /// - Bytes → Str (fallible, UTF-8 decoding)
+/// - Int → BigInt (infallible) and BigInt → Int (fallible, range-checked)
+/// - Int → Decimal (infallible) and Decimal → Int (fallible, truncates)
 ///
 /// # TODO(effects)
 ///
@@ -62,6 +73,8 @@ pub fn is_cast_valid<'types>(
         // Numeric conversions
         (TypeKind::Int, TypeKind::Float) => true,
         (TypeKind::Float, TypeKind::Int) => true,
+        (TypeKind::Int, TypeKind::BigInt) | (TypeKind::BigInt, TypeKind::Int) => true,
+        (TypeKind::Int, TypeKind::Decimal) | (TypeKind::Decimal, TypeKind::Int) => true,
 
         // Bytes ↔ String (UTF-8)
         (TypeKind::Str, TypeKind::Bytes) => true,
@@ -103,10 +116,15 @@ pub fn validate_cast<'types>(
 /// - **Float → Int**: Truncates toward zero, wraps on overflow, NaN→0, Inf→i64::MAX/MIN
 /// - **Str → Bytes**: UTF-8 encoding (always succeeds)
 /// - **Bytes → Str**: UTF-8 decoding (fails on invalid UTF-8)
+/// - **Int → BigInt**: Exact widening
+/// - **BigInt → Int**: Exact, fails if the value does not fit in an `i64`
+/// - **Int → Decimal**: Exact
+/// - **Decimal → Int**: Truncates toward zero, fails if the value does not fit in an `i64`
 ///
 /// # Errors
 ///
 /// Returns `CastError::InvalidUtf8` if Bytes→Str fails due to invalid UTF-8.
+/// Returns `CastError::OutOfRange` if BigInt→Int or Decimal→Int does not fit.
 ///
 /// # Panics
 ///
@@ -162,6 +180,49 @@ pub fn perform_cast<'types, 'arena>(
             Ok(Value::int(type_manager, int_val))
         }
 
+        // Int → BigInt
+        #[cfg(feature = "bigint")]
+        (Int, BigInt) => {
+            let int_val = value.as_int().expect("Value type matches");
+            Ok(Value::bigint(arena, type_manager, &int_val.into()))
+        }
+
+        // BigInt → Int (range-checked)
+        #[cfg(feature = "bigint")]
+        (BigInt, Int) => {
+            use num_traits::ToPrimitive;
+
+            let big_val = value.as_bigint().expect("Value type matches");
+            match big_val.to_i64() {
+                Some(int_val) => Ok(Value::int(type_manager, int_val)),
+                None => Err(CastError::OutOfRange {
+                    value: crate::format!("{}", big_val),
+                    to: crate::format!("{}", target_type),
+                }),
+            }
+        }
+
+        // Int → Decimal
+        #[cfg(feature = "decimal")]
+        (Int, Decimal) => {
+            let int_val = value.as_int().expect("Value type matches");
+            let decimal_val = crate::values::decimal::Decimal::from_int(int_val);
+            Ok(Value::decimal(arena, type_manager, decimal_val))
+        }
+
+        // Decimal → Int (truncates, range-checked)
+        #[cfg(feature = "decimal")]
+        (Decimal, Int) => {
+            let decimal_val = value.as_decimal().expect("Value type matches");
+            match decimal_val.to_int() {
+                Some(int_val) => Ok(Value::int(type_manager, int_val)),
+                None => Err(CastError::OutOfRange {
+                    value: crate::format!("{}", decimal_val),
+                    to: crate::format!("{}", target_type),
+                }),
+            }
+        }
+
         // Str → Bytes (UTF-8 encoding)
         (Str, Bytes) => {
             let str_val = value.as_str().expect("Value type matches");
@@ -181,6 +242,21 @@ pub fn perform_cast<'types, 'arena>(
             }
         }
 
+        // Record → record with fewer fields, for a record argument passed
+        // where the callee expects fewer fields (inserted by the analyzer)
+        (Record(_), Record(target_fields)) => {
+            let record = value.as_record().expect("Value type matches");
+            let mut fields = Vec::with_capacity(target_fields.len());
+            for (name, field_ty) in target_fields.iter() {
+                let field = record.get(name).ok_or_else(|| CastError::InvalidCast {
+                    from: crate::format!("{}", value.ty),
+                    to: crate::format!("{}", target_type),
+                })?;
+                fields.push((*name, perform_cast(arena, field, field_ty, type_manager)?));
+            }
+            Ok(Value::record(arena, target_type, &fields).expect("Fields match the target type"))
+        }
+
         // Invalid cast (should never happen if analyzer validated)
         _ => {
             debug_assert!(
@@ -204,6 +280,9 @@ pub enum CastError {
 
     /// Invalid UTF-8 sequence when casting Bytes → Str
     InvalidUtf8 { error: String },
+
+    /// Value does not fit in the target type (e.g. BigInt → Int)
+    OutOfRange { value: String, to: String },
 }
 
 impl core::fmt::Display for CastError {
@@ -215,6 +294,9 @@ impl core::fmt::Display for CastError {
             CastError::InvalidUtf8 { error } => {
                 write!(f, "Invalid UTF-8 sequence: {}", error)
             }
+            CastError::OutOfRange { value, to } => {
+                write!(f, "Value {} is out of range for {}", value, to)
+            }
         }
     }
 }
@@ -425,4 +507,28 @@ mod tests {
         let result = perform_cast(&bump, float_val, tm.float(), tm).unwrap();
         assert_eq!(result.as_float().unwrap(), 3.14);
     }
+
+    #[cfg(feature = "bigint")]
+    #[test]
+    fn test_bigint_int_casts() {
+        let bump = Bump::new();
+        let tm = TypeManager::new(&bump);
+
+        assert!(is_cast_valid(tm.int(), tm.bigint()));
+        assert!(is_cast_valid(tm.bigint(), tm.int()));
+        assert!(!is_cast_valid(tm.bigint(), tm.float()));
+
+        // Int → BigInt → Int round-trips exactly at the edges of the range
+        for value in [i64::MIN, -1, 0, i64::MAX] {
+            let big = perform_cast(&bump, Value::int(tm, value), tm.bigint(), tm).unwrap();
+            let back = perform_cast(&bump, big, tm.int(), tm).unwrap();
+            assert_eq!(back.as_int().unwrap(), value);
+        }
+
+        // BigInt → Int fails once the value leaves the i64 range
+        let too_big = num_bigint::BigInt::from(i64::MAX) + 1;
+        let big = Value::bigint(&bump, tm, &too_big);
+        let result = perform_cast(&bump, big, tm.int(), tm);
+        assert!(matches!(result, Err(CastError::OutOfRange { .. })));
+    }
 }
@@ -4,9 +4,14 @@ use alloc::boxed::Box;
 
 use crate::{
     Vec,
-    analyzer::typed_expr::{Expr, ExprBuilder, LambdaInstantiations, TypedExpr},
-    parser::ComparisonOp,
+    analyzer::{
+        constant::fold_constant_bool,
+        typed_expr::{Expr, ExprBuilder, LambdaInstantiations, TypedExpr, recursive_group_len},
+    },
+    api::CompileOptions,
+    parser::{AnnotatedSource, ComparisonOp, Span},
     scope_stack::{CompleteScope, IncompleteScope, ScopeStack},
+    stdlib::builtins,
     types::{
         Type,
         manager::TypeManager,
@@ -16,8 +21,8 @@ use crate::{
     values::dynamic::Value,
     visitor::TreeTransformer,
     vm::{
-        ArrayContainsAdapter, CastAdapter, Code, FormatStrAdapter, FunctionAdapter, GenericAdapter,
-        Instruction, LambdaCode, LambdaKind,
+        ArrayContainsAdapter, CastAdapter, Code, EqualityAdapter, FormatStrAdapter,
+        FunctionAdapter, GenericAdapter, Instruction, LambdaCode, LambdaKind, MapAdapter, MapOp,
     },
 };
 use bumpalo::Bump;
@@ -39,10 +44,45 @@ enum ScopeEntry<'types, 'arena> {
     Local(u32),
     /// Captured variable index (from enclosing lambda scope)
     Capture(u32),
+    /// Index in the enclosing lambda's recursive group
+    Recursive(u32),
     /// Global value (e.g., Math package) to add to constants
     Global(Value<'types, 'arena>),
 }
 
+/// Key of the constant deduplication map.
+///
+/// Values are shared by content (so repeated string literals share one
+/// constant), but floats compare by bits: `-0.0 == 0.0` in Melbi, yet the two
+/// constants must stay distinct.
+#[derive(Clone, Copy)]
+struct ConstantKey<'types, 'arena>(Value<'types, 'arena>);
+
+impl PartialEq for ConstantKey<'_, '_> {
+    fn eq(&self, other: &Self) -> bool {
+        match (self.0.as_float(), other.0.as_float()) {
+            (Ok(left), Ok(right)) => left.to_bits() == right.to_bits(),
+            _ => self.0 == other.0,
+        }
+    }
+}
+
+impl Eq for ConstantKey<'_, '_> {}
+
+impl core::hash::Hash for ConstantKey<'_, '_> {
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+        match self.0.as_float() {
+            Ok(float) => float.to_bits().hash(state),
+            Err(_) => self.0.hash(state),
+        }
+    }
+}
+
+/// Array literals with more elements than this are built one element at a
+/// time with `ArrayStart`/`ArrayAppend`/`ArrayFinish`, so `max_stack_size`
+/// doesn't grow with the literal's length.
+const MAX_MAKE_ARRAY_ELEMENTS: usize = 1024;
+
 /// Bytecode compiler that transforms typed expressions into VM bytecode.
 ///
 /// The compiler implements the TreeTransformer pattern to traverse the AST
@@ -66,7 +106,7 @@ pub struct BytecodeCompiler<'types, 'arena> {
     /// Constant deduplication map: Value -> index
     ///
     /// Maps values to their index in the constants pool to avoid duplicates.
-    constant_map: hashbrown::HashMap<Value<'types, 'arena>, usize>,
+    constant_map: hashbrown::HashMap<ConstantKey<'types, 'arena>, usize>,
 
     /// Bytecode instructions
     instructions: alloc::vec::Vec<Instruction>,
@@ -118,6 +158,23 @@ pub struct BytecodeCompiler<'types, 'arena> {
     /// Used to resolve type variables to concrete types.
     /// None for top-level code and monomorphic lambdas.
     monomorphism: Option<Unification<'types, &'types TypeManager<'types>>>,
+
+    /// Whether to apply optimizations such as dead-branch elimination.
+    optimize: bool,
+
+    /// Names of the recursive `where` group that the next lambda belongs to.
+    /// The lambda loads these with `LoadRecursive` instead of capturing them.
+    recursive_group: &'arena [&'arena str],
+
+    /// Source annotations used to record instruction spans, if known.
+    source: Option<&'arena AnnotatedSource<'arena, Expr<'types, 'arena>>>,
+
+    /// Instruction spans recorded so far (see `Code::spans`).
+    spans: alloc::vec::Vec<(usize, Span)>,
+
+    /// Record literal field orders recorded so far (see
+    /// `Code::record_field_orders`).
+    record_field_orders: alloc::vec::Vec<(usize, alloc::vec::Vec<u32>)>,
 }
 
 impl<'types, 'arena> BytecodeCompiler<'types, 'arena> {
@@ -168,6 +225,11 @@ impl<'types, 'arena> BytecodeCompiler<'types, 'arena> {
             lambdas: alloc::vec::Vec::new(),
             lambda_instantiations,
             monomorphism: None,
+            optimize: false,
+            recursive_group: &[],
+            source: None,
+            spans: alloc::vec::Vec::new(),
+            record_field_orders: alloc::vec::Vec::new(),
         }
     }
 
@@ -181,12 +243,16 @@ impl<'types, 'arena> BytecodeCompiler<'types, 'arena> {
     /// * `type_mgr` - Type manager for creating function adapters
     /// * `arena` - Arena for allocations
     /// * `captures` - Names of captured variables (in order)
+    /// * `recursive` - Names of the lambda's recursive group (in binding order)
     /// * `monomorphism` - Optional type unification for polymorphic lambda instantiations
+    /// * `optimize` - Whether to apply optimizations (inherited from the enclosing code)
     fn new_for_lambda(
         type_mgr: &'types TypeManager<'types>,
         arena: &'arena Bump,
         captures: &[&'arena str],
+        recursive: &[&'arena str],
         monomorphism: Option<Unification<'types, &'types TypeManager<'types>>>,
+        optimize: bool,
     ) -> Self {
         // Build captures scope: name -> Capture(index)
         let captures_entries: &[(&str, ScopeEntry)] = arena.alloc_slice_fill_iter(
@@ -200,6 +266,18 @@ impl<'types, 'arena> BytecodeCompiler<'types, 'arena> {
         let mut scope_stack = ScopeStack::new();
         scope_stack.push(CompleteScope::from_sorted(captures_entries));
 
+        // The recursive group shadows captures of the same name
+        if !recursive.is_empty() {
+            let recursive_entries: &mut [(&str, ScopeEntry)] = arena.alloc_slice_fill_iter(
+                recursive
+                    .iter()
+                    .enumerate()
+                    .map(|(i, &name)| (name, ScopeEntry::Recursive(i as u32))),
+            );
+            recursive_entries.sort_by_key(|(name, _)| *name);
+            scope_stack.push(CompleteScope::from_sorted(recursive_entries));
+        }
+
         Self {
             type_mgr,
             arena,
@@ -215,6 +293,11 @@ impl<'types, 'arena> BytecodeCompiler<'types, 'arena> {
             lambdas: alloc::vec::Vec::new(),
             lambda_instantiations: None, // Lambda compilers don't need instantiation info
             monomorphism,
+            optimize,
+            recursive_group: &[],
+            source: None,
+            spans: alloc::vec::Vec::new(),
+            record_field_orders: alloc::vec::Vec::new(),
         }
     }
 
@@ -238,6 +321,8 @@ impl<'types, 'arena> BytecodeCompiler<'types, 'arena> {
             num_locals: self.num_locals,
             max_stack_size: self.max_stack_size,
             lambdas: self.lambdas,
+            spans: self.spans,
+            record_field_orders: self.record_field_orders,
         }
     }
 
@@ -248,11 +333,29 @@ impl<'types, 'arena> BytecodeCompiler<'types, 'arena> {
     /// * `arena` - Arena for allocations
     /// * `globals` - Global values (e.g., Math package) sorted by name
     /// * `typed_expr` - The typed expression (with lambda instantiation info) to compile
+    /// * `options` - Compilation options (e.g., whether to optimize)
     pub fn compile(
         type_mgr: &'types TypeManager<'types>,
         arena: &'arena Bump,
         globals: &'arena [(&'arena str, Value<'types, 'arena>)],
         typed_expr: &'arena TypedExpr<'types, 'arena>,
+        options: &CompileOptions,
+    ) -> Result<Code<'types>, CompileError> {
+        Self::compile_with_params(type_mgr, arena, globals, &[], typed_expr, options)
+    }
+
+    /// Compile an expression that takes parameters.
+    ///
+    /// The parameters shadow globals of the same name, and are read from
+    /// locals `0..params.len()`, so the bytecode runs with the arguments as
+    /// its initial locals (see `VM::new`).
+    pub fn compile_with_params(
+        type_mgr: &'types TypeManager<'types>,
+        arena: &'arena Bump,
+        globals: &'arena [(&'arena str, Value<'types, 'arena>)],
+        params: &[&'arena str],
+        typed_expr: &'arena TypedExpr<'types, 'arena>,
+        options: &CompileOptions,
     ) -> Result<Code<'types>, CompileError> {
         let lambda_instantiations = if typed_expr.lambda_instantiations.is_empty() {
             None
@@ -260,11 +363,28 @@ impl<'types, 'arena> BytecodeCompiler<'types, 'arena> {
             Some(&typed_expr.lambda_instantiations)
         };
         let mut compiler = Self::new(type_mgr, arena, globals, lambda_instantiations);
+        compiler.optimize = options.optimize;
+        compiler.source = Some(typed_expr.ann);
+        if !params.is_empty() {
+            compiler.scope_stack.push(
+                IncompleteScope::new(arena, params)
+                    .expect("Duplicate parameter names (should be caught by type checker)"),
+            );
+            for &param in params {
+                let local_idx = compiler.allocate_local()?;
+                compiler
+                    .scope_stack
+                    .bind_in_current(param, ScopeEntry::Local(local_idx))
+                    .expect("Parameter binding");
+            }
+        }
         compiler.transform(typed_expr.expr)?;
         debug_assert_eq!(compiler.current_stack_depth, 1);
         // Emit Return instruction to signal end of execution
         compiler.emit(Instruction::Return);
-        Ok(compiler.finalize())
+        let code = compiler.finalize();
+        debug_assert_eq!(code.verify(), Ok(()), "{code:?}");
+        Ok(code)
     }
 
     // === Stack Management ===
@@ -368,6 +488,9 @@ impl<'types, 'arena> BytecodeCompiler<'types, 'arena> {
             Some(ScopeEntry::Capture(index)) => {
                 self.emit_with_arg(Instruction::LoadCapture, *index as u32);
             }
+            Some(ScopeEntry::Recursive(index)) => {
+                self.emit_with_arg(Instruction::LoadRecursive, *index);
+            }
             Some(ScopeEntry::Global(value)) => {
                 let const_index = self.add_constant(*value)?;
                 self.emit_with_arg(Instruction::ConstLoad, const_index);
@@ -383,6 +506,61 @@ impl<'types, 'arena> BytecodeCompiler<'types, 'arena> {
         Ok(())
     }
 
+    /// Compile a `where` binding into a new local.
+    fn compile_where_binding(
+        &mut self,
+        name: &'arena str,
+        value_expr: &'arena Expr<'types, 'arena>,
+    ) -> Result<(), CompileError> {
+        // Compile the value expression
+        self.transform(value_expr)?;
+        self.pop_stack();
+
+        // Allocate a new local slot
+        let index = self.allocate_local()?;
+        self.emit_with_arg(Instruction::StoreLocal, index);
+
+        // Bind the name to the local slot in the current scope
+        self.scope_stack
+            .bind_in_current(name, ScopeEntry::Local(index))
+            .expect("Failed to bind variable (should not happen)");
+        Ok(())
+    }
+
+    /// Compile `where` lambdas that use each other into new locals.
+    ///
+    /// The closures are made one after another, so they cannot capture each
+    /// other. `BindRecursive` shares the finished group with all of them.
+    fn compile_recursive_lambdas(
+        &mut self,
+        group: &[(&'arena str, &'arena Expr<'types, 'arena>)],
+    ) -> Result<(), CompileError> {
+        let names = self
+            .arena
+            .alloc_slice_fill_iter(group.iter().map(|(name, _)| *name));
+        for (_, value_expr) in group {
+            self.recursive_group = names;
+            self.transform(value_expr)?;
+        }
+        self.emit_with_arg(Instruction::BindRecursive, group.len() as u32);
+
+        // The last closure is on top of the stack
+        let first_local = self.num_locals as u32;
+        for _ in group {
+            self.allocate_local()?;
+        }
+        for offset in (0..group.len() as u32).rev() {
+            self.emit_with_arg(Instruction::StoreLocal, first_local + offset);
+            self.pop_stack();
+        }
+        for (offset, (name, _)) in group.iter().enumerate() {
+            self.scope_stack
+                .bind_in_current(name, ScopeEntry::Local(first_local + offset as u32))
+                .expect("Failed to bind variable (should not happen)");
+        }
+        Ok(())
+    }
+
     /// Compile a lambda body into a LambdaCode with Mono kind.
     ///
     /// Creates a fresh compiler for the lambda, sets up parameters as locals,
@@ -392,19 +570,28 @@ impl<'types, 'arena> BytecodeCompiler<'types, 'arena> {
     /// * `params` - Parameter names
     /// * `body` - Lambda body expression
     /// * `captures` - Names of captured variables
+    /// * `recursive` - Names of the lambda's recursive group
     /// * `lambda_type` - The concrete type of this lambda instantiation
     /// * `monomorphism` - Optional type unification for polymorphic lambdas
     fn compile_lambda_body(
         &self,
         params: &[&'arena str],
-        body: &Expr<'types, 'arena>,
+        body: &'arena Expr<'types, 'arena>,
         captures: &[&'arena str],
+        recursive: &[&'arena str],
         lambda_type: &'types Type<'types>,
         monomorphism: Option<Unification<'types, &'types TypeManager<'types>>>,
     ) -> Result<LambdaCode<'types>, CompileError> {
         // Create fresh compiler for lambda
-        let mut lambda_compiler =
-            BytecodeCompiler::new_for_lambda(self.type_mgr, self.arena, captures, monomorphism);
+        let mut lambda_compiler = BytecodeCompiler::new_for_lambda(
+            self.type_mgr,
+            self.arena,
+            captures,
+            recursive,
+            monomorphism,
+            self.optimize,
+        );
+        lambda_compiler.source = self.source;
 
         // Set up parameters as locals (in order)
         // Parameters are passed by the caller via VM locals
@@ -442,11 +629,14 @@ impl<'types, 'arena> BytecodeCompiler<'types, 'arena> {
             num_locals: lambda_compiler.num_locals,
             max_stack_size: lambda_compiler.max_stack_size,
             lambdas: lambda_compiler.lambdas,
+            spans: lambda_compiler.spans,
+            record_field_orders: lambda_compiler.record_field_orders,
         };
 
         Ok(LambdaCode {
             lambda_type,
             num_captures: num_captures as u32,
+            num_recursive: recursive.len() as u32,
             kind: LambdaKind::Mono { code },
         })
     }
@@ -455,18 +645,18 @@ impl<'types, 'arena> BytecodeCompiler<'types, 'arena> {
 
     /// Add a constant to the pool (or reuse existing) and return its index.
     ///
-    /// Deduplicates constants by value equality.
+    /// Deduplicates constants by value equality (see [`ConstantKey`]).
     /// Returns the index as u32 - emit_with_arg handles WideArg if needed.
     fn add_constant(&mut self, value: Value<'types, 'arena>) -> Result<u32, CompileError> {
         // Check if this constant already exists
-        if let Some(&existing_index) = self.constant_map.get(&value) {
+        if let Some(&existing_index) = self.constant_map.get(&ConstantKey(value)) {
             return Ok(existing_index as u32);
         }
 
         // Add new constant
         let index = self.constants.len();
         self.constants.push(value);
-        self.constant_map.insert(value, index);
+        self.constant_map.insert(ConstantKey(value), index);
         index.try_into().map_err(|_| CompileError::TooManyConstants)
     }
 
@@ -657,14 +847,23 @@ impl<'types, 'arena> BytecodeCompiler<'types, 'arena> {
     }
 }
 
-impl<'types, 'arena> TreeTransformer<ExprBuilder<'types, 'arena>>
-    for BytecodeCompiler<'types, 'arena>
+impl<'types, 'arena> BytecodeCompiler<'types, 'arena>
 where
     'types: 'arena,
 {
-    type Output = Result<(), CompileError>;
+    /// Record that the instructions emitted from now on belong to the
+    /// expression at `span`.
+    fn mark_span(&mut self, span: Span) {
+        let index = self.instructions.len();
+        match self.spans.last_mut() {
+            Some((_, last)) if *last == span => {}
+            // Nothing was emitted for the previous span
+            Some((start, last)) if *start == index => *last = span,
+            _ => self.spans.push((index, span)),
+        }
+    }
 
-    fn transform(&mut self, tree: &'arena Expr<'types, 'arena>) -> Self::Output {
+    fn compile_expr(&mut self, tree: &'arena Expr<'types, 'arena>) -> Result<(), CompileError> {
         use crate::{
             analyzer::typed_expr::ExprInner,
             parser::{BinaryOp, BoolOp},
@@ -728,6 +927,8 @@ where
                 match resolved_type.view() {
                     TypeKind::Float => self.emit(Instruction::FloatBinOp(op_byte)),
                     TypeKind::Int => self.emit(Instruction::IntBinOp(op_byte)),
+                    TypeKind::BigInt => self.emit(Instruction::BigIntBinOp(op_byte)),
+                    TypeKind::Decimal => self.emit(Instruction::DecimalBinOp(op_byte)),
                     _ => panic!(
                         "Binary operation on non-numeric type: {} (type checker bug)",
                         resolved_type
@@ -753,6 +954,8 @@ where
                         match resolved_type.view() {
                             TypeKind::Float => self.emit(Instruction::NegFloat),
                             TypeKind::Int => self.emit(Instruction::NegInt),
+                            TypeKind::BigInt => self.emit(Instruction::NegBigInt),
+                            TypeKind::Decimal => self.emit(Instruction::NegDecimal),
                             _ => panic!(
                                 "Negation on non-numeric type: {} (type checker bug)",
                                 resolved_type
@@ -795,10 +998,13 @@ where
                             );
                         }
                         TypeKind::Map(_, _) => {
-                            self.emit(Instruction::MapHas);
-                            if op == ComparisonOp::NotIn {
-                                self.emit(Instruction::Not);
-                            }
+                            let adapter = MapAdapter::new(haystack_type, MapOp::Contains(op));
+                            let adapter_index = self.generic_adapters.len();
+                            self.generic_adapters.push(Box::new(adapter));
+                            self.emit_with_arg(
+                                Instruction::CallGenericAdapter,
+                                adapter_index as u32,
+                            );
                         }
                         _ => panic!(
                             "Containment on unsupported type: {} (type checker bug)",
@@ -810,8 +1016,21 @@ where
                     match resolved_type.view() {
                         TypeKind::Float => self.emit(Instruction::FloatCmpOp(op)),
                         TypeKind::Int => self.emit(Instruction::IntCmpOp(op)),
+                        TypeKind::BigInt => self.emit(Instruction::BigIntCmpOp(op)),
+                        TypeKind::Decimal => self.emit(Instruction::DecimalCmpOp(op)),
                         TypeKind::Str => self.emit(Instruction::StringCmpOp(op)),
                         TypeKind::Bytes => self.emit(Instruction::BytesCmpOp(op)),
+                        _ if matches!(op, ComparisonOp::Eq | ComparisonOp::Neq) => {
+                            // Structural equality for Bool, containers, and
+                            // other types without a comparison instruction
+                            let adapter = EqualityAdapter::new(resolved_type, op);
+                            let adapter_index = self.generic_adapters.len();
+                            self.generic_adapters.push(Box::new(adapter));
+                            self.emit_with_arg(
+                                Instruction::CallGenericAdapter,
+                                adapter_index as u32,
+                            );
+                        }
                         _ => panic!(
                             "Comparison on unsupported type: {} (type checker bug)",
                             resolved_type
@@ -894,6 +1113,14 @@ where
                 then_branch,
                 else_branch,
             } => {
+                // Dead-branch elimination: emit only the taken branch. Both
+                // branches were already type-checked by the analyzer.
+                if self.optimize
+                    && let Some(taken) = fold_constant_bool(cond)
+                {
+                    return self.transform(if taken { then_branch } else { else_branch });
+                }
+
                 // Compile condition
                 self.transform(cond)?;
                 self.pop_stack(); // Condition consumed by PopJumpIfFalse
@@ -925,6 +1152,18 @@ where
             }
 
             // === Array Construction ===
+            ExprInner::Array { elements } if elements.len() > MAX_MAKE_ARRAY_ELEMENTS => {
+                // Each element is appended as soon as it is computed, so only
+                // one is ever on the stack
+                self.emit(Instruction::ArrayStart);
+                for element in elements.iter() {
+                    self.transform(element)?;
+                    self.emit(Instruction::ArrayAppend);
+                    self.pop_stack();
+                }
+                self.emit(Instruction::ArrayFinish);
+                self.push_stack();
+            }
             ExprInner::Array { elements } => {
                 // Compile all element expressions
                 // They will be pushed onto the stack in order
@@ -942,9 +1181,16 @@ where
             }
 
             // === Variable Access ===
-            ExprInner::Ident(name) => {
-                self.compile_variable_load(name)?;
-            }
+            ExprInner::Ident(name) => match builtins::lookup(name) {
+                // Bindings shadow builtins, which take the type of this use
+                Some(builtin) if self.scope_stack.lookup(name).is_none() => {
+                    let value = builtin.value(self.arena, self.resolve_type(tree.0));
+                    let const_index = self.add_constant(value)?;
+                    self.emit_with_arg(Instruction::ConstLoad, const_index);
+                    self.push_stack();
+                }
+                _ => self.compile_variable_load(name)?,
+            },
 
             // === Where Bindings ===
             ExprInner::Where { expr, bindings } => {
@@ -958,19 +1204,17 @@ where
                 );
 
                 // Compile all bindings first (in order)
-                for (name, value_expr) in bindings.iter() {
-                    // Compile the value expression
-                    self.transform(value_expr)?;
-                    self.pop_stack();
-
-                    // Allocate a new local slot
-                    let index = self.allocate_local()?;
-                    self.emit_with_arg(Instruction::StoreLocal, index);
-
-                    // Bind the name to the local slot in the current scope
-                    self.scope_stack
-                        .bind_in_current(name, ScopeEntry::Local(index))
-                        .expect("Failed to bind variable (should not happen)");
+                let mut index = 0;
+                while index < bindings.len() {
+                    let group_len = recursive_group_len(bindings, index);
+                    if group_len == 0 {
+                        let (name, value_expr) = bindings[index];
+                        self.compile_where_binding(name, value_expr)?;
+                        index += 1;
+                    } else {
+                        self.compile_recursive_lambdas(&bindings[index..index + group_len])?;
+                        index += group_len;
+                    }
                 }
 
                 // Then compile the main expression (which can reference the bindings)
@@ -1015,6 +1259,25 @@ where
                     }
                 }
 
+                // Records are indexed by name: load the field names to search
+                if let TypeKind::Record(fields) = container_type.view() {
+                    let str_ty = self.type_mgr.str();
+                    let field_names: alloc::vec::Vec<Value<'types, 'arena>> = fields
+                        .map(|(name, _)| Value::str(self.arena, str_ty, name))
+                        .collect();
+                    let field_names =
+                        Value::array(self.arena, self.type_mgr.array(str_ty), &field_names)
+                            .expect("Field names should form an Array[Str]");
+                    let const_index = self.add_constant(field_names)?;
+                    self.emit_with_arg(Instruction::ConstLoad, const_index);
+                    self.push_stack(); // Push field names
+                    self.transform(index)?;
+                    self.pop_stack_n(3); // Pop name, field names, and record
+                    self.emit(Instruction::RecordGetDynamic);
+                    self.push_stack(); // Push result
+                    return Ok(());
+                }
+
                 // Dynamic index: compile index expression
                 self.transform(index)?;
 
@@ -1024,9 +1287,16 @@ where
                     TypeKind::Array(_) => {
                         self.emit(Instruction::ArrayGet);
                     }
-                    TypeKind::Map(_, _) => {
+                    // `MapGet` compares keys as integers
+                    TypeKind::Map(key_type, _) if matches!(key_type.view(), TypeKind::Int) => {
                         self.emit(Instruction::MapGet);
                     }
+                    TypeKind::Map(_, _) => {
+                        let adapter = MapAdapter::new(container_type, MapOp::Get);
+                        let adapter_index = self.generic_adapters.len();
+                        self.generic_adapters.push(Box::new(adapter));
+                        self.emit_with_arg(Instruction::CallGenericAdapter, adapter_index as u32);
+                    }
                     TypeKind::Bytes => {
                         self.emit(Instruction::BytesGet);
                     }
@@ -1076,11 +1346,18 @@ where
             ExprInner::Record { fields } => {
                 // Sort fields by name to match the type's field order
                 // (TypeManager::record sorts fields alphabetically)
-                let mut sorted_fields: Vec<_> = fields.iter().collect();
-                sorted_fields.sort_by_key(|(name, _)| *name);
+                let mut sorted_fields: Vec<_> = fields.iter().enumerate().collect();
+                sorted_fields.sort_by_key(|(_, (name, _))| *name);
+
+                // Remember where each source field landed in the sorted layout,
+                // for displaying records in source order
+                let mut field_order = alloc::vec![0; fields.len()];
+                for (sorted_index, (source_index, _)) in sorted_fields.iter().enumerate() {
+                    field_order[*source_index] = sorted_index as u32;
+                }
 
                 // Compile field values in sorted order
-                for (_name, value_expr) in sorted_fields.iter() {
+                for (_, (_name, value_expr)) in sorted_fields.iter() {
                     self.transform(value_expr)?;
                 }
 
@@ -1090,6 +1367,10 @@ where
 
                 // Emit MakeRecord instruction
                 self.emit_with_arg(Instruction::MakeRecord, count as u32);
+                if !field_order.is_sorted() {
+                    let index = self.instructions.len() - 1;
+                    self.record_field_orders.push((index, field_order));
+                }
                 self.push_stack();
             }
 
@@ -1106,8 +1387,17 @@ where
                 let num_pairs = elements.len();
                 self.pop_stack_n(num_pairs * 2);
 
-                // Emit MakeMap instruction
-                self.emit_with_arg(Instruction::MakeMap, num_pairs as u32);
+                // `MakeMap` compares keys as integers, other keys need the adapter
+                let map_type = self.resolve_type(tree.0);
+                match map_type.view() {
+                    TypeKind::Map(key_type, _) if !matches!(key_type.view(), TypeKind::Int) => {
+                        let adapter = MapAdapter::new(map_type, MapOp::Make { num_pairs });
+                        let adapter_index = self.generic_adapters.len();
+                        self.generic_adapters.push(Box::new(adapter));
+                        self.emit_with_arg(Instruction::CallGenericAdapter, adapter_index as u32);
+                    }
+                    _ => self.emit_with_arg(Instruction::MakeMap, num_pairs as u32),
+                }
                 self.push_stack();
             }
 
@@ -1149,6 +1439,31 @@ where
                 )?;
             }
 
+            ExprInner::Coalesce { option, default } => {
+                // Compile the option (leaves one option on the stack)
+                self.transform(option)?;
+
+                // Some: unwrap the inner value and fall through; None: jump to default
+                let default_jump = self.jump_placeholder(Instruction::MatchSomeOrJump);
+
+                // Reserve space for jump over default branch
+                let end_jump = self.jump_placeholder(Instruction::JumpForward);
+
+                // Patch the none jump to point here
+                let default_label = self.label();
+                self.patch_jump(default_jump, default_label, Instruction::MatchSomeOrJump)?;
+
+                // Pop the unwrapped value for stack tracking (default runs instead at runtime)
+                self.pop_stack();
+
+                // Compile default (leaves one result on stack)
+                self.transform(default)?;
+
+                // Patch the end jump to point here
+                let end_label = self.label();
+                self.patch_jump(end_jump, end_label, Instruction::JumpForward)?;
+            }
+
             // === Option Construction ===
             ExprInner::Option { inner } => {
                 match inner {
@@ -1225,6 +1540,18 @@ where
                 body,
                 captures,
             } => {
+                // Lambdas of the recursive group are loaded when called, not captured,
+                // and unbound builtins are built at each use in the body
+                let recursive = core::mem::take(&mut self.recursive_group);
+                let captures: alloc::vec::Vec<&'arena str> = captures
+                    .iter()
+                    .copied()
+                    .filter(|name| !recursive.contains(name))
+                    .filter(|name| {
+                        builtins::lookup(name).is_none() || self.scope_stack.lookup(name).is_some()
+                    })
+                    .collect();
+
                 // Push captured values onto stack (for MakeClosure to consume)
                 for &capture_name in captures.iter() {
                     self.compile_variable_load(capture_name)?;
@@ -1260,7 +1587,8 @@ where
                             let lambda_code = self.compile_lambda_body(
                                 params,
                                 body,
-                                captures,
+                                &captures,
+                                recursive,
                                 concrete_type,
                                 Some(monomorphism),
                             )?;
@@ -1272,6 +1600,7 @@ where
                         let poly_entry = LambdaCode {
                             lambda_type: tree.0,
                             num_captures,
+                            num_recursive: recursive.len() as u32,
                             kind: LambdaKind::Poly { monos },
                         };
                         self.lambdas.push(poly_entry);
@@ -1280,8 +1609,9 @@ where
                     _ => {
                         // Monomorphic lambda: compile once
                         let mono_index = self.lambdas.len();
-                        let lambda_code =
-                            self.compile_lambda_body(params, body, captures, tree.0, None)?;
+                        let lambda_code = self.compile_lambda_body(
+                            params, body, &captures, recursive, tree.0, None,
+                        )?;
                         self.lambdas.push(lambda_code);
                         mono_index
                     }
@@ -1303,8 +1633,9 @@ where
                 //    d. Pop the original matched value (pattern succeeded, except last arm)
                 //    e. Compile body
                 //    f. Pop the pattern scope
-                //    g. Jump to end (except last arm)
-                //    h. Patch pattern fail jumps to next arm
+                //    g. Jump to end (except an irrefutable last arm)
+                //    h. Patch pattern fail jumps to next arm, or to a `Halt` after
+                //       the last arm
                 // 3. Patch all end jumps
 
                 // Compile the matched expression
@@ -1350,21 +1681,26 @@ where
                         self.scope_stack.pop().expect("Scope stack underflow");
                     }
 
-                    // Jump to end (except for last arm)
-                    if !is_last_arm {
+                    // Jump to end (except for an irrefutable last arm)
+                    if !is_last_arm || !fail_jumps.is_empty() {
                         let end_jump = self.jump_placeholder(Instruction::JumpForward);
                         end_jumps.push(end_jump);
                     }
 
                     // Patch pattern fail jumps to next arm (after end_jump for non-last arms)
                     let next_arm_label = self.label();
-                    for fail_jump in fail_jumps {
+                    for fail_jump in &fail_jumps {
                         self.patch_jump(
                             fail_jump.placeholder,
                             next_arm_label,
                             fail_jump.make_jump,
                         )?;
                     }
+
+                    // Exhaustiveness makes the last arm's pattern match
+                    if is_last_arm && !fail_jumps.is_empty() {
+                        self.emit(Instruction::Halt);
+                    }
                 }
 
                 // Patch all end jumps to point here
@@ -1403,3 +1739,26 @@ where
         Ok(())
     }
 }
+
+impl<'types, 'arena> TreeTransformer<ExprBuilder<'types, 'arena>>
+    for BytecodeCompiler<'types, 'arena>
+where
+    'types: 'arena,
+{
+    type Output = Result<(), CompileError>;
+
+    fn transform(&mut self, tree: &'arena Expr<'types, 'arena>) -> Self::Output {
+        let Some(span) = self.source.and_then(|source| source.span_of(tree)) else {
+            return self.compile_expr(tree);
+        };
+        // Instructions emitted after the subexpression, such as the operator
+        // applied to it, belong to the enclosing expression again
+        let enclosing = self.spans.last().map(|(_, span)| span.clone());
+        self.mark_span(span);
+        self.compile_expr(tree)?;
+        if let Some(enclosing) = enclosing {
+            self.mark_span(enclosing);
+        }
+        Ok(())
+    }
+}
@@ -2,17 +2,22 @@
 
 use crate::{
     analyzer,
+    api::CompileOptions,
     compiler::BytecodeCompiler,
-    evaluator::ExecutionError,
+    evaluator::{
+        Evaluator, EvaluatorOptions, ExecutionError, ExecutionErrorKind, ResourceExceededError,
+    },
     parser::{self, ComparisonOp},
     stdlib::math::register_math_functions,
+    types::Type,
     types::manager::TypeManager,
     values::{
         RawValue,
         binder::Binder,
         dynamic::{RecordBuilder, Value},
+        raw::MapEntry,
     },
-    vm::{Code, Instruction, VM},
+    vm::{Code, Instruction, STACK_INLINE_CAPACITY, VM},
 };
 use bumpalo::Bump;
 
@@ -26,6 +31,49 @@ fn compile_and_run<'a>(
     type_manager: &'a TypeManager<'a>,
     source: &str,
 ) -> (Code<'a>, Result<Value<'a, 'a>, ExecutionError>) {
+    let (code, result_type) = compile_source(arena, type_manager, source);
+    let result = VM::execute(arena, &code).map(|raw| Value::from_raw_unchecked(result_type, raw));
+    (code, result)
+}
+
+/// Helper function to run a source expression with the tree-walking evaluator,
+/// for comparing against the VM.
+fn evaluate<'a>(
+    arena: &'a Bump,
+    type_manager: &'a TypeManager<'a>,
+    source: &str,
+) -> Result<Value<'a, 'a>, ExecutionError> {
+    let source = arena.alloc_str(source);
+    let parsed = parser::parse(arena, source).unwrap();
+    let typed = analyzer::analyze(type_manager, arena, &parsed, &[], &[]).unwrap();
+    Evaluator::new(
+        EvaluatorOptions::default(),
+        arena,
+        type_manager,
+        typed,
+        &[],
+        &[],
+    )
+    .eval()
+}
+
+/// Helper function to compile a source expression without running it.
+/// Returns the compiled bytecode and the type of the expression.
+fn compile_source<'a>(
+    arena: &'a Bump,
+    type_manager: &'a TypeManager<'a>,
+    source: &str,
+) -> (Code<'a>, &'a Type<'a>) {
+    compile_source_with_options(arena, type_manager, source, &CompileOptions::default())
+}
+
+/// Like `compile_source`, but with explicit compile options (e.g., `optimize`).
+fn compile_source_with_options<'a>(
+    arena: &'a Bump,
+    type_manager: &'a TypeManager<'a>,
+    source: &str,
+    options: &CompileOptions,
+) -> (Code<'a>, &'a Type<'a>) {
     // Build Math package (available to all tests)
     let math =
         register_math_functions(arena, type_manager, RecordBuilder::new(arena, type_manager))
@@ -40,9 +88,14 @@ fn compile_and_run<'a>(
     let parsed = parser::parse(arena, source).unwrap();
     let typed = analyzer::analyze(type_manager, arena, &parsed, globals_types, &[]).unwrap();
     let result_type = typed.expr.0;
-    let code = BytecodeCompiler::compile(type_manager, arena, globals_values, typed).unwrap();
-    let result = VM::execute(arena, &code).map(|raw| Value::from_raw_unchecked(result_type, raw));
-    (code, result)
+    let code =
+        BytecodeCompiler::compile(type_manager, arena, globals_values, typed, options).unwrap();
+    (code, result_type)
+}
+
+/// Total number of bytes handed out by `arena` so far.
+fn arena_bytes_used(arena: &mut Bump) -> usize {
+    arena.iter_allocated_chunks().map(|chunk| chunk.len()).sum()
 }
 
 #[test]
@@ -172,6 +225,96 @@ fn test_stack_depth_tracking() {
     assert_eq!(result.unwrap().as_int().unwrap(), 6);
 }
 
+#[test]
+fn test_vm_matches_evaluator_across_stack_depths() {
+    // Right-nested operations keep every operand on the stack, so these cover
+    // both inline stacks and ones that spill to the heap.
+    let nested_sum = (1..=20)
+        .map(|i| i.to_string())
+        .collect::<Vec<_>>()
+        .join(" + (");
+    let nested_sum = format!("{}{}", nested_sum, ")".repeat(19));
+    let corpus = [
+        "42",
+        "1 + 2 * 3 - 4",
+        "if 1 < 2 then [1, 2, 3] else []",
+        "x * (y + (x * (y + (x * (y + x))))) where { x = 3, y = 4 }",
+        "[[a, a + 1], [a + 2, a * (a + (a * (a + 3)))]] where { a = 10 }",
+        "{ a = 1.5, b = 2.5 + (3.5 * (4.5 - (5.5 / (6.5 + 7.5)))) }",
+        &nested_sum,
+    ];
+
+    let mut max_depth = 0;
+    for source in corpus {
+        let arena = Bump::new();
+        let type_manager = TypeManager::new(&arena);
+        let (code, vm_result) = compile_and_run(&arena, &type_manager, source);
+        max_depth = max_depth.max(code.max_stack_size);
+
+        let evaluator_result = evaluate(&arena, &type_manager, source);
+
+        assert_eq!(vm_result.unwrap(), evaluator_result.unwrap(), "{source}");
+    }
+    assert!(max_depth > STACK_INLINE_CAPACITY);
+}
+
+#[test]
+fn test_vm_structural_equality_matches_evaluator() {
+    let corpus = [
+        ("true == true", true),
+        ("true != false", true),
+        ("[1, 2] == [1, 2]", true),
+        ("[1, 2] != [1, 2, 3]", true),
+        ("[[1, 2], [3]] == [[1, 2], [4]]", false),
+        ("[[1, 2], [3]] != [[1, 2], [4]]", true),
+        ("{ a = [1], b = some 2 } == { a = [1], b = some 2 }", true),
+        ("{ a = [1], b = some 2 } == { a = [1], b = none }", false),
+        ("{ 1: [10], 2: [20] } == { 2: [20], 1: [10] }", true),
+        ("{ 1: [10], 2: [20] } != { 1: [10], 2: [21] }", true),
+        ("some [some 1] == some [none]", false),
+        ("[-0.0] == [0.0]", true),
+        ("a == a where { a = [0.0 / 0.0] }", false),
+    ];
+
+    for (source, expected) in corpus {
+        let arena = Bump::new();
+        let type_manager = TypeManager::new(&arena);
+        let (_code, vm_result) = compile_and_run(&arena, &type_manager, source);
+        let evaluator_result = evaluate(&arena, &type_manager, source);
+
+        assert_eq!(vm_result.unwrap().as_bool().unwrap(), expected, "{source}");
+        assert_eq!(
+            evaluator_result.unwrap().as_bool().unwrap(),
+            expected,
+            "{source}"
+        );
+    }
+}
+
+#[test]
+fn test_vm_error_spans_match_evaluator() {
+    let corpus = [
+        "1 + 10 / (2 - 2)",
+        "[1, 2][i] + 1 where { i = 5 }",
+        "x + [1][x] where { x = 3 }",
+        "if 1 > 0 then { a = 1 }.a + 4 / 0 else 0",
+        "([1][i] otherwise 0) + [1][i + 1] where { i = 2 }",
+        "1.0 + (10 / (i - i)) as Float where { i = 1 }",
+    ];
+
+    for source in corpus {
+        let arena = Bump::new();
+        let type_manager = TypeManager::new(&arena);
+        let (_code, vm_result) = compile_and_run(&arena, &type_manager, source);
+        let evaluator_result = evaluate(&arena, &type_manager, source);
+
+        let vm_error = vm_result.unwrap_err();
+        let evaluator_error = evaluator_result.unwrap_err();
+        assert_eq!(vm_error.kind, evaluator_error.kind, "{source}");
+        assert_eq!(vm_error.span, evaluator_error.span, "{source}");
+    }
+}
+
 #[test]
 fn test_debug_output() {
     let arena = Bump::new();
@@ -262,6 +405,50 @@ fn test_constant_deduplication() {
     assert_eq!(code.max_stack_size, 2);
 }
 
+#[test]
+fn test_string_constant_deduplication() {
+    let arena = Bump::new();
+    let type_manager = TypeManager::new(&arena);
+
+    let (code, result) = compile_and_run(
+        &arena,
+        &type_manager,
+        r#"[["x", "x", "y"], ["x", "y", "x"]]"#,
+    );
+
+    // Equal strings share a constant, even when allocated separately
+    assert_eq!(code.constants.len(), 2, "{:?}", code.constants);
+    let loads = code
+        .instructions
+        .iter()
+        .filter(|instruction| matches!(instruction, Instruction::ConstLoad(_)))
+        .count();
+    assert_eq!(loads, 6);
+    assert_eq!(
+        result.unwrap().to_string(),
+        r#"[["x", "x", "y"], ["x", "y", "x"]]"#
+    );
+}
+
+#[test]
+fn test_constant_deduplication_keeps_types_and_signed_zeros_apart() {
+    let arena = Bump::new();
+    let type_manager = TypeManager::new(&arena);
+
+    // `"x"` and `b"x"` have the same content but different types
+    let (code, _result) = compile_and_run(&arena, &type_manager, r#"{s = "x", b = b"x"}"#);
+    assert_eq!(code.constants.len(), 2, "{:?}", code.constants);
+
+    // `-0.0 == 0.0`, but merging them would lose the sign
+    let source = "[1.0 / 0.0, 1.0 / -0.0]";
+    let (code, result) = compile_and_run(&arena, &type_manager, source);
+    assert_eq!(
+        result.unwrap(),
+        evaluate(&arena, &type_manager, source).unwrap()
+    );
+    assert_eq!(code.constants.len(), 3, "{:?}", code.constants);
+}
+
 #[test]
 fn test_comparison_operations() {
     let arena = Bump::new();
@@ -358,6 +545,97 @@ fn test_if_expression() {
     assert_eq!(result.unwrap().as_int().unwrap(), 42);
 }
 
+#[test]
+fn test_optimize_constant_if_emits_only_taken_branch() {
+    let arena = Bump::new();
+    let type_manager = TypeManager::new(&arena);
+    let optimize = CompileOptions {
+        optimize: true,
+        ..Default::default()
+    };
+
+    let (code, _) =
+        compile_source_with_options(&arena, type_manager, "if true then 1 else 2", &optimize);
+    assert_eq!(
+        code.instructions,
+        [Instruction::ConstInt(1), Instruction::Return]
+    );
+
+    // Constant comparisons and `not` fold too; the then branch is dropped here
+    let (code, _) = compile_source_with_options(
+        &arena,
+        type_manager,
+        "if not (1 < 2) then 1 else 2",
+        &optimize,
+    );
+    assert_eq!(
+        code.instructions,
+        [Instruction::ConstInt(2), Instruction::Return]
+    );
+
+    // Without `optimize`, both branches are emitted
+    let (code, _) = compile_source(&arena, type_manager, "if true then 1 else 2");
+    assert!(code.instructions.len() > 2);
+}
+
+#[test]
+fn test_optimize_keeps_variable_if_conditions() {
+    let arena = Bump::new();
+    let type_manager = TypeManager::new(&arena);
+    let optimize = CompileOptions {
+        optimize: true,
+        ..Default::default()
+    };
+
+    // `x or true` is always true, but folding it could skip evaluating `x`
+    for source in [
+        "(if x then 1 else 2) where { x = false }",
+        "(if x or true then 1 else 2) where { x = false }",
+    ] {
+        let (code, result_type) =
+            compile_source_with_options(&arena, type_manager, source, &optimize);
+        assert!(
+            code.instructions
+                .iter()
+                .any(|instruction| matches!(instruction, Instruction::PopJumpIfFalse(_))),
+            "expected a conditional jump for {:?}",
+            source
+        );
+        let result =
+            VM::execute(&arena, &code).map(|raw| Value::from_raw_unchecked(result_type, raw));
+        assert!(result.is_ok());
+    }
+}
+
+#[test]
+fn test_optimize_still_type_checks_dead_branch() {
+    let arena = Bump::new();
+    let type_manager = TypeManager::new(&arena);
+
+    // Type checking runs before compilation, so an ill-typed dead branch is still rejected
+    let parsed = parser::parse(&arena, "if true then 1 else \"x\"").unwrap();
+    assert!(analyzer::analyze(type_manager, &arena, &parsed, &[], &[]).is_err());
+}
+
+#[test]
+fn test_optimize_constant_if_inside_lambda() {
+    let arena = Bump::new();
+    let type_manager = TypeManager::new(&arena);
+    let optimize = CompileOptions {
+        optimize: true,
+        ..Default::default()
+    };
+
+    let (code, result_type) = compile_source_with_options(
+        &arena,
+        type_manager,
+        "f(5) where { f = (x) => if false then 0 else x * 2 }",
+        &optimize,
+    );
+    let result = VM::execute(&arena, &code).map(|raw| Value::from_raw_unchecked(result_type, raw));
+    assert_eq!(result.unwrap().as_int().unwrap(), 10);
+}
+
 #[test]
 fn test_all_comparison_operators() {
     let arena = Bump::new();
@@ -716,6 +994,57 @@ fn test_nested_arrays() {
     );
 }
 
+#[test]
+fn test_long_array_literal_is_built_incrementally() {
+    let arena = Bump::new();
+    let type_manager = TypeManager::new(&arena);
+
+    let elements: alloc::vec::Vec<_> = (0..1500).map(|i| format!("{} * 2", i)).collect();
+    let source = format!("[{}]", elements.join(", "));
+    let (code, result) = compile_and_run(&arena, &type_manager, &source);
+
+    assert_eq!(code.instructions[0], Instruction::ArrayStart);
+    assert!(
+        !code
+            .instructions
+            .iter()
+            .any(|inst| matches!(inst, Instruction::MakeArray(_)))
+    );
+    // Each element is appended before the next one is computed
+    assert_eq!(code.max_stack_size, 2);
+
+    let result = result.unwrap();
+    assert_eq!(result, evaluate(&arena, &type_manager, &source).unwrap());
+    let array = result.as_array().unwrap();
+    assert_eq!(array.len(), 1500);
+    assert_eq!(array.get(1499).unwrap().as_int().unwrap(), 2998);
+}
+
+#[test]
+fn test_otherwise_discards_partly_built_array() {
+    let arena = Bump::new();
+    let type_manager = TypeManager::new(&arena);
+
+    // The inner literal fails half way while the outer one is also being
+    // built; only the inner builder is dropped
+    let numbers: alloc::vec::Vec<_> = (0..1100).map(|i| i.to_string()).collect();
+    let singletons: alloc::vec::Vec<_> = (0..1100).map(|i| format!("[{}]", i)).collect();
+    let source = format!(
+        "[{}, [{}, [1][5]] otherwise [42]]",
+        singletons.join(", "),
+        numbers.join(", ")
+    );
+    let (code, result) = compile_and_run(&arena, &type_manager, &source);
+    assert_eq!(code.instructions[0], Instruction::ArrayStart);
+
+    let result = result.unwrap();
+    assert_eq!(result, evaluate(&arena, &type_manager, &source).unwrap());
+    let array = result.as_array().unwrap();
+    assert_eq!(array.len(), 1101);
+    assert_eq!(format!("{:?}", array.get(0).unwrap()), "[0]");
+    assert_eq!(format!("{:?}", array.get(1100).unwrap()), "[42]");
+}
+
 #[test]
 fn test_array_of_booleans() {
     let arena = Bump::new();
@@ -755,6 +1084,46 @@ fn test_single_element_array() {
     assert_eq!(code.max_stack_size, 1);
 }
 
+#[test]
+fn test_vm_large_array_uses_wide_make_array() {
+    let arena = Bump::new();
+    let type_manager = TypeManager::new(&arena);
+
+    let elements: alloc::vec::Vec<String> = (0..300).map(|i| i.to_string()).collect();
+    let source = format!("[{}]", elements.join(", "));
+    let (code, result) = compile_and_run(&arena, &type_manager, &source);
+
+    // 300 = 0x012C, so the count needs a WideArg prefix.
+    let n = code.instructions.len();
+    assert_eq!(code.instructions[n - 3], Instruction::WideArg(0x01));
+    assert_eq!(code.instructions[n - 2], Instruction::MakeArray(0x2C));
+    assert_eq!(code.max_stack_size, 300);
+
+    let array = result.unwrap().as_array().unwrap();
+    assert_eq!(array.len(), 300);
+    for (i, element) in array.iter().enumerate() {
+        assert_eq!(element.as_int().unwrap(), i as i64);
+    }
+}
+
+#[test]
+fn test_vm_make_array_allocates_once() {
+    let arena = Bump::new();
+    let type_manager = TypeManager::new(&arena);
+
+    let elements: alloc::vec::Vec<String> = (0..300).map(|i| i.to_string()).collect();
+    let source = format!("[{}]", elements.join(", "));
+    let (code, _) = compile_source(&arena, &type_manager, &source);
+
+    // Run in a separate arena so only the VM's allocations are counted.
+    let mut vm_arena = Bump::new();
+    VM::execute(&vm_arena, &code).unwrap();
+
+    // The length header plus exactly 300 slots, in a single allocation.
+    let expected = core::mem::size_of::<usize>() + 300 * core::mem::size_of::<RawValue>();
+    assert_eq!(arena_bytes_used(&mut vm_arena), expected);
+}
+
 #[test]
 fn test_float_addition() {
     let arena = Bump::new();
@@ -1184,6 +1553,111 @@ fn test_vm_where_binding() {
     assert_eq!(result.unwrap().as_int().unwrap(), 6);
 }
 
+#[test]
+fn test_vm_where_forward_reference() {
+    let arena = Bump::new();
+    let type_manager = TypeManager::new(&arena);
+
+    let (_code, result) = compile_and_run(
+        &arena,
+        &type_manager,
+        "a * 10 + b where { a = b + 1, b = 2 }",
+    );
+
+    // b = 2, a = 3
+    assert_eq!(result.unwrap().as_int().unwrap(), 32);
+}
+
+#[test]
+fn test_vm_where_recursive_lambda() {
+    let arena = Bump::new();
+    let type_manager = TypeManager::new(&arena);
+
+    let (code, result) = compile_and_run(
+        &arena,
+        &type_manager,
+        "factorial(5) where { factorial = (n) => if n <= 1 then 1 else n * factorial(n - 1) }",
+    );
+    assert_eq!(result.unwrap().as_int().unwrap(), 120);
+    assert_eq!(code.verify(), Ok(()));
+    assert_eq!(code.lambdas[0].num_captures, 0);
+    assert_eq!(code.lambdas[0].num_recursive, 1);
+}
+
+#[test]
+fn test_vm_where_mutually_recursive_lambdas() {
+    let arena = Bump::new();
+    let type_manager = TypeManager::new(&arena);
+
+    let source = "[is_even(10), is_odd(7), is_even(3), id(1) == 1, id(\"s\") == \"s\"] where {
+        is_even = (n) => if n == 0 then true else is_odd(n - 1),
+        is_odd = (n) => if n == 0 then false else is_even(n - 1),
+        id = (x) => if false then id(x) else x,
+    }";
+    let (code, result) = compile_and_run(&arena, &type_manager, source);
+    assert_eq!(code.verify(), Ok(()));
+    assert_eq!(
+        result.unwrap(),
+        evaluate(&arena, &type_manager, source).unwrap()
+    );
+}
+
+#[test]
+fn test_compiled_bytecode_verifies_with_and_without_optimize() {
+    // `compile` only checks this in debug builds
+    let corpus = [
+        "1 + 2 * 3 - 4",
+        "if 1 < 2 then [1, 2, 3] else []",
+        "if true then 1 else 2",
+        "[1, 2, 3][10] otherwise 42",
+        "([1, 2][10] otherwise 50) otherwise 99",
+        "(42 as Float) + 0.5",
+        "Math.Sqrt(a * a + b * b) where { a = 3.0, b = 4.0 }",
+        "{ a = 1.5, b = { c = [true, false] } }.b.c[0]",
+        r#"{ "a": 1, "b": 2 }["b"]"#,
+        r#""a" in { "b": 2 }"#,
+        "(some { x = 1 })?.x ?? 0",
+        "opt match { some (some x) -> x, _ -> 0 } where { opt = some (some 5) }",
+        r#"f"{a} + {b} = {a + b}" where { a = 1, b = 2 }"#,
+        "f(5) where { a = 1, f = (x) => g(10) where { g = (y) => x + y + a } }",
+        "factorial(5) where { factorial = (n) => if n <= 1 then 1 else n * factorial(n - 1) }",
+        "[is_even(10), is_odd(7)] where {
+            is_even = (n) => if n == 0 then true else is_odd(n - 1),
+            is_odd = (n) => if n == 0 then false else is_even(n - 1),
+        }",
+        r#"[id(1) == 1, id("s") == "s"] where { id = (x) => x }"#,
+    ];
+
+    for optimize in [false, true] {
+        let options = CompileOptions {
+            optimize,
+            ..Default::default()
+        };
+        for source in corpus {
+            let arena = Bump::new();
+            let type_manager = TypeManager::new(&arena);
+            let (code, _) = compile_source_with_options(&arena, type_manager, source, &options);
+            assert_eq!(code.verify(), Ok(()), "{source} (optimize: {optimize})");
+        }
+    }
+}
+
+#[test]
+fn test_vm_recursion_stops_at_max_depth() {
+    let arena = Bump::new();
+    let type_manager = TypeManager::new(&arena);
+
+    let (code, _) = compile_source(&arena, &type_manager, "f(5) where { f = (n) => f(n - 1) }");
+    let err = VM::execute_with_limits(&arena, &code, 50, None).unwrap_err();
+    assert_eq!(
+        err.kind,
+        ExecutionErrorKind::ResourceExceeded(ResourceExceededError::StackOverflow {
+            depth: 50,
+            max_depth: 50
+        })
+    );
+}
+
 #[test]
 fn test_vm_scope_restoration() {
     let arena = Bump::new();
@@ -1384,6 +1858,58 @@ fn test_field_access_second_field() {
     assert_eq!(result.unwrap().as_int().unwrap(), 20);
 }
 
+#[test]
+fn test_record_index_constant_key() {
+    let arena = Bump::new();
+    let type_manager = TypeManager::new(&arena);
+
+    let (code, result) = compile_and_run(&arena, &type_manager, "{ x = 10, y = 20 }[\"y\"]");
+
+    // A constant key compiles like field access: RecordGet(1) for 'y'
+    assert_eq!(code.instructions[3], Instruction::RecordGet(1));
+    assert_eq!(result.unwrap().as_int().unwrap(), 20);
+}
+
+#[test]
+fn test_record_index_dynamic_key() {
+    let arena = Bump::new();
+    let type_manager = TypeManager::new(&arena);
+
+    let (code, result) = compile_and_run(
+        &arena,
+        &type_manager,
+        "{ x = 10, y = 20 }[key] where { key = \"y\" }",
+    );
+
+    assert!(code.instructions.contains(&Instruction::RecordGetDynamic));
+    assert_eq!(result.unwrap().as_int().unwrap(), 20);
+}
+
+#[test]
+fn test_record_index_dynamic_key_not_found() {
+    let arena = Bump::new();
+    let type_manager = TypeManager::new(&arena);
+
+    let (_code, result) = compile_and_run(
+        &arena,
+        &type_manager,
+        "{ x = 10, y = 20 }[key] where { key = \"z\" }",
+    );
+    assert!(matches!(
+        result.unwrap_err().kind,
+        crate::evaluator::ExecutionErrorKind::Runtime(
+            crate::evaluator::RuntimeError::KeyNotFound { .. }
+        )
+    ));
+
+    let (_code, result) = compile_and_run(
+        &arena,
+        &type_manager,
+        "{ x = 10, y = 20 }[key] otherwise -1 where { key = \"z\" }",
+    );
+    assert_eq!(result.unwrap().as_int().unwrap(), -1);
+}
+
 #[test]
 fn test_vm_record_field_access() {
     let arena = Bump::new();
@@ -1458,6 +1984,29 @@ fn test_map_construction() {
     assert_eq!(map.len(), 2);
 }
 
+#[test]
+fn test_vm_make_map_allocates_once() {
+    let arena = Bump::new();
+    let type_manager = TypeManager::new(&arena);
+
+    let entries: alloc::vec::Vec<String> = (0..300).map(|i| format!("{i}: {i}")).collect();
+    let source = format!("{{ {} }}", entries.join(", "));
+    let (code, result_type) = compile_source(&arena, &type_manager, &source);
+
+    // Run in a separate arena so only the VM's allocations are counted.
+    let mut vm_arena = Bump::new();
+    let raw = VM::execute(&vm_arena, &code).unwrap();
+
+    let map = Value::from_raw_unchecked(result_type, raw)
+        .as_map()
+        .unwrap();
+    assert_eq!(map.len(), 300);
+
+    // The length header plus exactly 300 entries, in a single allocation.
+    let expected = core::mem::size_of::<usize>() + 300 * core::mem::size_of::<MapEntry>();
+    assert_eq!(arena_bytes_used(&mut vm_arena), expected);
+}
+
 #[test]
 fn test_map_indexing() {
     let arena = Bump::new();
@@ -1482,6 +2031,28 @@ fn test_map_indexing() {
     assert_eq!(result.unwrap().as_int().unwrap(), 100);
 }
 
+#[test]
+fn test_map_iteration_order_matches_evaluator() {
+    let arena = Bump::new();
+    let type_manager = TypeManager::new(&arena);
+
+    // Out-of-order keys, with `1` repeated
+    let source = "{ 3: 30, -5: 50, 1: 10, 2: 20, 1: 11 }";
+    let (_code, vm_result) = compile_and_run(&arena, &type_manager, source);
+
+    let evaluator_result = evaluate(&arena, &type_manager, source);
+
+    let entries = |value: Value<'_, '_>| -> Vec<(i64, i64)> {
+        let map = value.as_map().unwrap();
+        map.iter()
+            .map(|(key, value)| (key.as_int().unwrap(), value.as_int().unwrap()))
+            .collect()
+    };
+    let expected = [(-5, 50), (1, 11), (2, 20), (3, 30)];
+    assert_eq!(entries(vm_result.unwrap()), expected);
+    assert_eq!(entries(evaluator_result.unwrap()), expected);
+}
+
 #[test]
 fn test_vm_map_indexing() {
     let arena = Bump::new();
@@ -1533,7 +2104,6 @@ fn test_vm_array_negative_index_first() {
 }
 
 #[test]
-#[ignore = "Map string keys not implemented in VM (only integer keys work)"]
 fn test_vm_map_string_keys() {
     let arena = Bump::new();
     let type_manager = TypeManager::new(&arena);
@@ -1544,18 +2114,37 @@ fn test_vm_map_string_keys() {
 }
 
 #[test]
-#[ignore = "Map[Str, Str] not implemented in VM"]
 fn test_vm_map_string_to_string() {
     let arena = Bump::new();
     let type_manager = TypeManager::new(&arena);
 
     // Test: { "greeting": "hello", "farewell": "goodbye" }["greeting"]
-    let (_code, _result) = compile_and_run(
+    let (_code, result) = compile_and_run(
         &arena,
         &type_manager,
         r#"{ "greeting": "hello", "farewell": "goodbye" }["greeting"]"#,
     );
-    // Result should be the string "hello" but string extraction not implemented yet
+    assert_eq!(result.unwrap().as_str().unwrap(), "hello");
+}
+
+#[test]
+fn test_vm_maps_with_string_keys_match_evaluator() {
+    let arena = Bump::new();
+    let type_manager = TypeManager::new(&arena);
+
+    for source in [
+        r#"{ "b": 2, "a": 1 } == { "a": 1, "b": 2 }"#,
+        r#""a" in { "b": 2, "a": 1 }"#,
+        r#""c" not in { "b": 2, "a": 1 }"#,
+        r#"({ "a": 1 }["b"] otherwise 0) == 0"#,
+        r#"{ "a": 1, "a": 2 } == { "a": 2 }"#,
+        "3 in { 3: true }",
+    ] {
+        let (_code, vm_result) = compile_and_run(&arena, &type_manager, source);
+        assert!(vm_result.unwrap().as_bool().unwrap(), "{}", source);
+        let evaluator_result = evaluate(&arena, &type_manager, source);
+        assert!(evaluator_result.unwrap().as_bool().unwrap(), "{}", source);
+    }
 }
 
 #[test]
@@ -1808,6 +2397,67 @@ fn test_vm_otherwise_chained() {
     assert_eq!(result.unwrap().as_int().unwrap(), 42);
 }
 
+/// Asserts that `source` fails with the given runtime error in the VM.
+fn assert_vm_runtime_error(source: &str, expected: crate::evaluator::RuntimeError) {
+    let arena = Bump::new();
+    let type_manager = TypeManager::new(&arena);
+
+    let (_code, result) = compile_and_run(&arena, &type_manager, source);
+    assert_eq!(
+        result.unwrap_err().kind,
+        crate::evaluator::ExecutionErrorKind::Runtime(expected),
+        "source: {source}"
+    );
+}
+
+#[test]
+fn test_vm_otherwise_fallback_error_propagates() {
+    // Both primary and fallback fail: the fallback's error is not swallowed.
+    assert_vm_runtime_error(
+        "[1][5] otherwise [2, 3][i] where { i = 7 }",
+        crate::evaluator::RuntimeError::IndexOutOfBounds { index: 7, len: 2 },
+    );
+}
+
+#[test]
+fn test_vm_otherwise_fallback_error_caught_by_outer() {
+    let arena = Bump::new();
+    let type_manager = TypeManager::new(&arena);
+
+    let (_code, result) = compile_and_run(
+        &arena,
+        &type_manager,
+        "([1][5] otherwise [2, 3][7]) otherwise 42",
+    );
+    assert_eq!(result.unwrap().as_int().unwrap(), 42);
+}
+
+#[test]
+fn test_vm_otherwise_does_not_catch_error_on_its_result() {
+    // The primary succeeds; indexing its result afterwards is outside the scope.
+    assert_vm_runtime_error(
+        "([1, 2] otherwise [3])[5]",
+        crate::evaluator::RuntimeError::IndexOutOfBounds { index: 5, len: 2 },
+    );
+}
+
+#[test]
+fn test_vm_otherwise_does_not_catch_later_operation() {
+    assert_vm_runtime_error(
+        "([1][0] otherwise 0) + [2][i] where { i = 5 }",
+        crate::evaluator::RuntimeError::IndexOutOfBounds { index: 5, len: 1 },
+    );
+}
+
+#[test]
+fn test_vm_otherwise_handled_error_then_later_error() {
+    // The first error is handled, the second one happens outside the scope.
+    assert_vm_runtime_error(
+        "([1][5] otherwise 0) + 10 / 0",
+        crate::evaluator::RuntimeError::DivisionByZero {},
+    );
+}
+
 // ============================================================================
 // Error Tests (Without Otherwise Handlers)
 // ============================================================================
@@ -1818,7 +2468,8 @@ fn test_vm_array_index_error_no_otherwise() {
     let type_manager = TypeManager::new(&arena);
 
     // Array index out of bounds without otherwise should return error
-    let (_code, result) = compile_and_run(&arena, &type_manager, "[1, 2, 3][10]");
+    let (_code, result) =
+        compile_and_run(&arena, &type_manager, "[1, 2, 3][i] where { i = 10 }");
 
     assert!(result.is_err(), "Expected error for out of bounds access");
     let err = result.unwrap_err();
@@ -1934,7 +2585,7 @@ fn test_vm_negative_index_out_of_bounds() {
     let type_manager = TypeManager::new(&arena);
 
     // Test: [1, 2][-3] should error (too negative)
-    let (_code, result) = compile_and_run(&arena, &type_manager, "[1, 2][-3]");
+    let (_code, result) = compile_and_run(&arena, &type_manager, "[1, 2][i] where { i = -3 }");
 
     assert!(
         result.is_err(),
@@ -1959,7 +2610,8 @@ fn test_vm_negative_index_way_out_of_bounds() {
     let type_manager = TypeManager::new(&arena);
 
     // Test: [1, 2][-100] should error (way too negative)
-    let (_code, result) = compile_and_run(&arena, &type_manager, "[1, 2][-100]");
+    let (_code, result) =
+        compile_and_run(&arena, &type_manager, "[1, 2][i] where { i = -100 }");
 
     assert!(
         result.is_err(),
@@ -2081,7 +2733,7 @@ fn test_vm_empty_array_positive_index_error() {
     let type_manager = TypeManager::new(&arena);
 
     // Indexing empty array with positive index should error
-    let (_code, result) = compile_and_run(&arena, &type_manager, "[][0]");
+    let (_code, result) = compile_and_run(&arena, &type_manager, "[][i] where { i = 0 }");
 
     assert!(result.is_err(), "Expected error for indexing empty array");
     let err = result.unwrap_err();
@@ -2103,7 +2755,7 @@ fn test_vm_empty_array_negative_index_error() {
     let type_manager = TypeManager::new(&arena);
 
     // Indexing empty array with negative index should error
-    let (_code, result) = compile_and_run(&arena, &type_manager, "[][-1]");
+    let (_code, result) = compile_and_run(&arena, &type_manager, "[][i] where { i = -1 }");
 
     assert!(
         result.is_err(),
@@ -2283,6 +2935,93 @@ fn test_vm_some_with_record() {
     assert!(record.as_record().is_ok(), "Expected record inside Some");
 }
 
+#[test]
+fn test_vm_coalesce_some() {
+    let arena = Bump::new();
+    let type_manager = TypeManager::new(&arena);
+
+    let (code, result) = compile_and_run(&arena, &type_manager, "some 5 ?? 0");
+    assert_eq!(result.unwrap().as_int().unwrap(), 5);
+
+    // Compiles to a branch on the option discriminant
+    assert!(
+        code.instructions
+            .iter()
+            .any(|instr| matches!(instr, Instruction::MatchSomeOrJump(_)))
+    );
+}
+
+#[test]
+fn test_vm_coalesce_none() {
+    let arena = Bump::new();
+    let type_manager = TypeManager::new(&arena);
+
+    let (_code, result) = compile_and_run(&arena, &type_manager, "(none ?? 0) == 0");
+    assert!(result.unwrap().as_bool().unwrap());
+}
+
+#[test]
+fn test_vm_coalesce_chained() {
+    let arena = Bump::new();
+    let type_manager = TypeManager::new(&arena);
+
+    let (_code, result) = compile_and_run(
+        &arena,
+        &type_manager,
+        "a ?? b ?? 3 where { a = none, b = some 2 }",
+    );
+    assert_eq!(result.unwrap().as_int().unwrap(), 2);
+
+    let (_code, result) = compile_and_run(
+        &arena,
+        &type_manager,
+        "a ?? b ?? 3 where { a = none, b = none }",
+    );
+    assert_eq!(result.unwrap().as_int().unwrap(), 3);
+}
+
+#[test]
+fn test_vm_optional_field() {
+    let arena = Bump::new();
+    let type_manager = TypeManager::new(&arena);
+
+    let (_code, result) = compile_and_run(&arena, &type_manager, "(some { x = 1 })?.x ?? 0");
+    assert_eq!(result.unwrap().as_int().unwrap(), 1);
+
+    let (_code, result) = compile_and_run(
+        &arena,
+        &type_manager,
+        "opt?.x ?? -1 where { opt = if false then some { x = 1 } else none }",
+    );
+    assert_eq!(result.unwrap().as_int().unwrap(), -1);
+}
+
+#[test]
+fn test_vm_optional_field_chained() {
+    let arena = Bump::new();
+    let type_manager = TypeManager::new(&arena);
+
+    let (_code, result) =
+        compile_and_run(&arena, &type_manager, "(some { a = { b = 7 } })?.a?.b ?? 0");
+    assert_eq!(result.unwrap().as_int().unwrap(), 7);
+
+    let (_code, result) = compile_and_run(
+        &arena,
+        &type_manager,
+        "opt?.a?.b ?? 0 where { opt = if false then some { a = { b = 7 } } else none }",
+    );
+    assert_eq!(result.unwrap().as_int().unwrap(), 0);
+}
+
+#[test]
+fn test_vm_coalesce_does_not_evaluate_default_for_some() {
+    let arena = Bump::new();
+    let type_manager = TypeManager::new(&arena);
+
+    let (_code, result) = compile_and_run(&arena, &type_manager, "some 1 ?? 1 / 0");
+    assert_eq!(result.unwrap().as_int().unwrap(), 1);
+}
+
 // ============================================================================
 // FFI Function Call Tests
 // ============================================================================
@@ -2617,6 +3356,8 @@ fn test_wide_arg_encoding_bytes() {
         num_locals: 0,
         max_stack_size: 1,
         lambdas: alloc::vec::Vec::new(),
+        spans: alloc::vec::Vec::new(),
+        record_field_orders: alloc::vec::Vec::new(),
     };
 
     let result = VM::execute(&arena, &code);
@@ -2652,6 +3393,8 @@ fn test_wide_arg_three_byte_encoding() {
         num_locals: 0,
         max_stack_size: 1,
         lambdas: alloc::vec::Vec::new(),
+        spans: alloc::vec::Vec::new(),
+        record_field_orders: alloc::vec::Vec::new(),
     };
 
     let result = VM::execute(&arena, &code);
@@ -2874,6 +3617,8 @@ fn test_wide_jump_vm_direct() {
         num_locals: 0,
         max_stack_size: 1,
         lambdas: alloc::vec::Vec::new(),
+        spans: alloc::vec::Vec::new(),
+        record_field_orders: alloc::vec::Vec::new(),
     };
 
     let result = VM::execute(&arena, &code);
@@ -2911,6 +3656,8 @@ fn test_wide_jump_pop_jump_if_false_vm_direct() {
         num_locals: 0,
         max_stack_size: 1,
         lambdas: alloc::vec::Vec::new(),
+        spans: alloc::vec::Vec::new(),
+        record_field_orders: alloc::vec::Vec::new(),
     };
 
     let result = VM::execute(&arena, &code);
@@ -4067,3 +4814,67 @@ fn test_polymorphic_lambda_function_call() {
         "Polymorphic apply with String function should work"
     );
 }
+
+#[cfg(feature = "bigint")]
+#[test]
+fn test_bigint_arithmetic() {
+    let arena = Bump::new();
+    let type_manager = TypeManager::new(&arena);
+
+    let (code, result) = compile_and_run(&arena, &type_manager, "9223372036854775807n + 1n");
+    assert!(
+        code.instructions
+            .iter()
+            .any(|i| matches!(i, Instruction::BigIntBinOp(b'+')))
+    );
+    assert_eq!(
+        result.unwrap().as_bigint().unwrap(),
+        num_bigint::BigInt::from(i64::MAX) + 1
+    );
+
+    let (_code, result) = compile_and_run(&arena, &type_manager, "-(2n ^ 64n) < -1n");
+    assert!(result.unwrap().as_bool().unwrap());
+}
+
+#[cfg(feature = "bigint")]
+#[test]
+fn test_bigint_int_conversion() {
+    let arena = Bump::new();
+    let type_manager = TypeManager::new(&arena);
+
+    let (_code, result) = compile_and_run(&arena, &type_manager, "(-5 as BigInt * 3n) as Int");
+    assert_eq!(result.unwrap().as_int().unwrap(), -15);
+
+    let (_code, result) = compile_and_run(
+        &arena,
+        &type_manager,
+        "(-9223372036854775808 as BigInt - 1n) as Int otherwise 0",
+    );
+    assert_eq!(result.unwrap().as_int().unwrap(), 0);
+}
+
+#[cfg(feature = "decimal")]
+#[test]
+fn test_decimal_arithmetic() {
+    let arena = Bump::new();
+    let type_manager = TypeManager::new(&arena);
+
+    let (code, result) = compile_and_run(&arena, &type_manager, "0.1d + 0.2d == 0.3d");
+    assert!(
+        code.instructions
+            .iter()
+            .any(|i| matches!(i, Instruction::DecimalBinOp(b'+')))
+    );
+    assert!(result.unwrap().as_bool().unwrap());
+
+    let (_code, result) = compile_and_run(&arena, &type_manager, "-(1d / 8d)");
+    assert_eq!(format!("{:?}", result.unwrap()), "-0.125d");
+
+    let (_code, result) = compile_and_run(&arena, &type_manager, "1d / 0d");
+    assert!(matches!(
+        result.unwrap_err().kind,
+        crate::evaluator::ExecutionErrorKind::Runtime(
+            crate::evaluator::RuntimeError::DivisionByZero {}
+        )
+    ));
+}
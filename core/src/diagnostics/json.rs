@@ -0,0 +1,239 @@
+//! JSON serialization of diagnostics for editors, CI, and other tooling.
+//!
+//! Every diagnostic becomes one object with a fixed set of keys, always
+//! present and always in this order:
+//!
+//! ```text
+//! {
+//!   "severity": "error",                  // "error", "warning" or "info"
+//!   "message": "Type mismatch",
+//!   "code": "E001",                       // or null
+//!   "span": {"start": 4, "end": 8},       // byte offsets into the source
+//!   "help": ["Types must match"],
+//!   "related": [{"message": "x defined here", "span": {"start": 0, "end": 1}}],
+//!   "suggestions": []
+//! }
+//! ```
+//!
+//! [`JsonDiagnostic`] implements `Serialize` for this schema, so hosts pass
+//! it to the serde serializer of their choice. `suggestions` is reserved for
+//! machine-applicable fixes; no diagnostic carries one yet, so it is always
+//! empty. Hosts that index the source differently (JavaScript counts UTF-16
+//! code units) can remap offsets with [`Diagnostic::to_json_with_offsets`]
+//! and [`Utf16Index`].
+
+use serde::Serialize;
+
+use crate::api::{Diagnostic, Severity};
+use crate::parser::Span;
+use crate::{String, Vec};
+
+/// A diagnostic in the [`crate::diagnostics::json`] schema.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct JsonDiagnostic {
+    severity: Severity,
+    message: String,
+    code: Option<String>,
+    span: JsonSpan,
+    help: Vec<String>,
+    related: Vec<JsonRelatedInfo>,
+    /// Always empty: reserved for machine-applicable fixes.
+    suggestions: [(); 0],
+}
+
+/// A span as start and end offsets.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct JsonSpan {
+    start: usize,
+    end: usize,
+}
+
+/// A related location of a [`JsonDiagnostic`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct JsonRelatedInfo {
+    message: String,
+    span: JsonSpan,
+}
+
+impl Diagnostic {
+    /// This diagnostic in the JSON schema, with spans as byte offsets. See
+    /// [`crate::diagnostics::json`] for the schema.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use melbi_core::api::{Engine, Error};
+    /// use bumpalo::Bump;
+    ///
+    /// let arena = Bump::new();
+    /// let engine = Engine::new(Default::default(), &arena, |_, _, env| env);
+    /// let Err(Error::Compilation { diagnostics, .. }) =
+    ///     engine.compile(Default::default(), "1 + true", &[])
+    /// else {
+    ///     panic!("expected a compilation error");
+    /// };
+    /// let json = serde_json::to_string(&diagnostics[0].to_json()).unwrap();
+    /// assert!(json.starts_with(r#"{"severity":"error","#));
+    /// ```
+    pub fn to_json(&self) -> JsonDiagnostic {
+        self.to_json_with_offsets(|offset| offset)
+    }
+
+    /// This diagnostic in the JSON schema like [`to_json`](Self::to_json),
+    /// mapping every byte offset through `offset` first.
+    pub fn to_json_with_offsets(&self, offset: impl Fn(usize) -> usize) -> JsonDiagnostic {
+        let span = |span: &Span| JsonSpan {
+            start: offset(span.0.start),
+            end: offset(span.0.end),
+        };
+        JsonDiagnostic {
+            severity: self.severity,
+            message: self.message.clone(),
+            code: self.code.clone(),
+            span: span(&self.span),
+            help: self.help.clone(),
+            related: self
+                .related
+                .iter()
+                .map(|info| JsonRelatedInfo {
+                    message: info.message.clone(),
+                    span: span(&info.span),
+                })
+                .collect(),
+            suggestions: [],
+        }
+    }
+}
+
+/// Maps byte offsets in a source string to UTF-16 code-unit offsets.
+///
+/// `Span`s are byte offsets into the UTF-8 source, but JavaScript strings (and
+/// therefore editors running in a browser) index by UTF-16 code units, so
+/// highlights drift after any multibyte character without this conversion.
+pub struct Utf16Index {
+    /// `offsets[byte]` is the UTF-16 offset of the character containing `byte`.
+    offsets: Vec<usize>,
+}
+
+impl Utf16Index {
+    /// Index the UTF-16 offsets of every byte of `source`.
+    pub fn new(source: &str) -> Self {
+        let mut offsets = Vec::with_capacity(source.len() + 1);
+        let mut utf16_offset = 0;
+        for ch in source.chars() {
+            offsets.extend(core::iter::repeat_n(utf16_offset, ch.len_utf8()));
+            utf16_offset += ch.len_utf16();
+        }
+        offsets.push(utf16_offset);
+        Self { offsets }
+    }
+
+    /// The UTF-16 offset of `byte_offset`, clamped to the end of the source.
+    pub fn to_utf16(&self, byte_offset: usize) -> usize {
+        let last = self.offsets.len() - 1;
+        self.offsets[byte_offset.min(last)]
+    }
+
+    /// `diagnostic` in the JSON schema, with its spans in UTF-16 code units.
+    pub fn diagnostic_to_json(&self, diagnostic: &Diagnostic) -> JsonDiagnostic {
+        diagnostic.to_json_with_offsets(|offset| self.to_utf16(offset))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::RelatedInfo;
+    use crate::{ToString, vec};
+
+    fn diagnostic(span: Span, message: &str) -> Diagnostic {
+        Diagnostic {
+            severity: Severity::Error,
+            message: message.to_string(),
+            span,
+            related: vec![],
+            help: vec![],
+            code: None,
+        }
+    }
+
+    fn to_string(diag: &Diagnostic) -> String {
+        serde_json::to_string(&diag.to_json()).unwrap()
+    }
+
+    #[test]
+    fn test_error_with_related_info_and_help() {
+        let mut diag = diagnostic(Span::new(25, 29), "Type mismatch: expected Int, found Bool");
+        diag.code = Some("E001".to_string());
+        diag.help = vec!["Types must match in this context".to_string()];
+        diag.related = vec![RelatedInfo {
+            span: Span::new(12, 13),
+            message: "x defined here".to_string(),
+        }];
+
+        assert_eq!(
+            to_string(&diag),
+            concat!(
+                r#"{"severity":"error","#,
+                r#""message":"Type mismatch: expected Int, found Bool","#,
+                r#""code":"E001","#,
+                r#""span":{"start":25,"end":29},"#,
+                r#""help":["Types must match in this context"],"#,
+                r#""related":[{"message":"x defined here","span":{"start":12,"end":13}}],"#,
+                r#""suggestions":[]}"#,
+            )
+        );
+    }
+
+    #[test]
+    fn test_missing_fields_keep_their_keys() {
+        let mut diag = diagnostic(Span::new(0, 0), "Unused binding `x`");
+        diag.severity = Severity::Warning;
+
+        assert_eq!(
+            to_string(&diag),
+            concat!(
+                r#"{"severity":"warning","message":"Unused binding `x`","code":null,"#,
+                r#""span":{"start":0,"end":0},"help":[],"related":[],"suggestions":[]}"#,
+            )
+        );
+    }
+
+    #[test]
+    fn test_strings_are_escaped() {
+        let message = "bad \"quote\" \\ and\nnewline\t\u{1}é";
+        let diag = diagnostic(Span::new(0, 1), message);
+
+        let value: serde_json::Value = serde_json::from_str(&to_string(&diag)).unwrap();
+        assert_eq!(value["message"], message);
+    }
+
+    #[test]
+    fn test_offsets_are_remapped() {
+        let mut diag = diagnostic(Span::new(4, 8), "found Bool");
+        diag.related = vec![RelatedInfo {
+            span: Span::new(0, 2),
+            message: "here".to_string(),
+        }];
+
+        let json = diag.to_json_with_offsets(|offset| offset / 2);
+        assert_eq!(json.span, JsonSpan { start: 2, end: 4 });
+        assert_eq!(json.related[0].span, JsonSpan { start: 0, end: 1 });
+    }
+
+    #[test]
+    fn test_utf16_index_counts_surrogate_pairs() {
+        // 'é' is 2 bytes / 1 unit, '😀' is 4 bytes / 2 units.
+        let index = Utf16Index::new("é😀x");
+        assert_eq!(index.to_utf16(0), 0);
+        assert_eq!(index.to_utf16(2), 1);
+        assert_eq!(index.to_utf16(6), 3);
+        assert_eq!(index.to_utf16(7), 4);
+        // Offsets past the end clamp to the end
+        assert_eq!(index.to_utf16(100), 4);
+
+        let diag = diagnostic(Span::new(2, 7), "here");
+        let json = index.diagnostic_to_json(&diag);
+        assert_eq!(json.span, JsonSpan { start: 1, end: 4 });
+    }
+}
@@ -1,2 +1,4 @@
 pub mod context;
+pub mod json;
+pub mod render;
 // Future: pub mod collection;
@@ -0,0 +1,436 @@
+//! Plain-text diagnostic rendering that works without `std`.
+//!
+//! Produces the same snippet+caret layout as the `ariadne`-based renderer in
+//! the top-level `melbi` crate (without colors), so embedders on bare-metal or
+//! wasm targets can still show good error messages. Output is written to any
+//! [`core::fmt::Write`] sink.
+
+use core::fmt::{self, Write};
+
+use crate::api::{Diagnostic, Error, Severity};
+use crate::parser::Span;
+use crate::{String, Vec};
+
+/// Character set for rendering error messages.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CharSet {
+    /// Use Unicode characters for rich visual output.
+    #[default]
+    Unicode,
+    /// Use ASCII-only characters for compatibility.
+    Ascii,
+}
+
+/// Box-drawing glyphs for a given [`CharSet`].
+struct Glyphs {
+    top_left: &'static str,
+    horizontal: char,
+    vertical: char,
+    skipped: char,
+    bottom_right: char,
+    underline: char,
+    underbar: char,
+    arrow_corner: char,
+}
+
+impl Glyphs {
+    const UNICODE: Glyphs = Glyphs {
+        top_left: "╭─[",
+        horizontal: '─',
+        vertical: '│',
+        skipped: '┆',
+        bottom_right: '╯',
+        underline: '─',
+        underbar: '┬',
+        arrow_corner: '╰',
+    };
+
+    const ASCII: Glyphs = Glyphs {
+        top_left: ",-[",
+        horizontal: '-',
+        vertical: '|',
+        skipped: ':',
+        bottom_right: '\'',
+        underline: '^',
+        underbar: '|',
+        arrow_corner: '`',
+    };
+
+    fn for_charset(charset: CharSet) -> &'static Glyphs {
+        match charset {
+            CharSet::Unicode => &Glyphs::UNICODE,
+            CharSet::Ascii => &Glyphs::ASCII,
+        }
+    }
+}
+
+/// A label resolved to a (line, column) position within the source.
+///
+/// Columns are counted in characters, not bytes, so multibyte source lines
+/// stay aligned with their carets.
+struct Label<'a> {
+    line: usize,
+    start: usize,
+    end: usize,
+    message: &'a str,
+}
+
+impl Label<'_> {
+    /// Column where the label's arrow attaches (middle of the span).
+    fn anchor(&self) -> usize {
+        (self.start + self.end) / 2
+    }
+}
+
+/// Render an error to `out` using the plain-text renderer.
+///
+/// Mirrors `melbi::render_error_to` with colors disabled: compilation and
+/// runtime errors render their diagnostics with source snippets, while
+/// resource and API errors render as a single line.
+pub fn render_error(
+    out: &mut dyn Write,
+    error: &Error,
+    filename: Option<&str>,
+    charset: CharSet,
+) -> fmt::Result {
+    let filename = error.filename().or(filename).unwrap_or("<unknown>");
+
+    match error {
+        Error::Compilation {
+            diagnostics,
+            source,
+            ..
+        } => {
+            for diagnostic in diagnostics {
+                render_diagnostic(out, source, diagnostic, filename, charset)?;
+            }
+            Ok(())
+        }
+        Error::Runtime {
+            diagnostic, source, ..
+        } => render_diagnostic(out, source, diagnostic, filename, charset),
+        Error::ResourceExceeded(msg) => writeln!(out, "Resource limit exceeded: {}", msg),
+        Error::Api(msg) => writeln!(out, "API error: {}", msg),
+        Error::RuntimeMismatch { .. } => writeln!(out, "{}", error),
+    }
+}
+
+/// Render a single diagnostic against `source` to `out`.
+pub fn render_diagnostic(
+    out: &mut dyn Write,
+    source: &str,
+    diagnostic: &Diagnostic,
+    filename: &str,
+    charset: CharSet,
+) -> fmt::Result {
+    let glyphs = Glyphs::for_charset(charset);
+    let lines: Vec<&str> = source.split('\n').collect();
+
+    let mut labels = Vec::with_capacity(1 + diagnostic.related.len());
+    labels.push(resolve_label(&lines, &diagnostic.span, &diagnostic.message));
+    for related in &diagnostic.related {
+        labels.push(resolve_label(&lines, &related.span, &related.message));
+    }
+
+    // Header: `[CODE] Kind: message`
+    if let Some(code) = &diagnostic.code {
+        write!(out, "[{}] ", code)?;
+    }
+    let kind = match diagnostic.severity {
+        Severity::Error => "Error",
+        Severity::Warning => "Warning",
+        Severity::Info => "Advice",
+    };
+    writeln!(out, "{}: {}", kind, diagnostic.message)?;
+
+    let mut line_numbers: Vec<usize> = labels.iter().map(|label| label.line).collect();
+    line_numbers.sort_unstable();
+    line_numbers.dedup();
+
+    let number_width = digit_count(line_numbers.last().copied().unwrap_or(0) + 1);
+    let margin = number_width + 2;
+
+    // Location line: `╭─[ file:line:col ]`, pointing at the primary label.
+    let primary = &labels[0];
+    pad(out, margin)?;
+    writeln!(
+        out,
+        "{} {}:{}:{} ]",
+        glyphs.top_left,
+        filename,
+        primary.line + 1,
+        primary.start + 1
+    )?;
+    pad(out, margin)?;
+    writeln!(out, "{}", glyphs.vertical)?;
+
+    let mut previous_line: Option<usize> = None;
+    for &line in &line_numbers {
+        if previous_line.is_some_and(|previous| line > previous + 1) {
+            pad(out, margin)?;
+            writeln!(out, "{}", glyphs.skipped)?;
+        }
+        previous_line = Some(line);
+
+        writeln!(
+            out,
+            " {:>width$} {} {}",
+            line + 1,
+            glyphs.vertical,
+            lines[line],
+            width = number_width
+        )?;
+
+        let mut line_labels: Vec<&Label> = labels.iter().filter(|l| l.line == line).collect();
+        line_labels.sort_by_key(|label| label.anchor());
+        let row_width = line_labels.iter().map(|l| l.end).max().unwrap_or(0) + 2;
+
+        let mut row = String::new();
+        for column in 0..row_width {
+            let underbar = line_labels.iter().any(|l| l.anchor() == column);
+            let underline = line_labels
+                .iter()
+                .any(|l| l.start <= column && column < l.end);
+            row.push(if underbar {
+                if line_labels
+                    .iter()
+                    .any(|l| l.anchor() == column && l.start == l.end)
+                {
+                    glyphs.vertical
+                } else {
+                    glyphs.underbar
+                }
+            } else if underline {
+                glyphs.underline
+            } else {
+                ' '
+            });
+        }
+        write_margin_line(out, margin, glyphs, &row)?;
+
+        // Arrows are drawn right-to-left so that no horizontal line crosses
+        // the vertical line of a label that is still pending.
+        for (index, label) in line_labels.iter().enumerate().rev() {
+            let mut row = String::new();
+            for column in 0..row_width {
+                if column == label.anchor() {
+                    row.push(glyphs.arrow_corner);
+                } else if column > label.anchor() {
+                    row.push(glyphs.horizontal);
+                } else if line_labels[..index].iter().any(|l| l.anchor() == column) {
+                    row.push(glyphs.vertical);
+                } else {
+                    row.push(' ');
+                }
+            }
+            row.push(' ');
+            row.push_str(label.message);
+            write_margin_line(out, margin, glyphs, &row)?;
+        }
+    }
+
+    for help in &diagnostic.help {
+        write_margin_line(out, margin, glyphs, "")?;
+        write_margin_line(out, margin, glyphs, &crate::format!("Help: {}", help))?;
+    }
+
+    for _ in 0..margin {
+        out.write_char(glyphs.horizontal)?;
+    }
+    writeln!(out, "{}", glyphs.bottom_right)
+}
+
+/// Convenience wrapper around [`render_error`] that returns a `String`.
+pub fn render_error_to_string(error: &Error, filename: Option<&str>, charset: CharSet) -> String {
+    let mut out = String::new();
+    render_error(&mut out, error, filename, charset).expect("writing to a String cannot fail");
+    out
+}
+
+fn resolve_label<'a>(lines: &[&str], span: &Span, message: &'a str) -> Label<'a> {
+    let (line, start) = line_column(lines, span.0.start);
+    let (end_line, end) = line_column(lines, span.0.end.max(span.0.start));
+    // Multi-line spans are clamped to the end of their first line.
+    let end = if end_line == line {
+        end
+    } else {
+        lines[line].chars().count()
+    };
+    Label {
+        line,
+        start,
+        end,
+        message,
+    }
+}
+
+/// Map a byte offset to a zero-based (line, character column) pair.
+fn line_column(lines: &[&str], offset: usize) -> (usize, usize) {
+    let mut line_start = 0;
+    for (index, line) in lines.iter().enumerate() {
+        let line_end = line_start + line.len();
+        if offset <= line_end || index + 1 == lines.len() {
+            let within = offset.saturating_sub(line_start).min(line.len());
+            let column = line
+                .char_indices()
+                .take_while(|(byte, _)| *byte < within)
+                .count();
+            return (index, column);
+        }
+        line_start = line_end + 1;
+    }
+    (0, 0)
+}
+
+fn write_margin_line(
+    out: &mut dyn Write,
+    margin: usize,
+    glyphs: &Glyphs,
+    text: &str,
+) -> fmt::Result {
+    pad(out, margin)?;
+    writeln!(out, "{} {}", glyphs.vertical, text)
+}
+
+fn pad(out: &mut dyn Write, width: usize) -> fmt::Result {
+    write!(out, "{:width$}", "", width = width)
+}
+
+fn digit_count(mut n: usize) -> usize {
+    let mut digits = 1;
+    while n >= 10 {
+        n /= 10;
+        digits += 1;
+    }
+    digits
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::RelatedInfo;
+    use crate::{ToString, vec};
+
+    fn diagnostic(span: Span, message: &str) -> Diagnostic {
+        Diagnostic {
+            severity: Severity::Error,
+            message: message.to_string(),
+            span,
+            related: vec![],
+            help: vec![],
+            code: None,
+        }
+    }
+
+    fn render(source: &str, diagnostic: &Diagnostic, charset: CharSet) -> String {
+        let mut out = String::new();
+        render_diagnostic(&mut out, source, diagnostic, "test.melbi", charset).unwrap();
+        out
+    }
+
+    #[test]
+    fn test_render_type_error_unicode() {
+        let mut diag = diagnostic(Span::new(4, 8), "Type mismatch: expected Int, found Bool");
+        diag.code = Some("E001".to_string());
+        diag.help = vec!["Types must match in this context".to_string()];
+
+        assert_eq!(
+            render("1 + true", &diag, CharSet::Unicode),
+            "[E001] Error: Type mismatch: expected Int, found Bool\n   \
+             ╭─[ test.melbi:1:5 ]\n   \
+             │\n \
+             1 │ 1 + true\n   \
+             │     ──┬─  \n   \
+             │       ╰─── Type mismatch: expected Int, found Bool\n   \
+             │ \n   \
+             │ Help: Types must match in this context\n\
+             ───╯\n"
+        );
+    }
+
+    #[test]
+    fn test_render_type_error_ascii() {
+        let mut diag = diagnostic(Span::new(4, 8), "Type mismatch: expected Int, found Bool");
+        diag.code = Some("E001".to_string());
+        diag.help = vec!["Types must match in this context".to_string()];
+
+        assert_eq!(
+            render("1 + true", &diag, CharSet::Ascii),
+            "[E001] Error: Type mismatch: expected Int, found Bool\n   \
+             ,-[ test.melbi:1:5 ]\n   \
+             |\n \
+             1 | 1 + true\n   \
+             |     ^^|^  \n   \
+             |       `--- Type mismatch: expected Int, found Bool\n   \
+             | \n   \
+             | Help: Types must match in this context\n\
+             ---'\n"
+        );
+    }
+
+    #[test]
+    fn test_render_empty_span_at_end_of_input() {
+        let diag = diagnostic(Span::new(7, 7), "Expected expression");
+
+        assert_eq!(
+            render("{ x = 1", &diag, CharSet::Unicode),
+            "Error: Expected expression\n   \
+             ╭─[ test.melbi:1:8 ]\n   \
+             │\n \
+             1 │ { x = 1\n   \
+             │        │ \n   \
+             │        ╰─ Expected expression\n\
+             ───╯\n"
+        );
+    }
+
+    #[test]
+    fn test_render_multiline_source_and_related() {
+        let source = "x where {\n  x = 1,\n  y = true,\n}";
+        let mut diag = diagnostic(Span::new(25, 29), "found Bool");
+        diag.related = vec![RelatedInfo {
+            span: Span::new(12, 13),
+            message: "x defined here".to_string(),
+        }];
+
+        assert_eq!(
+            render(source, &diag, CharSet::Ascii),
+            "Error: found Bool\n   \
+             ,-[ test.melbi:3:7 ]\n   \
+             |\n \
+             2 |   x = 1,\n   \
+             |   |  \n   \
+             |   `-- x defined here\n \
+             3 |   y = true,\n   \
+             |       ^^|^  \n   \
+             |         `--- found Bool\n\
+             ---'\n"
+        );
+    }
+
+    #[test]
+    fn test_render_columns_count_characters() {
+        // The emoji is 4 bytes but a single column.
+        let source = "\"😀\" + 1";
+        let diag = diagnostic(Span::new(0, 6), "expected Int");
+        let output = render(source, &diag, CharSet::Ascii);
+        assert!(output.contains("test.melbi:1:1"));
+        assert!(output.contains(" | ^|^  \n"));
+    }
+
+    #[test]
+    fn test_render_non_diagnostic_errors() {
+        assert_eq!(
+            render_error_to_string(
+                &Error::ResourceExceeded("stack".to_string()),
+                None,
+                CharSet::Unicode
+            ),
+            "Resource limit exceeded: stack\n"
+        );
+        assert_eq!(
+            render_error_to_string(&Error::Api("bad".to_string()), None, CharSet::Unicode),
+            "API error: bad\n"
+        );
+    }
+}
@@ -70,6 +70,12 @@ pub enum RuntimeError {
     /// TODO(effects): When effect system is implemented, mark fallible casts
     /// with `!` effect and make them catchable with `otherwise`.
     CastError { message: String },
+
+    /// A function was called with an argument outside its domain (e.g., a zero step).
+    InvalidArgument { message: String },
+
+    /// Decimal result does not fit the fixed-point representation.
+    DecimalOverflow {},
 }
 
 /// Resource limit exceeded errors that cannot be caught.
@@ -81,6 +87,10 @@ pub enum RuntimeError {
 pub enum ResourceExceededError {
     /// Evaluation recursion depth exceeded.
     StackOverflow { depth: usize, max_depth: usize },
+    /// The run used up its fuel budget.
+    OutOfFuel { max_fuel: u64 },
+    /// The function call hook rejected a call.
+    CallRejected { function: String, reason: String },
     // Future resource limits:
     // MemoryExceeded { bytes: usize, max_bytes: usize },
     // TimeExceeded { millis: u64, max_millis: u64 },
@@ -133,6 +143,16 @@ impl ExecutionError {
                 Some("R004"),
                 vec!["Verify the value can be safely converted to the target type".to_string()],
             ),
+            ExecutionErrorKind::Runtime(RuntimeError::InvalidArgument { message }) => (
+                format!("Invalid argument: {}", message),
+                Some("R008"),
+                vec!["Check the function's documentation for valid argument values".to_string()],
+            ),
+            ExecutionErrorKind::Runtime(RuntimeError::DecimalOverflow {}) => (
+                String::from("Decimal overflow"),
+                Some("R009"),
+                vec!["The result exceeds the range or precision of Decimal".to_string()],
+            ),
             ExecutionErrorKind::ResourceExceeded(ResourceExceededError::StackOverflow {
                 depth,
                 max_depth,
@@ -144,6 +164,21 @@ impl ExecutionError {
                 Some("R005"),
                 vec!["Reduce recursion depth or increase stack limit".to_string()],
             ),
+            ExecutionErrorKind::ResourceExceeded(ResourceExceededError::OutOfFuel { max_fuel }) => {
+                (
+                    format!("Out of fuel: used more than {}", max_fuel),
+                    Some("R010"),
+                    vec!["Reduce the work done by the expression or increase max_fuel".to_string()],
+                )
+            }
+            ExecutionErrorKind::ResourceExceeded(ResourceExceededError::CallRejected {
+                function,
+                reason,
+            }) => (
+                format!("Call to {} rejected: {}", function, reason),
+                Some("R011"),
+                vec!["The host does not allow this call".to_string()],
+            ),
             ExecutionErrorKind::Internal(InternalError::InvariantViolation { message }) => (
                 format!("Internal error: {}", message),
                 Some("R006"),
@@ -196,6 +231,12 @@ impl fmt::Display for RuntimeError {
             RuntimeError::CastError { message } => {
                 write!(f, "Cast error: {}", message)
             }
+            RuntimeError::InvalidArgument { message } => {
+                write!(f, "Invalid argument: {}", message)
+            }
+            RuntimeError::DecimalOverflow {} => {
+                write!(f, "Decimal overflow")
+            }
         }
     }
 }
@@ -210,6 +251,12 @@ impl fmt::Display for ResourceExceededError {
                     depth, max_depth
                 )
             }
+            ResourceExceededError::OutOfFuel { max_fuel } => {
+                write!(f, "Evaluation ran out of fuel: used more than {}", max_fuel)
+            }
+            ResourceExceededError::CallRejected { function, reason } => {
+                write!(f, "Call to {} rejected: {}", function, reason)
+            }
         }
     }
 }
@@ -2,18 +2,25 @@
 
 use alloc::string::ToString;
 use bumpalo::Bump;
+use core::cell::OnceCell;
 
 use crate::{
     Vec,
-    analyzer::typed_expr::{Expr, ExprInner, TypedExpr, TypedPattern},
+    analyzer::typed_expr::{
+        Expr, ExprInner, TypedExpr, TypedMatchArm, TypedPattern, recursive_group_len,
+    },
     evaluator::{
-        EvaluatorOptions, ExecutionError, ExecutionErrorKind, InternalError::*,
-        ResourceExceededError::*, RuntimeError::*,
+        EvaluatorOptions, ExecutionError, ExecutionErrorKind, InternalError::*, RuntimeError::*,
     },
-    parser::{BoolOp, ComparisonOp},
+    parser::{BinaryOp, BoolOp, ComparisonOp, UnaryOp},
     scope_stack::{self, ScopeStack},
+    stdlib::builtins,
     types::{Type, manager::TypeManager, unification::Unification},
-    values::{EvalLambda, dynamic::Value, function::FfiContext},
+    values::{
+        EvalLambda,
+        dynamic::Value,
+        function::{CallDepth, FfiContext, Fuel},
+    },
 };
 
 /// Evaluator for type-checked expressions.
@@ -24,7 +31,10 @@ pub struct Evaluator<'types, 'arena> {
     /// The typed expression being evaluated (used for error context).
     expr: &'arena TypedExpr<'types, 'arena>,
     scope_stack: ScopeStack<'arena, Value<'types, 'arena>>,
-    depth: usize,
+    /// Number of lambda calls the current body is nested in.
+    call_depth: usize,
+    /// Fuel budget, charged one unit per evaluated expression.
+    fuel: Option<&'arena Fuel>,
     /// Type unification for monomorphizing polymorphic lambda bodies.
     /// When evaluating a polymorphic lambda, this contains the unification
     /// of the lambda's parameter types with the concrete argument types.
@@ -61,7 +71,8 @@ impl<'types, 'arena> Evaluator<'types, 'arena> {
             type_manager,
             expr,
             scope_stack,
-            depth: 0,
+            call_depth: 0,
+            fuel: None,
             monomorphism: None,
         }
     }
@@ -85,6 +96,17 @@ impl<'types, 'arena> Evaluator<'types, 'arena> {
         self.monomorphism = Some(unification);
     }
 
+    /// Set the number of lambda calls the evaluated body is nested in.
+    pub fn set_call_depth(&mut self, call_depth: usize) {
+        self.call_depth = call_depth;
+    }
+
+    /// Set the fuel budget that evaluation draws from, such as the budget of
+    /// the run that calls a lambda.
+    pub fn set_fuel(&mut self, fuel: Option<&'arena Fuel>) {
+        self.fuel = fuel;
+    }
+
     /// Resolve a type by applying monomorphization if present.
     /// This replaces type variables with concrete types when evaluating
     /// polymorphic lambda bodies.
@@ -115,6 +137,68 @@ impl<'types, 'arena> Evaluator<'types, 'arena> {
         ExecutionError { kind, span, source }
     }
 
+    /// Create a lambda capturing the values of its free variables from the
+    /// current scope.
+    fn new_lambda(
+        &self,
+        ty: &'types Type<'types>,
+        params: &'arena [&'arena str],
+        body: &'arena Expr<'types, 'arena>,
+        captures: &'arena [&'arena str],
+    ) -> EvalLambda<'types, 'arena> {
+        // Capture the values of free variables from the current scope
+        let mut capture_values = Vec::new();
+        for &name in captures.iter() {
+            if let Some(value) = self.scope_stack.lookup(name) {
+                // TODO: Filter out globals (they should be accessed during call, not captured)
+                capture_values.push((name, *value));
+            }
+        }
+
+        let captures_slice = self.arena.alloc_slice_copy(&capture_values);
+
+        // Construct a TypedExpr for the lambda body so it can report errors with spans
+        // We use the same annotation source as the parent expression
+        let body_typed = self.arena.alloc(TypedExpr {
+            expr: body,
+            ann: self.expr.ann,
+            // Evaluator doesn't need instantiation info (just for error reporting)
+            lambda_instantiations: hashbrown::HashMap::new_in(self.arena),
+        });
+
+        EvalLambda::new(ty, params, body_typed, captures_slice)
+    }
+
+    /// Bind `where` lambdas that use each other.
+    ///
+    /// The lambdas cannot capture each other's values before they exist, so
+    /// they share the group's bindings, which are filled in once all are made.
+    fn bind_recursive_lambdas(&mut self, group: &[(&'arena str, &'arena Expr<'types, 'arena>)]) {
+        let group_bindings = self.arena.alloc(OnceCell::new());
+        let mut values = Vec::new();
+        for &(name, value_expr) in group {
+            let ExprInner::Lambda {
+                params,
+                body,
+                captures,
+            } = &value_expr.1
+            else {
+                unreachable!("Recursive bindings are lambdas - analyzer should have caught this")
+            };
+            let lambda = self
+                .new_lambda(value_expr.0, params, body, captures)
+                .with_recursive_bindings(group_bindings);
+            let value =
+                Value::function(self.arena, lambda).expect("Type checker guarantees Function type");
+            self.scope_stack
+                .bind_in_current(name, value)
+                .expect("Failed to bind in where - analyzer should have caught this");
+            values.push((name, value));
+        }
+        values.sort_by_key(|(name, _)| *name);
+        let _ = group_bindings.set(&*self.arena.alloc_slice_copy(&values));
+    }
+
     /// Evaluate a type-checked expression.
     pub fn eval(&mut self) -> Result<Value<'types, 'arena>, ExecutionError> {
         self.eval_expr(self.expr.expr)
@@ -125,26 +209,20 @@ impl<'types, 'arena> Evaluator<'types, 'arena> {
         &mut self,
         expr: &'arena Expr<'types, 'arena>,
     ) -> Result<Value<'types, 'arena>, ExecutionError> {
-        // Check depth before recursing
-        if self.depth >= self.options.max_depth {
-            return self.error(
-                expr,
-                StackOverflow {
-                    depth: self.depth,
-                    max_depth: self.options.max_depth,
-                }
-                .into(),
-            );
+        if let Some(fuel) = self.fuel
+            && let Err(error) = fuel.consume(1)
+        {
+            return self.error(expr, error.into());
         }
 
-        self.depth += 1;
-        let result = self.eval_expr_inner(expr);
-        self.depth -= 1;
-
-        result
+        self.eval_expr_inner(expr)
     }
 
-    /// Inner evaluation logic (no depth tracking).
+    /// Inner evaluation logic (no fuel tracking).
+    ///
+    /// Most arms live in their own `#[inline(never)]` methods, so every
+    /// nested expression takes a small native stack frame, not one sized for
+    /// the largest arm.
     fn eval_expr_inner(
         &mut self,
         expr: &'arena Expr<'types, 'arena>,
@@ -155,591 +233,815 @@ impl<'types, 'arena> Evaluator<'types, 'arena> {
                 Ok(*value)
             }
 
-            ExprInner::Ident(name) => {
-                // Look up variable in scope stack
-                match self.scope_stack.lookup(name) {
-                    Some(value) => Ok(*value),
-                    None => {
-                        // This should never happen if the expression was type-checked
-                        debug_assert!(
-                            false,
-                            "Undefined variable '{}' - analyzer should have caught this",
-                            name
-                        );
-                        // In release mode, we can't panic, so return a dummy value
-                        // This is safe because the expression is guaranteed to be well-typed
-                        unreachable!("Undefined variable '{}' in type-checked expression", name)
-                    }
+            ExprInner::Ident(name) => self.eval_ident(expr, name),
+
+            ExprInner::Binary { op, left, right } => self.eval_binary(expr, *op, left, right),
+
+            ExprInner::Boolean { op, left, right } => self.eval_boolean(*op, left, right),
+
+            ExprInner::Comparison { op, left, right } => self.eval_comparison(*op, left, right),
+
+            ExprInner::Where { expr, bindings } => self.eval_where(expr, bindings),
+
+            ExprInner::Record { fields } => self.eval_record(expr, fields),
+
+            ExprInner::Field { value, field } => self.eval_field(value, field),
+
+            ExprInner::Unary { op, expr: operand } => self.eval_unary(expr, *op, operand),
+
+            ExprInner::If {
+                cond,
+                then_branch,
+                else_branch,
+            } => {
+                // Evaluate the condition
+                let cond_val = self.eval_expr(cond)?;
+                let cond_bool = cond_val.as_bool().expect("Type-checked as Bool");
+
+                // Evaluate the appropriate branch (lazy evaluation)
+                if cond_bool {
+                    self.eval_expr(then_branch)
+                } else {
+                    self.eval_expr(else_branch)
                 }
             }
 
-            ExprInner::Binary { op, left, right } => {
-                use crate::types::Type;
+            ExprInner::Array { elements } => self.eval_array(expr, elements),
 
-                // Recursively evaluate operands (direct call to eval_expr, not eval)
-                let left_val = self.eval_expr(left)?;
-                let right_val = self.eval_expr(right)?;
+            ExprInner::Index { value, index } => self.eval_index(expr, value, index),
 
-                // Dispatch based on type (we know both operands have the same type after type-checking)
-                match left_val.ty {
-                    Type::Int => {
-                        let l = left_val.as_int().expect("Type-checked as Int");
-                        let r = right_val.as_int().expect("Type-checked as Int");
-                        let result = super::operators::eval_binary_int(*op, l, r)
-                            .map_err(|e| self.add_error_context(expr, e))?;
-                        Ok(Value::int(self.type_manager, result))
-                    }
-                    Type::Float => {
-                        let l = left_val.as_float().expect("Type-checked as Float");
-                        let r = right_val.as_float().expect("Type-checked as Float");
-                        let result = super::operators::eval_binary_float(*op, l, r);
-                        Ok(Value::float(self.type_manager, result))
-                    }
-                    _ => {
-                        // Type checker should have caught this
-                        debug_assert!(false, "Binary operator on non-numeric type");
-                        unreachable!("Binary operator on invalid type in type-checked expression")
-                    }
+            ExprInner::FormatStr { strs, exprs } => self.eval_format_str(strs, exprs),
+
+            ExprInner::Otherwise { primary, fallback } => self.eval_otherwise(primary, fallback),
+
+            ExprInner::Coalesce { option, default } => {
+                let value = self.eval_expr(option)?;
+                match value.as_option().expect("Type-checked as Option") {
+                    Some(inner) => Ok(inner),
+                    None => self.eval_expr(default),
                 }
             }
 
-            ExprInner::Boolean { op, left, right } => {
-                // Evaluate left operand
-                let left_val = self.eval_expr(left)?;
-                let left_bool = left_val.as_bool().expect("Type-checked as Bool");
+            ExprInner::Option { inner } => self.eval_option(expr, *inner),
 
-                // Short-circuit evaluation
-                match op {
-                    BoolOp::And => {
-                        // If left is false, return false without evaluating right
-                        if !left_bool {
-                            return Ok(Value::bool(self.type_manager, false));
-                        }
-                        // Left is true, return right's value
-                        let right_val = self.eval_expr(right)?;
-                        let right_bool = right_val.as_bool().expect("Type-checked as Bool");
-                        Ok(Value::bool(self.type_manager, right_bool))
-                    }
-                    BoolOp::Or => {
-                        // If left is true, return true without evaluating right
-                        if left_bool {
-                            return Ok(Value::bool(self.type_manager, true));
-                        }
-                        // Left is false, return right's value
-                        let right_val = self.eval_expr(right)?;
-                        let right_bool = right_val.as_bool().expect("Type-checked as Bool");
-                        Ok(Value::bool(self.type_manager, right_bool))
-                    }
-                }
+            ExprInner::Cast { expr: inner_expr } => self.eval_cast(expr, inner_expr),
+            ExprInner::Call { callable, args } => self.eval_call(expr, callable, args),
+            ExprInner::Lambda {
+                params,
+                body,
+                captures,
+            } => {
+                let lambda = self.new_lambda(expr.0, params, body, captures);
+
+                // Value::function returns Result, but should never fail because
+                // the type checker guarantees expr.0 is a Function type
+                let fun = Value::function(self.arena, lambda)
+                    .expect("Type checker guarantees Function type");
+                Ok(fun)
+            }
+            ExprInner::Map { elements } => self.eval_map(expr, elements),
+
+            ExprInner::Match {
+                expr: match_expr,
+                arms,
+            } => self.eval_match(match_expr, arms),
+        }
+    }
+
+    /// Look up a variable, falling back to the builtins.
+    #[inline(never)]
+    fn eval_ident(
+        &mut self,
+        expr: &'arena Expr<'types, 'arena>,
+        name: &'arena str,
+    ) -> Result<Value<'types, 'arena>, ExecutionError> {
+        // Look up variable in scope stack, then builtins
+        if let Some(value) = self.scope_stack.lookup(name) {
+            return Ok(*value);
+        }
+        match builtins::lookup(name) {
+            Some(builtin) => Ok(builtin.value(self.arena, self.resolve_type(expr.0))),
+            None => {
+                // This should never happen if the expression was type-checked
+                debug_assert!(
+                    false,
+                    "Undefined variable '{}' - analyzer should have caught this",
+                    name
+                );
+                // In release mode, we can't panic, so return a dummy value
+                // This is safe because the expression is guaranteed to be well-typed
+                unreachable!("Undefined variable '{}' in type-checked expression", name)
             }
+        }
+    }
 
-            ExprInner::Comparison { op, left, right } => {
-                use crate::types::Type;
+    /// Evaluate an arithmetic operation.
+    #[inline(never)]
+    fn eval_binary(
+        &mut self,
+        expr: &'arena Expr<'types, 'arena>,
+        op: BinaryOp,
+        left: &'arena Expr<'types, 'arena>,
+        right: &'arena Expr<'types, 'arena>,
+    ) -> Result<Value<'types, 'arena>, ExecutionError> {
+        use crate::types::Type;
+
+        // Recursively evaluate operands (direct call to eval_expr, not eval)
+        let left_val = self.eval_expr(left)?;
+        let right_val = self.eval_expr(right)?;
+
+        // Dispatch based on type (we know both operands have the same type after type-checking)
+        match left_val.ty {
+            Type::Int => {
+                let l = left_val.as_int().expect("Type-checked as Int");
+                let r = right_val.as_int().expect("Type-checked as Int");
+                let result = super::operators::eval_binary_int(op, l, r)
+                    .map_err(|e| self.add_error_context(expr, e))?;
+                Ok(Value::int(self.type_manager, result))
+            }
+            Type::Float => {
+                let l = left_val.as_float().expect("Type-checked as Float");
+                let r = right_val.as_float().expect("Type-checked as Float");
+                let result = super::operators::eval_binary_float(op, l, r);
+                Ok(Value::float(self.type_manager, result))
+            }
+            #[cfg(feature = "bigint")]
+            Type::BigInt => {
+                let l = left_val.as_bigint().expect("Type-checked as BigInt");
+                let r = right_val.as_bigint().expect("Type-checked as BigInt");
+                let result = super::operators::eval_binary_bigint(op, &l, &r)
+                    .map_err(|e| self.add_error_context(expr, e))?;
+                Ok(Value::bigint(self.arena, self.type_manager, &result))
+            }
+            #[cfg(feature = "decimal")]
+            Type::Decimal => {
+                let l = left_val.as_decimal().expect("Type-checked as Decimal");
+                let r = right_val.as_decimal().expect("Type-checked as Decimal");
+                let result = super::operators::eval_binary_decimal(op, l, r)
+                    .map_err(|e| self.add_error_context(expr, e))?;
+                Ok(Value::decimal(self.arena, self.type_manager, result))
+            }
+            _ => {
+                // Type checker should have caught this
+                debug_assert!(false, "Binary operator on non-numeric type");
+                unreachable!("Binary operator on invalid type in type-checked expression")
+            }
+        }
+    }
 
-                // Recursively evaluate operands
-                let left_val = self.eval_expr(left)?;
+    /// Evaluate a short-circuiting boolean operation.
+    #[inline(never)]
+    fn eval_boolean(
+        &mut self,
+        op: BoolOp,
+        left: &'arena Expr<'types, 'arena>,
+        right: &'arena Expr<'types, 'arena>,
+    ) -> Result<Value<'types, 'arena>, ExecutionError> {
+        // Evaluate left operand
+        let left_val = self.eval_expr(left)?;
+        let left_bool = left_val.as_bool().expect("Type-checked as Bool");
+
+        // Short-circuit evaluation
+        match op {
+            BoolOp::And => {
+                // If left is false, return false without evaluating right
+                if !left_bool {
+                    return Ok(Value::bool(self.type_manager, false));
+                }
+                // Left is true, return right's value
+                let right_val = self.eval_expr(right)?;
+                let right_bool = right_val.as_bool().expect("Type-checked as Bool");
+                Ok(Value::bool(self.type_manager, right_bool))
+            }
+            BoolOp::Or => {
+                // If left is true, return true without evaluating right
+                if left_bool {
+                    return Ok(Value::bool(self.type_manager, true));
+                }
+                // Left is false, return right's value
                 let right_val = self.eval_expr(right)?;
+                let right_bool = right_val.as_bool().expect("Type-checked as Bool");
+                Ok(Value::bool(self.type_manager, right_bool))
+            }
+        }
+    }
 
-                // For containment operations, dispatch based on haystack (right) type
-                // For other comparisons, dispatch based on left type
-                let result = if matches!(op, ComparisonOp::In | ComparisonOp::NotIn) {
-                    // Containment: needle in haystack
-                    match right_val.ty {
-                        Type::Str => {
-                            let needle = left_val.as_str().expect("Type-checked as Str");
-                            let haystack = right_val.as_str().expect("Type-checked as Str");
-                            super::operators::eval_comparison_string(*op, needle, haystack)
-                        }
-                        Type::Bytes => {
-                            let needle = left_val.as_bytes().expect("Type-checked as Bytes");
-                            let haystack = right_val.as_bytes().expect("Type-checked as Bytes");
-                            super::operators::eval_comparison_bytes(*op, needle, haystack)
-                        }
-                        Type::Array(_) => {
-                            let needle = left_val;
-                            let haystack = right_val.as_array().expect("Type-checked as Array");
-                            let found = haystack.iter().any(|elem| elem == needle);
-                            match op {
-                                ComparisonOp::In => found,
-                                ComparisonOp::NotIn => !found,
-                                _ => unreachable!(),
-                            }
-                        }
-                        Type::Map(_, _) => {
-                            let key = left_val;
-                            let haystack = right_val.as_map().expect("Type-checked as Map");
-                            let found = haystack.get(&key).is_some();
-                            match op {
-                                ComparisonOp::In => found,
-                                ComparisonOp::NotIn => !found,
-                                _ => unreachable!(),
-                            }
-                        }
+    /// Evaluate a comparison or containment test.
+    #[inline(never)]
+    fn eval_comparison(
+        &mut self,
+        op: ComparisonOp,
+        left: &'arena Expr<'types, 'arena>,
+        right: &'arena Expr<'types, 'arena>,
+    ) -> Result<Value<'types, 'arena>, ExecutionError> {
+        use crate::types::Type;
+
+        // Recursively evaluate operands
+        let left_val = self.eval_expr(left)?;
+        let right_val = self.eval_expr(right)?;
+
+        // For containment operations, dispatch based on haystack (right) type
+        // For other comparisons, dispatch based on left type
+        let result = if matches!(op, ComparisonOp::In | ComparisonOp::NotIn) {
+            // Containment: needle in haystack
+            match right_val.ty {
+                Type::Str => {
+                    let needle = left_val.as_str().expect("Type-checked as Str");
+                    let haystack = right_val.as_str().expect("Type-checked as Str");
+                    super::operators::eval_comparison_string(op, needle, haystack)
+                }
+                Type::Bytes => {
+                    let needle = left_val.as_bytes().expect("Type-checked as Bytes");
+                    let haystack = right_val.as_bytes().expect("Type-checked as Bytes");
+                    super::operators::eval_comparison_bytes(op, needle, haystack)
+                }
+                Type::Array(_) => {
+                    let needle = left_val;
+                    let haystack = right_val.as_array().expect("Type-checked as Array");
+                    let found = haystack.iter().any(|elem| elem == needle);
+                    match op {
+                        ComparisonOp::In => found,
+                        ComparisonOp::NotIn => !found,
+                        _ => unreachable!(),
+                    }
+                }
+                Type::Map(_, _) => {
+                    let key = left_val;
+                    let haystack = right_val.as_map().expect("Type-checked as Map");
+                    let found = haystack.get(&key).is_some();
+                    match op {
+                        ComparisonOp::In => found,
+                        ComparisonOp::NotIn => !found,
+                        _ => unreachable!(),
+                    }
+                }
+                _ => {
+                    debug_assert!(false, "Containment operation on non-containable type");
+                    unreachable!("Containment on invalid haystack type in type-checked expression")
+                }
+            }
+        } else {
+            // Regular comparison: dispatch based on left type
+            match left_val.ty {
+                Type::Int => {
+                    let l = left_val.as_int().expect("Type-checked as Int");
+                    let r = right_val.as_int().expect("Type-checked as Int");
+                    super::operators::eval_comparison_int(op, l, r)
+                }
+                Type::Float => {
+                    let l = left_val.as_float().expect("Type-checked as Float");
+                    let r = right_val.as_float().expect("Type-checked as Float");
+                    super::operators::eval_comparison_float(op, l, r)
+                }
+                Type::Bool => {
+                    let l = left_val.as_bool().expect("Type-checked as Bool");
+                    let r = right_val.as_bool().expect("Type-checked as Bool");
+                    super::operators::eval_comparison_bool(op, l, r)
+                }
+                Type::Str => {
+                    let l = left_val.as_str().expect("Type-checked as Str");
+                    let r = right_val.as_str().expect("Type-checked as Str");
+                    super::operators::eval_comparison_string(op, l, r)
+                }
+                Type::Bytes => {
+                    let l = left_val.as_bytes().expect("Type-checked as Bytes");
+                    let r = right_val.as_bytes().expect("Type-checked as Bytes");
+                    super::operators::eval_comparison_bytes(op, l, r)
+                }
+                #[cfg(feature = "bigint")]
+                Type::BigInt => {
+                    let l = left_val.as_bigint().expect("Type-checked as BigInt");
+                    let r = right_val.as_bigint().expect("Type-checked as BigInt");
+                    super::operators::eval_comparison_bigint(op, &l, &r)
+                }
+                #[cfg(feature = "decimal")]
+                Type::Decimal => {
+                    let l = left_val.as_decimal().expect("Type-checked as Decimal");
+                    let r = right_val.as_decimal().expect("Type-checked as Decimal");
+                    super::operators::eval_comparison_decimal(op, l, r)
+                }
+                _ => {
+                    // For other types, we only support equality operators
+                    match op {
+                        ComparisonOp::Eq => left_val == right_val,
+                        ComparisonOp::Neq => left_val != right_val,
                         _ => {
-                            debug_assert!(false, "Containment operation on non-containable type");
+                            // Type checker should have caught this
+                            debug_assert!(false, "Ordering comparison on non-orderable type");
                             unreachable!(
-                                "Containment on invalid haystack type in type-checked expression"
+                                "Ordering comparison on invalid type in type-checked expression"
                             )
                         }
                     }
-                } else {
-                    // Regular comparison: dispatch based on left type
-                    match left_val.ty {
-                        Type::Int => {
-                            let l = left_val.as_int().expect("Type-checked as Int");
-                            let r = right_val.as_int().expect("Type-checked as Int");
-                            super::operators::eval_comparison_int(*op, l, r)
-                        }
-                        Type::Float => {
-                            let l = left_val.as_float().expect("Type-checked as Float");
-                            let r = right_val.as_float().expect("Type-checked as Float");
-                            super::operators::eval_comparison_float(*op, l, r)
-                        }
-                        Type::Bool => {
-                            let l = left_val.as_bool().expect("Type-checked as Bool");
-                            let r = right_val.as_bool().expect("Type-checked as Bool");
-                            super::operators::eval_comparison_bool(*op, l, r)
-                        }
-                        Type::Str => {
-                            let l = left_val.as_str().expect("Type-checked as Str");
-                            let r = right_val.as_str().expect("Type-checked as Str");
-                            super::operators::eval_comparison_string(*op, l, r)
-                        }
-                        Type::Bytes => {
-                            let l = left_val.as_bytes().expect("Type-checked as Bytes");
-                            let r = right_val.as_bytes().expect("Type-checked as Bytes");
-                            super::operators::eval_comparison_bytes(*op, l, r)
-                        }
-                        _ => {
-                            // For other types, we only support equality operators
-                            match op {
-                                ComparisonOp::Eq => left_val == right_val,
-                                ComparisonOp::Neq => left_val != right_val,
-                                _ => {
-                                    // Type checker should have caught this
-                                    debug_assert!(
-                                        false,
-                                        "Ordering comparison on non-orderable type"
-                                    );
-                                    unreachable!(
-                                        "Ordering comparison on invalid type in type-checked expression"
-                                    )
-                                }
-                            }
-                        }
-                    }
-                };
-
-                Ok(Value::bool(self.type_manager, result))
-            }
-
-            ExprInner::Where { expr, bindings } => {
-                // Extract binding names
-                let names: crate::Vec<&'arena str> =
-                    bindings.iter().map(|(name, _)| *name).collect();
-
-                // Push incomplete scope with all binding names
-                // This allows sequential binding (later bindings can reference earlier ones)
-                self.scope_stack.push(
-                    scope_stack::IncompleteScope::new(self.arena, &names)
-                        .expect("Duplicate binding in where - analyzer should have caught this"),
-                );
-
-                // Evaluate and bind each expression sequentially
-                for (name, value_expr) in bindings.iter() {
-                    let value = self.eval_expr(value_expr)?;
-                    self.scope_stack
-                        .bind_in_current(name, value)
-                        .expect("Failed to bind in where - analyzer should have caught this");
                 }
+            }
+        };
 
-                // Evaluate the body expression (has access to all bindings)
-                let result = self.eval_expr(expr)?;
+        Ok(Value::bool(self.type_manager, result))
+    }
 
-                // Pop the scope
+    /// Evaluate a `where` expression, binding its values in a new scope.
+    #[inline(never)]
+    fn eval_where(
+        &mut self,
+        expr: &'arena Expr<'types, 'arena>,
+        bindings: &'arena [(&'arena str, &'arena Expr<'types, 'arena>)],
+    ) -> Result<Value<'types, 'arena>, ExecutionError> {
+        // Extract binding names
+        let names: crate::Vec<&'arena str> = bindings.iter().map(|(name, _)| *name).collect();
+
+        // Push incomplete scope with all binding names
+        // This allows sequential binding (later bindings can reference earlier ones)
+        self.scope_stack.push(
+            scope_stack::IncompleteScope::new(self.arena, &names)
+                .expect("Duplicate binding in where - analyzer should have caught this"),
+        );
+
+        // Evaluate and bind each expression sequentially
+        let mut index = 0;
+        while index < bindings.len() {
+            let group_len = recursive_group_len(bindings, index);
+            if group_len == 0 {
+                let (name, value_expr) = bindings[index];
+                let value = self.eval_expr(value_expr)?;
                 self.scope_stack
-                    .pop()
-                    .expect("Failed to pop where scope - internal error");
-
-                Ok(result)
+                    .bind_in_current(name, value)
+                    .expect("Failed to bind in where - analyzer should have caught this");
+                index += 1;
+            } else {
+                self.bind_recursive_lambdas(&bindings[index..index + group_len]);
+                index += group_len;
             }
+        }
 
-            ExprInner::Record { fields } => {
-                // Resolve type (replaces type variables if evaluating polymorphic lambda)
-                let resolved_ty = self.resolve_type(expr.0);
-
-                // Get field names from the type (which has the right lifetime 'types)
-                let Type::Record(field_types) = resolved_ty else {
-                    unreachable!("Record expression must have Record type")
-                };
+        // Evaluate the body expression (has access to all bindings)
+        let result = self.eval_expr(expr)?;
 
-                // Evaluate fields in type order (sorted), not AST order
-                // Build a map from field name to field expression for quick lookup
-                let mut field_map = hashbrown::HashMap::new();
-                for (name, expr) in fields.iter() {
-                    field_map.insert(*name, expr);
-                }
+        // Pop the scope
+        self.scope_stack
+            .pop()
+            .expect("Failed to pop where scope - internal error");
 
-                // Evaluate in type order and collect values
-                let mut field_values_temp: crate::Vec<(&'types str, Value<'types, 'arena>)> =
-                    crate::Vec::new();
+        Ok(result)
+    }
 
-                for (field_name, _field_ty) in field_types.iter() {
-                    // Look up the expression for this field
-                    let field_expr = field_map
-                        .get(field_name)
-                        .expect("Field in type but not in AST - analyzer should have caught this");
+    /// Evaluate a record, its fields in sorted order.
+    #[inline(never)]
+    fn eval_record(
+        &mut self,
+        expr: &'arena Expr<'types, 'arena>,
+        fields: &'arena [(&'arena str, &'arena Expr<'types, 'arena>)],
+    ) -> Result<Value<'types, 'arena>, ExecutionError> {
+        // Resolve type (replaces type variables if evaluating polymorphic lambda)
+        let resolved_ty = self.resolve_type(expr.0);
+
+        // Get field names from the type (which has the right lifetime 'types)
+        let Type::Record(field_types) = resolved_ty else {
+            unreachable!("Record expression must have Record type")
+        };
+
+        // Evaluate fields in type order (sorted), not AST order
+        // Build a map from field name to field expression for quick lookup
+        let mut field_map = hashbrown::HashMap::new();
+        for (name, expr) in fields.iter() {
+            field_map.insert(*name, expr);
+        }
 
-                    // Evaluate the field expression
-                    let field_value = self.eval_expr(field_expr)?;
+        // Evaluate in type order and collect values
+        let mut field_values_temp: crate::Vec<(&'types str, Value<'types, 'arena>)> =
+            crate::Vec::new();
 
-                    // Use field name from type (has 'types lifetime)
-                    field_values_temp.push((*field_name, field_value));
-                }
+        for (field_name, _field_ty) in field_types.iter() {
+            // Look up the expression for this field
+            let field_expr = field_map
+                .get(field_name)
+                .expect("Field in type but not in AST - analyzer should have caught this");
 
-                // Allocate in arena to get proper lifetime
-                let field_values = self.arena.alloc_slice_copy(&field_values_temp);
+            // Evaluate the field expression
+            let field_value = self.eval_expr(field_expr)?;
 
-                // Construct record value (fields are now in sorted order)
-                Ok(Value::record(self.arena, resolved_ty, field_values)
-                    .expect("Record construction failed - analyzer should have validated types"))
-            }
+            // Use field name from type (has 'types lifetime)
+            field_values_temp.push((*field_name, field_value));
+        }
 
-            ExprInner::Field { value, field } => {
-                // Evaluate the record expression
-                let record_value = self.eval_expr(value)?;
+        // Allocate in arena to get proper lifetime
+        let field_values = self.arena.alloc_slice_copy(&field_values_temp);
+
+        if self.options.preserve_record_field_order {
+            // Remember where each source field landed in the sorted layout
+            let field_order: crate::Vec<u32> = fields
+                .iter()
+                .map(|(name, _)| {
+                    field_types
+                        .binary_search_by_key(name, |(field_name, _)| *field_name)
+                        .expect("Field in AST but not in type") as u32
+                })
+                .collect();
+            return Ok(Value::record_with_field_order(
+                self.arena,
+                resolved_ty,
+                field_values,
+                &field_order,
+            )
+            .expect("Record construction failed - analyzer should have validated types"));
+        }
 
-                // Extract as record
-                let record = record_value
-                    .as_record()
-                    .expect("Field access on non-record - analyzer should have caught this");
+        // Construct record value (fields are now in sorted order)
+        Ok(Value::record(self.arena, resolved_ty, field_values)
+            .expect("Record construction failed - analyzer should have validated types"))
+    }
 
-                // Look up field by name
-                Ok(record
-                    .get(field)
-                    .expect("Field not found in record - analyzer should have caught this"))
-            }
+    /// Evaluate a record field access.
+    #[inline(never)]
+    fn eval_field(
+        &mut self,
+        value: &'arena Expr<'types, 'arena>,
+        field: &'arena str,
+    ) -> Result<Value<'types, 'arena>, ExecutionError> {
+        // Evaluate the record expression
+        let record_value = self.eval_expr(value)?;
+
+        // Extract as record
+        let record = record_value
+            .as_record()
+            .expect("Field access on non-record - analyzer should have caught this");
+
+        // Look up field by name
+        Ok(record
+            .get(field)
+            .expect("Field not found in record - analyzer should have caught this"))
+    }
 
-            ExprInner::Unary { op, expr: operand } => {
-                use crate::types::Type;
+    /// Evaluate a unary operation.
+    #[inline(never)]
+    #[cfg_attr(not(feature = "decimal"), allow(unused_variables))]
+    fn eval_unary(
+        &mut self,
+        expr: &'arena Expr<'types, 'arena>,
+        op: UnaryOp,
+        operand: &'arena Expr<'types, 'arena>,
+    ) -> Result<Value<'types, 'arena>, ExecutionError> {
+        use crate::types::Type;
 
-                // Evaluate the operand
-                let operand_val = self.eval_expr(operand)?;
+        // Evaluate the operand
+        let operand_val = self.eval_expr(operand)?;
 
-                // Dispatch based on type
-                match operand_val.ty {
-                    Type::Int => {
-                        let val = operand_val.as_int().expect("Type-checked as Int");
-                        let result = super::operators::eval_unary_int(*op, val);
-                        Ok(Value::int(self.type_manager, result))
-                    }
-                    Type::Float => {
-                        let val = operand_val.as_float().expect("Type-checked as Float");
-                        let result = super::operators::eval_unary_float(*op, val);
-                        Ok(Value::float(self.type_manager, result))
-                    }
-                    Type::Bool => {
-                        let val = operand_val.as_bool().expect("Type-checked as Bool");
-                        let result = super::operators::eval_unary_bool(*op, val);
-                        Ok(Value::bool(self.type_manager, result))
-                    }
-                    _ => {
-                        // Type checker should have caught this
-                        debug_assert!(false, "Unary operator on invalid type");
-                        unreachable!("Unary operator on invalid type in type-checked expression")
-                    }
-                }
+        // Dispatch based on type
+        match operand_val.ty {
+            Type::Int => {
+                let val = operand_val.as_int().expect("Type-checked as Int");
+                let result = super::operators::eval_unary_int(op, val);
+                Ok(Value::int(self.type_manager, result))
             }
-
-            ExprInner::If {
-                cond,
-                then_branch,
-                else_branch,
-            } => {
-                // Evaluate the condition
-                let cond_val = self.eval_expr(cond)?;
-                let cond_bool = cond_val.as_bool().expect("Type-checked as Bool");
-
-                // Evaluate the appropriate branch (lazy evaluation)
-                if cond_bool {
-                    self.eval_expr(then_branch)
-                } else {
-                    self.eval_expr(else_branch)
-                }
+            Type::Float => {
+                let val = operand_val.as_float().expect("Type-checked as Float");
+                let result = super::operators::eval_unary_float(op, val);
+                Ok(Value::float(self.type_manager, result))
             }
-
-            ExprInner::Array { elements } => {
-                // Evaluate all element expressions
-                let mut element_values: Vec<Value<'types, 'arena>> = Vec::new();
-                for elem_expr in elements.iter() {
-                    let elem_value = self.eval_expr(elem_expr)?;
-                    element_values.push(elem_value);
-                }
-
-                // Resolve type (replaces type variables if evaluating polymorphic lambda)
-                let resolved_ty = self.resolve_type(expr.0);
-
-                // Construct array value
-                // The analyzer ensures all elements have the same type, so this should never fail
-                Ok(Value::array(self.arena, resolved_ty, &element_values)
-                    .expect("Array construction failed - analyzer should have validated types"))
+            #[cfg(feature = "bigint")]
+            Type::BigInt => {
+                let val = operand_val.as_bigint().expect("Type-checked as BigInt");
+                let result = super::operators::eval_unary_bigint(op, &val);
+                Ok(Value::bigint(self.arena, self.type_manager, &result))
+            }
+            #[cfg(feature = "decimal")]
+            Type::Decimal => {
+                let val = operand_val.as_decimal().expect("Type-checked as Decimal");
+                let result = super::operators::eval_unary_decimal(op, val)
+                    .map_err(|e| self.add_error_context(expr, e))?;
+                Ok(Value::decimal(self.arena, self.type_manager, result))
+            }
+            Type::Bool => {
+                let val = operand_val.as_bool().expect("Type-checked as Bool");
+                let result = super::operators::eval_unary_bool(op, val);
+                Ok(Value::bool(self.type_manager, result))
+            }
+            _ => {
+                // Type checker should have caught this
+                debug_assert!(false, "Unary operator on invalid type");
+                unreachable!("Unary operator on invalid type in type-checked expression")
             }
+        }
+    }
 
-            ExprInner::Index { value, index } => {
-                // Evaluate the value being indexed
-                let indexed_value = self.eval_expr(value)?;
+    /// Evaluate an array literal.
+    #[inline(never)]
+    fn eval_array(
+        &mut self,
+        expr: &'arena Expr<'types, 'arena>,
+        elements: &'arena [&'arena Expr<'types, 'arena>],
+    ) -> Result<Value<'types, 'arena>, ExecutionError> {
+        // Evaluate all element expressions
+        let mut element_values: Vec<Value<'types, 'arena>> = Vec::new();
+        for elem_expr in elements.iter() {
+            let elem_value = self.eval_expr(elem_expr)?;
+            element_values.push(elem_value);
+        }
 
-                // Evaluate the index expression
-                let index_value = self.eval_expr(index)?;
+        // Resolve type (replaces type variables if evaluating polymorphic lambda)
+        let resolved_ty = self.resolve_type(expr.0);
 
-                // Handle array indexing
-                if let Ok(array) = indexed_value.as_array() {
-                    let original_index: i64 = index_value
-                        .as_int()
-                        .expect("Index with non-integer - analyzer should have caught this");
+        // Construct array value
+        // The analyzer ensures all elements have the same type, so this should never fail
+        Ok(Value::array(self.arena, resolved_ty, &element_values)
+            .expect("Array construction failed - analyzer should have validated types"))
+    }
 
-                    let index = usize::try_from(if original_index < 0 {
-                        original_index + array.len() as i64
-                    } else {
-                        original_index
-                    });
-                    if index.is_err() || index.unwrap() >= array.len() {
-                        return self.error(
-                            expr,
-                            IndexOutOfBounds {
-                                index: original_index,
-                                len: array.len(),
-                            }
-                            .into(),
-                        );
+    /// Evaluate indexing into an array, map or record.
+    #[inline(never)]
+    fn eval_index(
+        &mut self,
+        expr: &'arena Expr<'types, 'arena>,
+        value: &'arena Expr<'types, 'arena>,
+        index: &'arena Expr<'types, 'arena>,
+    ) -> Result<Value<'types, 'arena>, ExecutionError> {
+        // Evaluate the value being indexed
+        let indexed_value = self.eval_expr(value)?;
+
+        // Evaluate the index expression
+        let index_value = self.eval_expr(index)?;
+
+        // Handle array indexing
+        if let Ok(array) = indexed_value.as_array() {
+            let original_index: i64 = index_value
+                .as_int()
+                .expect("Index with non-integer - analyzer should have caught this");
+
+            let index = usize::try_from(if original_index < 0 {
+                original_index + array.len() as i64
+            } else {
+                original_index
+            });
+            if index.is_err() || index.unwrap() >= array.len() {
+                return self.error(
+                    expr,
+                    IndexOutOfBounds {
+                        index: original_index,
+                        len: array.len(),
                     }
+                    .into(),
+                );
+            }
 
-                    // Get element (safe after bounds check)
-                    Ok(array
-                        .get(index.unwrap())
-                        .expect("Index should be in bounds after check"))
-
-                // Handle map indexing
-                } else if let Ok(map) = indexed_value.as_map() {
-                    // Look up the key in the map
-                    match map.get(&index_value) {
-                        Some(result) => Ok(result),
-                        None => {
-                            // Key not found - return error with formatted key
-                            self.error(
-                                expr,
-                                KeyNotFound {
-                                    key_display: alloc::format!("{}", index_value),
-                                }
-                                .into(),
-                            )
+            // Get element (safe after bounds check)
+            Ok(array
+                .get(index.unwrap())
+                .expect("Index should be in bounds after check"))
+
+        // Handle map indexing
+        } else if let Ok(map) = indexed_value.as_map() {
+            // Look up the key in the map
+            match map.get(&index_value) {
+                Some(result) => Ok(result),
+                None => {
+                    // Key not found - return error with formatted key
+                    self.error(
+                        expr,
+                        KeyNotFound {
+                            key_display: alloc::format!("{}", index_value),
                         }
-                    }
-                } else {
-                    unreachable!(
-                        "Index operation on non-indexable type - analyzer should have caught this"
+                        .into(),
                     )
                 }
             }
+        // Handle record indexing by a non-constant field name
+        } else if let Ok(record) = indexed_value.as_record() {
+            let field = index_value
+                .as_str()
+                .expect("Record index with non-string - analyzer should have caught this");
+            match record.get(field) {
+                Some(result) => Ok(result),
+                None => self.error(
+                    expr,
+                    KeyNotFound {
+                        key_display: alloc::format!("{}", index_value),
+                    }
+                    .into(),
+                ),
+            }
+        } else {
+            unreachable!("Index operation on non-indexable type - analyzer should have caught this")
+        }
+    }
 
-            ExprInner::FormatStr { strs, exprs } => {
-                // Invariant: strs.len() == exprs.len() + 1
-                // Format: strs[0] + value(exprs[0]) + strs[1] + value(exprs[1]) + ... + strs[n]
+    /// Evaluate a format string.
+    #[inline(never)]
+    fn eval_format_str(
+        &mut self,
+        strs: &'arena [&'arena str],
+        exprs: &'arena [&'arena Expr<'types, 'arena>],
+    ) -> Result<Value<'types, 'arena>, ExecutionError> {
+        // Invariant: strs.len() == exprs.len() + 1
+        // Format: strs[0] + value(exprs[0]) + strs[1] + value(exprs[1]) + ... + strs[n]
 
-                use core::fmt::Write;
-                let mut result = crate::String::new();
+        use core::fmt::Write;
+        let mut result = crate::String::new();
 
-                // Add first string part
-                result.push_str(strs[0]);
+        // Add first string part
+        result.push_str(strs[0]);
 
-                // Interleave evaluated expressions and string parts
-                for (i, expr_item) in exprs.iter().enumerate() {
-                    let value = self.eval_expr(expr_item)?;
-                    // Use Display which outputs strings without quotes
-                    write!(result, "{}", value).expect("Writing to String should not fail");
-                    result.push_str(strs[i + 1]);
-                }
+        // Interleave evaluated expressions and string parts
+        for (i, expr_item) in exprs.iter().enumerate() {
+            let value = self.eval_expr(expr_item)?;
+            // Use Display which outputs strings without quotes
+            write!(result, "{}", value).expect("Writing to String should not fail");
+            result.push_str(strs[i + 1]);
+        }
 
-                // Allocate string in arena
-                let result_str = self.arena.alloc_str(&result);
-                Ok(Value::str(self.arena, self.type_manager.str(), result_str))
-            }
-
-            ExprInner::Otherwise { primary, fallback } => {
-                // Try to evaluate the primary expression
-                match self.eval_expr(primary) {
-                    Ok(value) => Ok(value),
-                    // Runtime errors trigger the fallback. Resource exceeded errors and
-                    // internal errors propagate without running the fallback.
-                    Err(e) => match e.kind {
-                        crate::evaluator::ExecutionErrorKind::Runtime(runtime_error) => {
-                            tracing::debug!(error = %runtime_error, "Handled by `otherwise` block");
-                            self.eval_expr(fallback)
-                        }
-                        crate::evaluator::ExecutionErrorKind::ResourceExceeded(_) => Err(e),
-                        crate::evaluator::ExecutionErrorKind::Internal(_) => Err(e),
-                    },
-                }
-            }
+        // Allocate string in arena
+        let result_str = self.arena.alloc_str(&result);
+        Ok(Value::str(self.arena, self.type_manager.str(), result_str))
+    }
 
-            ExprInner::Option { inner } => {
-                // Resolve type (replaces type variables if evaluating polymorphic lambda)
-                let resolved_ty = self.resolve_type(expr.0);
-
-                // Evaluate Option constructor
-                match inner {
-                    Some(expr_inner) => {
-                        // Evaluate the inner expression
-                        let inner_value = self.eval_expr(expr_inner)?;
-
-                        // Create Some(value) with resolved type
-                        Value::optional(self.arena, resolved_ty, Some(inner_value))
-                            .map_err(|_| {
-                                self.add_error_context(
-                                    expr,
-                                    InvariantViolation {
-                                        message: "Type resolution failed for Option value - this indicates a compiler bug".to_string(),
-                                    }.into()
-                                )
-                            })
-                    }
-                    None => {
-                        // Create None with resolved type
-                        Value::optional(self.arena, resolved_ty, None)
-                            .map_err(|_| {
-                                self.add_error_context(
-                                    expr,
-                                    InvariantViolation {
-                                        message: "Type resolution failed for Option value - this indicates a compiler bug".to_string(),
-                                    }.into()
-                                )
-                            })
-                    }
+    /// Evaluate `otherwise`, running the fallback on a runtime error.
+    #[inline(never)]
+    fn eval_otherwise(
+        &mut self,
+        primary: &'arena Expr<'types, 'arena>,
+        fallback: &'arena Expr<'types, 'arena>,
+    ) -> Result<Value<'types, 'arena>, ExecutionError> {
+        // Try to evaluate the primary expression
+        match self.eval_expr(primary) {
+            Ok(value) => Ok(value),
+            // Runtime errors trigger the fallback. Resource exceeded errors and
+            // internal errors propagate without running the fallback.
+            Err(e) => match e.kind {
+                crate::evaluator::ExecutionErrorKind::Runtime(runtime_error) => {
+                    tracing::debug!(error = %runtime_error, "Handled by `otherwise` block");
+                    self.eval_expr(fallback)
                 }
-            }
-
-            ExprInner::Cast { expr: inner_expr } => {
-                // Evaluate the expression being cast
-                let value = self.eval_expr(inner_expr)?;
-
-                // Resolve type (replaces type variables if evaluating polymorphic lambda)
-                let resolved_ty = self.resolve_type(expr.0);
+                crate::evaluator::ExecutionErrorKind::ResourceExceeded(_) => Err(e),
+                crate::evaluator::ExecutionErrorKind::Internal(_) => Err(e),
+            },
+        }
+    }
 
-                // Perform the cast using the casting library
-                // The target type is in expr.0 (the type of the Cast expression)
-                crate::casting::perform_cast(self.arena, value, resolved_ty, self.type_manager)
-                    .map_err(|e| self.add_error_context(expr, e.into()))
+    /// Evaluate an option constructor.
+    #[inline(never)]
+    fn eval_option(
+        &mut self,
+        expr: &'arena Expr<'types, 'arena>,
+        inner: Option<&'arena Expr<'types, 'arena>>,
+    ) -> Result<Value<'types, 'arena>, ExecutionError> {
+        // Resolve type (replaces type variables if evaluating polymorphic lambda)
+        let resolved_ty = self.resolve_type(expr.0);
+
+        // Evaluate Option constructor
+        match inner {
+            Some(expr_inner) => {
+                // Evaluate the inner expression
+                let inner_value = self.eval_expr(expr_inner)?;
+
+                // Create Some(value) with resolved type
+                Value::optional(self.arena, resolved_ty, Some(inner_value))
+                    .map_err(|_| {
+                        self.add_error_context(
+                            expr,
+                            InvariantViolation {
+                                message: "Type resolution failed for Option value - this indicates a compiler bug".to_string(),
+                            }.into()
+                        )
+                    })
+            }
+            None => {
+                // Create None with resolved type
+                Value::optional(self.arena, resolved_ty, None)
+                    .map_err(|_| {
+                        self.add_error_context(
+                            expr,
+                            InvariantViolation {
+                                message: "Type resolution failed for Option value - this indicates a compiler bug".to_string(),
+                            }.into()
+                        )
+                    })
             }
-            ExprInner::Call { callable, args } => {
-                // Evaluate the callable expression
-                let func_value = self.eval_expr(callable)?;
+        }
+    }
+
+    /// Evaluate a cast to the type of `expr`.
+    #[inline(never)]
+    fn eval_cast(
+        &mut self,
+        expr: &'arena Expr<'types, 'arena>,
+        inner_expr: &'arena Expr<'types, 'arena>,
+    ) -> Result<Value<'types, 'arena>, ExecutionError> {
+        // Evaluate the expression being cast
+        let value = self.eval_expr(inner_expr)?;
 
-                // Extract function trait object
-                let func = func_value
-                    .as_function()
-                    .expect("Type checker guarantees callable is a Function");
+        // Resolve type (replaces type variables if evaluating polymorphic lambda)
+        let resolved_ty = self.resolve_type(expr.0);
 
-                // Evaluate all arguments
-                let arg_values: alloc::vec::Vec<Value<'types, 'arena>> = args
-                    .iter()
-                    .map(|arg| self.eval_expr(arg))
-                    .collect::<Result<_, _>>()?;
+        // Perform the cast using the casting library
+        // The target type is in expr.0 (the type of the Cast expression)
+        crate::casting::perform_cast(self.arena, value, resolved_ty, self.type_manager)
+            .map_err(|e| self.add_error_context(expr, e.into()))
+    }
 
-                // Call the function via trait method
-                // SAFETY: The type checker guarantees the function type matches,
-                // arguments have correct types, and arity is correct.
-                let ctx = FfiContext::new(self.arena, self.type_manager);
-                unsafe { func.call_unchecked(&ctx, &arg_values) }
+    /// Evaluate a function call.
+    #[inline(never)]
+    fn eval_call(
+        &mut self,
+        expr: &'arena Expr<'types, 'arena>,
+        callable: &'arena Expr<'types, 'arena>,
+        args: &'arena [&'arena Expr<'types, 'arena>],
+    ) -> Result<Value<'types, 'arena>, ExecutionError> {
+        // Evaluate the callable expression
+        let func_value = self.eval_expr(callable)?;
+
+        // Extract function trait object
+        let func = func_value
+            .as_function()
+            .expect("Type checker guarantees callable is a Function");
+
+        // Evaluate all arguments
+        let arg_values: alloc::vec::Vec<Value<'types, 'arena>> = args
+            .iter()
+            .map(|arg| self.eval_expr(arg))
+            .collect::<Result<_, _>>()?;
+
+        // Call the function via trait method
+        // SAFETY: The type checker guarantees the function type matches,
+        // arguments have correct types, and arity is correct.
+        let ctx = FfiContext::new(self.arena, self.type_manager)
+            .with_call_depth(CallDepth {
+                depth: self.call_depth,
+                max_depth: self.options.max_depth,
+            })
+            .with_function_call_hook(self.options.on_function_call.clone())
+            .with_fuel(self.fuel)
+            .with_record_field_order(self.options.preserve_record_field_order);
+        unsafe { func.call_unchecked(&ctx, &arg_values) }.map_err(|error| {
+            // Native functions don't know the source, so their errors point at the call
+            if error.source.is_empty() {
+                self.add_error_context(expr, error.kind)
+            } else {
+                error
             }
-            ExprInner::Lambda {
-                params,
-                body,
-                captures,
-            } => {
-                // Capture the values of free variables from the current scope
-                let mut capture_values = Vec::new();
-                for &name in captures.iter() {
-                    if let Some(value) = self.scope_stack.lookup(name) {
-                        // TODO: Filter out globals (they should be accessed during call, not captured)
-                        capture_values.push((name, *value));
-                    }
-                }
+        })
+    }
 
-                let captures_slice = self.arena.alloc_slice_copy(&capture_values);
+    /// Evaluate a map literal.
+    #[inline(never)]
+    fn eval_map(
+        &mut self,
+        expr: &'arena Expr<'types, 'arena>,
+        elements: &'arena [(&'arena Expr<'types, 'arena>, &'arena Expr<'types, 'arena>)],
+    ) -> Result<Value<'types, 'arena>, ExecutionError> {
+        // Evaluate all key-value pairs
+        let mut pair_values: Vec<(Value<'types, 'arena>, Value<'types, 'arena>)> = Vec::new();
+        for (key_expr, value_expr) in elements.iter() {
+            let key_value = self.eval_expr(key_expr)?;
+            let value_value = self.eval_expr(value_expr)?;
+            pair_values.push((key_value, value_value));
+        }
 
-                // Construct a TypedExpr for the lambda body so it can report errors with spans
-                // We use the same annotation source as the parent expression
-                let body_typed = self.arena.alloc(TypedExpr {
-                    expr: body,
-                    ann: self.expr.ann,
-                    // Evaluator doesn't need instantiation info (just for error reporting)
-                    lambda_instantiations: hashbrown::HashMap::new_in(self.arena),
-                });
+        // Resolve type to handle polymorphic lambda bodies
+        let resolved_ty = self.resolve_type(expr.0);
 
-                let lambda = EvalLambda::new(expr.0, *params, body_typed, captures_slice);
+        // Construct map value
+        // The analyzer ensures all keys and values have consistent types
+        Ok(Value::map(self.arena, resolved_ty, &pair_values)
+            .expect("Map construction failed - analyzer should have validated types"))
+    }
 
-                // Value::function returns Result, but should never fail because
-                // the type checker guarantees expr.0 is a Function type
-                let fun = Value::function(self.arena, lambda)
-                    .expect("Type checker guarantees Function type");
-                Ok(fun)
-            }
-            ExprInner::Map { elements } => {
-                // Evaluate all key-value pairs
-                let mut pair_values: Vec<(Value<'types, 'arena>, Value<'types, 'arena>)> =
-                    Vec::new();
-                for (key_expr, value_expr) in elements.iter() {
-                    let key_value = self.eval_expr(key_expr)?;
-                    let value_value = self.eval_expr(value_expr)?;
-                    pair_values.push((key_value, value_value));
-                }
+    /// Evaluate a `match`, running the first arm whose pattern matches.
+    #[inline(never)]
+    fn eval_match(
+        &mut self,
+        match_expr: &'arena Expr<'types, 'arena>,
+        arms: &'arena [TypedMatchArm<'types, 'arena>],
+    ) -> Result<Value<'types, 'arena>, ExecutionError> {
+        // Evaluate the matched expression
+        let matched_value = self.eval_expr(match_expr)?;
 
-                // Resolve type to handle polymorphic lambda bodies
-                let resolved_ty = self.resolve_type(expr.0);
+        // Try each pattern arm in order
+        for arm in arms.iter() {
+            // Check if pattern matches, and if so, bind variables and evaluate body
+            if let Some(mut bindings) = self.match_pattern(arm.pattern, matched_value)? {
+                // Sort bindings by variable name (required by CompleteScope::from_sorted)
+                bindings.sort_by_key(|(name, _)| *name);
 
-                // Construct map value
-                // The analyzer ensures all keys and values have consistent types
-                Ok(Value::map(self.arena, resolved_ty, &pair_values)
-                    .expect("Map construction failed - analyzer should have validated types"))
-            }
+                // Create a new scope with pattern bindings
+                self.scope_stack
+                    .push(scope_stack::CompleteScope::from_sorted(
+                        self.arena.alloc_slice_copy(&bindings),
+                    ));
 
-            ExprInner::Match {
-                expr: match_expr,
-                arms,
-            } => {
-                // Evaluate the matched expression
-                let matched_value = self.eval_expr(match_expr)?;
-
-                // Try each pattern arm in order
-                for arm in arms.iter() {
-                    // Check if pattern matches, and if so, bind variables and evaluate body
-                    if let Some(mut bindings) = self.match_pattern(arm.pattern, matched_value)? {
-                        // Sort bindings by variable name (required by CompleteScope::from_sorted)
-                        bindings.sort_by_key(|(name, _)| *name);
-
-                        // Create a new scope with pattern bindings
-                        self.scope_stack
-                            .push(scope_stack::CompleteScope::from_sorted(
-                                self.arena.alloc_slice_copy(&bindings),
-                            ));
-
-                        // Evaluate the arm body (don't use ? yet to ensure scope cleanup)
-                        let result = self.eval_expr(arm.body);
-
-                        // Always pop pattern binding scope, even on error
-                        self.scope_stack
-                            .pop()
-                            .expect("Scope stack underflow - this is a bug");
-
-                        // Now return the result (propagate error if any)
-                        return result;
-                    }
-                }
+                // Evaluate the arm body (don't use ? yet to ensure scope cleanup)
+                let result = self.eval_expr(arm.body);
 
-                // This should never happen if exhaustiveness checking is working
-                unreachable!("Non-exhaustive pattern match - analyzer should have caught this")
+                // Always pop pattern binding scope, even on error
+                self.scope_stack
+                    .pop()
+                    .expect("Scope stack underflow - this is a bug");
+
+                // Now return the result (propagate error if any)
+                return result;
             }
         }
+
+        // This should never happen if exhaustiveness checking is working
+        unreachable!("Non-exhaustive pattern match - analyzer should have caught this")
     }
 
     /// Check if a pattern matches a value, returning variable bindings if it matches.
@@ -9,7 +9,10 @@ use crate::{
     },
     parser::{self, Span},
     types::manager::TypeManager,
-    values::{dynamic::Value, function::{FfiContext, NativeFunction}},
+    values::{
+        dynamic::Value,
+        function::{FfiContext, NativeFunction},
+    },
 };
 
 struct Runner<'a> {
@@ -99,6 +102,7 @@ impl<'a> Runner<'a> {
         Evaluator::new(
             EvaluatorOptions {
                 max_depth: max_stack_depth,
+                ..Default::default()
             },
             self.arena,
             self.type_mgr,
@@ -321,6 +325,18 @@ fn test_float_division_by_zero() {
     assert!(result.as_float().unwrap().is_infinite());
 }
 
+#[test]
+fn test_float_equality_inside_arrays() {
+    let arena = Bump::new();
+    let result = Runner::new(&arena)
+        .run("a == a where { a = [0.0 / 0.0] }", &[], &[])
+        .unwrap();
+    assert!(!result.as_bool().unwrap());
+
+    let result = Runner::new(&arena).run("[-0.0] == [0.0]", &[], &[]).unwrap();
+    assert!(result.as_bool().unwrap());
+}
+
 #[test]
 fn test_float_power() {
     let arena = Bump::new();
@@ -493,51 +509,64 @@ fn test_operator_precedence() {
 // ============================================================================
 
 #[test]
-#[ignore = "TODO(investigage): this is actually overflowing the stack"]
 fn test_stack_depth_limit() {
     let arena = Bump::new();
     let runner = Runner::new(&arena);
 
-    // Create a deeply nested expression using actual operations: 1 + (1 + (1 + ...))
-    // This creates real recursion depth, unlike just parentheses
-    let mut source = String::from("1");
-    for _ in 0..100 {
-        source = format!("1 + ({})", source);
-    }
+    // Recursion nests one lambda call per step
+    let source = "f(100) where { f = (n) => if n == 0 then 0 else f(n - 1) }";
 
-    // With default limit of 1000, this should succeed (100 < 1000)
-    let result = runner.run(&source, &[], &[]);
+    // With default limit of 1000, this should succeed (101 calls < 1000)
+    let result = runner.run(source, &[], &[]);
     assert!(result.is_ok());
 
     // But with a lower limit of 50, it should fail
-    let result = runner.run_with_limits(&source, &[], &[], 50);
+    let result = runner.run_with_limits(source, &[], &[], 50);
     assert!(matches!(
         result,
         Err(ExecutionError {
-            kind: ExecutionErrorKind::ResourceExceeded(ResourceExceededError::StackOverflow { .. }),
+            kind: ExecutionErrorKind::ResourceExceeded(ResourceExceededError::StackOverflow {
+                depth: 50,
+                max_depth: 50
+            }),
             ..
         })
     ));
 }
 
+#[test]
+fn test_stack_depth_limit_ignores_nested_expressions() {
+    let arena = Bump::new();
+    let runner = Runner::new(&arena);
+
+    // The limit counts lambda calls, as in the VM, not nested expressions
+    let mut source = String::from("1");
+    for _ in 0..100 {
+        source = format!("1 + ({})", source);
+    }
+
+    let result = runner.run_with_limits(&source, &[], &[], 50);
+    assert_eq!(result.unwrap().as_int().unwrap(), 101);
+}
+
 #[test]
 fn test_custom_stack_depth_limit() {
     let arena = Bump::new();
     let type_manager = TypeManager::new(&arena);
 
     // Create expression within custom limit
-    let mut source = String::from("1");
-    for _ in 0..50 {
-        source = format!("({} + 1)", source);
-    }
+    let source = "f(50) where { f = (n) => if n == 0 then 0 else f(n - 1) }";
 
-    let parsed = parser::parse(&arena, &source).expect("Parse failed");
+    let parsed = parser::parse(&arena, source).expect("Parse failed");
     let typed =
         analyzer::analyze(type_manager, &arena, &parsed, &[], &[]).expect("Type-check failed");
 
     // With custom limit of 100, this should succeed
     let result = Evaluator::new(
-        EvaluatorOptions { max_depth: 100 },
+        EvaluatorOptions {
+            max_depth: 100,
+            ..Default::default()
+        },
         &arena,
         type_manager,
         &typed,
@@ -549,7 +578,10 @@ fn test_custom_stack_depth_limit() {
 
     // But with limit of 40, it should fail
     let result = Evaluator::new(
-        EvaluatorOptions { max_depth: 40 },
+        EvaluatorOptions {
+            max_depth: 40,
+            ..Default::default()
+        },
         &arena,
         type_manager,
         &typed,
@@ -747,6 +779,25 @@ fn test_where_sequential_binding_chain() {
     assert_eq!(result.as_int().unwrap(), 3); // a=1, b=2, c=3
 }
 
+#[test]
+fn test_where_forward_reference() {
+    let arena = Bump::new();
+    // a can reference b, bound after it
+    let result = Runner::new(&arena)
+        .run("a where { a = b + 1, b = 2 }", &[], &[])
+        .unwrap();
+    assert_eq!(result.as_int().unwrap(), 3);
+
+    let result = Runner::new(&arena)
+        .run(
+            "f(1) where { f = (x) => g(x) * 2, g = (x) => x + c, c = 10 }",
+            &[],
+            &[],
+        )
+        .unwrap();
+    assert_eq!(result.as_int().unwrap(), 22);
+}
+
 #[test]
 fn test_where_complex_expression() {
     let arena = Bump::new();
@@ -828,6 +879,50 @@ fn test_field_access_multiple_fields() {
     assert_eq!(result.as_int().unwrap(), 20);
 }
 
+#[test]
+fn test_record_index_constant_key() {
+    let arena = Bump::new();
+    let result = Runner::new(&arena)
+        .run("{ a = 10, b = \"twenty\" }[\"b\"]", &[], &[])
+        .unwrap();
+    assert_eq!(result.as_str().unwrap(), "twenty");
+}
+
+#[test]
+fn test_record_index_dynamic_key() {
+    let arena = Bump::new();
+    let result = Runner::new(&arena)
+        .run(
+            "{ a = 10, b = 20, c = 30 }[key] where { key = \"c\" }",
+            &[],
+            &[],
+        )
+        .unwrap();
+    assert_eq!(result.as_int().unwrap(), 30);
+}
+
+#[test]
+fn test_record_index_dynamic_key_not_found() {
+    let arena = Bump::new();
+    let result = Runner::new(&arena).run("{ a = 10, b = 20 }[key] where { key = \"z\" }", &[], &[]);
+    assert!(matches!(
+        result,
+        Err(ExecutionError {
+            kind: ExecutionErrorKind::Runtime(RuntimeError::KeyNotFound { .. }),
+            ..
+        })
+    ));
+
+    let result = Runner::new(&arena)
+        .run(
+            "{ a = 10, b = 20 }[key] otherwise -1 where { key = \"z\" }",
+            &[],
+            &[],
+        )
+        .unwrap();
+    assert_eq!(result.as_int().unwrap(), -1);
+}
+
 #[test]
 fn test_field_access_in_expression() {
     let arena = Bump::new();
@@ -1311,7 +1406,7 @@ fn test_index_with_expression() {
 #[test]
 fn test_index_out_of_bounds_positive() {
     let arena = Bump::new();
-    let result = Runner::new(&arena).run("[1, 2][5]", &[], &[]);
+    let result = Runner::new(&arena).run("[1, 2][i] where { i = 5 }", &[], &[]);
     assert!(matches!(
         result,
         Err(ExecutionError {
@@ -1351,7 +1446,7 @@ fn test_index_negative_first() {
 fn test_index_out_of_bounds_negative() {
     let arena = Bump::new();
     // -3 is out of bounds for a 2-element array
-    let result = Runner::new(&arena).run("[1, 2][-3]", &[], &[]);
+    let result = Runner::new(&arena).run("[1, 2][i] where { i = -3 }", &[], &[]);
     assert!(result.is_err());
     assert_eq!(
         &result.unwrap_err().kind,
@@ -1695,6 +1790,101 @@ fn test_format_str_array_with_strings() {
 // Otherwise Operator Tests
 // ================================
 
+#[test]
+fn test_coalesce_some() {
+    let arena = Bump::new();
+    let result = Runner::new(&arena)
+        .run("(some 5 ?? 0) == 5", &[], &[])
+        .unwrap();
+    assert!(result.as_bool().unwrap());
+}
+
+#[test]
+fn test_coalesce_none() {
+    let arena = Bump::new();
+    let result = Runner::new(&arena)
+        .run("(none ?? 0) == 0", &[], &[])
+        .unwrap();
+    assert!(result.as_bool().unwrap());
+}
+
+#[test]
+fn test_coalesce_chained() {
+    let arena = Bump::new();
+    let result = Runner::new(&arena)
+        .run("a ?? b ?? 3 where { a = none, b = some 2 }", &[], &[])
+        .unwrap();
+    assert_eq!(result.as_int().unwrap(), 2);
+
+    let result = Runner::new(&arena)
+        .run("a ?? b ?? 3 where { a = none, b = none }", &[], &[])
+        .unwrap();
+    assert_eq!(result.as_int().unwrap(), 3);
+}
+
+#[test]
+fn test_coalesce_does_not_evaluate_default_for_some() {
+    let arena = Bump::new();
+    // The default would fail with division by zero if it were evaluated
+    let result = Runner::new(&arena)
+        .run("some 1 ?? 1 / 0", &[], &[])
+        .unwrap();
+    assert_eq!(result.as_int().unwrap(), 1);
+}
+
+#[test]
+fn test_optional_field_some() {
+    let arena = Bump::new();
+    let result = Runner::new(&arena)
+        .run("(some { x = 1 })?.x == some 1", &[], &[])
+        .unwrap();
+    assert!(result.as_bool().unwrap());
+}
+
+#[test]
+fn test_optional_field_none() {
+    let arena = Bump::new();
+    let result = Runner::new(&arena)
+        .run(
+            "opt?.x ?? -1 where { opt = if false then some { x = 1 } else none }",
+            &[],
+            &[],
+        )
+        .unwrap();
+    assert_eq!(result.as_int().unwrap(), -1);
+}
+
+#[test]
+fn test_optional_field_chained() {
+    let arena = Bump::new();
+    let result = Runner::new(&arena)
+        .run("(some { a = { b = 7 } })?.a?.b ?? 0", &[], &[])
+        .unwrap();
+    assert_eq!(result.as_int().unwrap(), 7);
+
+    let result = Runner::new(&arena)
+        .run(
+            "opt?.a?.b ?? 0 where { opt = if false then some { a = { b = 7 } } else none }",
+            &[],
+            &[],
+        )
+        .unwrap();
+    assert_eq!(result.as_int().unwrap(), 0);
+}
+
+#[test]
+fn test_optional_field_does_not_shadow_bindings() {
+    let arena = Bump::new();
+    let result = Runner::new(&arena)
+        .run(
+            "(opt?.x ?? 0) + value where { value = 10, opt = some { x = 1 } }",
+            &[],
+            &[],
+        )
+        .unwrap();
+    assert_eq!(result.as_int().unwrap(), 11);
+}
+
 #[test]
 fn test_otherwise_no_error() {
     let arena = Bump::new();
@@ -1796,6 +1986,66 @@ fn test_otherwise_nested() {
     assert_eq!(result.as_int().unwrap(), 42);
 }
 
+#[test]
+fn test_otherwise_fallback_error_propagates() {
+    let arena = Bump::new();
+    // Both primary and fallback fail: the fallback's error is not swallowed.
+    let result = Runner::new(&arena).run(
+        "[1][5] otherwise [2, 3][i] where { i = 7 }",
+        &[],
+        &[],
+    );
+    assert_eq!(
+        &result.unwrap_err().kind,
+        &ExecutionErrorKind::Runtime(RuntimeError::IndexOutOfBounds { index: 7, len: 2 })
+    );
+}
+
+#[test]
+fn test_otherwise_fallback_error_caught_by_outer() {
+    let arena = Bump::new();
+    let result = Runner::new(&arena)
+        .run("([1][5] otherwise [2, 3][7]) otherwise 42", &[], &[])
+        .unwrap();
+    assert_eq!(result.as_int().unwrap(), 42);
+}
+
+#[test]
+fn test_otherwise_does_not_catch_error_on_its_result() {
+    let arena = Bump::new();
+    // The primary succeeds; indexing its result afterwards is outside the scope.
+    let result = Runner::new(&arena).run("([1, 2] otherwise [3])[5]", &[], &[]);
+    assert_eq!(
+        &result.unwrap_err().kind,
+        &ExecutionErrorKind::Runtime(RuntimeError::IndexOutOfBounds { index: 5, len: 2 })
+    );
+}
+
+#[test]
+fn test_otherwise_does_not_catch_later_operation() {
+    let arena = Bump::new();
+    let result = Runner::new(&arena).run(
+        "([1][0] otherwise 0) + [2][i] where { i = 5 }",
+        &[],
+        &[],
+    );
+    assert_eq!(
+        &result.unwrap_err().kind,
+        &ExecutionErrorKind::Runtime(RuntimeError::IndexOutOfBounds { index: 5, len: 1 })
+    );
+}
+
+#[test]
+fn test_otherwise_handled_error_then_later_error() {
+    let arena = Bump::new();
+    // The first error is handled, the second one happens outside the scope.
+    let result = Runner::new(&arena).run("([1][5] otherwise 0) + 10 / 0", &[], &[]);
+    assert_eq!(
+        &result.unwrap_err().kind,
+        &ExecutionErrorKind::Runtime(RuntimeError::DivisionByZero {})
+    );
+}
+
 #[test]
 fn test_otherwise_with_where() {
     let arena = Bump::new();
@@ -1845,12 +2095,9 @@ fn test_otherwise_does_not_catch_stack_overflow() {
     let arena = Bump::new();
     let type_manager = TypeManager::new(&arena);
 
-    // Create a deeply nested expression that will exceed stack depth
+    // Create a deep recursion that will exceed stack depth
     // Use a very small depth limit to trigger overflow quickly
-    let mut expr = "1".to_string();
-    for _ in 0..50 {
-        expr = format!("({}) + 1", expr);
-    }
+    let expr = "f(50) where { f = (n) => if n == 0 then 0 else f(n - 1) }";
 
     // Add otherwise clause - this should NOT catch the StackOverflow error
     let source = format!("({}) otherwise 999", expr);
@@ -1860,7 +2107,10 @@ fn test_otherwise_does_not_catch_stack_overflow() {
 
     // Use a very small depth limit to trigger stack overflow
     let result = Evaluator::new(
-        EvaluatorOptions { max_depth: 10 },
+        EvaluatorOptions {
+            max_depth: 10,
+            ..Default::default()
+        },
         &arena,
         type_manager,
         &typed,
@@ -2051,6 +2301,46 @@ fn ffi_divide<'types, 'arena>(
     Ok(Value::int(ctx.type_mgr(), a / b))
 }
 
+#[test]
+fn test_from_rust_primitives_as_globals() {
+    use crate::values::typed::Str;
+
+    let arena = Bump::new();
+    let runner = Runner::new(&arena);
+    let type_mgr = runner.type_mgr;
+
+    let bytes: &[u8] = arena.alloc_slice_copy(b"\x01\x02");
+    // Globals must be sorted by name
+    let globals = [
+        ("count", Value::from_rust(&arena, type_mgr, 40_i64)),
+        ("enabled", Value::from_rust(&arena, type_mgr, true)),
+        (
+            "name",
+            Value::from_rust(&arena, type_mgr, Str::from_str(&arena, "melbi")),
+        ),
+        ("payload", Value::from_rust(&arena, type_mgr, bytes)),
+        ("ratio", Value::from_rust(&arena, type_mgr, 0.5_f64)),
+    ];
+    assert_eq!(globals[0].1.ty, type_mgr.int());
+    assert_eq!(globals[1].1.ty, type_mgr.bool());
+    assert_eq!(globals[2].1.ty, type_mgr.str());
+    assert_eq!(globals[3].1.ty, type_mgr.bytes());
+    assert_eq!(globals[4].1.ty, type_mgr.float());
+
+    let result = runner.run("count + 2", &globals, &[]).unwrap();
+    assert_eq!(result.as_int().unwrap(), 42);
+    let result = runner.run("ratio * 4.0", &globals, &[]).unwrap();
+    assert_eq!(result.as_float().unwrap(), 2.0);
+    let result = runner.run("not enabled", &globals, &[]).unwrap();
+    assert!(!result.as_bool().unwrap());
+    let result = runner.run("f\"hello {name}\"", &globals, &[]).unwrap();
+    assert_eq!(result.as_str().unwrap(), "hello melbi");
+    let result = runner
+        .run("payload == b\"\\x01\\x02\"", &globals, &[])
+        .unwrap();
+    assert!(result.as_bool().unwrap());
+}
+
 #[test]
 fn test_ffi_simple_call() {
     let arena = Bump::new();
@@ -2589,27 +2879,66 @@ fn test_lambda_with_format_string() {
     assert_eq!(result.as_str().unwrap(), "Hello, World!");
 }
 
-// Milestone 4.1: Recursive Closure Detection
+// Milestone 4.1: Recursive Closures
 #[test]
-#[ignore = "needs recursive closure detection in analyzer"]
 fn test_recursive_closure_direct_self_reference() {
     let arena = Bump::new();
-    let result = Runner::new(&arena).run("f(5) where { f = (n) => f(n - 1) }", &[], &[]);
-    // Should fail with RecursiveClosure error
-    assert!(result.is_err());
+    // Recursion without a base case stops at the depth limit
+    let result =
+        Runner::new(&arena).run_with_limits("f(5) where { f = (n) => f(n - 1) }", &[], &[], 50);
+    assert!(matches!(
+        result,
+        Err(ExecutionError {
+            kind: ExecutionErrorKind::ResourceExceeded(ResourceExceededError::StackOverflow {
+                depth: 50,
+                max_depth: 50
+            }),
+            ..
+        })
+    ));
 }
 
 #[test]
-#[ignore = "needs recursive closure detection in analyzer"]
 fn test_recursive_closure_factorial() {
     let arena = Bump::new();
-    let result = Runner::new(&arena).run(
-        "factorial(5) where { factorial = (n) => if n <= 1 then 1 else n * factorial(n - 1) }",
-        &[],
-        &[],
-    );
-    // Should fail with RecursiveClosure error
-    assert!(result.is_err());
+    let result = Runner::new(&arena)
+        .run(
+            "factorial(5) where { factorial = (n) => if n <= 1 then 1 else n * factorial(n - 1) }",
+            &[],
+            &[],
+        )
+        .unwrap();
+    assert_eq!(result.as_int().unwrap(), 120);
+}
+
+#[test]
+fn test_recursive_closure_mutual() {
+    let arena = Bump::new();
+    let result = Runner::new(&arena)
+        .run(
+            "[is_even(10), is_odd(7), is_even(3)] where {
+                is_even = (n) => if n == 0 then true else is_odd(n - 1),
+                is_odd = (n) => if n == 0 then false else is_even(n - 1),
+            }",
+            &[],
+            &[],
+        )
+        .unwrap();
+    assert_eq!(result.to_string(), "[true, true, false]");
+}
+
+#[test]
+fn test_recursive_closure_captures_outer_bindings() {
+    let arena = Bump::new();
+    // `step` is captured by value; `sum` reaches itself through its group
+    let result = Runner::new(&arena)
+        .run(
+            "sum(4) where { step = 10, sum = (n) => if n == 0 then 0 else step + sum(n - 1) }",
+            &[],
+            &[],
+        )
+        .unwrap();
+    assert_eq!(result.as_int().unwrap(), 40);
 }
 
 // Milestone 4.3: Edge Cases
@@ -3084,6 +3413,37 @@ fn test_match_option_nested_some_none() {
     assert_eq!(result.as_int().unwrap(), -1);
 }
 
+#[test]
+fn test_if_some_with_some() {
+    let arena = Bump::new();
+    let result = Runner::new(&arena)
+        .run("if some x = some 20 then x + 1 else 0", &[], &[])
+        .unwrap();
+    assert_eq!(result.as_int().unwrap(), 21);
+}
+
+#[test]
+fn test_if_some_with_none() {
+    let arena = Bump::new();
+    let result = Runner::new(&arena)
+        .run(
+            "if some x = opt then x + 1 else 0 where { opt = none }",
+            &[],
+            &[],
+        )
+        .unwrap();
+    assert_eq!(result.as_int().unwrap(), 0);
+}
+
+#[test]
+fn test_if_some_nested_pattern() {
+    let arena = Bump::new();
+    let result = Runner::new(&arena)
+        .run("if some (some x) = some none then x else -1", &[], &[])
+        .unwrap();
+    assert_eq!(result.as_int().unwrap(), -1);
+}
+
 #[test]
 fn test_match_in_where_binding() {
     let arena = Bump::new();
@@ -3146,3 +3506,97 @@ fn test_match_in_where_with_known_type() {
         .unwrap();
     assert_eq!(result.as_int().unwrap(), 10);
 }
+
+#[cfg(feature = "bigint")]
+#[test]
+fn test_bigint_addition_past_int_max() {
+    let arena = Bump::new();
+    let result = Runner::new(&arena)
+        .run("9223372036854775807n + 1n", &[], &[])
+        .unwrap();
+    assert_eq!(
+        result.as_bigint().unwrap(),
+        num_bigint::BigInt::from(i64::MAX) + 1
+    );
+    assert_eq!(format!("{:?}", result), "9223372036854775808n");
+
+    let result = Runner::new(&arena)
+        .run("2n ^ 100n - 2n ^ 100n", &[], &[])
+        .unwrap();
+    assert_eq!(result.as_bigint().unwrap(), num_bigint::BigInt::from(0));
+}
+
+#[cfg(feature = "bigint")]
+#[test]
+fn test_bigint_pow_result_size_is_capped() {
+    let arena = Bump::new();
+    let runner = Runner::new(&arena);
+
+    let error = runner.run("2n ^ 100000000n", &[], &[]).unwrap_err();
+    assert!(matches!(
+        error.kind,
+        ExecutionErrorKind::Runtime(RuntimeError::IntegerOverflow {})
+    ));
+
+    let result = runner
+        .run("2n ^ 100000000n otherwise 0n", &[], &[])
+        .unwrap();
+    assert_eq!(result.as_bigint().unwrap(), num_bigint::BigInt::from(0));
+
+    // Bases 0, 1 and -1 never grow, whatever the exponent
+    let result = runner.run("(-1n) ^ 4000000001n", &[], &[]).unwrap();
+    assert_eq!(result.as_bigint().unwrap(), num_bigint::BigInt::from(-1));
+}
+
+#[cfg(feature = "bigint")]
+#[test]
+fn test_bigint_comparison() {
+    let arena = Bump::new();
+    let runner = Runner::new(&arena);
+    for source in [
+        "2n ^ 64n > 9223372036854775807n",
+        "-(2n ^ 64n) < -9223372036854775808n",
+        "0x10n == 16n",
+        "3n != -3n",
+        "-7n / 2n == -4n",
+    ] {
+        let result = runner.run(source, &[], &[]).unwrap();
+        assert!(result.as_bool().unwrap(), "Expected true: {}", source);
+    }
+}
+
+#[cfg(feature = "bigint")]
+#[test]
+fn test_bigint_int_conversion() {
+    let arena = Bump::new();
+    let runner = Runner::new(&arena);
+
+    let result = runner
+        .run("(9223372036854775807 as BigInt * 2n / 2n) as Int", &[], &[])
+        .unwrap();
+    assert_eq!(result.as_int().unwrap(), i64::MAX);
+
+    let error = runner
+        .run("(9223372036854775807 as BigInt + 1n) as Int", &[], &[])
+        .unwrap_err();
+    assert!(matches!(
+        error.kind,
+        ExecutionErrorKind::Runtime(RuntimeError::CastError { .. })
+    ));
+
+    let result = runner
+        .run("(2n ^ 64n) as Int otherwise -1", &[], &[])
+        .unwrap();
+    assert_eq!(result.as_int().unwrap(), -1);
+}
+
+#[cfg(feature = "bigint")]
+#[test]
+fn test_bigint_division_by_zero() {
+    let arena = Bump::new();
+    let error = Runner::new(&arena).run("1n / 0n", &[], &[]).unwrap_err();
+    assert!(matches!(
+        error.kind,
+        ExecutionErrorKind::Runtime(RuntimeError::DivisionByZero {})
+    ));
+}
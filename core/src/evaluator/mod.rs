@@ -36,7 +36,7 @@
 
 mod error;
 mod eval;
-mod operators;
+pub(crate) mod operators;
 
 #[cfg(test)]
 mod eval_test;
@@ -45,15 +45,32 @@ pub use error::{
     ExecutionError, ExecutionErrorKind, InternalError, ResourceExceededError, RuntimeError,
 };
 
+use crate::values::FunctionCallHook;
+
+/// Default maximum number of nested lambda calls.
+///
+/// Each nested call uses native stack: about 1.5 KB in release builds, but up
+/// to 30 KB in debug builds (on the VM), so running this many calls needs a
+/// thread whose stack is large enough for the build.
+pub const DEFAULT_MAX_DEPTH: usize = 1000;
+
 /// Options for configuring the evaluator.
 pub struct EvaluatorOptions {
-    /// Maximum evaluation stack depth (for recursion protection).
+    /// Maximum number of nested lambda calls (for recursion protection).
     pub max_depth: usize,
+    /// Display record fields in source order instead of sorted order.
+    pub preserve_record_field_order: bool,
+    /// Hook called before each native function call.
+    pub on_function_call: Option<FunctionCallHook>,
 }
 
 impl Default for EvaluatorOptions {
     fn default() -> Self {
-        Self { max_depth: 1000 }
+        Self {
+            max_depth: DEFAULT_MAX_DEPTH,
+            preserve_record_field_order: false,
+            on_function_call: None,
+        }
     }
 }
 
@@ -4,12 +4,19 @@ use crate::{
     evaluator::{ExecutionErrorKind, RuntimeError::*},
     parser::{BinaryOp, ComparisonOp, UnaryOp},
 };
+#[cfg(feature = "bigint")]
+use num_bigint::BigInt;
+#[cfg(feature = "bigint")]
+use num_traits::{Euclid, Signed, ToPrimitive, Zero};
+
+#[cfg(feature = "decimal")]
+use crate::values::decimal::Decimal;
 
 /// Evaluate a binary operation on two integers.
 ///
 /// Uses wrapping arithmetic to prevent panics on overflow.
 /// Division by zero returns an error.
-pub(super) fn eval_binary_int(
+pub(crate) fn eval_binary_int(
     op: BinaryOp,
     left: i64,
     right: i64,
@@ -47,7 +54,7 @@ pub(super) fn eval_binary_int(
 /// Evaluate a binary operation on two floats.
 ///
 /// Follows IEEE 754 semantics (produces inf/nan rather than panicking).
-pub(super) fn eval_binary_float(op: BinaryOp, left: f64, right: f64) -> f64 {
+pub(crate) fn eval_binary_float(op: BinaryOp, left: f64, right: f64) -> f64 {
     match op {
         BinaryOp::Add => left + right,
         BinaryOp::Sub => left - right,
@@ -57,6 +64,90 @@ pub(super) fn eval_binary_float(op: BinaryOp, left: f64, right: f64) -> f64 {
     }
 }
 
+/// Largest `BigInt` result, in bits, that `^` will compute (about 315k
+/// decimal digits). Bigger powers are reported as overflow instead of
+/// allocating without bound.
+#[cfg(feature = "bigint")]
+pub(crate) const MAX_BIGINT_POW_BITS: u64 = 1 << 20;
+
+/// Evaluate a binary operation on two arbitrary-precision integers.
+///
+/// Division uses Euclidean semantics like `Int`. Negative exponents yield 0;
+/// powers whose result would exceed [`MAX_BIGINT_POW_BITS`] are reported as
+/// overflow.
+#[cfg(feature = "bigint")]
+pub(crate) fn eval_binary_bigint(
+    op: BinaryOp,
+    left: &BigInt,
+    right: &BigInt,
+) -> Result<BigInt, ExecutionErrorKind> {
+    match op {
+        BinaryOp::Add => Ok(left + right),
+        BinaryOp::Sub => Ok(left - right),
+        BinaryOp::Mul => Ok(left * right),
+        BinaryOp::Div => {
+            if right.is_zero() {
+                Err(DivisionByZero {}.into())
+            } else {
+                Ok(left.div_euclid(right))
+            }
+        }
+        BinaryOp::Pow => {
+            if right.is_negative() {
+                Ok(BigInt::zero())
+            } else {
+                let exponent = right.to_u32().ok_or(IntegerOverflow {})?;
+                // |left| >= 2^(bits - 1), so the result has at least
+                // (bits - 1) * exponent bits; 0, 1 and -1 stay small.
+                let min_bits = left
+                    .bits()
+                    .saturating_sub(1)
+                    .saturating_mul(u64::from(exponent));
+                if min_bits > MAX_BIGINT_POW_BITS {
+                    return Err(IntegerOverflow {}.into());
+                }
+                Ok(left.pow(exponent))
+            }
+        }
+    }
+}
+
+/// Evaluate a binary operation on two fixed-point decimals.
+///
+/// Addition, subtraction and multiplication are exact within the precision
+/// limit; division rounds half-to-even. Exponents must be whole numbers.
+#[cfg(feature = "decimal")]
+pub(crate) fn eval_binary_decimal(
+    op: BinaryOp,
+    left: Decimal,
+    right: Decimal,
+) -> Result<Decimal, ExecutionErrorKind> {
+    let result = match op {
+        BinaryOp::Add => left.checked_add(right),
+        BinaryOp::Sub => left.checked_sub(right),
+        BinaryOp::Mul => left.checked_mul(right),
+        BinaryOp::Div => {
+            if right.is_zero() {
+                return Err(DivisionByZero {}.into());
+            }
+            left.checked_div(right)
+        }
+        BinaryOp::Pow => {
+            let Some(exponent) = right.to_whole_int() else {
+                return Err(InvalidArgument {
+                    message: "Decimal exponent must be a whole number".into(),
+                }
+                .into());
+            };
+            if left.is_zero() && exponent < 0 {
+                return Err(DivisionByZero {}.into());
+            }
+            left.checked_powi(exponent)
+        }
+    };
+    result.ok_or_else(|| DecimalOverflow {}.into())
+}
+
 /// Evaluate a unary operation on an integer.
 ///
 /// Uses wrapping arithmetic for negation to prevent panics on overflow.
@@ -107,6 +198,65 @@ pub(super) fn eval_comparison_int(op: ComparisonOp, left: i64, right: i64) -> bo
     }
 }
 
+/// Evaluate a unary operation on an arbitrary-precision integer.
+#[cfg(feature = "bigint")]
+pub(crate) fn eval_unary_bigint(op: UnaryOp, value: &BigInt) -> BigInt {
+    match op {
+        UnaryOp::Neg => -value,
+        UnaryOp::Not => {
+            // Type checker should have caught this
+            unreachable!("Not operator not valid for BigInt")
+        }
+    }
+}
+
+/// Evaluate a comparison operation on two arbitrary-precision integers.
+#[cfg(feature = "bigint")]
+pub(crate) fn eval_comparison_bigint(op: ComparisonOp, left: &BigInt, right: &BigInt) -> bool {
+    match op {
+        ComparisonOp::Eq => left == right,
+        ComparisonOp::Neq => left != right,
+        ComparisonOp::Lt => left < right,
+        ComparisonOp::Gt => left > right,
+        ComparisonOp::Le => left <= right,
+        ComparisonOp::Ge => left >= right,
+        ComparisonOp::In | ComparisonOp::NotIn => {
+            unreachable!("In/NotIn not valid for BigInt")
+        }
+    }
+}
+
+/// Evaluate a unary operation on a fixed-point decimal.
+#[cfg(feature = "decimal")]
+pub(crate) fn eval_unary_decimal(
+    op: UnaryOp,
+    value: Decimal,
+) -> Result<Decimal, ExecutionErrorKind> {
+    match op {
+        UnaryOp::Neg => value.checked_neg().ok_or_else(|| DecimalOverflow {}.into()),
+        UnaryOp::Not => {
+            // Type checker should have caught this
+            unreachable!("Not operator not valid for Decimal")
+        }
+    }
+}
+
+/// Evaluate a comparison operation on two fixed-point decimals.
+#[cfg(feature = "decimal")]
+pub(crate) fn eval_comparison_decimal(op: ComparisonOp, left: Decimal, right: Decimal) -> bool {
+    match op {
+        ComparisonOp::Eq => left == right,
+        ComparisonOp::Neq => left != right,
+        ComparisonOp::Lt => left < right,
+        ComparisonOp::Gt => left > right,
+        ComparisonOp::Le => left <= right,
+        ComparisonOp::Ge => left >= right,
+        ComparisonOp::In | ComparisonOp::NotIn => {
+            unreachable!("In/NotIn not valid for Decimal")
+        }
+    }
+}
+
 /// Evaluate a comparison operation on two floats.
 pub(super) fn eval_comparison_float(op: ComparisonOp, left: f64, right: f64) -> bool {
     match op {
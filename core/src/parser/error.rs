@@ -14,6 +14,9 @@ pub struct ParseError {
     pub context: Vec<Context>,
 }
 
+/// Message the parser attaches to integer literals that overflow `i64`.
+pub(crate) const INTEGER_OUT_OF_RANGE: &str = "integer literal out of range for Int (i64)";
+
 /// Specific kinds of parse errors
 #[derive(Debug)]
 pub enum ParseErrorKind {
@@ -25,6 +28,8 @@ pub enum ParseErrorKind {
     InvalidNumber { text: String },
     /// Maximum nesting depth exceeded
     MaxDepthExceeded { depth: usize, max_depth: usize },
+    /// Integer literal that does not fit in an `i64`
+    IntegerOutOfRange { text: String },
     /// Other parse errors (catch-all for Pest errors we don't specifically handle)
     Other { message: String },
 }
@@ -68,6 +73,25 @@ impl ParseError {
                 Some("P004"),
                 vec!["Reduce nesting or simplify the expression".to_string()],
             ),
+            ParseErrorKind::IntegerOutOfRange { text } => {
+                let mut help = vec![format!(
+                    "Int values range from {} to {}",
+                    i64::MIN,
+                    i64::MAX
+                )];
+                // Only decimal literals have a Float spelling
+                if text
+                    .trim_start_matches('-')
+                    .chars()
+                    .all(|c| c.is_ascii_digit() || c == '_')
+                {
+                    help.push(format!(
+                        "Write `{}.0` to use a Float if the value does not need to be exact",
+                        text
+                    ));
+                }
+                (INTEGER_OUT_OF_RANGE.to_string(), Some("P005"), help)
+            }
             ParseErrorKind::Other { message, .. } => (message.clone(), Some("P999"), vec![]),
         };
 
@@ -123,6 +147,11 @@ pub fn convert_pest_error(err: pest::error::Error<Rule>, source: &str) -> ParseE
 
             ParseErrorKind::UnexpectedToken { expected, found }
         }
+        ErrorVariant::CustomError { message } if message == INTEGER_OUT_OF_RANGE => {
+            ParseErrorKind::IntegerOutOfRange {
+                text: source[span.0.clone()].to_string(),
+            }
+        }
         ErrorVariant::CustomError { message } => {
             // Check if it's a depth error
             if message.contains("nesting depth") {
@@ -176,12 +205,23 @@ fn format_expected_rules(rules: &[Rule]) -> String {
 
     for rule in rules {
         match rule {
-            Rule::grouped | Rule::neg | Rule::not | Rule::if_op | Rule::lambda_op => {
+            Rule::grouped
+            | Rule::neg
+            | Rule::not
+            | Rule::if_op
+            | Rule::if_some_op
+            | Rule::lambda_op => {
                 if !concepts.contains(&"expression") {
                     concepts.push("expression");
                 }
             }
-            Rule::integer | Rule::float | Rule::boolean | Rule::string | Rule::bytes => {
+            Rule::integer
+            | Rule::big_integer
+            | Rule::decimal
+            | Rule::float
+            | Rule::boolean
+            | Rule::string
+            | Rule::bytes => {
                 if !concepts.contains(&"literal") {
                     concepts.push("literal");
                 }
@@ -227,6 +267,8 @@ fn format_found_rules(rules: &[Rule]) -> String {
     match rules[0] {
         Rule::ident => "identifier".to_string(),
         Rule::integer => "integer".to_string(),
+        Rule::big_integer => "big integer".to_string(),
+        Rule::decimal => "decimal".to_string(),
         Rule::float => "floating-point number".to_string(),
         Rule::boolean => "boolean".to_string(),
         Rule::string => "string".to_string(),
@@ -235,7 +277,7 @@ fn format_found_rules(rules: &[Rule]) -> String {
         Rule::grouped => "grouped expression".to_string(),
         Rule::neg => "negation".to_string(),
         Rule::not => "logical not".to_string(),
-        Rule::if_op => "if expression".to_string(),
+        Rule::if_op | Rule::if_some_op => "if expression".to_string(),
         Rule::lambda_op => "lambda expression".to_string(),
         _ => format!("{:?}", rules[0]),
     }
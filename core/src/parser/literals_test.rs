@@ -1,5 +1,5 @@
 use super::parser::parse;
-use crate::parser::{BinaryOp, Expr, Literal, UnaryOp};
+use crate::parser::{BinaryOp, Expr, Literal, Span, UnaryOp};
 use bumpalo::Bump;
 
 #[test]
@@ -365,6 +365,33 @@ fn test_integer_overflow() {
     assert!(result.is_err(), "Expected overflow error for i64::MAX + 2");
 }
 
+#[test]
+fn test_integer_overflow_diagnostic() {
+    let arena = Bump::new();
+
+    let error = parse(&arena, "1 + 99999999999999999999").unwrap_err();
+    let diagnostic = error.to_diagnostic();
+    assert_eq!(diagnostic.code.as_deref(), Some("P005"));
+    assert_eq!(
+        diagnostic.message,
+        "integer literal out of range for Int (i64)"
+    );
+    assert_eq!(diagnostic.span, Span(4..24));
+    assert!(
+        diagnostic
+            .help
+            .iter()
+            .any(|help| help.contains("`99999999999999999999.0`"))
+    );
+
+    // Non-decimal literals have no Float spelling to suggest; suffixes are not part of the span
+    let error = parse(&arena, "0xFFFF_FFFF_FFFF_FFFF_F`m`").unwrap_err();
+    let diagnostic = error.to_diagnostic();
+    assert_eq!(diagnostic.code.as_deref(), Some("P005"));
+    assert_eq!(diagnostic.span, Span(0..23));
+    assert!(!diagnostic.help.iter().any(|help| help.contains("Float")));
+}
+
 #[test]
 fn test_integer_min_value() {
     let arena = Bump::new();
@@ -379,3 +406,58 @@ fn test_integer_min_value() {
         })
     );
 }
+
+#[test]
+fn test_big_integer_literals() {
+    let arena = Bump::new();
+
+    let cases = [
+        ("123n", "123"),
+        ("99999999999999999999n", "99999999999999999999"),
+        ("0x_FFn", "0x_FF"),
+        ("0b101n", "0b101"),
+        ("-42n", "-42"),
+    ];
+
+    for (input, expected) in cases {
+        let parsed = parse(&arena, input).unwrap();
+        assert_eq!(
+            *parsed.expr,
+            Expr::Literal(Literal::BigInt(expected)),
+            "Failed for input: {}",
+            input
+        );
+    }
+
+    // The `n` marker must end the literal
+    assert!(parse(&arena, "123nope").is_err());
+}
+
+#[test]
+fn test_decimal_literals() {
+    let arena = Bump::new();
+
+    let cases = [
+        ("0.1d", "0.1"),
+        ("12d", "12"),
+        (".5d", ".5"),
+        ("1_000.25d", "1_000.25"),
+        ("-3.d", "-3."),
+    ];
+
+    for (input, expected) in cases {
+        let parsed = parse(&arena, input).unwrap();
+        assert_eq!(
+            *parsed.expr,
+            Expr::Literal(Literal::Decimal(expected)),
+            "Failed for input: {}",
+            input
+        );
+    }
+
+    // Hex digits are not decimal markers
+    assert!(matches!(
+        parse(&arena, "0xd").unwrap().expr,
+        Expr::Literal(Literal::Int { value: 13, .. })
+    ));
+}
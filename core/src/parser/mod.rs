@@ -2,6 +2,7 @@ mod parsed_expr;
 pub mod parser;
 mod syntax;
 pub mod error;
+pub mod tokenizer;
 
 // Re-export the parser and rule enum for external use
 pub use parser::ExpressionParser;
@@ -13,6 +14,7 @@ pub use parsed_expr::{Expr, Literal, MatchArm, ParsedExpr, Pattern, TypeExpr};
 pub use syntax::AnnotatedSource;
 pub use syntax::{BinaryOp, BoolOp, ComparisonOp, Span, UnaryOp};
 pub use error::{ParseError, ParseErrorKind};
+pub use tokenizer::{TokenKind, tokenize};
 
 #[cfg(test)]
 mod literals_test;
@@ -25,3 +27,9 @@ mod rule_valid_test;
 
 #[cfg(test)]
 mod precedence_test;
+
+#[cfg(test)]
+mod parsed_expr_test;
+
+#[cfg(test)]
+mod tokenizer_test;
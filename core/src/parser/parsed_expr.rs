@@ -1,4 +1,9 @@
-use crate::parser::{BinaryOp, BoolOp, ComparisonOp, UnaryOp, syntax::AnnotatedSource};
+use core::hash::Hasher;
+
+use crate::{
+    Vec,
+    parser::{BinaryOp, BoolOp, ComparisonOp, UnaryOp, syntax::AnnotatedSource},
+};
 use serde::Serialize;
 
 #[derive(Debug)]
@@ -61,6 +66,11 @@ pub enum Expr<'a> {
         primary: &'a Expr<'a>,
         fallback: &'a Expr<'a>,
     },
+    /// Option coalescing: `option ?? default`
+    Coalesce {
+        option: &'a Expr<'a>,
+        default: &'a Expr<'a>,
+    },
     /// Option constructor: `some expr` or `none`
     /// Inner is Some(expr) for `some expr`, None for `none`
     Option {
@@ -87,6 +97,569 @@ impl<'a> Expr<'a> {
     pub fn as_ptr(&self) -> *const Self {
         self as *const _
     }
+
+    /// Structural equality of two expression trees, ignoring spans.
+    ///
+    /// Spans live in the `AnnotatedSource`, so two parses of the same source
+    /// (or of sources differing only in whitespace, comments, or redundant
+    /// parentheses) are `ast_eq`, even across arenas. Unlike `==`, float
+    /// literals compare by bit pattern, making this a true equivalence
+    /// relation suitable for keying caches.
+    pub fn ast_eq(&self, other: &Expr<'_>) -> bool {
+        match (self, other) {
+            (
+                Expr::Binary { op, left, right },
+                Expr::Binary {
+                    op: other_op,
+                    left: other_left,
+                    right: other_right,
+                },
+            ) => op == other_op && left.ast_eq(other_left) && right.ast_eq(other_right),
+            (
+                Expr::Boolean { op, left, right },
+                Expr::Boolean {
+                    op: other_op,
+                    left: other_left,
+                    right: other_right,
+                },
+            ) => op == other_op && left.ast_eq(other_left) && right.ast_eq(other_right),
+            (
+                Expr::Comparison { op, left, right },
+                Expr::Comparison {
+                    op: other_op,
+                    left: other_left,
+                    right: other_right,
+                },
+            ) => op == other_op && left.ast_eq(other_left) && right.ast_eq(other_right),
+            (
+                Expr::Unary { op, expr },
+                Expr::Unary {
+                    op: other_op,
+                    expr: other_expr,
+                },
+            ) => op == other_op && expr.ast_eq(other_expr),
+            (
+                Expr::Call { callable, args },
+                Expr::Call {
+                    callable: other_callable,
+                    args: other_args,
+                },
+            ) => callable.ast_eq(other_callable) && exprs_ast_eq(args, other_args),
+            (
+                Expr::Index { value, index },
+                Expr::Index {
+                    value: other_value,
+                    index: other_index,
+                },
+            ) => value.ast_eq(other_value) && index.ast_eq(other_index),
+            (
+                Expr::Field { value, field },
+                Expr::Field {
+                    value: other_value,
+                    field: other_field,
+                },
+            ) => field == other_field && value.ast_eq(other_value),
+            (
+                Expr::Cast { ty, expr },
+                Expr::Cast {
+                    ty: other_ty,
+                    expr: other_expr,
+                },
+            ) => ty == other_ty && expr.ast_eq(other_expr),
+            (
+                Expr::Lambda { params, body },
+                Expr::Lambda {
+                    params: other_params,
+                    body: other_body,
+                },
+            ) => params == other_params && body.ast_eq(other_body),
+            (
+                Expr::If {
+                    cond,
+                    then_branch,
+                    else_branch,
+                },
+                Expr::If {
+                    cond: other_cond,
+                    then_branch: other_then,
+                    else_branch: other_else,
+                },
+            ) => {
+                cond.ast_eq(other_cond)
+                    && then_branch.ast_eq(other_then)
+                    && else_branch.ast_eq(other_else)
+            }
+            (
+                Expr::Where { expr, bindings },
+                Expr::Where {
+                    expr: other_expr,
+                    bindings: other_bindings,
+                },
+            ) => expr.ast_eq(other_expr) && fields_ast_eq(bindings, other_bindings),
+            (
+                Expr::Otherwise { primary, fallback },
+                Expr::Otherwise {
+                    primary: other_primary,
+                    fallback: other_fallback,
+                },
+            ) => primary.ast_eq(other_primary) && fallback.ast_eq(other_fallback),
+            (
+                Expr::Coalesce { option, default },
+                Expr::Coalesce {
+                    option: other_option,
+                    default: other_default,
+                },
+            ) => option.ast_eq(other_option) && default.ast_eq(other_default),
+            (Expr::Option { inner }, Expr::Option { inner: other_inner }) => {
+                match (inner, other_inner) {
+                    (Some(inner), Some(other_inner)) => inner.ast_eq(other_inner),
+                    (None, None) => true,
+                    _ => false,
+                }
+            }
+            (
+                Expr::Match { expr, arms },
+                Expr::Match {
+                    expr: other_expr,
+                    arms: other_arms,
+                },
+            ) => {
+                expr.ast_eq(other_expr)
+                    && arms.len() == other_arms.len()
+                    && arms.iter().zip(other_arms.iter()).all(|(arm, other_arm)| {
+                        arm.pattern.ast_eq(other_arm.pattern) && arm.body.ast_eq(other_arm.body)
+                    })
+            }
+            (Expr::Record(fields), Expr::Record(other_fields)) => {
+                fields_ast_eq(fields, other_fields)
+            }
+            (Expr::Map(entries), Expr::Map(other_entries)) => {
+                entries.len() == other_entries.len()
+                    && entries.iter().zip(other_entries.iter()).all(
+                        |((key, value), (other_key, other_value))| {
+                            key.ast_eq(other_key) && value.ast_eq(other_value)
+                        },
+                    )
+            }
+            (Expr::Array(elements), Expr::Array(other_elements)) => {
+                exprs_ast_eq(elements, other_elements)
+            }
+            (
+                Expr::FormatStr { strs, exprs },
+                Expr::FormatStr {
+                    strs: other_strs,
+                    exprs: other_exprs,
+                },
+            ) => strs == other_strs && exprs_ast_eq(exprs, other_exprs),
+            (Expr::Literal(literal), Expr::Literal(other_literal)) => literal.ast_eq(other_literal),
+            (Expr::Ident(name), Expr::Ident(other_name)) => name == other_name,
+            _ => false,
+        }
+    }
+
+    /// Feed the expression's structure into `state`, ignoring spans.
+    ///
+    /// Consistent with [`Expr::ast_eq`]: expressions that are `ast_eq` hash
+    /// identically. Variants are fed as explicit tags and integers as
+    /// little-endian bytes, so a platform-independent hasher yields the same
+    /// hash on every platform and compiler version.
+    pub fn ast_hash<H: Hasher>(&self, state: &mut H) {
+        match self {
+            Expr::Binary { op, left, right } => {
+                state.write_u8(0);
+                state.write_u8(*op as u8);
+                left.ast_hash(state);
+                right.ast_hash(state);
+            }
+            Expr::Boolean { op, left, right } => {
+                state.write_u8(1);
+                state.write_u8(*op as u8);
+                left.ast_hash(state);
+                right.ast_hash(state);
+            }
+            Expr::Comparison { op, left, right } => {
+                state.write_u8(2);
+                state.write_u8(*op as u8);
+                left.ast_hash(state);
+                right.ast_hash(state);
+            }
+            Expr::Unary { op, expr } => {
+                state.write_u8(3);
+                state.write_u8(*op as u8);
+                expr.ast_hash(state);
+            }
+            Expr::Call { callable, args } => {
+                state.write_u8(4);
+                callable.ast_hash(state);
+                exprs_ast_hash(args, state);
+            }
+            Expr::Index { value, index } => {
+                state.write_u8(5);
+                value.ast_hash(state);
+                index.ast_hash(state);
+            }
+            Expr::Field { value, field } => {
+                state.write_u8(6);
+                value.ast_hash(state);
+                str_ast_hash(field, state);
+            }
+            Expr::Cast { ty, expr } => {
+                state.write_u8(7);
+                ty.ast_hash(state);
+                expr.ast_hash(state);
+            }
+            Expr::Lambda { params, body } => {
+                state.write_u8(8);
+                strs_ast_hash(params, state);
+                body.ast_hash(state);
+            }
+            Expr::If {
+                cond,
+                then_branch,
+                else_branch,
+            } => {
+                state.write_u8(9);
+                cond.ast_hash(state);
+                then_branch.ast_hash(state);
+                else_branch.ast_hash(state);
+            }
+            Expr::Where { expr, bindings } => {
+                state.write_u8(10);
+                expr.ast_hash(state);
+                fields_ast_hash(bindings, state);
+            }
+            Expr::Otherwise { primary, fallback } => {
+                state.write_u8(11);
+                primary.ast_hash(state);
+                fallback.ast_hash(state);
+            }
+            Expr::Coalesce { option, default } => {
+                state.write_u8(12);
+                option.ast_hash(state);
+                default.ast_hash(state);
+            }
+            Expr::Option { inner } => {
+                state.write_u8(13);
+                suffix_ast_hash(inner, state);
+            }
+            Expr::Match { expr, arms } => {
+                state.write_u8(14);
+                expr.ast_hash(state);
+                len_ast_hash(arms.len(), state);
+                for arm in arms.iter() {
+                    arm.pattern.ast_hash(state);
+                    arm.body.ast_hash(state);
+                }
+            }
+            Expr::Record(fields) => {
+                state.write_u8(15);
+                fields_ast_hash(fields, state);
+            }
+            Expr::Map(entries) => {
+                state.write_u8(16);
+                len_ast_hash(entries.len(), state);
+                for (key, value) in entries.iter() {
+                    key.ast_hash(state);
+                    value.ast_hash(state);
+                }
+            }
+            Expr::Array(elements) => {
+                state.write_u8(17);
+                exprs_ast_hash(elements, state);
+            }
+            Expr::FormatStr { strs, exprs } => {
+                state.write_u8(18);
+                strs_ast_hash(strs, state);
+                exprs_ast_hash(exprs, state);
+            }
+            Expr::Literal(literal) => {
+                state.write_u8(19);
+                literal.ast_hash(state);
+            }
+            Expr::Ident(name) => {
+                state.write_u8(20);
+                str_ast_hash(name, state);
+            }
+        }
+    }
+
+    /// A 64-bit fingerprint of the expression's structure, ignoring spans.
+    ///
+    /// Unlike hashing with a randomly seeded hasher, the fingerprint is
+    /// deterministic across runs, so it can key persistent caches without
+    /// retaining the source string. Distinct expressions may collide, so
+    /// caches should confirm hits with [`Expr::ast_eq`] when the expression
+    /// is available.
+    pub fn fingerprint(&self) -> u64 {
+        let mut hasher = FnvHasher::default();
+        self.ast_hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// The deepest nesting of binding scopes in the expression.
+    ///
+    /// Each `where` and match arm opens one scope and each lambda opens two
+    /// (captures and parameters), mirroring what the analyzer pushes. This
+    /// is a single cheap walk, used to size scope stacks up front.
+    pub fn scope_depth(&self) -> usize {
+        match self {
+            Expr::Binary { left, right, .. }
+            | Expr::Boolean { left, right, .. }
+            | Expr::Comparison { left, right, .. }
+            | Expr::Index {
+                value: left,
+                index: right,
+            }
+            | Expr::Otherwise {
+                primary: left,
+                fallback: right,
+            }
+            | Expr::Coalesce {
+                option: left,
+                default: right,
+            } => left.scope_depth().max(right.scope_depth()),
+            Expr::Unary { expr, .. }
+            | Expr::Field { value: expr, .. }
+            | Expr::Cast { expr, .. } => expr.scope_depth(),
+            Expr::Call { callable, args } => callable.scope_depth().max(exprs_scope_depth(args)),
+            Expr::Lambda { body, .. } => body.scope_depth() + 2,
+            Expr::If {
+                cond,
+                then_branch,
+                else_branch,
+            } => cond
+                .scope_depth()
+                .max(then_branch.scope_depth())
+                .max(else_branch.scope_depth()),
+            Expr::Where { expr, bindings } => {
+                let bindings_depth = bindings
+                    .iter()
+                    .map(|(_, value)| value.scope_depth())
+                    .max()
+                    .unwrap_or(0);
+                expr.scope_depth().max(bindings_depth) + 1
+            }
+            Expr::Option { inner } => inner.map_or(0, |inner| inner.scope_depth()),
+            Expr::Match { expr, arms } => arms
+                .iter()
+                .map(|arm| arm.body.scope_depth() + 1)
+                .fold(expr.scope_depth(), usize::max),
+            Expr::Record(fields) => fields
+                .iter()
+                .map(|(_, value)| value.scope_depth())
+                .max()
+                .unwrap_or(0),
+            Expr::Map(entries) => entries
+                .iter()
+                .map(|(key, value)| key.scope_depth().max(value.scope_depth()))
+                .max()
+                .unwrap_or(0),
+            Expr::Array(exprs) | Expr::FormatStr { exprs, .. } => exprs_scope_depth(exprs),
+            Expr::Literal(_) | Expr::Ident(_) => 0,
+        }
+    }
+
+    /// Identifiers this expression uses but does not bind, in order of first
+    /// use and without duplicates.
+    ///
+    /// Names bound inside the expression (lambda parameters, `where`
+    /// bindings, match pattern variables) are not free where they are in
+    /// scope.
+    pub fn free_identifiers(&self) -> Vec<&'a str> {
+        let mut free = Vec::new();
+        self.collect_free_identifiers(&mut Vec::new(), &mut free);
+        free
+    }
+
+    fn collect_free_identifiers(&self, bound: &mut Vec<&'a str>, free: &mut Vec<&'a str>) {
+        match self {
+            Expr::Ident(name) => {
+                if !bound.contains(name) && !free.contains(name) {
+                    free.push(name);
+                }
+            }
+            Expr::Literal(_) | Expr::Option { inner: None } => {}
+            Expr::Binary { left, right, .. }
+            | Expr::Boolean { left, right, .. }
+            | Expr::Comparison { left, right, .. }
+            | Expr::Index {
+                value: left,
+                index: right,
+            }
+            | Expr::Otherwise {
+                primary: left,
+                fallback: right,
+            }
+            | Expr::Coalesce {
+                option: left,
+                default: right,
+            } => {
+                left.collect_free_identifiers(bound, free);
+                right.collect_free_identifiers(bound, free);
+            }
+            Expr::Unary { expr, .. }
+            | Expr::Field { value: expr, .. }
+            | Expr::Cast { expr, .. }
+            | Expr::Option { inner: Some(expr) } => expr.collect_free_identifiers(bound, free),
+            Expr::Call { callable, args } => {
+                callable.collect_free_identifiers(bound, free);
+                for arg in args.iter() {
+                    arg.collect_free_identifiers(bound, free);
+                }
+            }
+            Expr::Lambda { params, body } => {
+                let depth = bound.len();
+                bound.extend(params.iter().copied());
+                body.collect_free_identifiers(bound, free);
+                bound.truncate(depth);
+            }
+            Expr::If {
+                cond,
+                then_branch,
+                else_branch,
+            } => {
+                cond.collect_free_identifiers(bound, free);
+                then_branch.collect_free_identifiers(bound, free);
+                else_branch.collect_free_identifiers(bound, free);
+            }
+            Expr::Where { expr, bindings } => {
+                let depth = bound.len();
+                bound.extend(bindings.iter().map(|(name, _)| *name));
+                for (_, value) in bindings.iter() {
+                    value.collect_free_identifiers(bound, free);
+                }
+                expr.collect_free_identifiers(bound, free);
+                bound.truncate(depth);
+            }
+            Expr::Match { expr, arms } => {
+                expr.collect_free_identifiers(bound, free);
+                for arm in arms.iter() {
+                    let depth = bound.len();
+                    arm.pattern.collect_vars(bound);
+                    arm.body.collect_free_identifiers(bound, free);
+                    bound.truncate(depth);
+                }
+            }
+            Expr::Record(fields) => {
+                for (_, value) in fields.iter() {
+                    value.collect_free_identifiers(bound, free);
+                }
+            }
+            Expr::Map(entries) => {
+                for (key, value) in entries.iter() {
+                    key.collect_free_identifiers(bound, free);
+                    value.collect_free_identifiers(bound, free);
+                }
+            }
+            Expr::Array(exprs) | Expr::FormatStr { exprs, .. } => {
+                for expr in exprs.iter() {
+                    expr.collect_free_identifiers(bound, free);
+                }
+            }
+        }
+    }
+}
+
+fn exprs_scope_depth(exprs: &[&Expr<'_>]) -> usize {
+    exprs
+        .iter()
+        .map(|expr| expr.scope_depth())
+        .max()
+        .unwrap_or(0)
+}
+
+fn exprs_ast_eq(exprs: &[&Expr<'_>], others: &[&Expr<'_>]) -> bool {
+    exprs.len() == others.len()
+        && exprs
+            .iter()
+            .zip(others.iter())
+            .all(|(expr, other)| expr.ast_eq(other))
+}
+
+fn fields_ast_eq(fields: &[(&str, &Expr<'_>)], others: &[(&str, &Expr<'_>)]) -> bool {
+    fields.len() == others.len()
+        && fields
+            .iter()
+            .zip(others.iter())
+            .all(|((name, expr), (other_name, other))| name == other_name && expr.ast_eq(other))
+}
+
+fn suffix_ast_eq(suffix: &Option<&Expr<'_>>, other: &Option<&Expr<'_>>) -> bool {
+    match (suffix, other) {
+        (Some(suffix), Some(other)) => suffix.ast_eq(other),
+        (None, None) => true,
+        _ => false,
+    }
+}
+
+fn len_ast_hash<H: Hasher>(len: usize, state: &mut H) {
+    state.write(&(len as u64).to_le_bytes());
+}
+
+fn bytes_ast_hash<H: Hasher>(bytes: &[u8], state: &mut H) {
+    len_ast_hash(bytes.len(), state);
+    state.write(bytes);
+}
+
+fn str_ast_hash<H: Hasher>(text: &str, state: &mut H) {
+    bytes_ast_hash(text.as_bytes(), state);
+}
+
+fn strs_ast_hash<H: Hasher>(texts: &[&str], state: &mut H) {
+    len_ast_hash(texts.len(), state);
+    for text in texts {
+        str_ast_hash(text, state);
+    }
+}
+
+fn exprs_ast_hash<H: Hasher>(exprs: &[&Expr<'_>], state: &mut H) {
+    len_ast_hash(exprs.len(), state);
+    for expr in exprs {
+        expr.ast_hash(state);
+    }
+}
+
+fn fields_ast_hash<H: Hasher>(fields: &[(&str, &Expr<'_>)], state: &mut H) {
+    len_ast_hash(fields.len(), state);
+    for (name, expr) in fields {
+        str_ast_hash(name, state);
+        expr.ast_hash(state);
+    }
+}
+
+fn suffix_ast_hash<H: Hasher>(suffix: &Option<&Expr<'_>>, state: &mut H) {
+    match suffix {
+        Some(expr) => {
+            state.write_u8(1);
+            expr.ast_hash(state);
+        }
+        None => state.write_u8(0),
+    }
+}
+
+/// 64-bit FNV-1a hasher, used for deterministic fingerprints.
+///
+/// Only ever fed bytes and `u8` tags by the `ast_hash` methods, so its output
+/// doesn't depend on endianness or pointer width.
+struct FnvHasher(u64);
+
+impl Default for FnvHasher {
+    fn default() -> Self {
+        FnvHasher(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl Hasher for FnvHasher {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= u64::from(*byte);
+            self.0 = self.0.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
 }
 
 #[derive(Clone, PartialEq, Serialize)]
@@ -102,6 +675,76 @@ pub enum Literal<'a> {
     Bool(bool),
     Str(&'a str),
     Bytes(&'a [u8]),
+    /// Source text of a `123n` literal, without the `n` (sign, radix prefix
+    /// and underscores included). Converted to a value by the analyzer.
+    BigInt(&'a str),
+    /// Source text of a `0.1d` literal, without the `d` (sign and underscores
+    /// included). Converted to a value by the analyzer.
+    Decimal(&'a str),
+}
+
+impl<'a> Literal<'a> {
+    /// Structural equality, comparing floats by bit pattern. See [`Expr::ast_eq`].
+    pub fn ast_eq(&self, other: &Literal<'_>) -> bool {
+        match (self, other) {
+            (
+                Literal::Int { value, suffix },
+                Literal::Int {
+                    value: other_value,
+                    suffix: other_suffix,
+                },
+            ) => value == other_value && suffix_ast_eq(suffix, other_suffix),
+            (
+                Literal::Float { value, suffix },
+                Literal::Float {
+                    value: other_value,
+                    suffix: other_suffix,
+                },
+            ) => value.to_bits() == other_value.to_bits() && suffix_ast_eq(suffix, other_suffix),
+            (Literal::Bool(value), Literal::Bool(other)) => value == other,
+            (Literal::Str(value), Literal::Str(other)) => value == other,
+            (Literal::Bytes(value), Literal::Bytes(other)) => value == other,
+            (Literal::BigInt(value), Literal::BigInt(other)) => value == other,
+            (Literal::Decimal(value), Literal::Decimal(other)) => value == other,
+            _ => false,
+        }
+    }
+
+    /// Hash consistent with [`Literal::ast_eq`]. See [`Expr::ast_hash`].
+    pub fn ast_hash<H: Hasher>(&self, state: &mut H) {
+        match self {
+            Literal::Int { value, suffix } => {
+                state.write_u8(0);
+                state.write(&value.to_le_bytes());
+                suffix_ast_hash(suffix, state);
+            }
+            Literal::Float { value, suffix } => {
+                state.write_u8(1);
+                state.write(&value.to_bits().to_le_bytes());
+                suffix_ast_hash(suffix, state);
+            }
+            Literal::Bool(value) => {
+                state.write_u8(2);
+                state.write_u8(u8::from(*value));
+            }
+            Literal::Str(value) => {
+                state.write_u8(3);
+                str_ast_hash(value, state);
+            }
+            Literal::Bytes(value) => {
+                state.write_u8(4);
+                bytes_ast_hash(value, state);
+            }
+            Literal::BigInt(value) => {
+                state.write_u8(5);
+                str_ast_hash(value, state);
+            }
+            Literal::Decimal(value) => {
+                state.write_u8(6);
+                str_ast_hash(value, state);
+            }
+        }
+    }
 }
 
 impl<'a> core::fmt::Debug for Literal<'a> {
@@ -126,6 +769,8 @@ impl<'a> core::fmt::Debug for Literal<'a> {
             Literal::Bool(b) => write!(f, "Bool({b})"),
             Literal::Str(s) => write!(f, "Str({s:?})"),
             Literal::Bytes(bytes) => write!(f, "Bytes({bytes:?})"),
+            Literal::BigInt(text) => write!(f, "BigInt({text})"),
+            Literal::Decimal(text) => write!(f, "Decimal({text})"),
         }
     }
 }
@@ -140,6 +785,34 @@ pub enum TypeExpr<'a> {
     Record(&'a [(&'a str, TypeExpr<'a>)]),
 }
 
+impl TypeExpr<'_> {
+    /// Hash of the type's structure. See [`Expr::ast_hash`].
+    pub fn ast_hash<H: Hasher>(&self, state: &mut H) {
+        match self {
+            TypeExpr::Path(path) => {
+                state.write_u8(0);
+                str_ast_hash(path, state);
+            }
+            TypeExpr::Parametrized { path, params } => {
+                state.write_u8(1);
+                str_ast_hash(path, state);
+                len_ast_hash(params.len(), state);
+                for param in params.iter() {
+                    param.ast_hash(state);
+                }
+            }
+            TypeExpr::Record(fields) => {
+                state.write_u8(2);
+                len_ast_hash(fields.len(), state);
+                for (name, ty) in fields.iter() {
+                    str_ast_hash(name, state);
+                    ty.ast_hash(state);
+                }
+            }
+        }
+    }
+}
+
 /// A single arm in a match expression.
 #[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct MatchArm<'a> {
@@ -161,3 +834,47 @@ pub enum Pattern<'a> {
     /// None pattern `none` - matches Option::None
     None,
 }
+
+impl<'a> Pattern<'a> {
+    /// Structural equality, comparing float literals by bit pattern. See [`Expr::ast_eq`].
+    pub fn ast_eq(&self, other: &Pattern<'_>) -> bool {
+        match (self, other) {
+            (Pattern::Wildcard, Pattern::Wildcard) | (Pattern::None, Pattern::None) => true,
+            (Pattern::Var(name), Pattern::Var(other_name)) => name == other_name,
+            (Pattern::Literal(literal), Pattern::Literal(other_literal)) => {
+                literal.ast_eq(other_literal)
+            }
+            (Pattern::Some(inner), Pattern::Some(other_inner)) => inner.ast_eq(other_inner),
+            _ => false,
+        }
+    }
+
+    /// Append the variables this pattern binds to `vars`.
+    fn collect_vars(&self, vars: &mut Vec<&'a str>) {
+        match self {
+            Pattern::Var(name) => vars.push(name),
+            Pattern::Some(inner) => inner.collect_vars(vars),
+            Pattern::Wildcard | Pattern::Literal(_) | Pattern::None => {}
+        }
+    }
+
+    /// Hash consistent with [`Pattern::ast_eq`]. See [`Expr::ast_hash`].
+    pub fn ast_hash<H: Hasher>(&self, state: &mut H) {
+        match self {
+            Pattern::Wildcard => state.write_u8(0),
+            Pattern::Var(name) => {
+                state.write_u8(1);
+                str_ast_hash(name, state);
+            }
+            Pattern::Literal(literal) => {
+                state.write_u8(2);
+                literal.ast_hash(state);
+            }
+            Pattern::Some(inner) => {
+                state.write_u8(3);
+                inner.ast_hash(state);
+            }
+            Pattern::None => state.write_u8(4),
+        }
+    }
+}
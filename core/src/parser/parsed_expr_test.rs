@@ -0,0 +1,174 @@
+// Tests for structural AST equality, hashing, and free identifiers.
+
+use crate::parser::{Expr, Literal, parse};
+use bumpalo::Bump;
+
+fn ast_eq(left: &str, right: &str) -> bool {
+    let left_arena = Bump::new();
+    let right_arena = Bump::new();
+    let left = parse(&left_arena, left).unwrap();
+    let right = parse(&right_arena, right).unwrap();
+    left.expr.ast_eq(right.expr)
+}
+
+#[test]
+fn test_ast_eq_same_source() {
+    let sources = [
+        "1 + 2 * 3",
+        "if a then b else c",
+        "f(x, y)[0].field as Int",
+        "(x, y) => x + y where { z = 1 }",
+        "opt match { some (some x) -> x, some none -> -1.5, _ -> 0 }",
+        "{ a = [1, 2], b = {\"k\": b\"v\"} }",
+        "f\"value: {x ?? 0}\"",
+        "a otherwise b",
+        "42`kg` + 1.5`m`",
+    ];
+    for source in sources {
+        assert!(ast_eq(source, source), "Expected `{}` to be ast_eq", source);
+    }
+}
+
+#[test]
+fn test_ast_eq_ignores_spans() {
+    // Whitespace, comments and redundant parentheses only affect spans.
+    assert!(ast_eq("1+2", "  1 +   2 // comment"));
+    assert!(ast_eq("(a * b) + c", "a * b + c"));
+    assert!(ast_eq("x where {x = 1}", "x where {\n  x = 1,\n}"));
+}
+
+#[test]
+fn test_ast_eq_different_structure() {
+    let pairs = [
+        ("1 + 2", "1 - 2"),
+        ("1 + 2", "2 + 1"),
+        ("a * (b + c)", "a * b + c"),
+        ("f(x)", "f(x, y)"),
+        ("a.b", "a.c"),
+        ("[1, 2]", "[1, 2, 3]"),
+        ("{ a = 1 }", "{ b = 1 }"),
+        ("x as Int", "x as Float"),
+        ("some x", "none"),
+        ("1", "1.0"),
+        ("\"a\"", "b\"a\""),
+        ("42`kg`", "42`m`"),
+        ("x match { some y -> y }", "x match { some _ -> y }"),
+        ("(x) => x", "(y) => x"),
+    ];
+    for (left, right) in pairs {
+        assert!(
+            !ast_eq(left, right),
+            "Expected `{}` and `{}` not to be ast_eq",
+            left,
+            right
+        );
+    }
+}
+
+#[test]
+fn test_ast_eq_compares_floats_by_bits() {
+    let nan = Expr::Literal(Literal::Float {
+        value: f64::NAN,
+        suffix: None,
+    });
+    assert!(nan.ast_eq(&nan));
+    assert_ne!(nan, nan);
+
+    let zero = Expr::Literal(Literal::Float {
+        value: 0.0,
+        suffix: None,
+    });
+    let negative_zero = Expr::Literal(Literal::Float {
+        value: -0.0,
+        suffix: None,
+    });
+    assert!(!zero.ast_eq(&negative_zero));
+}
+
+fn fingerprint(source: &str) -> u64 {
+    let arena = Bump::new();
+    parse(&arena, source).unwrap().expr.fingerprint()
+}
+
+#[test]
+fn test_fingerprint_ignores_spans() {
+    assert_eq!(fingerprint("1+2"), fingerprint("  1 +   2 // comment"));
+    assert_eq!(fingerprint("(a * b) + c"), fingerprint("a * b + c"));
+    assert_eq!(
+        fingerprint("f(x) where { x = some 1.5 }"),
+        fingerprint("f(x)\nwhere {\n  x = some 1.5,\n}")
+    );
+}
+
+#[test]
+fn test_fingerprint_near_misses_differ() {
+    let pairs = [
+        ("1 + 2", "1 + 3"),
+        ("1 + 2", "2 + 1"),
+        ("a.b", "a.c"),
+        ("[a, b]", "[[a, b]]"),
+        ("f(a, b)", "f(a)(b)"),
+        ("{ a = 1 }", "{ a: 1 }"),
+        ("x match { some y -> y }", "x match { some _ -> y }"),
+        ("1", "1.0"),
+        ("\"ab\"", "b\"ab\""),
+        ("f\"{a}b\"", "f\"a{b}\""),
+    ];
+    for (left, right) in pairs {
+        assert_ne!(
+            fingerprint(left),
+            fingerprint(right),
+            "Expected `{}` and `{}` to have different fingerprints",
+            left,
+            right
+        );
+    }
+}
+
+#[test]
+fn test_ast_hash_consistent_with_ast_eq() {
+    use core::hash::{BuildHasher, Hasher};
+
+    let build_hasher = hashbrown::DefaultHashBuilder::default();
+    let hash = |expr: &Expr| {
+        let mut hasher = build_hasher.build_hasher();
+        expr.ast_hash(&mut hasher);
+        hasher.finish()
+    };
+
+    let arena = Bump::new();
+    let left = parse(&arena, "(x) => x * 2 + y where { y = 1 }").unwrap();
+    let right = parse(&arena, "(x) => (x * 2) + y where {y=1}").unwrap();
+    assert!(left.expr.ast_eq(right.expr));
+    assert_eq!(hash(left.expr), hash(right.expr));
+}
+
+fn free_identifiers(source: &str) -> Vec<String> {
+    let arena = Bump::new();
+    let parsed = parse(&arena, source).unwrap();
+    parsed
+        .expr
+        .free_identifiers()
+        .into_iter()
+        .map(String::from)
+        .collect()
+}
+
+#[test]
+fn test_free_identifiers_skip_bound_names() {
+    assert_eq!(free_identifiers("a + b * a"), ["a", "b"]);
+    assert_eq!(free_identifiers("(x) => x + y"), ["y"]);
+    assert_eq!(free_identifiers("x + y where { x = y, y = z }"), ["z"]);
+    assert_eq!(
+        free_identifiers("o match { some v -> v + w, none -> v }"),
+        ["o", "w", "v"]
+    );
+    assert_eq!(free_identifiers("Math.Sqrt(x.y)"), ["Math", "x"]);
+}
+
+#[test]
+fn test_fingerprint_is_pinned() {
+    // Fingerprints key persistent caches, so they must not change across
+    // platforms, compiler versions or releases.
+    assert_eq!(fingerprint("1 + x"), 0xeee6_e826_a66d_0b56);
+}
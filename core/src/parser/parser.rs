@@ -1,12 +1,13 @@
 use alloc::string::ToString;
 use bumpalo::Bump;
+use core::num::IntErrorKind;
 use lazy_static::lazy_static;
 use pest::Parser;
 use pest::iterators::Pair;
 use pest::pratt_parser::{Assoc, Op, PrattParser};
 use pest_derive::Parser;
 
-use crate::parser::error::{ParseError, convert_pest_error};
+use crate::parser::error::{INTEGER_OUT_OF_RANGE, ParseError, convert_pest_error};
 use crate::parser::parsed_expr::TypeExpr;
 use crate::parser::syntax::AnnotatedSource;
 use crate::parser::{
@@ -28,7 +29,8 @@ lazy_static! {
         .op(Op::infix(Rule::otherwise_op, Assoc::Right)) // `otherwise`
 
         // Logical operators.
-        .op(Op::prefix(Rule::if_op))                     // `if`
+        .op(Op::prefix(Rule::if_op) |
+            Op::prefix(Rule::if_some_op))                // `if`, `if some x = ...`
         .op(Op::infix(Rule::or, Assoc::Left))            // `or`
         .op(Op::infix(Rule::and, Assoc::Left))           // `and`
         .op(Op::prefix(Rule::not))                       // `not`
@@ -45,6 +47,9 @@ lazy_static! {
             Op::infix(Rule::not_in, Assoc::Left)
         )                                               // `==`, `!=`, `<`, `>`, `<=`, `>=`, `in`, `not in`
 
+        // Option coalescing operator.
+        .op(Op::infix(Rule::coalesce, Assoc::Right))     // `??`
+
         // Arithmetic operators.
         .op(
             Op::infix(Rule::add, Assoc::Left) |
@@ -61,7 +66,8 @@ lazy_static! {
         // Postfix operators.
         .op(Op::postfix(Rule::call_op))                  // `()`
         .op(Op::postfix(Rule::index_op))                 // `[]`
-        .op(Op::postfix(Rule::field_op))                 // `.`
+        .op(Op::postfix(Rule::field_op) |
+            Op::postfix(Rule::optional_field_op))        // `.`, `?.`
         .op(Op::postfix(Rule::cast_op))                  // `as`
         // (highest precedence)
         ;
@@ -119,6 +125,8 @@ impl<'a, 'input> ParseContext<'a, 'input> {
             Rule::expression => self.parse_expression(pair),
             Rule::array => self.parse_array(pair),
             Rule::integer => self.parse_integer(pair),
+            Rule::big_integer => self.parse_big_integer(pair),
+            Rule::decimal => self.parse_decimal(pair),
             Rule::float => self.parse_float(pair),
             Rule::boolean => self.parse_boolean(pair),
             Rule::none => self.parse_none(pair),
@@ -162,6 +170,7 @@ impl<'a, 'input> ParseContext<'a, 'input> {
                 match op.as_rule() {
                     Rule::neg | Rule::not => self.parse_unary_op(op, rhs_value, span),
                     Rule::if_op => self.parse_if_expr(op, rhs_value, span),
+                    Rule::if_some_op => self.parse_if_some_expr(op, rhs_value, span),
                     Rule::lambda_op => self.parse_lambda_expr(op, rhs_value, span),
                     Rule::some_op => self.parse_some_expr(rhs_value, span),
                     _ => unreachable!("Unknown prefix operator: {:?}", op.as_rule()),
@@ -191,6 +200,7 @@ impl<'a, 'input> ParseContext<'a, 'input> {
                     | Rule::in_op
                     | Rule::not_in => self.parse_comparison_op(op, lhs_expr, rhs_expr, span),
                     Rule::otherwise_op => self.parse_otherwise_expr(lhs_expr, rhs_expr, span),
+                    Rule::coalesce => self.parse_coalesce_expr(lhs_expr, rhs_expr, span),
                     _ => unreachable!("Unknown binary operator: {:?}", op.as_rule()),
                 }
             })
@@ -202,6 +212,7 @@ impl<'a, 'input> ParseContext<'a, 'input> {
                     Rule::call_op => self.parse_call_expr(lhs_expr, op, span),
                     Rule::index_op => self.parse_index_expr(lhs_expr, op, span),
                     Rule::field_op => self.parse_field_expr(lhs_expr, op, span),
+                    Rule::optional_field_op => self.parse_optional_field_expr(lhs_expr, op, span),
                     Rule::cast_op => self.parse_cast_expr(lhs_expr, op, span),
                     Rule::where_op => self.parse_where_expr(lhs_expr, op, span),
                     Rule::match_op => self.parse_match_expr(lhs_expr, op, span),
@@ -259,6 +270,30 @@ impl<'a, 'input> ParseContext<'a, 'input> {
         ))
     }
 
+    /// Desugars `if some x = opt then a else b` into `opt match { some x -> a, _ -> b }`.
+    fn parse_if_some_expr(
+        &self,
+        op: Pair<Rule>,
+        else_branch: &'a Expr<'a>,
+        span: Span,
+    ) -> Result<&'a Expr<'a>, pest::error::Error<Rule>> {
+        let mut pairs = op.into_inner();
+        let pattern = self.parse_pattern(pairs.next().unwrap())?;
+        let option = self.parse_expr(pairs.next().unwrap())?;
+        let then_branch = self.parse_expr(pairs.next().unwrap())?;
+        let arms = self.arena.alloc_slice_fill_iter([
+            MatchArm {
+                pattern,
+                body: then_branch,
+            },
+            MatchArm {
+                pattern: self.arena.alloc(Pattern::Wildcard),
+                body: else_branch,
+            },
+        ]);
+        Ok(self.alloc_with_span(Expr::Match { expr: option, arms }, span))
+    }
+
     fn parse_lambda_expr(
         &self,
         op: Pair<Rule>,
@@ -363,6 +398,15 @@ impl<'a, 'input> ParseContext<'a, 'input> {
         Ok(self.alloc_with_span(Expr::Otherwise { primary, fallback }, span))
     }
 
+    fn parse_coalesce_expr(
+        &self,
+        option: &'a Expr<'a>,
+        default: &'a Expr<'a>,
+        span: Span,
+    ) -> Result<&'a Expr<'a>, pest::error::Error<Rule>> {
+        Ok(self.alloc_with_span(Expr::Coalesce { option, default }, span))
+    }
+
     // Postfix operators
     fn parse_call_expr(
         &self,
@@ -396,6 +440,45 @@ impl<'a, 'input> ParseContext<'a, 'input> {
         op: Pair<Rule>,
         span: Span,
     ) -> Result<&'a Expr<'a>, pest::error::Error<Rule>> {
+        let field = self.parse_field_name(op)?;
+        Ok(self.alloc_with_span(Expr::Field { value, field }, span))
+    }
+
+    /// Desugars `opt?.x` into `opt match { some v -> some v.x, none -> none }`.
+    ///
+    /// The bound variable uses a name that cannot be written in source code,
+    /// so it never shadows user bindings.
+    fn parse_optional_field_expr(
+        &self,
+        option: &'a Expr<'a>,
+        op: Pair<Rule>,
+        span: Span,
+    ) -> Result<&'a Expr<'a>, pest::error::Error<Rule>> {
+        const BINDING: &str = "$value";
+        let field = self.parse_field_name(op)?;
+        let value = self.alloc_with_span(Expr::Ident(BINDING), span.clone());
+        let field_value = self.alloc_with_span(Expr::Field { value, field }, span.clone());
+        let arms = self.arena.alloc_slice_fill_iter([
+            MatchArm {
+                pattern: self
+                    .arena
+                    .alloc(Pattern::Some(self.arena.alloc(Pattern::Var(BINDING)))),
+                body: self.alloc_with_span(
+                    Expr::Option {
+                        inner: Some(field_value),
+                    },
+                    span.clone(),
+                ),
+            },
+            MatchArm {
+                pattern: self.arena.alloc(Pattern::None),
+                body: self.alloc_with_span(Expr::Option { inner: None }, span.clone()),
+            },
+        ]);
+        Ok(self.alloc_with_span(Expr::Match { expr: option, arms }, span))
+    }
+
+    fn parse_field_name(&self, op: Pair<Rule>) -> Result<&'a str, pest::error::Error<Rule>> {
         let op_span = op.as_span();
         let field = op
             .into_inner()
@@ -409,13 +492,7 @@ impl<'a, 'input> ParseContext<'a, 'input> {
                 )
             })?
             .as_str();
-        Ok(self.alloc_with_span(
-            Expr::Field {
-                value,
-                field: self.reslice(field),
-            },
-            span,
-        ))
+        Ok(self.reslice(field))
     }
 
     fn parse_type_expr(&self, pair: Pair<Rule>) -> Result<TypeExpr<'a>, pest::error::Error<Rule>> {
@@ -620,27 +697,8 @@ impl<'a, 'input> ParseContext<'a, 'input> {
     ) -> Result<Literal<'a>, pest::error::Error<Rule>> {
         let pair_span = pair.as_span();
         let mut inner = pair.into_inner();
-        let integer_number = inner.next().unwrap();
-
-        let number_str = integer_number.as_str().replace('_', "");
-        let mut inner_num = integer_number.into_inner();
-        let integer_type = inner_num.next().unwrap();
-
-        let value = match integer_type.as_rule() {
-            Rule::dec_integer => i64::from_str_radix(&number_str, 10),
-            Rule::bin_integer => i64::from_str_radix(&number_str.replacen("0b", "", 1), 2),
-            Rule::oct_integer => i64::from_str_radix(&number_str.replacen("0o", "", 1), 8),
-            Rule::hex_integer => i64::from_str_radix(&number_str.replacen("0x", "", 1), 16),
-            _ => unreachable!("Unknown integer format: {:?}", integer_type.as_rule()),
-        }
-        .map_err(|_| {
-            pest::error::Error::new_from_span(
-                pest::error::ErrorVariant::CustomError {
-                    message: "invalid integer literal in pattern".to_string(),
-                },
-                pair_span,
-            )
-        })?;
+        let value =
+            parse_integer_number(inner.next().unwrap(), "invalid integer literal in pattern")?;
 
         // Patterns don't support suffixes
         if inner.next().is_some() {
@@ -742,41 +800,67 @@ impl<'a, 'input> ParseContext<'a, 'input> {
 
     fn parse_array(&self, pair: Pair<Rule>) -> Result<&'a Expr<'a>, pest::error::Error<Rule>> {
         let pair_span = pair.as_span();
-        let items_iter = pair.into_inner().map(|p| self.parse_expr(p));
+        let mut inner = pair.into_inner();
+        if let Some(comprehension) = inner.clone().nth(1)
+            && comprehension.as_rule() == Rule::comprehension
+        {
+            let body = self.parse_expr(inner.next().unwrap())?;
+            return self.parse_comprehension(body, comprehension, pair_span.into());
+        }
+        let items_iter = inner.map(|p| self.parse_expr(p));
         let items = self.arena.alloc_slice_try_fill_iter(items_iter)?;
         let node = self.arena.alloc(Expr::Array(items));
         self.ann.add_span(node, pair_span.into());
         Ok(node)
     }
 
+    /// Desugars `[body for x in source if cond]` into
+    /// `#map(#filter(source, (x) => cond), (x) => body)`.
+    ///
+    /// Without a filter, the source is passed to `#map` directly.
+    ///
+    /// Map comprehensions `{key: value for x in source}` reuse this with a
+    /// singleton map `{key: value}` as the body, and wrap the result in
+    /// `#merge_maps`. Later entries win when keys repeat.
+    fn parse_comprehension(
+        &self,
+        body: &'a Expr<'a>,
+        pair: Pair<Rule>,
+        span: Span,
+    ) -> Result<&'a Expr<'a>, pest::error::Error<Rule>> {
+        let mut inner = pair.into_inner();
+        let params = self
+            .arena
+            .alloc_slice_copy(&[self.reslice(inner.next().unwrap().as_str())]);
+        let mut source = self.parse_expr(inner.next().unwrap())?;
+        if let Some(filter) = inner.next() {
+            let cond = self.parse_expr(filter.into_inner().next().unwrap())?;
+            let predicate = self.alloc_with_span(Expr::Lambda { params, body: cond }, span.clone());
+            source = self.builtin_call("#filter", &[source, predicate], span.clone());
+        }
+        let mapper = self.alloc_with_span(Expr::Lambda { params, body }, span.clone());
+        Ok(self.builtin_call("#map", &[source, mapper], span))
+    }
+
+    /// Builds `builtin(args...)` for desugared expressions.
+    ///
+    /// `builtin` is one of the internal builtins (named with a leading `#`),
+    /// which user bindings cannot shadow.
+    fn builtin_call(
+        &self,
+        builtin: &'static str,
+        args: &[&'a Expr<'a>],
+        span: Span,
+    ) -> &'a Expr<'a> {
+        let callable = self.alloc_with_span(Expr::Ident(builtin), span.clone());
+        let args = self.arena.alloc_slice_copy(args);
+        self.alloc_with_span(Expr::Call { callable, args }, span)
+    }
+
     fn parse_integer(&self, pair: Pair<Rule>) -> Result<&'a Expr<'a>, pest::error::Error<Rule>> {
         let pair_span = pair.as_span();
         let mut inner = pair.into_inner();
-        let integer_number = inner.next().unwrap();
-
-        // The integer_number is ${ "-"? ~ integer_literal }
-        // So we can get the full signed string
-        let number_str = integer_number.as_str().replace('_', "");
-
-        // And check what kind of integer it is from the inner tokens
-        let mut inner_num = integer_number.into_inner();
-        let integer_type = inner_num.next().unwrap();
-
-        let value = match integer_type.as_rule() {
-            Rule::dec_integer => i64::from_str_radix(&number_str, 10),
-            Rule::bin_integer => i64::from_str_radix(&number_str.replacen("0b", "", 1), 2),
-            Rule::oct_integer => i64::from_str_radix(&number_str.replacen("0o", "", 1), 8),
-            Rule::hex_integer => i64::from_str_radix(&number_str.replacen("0x", "", 1), 16),
-            _ => unreachable!("Unknown integer format: {:?}", integer_type.as_rule()),
-        }
-        .map_err(|_| {
-            pest::error::Error::new_from_span(
-                pest::error::ErrorVariant::CustomError {
-                    message: "invalid integer literal".to_string(),
-                },
-                pair_span,
-            )
-        })?;
+        let value = parse_integer_number(inner.next().unwrap(), "invalid integer literal")?;
 
         let suffix = match inner.next() {
             Some(s) => {
@@ -793,6 +877,27 @@ impl<'a, 'input> ParseContext<'a, 'input> {
         Ok(node)
     }
 
+    fn parse_big_integer(
+        &self,
+        pair: Pair<Rule>,
+    ) -> Result<&'a Expr<'a>, pest::error::Error<Rule>> {
+        let pair_span = pair.as_span();
+        let integer_number = pair.into_inner().next().unwrap();
+        let text = self.arena.alloc_str(integer_number.as_str());
+        let node = self.arena.alloc(Expr::Literal(Literal::BigInt(text)));
+        self.ann.add_span(node, pair_span.into());
+        Ok(node)
+    }
+
+    fn parse_decimal(&self, pair: Pair<Rule>) -> Result<&'a Expr<'a>, pest::error::Error<Rule>> {
+        let pair_span = pair.as_span();
+        let decimal_number = pair.into_inner().next().unwrap();
+        let text = self.arena.alloc_str(decimal_number.as_str());
+        let node = self.arena.alloc(Expr::Literal(Literal::Decimal(text)));
+        self.ann.add_span(node, pair_span.into());
+        Ok(node)
+    }
+
     fn parse_float(&self, pair: Pair<Rule>) -> Result<&'a Expr<'a>, pest::error::Error<Rule>> {
         let pair_span = pair.as_span();
         let mut inner = pair.into_inner();
@@ -984,7 +1089,19 @@ impl<'a, 'input> ParseContext<'a, 'input> {
 
     fn parse_map(&self, pair: Pair<Rule>) -> Result<&'a Expr<'a>, pest::error::Error<Rule>> {
         let pair_span = pair.as_span();
-        let entries_iter = pair.into_inner().map(|p| self.parse_map_entry(p));
+        let mut inner = pair.into_inner();
+        if let Some(comprehension) = inner.clone().nth(1)
+            && comprehension.as_rule() == Rule::comprehension
+        {
+            let span = Span::from(pair_span);
+            let entry = self.parse_map_entry(inner.next().unwrap())?;
+            // Each element becomes a singleton map, so keys are checked like map literal keys
+            let entries = self.arena.alloc_slice_copy(&[entry]);
+            let body = self.alloc_with_span(Expr::Map(entries), span.clone());
+            let maps = self.parse_comprehension(body, comprehension, span.clone())?;
+            return Ok(self.builtin_call("#merge_maps", &[maps], span));
+        }
+        let entries_iter = inner.map(|p| self.parse_map_entry(p));
         let entries = self.arena.alloc_slice_try_fill_iter(entries_iter)?;
         let span = Span::from(pair_span);
         let node = self.arena.alloc(Expr::Map(entries));
@@ -1058,6 +1175,44 @@ impl<'a, 'input> ParseContext<'a, 'input> {
     }
 }
 
+/// Parses an `integer_number` pair (`"-"? ~ integer_literal`) into an `i64`.
+///
+/// Literals that overflow get a dedicated error pointing at the digits, so
+/// the diagnostic can suggest alternatives; other failures use `invalid_message`.
+fn parse_integer_number(
+    integer_number: Pair<Rule>,
+    invalid_message: &str,
+) -> Result<i64, pest::error::Error<Rule>> {
+    let span = integer_number.as_span();
+
+    // The integer_number is ${ "-"? ~ integer_literal }
+    // So we can get the full signed string
+    let number_str = integer_number.as_str().replace('_', "");
+
+    // And check what kind of integer it is from the inner tokens
+    let integer_type = integer_number.into_inner().next().unwrap();
+
+    match integer_type.as_rule() {
+        Rule::dec_integer => i64::from_str_radix(&number_str, 10),
+        Rule::bin_integer => i64::from_str_radix(&number_str.replacen("0b", "", 1), 2),
+        Rule::oct_integer => i64::from_str_radix(&number_str.replacen("0o", "", 1), 8),
+        Rule::hex_integer => i64::from_str_radix(&number_str.replacen("0x", "", 1), 16),
+        _ => unreachable!("Unknown integer format: {:?}", integer_type.as_rule()),
+    }
+    .map_err(|error| {
+        let message = match error.kind() {
+            IntErrorKind::PosOverflow | IntErrorKind::NegOverflow => INTEGER_OUT_OF_RANGE,
+            _ => invalid_message,
+        };
+        pest::error::Error::new_from_span(
+            pest::error::ErrorVariant::CustomError {
+                message: message.to_string(),
+            },
+            span,
+        )
+    })
+}
+
 /// Default maximum nesting depth for expression parsing.
 /// This prevents stack overflow from deeply nested expressions like `(((((...(1)...)))))`.
 const DEFAULT_MAX_PARSE_DEPTH: usize = 500;
@@ -1318,6 +1473,161 @@ mod tests {
         assert_eq!(parsed.ann.span_of(else_branch), Some(Span::new(29, 33)));
     }
 
+    #[test]
+    fn test_if_some_desugars_to_match() {
+        let arena = Bump::new();
+        let input = "if some x = opt then x else 0";
+        let parsed = parse(&arena, input).unwrap();
+
+        assert_eq!(
+            *parsed.expr,
+            Expr::Match {
+                expr: arena.alloc(Expr::Ident("opt")),
+                arms: &[
+                    MatchArm {
+                        pattern: arena.alloc(Pattern::Some(arena.alloc(Pattern::Var("x")))),
+                        body: arena.alloc(Expr::Ident("x")),
+                    },
+                    MatchArm {
+                        pattern: arena.alloc(Pattern::Wildcard),
+                        body: arena.alloc(Expr::Literal(Literal::Int {
+                            value: 0,
+                            suffix: None,
+                        })),
+                    },
+                ],
+            }
+        );
+
+        assert_eq!(parsed.ann.span_of(parsed.expr), Some(Span::new(0, 29)));
+        let Expr::Match { expr, arms } = parsed.expr else {
+            panic!("Expected Match expression");
+        };
+        assert_eq!(parsed.ann.span_of(expr), Some(Span::new(12, 15)));
+        assert_eq!(parsed.ann.span_of(arms[0].body), Some(Span::new(21, 22)));
+        assert_eq!(parsed.ann.span_of(arms[1].body), Some(Span::new(28, 29)));
+    }
+
+    #[test]
+    fn test_if_some_matches_explicit_match() {
+        let arena = Bump::new();
+        let sugar = parse(&arena, "if some (some y) = a.b then y * 2 else -1").unwrap();
+        let explicit = parse(&arena, "a.b match { some (some y) -> y * 2, _ -> -1 }").unwrap();
+        assert_eq!(sugar.expr, explicit.expr);
+    }
+
+    #[test]
+    fn test_if_some_does_not_capture_plain_if() {
+        let arena = Bump::new();
+        // Without `=` the condition is an ordinary expression.
+        let parsed = parse(&arena, "if some x == y then 1 else 0").unwrap();
+        assert!(matches!(parsed.expr, Expr::If { .. }));
+        let parsed = parse(&arena, "if something then 1 else 0").unwrap();
+        assert!(matches!(parsed.expr, Expr::If { .. }));
+    }
+
+    #[test]
+    fn test_optional_field_desugars_to_match() {
+        let arena = Bump::new();
+        let parsed = parse(&arena, "opt?.x").unwrap();
+
+        assert_eq!(
+            *parsed.expr,
+            Expr::Match {
+                expr: arena.alloc(Expr::Ident("opt")),
+                arms: &[
+                    MatchArm {
+                        pattern: arena.alloc(Pattern::Some(arena.alloc(Pattern::Var("$value")))),
+                        body: arena.alloc(Expr::Option {
+                            inner: Some(arena.alloc(Expr::Field {
+                                value: arena.alloc(Expr::Ident("$value")),
+                                field: "x",
+                            })),
+                        }),
+                    },
+                    MatchArm {
+                        pattern: arena.alloc(Pattern::None),
+                        body: arena.alloc(Expr::Option { inner: None }),
+                    },
+                ],
+            }
+        );
+
+        assert_eq!(parsed.ann.span_of(parsed.expr), Some(Span::new(0, 6)));
+        let Expr::Match { expr, arms } = parsed.expr else {
+            panic!("Expected Match expression");
+        };
+        assert_eq!(parsed.ann.span_of(expr), Some(Span::new(0, 3)));
+        assert_eq!(parsed.ann.span_of(arms[0].body), Some(Span::new(0, 6)));
+    }
+
+    /// Builds a call to the internal builtin `name`, as the parser desugars to.
+    fn builtin_call<'a>(arena: &'a Bump, name: &'a str, args: &[&'a Expr<'a>]) -> &'a Expr<'a> {
+        arena.alloc(Expr::Call {
+            callable: arena.alloc(Expr::Ident(name)),
+            args: arena.alloc_slice_copy(args),
+        })
+    }
+
+    #[test]
+    fn test_comprehension_desugars_to_map() {
+        let arena = Bump::new();
+        let sugar = parse(&arena, "[x * 2 for x in xs]").unwrap();
+        let mapper = parse(&arena, "(x) => x * 2").unwrap().expr;
+        let xs = arena.alloc(Expr::Ident("xs"));
+        assert_eq!(sugar.expr, builtin_call(&arena, "#map", &[xs, mapper]));
+        assert_eq!(sugar.ann.span_of(sugar.expr), Some(Span::new(0, 19)));
+    }
+
+    #[test]
+    fn test_comprehension_with_filter_desugars_to_filter_and_map() {
+        let arena = Bump::new();
+        let sugar = parse(&arena, "[x * 2 for x in xs if x > 0]").unwrap();
+        let predicate = parse(&arena, "(x) => x > 0").unwrap().expr;
+        let mapper = parse(&arena, "(x) => x * 2").unwrap().expr;
+        let xs = arena.alloc(Expr::Ident("xs"));
+        let filtered = builtin_call(&arena, "#filter", &[xs, predicate]);
+        assert_eq!(
+            sugar.expr,
+            builtin_call(&arena, "#map", &[filtered, mapper])
+        );
+    }
+
+    #[test]
+    fn test_map_comprehension_desugars_to_merge_maps() {
+        let arena = Bump::new();
+        let sugar = parse(&arena, "{x: x * 2 for x in xs if x > 0}").unwrap();
+        let predicate = parse(&arena, "(x) => x > 0").unwrap().expr;
+        let mapper = parse(&arena, "(x) => {x: x * 2}").unwrap().expr;
+        let xs = arena.alloc(Expr::Ident("xs"));
+        let filtered = builtin_call(&arena, "#filter", &[xs, predicate]);
+        let maps = builtin_call(&arena, "#map", &[filtered, mapper]);
+        assert_eq!(sugar.expr, builtin_call(&arena, "#merge_maps", &[maps]));
+        assert_eq!(sugar.ann.span_of(sugar.expr), Some(Span::new(0, 31)));
+    }
+
+    #[test]
+    fn test_array_with_for_identifier_is_not_a_comprehension() {
+        let arena = Bump::new();
+        let parsed = parse(&arena, "[for, x]").unwrap();
+        assert!(matches!(parsed.expr, Expr::Array(items) if items.len() == 2));
+    }
+
+    #[test]
+    fn test_optional_field_chained() {
+        let arena = Bump::new();
+        let parsed = parse(&arena, "opt?.a?.b").unwrap();
+
+        // The outer match scrutinizes the desugared `opt?.a`.
+        let Expr::Match { expr, .. } = parsed.expr else {
+            panic!("Expected Match expression");
+        };
+        let Expr::Match { expr: inner, .. } = expr else {
+            panic!("Expected nested Match expression");
+        };
+        assert_eq!(**inner, Expr::Ident("opt"));
+    }
+
     #[test]
     fn test_lambda_expr() {
         let arena = Bump::new();
@@ -265,6 +265,44 @@ fn test_otherwise() {
     );
 }
 
+#[test]
+fn test_if_some_vs_binary_and_otherwise() {
+    let arena = Bump::new();
+    assert_eq!(
+        ast(&arena, "if some x = a then x else b + c"),
+        ast(&arena, "if some x = a then x else (b + c)")
+    );
+    assert_eq!(
+        ast(&arena, "if some x = a then x else b otherwise c"),
+        ast(&arena, "(if some x = a then x else b) otherwise c")
+    );
+}
+
+#[test]
+fn test_coalesce() {
+    let arena = Bump::new();
+    // `??` is right-associative so options can be chained
+    assert_eq!(ast(&arena, "a ?? b ?? c"), ast(&arena, "a ?? (b ?? c)"));
+    // Binds tighter than comparison, looser than arithmetic
+    assert_eq!(ast(&arena, "a ?? b == c"), ast(&arena, "(a ?? b) == c"));
+    assert_eq!(ast(&arena, "a ?? b + c"), ast(&arena, "a ?? (b + c)"));
+    assert_eq!(ast(&arena, "some a ?? b"), ast(&arena, "(some a) ?? b"));
+    assert_eq!(
+        ast(&arena, "a ?? b otherwise c"),
+        ast(&arena, "(a ?? b) otherwise c")
+    );
+}
+
+#[test]
+fn test_optional_field() {
+    let arena = Bump::new();
+    // `?.` is a postfix operator, chained left to right like `.`
+    assert_eq!(ast(&arena, "a?.b?.c"), ast(&arena, "(a?.b)?.c"));
+    assert_eq!(ast(&arena, "a.b?.c"), ast(&arena, "(a.b)?.c"));
+    assert_eq!(ast(&arena, "some a?.b"), ast(&arena, "some (a?.b)"));
+    assert_eq!(ast(&arena, "a?.b ?? c"), ast(&arena, "(a?.b) ?? c"));
+}
+
 #[test]
 fn test_otherwise_vs_if() {
     let arena = Bump::new();
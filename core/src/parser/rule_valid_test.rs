@@ -52,6 +52,14 @@ rule_examples! {
     ident => ["foo", "_bar123", "`0`", "`some-name`", "`with.dots`", "`:`", "`/path`"],
     call_op => ["foo()", "foo(1)", "foo(1, 2, 3)", "f(\"x\")", "foo.bar(x)"],
     array => ["[]", "[1]", "[1, 2, 3]", "[a, b,]"],
+    comprehension => [
+        "[x * 2 for x in xs]",
+        "[x for x in xs if x > 0]",
+        "[[y for y in x] for x in xs]",
+        "{x: x * 2 for x in xs}",
+        "{k: v for k in ks if k > 0}",
+    ],
+    comprehension_filter => ["[x for x in xs if x > 0]"],
     map => ["{}", "{a: 1}", "{a: 1, b: 2,}", "{foo(): bar()}"],
     record => ["{x = 1}", "{x = 1, y = 2}", "Record {}"],
     cast_op => ["1 as Integer", "\"abc\" as Bytes", "{x = 1} as Record[x: Integer]"],
@@ -59,10 +67,17 @@ rule_examples! {
     mul => ["1 * 2", "a * ( b + c )"],
     pow => ["2 ^ 3", "a ^ b"],
     and => ["true and false", "a and b"],
-    if_op => ["if true then 1 else 0", "if x then y else z"],
+    if_op => ["if true then 1 else 0", "if x then y else z", "if some_flag then 1 else 0"],
+    if_some_op => [
+        "if some x = opt then x else 0",
+        "if some (some x) = opt then x else 0",
+        "if some _ = opt then true else false",
+        "if some x = f(y) then x + 1 else if some z = w then z else 0",
+    ],
     where_op => ["a where {a = 1}", "x + y where {x = 1, y = 2}"],
     format_string => ["f\"Hello, {name}!\"", "f'Value: {x}'"],
     field_op => ["foo.bar", "a.b.c"],
+    optional_field_op => ["opt?.x", "opt?.a?.b", "f(x)?.y"],
     lambda_op => [
         "(a) => a + 1",
         "(x, y) => x * y",
@@ -81,6 +96,7 @@ rule_examples! {
     in_op => ["5 in [1, 2, 3]", "\"lo\" in \"hello\"", "key in map"],
     not_in => ["5 not in [1, 2, 3]", "\"x\" not in \"hello\"", "key not in map"],
     otherwise_op => ["1 / 0 otherwise -1", "map[key] otherwise \"\""],
+    coalesce => ["opt ?? 0", "a ?? b ?? c", "map[key] ?? \"\""],
     type_expr => [
         "value as Integer",
         "value as Map[String, Float]",
@@ -30,6 +30,15 @@ impl<'a, T> AnnotatedSource<'a, T> {
     pub fn snippet(&self, span: Span) -> &str {
         &self.source[span.0]
     }
+    /// Span of a slice of the source, such as a binding or field name.
+    ///
+    /// Returns `None` for strings that do not point into the source (e.g.,
+    /// names synthesized by desugaring).
+    pub fn span_of_str(&self, s: &str) -> Option<Span> {
+        let start = (s.as_ptr() as usize).checked_sub(self.source.as_ptr() as usize)?;
+        let end = start + s.len();
+        (end <= self.source.len()).then(|| Span::new(start, end))
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
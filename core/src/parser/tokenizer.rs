@@ -0,0 +1,265 @@
+//! Tokenizer for editor integrations (e.g., semantic highlighting).
+//!
+//! Tokens are derived from the pest grammar: rules that form a single token
+//! (identifiers, literals, operators) are classified by rule, and the text the
+//! grammar matches between rules (keywords like `where`, delimiters, `=>`) is
+//! split into tokens and classified by its characters.
+//!
+//! Source that does not parse yet (the user is typing) is tokenized up to the
+//! parse error, one token at a time: literals and identifiers by their grammar
+//! rules, everything else by its characters.
+
+use pest::{Parser, error::InputLocation, iterators::Pair};
+
+use crate::{
+    Vec,
+    parser::{ExpressionParser, Rule, Span},
+};
+
+/// Classification of a token, for highlighting.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenKind {
+    /// Reserved word: `if`, `where`, `match`, `not`, `and`, `some`, `none`, ...
+    Keyword,
+    /// Variable, parameter, field, or package name.
+    Identifier,
+    /// Type name in a cast, such as `Int` in `x as Int`.
+    TypeName,
+    /// Integer, float, or decimal literal.
+    Number,
+    /// String or bytes literal.
+    String,
+    /// `true` or `false`.
+    Boolean,
+    /// Symbolic operator: `+`, `==`, `??`, `=>`, `=`, ...
+    Operator,
+    /// Brackets, braces, parentheses, `,`, `:`, and `.`.
+    Punctuation,
+    /// Opening `f"` or closing `"` of a format string.
+    FormatStringDelimiter,
+    /// Literal text inside a format string.
+    FormatStringText,
+    /// `{` or `}` around an expression interpolated in a format string.
+    InterpolationDelimiter,
+    /// `// ...` comment.
+    Comment,
+}
+
+/// Tokenize `source`, yielding each token's kind and span in source order.
+///
+/// Whitespace is skipped. If the source does not parse, only the tokens
+/// before the parse error are yielded, and they are classified without the
+/// context of the surrounding expression (e.g., a type name in an unfinished
+/// cast is an identifier).
+pub fn tokenize(source: &str) -> impl Iterator<Item = (TokenKind, Span)> {
+    let mut tokenizer = Tokenizer {
+        source,
+        tokens: Vec::new(),
+    };
+    match ExpressionParser::parse(Rule::main, source) {
+        Ok(mut pairs) => {
+            let main = pairs.next().unwrap(); // Safe: Rule::main always produces one pair.
+            tokenizer.visit(main);
+        }
+        Err(err) => {
+            let end = match err.location {
+                InputLocation::Pos(pos) => pos,
+                InputLocation::Span((start, _)) => start,
+            };
+            tokenizer.scan(end);
+        }
+    }
+    tokenizer.tokens.into_iter()
+}
+
+/// Rules tried, in order, at each token of source that does not parse. The
+/// first three are the string literals.
+const SCAN_RULES: &[Rule] = &[
+    Rule::format_string,
+    Rule::bytes,
+    Rule::string,
+    Rule::decimal,
+    Rule::big_integer,
+    Rule::float,
+    Rule::integer,
+    Rule::ident,
+    Rule::boolean,
+];
+
+struct Tokenizer<'s> {
+    source: &'s str,
+    tokens: Vec<(TokenKind, Span)>,
+}
+
+impl<'s> Tokenizer<'s> {
+    fn visit(&mut self, pair: Pair<'_, Rule>) {
+        let span = Span::from(pair.as_span());
+        if let Some(kind) = leaf_kind(pair.as_rule()) {
+            self.tokens.push((kind, span));
+            return;
+        }
+        let parent = pair.as_rule();
+        let mut cursor = span.0.start;
+        for child in pair.into_inner() {
+            let child_span = child.as_span();
+            self.gap(parent, cursor, child_span.start());
+            cursor = child_span.end();
+            self.visit(child);
+        }
+        self.gap(parent, cursor, span.0.end);
+    }
+
+    /// Split the source up to `end`, which does not parse as a whole, into
+    /// tokens.
+    fn scan(&mut self, end: usize) {
+        let mut offset = 0;
+        while offset < end {
+            let rest = &self.source[offset..end];
+            let c = rest.chars().next().unwrap(); // Safe: `rest` is not empty.
+            if c.is_whitespace() {
+                offset += c.len_utf8();
+                continue;
+            }
+            let string_start = matches!(c, '"' | '\'')
+                || (matches!(c, 'b' | 'f') && rest[1..].starts_with(['"', '\'']));
+            // Keep `f` of an unterminated `f"` from matching as an identifier
+            let rules = if string_start {
+                &SCAN_RULES[..3]
+            } else {
+                SCAN_RULES
+            };
+            // A leading `-` is an operator here, not part of a number
+            let matched = if c == '-' || rest.starts_with("//") {
+                None
+            } else {
+                rules
+                    .iter()
+                    .find_map(|rule| ExpressionParser::parse(*rule, rest).ok())
+                    .and_then(|mut pairs| pairs.next())
+            };
+            offset += match matched {
+                Some(pair) => {
+                    // The pair's spans are relative to `rest`
+                    let length = pair.as_span().end();
+                    let mut inner = Tokenizer {
+                        source: rest,
+                        tokens: Vec::new(),
+                    };
+                    inner.visit(pair);
+                    self.tokens
+                        .extend(inner.tokens.into_iter().map(|(kind, span)| {
+                            (kind, Span::new(offset + span.0.start, offset + span.0.end))
+                        }));
+                    length
+                }
+                None if string_start => {
+                    // An unterminated string runs to the end
+                    self.tokens
+                        .push((TokenKind::String, Span::new(offset, end)));
+                    rest.len()
+                }
+                None => self.gap_token(Rule::main, offset, end),
+            };
+        }
+    }
+
+    /// Split text matched directly by `parent` (outside its child rules) into
+    /// tokens.
+    fn gap(&mut self, parent: Rule, start: usize, end: usize) {
+        if parent == Rule::format_string {
+            // `f"` or the closing quote: atomic, so no whitespace to skip
+            if start < end {
+                self.tokens
+                    .push((TokenKind::FormatStringDelimiter, Span::new(start, end)));
+            }
+            return;
+        }
+        let mut offset = start;
+        while offset < end {
+            let c = self.source[offset..end].chars().next().unwrap(); // Safe: not empty.
+            if c.is_whitespace() {
+                offset += c.len_utf8();
+                continue;
+            }
+            offset += self.gap_token(parent, offset, end);
+        }
+    }
+
+    /// Add the token at `offset`, which is not whitespace, classified by its
+    /// characters, and return its length. The token ends by `end`.
+    fn gap_token(&mut self, parent: Rule, offset: usize, end: usize) -> usize {
+        let rest = &self.source[offset..end];
+        let c = rest.chars().next().unwrap(); // Safe: `rest` is not empty.
+        let length = if rest.starts_with("//") {
+            rest.find('\n').unwrap_or(rest.len())
+        } else if is_word_char(c) {
+            rest.find(|c| !is_word_char(c)).unwrap_or(rest.len())
+        } else if is_delimiter(c) {
+            c.len_utf8()
+        } else {
+            rest.find(|c: char| c.is_whitespace() || is_word_char(c) || is_delimiter(c))
+                .unwrap_or(rest.len())
+        };
+        let text = &rest[..length];
+        let kind = if text.starts_with("//") {
+            TokenKind::Comment
+        } else if text == "Record" {
+            TokenKind::TypeName
+        } else if is_word_char(c) {
+            TokenKind::Keyword
+        } else if parent == Rule::format_expr && matches!(c, '{' | '}') {
+            TokenKind::InterpolationDelimiter
+        } else if is_delimiter(c) {
+            TokenKind::Punctuation
+        } else {
+            TokenKind::Operator
+        };
+        self.tokens.push((kind, Span::new(offset, offset + length)));
+        length
+    }
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_'
+}
+
+/// Characters that always form a token on their own.
+fn is_delimiter(c: char) -> bool {
+    matches!(c, '(' | ')' | '[' | ']' | '{' | '}' | ',' | ':' | '.')
+}
+
+/// The kind of rules that form a single token.
+fn leaf_kind(rule: Rule) -> Option<TokenKind> {
+    Some(match rule {
+        Rule::ident | Rule::pattern_var | Rule::pattern_wildcard => TokenKind::Identifier,
+        Rule::type_path => TokenKind::TypeName,
+        Rule::integer | Rule::float | Rule::big_integer | Rule::decimal => TokenKind::Number,
+        Rule::string | Rule::bytes => TokenKind::String,
+        Rule::boolean => TokenKind::Boolean,
+        Rule::format_text | Rule::format_text_single => TokenKind::FormatStringText,
+        Rule::none
+        | Rule::pattern_none
+        | Rule::some_op
+        | Rule::pattern_some
+        | Rule::not
+        | Rule::not_in
+        | Rule::in_op
+        | Rule::and
+        | Rule::or
+        | Rule::otherwise_op => TokenKind::Keyword,
+        Rule::neg
+        | Rule::pow
+        | Rule::mul
+        | Rule::div
+        | Rule::add
+        | Rule::sub
+        | Rule::eq
+        | Rule::neq
+        | Rule::le
+        | Rule::ge
+        | Rule::lt
+        | Rule::gt
+        | Rule::coalesce => TokenKind::Operator,
+        _ => return None,
+    })
+}
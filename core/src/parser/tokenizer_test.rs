@@ -0,0 +1,155 @@
+// Tests for the editor tokenizer.
+
+use crate::parser::{TokenKind, tokenize};
+
+/// Tokenize `source` into `(kind, text)` pairs.
+fn tokens(source: &str) -> Vec<(TokenKind, &str)> {
+    tokenize(source)
+        .map(|(kind, span)| (kind, span.str_of(source)))
+        .collect()
+}
+
+#[test]
+fn test_format_string_where_tokens() {
+    use TokenKind::*;
+
+    let source = r#"f"{x}" where {x=1}"#;
+    let tokens: Vec<_> = tokenize(source).collect();
+    let expected = [
+        (FormatStringDelimiter, 0..2),
+        (InterpolationDelimiter, 2..3),
+        (Identifier, 3..4),
+        (InterpolationDelimiter, 4..5),
+        (FormatStringDelimiter, 5..6),
+        (Keyword, 7..12),
+        (Punctuation, 13..14),
+        (Identifier, 14..15),
+        (Operator, 15..16),
+        (Number, 16..17),
+        (Punctuation, 17..18),
+    ];
+    assert_eq!(tokens.len(), expected.len(), "{:?}", tokens);
+    for ((kind, span), (expected_kind, expected_range)) in tokens.iter().zip(expected) {
+        assert_eq!((*kind, span.0.clone()), (expected_kind, expected_range));
+    }
+}
+
+#[test]
+fn test_format_string_text() {
+    use TokenKind::*;
+
+    assert_eq!(
+        tokens(r#"f"a {b} c""#),
+        [
+            (FormatStringDelimiter, "f\""),
+            (FormatStringText, "a "),
+            (InterpolationDelimiter, "{"),
+            (Identifier, "b"),
+            (InterpolationDelimiter, "}"),
+            (FormatStringText, " c"),
+            (FormatStringDelimiter, "\""),
+        ]
+    );
+}
+
+#[test]
+fn test_keywords_operators_and_literals() {
+    use TokenKind::*;
+
+    assert_eq!(
+        tokens(r#"if not a and b then (x) => x + 1.5 else "s" // done"#),
+        [
+            (Keyword, "if"),
+            (Keyword, "not"),
+            (Identifier, "a"),
+            (Keyword, "and"),
+            (Identifier, "b"),
+            (Keyword, "then"),
+            (Punctuation, "("),
+            (Identifier, "x"),
+            (Punctuation, ")"),
+            (Operator, "=>"),
+            (Identifier, "x"),
+            (Operator, "+"),
+            (Number, "1.5"),
+            (Keyword, "else"),
+            (String, "\"s\""),
+            (Comment, "// done"),
+        ]
+    );
+    assert_eq!(
+        tokens("x as Option[Int] otherwise none"),
+        [
+            (Identifier, "x"),
+            (Keyword, "as"),
+            (TypeName, "Option"),
+            (Punctuation, "["),
+            (TypeName, "Int"),
+            (Punctuation, "]"),
+            (Keyword, "otherwise"),
+            (Keyword, "none"),
+        ]
+    );
+    assert_eq!(
+        tokens("o match { some true -> 1, _ -> -2 }"),
+        [
+            (Identifier, "o"),
+            (Keyword, "match"),
+            (Punctuation, "{"),
+            (Keyword, "some"),
+            (Boolean, "true"),
+            (Operator, "->"),
+            (Number, "1"),
+            (Punctuation, ","),
+            (Identifier, "_"),
+            (Operator, "->"),
+            (Number, "-2"),
+            (Punctuation, "}"),
+        ]
+    );
+}
+
+#[test]
+fn test_tokenize_half_typed_source() {
+    use TokenKind::*;
+
+    assert_eq!(tokens("f("), [(Identifier, "f"), (Punctuation, "(")]);
+    assert_eq!(
+        tokens(r#"x + 1.5 where { s = "a", t = f"{s}", u = true and "#),
+        [
+            (Identifier, "x"),
+            (Operator, "+"),
+            (Number, "1.5"),
+            (Keyword, "where"),
+            (Punctuation, "{"),
+            (Identifier, "s"),
+            (Operator, "="),
+            (String, r#""a""#),
+            (Punctuation, ","),
+            (Identifier, "t"),
+            (Operator, "="),
+            (FormatStringDelimiter, "f\""),
+            (InterpolationDelimiter, "{"),
+            (Identifier, "s"),
+            (InterpolationDelimiter, "}"),
+            (FormatStringDelimiter, "\""),
+            (Punctuation, ","),
+            (Identifier, "u"),
+            (Operator, "="),
+            (Boolean, "true"),
+            (Keyword, "and"),
+        ]
+    );
+    // Tokens after the parse error are dropped
+    assert_eq!(
+        tokens("[1, 2 3] // done"),
+        [
+            (Punctuation, "["),
+            (Number, "1"),
+            (Punctuation, ","),
+            (Number, "2")
+        ]
+    );
+    // An unterminated string runs to the parse error
+    assert_eq!(tokens(r#"f"{x} abc"#), [(String, r#"f"{x} abc"#)]);
+}
@@ -174,6 +174,20 @@ impl<'a, T: Copy> ScopeStack<'a, T> {
         Self { scopes: Vec::new() }
     }
 
+    /// Create an empty scope stack with room for `capacity` scopes.
+    ///
+    /// Pushing up to `capacity` scopes will not reallocate.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            scopes: Vec::with_capacity(capacity),
+        }
+    }
+
+    /// Number of scopes the stack can hold without reallocating.
+    pub fn capacity(&self) -> usize {
+        self.scopes.capacity()
+    }
+
     /// Push a scope onto the stack.
     pub fn push<S: Scope<'a, T> + 'a>(&mut self, scope: S) {
         self.scopes.push(Box::new(scope));
@@ -292,6 +306,21 @@ mod tests {
         assert_eq!(stack.lookup("d"), None);
     }
 
+    #[test]
+    fn test_with_capacity_avoids_reallocation() {
+        let bump = Bump::new();
+        let mut stack = ScopeStack::with_capacity(4);
+        let capacity = stack.capacity();
+        assert!(capacity >= 4);
+
+        for _ in 0..4 {
+            stack.push(IncompleteScope::new(&bump, &["a"]).unwrap());
+            stack.bind_in_current("a", 1).unwrap();
+        }
+        assert_eq!(stack.capacity(), capacity);
+        assert_eq!(stack.lookup("a"), Some(&1));
+    }
+
     #[test]
     fn test_incomplete_scope_sequential_binding() {
         let bump = Bump::new();
@@ -1,6 +1,9 @@
 //! `Array` package for Melbi
 use crate::{
-    evaluator::ExecutionError,
+    String,
+    evaluator::{ExecutionError, RuntimeError},
+    format,
+    parser::Span,
     types::{
         Type,
         manager::TypeManager,
@@ -15,6 +18,18 @@ use crate::{
 use alloc::{vec, vec::Vec};
 use bumpalo::Bump;
 
+/// Charge fuel for work on `count` elements.
+fn charge<'types, 'arena>(
+    ctx: &FfiContext<'types, 'arena>,
+    count: usize,
+) -> Result<(), ExecutionError> {
+    ctx.charge(count as u64).map_err(|error| ExecutionError {
+        kind: error.into(),
+        source: String::new(),
+        span: Span(0..0),
+    })
+}
+
 // ============================================================================
 // Basic Functions
 // ============================================================================
@@ -52,6 +67,83 @@ fn array_is_empty<'types, 'arena>(
     Ok(Value::bool(ctx.type_mgr(), arr.is_empty()))
 }
 
+// ============================================================================
+// Construction Functions
+// ============================================================================
+
+/// Largest array `Array.Range` will build.
+pub const MAX_RANGE_LENGTH: usize = 1 << 24;
+
+/// Generate the integers from `start` (inclusive) to `end` (exclusive)
+///
+/// Counts up for a positive `step` and down for a negative one.
+///
+/// # Edge Cases
+///
+/// - `start == end`, or `end` lies in the other direction: returns an empty array
+/// - `step == 0`: fails with an invalid argument error
+/// - More than [`MAX_RANGE_LENGTH`] elements: fails with an invalid argument
+///   error before allocating anything
+///
+/// # Examples
+/// - `Array.Range(0, 5, 1)` → `[0, 1, 2, 3, 4]`
+/// - `Array.Range(0, 10, 3)` → `[0, 3, 6, 9]`
+/// - `Array.Range(5, 0, -2)` → `[5, 3, 1]`
+/// - `Array.Range(3, 3, 1)` → `[]`
+fn array_range<'types, 'arena>(
+    ctx: &FfiContext<'types, 'arena>,
+    args: &[Value<'types, 'arena>],
+) -> Result<Value<'types, 'arena>, ExecutionError> {
+    debug_assert_eq!(args.len(), 3);
+    let start = args[0].as_int().expect("Expected int");
+    let end = args[1].as_int().expect("Expected int");
+    let step = args[2].as_int().expect("Expected int");
+
+    if step == 0 {
+        return Err(ExecutionError {
+            kind: RuntimeError::InvalidArgument {
+                message: String::from("Array.Range step must not be zero"),
+            }
+            .into(),
+            source: String::new(),
+            span: Span(0..0),
+        });
+    }
+
+    // Count the elements in i128 so ranges spanning the whole Int range
+    // don't overflow
+    let (distance, stride) = (end as i128 - start as i128, step as i128);
+    let count = if distance.signum() == stride.signum() {
+        (distance + stride - stride.signum()) / stride
+    } else {
+        0
+    };
+    if count > MAX_RANGE_LENGTH as i128 {
+        return Err(ExecutionError {
+            kind: RuntimeError::InvalidArgument {
+                message: format!(
+                    "Array.Range would produce {} elements, more than the maximum of {}",
+                    count, MAX_RANGE_LENGTH
+                ),
+            }
+            .into(),
+            source: String::new(),
+            span: Span(0..0),
+        });
+    }
+    let count = count as usize;
+    charge(ctx, count)?;
+
+    let int_ty = ctx.type_mgr().int();
+    // Every element lies between `start` and `end`, so none overflows
+    let results: Vec<_> = (0..count as i128)
+        .map(|i| Value::int(ctx.type_mgr(), (start as i128 + i * stride) as i64))
+        .collect();
+
+    Ok(Value::array(ctx.arena(), ctx.type_mgr().array(int_ty), &results)
+        .expect("Type error in Array.Range: array construction failed"))
+}
+
 // ============================================================================
 // Slice Functions
 // ============================================================================
@@ -97,6 +189,7 @@ fn array_slice<'types, 'arena>(
         );
     }
 
+    charge(ctx, end_idx - start_idx)?;
     let slice: Vec<Value<'types, 'arena>> = arr
         .iter()
         .skip(start_idx)
@@ -132,6 +225,7 @@ fn array_concat<'types, 'arena>(
     let arr1 = args[0].as_array().expect("Expected array");
     let arr2 = args[1].as_array().expect("Expected array");
 
+    charge(ctx, arr1.len() + arr2.len())?;
     let mut result = Vec::new();
     result.extend(arr1.iter());
     result.extend(arr2.iter());
@@ -159,6 +253,7 @@ fn array_flatten<'types, 'arena>(
     let mut result = Vec::new();
     for inner_val in arr.iter() {
         let inner_arr = inner_val.as_array().expect("Expected array element");
+        charge(ctx, inner_arr.len())?;
         result.extend(inner_arr.iter());
     }
 
@@ -217,6 +312,7 @@ fn array_zip<'types, 'arena>(
         _ => panic!("Expected array type"),
     };
 
+    charge(ctx, arr1.len().min(arr2.len()))?;
     let mut result = Vec::new();
     for (val1, val2) in arr1.iter().zip(arr2.iter()) {
         // Create a tuple as a record with fields "first" and "second"
@@ -239,6 +335,45 @@ fn array_zip<'types, 'arena>(
     )
 }
 
+// ============================================================================
+// Search Functions
+// ============================================================================
+
+/// Find the index of the first element equal to a value
+///
+/// Polymorphic - works with arrays of any element type. Elements are compared
+/// structurally, so records and nested arrays are found by content.
+///
+/// # Edge Cases
+///
+/// - Duplicates: returns the index of the first match
+/// - Empty array: `Array.IndexOf([], 1)` → `none`
+///
+/// # Examples
+/// - `Array.IndexOf([10, 20, 30], 20)` → `some 1`
+/// - `Array.IndexOf(["a", "b"], "z")` → `none`
+/// - `Array.IndexOf([{x = 1}, {x = 2}], {x = 2})` → `some 1`
+fn array_index_of<'types, 'arena>(
+    ctx: &FfiContext<'types, 'arena>,
+    args: &[Value<'types, 'arena>],
+) -> Result<Value<'types, 'arena>, ExecutionError> {
+    debug_assert_eq!(args.len(), 2);
+    let arr = args[0].as_array().expect("Expected array");
+    let needle = args[1];
+    charge(ctx, arr.len())?;
+
+    let type_mgr = ctx.type_mgr();
+    let index = arr
+        .iter()
+        .position(|elem| elem == needle)
+        .map(|index| Value::int(type_mgr, index as i64));
+
+    Ok(
+        Value::optional(ctx.arena(), type_mgr.option(type_mgr.int()), index)
+            .expect("Type error in Array.IndexOf: option construction failed"),
+    )
+}
+
 // ============================================================================
 // Transformation Functions
 // ============================================================================
@@ -261,6 +396,7 @@ fn array_reverse<'types, 'arena>(
 ) -> Result<Value<'types, 'arena>, ExecutionError> {
     debug_assert_eq!(args.len(), 1);
     let arr = args[0].as_array().expect("Expected array");
+    charge(ctx, arr.len())?;
 
     let mut result: Vec<Value<'types, 'arena>> = arr.iter().collect();
     result.reverse();
@@ -277,6 +413,32 @@ fn array_reverse<'types, 'arena>(
     )
 }
 
+/// Sort an array in ascending order
+///
+/// Polymorphic - uses the same total order as map keys and `min`/`max`, so
+/// `NaN` sorts after every other float. The sort is stable. Charges
+/// `n log n` fuel for `n` elements.
+///
+/// # Examples
+/// - `Array.Sort([3, 1, 2])` → `[1, 2, 3]`
+/// - `Array.Sort(["b", "a"])` → `["a", "b"]`
+/// - `Array.Sort([])` → `[]`
+fn array_sort<'types, 'arena>(
+    ctx: &FfiContext<'types, 'arena>,
+    args: &[Value<'types, 'arena>],
+) -> Result<Value<'types, 'arena>, ExecutionError> {
+    debug_assert_eq!(args.len(), 1);
+    let arr = args[0].as_array().expect("Expected array");
+    let log_len = (usize::BITS - arr.len().leading_zeros()) as usize;
+    charge(ctx, arr.len().saturating_mul(log_len))?;
+
+    let mut result: Vec<Value<'types, 'arena>> = arr.iter().collect();
+    result.sort();
+
+    Ok(Value::array(ctx.arena(), args[0].ty, &result)
+        .expect("Type error in Array.Sort: array construction failed"))
+}
+
 // ============================================================================
 // Higher-Order Functions
 // ============================================================================
@@ -289,13 +451,14 @@ fn array_reverse<'types, 'arena>(
 /// - `Array.Map([1, 2, 3], (x) => x * 2)` → `[2, 4, 6]`
 /// - `Array.Map(["a", "bb"], (s) => String.Len(s))` → `[1, 2]`
 /// - `Array.Map([], (x) => x)` → `[]`
-fn array_map<'types, 'arena>(
+pub(crate) fn array_map<'types, 'arena>(
     ctx: &FfiContext<'types, 'arena>,
     args: &[Value<'types, 'arena>],
 ) -> Result<Value<'types, 'arena>, ExecutionError> {
     debug_assert_eq!(args.len(), 2);
     let arr = args[0].as_array().expect("Expected array");
     let func = args[1].as_function().expect("Expected function");
+    charge(ctx, arr.len())?;
 
     let mut results = Vec::new();
     for elem in arr.iter() {
@@ -315,6 +478,178 @@ fn array_map<'types, 'arena>(
     )
 }
 
+/// Keep the elements of an array for which a predicate returns true
+///
+/// Polymorphic - preserves element type and order.
+///
+/// # Examples
+/// - `Array.Filter([1, 2, 3, 4], (x) => x > 2)` → `[3, 4]`
+/// - `Array.Filter(["a", "", "b"], (s) => s != "")` → `["a", "b"]`
+/// - `Array.Filter([1, 2], (x) => false)` → `[]`
+pub(crate) fn array_filter<'types, 'arena>(
+    ctx: &FfiContext<'types, 'arena>,
+    args: &[Value<'types, 'arena>],
+) -> Result<Value<'types, 'arena>, ExecutionError> {
+    debug_assert_eq!(args.len(), 2);
+    let arr = args[0].as_array().expect("Expected array");
+    let predicate = args[1].as_function().expect("Expected function");
+    charge(ctx, arr.len())?;
+
+    let mut results = Vec::new();
+    for elem in arr.iter() {
+        let keep = unsafe { predicate.call_unchecked(ctx, &[elem]) }?;
+        if keep.as_bool().expect("Expected Bool from predicate") {
+            results.push(elem);
+        }
+    }
+
+    Ok(Value::array(ctx.arena(), args[0].ty, &results)
+        .expect("Type error in Array.Filter: array construction failed"))
+}
+
+/// Split an array in a single pass by a predicate
+///
+/// Polymorphic - both partitions keep the element type and the original order.
+/// Returns a record: `matched` holds the elements for which the predicate
+/// returned true, `rest` holds the others.
+///
+/// # Examples
+/// - `Array.Partition([1, 2, 3, 4], (x) => x > 2)` → `{matched = [3, 4], rest = [1, 2]}`
+/// - `Array.Partition([1, 2], (x) => true)` → `{matched = [1, 2], rest = []}`
+/// - `Array.Partition([], (x) => true)` → `{matched = [], rest = []}`
+fn array_partition<'types, 'arena>(
+    ctx: &FfiContext<'types, 'arena>,
+    args: &[Value<'types, 'arena>],
+) -> Result<Value<'types, 'arena>, ExecutionError> {
+    debug_assert_eq!(args.len(), 2);
+    let arr = args[0].as_array().expect("Expected array");
+    let predicate = args[1].as_function().expect("Expected function");
+    charge(ctx, arr.len())?;
+
+    let mut matched = Vec::new();
+    let mut rest = Vec::new();
+    for elem in arr.iter() {
+        let keep = unsafe { predicate.call_unchecked(ctx, &[elem]) }?;
+        if keep.as_bool().expect("Expected Bool from predicate") {
+            matched.push(elem);
+        } else {
+            rest.push(elem);
+        }
+    }
+
+    let matched = Value::array(ctx.arena(), args[0].ty, &matched)
+        .expect("Type error in Array.Partition: array construction failed");
+    let rest = Value::array(ctx.arena(), args[0].ty, &rest)
+        .expect("Type error in Array.Partition: array construction failed");
+    Ok(Value::record_builder(ctx.arena(), ctx.type_mgr())
+        .bind("matched", matched)
+        .bind("rest", rest)
+        .build()
+        .expect("Type error in Array.Partition: record construction failed"))
+}
+
+/// Fold an array from the right
+///
+/// Polymorphic - combines each element with the accumulator, starting from the
+/// last element: `Array.FoldRight([x1, x2], init, f)` is `f(x1, f(x2, init))`.
+///
+/// # Examples
+/// - `Array.FoldRight([1, 2, 3], 0, (x, acc) => x - acc)` → `2` (`1 - (2 - (3 - 0))`)
+/// - `Array.FoldRight([1, 2, 3], [], (x, acc) => Array.Concat(acc, [x]))` → `[3, 2, 1]`
+/// - `Array.FoldRight([], 7, (x, acc) => x + acc)` → `7`
+fn array_fold_right<'types, 'arena>(
+    ctx: &FfiContext<'types, 'arena>,
+    args: &[Value<'types, 'arena>],
+) -> Result<Value<'types, 'arena>, ExecutionError> {
+    debug_assert_eq!(args.len(), 3);
+    let arr = args[0].as_array().expect("Expected array");
+    let func = args[2].as_function().expect("Expected function");
+    charge(ctx, arr.len())?;
+
+    let elements: Vec<Value<'types, 'arena>> = arr.iter().collect();
+    let mut accumulator = args[1];
+    for &elem in elements.iter().rev() {
+        accumulator = unsafe { func.call_unchecked(ctx, &[elem, accumulator]) }?;
+    }
+    Ok(accumulator)
+}
+
+/// Running left fold of an array
+///
+/// Polymorphic - returns every intermediate accumulator, starting with the
+/// initial value, so the result has one more element than the input and its
+/// last element is the full left fold.
+///
+/// # Examples
+/// - `Array.Scan([1, 2, 3], 0, (acc, x) => acc + x)` → `[0, 1, 3, 6]`
+/// - `Array.Scan([1, 2, 3], 0, (acc, x) => acc - x)` → `[0, -1, -3, -6]`
+/// - `Array.Scan([], 7, (acc, x) => acc + x)` → `[7]`
+fn array_scan<'types, 'arena>(
+    ctx: &FfiContext<'types, 'arena>,
+    args: &[Value<'types, 'arena>],
+) -> Result<Value<'types, 'arena>, ExecutionError> {
+    debug_assert_eq!(args.len(), 3);
+    let arr = args[0].as_array().expect("Expected array");
+    let func = args[2].as_function().expect("Expected function");
+    charge(ctx, arr.len())?;
+
+    let mut accumulator = args[1];
+    let mut results = Vec::with_capacity(arr.len() + 1);
+    results.push(accumulator);
+    for elem in arr.iter() {
+        accumulator = unsafe { func.call_unchecked(ctx, &[accumulator, elem]) }?;
+        results.push(accumulator);
+    }
+
+    // Get accumulator type from function's return type
+    let accumulator_ty = match args[2].ty.view() {
+        TypeKind::Function { ret, .. } => ret,
+        _ => panic!("Expected function type"),
+    };
+
+    Ok(
+        Value::array(ctx.arena(), ctx.type_mgr().array(accumulator_ty), &results)
+            .expect("Type error in Array.Scan: array construction failed"),
+    )
+}
+
+/// Merge an array of maps into a single map
+///
+/// Polymorphic - all maps share the key and value types. This is the map
+/// constructor behind map comprehensions.
+///
+/// # Edge Cases
+///
+/// - Duplicate keys: the last write wins, so later maps override earlier ones
+/// - Empty array: `Array.MergeMaps([])` → `{}`
+///
+/// # Examples
+/// - `Array.MergeMaps([{1: "a"}, {2: "b"}])` → `{1: "a", 2: "b"}`
+/// - `Array.MergeMaps([{1: "a"}, {1: "b"}])` → `{1: "b"}`
+pub(crate) fn array_merge_maps<'types, 'arena>(
+    ctx: &FfiContext<'types, 'arena>,
+    args: &[Value<'types, 'arena>],
+) -> Result<Value<'types, 'arena>, ExecutionError> {
+    debug_assert_eq!(args.len(), 1);
+    let arr = args[0].as_array().expect("Expected array");
+
+    let mut entries = Vec::new();
+    for map in arr.iter() {
+        let map = map.as_map().expect("Expected map");
+        charge(ctx, map.len())?;
+        entries.extend(map.iter());
+    }
+
+    let map_ty = match args[0].ty.view() {
+        TypeKind::Array(elem_ty) => elem_ty,
+        _ => panic!("Expected array type"),
+    };
+
+    // `Value::map` sorts stably and keeps the last value for each key
+    Ok(Value::map(ctx.arena(), map_ty, &entries)
+        .expect("Type error in Array.MergeMaps: map construction failed"))
+}
+
 // ============================================================================
 // Package Registration
 // ============================================================================
@@ -393,6 +728,17 @@ where
     }
     .register(arena, builder);
 
+    // Range: (Int, Int, Int) -> Array<Int>
+    builder = NativeFunction {
+        name: "Range",
+        ty: type_mgr.function(
+            &[type_mgr.int(), type_mgr.int(), type_mgr.int()],
+            type_mgr.array(type_mgr.int()),
+        ),
+        ptr: array_range,
+    }
+    .register(arena, builder);
+
     // Slice: forall T. (Array<T>, Int, Int) -> Array<T>
     let t = type_mgr.fresh_type_var();
     builder = NativeFunction {
@@ -437,6 +783,18 @@ where
     }
     .register(arena, builder);
 
+    // IndexOf: forall T. (Array<T>, T) -> Option<Int>
+    let t = type_mgr.fresh_type_var();
+    builder = NativeFunction {
+        name: "IndexOf",
+        ty: type_mgr.function(
+            &[type_mgr.array(t), t],
+            type_mgr.option(type_mgr.int()),
+        ),
+        ptr: array_index_of,
+    }
+    .register(arena, builder);
+
     // Reverse: forall T. Array<T> -> Array<T>
     let t = type_mgr.fresh_type_var();
     builder = NativeFunction {
@@ -446,6 +804,15 @@ where
     }
     .register(arena, builder);
 
+    // Sort: forall T. Array<T> -> Array<T>
+    let t = type_mgr.fresh_type_var();
+    builder = NativeFunction {
+        name: "Sort",
+        ty: type_mgr.function(&[type_mgr.array(t)], type_mgr.array(t)),
+        ptr: array_sort,
+    }
+    .register(arena, builder);
+
     // Map: forall T, U. (Array<T>, (T) => U) -> Array<U>
     let t = type_mgr.fresh_type_var();
     let u = type_mgr.fresh_type_var();
@@ -457,6 +824,63 @@ where
     }
     .register(arena, builder);
 
+    // Filter: forall T. (Array<T>, (T) => Bool) -> Array<T>
+    let t = type_mgr.fresh_type_var();
+    let predicate_ty = type_mgr.function(&[t], type_mgr.bool());
+    builder = NativeFunction {
+        name: "Filter",
+        ty: type_mgr.function(&[type_mgr.array(t), predicate_ty], type_mgr.array(t)),
+        ptr: array_filter,
+    }
+    .register(arena, builder);
+
+    // Partition: forall T. (Array<T>, (T) => Bool) -> {matched: Array<T>, rest: Array<T>}
+    let t = type_mgr.fresh_type_var();
+    let predicate_ty = type_mgr.function(&[t], type_mgr.bool());
+    let partitions_ty = type_mgr.record(vec![
+        ("matched", type_mgr.array(t)),
+        ("rest", type_mgr.array(t)),
+    ]);
+    builder = NativeFunction {
+        name: "Partition",
+        ty: type_mgr.function(&[type_mgr.array(t), predicate_ty], partitions_ty),
+        ptr: array_partition,
+    }
+    .register(arena, builder);
+
+    // FoldRight: forall A, B. (Array<A>, B, (A, B) => B) -> B
+    let a = type_mgr.fresh_type_var();
+    let b = type_mgr.fresh_type_var();
+    let fn_ty = type_mgr.function(&[a, b], b);
+    builder = NativeFunction {
+        name: "FoldRight",
+        ty: type_mgr.function(&[type_mgr.array(a), b, fn_ty], b),
+        ptr: array_fold_right,
+    }
+    .register(arena, builder);
+
+    // Scan: forall A, B. (Array<A>, B, (B, A) => B) -> Array<B>
+    let a = type_mgr.fresh_type_var();
+    let b = type_mgr.fresh_type_var();
+    let fn_ty = type_mgr.function(&[b, a], b);
+    builder = NativeFunction {
+        name: "Scan",
+        ty: type_mgr.function(&[type_mgr.array(a), b, fn_ty], type_mgr.array(b)),
+        ptr: array_scan,
+    }
+    .register(arena, builder);
+
+    // MergeMaps: forall K, V. (Array<Map<K, V>>) -> Map<K, V>
+    let k = type_mgr.fresh_type_var();
+    let v = type_mgr.fresh_type_var();
+    let map_ty = type_mgr.map(k, v);
+    builder = NativeFunction {
+        name: "MergeMaps",
+        ty: type_mgr.function(&[type_mgr.array(map_ty)], map_ty),
+        ptr: array_merge_maps,
+    }
+    .register(arena, builder);
+
     builder
 }
 
@@ -2,7 +2,8 @@
 
 use super::register_array_functions;
 use crate::{
-    api::{CompileOptionsOverride, Engine, EngineOptions, Error},
+    api::{CompileOptionsOverride, Engine, EngineOptions, Error, RunOptionsOverride, RuntimeKind},
+    parser::Span,
     stdlib::{register_array_package, register_math_package, register_string_package},
     types::manager::TypeManager,
     values::{
@@ -26,12 +27,20 @@ fn test_array_package_builds() {
     assert!(!record.is_empty());
     assert!(record.get("Len").is_some());
     assert!(record.get("IsEmpty").is_some());
+    assert!(record.get("Range").is_some());
     assert!(record.get("Slice").is_some());
     assert!(record.get("Concat").is_some());
     assert!(record.get("Flatten").is_some());
     assert!(record.get("Zip").is_some());
+    assert!(record.get("IndexOf").is_some());
     assert!(record.get("Reverse").is_some());
+    assert!(record.get("Sort").is_some());
     assert!(record.get("Map").is_some());
+    assert!(record.get("Filter").is_some());
+    assert!(record.get("Partition").is_some());
+    assert!(record.get("FoldRight").is_some());
+    assert!(record.get("Scan").is_some());
+    assert!(record.get("MergeMaps").is_some());
 }
 
 /// Evaluates a Melbi expression with all standard packages (Array, Math, String).
@@ -173,6 +182,119 @@ fn test_reverse() {
     );
 }
 
+#[test]
+fn test_sort() {
+    let arena = Bump::new();
+
+    assert!(
+        eval(&arena, "Array.Sort([3, 1, 2, 1]) == [1, 1, 2, 3]")
+            .unwrap()
+            .as_bool()
+            .unwrap()
+    );
+    assert!(
+        eval(
+            &arena,
+            "Array.Sort([\"b\", \"c\", \"a\"]) == [\"a\", \"b\", \"c\"]"
+        )
+        .unwrap()
+        .as_bool()
+        .unwrap()
+    );
+    assert!(
+        eval(&arena, "Array.Sort([]) == []")
+            .unwrap()
+            .as_bool()
+            .unwrap()
+    );
+
+    // Arrays sort element by element, shorter first on a tie
+    assert!(
+        eval(
+            &arena,
+            "Array.Sort([[2], [1, 5], [1]]) == [[1], [1, 5], [2]]"
+        )
+        .unwrap()
+        .as_bool()
+        .unwrap()
+    );
+}
+
+// ============================================================================
+// IndexOf Tests
+// ============================================================================
+
+#[test]
+fn test_index_of_found() {
+    let arena = Bump::new();
+
+    assert!(
+        eval(&arena, "Array.IndexOf([10, 20, 30], 30) == some 2")
+            .unwrap()
+            .as_bool()
+            .unwrap()
+    );
+    assert!(
+        eval(&arena, "Array.IndexOf([\"a\", \"b\"], \"a\") == some 0")
+            .unwrap()
+            .as_bool()
+            .unwrap()
+    );
+
+    // Structural equality for records and nested arrays
+    assert!(
+        eval(
+            &arena,
+            "Array.IndexOf([{ x = 1, y = 2 }, { x = 3, y = 4 }], { y = 4, x = 3 }) == some 1"
+        )
+        .unwrap()
+        .as_bool()
+        .unwrap()
+    );
+    assert!(
+        eval(&arena, "Array.IndexOf([[1], [2, 3], []], [2, 3]) == some 1")
+            .unwrap()
+            .as_bool()
+            .unwrap()
+    );
+}
+
+#[test]
+fn test_index_of_not_found() {
+    let arena = Bump::new();
+
+    assert!(
+        eval(&arena, "Array.IndexOf([10, 20, 30], 40) == none")
+            .unwrap()
+            .as_bool()
+            .unwrap()
+    );
+    assert!(
+        eval(&arena, "Array.IndexOf([], 1) == none")
+            .unwrap()
+            .as_bool()
+            .unwrap()
+    );
+    assert!(
+        eval(&arena, "Array.IndexOf([[1, 2]], [2, 1]) == none")
+            .unwrap()
+            .as_bool()
+            .unwrap()
+    );
+}
+
+#[test]
+fn test_index_of_duplicates_returns_first() {
+    let arena = Bump::new();
+
+    assert!(
+        eval(&arena, "Array.IndexOf([7, 5, 7, 5], 5) == some 1")
+            .unwrap()
+            .as_bool()
+            .unwrap()
+    );
+}
+
 // ============================================================================
 // Map Tests
 // ============================================================================
@@ -359,6 +481,503 @@ fn test_map_runtime_error_propagation() {
     assert!(format!("{:?}", result.err()).contains("IndexOutOfBounds"));
 }
 
+// ============================================================================
+// Filter Tests
+// ============================================================================
+
+#[test]
+fn test_filter() {
+    let arena = Bump::new();
+
+    assert!(
+        eval(&arena, "Array.Filter([1, 2, 3, 4], (x) => x > 2) == [3, 4]")
+            .unwrap()
+            .as_bool()
+            .unwrap()
+    );
+
+    // Order is preserved
+    assert!(
+        eval(
+            &arena,
+            "Array.Filter([5, 1, 4, 2], (x) => x != 1) == [5, 4, 2]"
+        )
+        .unwrap()
+        .as_bool()
+        .unwrap()
+    );
+
+    // Nothing kept
+    assert!(
+        eval(&arena, "Array.Len(Array.Filter([1, 2], (x) => false)) == 0")
+            .unwrap()
+            .as_bool()
+            .unwrap()
+    );
+
+    // Predicate must return Bool
+    assert!(
+        format!("{:?}", eval(&arena, "Array.Filter([1, 2], (x) => x)").err())
+            .contains("Type mismatch")
+    );
+}
+
+// ============================================================================
+// Partition Tests
+// ============================================================================
+
+#[test]
+fn test_partition_mixed_preserves_order() {
+    let arena = Bump::new();
+
+    assert!(
+        eval(
+            &arena,
+            "Array.Partition([5, 1, 4, 2, 3], (x) => x > 2) == {matched = [5, 4, 3], rest = [1, 2]}"
+        )
+        .unwrap()
+        .as_bool()
+        .unwrap()
+    );
+    assert!(
+        eval(
+            &arena,
+            r#"Array.Partition(["b", "", "a", ""], (s) => s != "").matched == ["b", "a"]"#
+        )
+        .unwrap()
+        .as_bool()
+        .unwrap()
+    );
+}
+
+#[test]
+fn test_partition_all_match() {
+    let arena = Bump::new();
+
+    assert!(
+        eval(
+            &arena,
+            "Array.Partition([3, 1, 2], (x) => true) == {matched = [3, 1, 2], rest = []}"
+        )
+        .unwrap()
+        .as_bool()
+        .unwrap()
+    );
+}
+
+#[test]
+fn test_partition_none_match() {
+    let arena = Bump::new();
+
+    assert!(
+        eval(
+            &arena,
+            "Array.Partition([3, 1, 2], (x) => false) == {matched = [], rest = [3, 1, 2]}"
+        )
+        .unwrap()
+        .as_bool()
+        .unwrap()
+    );
+    assert!(
+        eval(
+            &arena,
+            "Array.Partition([], (x) => x > 0) == {matched = [], rest = []}"
+        )
+        .unwrap()
+        .as_bool()
+        .unwrap()
+    );
+}
+
+// ============================================================================
+// FoldRight and Scan Tests
+// ============================================================================
+
+#[test]
+fn test_fold_right() {
+    let arena = Bump::new();
+
+    // 1 - (2 - (3 - 0))
+    assert_eq!(
+        eval(&arena, "Array.FoldRight([1, 2, 3], 0, (x, acc) => x - acc)")
+            .unwrap()
+            .as_int()
+            .unwrap(),
+        2
+    );
+
+    // The accumulator can have a different type than the elements
+    assert!(
+        eval(
+            &arena,
+            "Array.FoldRight([1, 2, 3], [], (x, acc) => Array.Concat(acc, [x])) == [3, 2, 1]"
+        )
+        .unwrap()
+        .as_bool()
+        .unwrap()
+    );
+
+    // Empty array returns the initial value
+    assert_eq!(
+        eval(&arena, "Array.FoldRight([], 7, (x, acc) => x + acc)")
+            .unwrap()
+            .as_int()
+            .unwrap(),
+        7
+    );
+}
+
+#[test]
+fn test_fold_right_differs_from_left_fold_on_non_associative_operation() {
+    let arena = Bump::new();
+
+    // Left fold (last Scan element): ((0 - 1) - 2) - 3 = -6
+    // Right fold: 1 - (2 - (3 - 0)) = 2
+    assert!(
+        eval(
+            &arena,
+            "left[3] == -6 and right == 2 where { \
+                left = Array.Scan([1, 2, 3], 0, (acc, x) => acc - x), \
+                right = Array.FoldRight([1, 2, 3], 0, (x, acc) => x - acc) }"
+        )
+        .unwrap()
+        .as_bool()
+        .unwrap()
+    );
+}
+
+#[test]
+fn test_scan() {
+    let arena = Bump::new();
+
+    // Includes the initial value, then each prefix accumulation
+    assert!(
+        eval(
+            &arena,
+            "Array.Scan([1, 2, 3], 0, (acc, x) => acc + x) == [0, 1, 3, 6]"
+        )
+        .unwrap()
+        .as_bool()
+        .unwrap()
+    );
+    assert!(
+        eval(
+            &arena,
+            "Array.Scan([1, 2, 3], 10, (acc, x) => acc - x) == [10, 9, 7, 4]"
+        )
+        .unwrap()
+        .as_bool()
+        .unwrap()
+    );
+    assert!(
+        eval(
+            &arena,
+            r#"Array.Scan(["a", "b"], 0, (acc, s) => acc + String.Len(s)) == [0, 1, 2]"#
+        )
+        .unwrap()
+        .as_bool()
+        .unwrap()
+    );
+
+    // Empty array yields just the initial value
+    assert!(
+        eval(&arena, "Array.Scan([], 7, (acc, x) => acc + x) == [7]")
+            .unwrap()
+            .as_bool()
+            .unwrap()
+    );
+}
+
+// ============================================================================
+// Comprehension Tests
+// ============================================================================
+
+#[test]
+fn test_comprehension() {
+    let arena = Bump::new();
+
+    assert!(
+        eval(&arena, "[x * 2 for x in [1, 2, 3]] == [2, 4, 6]")
+            .unwrap()
+            .as_bool()
+            .unwrap()
+    );
+
+    // The element type reaches the body, so fields can be accessed
+    assert!(
+        eval(
+            &arena,
+            "[p.name for p in [{ name = \"a\", age = 1 }, { name = \"b\", age = 2 }]] == [\"a\", \"b\"]"
+        )
+        .unwrap()
+        .as_bool()
+        .unwrap()
+    );
+}
+
+#[test]
+fn test_comprehension_with_filter() {
+    let arena = Bump::new();
+
+    assert!(
+        eval(&arena, "[x * 2 for x in [-1, 2, 0, 3] if x > 0] == [4, 6]")
+            .unwrap()
+            .as_bool()
+            .unwrap()
+    );
+
+    // The filter sees the same binding as the body
+    assert!(
+        eval(
+            &arena,
+            "[p.age for p in [{ name = \"a\", age = 1 }, { name = \"b\", age = 2 }] if p.name == \"b\"] == [2]"
+        )
+        .unwrap()
+        .as_bool()
+        .unwrap()
+    );
+
+    // The filter must be a Bool
+    assert!(
+        format!("{:?}", eval(&arena, "[x for x in [1, 2] if x]").err()).contains("Type mismatch")
+    );
+}
+
+#[test]
+fn test_comprehension_ignores_bindings_named_array() {
+    let arena = Bump::new();
+
+    assert!(
+        eval(
+            &arena,
+            "[x * 2 for x in [1, 2] if x > 0] == [2, 4] where { Array = { Map = 1, Filter = 2 } }"
+        )
+        .unwrap()
+        .as_bool()
+        .unwrap()
+    );
+    assert!(
+        eval(&arena, "{x: x for x in [1]} == {1: 1} where { Array = 1 }")
+            .unwrap()
+            .as_bool()
+            .unwrap()
+    );
+}
+
+#[test]
+fn test_merge_maps() {
+    let arena = Bump::new();
+
+    assert!(
+        eval(
+            &arena,
+            "Array.MergeMaps([{1: \"a\"}, {2: \"b\"}]) == {1: \"a\", 2: \"b\"}"
+        )
+        .unwrap()
+        .as_bool()
+        .unwrap()
+    );
+
+    // Later maps override earlier ones
+    assert!(
+        eval(
+            &arena,
+            "Array.MergeMaps([{1: \"a\"}, {1: \"b\"}]) == {1: \"b\"}"
+        )
+        .unwrap()
+        .as_bool()
+        .unwrap()
+    );
+}
+
+#[test]
+fn test_map_comprehension() {
+    let arena = Bump::new();
+
+    assert!(
+        eval(
+            &arena,
+            "{p.name: p.age for p in [{ name = \"a\", age = 1 }, { name = \"b\", age = 2 }]} == {\"a\": 1, \"b\": 2}"
+        )
+        .unwrap()
+        .as_bool()
+        .unwrap()
+    );
+
+    assert!(
+        eval(
+            &arena,
+            "{x: x * x for x in [1, 2, 3] if x > 1} == {2: 4, 3: 9}"
+        )
+        .unwrap()
+        .as_bool()
+        .unwrap()
+    );
+}
+
+#[test]
+fn test_map_comprehension_duplicate_keys() {
+    let arena = Bump::new();
+
+    // The last write wins
+    assert!(
+        eval(
+            &arena,
+            "{String.Len(s): s for s in [\"a\", \"bb\", \"c\"]} == {1: \"c\", 2: \"bb\"}"
+        )
+        .unwrap()
+        .as_bool()
+        .unwrap()
+    );
+
+    // Keys must be usable as map keys
+    assert!(
+        format!("{:?}", eval(&arena, "{Math.Sin: x for x in [1, 2]}").err())
+            .contains("does not implement Hashable")
+    );
+}
+
+// ============================================================================
+// Range Tests
+// ============================================================================
+
+#[test]
+fn test_range_ascending() {
+    let arena = Bump::new();
+
+    assert!(
+        eval(&arena, "Array.Range(0, 5, 1) == [0, 1, 2, 3, 4]")
+            .unwrap()
+            .as_bool()
+            .unwrap()
+    );
+}
+
+#[test]
+fn test_range_descending() {
+    let arena = Bump::new();
+
+    assert!(
+        eval(&arena, "Array.Range(5, 0, -2) == [5, 3, 1]")
+            .unwrap()
+            .as_bool()
+            .unwrap()
+    );
+}
+
+#[test]
+fn test_range_empty() {
+    let arena = Bump::new();
+
+    assert!(
+        eval(&arena, "Array.Range(3, 3, 1) == []")
+            .unwrap()
+            .as_bool()
+            .unwrap()
+    );
+
+    // The end lies in the other direction of the step
+    assert!(
+        eval(&arena, "Array.Range(0, 5, -1) == []")
+            .unwrap()
+            .as_bool()
+            .unwrap()
+    );
+}
+
+#[test]
+fn test_range_with_step() {
+    let arena = Bump::new();
+
+    assert!(
+        eval(&arena, "Array.Range(0, 10, 3) == [0, 3, 6, 9]")
+            .unwrap()
+            .as_bool()
+            .unwrap()
+    );
+
+    // No wrap-around near the end of the Int range
+    assert_eq!(
+        eval(
+            &arena,
+            "Array.Len(Array.Range(9223372036854775806, 9223372036854775807, 5))"
+        )
+        .unwrap()
+        .as_int()
+        .unwrap(),
+        1
+    );
+}
+
+#[test]
+fn test_range_zero_step_errors() {
+    let arena = Bump::new();
+
+    assert!(
+        format!("{:?}", eval(&arena, "Array.Range(0, 5, 0)").err())
+            .contains("step must not be zero")
+    );
+
+    // The error can be handled with `otherwise`
+    assert!(
+        eval(&arena, "(Array.Range(0, 5, 0) otherwise []) == []")
+            .unwrap()
+            .as_bool()
+            .unwrap()
+    );
+}
+
+#[test]
+fn test_range_rejects_oversized_ranges() {
+    let arena = Bump::new();
+
+    let error = eval(&arena, "Array.Range(0, 9223372036854775807, 1)").unwrap_err();
+    assert!(
+        format!("{:?}", error).contains("more than the maximum"),
+        "{error:?}"
+    );
+
+    // Element counts are exact even when the range spans the whole Int range
+    assert!(
+        eval(
+            &arena,
+            "Array.Range(-9223372036854775808, 9223372036854775807, 4611686018427387904) \
+             == [-9223372036854775808, -4611686018427387904, 0, 4611686018427387904]"
+        )
+        .unwrap()
+        .as_bool()
+        .unwrap()
+    );
+}
+
+#[test]
+fn test_range_errors_point_at_the_call() {
+    let arena = Bump::new();
+
+    for runtime in [RuntimeKind::Evaluator, RuntimeKind::Vm] {
+        let engine = Engine::new(EngineOptions::default(), &arena, |arena, type_mgr, env| {
+            register_array_package(arena, type_mgr, env)
+        });
+        let expr = engine
+            .compile(
+                Default::default(),
+                "1 + Array.Len(Array.Range(0, 5, 0))",
+                &[],
+            )
+            .unwrap();
+        let options = RunOptionsOverride {
+            runtime: Some(runtime),
+            ..Default::default()
+        };
+        match expr.run(options, &arena, &[]).unwrap_err() {
+            Error::Runtime { diagnostic, .. } => {
+                assert_eq!(diagnostic.span, Span(14..34), "{runtime:?}");
+            }
+            error => panic!("unexpected error: {error:?}"),
+        }
+    }
+}
+
 // ============================================================================
 // Slice Tests
 // ============================================================================
@@ -0,0 +1,49 @@
+//! Bool Package
+//!
+//! Provides boolean connectives that have no operator in Melbi.
+//!
+//! Functions:
+//! - `Xor(a, b)`: Exclusive or (true when exactly one argument is true)
+//! - `Implies(a, b)`: Material implication (false only when `a` is true and `b` is false)
+//!
+//! Unlike `and` and `or`, these are regular functions: both arguments are
+//! always evaluated.
+
+use melbi_macros::{melbi_fn, melbi_package};
+
+#[melbi_package]
+mod bool {
+    use super::*;
+
+    /// Returns true when exactly one of `a` and `b` is true.
+    ///
+    /// Equivalent to `a != b`.
+    ///
+    /// Examples:
+    /// - `Bool.Xor(true, false) -> true`
+    /// - `Bool.Xor(true, true)  -> false`
+    #[melbi_fn]
+    fn xor(a: bool, b: bool) -> bool {
+        a != b
+    }
+
+    /// Returns whether `a` implies `b`: false only when `a` is true and `b`
+    /// is false.
+    ///
+    /// Equivalent to `not a or b`, except that `b` is always evaluated.
+    ///
+    /// Examples:
+    /// - `Bool.Implies(false, false) -> true`
+    /// - `Bool.Implies(true, false)  -> false`
+    #[melbi_fn]
+    fn implies(a: bool, b: bool) -> bool {
+        !a || b
+    }
+}
+
+// Re-export everything for cleaner access
+pub use self::bool::*;
+
+#[cfg(test)]
+#[path = "bool_test.rs"]
+mod bool_test;
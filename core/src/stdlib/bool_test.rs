@@ -0,0 +1,75 @@
+//! Tests for the Bool package
+
+use super::{register_bool_functions, register_bool_package};
+use crate::{
+    api::{CompileOptionsOverride, Engine, EngineOptions},
+    types::manager::TypeManager,
+    values::{binder::Binder, dynamic::RecordBuilder},
+};
+use bumpalo::Bump;
+
+#[test]
+fn test_bool_package_builds() {
+    let arena = Bump::new();
+    let type_mgr = TypeManager::new(&arena);
+
+    let bool_pkg = register_bool_functions(&arena, type_mgr, RecordBuilder::new(&arena, type_mgr))
+        .build()
+        .unwrap();
+    let record = bool_pkg.as_record().unwrap();
+
+    assert!(record.get("Xor").is_some());
+    assert!(record.get("Implies").is_some());
+}
+
+/// Evaluate a Bool expression with the Bool package registered.
+fn eval_bool(source: &str) -> Result<bool, String> {
+    let arena = Bump::new();
+    let engine = Engine::new(EngineOptions::default(), &arena, register_bool_package);
+    let expr = engine
+        .compile(CompileOptionsOverride::default(), source, &[])
+        .expect("compilation should succeed");
+
+    let val_arena = Bump::new();
+    expr.run(Default::default(), &val_arena, &[])
+        .map(|value| value.as_bool().unwrap())
+        .map_err(|err| err.to_string())
+}
+
+/// Check `function` against its truth table, given as results for
+/// `(false, false)`, `(false, true)`, `(true, false)` and `(true, true)`.
+fn assert_truth_table(function: &str, expected: [bool; 4]) {
+    let inputs = [(false, false), (false, true), (true, false), (true, true)];
+    for ((a, b), expected) in inputs.into_iter().zip(expected) {
+        let source = format!("{}({}, {})", function, a, b);
+        assert_eq!(eval_bool(&source), Ok(expected), "{}", source);
+    }
+}
+
+#[test]
+fn test_bool_xor_truth_table() {
+    assert_truth_table("Bool.Xor", [false, true, true, false]);
+}
+
+#[test]
+fn test_bool_implies_truth_table() {
+    assert_truth_table("Bool.Implies", [true, true, false, true]);
+}
+
+#[test]
+fn test_and_or_short_circuit() {
+    // The right operand is not evaluated when the left decides the result
+    assert_eq!(eval_bool("false and 1 / 0 == 0"), Ok(false));
+    assert_eq!(eval_bool("true or 1 / 0 == 0"), Ok(true));
+
+    // Otherwise it is, and its error propagates
+    assert!(eval_bool("true and 1 / 0 == 0").is_err());
+    assert!(eval_bool("false or 1 / 0 == 0").is_err());
+}
+
+#[test]
+fn test_bool_functions_evaluate_both_arguments() {
+    // Unlike `or`, `Bool.Implies` evaluates `b` even when `a` decides the result
+    assert!(eval_bool("Bool.Implies(false, 1 / 0 == 0)").is_err());
+    assert!(eval_bool("Bool.Xor(true, 1 / 0 == 0)").is_err());
+}
@@ -0,0 +1,447 @@
+//! Builtin functions
+//!
+//! Builtins are lowercase global functions that every expression can use
+//! without registering a package: `abs`, `coalesce`, `min`, `max`, `sum`, and
+//! `typeof`.
+//!
+//! Unlike package functions, builtins are polymorphic with type class
+//! constraints. Each use instantiates the signature with fresh type variables,
+//! so `min(1, 2)` and `min("a", "b")` (or `abs(-3)` and `abs(-3.0)`) can
+//! appear in the same expression, and
+//! the constraints are checked at the use site. At runtime, calls see the
+//! argument types of their call site (monomorphized by the analyzer).
+//!
+//! Any binding with the same name (a global, variable, or `where` binding)
+//! shadows the builtin.
+//!
+//! Builtins whose names start with `#` are internal: the parser desugars
+//! comprehensions into calls to `#filter`, `#map`, and `#merge_maps`. No
+//! identifier can start with `#`, so user code can neither call nor shadow
+//! them.
+
+use crate::{
+    String, ToString, Vec,
+    evaluator::{ExecutionError, ExecutionErrorKind, RuntimeError, operators},
+    parser::{BinaryOp, Span},
+    stdlib::array,
+    types::{Type, manager::TypeManager, traits::display_type_with_var_names},
+    values::{
+        dynamic::Value,
+        function::{FfiContext, Function, NativeFn},
+    },
+};
+use bumpalo::Bump;
+
+/// A type class constraint on a builtin's signature.
+#[derive(Debug, Clone, Copy)]
+pub enum Constraint<'t> {
+    /// The type must support ordering (`<`, `>`, ...).
+    Ord(&'t Type<'t>),
+    /// The type must support arithmetic (`+`, `-`, ...).
+    Numeric(&'t Type<'t>),
+}
+
+/// A builtin's type, instantiated with fresh type variables.
+pub struct Signature<'t> {
+    pub ty: &'t Type<'t>,
+    pub constraints: Vec<Constraint<'t>>,
+}
+
+/// A builtin function.
+pub struct Builtin {
+    pub name: &'static str,
+    signature: for<'t> fn(&'t TypeManager<'t>) -> Signature<'t>,
+    function: NativeFn,
+}
+
+impl Builtin {
+    /// Instantiate this builtin's signature with fresh type variables.
+    pub fn signature<'t>(&self, type_mgr: &'t TypeManager<'t>) -> Signature<'t> {
+        (self.signature)(type_mgr)
+    }
+
+    /// Whether this builtin is internal, only called by desugared syntax.
+    pub fn is_internal(&self) -> bool {
+        self.name.starts_with('#')
+    }
+
+    /// Build this builtin's function value, typed as at its use.
+    ///
+    /// `ty` is the type the analyzer gave the use, an instance of the
+    /// signature, so building the value allocates no type variables.
+    pub fn value<'types, 'arena>(
+        &self,
+        arena: &'arena Bump,
+        ty: &'types Type<'types>,
+    ) -> Value<'types, 'arena> {
+        Value::function(
+            arena,
+            BuiltinFunction {
+                name: self.name,
+                ty,
+                function: self.function,
+            },
+        )
+        .expect("Builtin signatures are function types")
+    }
+}
+
+/// A builtin's function value.
+struct BuiltinFunction<'t> {
+    name: &'static str,
+    ty: &'t Type<'t>,
+    function: NativeFn,
+}
+
+impl<'types, 'arena> Function<'types, 'arena> for BuiltinFunction<'types> {
+    fn ty(&self) -> &'types Type<'types> {
+        self.ty
+    }
+
+    #[allow(unsafe_code)]
+    unsafe fn call_unchecked(
+        &self,
+        ctx: &FfiContext<'types, 'arena>,
+        args: &[Value<'types, 'arena>],
+    ) -> Result<Value<'types, 'arena>, ExecutionError> {
+        if let Some(name) = self.global_name() {
+            ctx.report_call(name, args.len())?;
+        }
+        (self.function)(ctx, args)
+    }
+
+    /// Internal builtins are called by desugared syntax, not by name, so
+    /// calls to them are not reported.
+    fn global_name(&self) -> Option<&str> {
+        (!self.name.starts_with('#')).then_some(self.name)
+    }
+}
+
+/// All builtins, sorted by name.
+static BUILTINS: &[Builtin] = &[
+    Builtin {
+        name: "#filter",
+        signature: filter_signature,
+        function: array::array_filter,
+    },
+    Builtin {
+        name: "#map",
+        signature: map_signature,
+        function: array::array_map,
+    },
+    Builtin {
+        name: "#merge_maps",
+        signature: merge_maps_signature,
+        function: array::array_merge_maps,
+    },
+    Builtin {
+        name: "abs",
+        signature: numeric_unary_signature,
+        function: builtin_abs,
+    },
+    Builtin {
+        name: "coalesce",
+        signature: coalesce_signature,
+        function: builtin_coalesce,
+    },
+    Builtin {
+        name: "max",
+        signature: ordered_binary_signature,
+        function: builtin_max,
+    },
+    Builtin {
+        name: "min",
+        signature: ordered_binary_signature,
+        function: builtin_min,
+    },
+    Builtin {
+        name: "sum",
+        signature: numeric_sum_signature,
+        function: builtin_sum,
+    },
+    Builtin {
+        name: "typeof",
+        signature: typeof_signature,
+        function: builtin_typeof,
+    },
+];
+
+/// Look up a builtin by name.
+pub fn lookup(name: &str) -> Option<&'static Builtin> {
+    BUILTINS
+        .binary_search_by_key(&name, |builtin| builtin.name)
+        .ok()
+        .map(|index| &BUILTINS[index])
+}
+
+/// `(a, a) -> a` where `a` is ordered.
+fn ordered_binary_signature<'t>(type_mgr: &'t TypeManager<'t>) -> Signature<'t> {
+    let a = type_mgr.fresh_type_var();
+    Signature {
+        ty: type_mgr.function(&[a, a], a),
+        constraints: alloc::vec![Constraint::Ord(a)],
+    }
+}
+
+/// `(a) -> a` where `a` is numeric.
+fn numeric_unary_signature<'t>(type_mgr: &'t TypeManager<'t>) -> Signature<'t> {
+    let a = type_mgr.fresh_type_var();
+    Signature {
+        ty: type_mgr.function(&[a], a),
+        constraints: alloc::vec![Constraint::Numeric(a)],
+    }
+}
+
+/// `(Array[a]) -> a` where `a` is numeric.
+fn numeric_sum_signature<'t>(type_mgr: &'t TypeManager<'t>) -> Signature<'t> {
+    let a = type_mgr.fresh_type_var();
+    Signature {
+        ty: type_mgr.function(&[type_mgr.array(a)], a),
+        constraints: alloc::vec![Constraint::Numeric(a)],
+    }
+}
+
+/// `(a) -> Str`
+fn typeof_signature<'t>(type_mgr: &'t TypeManager<'t>) -> Signature<'t> {
+    Signature {
+        ty: type_mgr.function(&[type_mgr.fresh_type_var()], type_mgr.str()),
+        constraints: Vec::new(),
+    }
+}
+
+/// `(Array[Option[a]]) -> Option[a]`
+fn coalesce_signature<'t>(type_mgr: &'t TypeManager<'t>) -> Signature<'t> {
+    let option = type_mgr.option(type_mgr.fresh_type_var());
+    Signature {
+        ty: type_mgr.function(&[type_mgr.array(option)], option),
+        constraints: Vec::new(),
+    }
+}
+
+/// `(Array[a], (a) -> Bool) -> Array[a]`
+fn filter_signature<'t>(type_mgr: &'t TypeManager<'t>) -> Signature<'t> {
+    let a = type_mgr.fresh_type_var();
+    let predicate = type_mgr.function(&[a], type_mgr.bool());
+    Signature {
+        ty: type_mgr.function(&[type_mgr.array(a), predicate], type_mgr.array(a)),
+        constraints: Vec::new(),
+    }
+}
+
+/// `(Array[a], (a) -> b) -> Array[b]`
+fn map_signature<'t>(type_mgr: &'t TypeManager<'t>) -> Signature<'t> {
+    let a = type_mgr.fresh_type_var();
+    let b = type_mgr.fresh_type_var();
+    let mapper = type_mgr.function(&[a], b);
+    Signature {
+        ty: type_mgr.function(&[type_mgr.array(a), mapper], type_mgr.array(b)),
+        constraints: Vec::new(),
+    }
+}
+
+/// `(Array[Map[k, v]]) -> Map[k, v]`
+fn merge_maps_signature<'t>(type_mgr: &'t TypeManager<'t>) -> Signature<'t> {
+    let map = type_mgr.map(type_mgr.fresh_type_var(), type_mgr.fresh_type_var());
+    Signature {
+        ty: type_mgr.function(&[type_mgr.array(map)], map),
+        constraints: Vec::new(),
+    }
+}
+
+/// The first `some` in the array, or `none` if there is none.
+///
+/// Stops at the first `some`.
+fn builtin_coalesce<'types, 'arena>(
+    ctx: &FfiContext<'types, 'arena>,
+    args: &[Value<'types, 'arena>],
+) -> Result<Value<'types, 'arena>, ExecutionError> {
+    let Type::Array(option_ty) = args[0].ty else {
+        unreachable!("coalesce takes an array (type-checked)");
+    };
+    let options = args[0].as_array().expect("Type-checked as Array");
+    let first_some = options
+        .iter()
+        .find(|option| option.as_option().expect("Type-checked as Option").is_some());
+    Ok(match first_some {
+        Some(option) => option,
+        None => Value::optional(ctx.arena(), option_ty, None).expect("Element type is Option"),
+    })
+}
+
+/// The smaller argument, or the first one if they are equal.
+///
+/// Uses the same ordering as sorting, so `NaN` is greater than every float.
+fn builtin_min<'types, 'arena>(
+    _ctx: &FfiContext<'types, 'arena>,
+    args: &[Value<'types, 'arena>],
+) -> Result<Value<'types, 'arena>, ExecutionError> {
+    let (left, right) = (args[0], args[1]);
+    Ok(if right < left { right } else { left })
+}
+
+/// The larger argument, or the first one if they are equal.
+///
+/// Uses the same ordering as sorting, so `NaN` is greater than every float.
+fn builtin_max<'types, 'arena>(
+    _ctx: &FfiContext<'types, 'arena>,
+    args: &[Value<'types, 'arena>],
+) -> Result<Value<'types, 'arena>, ExecutionError> {
+    let (left, right) = (args[0], args[1]);
+    Ok(if right > left { right } else { left })
+}
+
+/// The absolute value of the argument.
+///
+/// Negates like unary `-`: `abs` of the smallest `Int` wraps around to itself.
+fn builtin_abs<'types, 'arena>(
+    ctx: &FfiContext<'types, 'arena>,
+    args: &[Value<'types, 'arena>],
+) -> Result<Value<'types, 'arena>, ExecutionError> {
+    let value = args[0];
+    let type_mgr = ctx.type_mgr();
+    Ok(match value.ty {
+        Type::Int => {
+            let int = value.as_int().expect("Type-checked as Int");
+            Value::int(type_mgr, int.wrapping_abs())
+        }
+        Type::Float => {
+            let float = value.as_float().expect("Type-checked as Float");
+            Value::float(type_mgr, float.abs())
+        }
+        #[cfg(feature = "bigint")]
+        Type::BigInt => {
+            let bigint = value.as_bigint().expect("Type-checked as BigInt");
+            let abs = if bigint.sign() == num_bigint::Sign::Minus {
+                operators::eval_unary_bigint(crate::parser::UnaryOp::Neg, &bigint)
+            } else {
+                bigint
+            };
+            Value::bigint(ctx.arena(), type_mgr, &abs)
+        }
+        #[cfg(feature = "decimal")]
+        Type::Decimal => {
+            let decimal = value.as_decimal().expect("Type-checked as Decimal");
+            let abs = if decimal < crate::values::decimal::Decimal::ZERO {
+                operators::eval_unary_decimal(crate::parser::UnaryOp::Neg, decimal)
+                    .map_err(execution_error)?
+            } else {
+                decimal
+            };
+            Value::decimal(ctx.arena(), type_mgr, abs)
+        }
+        _ => unreachable!("abs takes a numeric type (type-checked)"),
+    })
+}
+
+/// The sum of the array's elements, or zero if it is empty.
+///
+/// Adds like `+`: an `Int` sum wraps around on overflow. The analyzer
+/// rejects `sum([])`, whose element type nothing pins down; an empty array of
+/// unknown element type that still gets here has no zero to return, so
+/// summing it is an invalid argument error.
+fn builtin_sum<'types, 'arena>(
+    ctx: &FfiContext<'types, 'arena>,
+    args: &[Value<'types, 'arena>],
+) -> Result<Value<'types, 'arena>, ExecutionError> {
+    let Type::Array(element_ty) = args[0].ty else {
+        unreachable!("sum takes an array (type-checked)");
+    };
+    let elements = args[0].as_array().expect("Type-checked as Array");
+    elements
+        .iter()
+        .try_fold(zero(ctx, element_ty)?, |total, element| {
+            add(ctx, total, element)
+        })
+}
+
+/// The name of the argument's type, like `"Array[Int]"`.
+///
+/// The analyzer folds every call to `typeof` into a constant (without
+/// evaluating the argument), so this only runs when `typeof` is passed as a
+/// value, like in `Array.Map(xs, typeof)`.
+fn builtin_typeof<'types, 'arena>(
+    ctx: &FfiContext<'types, 'arena>,
+    args: &[Value<'types, 'arena>],
+) -> Result<Value<'types, 'arena>, ExecutionError> {
+    let type_name = display_type_with_var_names(args[0].ty);
+    Ok(Value::str(ctx.arena(), ctx.type_mgr().str(), &type_name))
+}
+
+/// Zero of a numeric type, or an error for a type variable the analyzer left
+/// unresolved.
+fn zero<'types, 'arena>(
+    ctx: &FfiContext<'types, 'arena>,
+    ty: &'types Type<'types>,
+) -> Result<Value<'types, 'arena>, ExecutionError> {
+    let type_mgr = ctx.type_mgr();
+    Ok(match ty {
+        Type::Int => Value::int(type_mgr, 0),
+        Type::Float => Value::float(type_mgr, 0.0),
+        #[cfg(feature = "bigint")]
+        Type::BigInt => Value::bigint(ctx.arena(), type_mgr, &num_bigint::BigInt::default()),
+        #[cfg(feature = "decimal")]
+        Type::Decimal => {
+            Value::decimal(ctx.arena(), type_mgr, crate::values::decimal::Decimal::ZERO)
+        }
+        Type::TypeVar(_) => {
+            return Err(execution_error(
+                RuntimeError::InvalidArgument {
+                    message: "cannot sum an empty array of unknown element type".to_string(),
+                }
+                .into(),
+            ));
+        }
+        _ => unreachable!("Numeric type (type-checked)"),
+    })
+}
+
+/// `left + right` for two values of the same numeric type.
+fn add<'types, 'arena>(
+    ctx: &FfiContext<'types, 'arena>,
+    left: Value<'types, 'arena>,
+    right: Value<'types, 'arena>,
+) -> Result<Value<'types, 'arena>, ExecutionError> {
+    let type_mgr = ctx.type_mgr();
+    Ok(match left.ty {
+        Type::Int => {
+            let l = left.as_int().expect("Type-checked as Int");
+            let r = right.as_int().expect("Type-checked as Int");
+            let sum = operators::eval_binary_int(BinaryOp::Add, l, r).map_err(execution_error)?;
+            Value::int(type_mgr, sum)
+        }
+        Type::Float => {
+            let l = left.as_float().expect("Type-checked as Float");
+            let r = right.as_float().expect("Type-checked as Float");
+            Value::float(type_mgr, operators::eval_binary_float(BinaryOp::Add, l, r))
+        }
+        #[cfg(feature = "bigint")]
+        Type::BigInt => {
+            let l = left.as_bigint().expect("Type-checked as BigInt");
+            let r = right.as_bigint().expect("Type-checked as BigInt");
+            let sum =
+                operators::eval_binary_bigint(BinaryOp::Add, &l, &r).map_err(execution_error)?;
+            Value::bigint(ctx.arena(), type_mgr, &sum)
+        }
+        #[cfg(feature = "decimal")]
+        Type::Decimal => {
+            let l = left.as_decimal().expect("Type-checked as Decimal");
+            let r = right.as_decimal().expect("Type-checked as Decimal");
+            let sum =
+                operators::eval_binary_decimal(BinaryOp::Add, l, r).map_err(execution_error)?;
+            Value::decimal(ctx.arena(), type_mgr, sum)
+        }
+        _ => unreachable!("Numeric type (type-checked)"),
+    })
+}
+
+fn execution_error(kind: ExecutionErrorKind) -> ExecutionError {
+    ExecutionError {
+        kind,
+        source: String::new(),
+        span: Span(0..0),
+    }
+}
+
+#[cfg(test)]
+#[path = "builtins_test.rs"]
+mod builtins_test;
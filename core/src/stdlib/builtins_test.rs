@@ -0,0 +1,304 @@
+//! Tests for builtin functions
+
+use super::lookup;
+use crate::{
+    api::{CompileOptionsOverride, Engine, EngineOptions, Error, RunOptionsOverride, RuntimeKind},
+    values::dynamic::Value,
+};
+use bumpalo::Bump;
+
+/// Evaluate `source` with both runtimes, which must agree on the result.
+fn eval<'a>(arena: &'a Bump, source: &'a str) -> Result<Value<'a, 'a>, Error> {
+    let engine = Engine::new(EngineOptions::default(), arena, |_arena, _type_mgr, env| {
+        env
+    });
+    let expr = engine.compile(CompileOptionsOverride::default(), source, &[])?;
+    let options = RunOptionsOverride {
+        runtime: Some(RuntimeKind::Both),
+        ..Default::default()
+    };
+    expr.run(options, arena, &[])
+}
+
+#[test]
+fn test_builtins_are_sorted() {
+    assert!(lookup("abs").is_some());
+    assert!(lookup("coalesce").is_some());
+    assert!(lookup("max").is_some());
+    assert!(lookup("min").is_some());
+    assert!(lookup("sum").is_some());
+    assert!(lookup("Min").is_none());
+    assert!(lookup("#filter").is_some());
+    assert!(lookup("#map").is_some());
+    assert!(lookup("#merge_maps").is_some());
+}
+
+#[test]
+fn test_comprehensions_need_no_packages() {
+    let arena = Bump::new();
+    let result = eval(&arena, "[x + 1 for x in [1, 2, 3] if x > 1]").unwrap();
+    assert_eq!(result.to_string(), "[3, 4]");
+    let result = eval(&arena, "{x: x * x for x in [1, 2]}").unwrap();
+    assert_eq!(result.to_string(), "{1: 1, 2: 4}");
+}
+
+#[test]
+fn test_min_max_polymorphic_in_one_expression() {
+    let arena = Bump::new();
+    let result = eval(
+        &arena,
+        r#"{
+            ints = [min(3, -2), max(3, -2)],
+            floats = [min(1.5, 2.5), max(1.5, 2.5)],
+            strs = [min("pear", "apple"), max("pear", "apple")],
+        }"#,
+    )
+    .unwrap();
+    assert_eq!(
+        result.to_string(),
+        r#"{floats = [1.5, 2.5], ints = [-2, 3], strs = ["apple", "pear"]}"#
+    );
+}
+
+#[test]
+fn test_min_max_inside_polymorphic_lambda() {
+    let arena = Bump::new();
+    let result = eval(
+        &arena,
+        r#"[clamp(15, 0, 10), clamp(-5, 0, 10)] == [10, 0] and clamp("m", "a", "c") == "c"
+            where { clamp = (x, low, high) => min(max(x, low), high) }"#,
+    )
+    .unwrap();
+    assert!(result.as_bool().unwrap());
+}
+
+#[test]
+fn test_min_requires_ordered_type() {
+    let arena = Bump::new();
+    let Err(Error::Compilation { diagnostics, .. }) = eval(&arena, "min(true, false)") else {
+        panic!("min on Bool should fail to compile");
+    };
+    assert!(diagnostics[0].message.contains("does not implement Ord"));
+}
+
+#[test]
+fn test_bindings_shadow_builtins() {
+    let arena = Bump::new();
+    let result = eval(&arena, "min(1, 2) where { min = (a, b) => a + b }").unwrap();
+    assert_eq!(result.as_int().unwrap(), 3);
+}
+
+#[test]
+fn test_coalesce_returns_first_some() {
+    let arena = Bump::new();
+    let result = eval(&arena, "coalesce([none, some 2, some 3])").unwrap();
+    let inner = result.as_option().unwrap().unwrap();
+    assert_eq!(inner.as_int().unwrap(), 2);
+}
+
+#[test]
+fn test_coalesce_all_none() {
+    let arena = Bump::new();
+    let result = eval(&arena, "coalesce([none, none])").unwrap();
+    assert!(result.as_option().unwrap().is_none());
+}
+
+#[test]
+fn test_coalesce_empty_array() {
+    let arena = Bump::new();
+    let result = eval(&arena, "coalesce([]) ?? 7").unwrap();
+    assert_eq!(result.as_int().unwrap(), 7);
+}
+
+#[test]
+fn test_abs_polymorphic_in_one_expression() {
+    let arena = Bump::new();
+    let result = eval(
+        &arena,
+        "{int = abs(-3), float = abs(-3.0), positive = abs(2)}",
+    )
+    .unwrap();
+    assert_eq!(result.to_string(), "{float = 3., int = 3, positive = 2}");
+}
+
+#[test]
+fn test_abs_of_smallest_int_wraps_like_negation() {
+    let arena = Bump::new();
+    let result = eval(
+        &arena,
+        "abs(-9223372036854775808) == -(-9223372036854775808)",
+    )
+    .unwrap();
+    assert!(result.as_bool().unwrap());
+}
+
+#[test]
+fn test_sum_float_array() {
+    let arena = Bump::new();
+    let result = eval(&arena, "sum([1.5, 2.25, -0.75])").unwrap();
+    assert_eq!(result.as_float().unwrap(), 3.0);
+}
+
+#[test]
+fn test_sum_empty_array_is_zero() {
+    let arena = Bump::new();
+    // The other branch gives the empty array its element type
+    let result = eval(
+        &arena,
+        "{ints = sum(if true then [] else [1]), floats = sum(if true then [] else [1.0])}",
+    )
+    .unwrap();
+    assert_eq!(result.to_string(), "{floats = 0., ints = 0}");
+}
+
+#[test]
+fn test_sum_of_untyped_empty_array_fails_to_compile() {
+    let arena = Bump::new();
+    // Nothing gives `[]` an element type, so there is no zero to return
+    for source in ["sum([])", "[sum([])]", "sum([]) where { xs = [1] }"] {
+        let Err(Error::Compilation { diagnostics, .. }) = eval(&arena, source) else {
+            panic!("{source} should fail to compile");
+        };
+        assert_eq!(diagnostics[0].code.as_deref(), Some("E028"), "{source}");
+    }
+    let Err(Error::Compilation { diagnostics, .. }) = eval(&arena, "[sum([])]") else {
+        panic!("sum([]) should fail to compile");
+    };
+    assert_eq!(diagnostics[0].span, crate::parser::Span(5..7));
+
+    // The rest of the expression may pin the element type down
+    let result = eval(&arena, "[sum([]), 1]").unwrap();
+    assert_eq!(result.to_string(), "[0, 1]");
+
+    // A polymorphic lambda's element type is known at each call
+    let result = eval(&arena, "f([1.5]) where { f = (xs) => sum(xs) }").unwrap();
+    assert_eq!(result.as_float().unwrap(), 1.5);
+
+    // A binding named `sum` is not the builtin
+    let result = eval(&arena, "sum([]) where { sum = (xs) => 0 }").unwrap();
+    assert_eq!(result.as_int().unwrap(), 0);
+}
+
+#[test]
+fn test_abs_and_sum_inside_polymorphic_lambda() {
+    let arena = Bump::new();
+    let result = eval(
+        &arena,
+        "magnitude([1, -7]) == 6 and magnitude([0.5, -2.0]) == 1.5 \
+            where { magnitude = (xs) => abs(sum(xs)) }",
+    )
+    .unwrap();
+    assert!(result.as_bool().unwrap());
+}
+
+#[test]
+fn test_abs_requires_numeric_type() {
+    let arena = Bump::new();
+    let Err(Error::Compilation { diagnostics, .. }) = eval(&arena, r#"abs("text")"#) else {
+        panic!("abs on Str should fail to compile");
+    };
+    assert!(
+        diagnostics[0]
+            .message
+            .contains("does not implement Numeric"),
+        "{}",
+        diagnostics[0].message
+    );
+}
+
+#[cfg(feature = "bigint")]
+#[test]
+fn test_abs_and_sum_bigint() {
+    let arena = Bump::new();
+    let result = eval(
+        &arena,
+        "abs(-9223372036854775808n) == sum([9223372036854775807n, 1n])",
+    )
+    .unwrap();
+    assert!(result.as_bool().unwrap());
+}
+
+#[cfg(feature = "decimal")]
+#[test]
+fn test_abs_and_sum_decimal() {
+    let arena = Bump::new();
+    let result = eval(&arena, "abs(-0.3d) == sum([0.1d, 0.2d])").unwrap();
+    assert!(result.as_bool().unwrap());
+}
+
+#[test]
+fn test_typeof_names_static_types() {
+    let arena = Bump::new();
+    let result = eval(
+        &arena,
+        "[typeof([1, 2]), typeof(some 1), typeof(1.5), typeof((x) => x + 1)]",
+    )
+    .unwrap();
+    assert_eq!(
+        result.to_string(),
+        r#"["Array[Int]", "Option[Int]", "Float", "(Int) => Int"]"#
+    );
+}
+
+#[test]
+fn test_typeof_does_not_evaluate_its_argument() {
+    let arena = Bump::new();
+    let result = eval(&arena, "typeof(1 / 0)").unwrap();
+    assert_eq!(result.as_str().unwrap(), "Int");
+}
+
+#[test]
+fn test_typeof_names_type_variables_in_order() {
+    let arena = Bump::new();
+    let result = eval(
+        &arena,
+        "[typeof(none), typeof((x) => x), typeof((x, y) => y), typeof(f)] where { f = (x) => x }",
+    )
+    .unwrap();
+    assert_eq!(
+        result.to_string(),
+        r#"["Option[a]", "(a) => a", "(a, b) => b", "(a) => a"]"#
+    );
+}
+
+#[test]
+fn test_typeof_inside_polymorphic_lambda_is_the_static_type() {
+    let arena = Bump::new();
+    let result = eval(
+        &arena,
+        r#"[describe(1), describe("s"), describe([some 1.5])] where { describe = (x) => typeof(x) }"#,
+    )
+    .unwrap();
+    assert_eq!(result.to_string(), r#"["a", "a", "a"]"#);
+
+    // The argument isn't evaluated there either
+    let result = eval(
+        &arena,
+        "describe(0) where { describe = (x) => typeof(x / x) }",
+    )
+    .unwrap();
+    assert_eq!(result.as_str().unwrap(), "a");
+}
+
+#[test]
+fn test_builtins_allocate_no_type_variables_when_run() {
+    let arena = Bump::new();
+    let engine = Engine::new(EngineOptions::default(), &arena, |arena, type_mgr, env| {
+        crate::stdlib::register_array_package(arena, type_mgr, env)
+    });
+    // Uses each builtin more times than there are type variable ids
+    let expr = engine
+        .compile(
+            CompileOptionsOverride::default(),
+            "Array.Map(Array.Range(0, 70000, 1), (i) => \
+             { n = min(i, 5) + max(i, 5) + abs(i) + sum([i]), o = coalesce([some i]) })",
+            &[],
+        )
+        .unwrap();
+    let options = RunOptionsOverride {
+        runtime: Some(RuntimeKind::Both),
+        ..Default::default()
+    };
+    let result = expr.run(options, &arena, &[]).unwrap();
+    assert_eq!(result.as_array().unwrap().len(), 70000);
+}
@@ -0,0 +1,60 @@
+//! Bytes Package
+//!
+//! Provides conversions between byte strings and integers for Melbi.
+//!
+//! Functions:
+//! - `FromInts(ints)`: Pack integers in `0..=255` into bytes
+//! - `ToInts(bytes)`: Unpack bytes into integers in `0..=255`
+
+use crate::{
+    Vec,
+    evaluator::RuntimeError,
+    format,
+    values::{FfiContext, typed::Array},
+};
+use melbi_macros::{melbi_fn, melbi_package};
+
+#[melbi_package]
+mod bytes {
+    use super::*;
+
+    /// Pack integers into bytes, one byte per integer.
+    ///
+    /// Fails (catchable with `otherwise`) if any value is outside `0..=255`.
+    ///
+    /// Examples:
+    /// - `Bytes.FromInts([104, 105]) -> b"hi"`
+    /// - `Bytes.FromInts([256])      -> error`
+    #[melbi_fn]
+    fn from_ints<'a>(
+        ctx: &FfiContext<'_, 'a>,
+        ints: Array<'a, i64>,
+    ) -> Result<&'a [u8], RuntimeError> {
+        let bytes = ints
+            .iter()
+            .map(|int| {
+                u8::try_from(int).map_err(|_| RuntimeError::InvalidArgument {
+                    message: format!("{} is not a byte (0 to 255)", int),
+                })
+            })
+            .collect::<Result<Vec<u8>, _>>()?;
+        Ok(ctx.arena().alloc_slice_copy(&bytes))
+    }
+
+    /// Unpack bytes into integers in `0..=255`, the inverse of `Bytes.FromInts`.
+    ///
+    /// Examples:
+    /// - `Bytes.ToInts(b"hi") -> [104, 105]`
+    #[melbi_fn]
+    fn to_ints<'a>(ctx: &FfiContext<'_, 'a>, bytes: &'a [u8]) -> Array<'a, i64> {
+        let ints: Vec<i64> = bytes.iter().map(|&byte| i64::from(byte)).collect();
+        Array::new(ctx.arena(), &ints)
+    }
+}
+
+// Re-export everything for cleaner access
+pub use self::bytes::*;
+
+#[cfg(test)]
+#[path = "bytes_test.rs"]
+mod bytes_test;
@@ -0,0 +1,126 @@
+//! Tests for the Bytes package
+
+use super::{register_bytes_functions, register_bytes_package};
+use crate::{
+    api::{CompileOptionsOverride, Engine, EngineOptions, Error},
+    types::manager::TypeManager,
+    values::{
+        binder::Binder,
+        dynamic::{RecordBuilder, Value},
+    },
+};
+use bumpalo::Bump;
+
+#[test]
+fn test_bytes_package_builds() {
+    let arena = Bump::new();
+    let type_mgr = TypeManager::new(&arena);
+
+    let bytes = register_bytes_functions(&arena, type_mgr, RecordBuilder::new(&arena, type_mgr))
+        .build()
+        .unwrap();
+    let record = bytes.as_record().unwrap();
+
+    assert!(record.get("FromInts").is_some());
+    assert!(record.get("ToInts").is_some());
+}
+
+// Helper function for integration tests using the Engine to evaluate Melbi code
+fn test_bytes_expr<F>(source: &str, check: F)
+where
+    F: FnOnce(Value),
+{
+    let options = EngineOptions::default();
+    let arena = Bump::new();
+
+    let engine = Engine::new(options, &arena, register_bytes_package);
+
+    let compile_opts = CompileOptionsOverride::default();
+    let expr = engine
+        .compile(compile_opts, source, &[])
+        .expect("compilation should succeed");
+
+    let val_arena = Bump::new();
+    let result = expr
+        .run(Default::default(), &val_arena, &[])
+        .expect("execution should succeed");
+
+    check(result);
+}
+
+// Helper function returning the diagnostic code of a failing Melbi expression
+fn bytes_expr_error_code(source: &str) -> Option<String> {
+    let options = EngineOptions::default();
+    let arena = Bump::new();
+
+    let engine = Engine::new(options, &arena, register_bytes_package);
+
+    let compile_opts = CompileOptionsOverride::default();
+    let expr = engine
+        .compile(compile_opts, source, &[])
+        .expect("compilation should succeed");
+
+    let val_arena = Bump::new();
+    match expr.run(Default::default(), &val_arena, &[]) {
+        Err(Error::Runtime { diagnostic, .. }) => diagnostic.code,
+        other => panic!("expected a runtime error, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_bytes_from_ints() {
+    test_bytes_expr("Bytes.FromInts([104, 105, 0, 255])", |r: Value| {
+        assert_eq!(r.as_bytes().unwrap(), b"hi\x00\xff");
+    });
+
+    test_bytes_expr("Bytes.FromInts([])", |r: Value| {
+        assert_eq!(r.as_bytes().unwrap(), b"");
+    });
+}
+
+#[test]
+fn test_bytes_to_ints() {
+    test_bytes_expr(r#"Bytes.ToInts(b"hi\x00\xff")"#, |r: Value| {
+        let ints: Vec<i64> = r
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|int| int.as_int().unwrap())
+            .collect();
+        assert_eq!(ints, [104, 105, 0, 255]);
+    });
+}
+
+#[test]
+fn test_bytes_round_trip() {
+    test_bytes_expr(
+        r#"Bytes.FromInts(Bytes.ToInts(b"\x00a\x7f\x80\xff"))"#,
+        |r: Value| {
+            assert_eq!(r.as_bytes().unwrap(), b"\x00a\x7f\x80\xff");
+        },
+    );
+
+    test_bytes_expr(
+        "Bytes.ToInts(Bytes.FromInts([0, 1, 127, 128, 255]))",
+        |r: Value| {
+            assert_eq!(r.to_string(), "[0, 1, 127, 128, 255]");
+        },
+    );
+}
+
+#[test]
+fn test_bytes_from_ints_out_of_range() {
+    // Negative and too-large values fail, catchable with `otherwise`
+    for int in ["-1", "256", "9223372036854775807"] {
+        let source = format!(r#"Bytes.FromInts([1, {}]) otherwise b"invalid""#, int);
+        test_bytes_expr(&source, |r: Value| {
+            assert_eq!(r.as_bytes().unwrap(), b"invalid");
+        });
+    }
+
+    // Reported as an invalid argument
+    assert_eq!(
+        bytes_expr_error_code("Bytes.FromInts([256])").as_deref(),
+        Some("R008")
+    );
+}
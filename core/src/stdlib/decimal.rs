@@ -0,0 +1,116 @@
+//! `Decimal` package for Melbi
+//!
+//! Parsing and formatting for fixed-point decimals (`decimal` feature).
+//!
+//! Functions:
+//! - `Parse(s)`: Parse a decimal string, returning `none` if it is not valid
+//! - `ToString(d)`: Format a decimal without trailing zeros
+use crate::{
+    evaluator::ExecutionError,
+    format,
+    types::manager::TypeManager,
+    values::{
+        binder::Binder,
+        decimal::Decimal,
+        dynamic::Value,
+        function::{FfiContext, NativeFunction},
+    },
+};
+use bumpalo::Bump;
+
+/// Parse a string as a decimal
+///
+/// Accepts an optional sign, digits and an optional fractional part. Returns
+/// `none` for other input or values exceeding Decimal's range or precision.
+///
+/// # Examples
+/// - `Decimal.Parse("19.99")` → `some 19.99d`
+/// - `Decimal.Parse("-.5")` → `some -0.5d`
+/// - `Decimal.Parse("1e3")` → `none`
+fn decimal_parse<'types, 'arena>(
+    ctx: &FfiContext<'types, 'arena>,
+    args: &[Value<'types, 'arena>],
+) -> Result<Value<'types, 'arena>, ExecutionError> {
+    debug_assert_eq!(args.len(), 1);
+    let text = args[0].as_str().expect("Expected string");
+    let type_mgr = ctx.type_mgr();
+    let parsed = Decimal::parse(text.trim())
+        .map(|decimal| Value::decimal(ctx.arena(), type_mgr, decimal));
+    Ok(
+        Value::optional(ctx.arena(), type_mgr.option(type_mgr.decimal()), parsed)
+            .expect("Type error in Decimal.Parse: option construction failed"),
+    )
+}
+
+/// Format a decimal as a string
+///
+/// # Examples
+/// - `Decimal.ToString(0.1d + 0.2d)` → `"0.3"`
+/// - `Decimal.ToString(-2.50d)` → `"-2.5"`
+fn decimal_to_string<'types, 'arena>(
+    ctx: &FfiContext<'types, 'arena>,
+    args: &[Value<'types, 'arena>],
+) -> Result<Value<'types, 'arena>, ExecutionError> {
+    debug_assert_eq!(args.len(), 1);
+    let decimal = args[0].as_decimal().expect("Expected decimal");
+    let type_mgr = ctx.type_mgr();
+    Ok(Value::str(ctx.arena(), type_mgr.str(), &format!("{}", decimal)))
+}
+
+// ============================================================================
+// Package Registration
+// ============================================================================
+
+/// Registers all functions from the Decimal package directly to a Binder.
+///
+/// Use this to flatten the package's contents into a global environment or another record.
+pub fn register_decimal_functions<'a, B>(
+    arena: &'a Bump,
+    type_mgr: &'a TypeManager<'a>,
+    mut builder: B,
+) -> B
+where
+    B: Binder<'a, 'a>,
+{
+    // Parse: (Str) -> Option<Decimal>
+    let parse = NativeFunction::new(
+        type_mgr.function(&[type_mgr.str()], type_mgr.option(type_mgr.decimal())),
+        decimal_parse,
+    );
+    builder = builder.bind(
+        "Parse",
+        Value::function(arena, parse).expect("Parse type should be a function"),
+    );
+
+    // ToString: (Decimal) -> Str
+    let to_string = NativeFunction::new(
+        type_mgr.function(&[type_mgr.decimal()], type_mgr.str()),
+        decimal_to_string,
+    );
+    builder.bind(
+        "ToString",
+        Value::function(arena, to_string).expect("ToString type should be a function"),
+    )
+}
+
+/// Creates a Record containing all Decimal functions, then binds it to the Binder.
+///
+/// The record is bound with the package name "Decimal".
+pub fn register_decimal_package<'a, B>(
+    arena: &'a Bump,
+    type_mgr: &'a TypeManager<'a>,
+    builder: B,
+) -> B
+where
+    B: Binder<'a, 'a>,
+{
+    let record_builder = Value::record_builder(arena, type_mgr);
+    let record = register_decimal_functions(arena, type_mgr, record_builder)
+        .build()
+        .expect("duplicate binding in package - check function names");
+    builder.bind("Decimal", record)
+}
+
+#[cfg(test)]
+#[path = "decimal_test.rs"]
+mod decimal_test;
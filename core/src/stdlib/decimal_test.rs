@@ -0,0 +1,115 @@
+//! Tests for the Decimal package
+
+use super::register_decimal_functions;
+use crate::{
+    api::{CompileOptionsOverride, Engine, EngineOptions, Error},
+    stdlib::register_decimal_package,
+    types::manager::TypeManager,
+    values::{
+        binder::Binder,
+        dynamic::{RecordBuilder, Value},
+    },
+};
+use bumpalo::Bump;
+
+#[test]
+fn test_decimal_package_builds() {
+    let arena = Bump::new();
+    let type_mgr = TypeManager::new(&arena);
+
+    let decimal =
+        register_decimal_functions(&arena, type_mgr, RecordBuilder::new(&arena, type_mgr))
+            .build()
+            .unwrap();
+    let record = decimal.as_record().unwrap();
+
+    assert!(record.get("Parse").is_some());
+    assert!(record.get("ToString").is_some());
+}
+
+fn eval<'a>(arena: &'a Bump, source: &'a str) -> Result<Value<'a, 'a>, Error> {
+    let options = EngineOptions::default();
+
+    let engine = Engine::new(options, arena, register_decimal_package);
+
+    let compile_opts = CompileOptionsOverride::default();
+    let expr = engine.compile(compile_opts, source, &[])?;
+    expr.run(Default::default(), arena, &[])
+}
+
+fn eval_bool(source: &str) -> bool {
+    let arena = Bump::new();
+    let source = arena.alloc_str(source);
+    eval(&arena, source).unwrap().as_bool().unwrap()
+}
+
+fn eval_string(source: &str) -> String {
+    let arena = Bump::new();
+    let source = arena.alloc_str(source);
+    eval(&arena, source).unwrap().as_str().unwrap().into()
+}
+
+#[test]
+fn test_decimal_addition_is_exact() {
+    assert!(eval_bool("0.1d + 0.2d == 0.3d"));
+    assert!(eval_bool("0.30d == 0.3d"));
+    assert_eq!(eval_string("Decimal.ToString(0.1d + 0.2d)"), "0.3");
+    assert_eq!(eval_string("Decimal.ToString(19.99d * 3d - 0.97d)"), "59");
+}
+
+#[test]
+fn test_decimal_division() {
+    assert_eq!(eval_string("Decimal.ToString(10d / 4d)"), "2.5");
+    // Non-terminating quotients round half-to-even at 18 fractional digits
+    assert_eq!(
+        eval_string("Decimal.ToString(2d / 3d)"),
+        "0.666666666666666667"
+    );
+    assert!(eval_bool("-7d / 2d == -3.5d"));
+}
+
+#[test]
+fn test_decimal_division_by_zero() {
+    let arena = Bump::new();
+    let error = eval(&arena, "1.5d / 0d").unwrap_err();
+    assert!(format!("{:?}", error).contains("Division by zero"));
+
+    // Runtime errors can be recovered from
+    assert!(eval_bool("(1.5d / 0d otherwise -1d) == -1d"));
+}
+
+#[test]
+fn test_decimal_comparison() {
+    assert!(eval_bool("0.1d < 0.11d"));
+    assert!(eval_bool("-0.5d < -0.25d"));
+    assert!(eval_bool("1.000d >= 1d"));
+    assert!(eval_bool("2.5d != 2.05d"));
+}
+
+#[test]
+fn test_decimal_power() {
+    assert!(eval_bool("1.1d ^ 2d == 1.21d"));
+    assert!(eval_bool("2d ^ -2d == 0.25d"));
+
+    let arena = Bump::new();
+    let error = eval(&arena, "2d ^ 0.5d").unwrap_err();
+    assert!(format!("{:?}", error).contains("whole number"));
+}
+
+#[test]
+fn test_decimal_parse() {
+    assert!(eval_bool("Decimal.Parse(\"19.99\") == some 19.99d"));
+    assert!(eval_bool("Decimal.Parse(\" -.5 \") == some -0.5d"));
+    assert!(eval_bool("Decimal.Parse(\"1e3\") == none"));
+    assert!(eval_bool("Decimal.Parse(\"\") == none"));
+    assert!(eval_bool(
+        "Decimal.Parse(\"0.0000000000000000001\") == none"
+    ));
+}
+
+#[test]
+fn test_decimal_int_casts() {
+    assert!(eval_bool("(7 as Decimal) / 2d == 3.5d"));
+    assert!(eval_bool("(-3.99d as Int) == -3"));
+    assert!(eval_bool("((10d ^ 20d) as Int otherwise 0) == 0"));
+}
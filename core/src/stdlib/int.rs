@@ -7,10 +7,62 @@
 //! - `Rem(a, b)`: Remainder of truncated division (sign matches dividend)
 //! - `Div(a, b)`: Euclidean division (remainder always non-negative)
 //! - `Mod(a, b)`: Euclidean modulus (always non-negative)
+//! - `DivMod(a, b)`: Euclidean quotient and modulus in one call
 
-use crate::evaluator::RuntimeError;
+use crate::{
+    evaluator::RuntimeError,
+    types::{Type, manager::TypeManager},
+    values::{
+        raw::{RawValue, RecordData},
+        typed::{Bridge, RawConvertible},
+    },
+    vec,
+};
+use bumpalo::Bump;
 use melbi_macros::{melbi_fn, melbi_package};
 
+// ============================================================================
+// Results
+// ============================================================================
+
+/// Result of `Int.DivMod`: the record `{quotient: Int, remainder: Int}`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DivModResult {
+    pub quotient: i64,
+    pub remainder: i64,
+}
+
+// Record fields are stored sorted by name: `quotient`, then `remainder`.
+impl RawConvertible for DivModResult {
+    fn to_raw_value(arena: &Bump, value: Self) -> RawValue {
+        let fields = [
+            RawValue::make_int(value.quotient),
+            RawValue::make_int(value.remainder),
+        ];
+        RecordData::new_with(arena, &fields).as_raw_value()
+    }
+
+    unsafe fn from_raw_value(raw: RawValue) -> Self {
+        let record = RecordData::from_raw_value(raw);
+        unsafe {
+            DivModResult {
+                quotient: record.get(0).as_int_unchecked(),
+                remainder: record.get(1).as_int_unchecked(),
+            }
+        }
+    }
+}
+
+impl Bridge for DivModResult {
+    type Raw = RawValue;
+    fn type_from<'b>(type_mgr: &'b TypeManager<'b>) -> &'b Type<'b> {
+        type_mgr.record(vec![
+            ("quotient", type_mgr.int()),
+            ("remainder", type_mgr.int()),
+        ])
+    }
+}
+
 // ============================================================================
 // Error Handling
 // ============================================================================
@@ -135,6 +187,32 @@ mod int {
         check_overflow(a, b)?;
         Ok(a.rem_euclid(b))
     }
+
+    /// Performs Euclidean division, returning both the quotient and the modulus.
+    ///
+    /// Equivalent to `{quotient = Int.Div(a, b), remainder = Int.Mod(a, b)}`,
+    /// so the remainder is always non-negative. For truncated semantics, use
+    /// `Int.Quot` and `Int.Rem`.
+    ///
+    /// Invariant: `a == (result.quotient * b) + result.remainder`
+    ///
+    /// Errors:
+    /// - DivisionByZero if `b == 0`
+    /// - IntegerOverflow if `a == i64::MIN && b == -1`
+    ///
+    /// Examples:
+    /// - `Int.DivMod(-7, 3)  -> {quotient = -3, remainder = 2}`
+    /// - `Int.DivMod(7, -3)  -> {quotient = -2, remainder = 1}`
+    /// - `Int.DivMod(-7, -3) -> {quotient = 3, remainder = 2}`
+    #[melbi_fn]
+    fn div_mod(a: i64, b: i64) -> Result<DivModResult, RuntimeError> {
+        check_division_by_zero(b)?;
+        check_overflow(a, b)?;
+        Ok(DivModResult {
+            quotient: a.div_euclid(b),
+            remainder: a.rem_euclid(b),
+        })
+    }
 }
 
 // Re-export everything for cleaner access
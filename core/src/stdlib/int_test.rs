@@ -22,6 +22,7 @@ fn test_int_package_builds() {
     assert!(record.get("Rem").is_some());
     assert!(record.get("Div").is_some());
     assert!(record.get("Mod").is_some());
+    assert!(record.get("DivMod").is_some());
 }
 
 // Helper function for integration tests using the Engine to evaluate Melbi code
@@ -229,6 +230,53 @@ fn test_int_mod_zero_dividend() {
     });
 }
 
+// ============================================================================
+// Euclidean Division with Modulus (Int.DivMod)
+// ============================================================================
+
+/// Check that `Int.DivMod(a, b)` returns the given quotient and remainder.
+fn assert_div_mod(a: i64, b: i64, quotient: i64, remainder: i64) {
+    test_int_expr(&format!("Int.DivMod({a}, {b})"), |r: Value| {
+        let record = r.as_record().unwrap();
+        let field = |name| record.get(name).unwrap().as_int().unwrap();
+        assert_eq!(
+            (field("quotient"), field("remainder")),
+            (quotient, remainder),
+            "Int.DivMod({a}, {b})"
+        );
+    });
+}
+
+#[test]
+fn test_int_div_mod_sign_combinations() {
+    assert_div_mod(7, 3, 2, 1);
+    assert_div_mod(-7, 3, -3, 2);
+    assert_div_mod(7, -3, -2, 1);
+    assert_div_mod(-7, -3, 3, 2);
+    assert_div_mod(6, -3, -2, 0);
+    assert_div_mod(0, 5, 0, 0);
+}
+
+#[test]
+fn test_int_div_mod_matches_div_and_mod() {
+    // Euclidean, like Int.Div and Int.Mod, not truncated like Int.Quot and Int.Rem
+    for (a, b) in [(7, 3), (-7, 3), (7, -3), (-7, -3), (-100, 7)] {
+        let source = format!(
+            "r.quotient == Int.Div({a}, {b}) and r.remainder == Int.Mod({a}, {b}) \
+             where {{ r = Int.DivMod({a}, {b}) }}"
+        );
+        test_int_expr(&source, |r: Value| {
+            assert!(r.as_bool().unwrap(), "Int.DivMod({a}, {b})");
+        });
+    }
+    test_int_expr(
+        "Int.DivMod(-7, 3).quotient == Int.Quot(-7, 3)",
+        |r: Value| {
+            assert!(!r.as_bool().unwrap());
+        },
+    );
+}
+
 // ============================================================================
 // Invariant Tests
 // ============================================================================
@@ -481,6 +529,13 @@ fn test_division_by_zero_with_zero_dividend() {
     test_int_expr_expects_error("Int.Mod(0, 0)", "Division by zero");
 }
 
+#[test]
+fn test_div_mod_division_by_zero_returns_error() {
+    // Int.DivMod(a, 0) returns a DivisionByZero error
+    test_int_expr_expects_error("Int.DivMod(7, 0)", "Division by zero");
+    test_int_expr_expects_error("Int.DivMod(0, 0)", "Division by zero");
+}
+
 // ============================================================================
 // Integer Overflow Tests (i64::MIN / -1)
 // ============================================================================
@@ -511,6 +566,12 @@ fn test_div_euclid_i64_min_divided_by_negative_one_returns_overflow_error() {
     test_int_expr_expects_error("Int.Div(-9223372036854775808, -1)", "Integer overflow");
 }
 
+#[test]
+fn test_div_mod_i64_min_divided_by_negative_one_returns_overflow_error() {
+    // Same overflow as Int.Div and Int.Mod
+    test_int_expr_expects_error("Int.DivMod(-9223372036854775808, -1)", "Integer overflow");
+}
+
 #[test]
 fn test_mod_euclid_i64_min_divided_by_negative_one_returns_overflow_error() {
     // i64::MIN.rem_euclid(-1) causes overflow - returns IntegerOverflow error
@@ -1,12 +1,18 @@
 //! Melbi Standard Library
 //!
 //! This module provides the standard library packages for Melbi, including:
-//! - Int: Integer arithmetic operations (Quot, Rem, Div, Mod)
+//! - Int: Integer arithmetic operations (Quot, Rem, Div, Mod, DivMod)
+//! - Bool: Boolean connectives without an operator (Xor, Implies)
+//! - Bytes: Conversions between bytes and integers (FromInts, ToInts)
 //! - Math: Mathematical functions and constants
 //! - String: String manipulation functions
 //! - Array: Array operations
+//! - Decimal: Fixed-point decimal parsing and formatting (`decimal` feature)
 //! - Option: Option utilities (future)
 //!
+//! It also provides builtins ([`builtins`]): polymorphic global functions such
+//! as `min` and `max` that are always available, without registration.
+//!
 //! Each package is implemented as a record containing functions and constants.
 //! Packages are built using native Rust functions (FFI) and registered in the
 //! global environment before user code executes.
@@ -16,12 +22,21 @@ use crate::values::binder::Binder;
 use bumpalo::Bump;
 
 pub mod array;
+pub mod bool;
+pub mod builtins;
+pub mod bytes;
+#[cfg(feature = "decimal")]
+pub mod decimal;
 pub mod int;
 pub mod math;
 pub mod string;
 
 // Re-export for convenience
 pub use array::{register_array_functions, register_array_package};
+pub use bool::{register_bool_functions, register_bool_package};
+pub use bytes::{register_bytes_functions, register_bytes_package};
+#[cfg(feature = "decimal")]
+pub use decimal::{register_decimal_functions, register_decimal_package};
 pub use int::{register_int_functions, register_int_package};
 pub use math::{register_math_functions, register_math_package};
 pub use string::{register_string_functions, register_string_package};
@@ -29,7 +44,7 @@ pub use string::{register_string_functions, register_string_package};
 /// Register all standard library packages in the environment.
 ///
 /// This is a convenience function that registers all "default" standard library
-/// packages (Math, String, Array, Int) in the global environment. Use this in your
+/// packages (Math, String, Array, Int, Bool, Bytes) in the global environment. Use this in your
 /// Engine initialization to get the full standard library.
 ///
 /// # Example
@@ -59,6 +74,10 @@ where
     let env = register_string_package(arena, type_mgr, env);
     let env = register_array_package(arena, type_mgr, env);
     let env = register_int_package(arena, type_mgr, env);
+    let env = register_bool_package(arena, type_mgr, env);
+    let env = register_bytes_package(arena, type_mgr, env);
+    #[cfg(feature = "decimal")]
+    let env = register_decimal_package(arena, type_mgr, env);
 
     // Future packages will be added here:
     // - Option package
@@ -6,10 +6,15 @@
 //! - String.Len returns UTF-8 codepoint count (not byte count)
 //! - Upper/Lower are ASCII-only to keep binary size minimal
 //! - For full Unicode support, use the Unicode package
-//! - Format strings (f"...") are built into the language, not library functions
+//! - Format strings (f"...") are built into the language, not library functions;
+//!   String.Format only covers templates chosen at runtime
+//! - Functions that build or scan a whole string charge one unit of fuel per
+//!   byte (or codepoint) they process
 
 use crate::{
     Vec,
+    evaluator::{ExecutionErrorKind, ResourceExceededError, RuntimeError},
+    format,
     values::{
         FfiContext,
         typed::{Array, Optional, Str},
@@ -61,16 +66,18 @@ mod string {
 
     /// Convert string to uppercase (ASCII-only)
     #[melbi_fn]
-    fn upper<'a>(ctx: &FfiContext<'_, 'a>, s: Str<'a>) -> Str<'a> {
+    fn upper<'a>(ctx: &FfiContext<'_, 'a>, s: Str<'a>) -> Result<Str<'a>, ResourceExceededError> {
+        ctx.charge(s.len() as u64)?;
         let upper = s.to_ascii_uppercase();
-        Str::from_str(ctx.arena(), &upper)
+        Ok(Str::from_str(ctx.arena(), &upper))
     }
 
     /// Convert string to lowercase (ASCII-only)
     #[melbi_fn]
-    fn lower<'a>(ctx: &FfiContext<'_, 'a>, s: Str<'a>) -> Str<'a> {
+    fn lower<'a>(ctx: &FfiContext<'_, 'a>, s: Str<'a>) -> Result<Str<'a>, ResourceExceededError> {
+        ctx.charge(s.len() as u64)?;
         let lower = s.to_ascii_lowercase();
-        Str::from_str(ctx.arena(), &lower)
+        Ok(Str::from_str(ctx.arena(), &lower))
     }
 
     /// Trim whitespace from both ends
@@ -96,9 +103,15 @@ mod string {
 
     /// Replace all occurrences of pattern with replacement
     #[melbi_fn]
-    fn replace<'a>(ctx: &FfiContext<'_, 'a>, s: Str<'a>, from: Str<'a>, to: Str<'a>) -> Str<'a> {
+    fn replace<'a>(
+        ctx: &FfiContext<'_, 'a>,
+        s: Str<'a>,
+        from: Str<'a>,
+        to: Str<'a>,
+    ) -> Result<Str<'a>, ResourceExceededError> {
+        ctx.charge(s.len() as u64)?;
         let replaced = s.replace(from.as_ref(), to.as_ref());
-        Str::from_str(ctx.arena(), &replaced)
+        Ok(Str::from_str(ctx.arena(), &replaced))
     }
 
     /// Replace first N occurrences of pattern with replacement
@@ -109,9 +122,10 @@ mod string {
         from: Str<'a>,
         to: Str<'a>,
         count: i64,
-    ) -> Str<'a> {
+    ) -> Result<Str<'a>, ResourceExceededError> {
+        ctx.charge(s.len() as u64)?;
         let replaced = s.replacen(from.as_ref(), to.as_ref(), count as usize);
-        Str::from_str(ctx.arena(), &replaced)
+        Ok(Str::from_str(ctx.arena(), &replaced))
     }
 
     // ========================================================================
@@ -122,7 +136,12 @@ mod string {
     ///
     /// Special case: empty delimiter splits into individual characters (codepoints)
     #[melbi_fn]
-    fn split<'a>(ctx: &FfiContext<'_, 'a>, s: Str<'a>, delimiter: Str<'a>) -> Array<'a, Str<'a>> {
+    fn split<'a>(
+        ctx: &FfiContext<'_, 'a>,
+        s: Str<'a>,
+        delimiter: Str<'a>,
+    ) -> Result<Array<'a, Str<'a>>, ResourceExceededError> {
+        ctx.charge(s.len() as u64)?;
         let parts: Vec<Str<'a>> = if delimiter.is_empty() {
             // Empty delimiter: split into individual characters (codepoints)
             // Note: This case still requires allocation since we need to create individual char strings
@@ -141,7 +160,7 @@ mod string {
                 .collect()
         };
 
-        Array::new(ctx.arena(), &parts)
+        Ok(Array::new(ctx.arena(), &parts))
     }
 
     /// Join array of strings with separator
@@ -150,10 +169,143 @@ mod string {
         ctx: &FfiContext<'_, 'a>,
         parts: Array<'a, Str<'a>>,
         separator: Str<'a>,
-    ) -> Str<'a> {
+    ) -> Result<Str<'a>, ResourceExceededError> {
         let strings: Vec<&'a str> = parts.iter().map(|s: Str<'a>| s.as_str()).collect();
+        let length: usize = strings.iter().map(|s| s.len()).sum();
+        ctx.charge((length + separator.len() * strings.len()) as u64)?;
         let joined = strings.join(separator.as_ref());
-        Str::from_str(ctx.arena(), &joined)
+        Ok(Str::from_str(ctx.arena(), &joined))
+    }
+
+    // ========================================================================
+    // Formatting
+    // ========================================================================
+
+    /// Fill a runtime template, replacing `{0}`, `{1}`, ... with `args` elements
+    ///
+    /// `{{` and `}}` produce literal braces. Fails (catchable with `otherwise`)
+    /// if a placeholder index is out of range or the template is malformed.
+    #[melbi_fn]
+    fn format<'a>(
+        ctx: &FfiContext<'_, 'a>,
+        template: Str<'a>,
+        args: Array<'a, Str<'a>>,
+    ) -> Result<Str<'a>, ExecutionErrorKind> {
+        let invalid_template = |reason: &str| RuntimeError::InvalidArgument {
+            message: format!("Invalid format template: {}", reason),
+        };
+
+        // Charge for each piece before copying it, so a huge result fails early
+        ctx.charge(template.len() as u64)?;
+        let mut result = alloc::string::String::with_capacity(template.len());
+        let mut rest = template.as_str();
+        while let Some(brace_pos) = rest.find(['{', '}']) {
+            result.push_str(&rest[..brace_pos]);
+            let brace = &rest[brace_pos..brace_pos + 1];
+            rest = &rest[brace_pos + 1..];
+
+            // Doubled brace is an escaped literal brace
+            if let Some(after_escape) = rest.strip_prefix(brace) {
+                result.push_str(brace);
+                rest = after_escape;
+                continue;
+            }
+            if brace == "}" {
+                return Err(invalid_template("unmatched '}'").into());
+            }
+
+            let placeholder_end = rest
+                .find('}')
+                .ok_or_else(|| invalid_template("unclosed '{'"))?;
+            let placeholder = &rest[..placeholder_end];
+            rest = &rest[placeholder_end + 1..];
+
+            // Only plain decimal indices (no sign, no names)
+            let index = placeholder
+                .bytes()
+                .all(|byte| byte.is_ascii_digit())
+                .then(|| placeholder.parse::<usize>().ok())
+                .flatten()
+                .ok_or_else(|| {
+                    invalid_template(&format!("'{{{}}}' is not a placeholder", placeholder))
+                })?;
+            let arg = args.get(index).ok_or(RuntimeError::IndexOutOfBounds {
+                index: i64::try_from(index).unwrap_or(i64::MAX),
+                len: args.len(),
+            })?;
+            ctx.charge(arg.len() as u64)?;
+            result.push_str(arg.as_str());
+        }
+        result.push_str(rest);
+
+        Ok(Str::from_str(ctx.arena(), &result))
+    }
+
+    // ========================================================================
+    // Iteration
+    // ========================================================================
+
+    /// Split string into its characters (codepoints), each as a single-character string
+    ///
+    /// Elements are zero-copy substrings of the input.
+    #[melbi_fn]
+    fn chars<'a>(
+        ctx: &FfiContext<'_, 'a>,
+        s: Str<'a>,
+    ) -> Result<Array<'a, Str<'a>>, ResourceExceededError> {
+        ctx.charge(s.len() as u64)?;
+        let s_str = s.as_str();
+        let chars: Vec<Str<'a>> = s_str
+            .char_indices()
+            .map(|(byte_pos, c)| {
+                Str::from_borrowed_str(ctx.arena(), &s_str[byte_pos..byte_pos + c.len_utf8()])
+            })
+            .collect();
+        Ok(Array::new(ctx.arena(), &chars))
+    }
+
+    /// Get the UTF-8 encoding of a string as bytes (zero-copy)
+    #[melbi_fn]
+    fn bytes<'a>(s: Str<'a>) -> &'a [u8] {
+        s.as_str().as_bytes()
+    }
+
+    // ========================================================================
+    // Codepoint Conversion
+    // ========================================================================
+
+    /// Convert string to its Unicode codepoints (scalar values)
+    #[melbi_fn]
+    fn to_codepoints<'a>(
+        ctx: &FfiContext<'_, 'a>,
+        s: Str<'a>,
+    ) -> Result<Array<'a, i64>, ResourceExceededError> {
+        ctx.charge(s.len() as u64)?;
+        let codepoints: Vec<i64> = s.as_str().chars().map(|c| c as i64).collect();
+        Ok(Array::new(ctx.arena(), &codepoints))
+    }
+
+    /// Build a string from Unicode codepoints
+    ///
+    /// Fails (catchable with `otherwise`) if any value is not a Unicode scalar
+    /// value, i.e. a surrogate (0xD800-0xDFFF) or outside 0..=0x10FFFF.
+    #[melbi_fn]
+    fn from_codepoints<'a>(
+        ctx: &FfiContext<'_, 'a>,
+        codepoints: Array<'a, i64>,
+    ) -> Result<Str<'a>, ExecutionErrorKind> {
+        ctx.charge(codepoints.len() as u64)?;
+        let mut result = alloc::string::String::with_capacity(codepoints.len());
+        for codepoint in codepoints.iter() {
+            let c = u32::try_from(codepoint)
+                .ok()
+                .and_then(char::from_u32)
+                .ok_or_else(|| RuntimeError::InvalidArgument {
+                    message: format!("{} is not a valid Unicode codepoint", codepoint),
+                })?;
+            result.push(c);
+        }
+        Ok(Str::from_str(ctx.arena(), &result))
     }
 
     // ========================================================================
@@ -2,7 +2,7 @@
 
 use super::{register_string_functions, register_string_package};
 use crate::{
-    api::{CompileOptionsOverride, Engine, EngineOptions},
+    api::{CompileOptionsOverride, Engine, EngineOptions, Error},
     types::manager::TypeManager,
     values::{
         binder::Binder,
@@ -48,6 +48,25 @@ where
     check(result);
 }
 
+// Helper function returning the diagnostic code of a failing Melbi expression
+fn string_expr_error_code(source: &str) -> Option<String> {
+    let options = EngineOptions::default();
+    let arena = Bump::new();
+
+    let engine = Engine::new(options, &arena, register_string_package);
+
+    let compile_opts = CompileOptionsOverride::default();
+    let expr = engine
+        .compile(compile_opts, source, &[])
+        .expect("compilation should succeed");
+
+    let val_arena = Bump::new();
+    match expr.run(Default::default(), &val_arena, &[]) {
+        Err(Error::Runtime { diagnostic, .. }) => diagnostic.code,
+        other => panic!("expected a runtime error, got {:?}", other),
+    }
+}
+
 #[test]
 fn test_string_len() {
     // ASCII string
@@ -219,6 +238,103 @@ fn test_string_split() {
     });
 }
 
+#[test]
+fn test_string_chars() {
+    test_string_expr("String.Chars(\"abc\")", |r: Value| {
+        let arr = r.as_array().unwrap();
+        assert_eq!(arr.len(), 3);
+        assert_eq!(arr.get(0).unwrap().as_str().unwrap(), "a");
+        assert_eq!(arr.get(2).unwrap().as_str().unwrap(), "c");
+    });
+
+    // Multibyte input: one element per codepoint, not per byte
+    test_string_expr("String.Chars(\"café 😀\")", |r: Value| {
+        let arr = r.as_array().unwrap();
+        assert_eq!(arr.len(), "café 😀".chars().count());
+        assert_eq!(arr.get(3).unwrap().as_str().unwrap(), "é");
+        assert_eq!(arr.get(5).unwrap().as_str().unwrap(), "😀");
+    });
+
+    test_string_expr("String.Chars(\"\")", |r: Value| {
+        assert_eq!(r.as_array().unwrap().len(), 0);
+    });
+}
+
+#[test]
+fn test_string_bytes() {
+    test_string_expr("String.Bytes(\"abc\")", |r: Value| {
+        assert_eq!(r.as_bytes().unwrap(), b"abc");
+    });
+
+    // Multibyte input: one element per byte of the UTF-8 encoding
+    test_string_expr("String.Bytes(\"café 😀\")", |r: Value| {
+        assert_eq!(r.as_bytes().unwrap().len(), "café 😀".len());
+        assert_eq!(r.as_bytes().unwrap(), "café 😀".as_bytes());
+    });
+}
+
+#[test]
+fn test_string_to_codepoints() {
+    test_string_expr("String.ToCodepoints(\"a😀\")", |r: Value| {
+        let arr = r.as_array().unwrap();
+        assert_eq!(arr.len(), 2);
+        assert_eq!(arr.get(0).unwrap().as_int().unwrap(), 0x61);
+        assert_eq!(arr.get(1).unwrap().as_int().unwrap(), 0x1F600);
+    });
+}
+
+#[test]
+fn test_string_from_codepoints() {
+    test_string_expr(
+        "String.FromCodepoints([0x63, 0x61, 0x66, 0xE9])",
+        |r: Value| {
+            assert_eq!(r.as_str().unwrap(), "café");
+        },
+    );
+
+    test_string_expr("String.FromCodepoints([])", |r: Value| {
+        assert_eq!(r.as_str().unwrap(), "");
+    });
+}
+
+#[test]
+fn test_string_codepoints_round_trip() {
+    test_string_expr(
+        "String.FromCodepoints(String.ToCodepoints(\"héllo 😀!\"))",
+        |r: Value| {
+            assert_eq!(r.as_str().unwrap(), "héllo 😀!");
+        },
+    );
+}
+
+#[test]
+fn test_string_from_codepoints_invalid() {
+    // Surrogates, out-of-range and negative values are not scalar values
+    for codepoint in ["0xD800", "0xDFFF", "0x110000", "-1"] {
+        let source = format!(
+            "String.FromCodepoints([0x61, {}]) otherwise \"invalid\"",
+            codepoint
+        );
+        test_string_expr(&source, |r: Value| {
+            assert_eq!(r.as_str().unwrap(), "invalid");
+        });
+    }
+
+    // The largest valid codepoint is accepted
+    test_string_expr(
+        "String.FromCodepoints([0x10FFFF]) otherwise \"invalid\"",
+        |r: Value| {
+            assert_eq!(r.as_str().unwrap(), "\u{10FFFF}");
+        },
+    );
+
+    // Reported as an invalid argument
+    assert_eq!(
+        string_expr_error_code("String.FromCodepoints([0xD800])").as_deref(),
+        Some("R008")
+    );
+}
+
 #[test]
 fn test_string_join() {
     test_string_expr("String.Join([\"a\", \"b\", \"c\"], \",\")", |r: Value| {
@@ -230,6 +346,59 @@ fn test_string_join() {
     });
 }
 
+#[test]
+fn test_string_format() {
+    test_string_expr(
+        "String.Format(\"{0} is {1} years old\", [\"Ada\", \"36\"])",
+        |r: Value| {
+            assert_eq!(r.as_str().unwrap(), "Ada is 36 years old");
+        },
+    );
+
+    // Placeholders can repeat and appear in any order
+    test_string_expr(
+        "String.Format(\"{1}{0}{1}\", [\"a\", \"b\"])",
+        |r: Value| {
+            assert_eq!(r.as_str().unwrap(), "bab");
+        },
+    );
+
+    // No placeholders; unused args are fine
+    test_string_expr("String.Format(\"plain\", [\"unused\"])", |r: Value| {
+        assert_eq!(r.as_str().unwrap(), "plain");
+    });
+}
+
+#[test]
+fn test_string_format_escaped_braces() {
+    test_string_expr(
+        "String.Format(\"{{{0}}} and {{}}\", [\"x\"])",
+        |r: Value| {
+            assert_eq!(r.as_str().unwrap(), "{x} and {}");
+        },
+    );
+}
+
+#[test]
+fn test_string_format_invalid() {
+    // Out-of-range index, unclosed/unmatched braces, non-numeric placeholders
+    for template in ["{2}", "{0", "0}", "{}", "{name}", "{-1}"] {
+        let source = format!(
+            "String.Format(\"{}\", [\"a\", \"b\"]) otherwise \"invalid\"",
+            template
+        );
+        test_string_expr(&source, |r: Value| {
+            assert_eq!(r.as_str().unwrap(), "invalid");
+        });
+    }
+
+    // A malformed template is reported as an invalid argument
+    assert_eq!(
+        string_expr_error_code("String.Format(\"{0\", [\"a\"])").as_deref(),
+        Some("R008")
+    );
+}
+
 #[test]
 fn test_string_substring() {
     // Normal substring
@@ -141,7 +141,13 @@ mod wire {
         fn is_unitary_type(type_tag: TypeTag) -> bool {
             matches!(
                 type_tag,
-                TypeTag::Int | TypeTag::Float | TypeTag::Bool | TypeTag::Str | TypeTag::Bytes
+                TypeTag::Int
+                    | TypeTag::Float
+                    | TypeTag::Bool
+                    | TypeTag::Str
+                    | TypeTag::Bytes
+                    | TypeTag::BigInt
+                    | TypeTag::Decimal
             )
         }
 
@@ -439,6 +445,8 @@ fn type_to_tag(ty: &Type) -> TypeTag {
             Type::Function { .. } => TypeTag::Function,
             Type::Symbol(_) => TypeTag::Symbol,
             Type::Option(_) => TypeTag::Option,
+            Type::BigInt => TypeTag::BigInt,
+            Type::Decimal => TypeTag::Decimal,
         }
     );
     tag
@@ -467,6 +475,12 @@ fn encode_inner(ty: &Type, buf: &mut BufferType) {
         Type::Int | Type::Float | Type::Bool | Type::Str | Type::Bytes => {
             unreachable!("types are always packed");
         }
+        Type::BigInt => {
+            unreachable!("types are always packed");
+        }
+        Type::Decimal => {
+            unreachable!("types are always packed");
+        }
         Type::TypeVar(id) => {
             encode_composite(buf, tag.to_byte(), |buf| {
                 write_u16_le(buf, *id);
@@ -583,6 +597,8 @@ impl<'a> TypeView<'a> for EncodedType<'a> {
             TypeTag::Bool => TypeKind::Bool,
             TypeTag::Str => TypeKind::Str,
             TypeTag::Bytes => TypeKind::Bytes,
+            TypeTag::BigInt => TypeKind::BigInt,
+            TypeTag::Decimal => TypeKind::Decimal,
             TypeTag::Array => match self.payload {
                 Payload::PackedArray(type_tag) => {
                     TypeKind::Array(EncodedType::new(type_tag, Payload::None))
@@ -61,6 +61,10 @@ pub fn type_expr_to_type<'types>(
                 "Bool" => Ok(type_manager.bool()),
                 "String" => Ok(type_manager.str()),
                 "Bytes" => Ok(type_manager.bytes()),
+                #[cfg(feature = "bigint")]
+                "BigInt" => Ok(type_manager.bigint()),
+                #[cfg(feature = "decimal")]
+                "Decimal" => Ok(type_manager.decimal()),
                 _ => Err(TypeConversionError::UnknownType {
                     name: path.to_string(),
                 }),
@@ -9,12 +9,31 @@ use bumpalo::Bump;
 use core::cell::{Cell, Ref, RefCell};
 use hashbrown::{DefaultHashBuilder, HashMap};
 
+/// Interning statistics reported by [`TypeManager::stats`].
+///
+/// A high miss count relative to hits, or a fast-growing `types` count, points
+/// at type explosion (e.g., many distinct instantiations of polymorphic code).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct InternStats {
+    /// Number of unique types interned.
+    pub types: usize,
+    /// Number of unique strings (field names, symbol parts) interned.
+    pub strings: usize,
+    /// Type lookups answered by an already-interned type.
+    pub hits: usize,
+    /// Type lookups that allocated a new type.
+    pub misses: usize,
+}
+
 pub struct TypeManager<'a> {
     // Arena holding all types from this TypeManager.
     arena: &'a Bump,
     interned_strs: RefCell<HashMap<&'a str, &'a str, DefaultHashBuilder, &'a Bump>>,
     interned: RefCell<HashMap<CompareTypeArgs<'a>, &'a Type<'a>, DefaultHashBuilder, &'a Bump>>,
     next_type_var: Cell<u16>,
+    // Interning counters for `stats()`; every lookup is either a hit or a miss.
+    type_lookups: Cell<usize>,
+    type_misses: Cell<usize>,
 }
 
 impl<'a> TypeManager<'a> {
@@ -24,6 +43,8 @@ impl<'a> TypeManager<'a> {
             interned_strs: RefCell::new(HashMap::new_in(arena)),
             interned: RefCell::new(HashMap::new_in(arena)),
             next_type_var: Cell::new(0),
+            type_lookups: Cell::new(0),
+            type_misses: Cell::new(0),
         })
     }
 
@@ -39,10 +60,12 @@ impl<'a> TypeManager<'a> {
     fn intern_map(
         &self,
     ) -> Ref<'_, HashMap<CompareTypeArgs<'a>, &'a Type<'a>, DefaultHashBuilder, &'a Bump>> {
+        self.type_lookups.set(self.type_lookups.get() + 1);
         self.interned.borrow()
     }
 
     fn alloc_and_intern(&self, ty: Type<'a>) -> &'a Type<'a> {
+        self.type_misses.set(self.type_misses.get() + 1);
         let arena_ty = self.arena.alloc(ty.clone());
         self.interned
             .borrow_mut()
@@ -50,6 +73,17 @@ impl<'a> TypeManager<'a> {
         arena_ty
     }
 
+    /// Interning statistics since this manager was created.
+    pub fn stats(&self) -> InternStats {
+        let misses = self.type_misses.get();
+        InternStats {
+            types: self.interned.borrow().len(),
+            strings: self.interned_strs.borrow().len(),
+            hits: self.type_lookups.get() - misses,
+            misses,
+        }
+    }
+
     // Generate fresh type variable
     pub fn fresh_type_var(&self) -> &'a Type<'a> {
         let var_id = self.next_type_var.get();
@@ -110,6 +144,18 @@ impl<'a> TypeManager<'a> {
         }
         self.alloc_and_intern(Type::Bytes)
     }
+    pub fn bigint(&self) -> &'a Type<'a> {
+        if let Some(&interned_ty) = self.intern_map().get(&CompareTypeArgs(Type::BigInt)) {
+            return interned_ty;
+        }
+        self.alloc_and_intern(Type::BigInt)
+    }
+    pub fn decimal(&self) -> &'a Type<'a> {
+        if let Some(&interned_ty) = self.intern_map().get(&CompareTypeArgs(Type::Decimal)) {
+            return interned_ty;
+        }
+        self.alloc_and_intern(Type::Decimal)
+    }
     pub fn array(&self, elem_ty: &'a Type<'a>) -> &'a Type<'a> {
         if let Some(&interned_ty) = self
             .intern_map()
@@ -239,6 +285,8 @@ impl<'a> TypeManager<'a> {
                 Type::Bool => this.bool(),
                 Type::Str => this.str(),
                 Type::Bytes => this.bytes(),
+                Type::BigInt => this.bigint(),
+                Type::Decimal => this.decimal(),
                 Type::TypeVar(_id) => {
                     let ptr = ty as *const Type<'b>;
                     if let Some(&mapped) = var_map.get(&ptr) {
@@ -301,6 +349,8 @@ impl<'a> TypeManager<'a> {
         ) -> &'a Type<'a> {
             match ty {
                 Type::Int | Type::Float | Type::Bool | Type::Str | Type::Bytes => ty,
+                Type::BigInt => ty,
+                Type::Decimal => ty,
                 Type::TypeVar(_) => {
                     let ptr = ty as *const Type<'a>;
                     if let Some(&mapped) = var_map.get(&ptr) {
@@ -377,6 +427,14 @@ impl<'a> TypeBuilder<'a> for &'a TypeManager<'a> {
         TypeManager::bytes(self)
     }
 
+    fn bigint(&self) -> Self::Repr {
+        TypeManager::bigint(self)
+    }
+
+    fn decimal(&self) -> Self::Repr {
+        TypeManager::decimal(self)
+    }
+
     fn type_var(&self, id: u16) -> Self::Repr {
         TypeManager::type_var(self, id)
     }
@@ -441,6 +499,8 @@ impl<'a> TypeView<'a> for &'a Type<'a> {
             },
             Type::Symbol(parts) => TypeKind::Symbol(parts.iter().copied()),
             Type::Option(inner) => TypeKind::Option(inner),
+            Type::BigInt => TypeKind::BigInt,
+            Type::Decimal => TypeKind::Decimal,
         }
     }
 }
@@ -488,3 +488,32 @@ fn test_record_with_strings_in_vec() {
         "Records created from String vecs should intern to the same type"
     );
 }
+
+#[test]
+fn test_stats_count_interning_hits() {
+    let bump = Bump::new();
+    let manager = TypeManager::new(&bump);
+
+    let before = manager.stats();
+    let first = manager.array(manager.int());
+    let after_first = manager.stats();
+    assert_eq!(after_first.types, before.types + 2);
+    assert_eq!(after_first.misses, before.misses + 2);
+
+    let second = manager.array(manager.int());
+    let after_second = manager.stats();
+    assert!(core::ptr::eq(first, second));
+    assert_eq!(after_second.hits, after_first.hits + 2);
+    assert_eq!(after_second.misses, after_first.misses);
+    assert_eq!(after_second.types, after_first.types);
+}
+
+#[test]
+fn test_stats_count_unique_strings() {
+    let bump = Bump::new();
+    let manager = TypeManager::new(&bump);
+
+    manager.record(vec![("name", manager.str()), ("age", manager.int())]);
+    manager.record(vec![("name", manager.str())]);
+    assert_eq!(manager.stats().strings, 2);
+}
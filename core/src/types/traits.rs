@@ -21,6 +21,7 @@ pub trait TypeView<'a>: Sized + Copy + Eq {
 // `struct { uint8_t tag; Payload payload; }`
 // See: https://github.com/rust-lang/rfcs/blob/master/text/2195-really-tagged-unions.md
 #[repr(C, u8)]
+#[non_exhaustive]
 pub enum TypeKind<'a, T: TypeView<'a>> {
     TypeVar(u16) = 0,
     Int = 1,
@@ -34,6 +35,8 @@ pub enum TypeKind<'a, T: TypeView<'a>> {
     Function { params: T::Iter, ret: T } = 9,
     Symbol(T::StrIter) = 10, // Must be sorted.
     Option(T) = 11,
+    BigInt = 12,
+    Decimal = 13,
 }
 
 impl<'a, T: TypeView<'a>> TypeKind<'a, T> {
@@ -65,12 +68,15 @@ impl<'a, T: TypeView<'a>> TypeKind<'a, T> {
             TypeKind::Function { .. } => TypeTag::Function,
             TypeKind::Symbol(_) => TypeTag::Symbol,
             TypeKind::Option(_) => TypeTag::Option,
+            TypeKind::BigInt => TypeTag::BigInt,
+            TypeKind::Decimal => TypeTag::Decimal,
         }
     }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[repr(u8)]
+#[non_exhaustive]
 pub enum TypeTag {
     TypeVar = 0,
     Int = 1,
@@ -84,6 +90,8 @@ pub enum TypeTag {
     Function = 9,
     Symbol = 10,
     Option = 11,
+    BigInt = 12,
+    Decimal = 13,
 }
 
 impl TryFrom<u8> for TypeTag {
@@ -103,6 +111,8 @@ impl TryFrom<u8> for TypeTag {
             9 => Ok(TypeTag::Function),
             10 => Ok(TypeTag::Symbol),
             11 => Ok(TypeTag::Option),
+            12 => Ok(TypeTag::BigInt),
+            13 => Ok(TypeTag::Decimal),
             _ => Err(()),
         }
     }
@@ -141,6 +151,8 @@ pub trait TypeBuilder<'a>: Copy {
     fn bool(&self) -> Self::Repr;
     fn str(&self) -> Self::Repr;
     fn bytes(&self) -> Self::Repr;
+    fn bigint(&self) -> Self::Repr;
+    fn decimal(&self) -> Self::Repr;
 
     // Type variable
     fn type_var(&self, id: u16) -> Self::Repr;
@@ -247,6 +259,8 @@ pub trait TypeTransformer<'a, B: TypeBuilder<'a>> {
             TypeKind::Bool => self.builder().bool(),
             TypeKind::Str => self.builder().str(),
             TypeKind::Bytes => self.builder().bytes(),
+            TypeKind::BigInt => self.builder().bigint(),
+            TypeKind::Decimal => self.builder().decimal(),
 
             // Type variable - preserve ID (override transform() to customize)
             TypeKind::TypeVar(id) => self.builder().type_var(id),
@@ -344,6 +358,8 @@ pub trait TypeVisitor<'a> {
             | TypeKind::Str
             | TypeKind::Bytes
             | TypeKind::TypeVar(_) => {}
+            TypeKind::BigInt => {}
+            TypeKind::Decimal => {}
 
             // Collections - recursively visit elements
             TypeKind::Array(elem) => {
@@ -552,6 +568,47 @@ where
 /// assert_eq!(display_type(arr_ty), "Array[Int]");
 /// ```
 pub(super) fn display_type<'a, V: TypeView<'a>>(ty: V) -> alloc::string::String {
+    display_type_with(ty, &mut |id| alloc::format!("_{}", id))
+}
+
+/// Format a type for display like [`display_type`], but with type variables
+/// named `a`, `b`, ... in order of first appearance.
+///
+/// The ids of type variables depend on how much the type checker did before
+/// reaching the type, so the same type can display differently from one
+/// program to the next. Names from this function only depend on the type.
+///
+/// # Example
+///
+/// ```ignore
+/// let a = mgr.fresh_type_var();
+/// let b = mgr.fresh_type_var();
+/// let func = mgr.function(&[b, a], b);
+///
+/// assert_eq!(display_type_with_var_names(func), "(a, b) => a");
+/// ```
+pub fn display_type_with_var_names<'a, V: TypeView<'a>>(ty: V) -> alloc::string::String {
+    let mut seen: alloc::vec::Vec<u16> = alloc::vec::Vec::new();
+    display_type_with(ty, &mut |id| {
+        let index = match seen.iter().position(|&seen_id| seen_id == id) {
+            Some(index) => index,
+            None => {
+                seen.push(id);
+                seen.len() - 1
+            }
+        };
+        match u8::try_from(index) {
+            Ok(index) if index < 26 => alloc::string::String::from((b'a' + index) as char),
+            _ => alloc::format!("t{}", index),
+        }
+    })
+}
+
+/// Format a type for display, naming type variables with `var_name`.
+fn display_type_with<'a, V: TypeView<'a>>(
+    ty: V,
+    var_name: &mut dyn FnMut(u16) -> alloc::string::String,
+) -> alloc::string::String {
     use alloc::string::ToString;
 
     match ty.view() {
@@ -560,32 +617,39 @@ pub(super) fn display_type<'a, V: TypeView<'a>>(ty: V) -> alloc::string::String
         TypeKind::Bool => "Bool".to_string(),
         TypeKind::Str => "Str".to_string(),
         TypeKind::Bytes => "Bytes".to_string(),
+        TypeKind::BigInt => "BigInt".to_string(),
+        TypeKind::Decimal => "Decimal".to_string(),
 
-        TypeKind::TypeVar(id) => alloc::format!("_{}", id),
+        TypeKind::TypeVar(id) => var_name(id),
 
         TypeKind::Array(elem) => {
-            alloc::format!("Array[{}]", display_type(elem))
+            alloc::format!("Array[{}]", display_type_with(elem, var_name))
         }
 
         TypeKind::Map(key, val) => {
-            alloc::format!("Map[{}, {}]", display_type(key), display_type(val))
+            let key = display_type_with(key, var_name);
+            alloc::format!("Map[{}, {}]", key, display_type_with(val, var_name))
         }
 
         TypeKind::Option(inner) => {
-            alloc::format!("Option[{}]", display_type(inner))
+            alloc::format!("Option[{}]", display_type_with(inner, var_name))
         }
 
         TypeKind::Record(fields) => {
             let field_strs: alloc::vec::Vec<alloc::string::String> = fields
-                .map(|(name, field_ty)| alloc::format!("{}: {}", name, display_type(field_ty)))
+                .map(|(name, field_ty)| {
+                    alloc::format!("{}: {}", name, display_type_with(field_ty, var_name))
+                })
                 .collect();
             alloc::format!("Record[{}]", field_strs.join(", "))
         }
 
         TypeKind::Function { params, ret } => {
-            let param_strs: alloc::vec::Vec<alloc::string::String> =
-                params.map(|param_ty| display_type(param_ty)).collect();
-            alloc::format!("({}) => {}", param_strs.join(", "), display_type(ret))
+            let param_strs: alloc::vec::Vec<alloc::string::String> = params
+                .map(|param_ty| display_type_with(param_ty, var_name))
+                .collect();
+            let ret = display_type_with(ret, var_name);
+            alloc::format!("({}) => {}", param_strs.join(", "), ret)
         }
 
         TypeKind::Symbol(parts) => {
@@ -603,6 +667,19 @@ mod tests {
     use bumpalo::Bump;
     use hashbrown::{HashMap, HashSet};
 
+    #[test]
+    fn test_display_type_with_var_names() {
+        let bump = Bump::new();
+        let mgr = TypeManager::new(&bump);
+
+        let var_0 = mgr.type_var(7);
+        let var_1 = mgr.type_var(3);
+        let func = mgr.function(&[var_0, mgr.array(var_1)], var_0);
+        assert_eq!(display_type(func), "(_7, Array[_3]) => _7");
+        assert_eq!(display_type_with_var_names(func), "(a, Array[b]) => a");
+        assert_eq!(display_type_with_var_names(mgr.int()), "Int");
+    }
+
     #[test]
     fn test_closure_transformer_simple_remap() {
         let bump = Bump::new();
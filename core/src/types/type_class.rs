@@ -102,6 +102,10 @@ pub fn has_instance<'a>(ty: &'a Type<'a>, class: TypeClassId) -> bool {
     match (ty.view(), class) {
         // Numeric: Int, Float
         (TypeKind::Int | TypeKind::Float, TypeClassId::Numeric) => true,
+        (TypeKind::BigInt, TypeClassId::Numeric | TypeClassId::Hashable | TypeClassId::Ord) => true,
+        (TypeKind::Decimal, TypeClassId::Numeric | TypeClassId::Hashable | TypeClassId::Ord) => {
+            true
+        }
 
         // Indexable: Array, Map, Bytes
         (TypeKind::Array(_), TypeClassId::Indexable) => true,
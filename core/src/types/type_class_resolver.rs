@@ -403,6 +403,8 @@ impl<'types> TypeClassResolver<'types> {
         let final_ty = unification.resolve(unified_operand);
         match final_ty.view() {
             TypeKind::Int | TypeKind::Float => Ok(()),
+            TypeKind::BigInt => Ok(()),
+            TypeKind::Decimal => Ok(()),
             TypeKind::TypeVar(_) => Ok(()), // Still polymorphic, OK
             _ => Err(ConstraintError {
                 ty: format!("{}", final_ty),
@@ -9,6 +9,7 @@ use crate::types::traits::display_type;
 
 #[derive(Serialize, Clone, Hash)]
 #[repr(C, u8)]
+#[non_exhaustive]
 pub enum Type<'a> {
     // Type variables.
     TypeVar(u16) = 0,
@@ -39,6 +40,13 @@ pub enum Type<'a> {
     // Option type.
     Option(&'a Type<'a>) = 11,
 
+    // Arbitrary-precision integers (values need the `bigint` feature).
+    BigInt = 12,
+
+    // Fixed-point decimals for exact base-10 arithmetic (values need the
+    // `decimal` feature).
+    Decimal = 13,
+
     // TODO: More types to add later:
     //   Custom(&'a str),
     //   Union(&'a [&'a Type<'a>]),  // Must be sorted.
@@ -61,6 +69,8 @@ impl Hash for CompareTypeArgs<'_> {
         match &self.0 {
             // Primitives - just discriminant is enough (no additional data)
             Type::Int | Type::Float | Type::Bool | Type::Str | Type::Bytes => {}
+            Type::BigInt => {}
+            Type::Decimal => {}
 
             // TypeVar - hash the ID
             Type::TypeVar(id) => {
@@ -108,6 +118,8 @@ impl PartialEq for CompareTypeArgs<'_> {
                 | (Type::Bool, Type::Bool)
                 | (Type::Str, Type::Str)
                 | (Type::Bytes, Type::Bytes) => true,
+                (Type::BigInt, Type::BigInt) => true,
+                (Type::Decimal, Type::Decimal) => true,
 
                 // TypeVar - compare IDs
                 (Type::TypeVar(id1), Type::TypeVar(id2)) => id1 == id2,
@@ -19,6 +19,7 @@ pub enum Error {
     OccursCheckFailed { type_var: String, ty: String },
     FieldCountMismatch { expected: usize, found: usize },
     FieldNameMismatch { expected: String, found: String },
+    MissingField { field: String },
     FunctionParamCountMismatch { expected: usize, found: usize },
     TypeMismatch { left: String, right: String },
 }
@@ -155,6 +156,8 @@ impl<'a, B: TypeBuilder<'a> + 'a> Unification<'a, B> {
             | TypeKind::Str
             | TypeKind::Bytes
             | TypeKind::Symbol(_) => resolved,
+            TypeKind::BigInt => resolved,
+            TypeKind::Decimal => resolved,
 
             // Composite types - recursively resolve all components
             TypeKind::Array(elem) => {
@@ -207,6 +210,8 @@ impl<'a, B: TypeBuilder<'a> + 'a> Unification<'a, B> {
                 params.any(|p| self.occurs_in(id, p)) || self.occurs_in(id, ret)
             }
             Symbol(_) | Int | Float | Bool | Str | Bytes | TypeVar(_) => false,
+            BigInt => false,
+            Decimal => false,
         }
     }
 
@@ -288,6 +293,8 @@ impl<'a, B: TypeBuilder<'a> + 'a> Unification<'a, B> {
 
             // Primitives - must match exactly
             (Int, Int) | (Float, Float) | (Bool, Bool) | (Str, Str) | (Bytes, Bytes) => Ok(t1),
+            (BigInt, BigInt) => Ok(t1),
+            (Decimal, Decimal) => Ok(t1),
 
             // Array - unify element types
             (Array(e1), Array(e2)) => {
@@ -389,6 +396,34 @@ impl<'a, B: TypeBuilder<'a> + 'a> Unification<'a, B> {
         }
     }
 
+    /// Check that a value of type `actual` can be used where `expected` is required.
+    ///
+    /// Records get width subtyping: a record with extra fields is accepted where
+    /// a record with fewer fields is expected, recursively through field types.
+    /// Any other pair of types must unify. Returns the expected type.
+    pub fn subsumes_to(&mut self, actual: B::Repr, expected: B::Repr) -> Result<B::Repr, Error> {
+        let actual = self.resolve(actual);
+        let expected = self.resolve(expected);
+
+        let (TypeKind::Record(actual_fields), TypeKind::Record(expected_fields)) =
+            (actual.view(), expected.view())
+        else {
+            return self.unifies_to(actual, expected);
+        };
+
+        let actual_fields: Vec<_> = actual_fields.collect();
+        for (name, expected_ty) in expected_fields {
+            let Some((_, actual_ty)) = actual_fields.iter().find(|(other, _)| *other == name)
+            else {
+                return Err(Error::MissingField {
+                    field: name.to_string(),
+                });
+            };
+            self.subsumes_to(*actual_ty, expected_ty)?;
+        }
+        Ok(expected)
+    }
+
     /// Collect all free type variables in a type (resolution-aware).
     ///
     /// Returns the set of type variable IDs that appear in the type after resolving
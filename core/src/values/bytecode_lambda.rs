@@ -10,8 +10,10 @@
 //! appropriate instantiation based on argument types.
 
 use super::dynamic::Value;
-use super::function::{FfiContext, Function};
-use crate::evaluator::ExecutionError;
+use super::function::{CallDepth, FfiContext, Function};
+use crate::String;
+use crate::evaluator::{ExecutionError, ResourceExceededError};
+use crate::parser::Span;
 use crate::types::{
     Type,
     traits::{TypeKind, TypeView},
@@ -19,6 +21,7 @@ use crate::types::{
 use crate::values::RawValue;
 use crate::vm::{Code, VM};
 use bumpalo::Bump;
+use core::cell::OnceCell;
 
 /// A single compiled instantiation of a lambda.
 ///
@@ -42,6 +45,13 @@ pub struct LambdaInstantiation<'types, 'arena> {
 /// Lambdas can capture variables from their enclosing scope. Captured variables are stored
 /// as a slice of RawValues and passed to the VM when the lambda is called.
 ///
+/// # Recursion
+///
+/// Lambdas bound in the same `where` that call each other share their group,
+/// which the VM fills in once all of them are created. Each call runs one
+/// level deeper than its caller and fails with a stack overflow error past
+/// `max_depth`.
+///
 /// # Polymorphism
 ///
 /// For polymorphic lambdas (e.g., `(x) => x`), multiple Code instantiations are stored,
@@ -58,6 +68,10 @@ pub struct BytecodeLambda<'types, 'arena> {
 
     /// Captured values from the enclosing scope
     captures: &'arena [RawValue],
+
+    /// Array of the lambdas of the same `where` that this one may call, in
+    /// binding order
+    recursive: Option<&'arena OnceCell<RawValue>>,
 }
 
 impl<'types, 'arena> BytecodeLambda<'types, 'arena> {
@@ -86,9 +100,17 @@ impl<'types, 'arena> BytecodeLambda<'types, 'arena> {
             ty,
             instantiations,
             captures,
+            recursive: None,
         }
     }
 
+    /// Give the lambda access to the lambdas of its recursive group, once
+    /// they are all created.
+    pub fn with_recursive(mut self, recursive: &'arena OnceCell<RawValue>) -> Self {
+        self.recursive = Some(recursive);
+        self
+    }
+
     /// Create a monomorphic lambda with a single instantiation.
     ///
     /// Convenience constructor for the common case of non-polymorphic lambdas.
@@ -152,6 +174,19 @@ impl<'types, 'arena> Function<'types, 'arena> for BytecodeLambda<'types, 'arena>
         ctx: &FfiContext<'types, 'arena>,
         args: &[Value<'types, 'arena>],
     ) -> Result<Value<'types, 'arena>, ExecutionError> {
+        let call_depth = ctx.call_depth();
+        if call_depth.is_exceeded() {
+            return Err(ExecutionError {
+                kind: ResourceExceededError::StackOverflow {
+                    depth: call_depth.depth,
+                    max_depth: call_depth.max_depth,
+                }
+                .into(),
+                source: String::new(),
+                span: Span(0..0),
+            });
+        }
+
         // Find the appropriate instantiation for these argument types
         let inst = self.find_instantiation(args);
 
@@ -161,7 +196,16 @@ impl<'types, 'arena> Function<'types, 'arena> for BytecodeLambda<'types, 'arena>
         let locals = args.iter().map(|arg| arg.as_raw()).collect();
 
         // Create VM with locals and captures, then execute
-        let mut vm = VM::new(ctx.arena(), inst.code, locals, self.captures);
+        let recursive = self.recursive.and_then(|cell| cell.get()).copied();
+        let mut vm = VM::new(ctx.arena(), inst.code, locals, self.captures)
+            .with_recursive(recursive)
+            .with_call_depth(CallDepth {
+                depth: call_depth.depth + 1,
+                ..call_depth
+            })
+            .with_fuel(ctx.fuel())
+            .with_function_call_hook(ctx.function_call_hook().cloned())
+            .with_record_field_order(ctx.preserve_record_field_order());
         let result = vm.run()?;
 
         tracing::trace!(result = ?result, "call_unchecked: result raw");
@@ -178,4 +222,8 @@ impl<'types, 'arena> Function<'types, 'arena> for BytecodeLambda<'types, 'arena>
         // for the result of evaluating the lambda body with these argument types.
         Ok(Value::from_raw_unchecked(return_type, result))
     }
+
+    fn is_lambda(&self) -> bool {
+        true
+    }
 }
@@ -0,0 +1,328 @@
+//! Fixed-point decimal numbers for exact base-10 arithmetic.
+//!
+//! A [`Decimal`] is an `i128` mantissa scaled by a power of ten, so values such
+//! as `0.1` are represented exactly (unlike `Float`). Decimals are kept
+//! normalized (no trailing zeros in the mantissa), which makes equal numbers
+//! bit-identical: `0.30` and `0.3` have the same representation.
+//!
+//! Results needing more than [`MAX_SCALE`] fractional digits (products and
+//! quotients) are rounded half-to-even. Operations return `None` when the
+//! mantissa would overflow.
+
+use core::cmp::Ordering;
+use core::fmt;
+
+/// Maximum number of digits after the decimal point.
+pub const MAX_SCALE: u32 = 18;
+
+/// Size of the byte encoding produced by [`Decimal::to_bytes`].
+pub const ENCODED_LEN: usize = 17;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Decimal {
+    mantissa: i128,
+    scale: u32,
+}
+
+fn pow10(exponent: u32) -> i128 {
+    10_i128.pow(exponent)
+}
+
+/// Divide `value` by `divisor`, rounding half-to-even.
+fn div_round_half_even(value: i128, divisor: i128) -> i128 {
+    let quotient = value / divisor;
+    let twice_remainder = (value % divisor).unsigned_abs() * 2;
+    let divisor = divisor.unsigned_abs();
+    if twice_remainder > divisor || (twice_remainder == divisor && quotient % 2 != 0) {
+        quotient + value.signum()
+    } else {
+        quotient
+    }
+}
+
+impl Decimal {
+    pub const ZERO: Decimal = Decimal {
+        mantissa: 0,
+        scale: 0,
+    };
+
+    /// Build a decimal equal to `mantissa / 10^scale`, rounding to [`MAX_SCALE`].
+    fn from_parts(mut mantissa: i128, mut scale: u32) -> Self {
+        if scale > MAX_SCALE {
+            mantissa = div_round_half_even(mantissa, pow10(scale - MAX_SCALE));
+            scale = MAX_SCALE;
+        }
+        while scale > 0 && mantissa % 10 == 0 {
+            mantissa /= 10;
+            scale -= 1;
+        }
+        Decimal { mantissa, scale }
+    }
+
+    pub fn from_int(value: i64) -> Self {
+        Decimal {
+            mantissa: value as i128,
+            scale: 0,
+        }
+    }
+
+    /// Parse `[+-]digits[.digits]` (either side of the point may be empty, but
+    /// not both). Returns `None` for other syntax, more than [`MAX_SCALE`]
+    /// significant fractional digits, or overflow.
+    pub fn parse(text: &str) -> Option<Self> {
+        let (negative, unsigned) = match text.as_bytes().first() {
+            Some(b'-') => (true, &text[1..]),
+            Some(b'+') => (false, &text[1..]),
+            _ => (false, text),
+        };
+        let (integer_digits, fraction_digits) = match unsigned.split_once('.') {
+            Some((integer, fraction)) => (integer, fraction),
+            None => (unsigned, ""),
+        };
+        if integer_digits.is_empty() && fraction_digits.is_empty() {
+            return None;
+        }
+        // Trailing zeros carry no precision, so only significant digits count.
+        let fraction_digits = fraction_digits.trim_end_matches('0');
+        if fraction_digits.len() as u32 > MAX_SCALE {
+            return None;
+        }
+
+        let mut mantissa: i128 = 0;
+        for byte in integer_digits.bytes().chain(fraction_digits.bytes()) {
+            if !byte.is_ascii_digit() {
+                return None;
+            }
+            mantissa = mantissa
+                .checked_mul(10)?
+                .checked_add((byte - b'0') as i128)?;
+        }
+        if negative {
+            mantissa = -mantissa;
+        }
+
+        Some(Self::from_parts(mantissa, fraction_digits.len() as u32))
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.mantissa == 0
+    }
+
+    /// Rescale both operands to the larger scale.
+    fn aligned(self, other: Self) -> Option<(i128, i128, u32)> {
+        let scale = self.scale.max(other.scale);
+        let left = self.mantissa.checked_mul(pow10(scale - self.scale))?;
+        let right = other.mantissa.checked_mul(pow10(scale - other.scale))?;
+        Some((left, right, scale))
+    }
+
+    pub fn checked_add(self, other: Self) -> Option<Self> {
+        let (left, right, scale) = self.aligned(other)?;
+        Some(Self::from_parts(left.checked_add(right)?, scale))
+    }
+
+    pub fn checked_sub(self, other: Self) -> Option<Self> {
+        let (left, right, scale) = self.aligned(other)?;
+        Some(Self::from_parts(left.checked_sub(right)?, scale))
+    }
+
+    pub fn checked_mul(self, other: Self) -> Option<Self> {
+        let mantissa = self.mantissa.checked_mul(other.mantissa)?;
+        Some(Self::from_parts(mantissa, self.scale + other.scale))
+    }
+
+    /// Divide by long division to [`MAX_SCALE`] digits. Returns `None` when
+    /// `other` is zero or on overflow.
+    pub fn checked_div(self, other: Self) -> Option<Self> {
+        if other.is_zero() {
+            return None;
+        }
+        let divisor = other.mantissa.unsigned_abs();
+        let dividend = self.mantissa.unsigned_abs();
+        let mut quotient = dividend / divisor;
+        let mut remainder = dividend % divisor;
+        let mut scale = self.scale as i64 - other.scale as i64;
+
+        while scale < 0 || (remainder != 0 && scale < MAX_SCALE as i64) {
+            remainder = remainder.checked_mul(10)?;
+            quotient = quotient.checked_mul(10)?.checked_add(remainder / divisor)?;
+            remainder %= divisor;
+            scale += 1;
+        }
+
+        let twice_remainder = remainder.checked_mul(2)?;
+        if twice_remainder > divisor || (twice_remainder == divisor && !quotient.is_multiple_of(2))
+        {
+            quotient = quotient.checked_add(1)?;
+        }
+
+        let magnitude = i128::try_from(quotient).ok()?;
+        let negative = (self.mantissa < 0) != (other.mantissa < 0);
+        let mantissa = if negative { -magnitude } else { magnitude };
+        Some(Self::from_parts(mantissa, scale as u32))
+    }
+
+    /// Raise to a whole-number power by repeated squaring. Negative exponents
+    /// divide one by the positive power. Returns `None` on overflow or when a
+    /// zero base is raised to a negative power.
+    pub fn checked_powi(self, exponent: i64) -> Option<Self> {
+        let mut base = self;
+        let mut remaining = exponent.unsigned_abs();
+        let mut result = Decimal::from_int(1);
+        while remaining > 0 {
+            if remaining & 1 == 1 {
+                result = result.checked_mul(base)?;
+            }
+            remaining >>= 1;
+            if remaining > 0 {
+                base = base.checked_mul(base)?;
+            }
+        }
+        if exponent < 0 {
+            Decimal::from_int(1).checked_div(result)
+        } else {
+            Some(result)
+        }
+    }
+
+    pub fn checked_neg(self) -> Option<Self> {
+        Some(Decimal {
+            mantissa: self.mantissa.checked_neg()?,
+            scale: self.scale,
+        })
+    }
+
+    /// Convert to `Int`, rounding toward zero. Returns `None` if out of range.
+    pub fn to_int(self) -> Option<i64> {
+        i64::try_from(self.mantissa / pow10(self.scale)).ok()
+    }
+
+    /// Returns the value as an `Int` if it has no fractional part and fits.
+    pub fn to_whole_int(self) -> Option<i64> {
+        if self.scale == 0 {
+            i64::try_from(self.mantissa).ok()
+        } else {
+            None
+        }
+    }
+
+    /// Encode as the little-endian mantissa followed by the scale byte.
+    pub fn to_bytes(self) -> [u8; ENCODED_LEN] {
+        let mut bytes = [0; ENCODED_LEN];
+        bytes[..16].copy_from_slice(&self.mantissa.to_le_bytes());
+        bytes[16] = self.scale as u8;
+        bytes
+    }
+
+    /// Decode bytes produced by [`Decimal::to_bytes`].
+    pub fn from_bytes(bytes: &[u8]) -> Self {
+        let mantissa = i128::from_le_bytes(bytes[..16].try_into().expect("16-byte mantissa"));
+        Decimal {
+            mantissa,
+            scale: bytes[16] as u32,
+        }
+    }
+
+    /// Split into floor integer part and the fraction scaled to [`MAX_SCALE`].
+    fn floor_and_fraction(self) -> (i128, i128) {
+        let unit = pow10(self.scale);
+        (
+            self.mantissa.div_euclid(unit),
+            self.mantissa.rem_euclid(unit) * pow10(MAX_SCALE - self.scale),
+        )
+    }
+}
+
+impl PartialOrd for Decimal {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Decimal {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.floor_and_fraction().cmp(&other.floor_and_fraction())
+    }
+}
+
+impl fmt::Display for Decimal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let unit = pow10(self.scale).unsigned_abs();
+        let magnitude = self.mantissa.unsigned_abs();
+        if self.mantissa < 0 {
+            write!(f, "-")?;
+        }
+        write!(f, "{}", magnitude / unit)?;
+        if self.scale > 0 {
+            write!(
+                f,
+                ".{:0width$}",
+                magnitude % unit,
+                width = self.scale as usize
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+
+    fn decimal(text: &str) -> Decimal {
+        Decimal::parse(text).unwrap()
+    }
+
+    #[test]
+    fn test_parse_normalizes() {
+        assert_eq!(decimal("0.30"), decimal("0.3"));
+        assert_eq!(decimal("-0"), Decimal::ZERO);
+        assert_eq!(decimal("+1.").to_string(), "1");
+        assert_eq!(decimal(".05").to_string(), "0.05");
+        assert_eq!(Decimal::parse("."), None);
+        assert_eq!(Decimal::parse("1.2.3"), None);
+        assert_eq!(Decimal::parse("1_000"), None);
+    }
+
+    #[test]
+    fn test_arithmetic_is_exact() {
+        let sum = decimal("0.1").checked_add(decimal("0.2")).unwrap();
+        assert_eq!(sum, decimal("0.3"));
+        let product = decimal("1.05").checked_mul(decimal("-2")).unwrap();
+        assert_eq!(product.to_string(), "-2.1");
+    }
+
+    #[test]
+    fn test_division_rounds_half_even() {
+        let quotient = decimal("1").checked_div(decimal("3")).unwrap();
+        assert_eq!(quotient.to_string(), "0.333333333333333333");
+        // 5e-19 is exactly half a unit in the last place: round to even (0)
+        let half = decimal("0.000000000000000001")
+            .checked_div(decimal("2"))
+            .unwrap();
+        assert_eq!(half, Decimal::ZERO);
+        assert_eq!(decimal("1").checked_div(Decimal::ZERO), None);
+    }
+
+    #[test]
+    fn test_ordering_across_scales() {
+        assert!(decimal("-1.5") < decimal("-1.25"));
+        assert!(decimal("2") > decimal("1.999999999999999999"));
+        assert!(decimal("0.1") < decimal("0.11"));
+    }
+
+    #[test]
+    fn test_overflow() {
+        let big = Decimal::from_int(i64::MAX);
+        let huge = big.checked_mul(big).unwrap().checked_mul(big);
+        assert_eq!(huge, None);
+        assert_eq!(decimal("100000000000000000000").to_int(), None);
+    }
+
+    #[test]
+    fn test_bytes_round_trip() {
+        let value = decimal("-12345.678");
+        assert_eq!(Decimal::from_bytes(&value.to_bytes()), value);
+    }
+}
@@ -12,7 +12,9 @@ use crate::{
         from_raw::TypeError,
         function::Function,
         raw::{ArrayData, MapData, MapEntry, RawValue, RecordData, Slice},
+        typed::Bridge,
     },
+    vec,
 };
 
 use alloc::collections::BTreeMap;
@@ -27,6 +29,10 @@ pub struct Value<'ty_arena: 'value_arena, 'value_arena> {
     _phantom: core::marker::PhantomData<&'value_arena ()>,
 }
 
+/// Structural equality, shared by the evaluator and the VM.
+///
+/// Floats follow IEEE 754 at every depth: `NaN` is not equal to anything, so
+/// `[NaN] != [NaN]`, and `-0.0 == 0.0`, so `[-0.0] == [0.0]`.
 impl<'ty_arena: 'value_arena, 'value_arena> PartialEq for Value<'ty_arena, 'value_arena> {
     fn eq(&self, other: &Self) -> bool {
         use crate::types::traits::TypeKind;
@@ -51,6 +57,14 @@ impl<'ty_arena: 'value_arena, 'value_arena> PartialEq for Value<'ty_arena, 'valu
             TypeKind::Bool => self.as_bool().unwrap() == other.as_bool().unwrap(),
             TypeKind::Str => self.as_str().unwrap() == other.as_str().unwrap(),
             TypeKind::Bytes => self.as_bytes().unwrap() == other.as_bytes().unwrap(),
+            TypeKind::BigInt => {
+                // Minimal two's-complement encoding is canonical, so bytes compare exactly.
+                self.raw.as_bytes_unchecked() == other.raw.as_bytes_unchecked()
+            }
+            TypeKind::Decimal => {
+                // Decimals are normalized, so bytes compare exactly.
+                self.raw.as_bytes_unchecked() == other.raw.as_bytes_unchecked()
+            }
             TypeKind::Array(_) => {
                 let a = self.as_array().unwrap();
                 let b = other.as_array().unwrap();
@@ -132,6 +146,8 @@ impl<'ty_arena: 'value_arena, 'value_arena> PartialEq for Value<'ty_arena, 'valu
     }
 }
 
+// NaN makes equality non-reflexive for floats. Hashing and ordering treat all
+// NaNs as one value, so NaN map keys and constants still behave consistently.
 impl<'ty_arena: 'value_arena, 'value_arena> Eq for Value<'ty_arena, 'value_arena> {}
 
 impl<'ty_arena: 'value_arena, 'value_arena> PartialOrd for Value<'ty_arena, 'value_arena> {
@@ -140,6 +156,10 @@ impl<'ty_arena: 'value_arena, 'value_arena> PartialOrd for Value<'ty_arena, 'val
     }
 }
 
+/// Total order used to sort values and map keys.
+///
+/// Agrees with `==` except for NaN: `-0.0` and `0.0` are equal, and all NaNs
+/// are equal to each other and greater than every other float.
 impl<'ty_arena: 'value_arena, 'value_arena> Ord for Value<'ty_arena, 'value_arena> {
     fn cmp(&self, other: &Self) -> core::cmp::Ordering {
         use crate::types::traits::{TypeKind, TypeView};
@@ -159,15 +179,22 @@ impl<'ty_arena: 'value_arena, 'value_arena> Ord for Value<'ty_arena, 'value_aren
         match self_view {
             TypeKind::Int => self.as_int().unwrap().cmp(&other.as_int().unwrap()),
             TypeKind::Float => {
-                // Use total_cmp for NaN-safe total ordering
-                // NaN sorts greater than all other values
-                self.as_float()
-                    .unwrap()
-                    .total_cmp(&other.as_float().unwrap())
+                // Use total_cmp for NaN-safe total ordering. Canonicalizing
+                // first keeps -0.0 equal to 0.0 and sorts every NaN last.
+                canonical_float(self.as_float().unwrap())
+                    .total_cmp(&canonical_float(other.as_float().unwrap()))
             }
             TypeKind::Bool => self.as_bool().unwrap().cmp(&other.as_bool().unwrap()),
             TypeKind::Str => self.as_str().unwrap().cmp(other.as_str().unwrap()),
             TypeKind::Bytes => self.as_bytes().unwrap().cmp(other.as_bytes().unwrap()),
+            #[cfg(feature = "bigint")]
+            TypeKind::BigInt => self.as_bigint().unwrap().cmp(&other.as_bigint().unwrap()),
+            #[cfg(feature = "decimal")]
+            TypeKind::Decimal => self.as_decimal().unwrap().cmp(&other.as_decimal().unwrap()),
+            #[cfg(not(feature = "bigint"))]
+            TypeKind::BigInt => unreachable!("BigInt values require the bigint feature"),
+            #[cfg(not(feature = "decimal"))]
+            TypeKind::Decimal => unreachable!("Decimal values require the decimal feature"),
             TypeKind::Array(_) => {
                 // Lexicographic comparison
                 let a = self.as_array().unwrap();
@@ -238,22 +265,27 @@ impl<'ty_arena: 'value_arena, 'value_arena> Ord for Value<'ty_arena, 'value_aren
     }
 }
 
-/// Canonicalize a float for hashing to maintain Hash/Eq invariant.
+/// Canonicalize a float for hashing and ordering.
 ///
 /// - Maps -0.0 to +0.0 (since -0.0 == +0.0)
 /// - Maps all NaN representations to a single canonical NaN
-fn canonical_f64(value: f64) -> u64 {
+fn canonical_float(value: f64) -> f64 {
     if value.is_nan() {
         // Use a canonical NaN representation
-        f64::NAN.to_bits()
+        f64::NAN
     } else if value == 0.0 {
         // Map both +0.0 and -0.0 to +0.0
-        0.0_f64.to_bits()
+        0.0
     } else {
-        value.to_bits()
+        value
     }
 }
 
+/// Canonical float bits for hashing, to maintain the Hash/Eq invariant.
+fn canonical_f64(value: f64) -> u64 {
+    canonical_float(value).to_bits()
+}
+
 impl<'ty_arena: 'value_arena, 'value_arena> core::hash::Hash for Value<'ty_arena, 'value_arena> {
     fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
         use crate::types::traits::TypeKind;
@@ -285,6 +317,12 @@ impl<'ty_arena: 'value_arena, 'value_arena> core::hash::Hash for Value<'ty_arena
             TypeKind::Bytes => {
                 self.as_bytes().unwrap().hash(state);
             }
+            TypeKind::BigInt => {
+                self.raw.as_bytes_unchecked().hash(state);
+            }
+            TypeKind::Decimal => {
+                self.raw.as_bytes_unchecked().hash(state);
+            }
             TypeKind::Array(_) => {
                 let array = self.as_array().unwrap();
                 // Hash length first
@@ -366,6 +404,20 @@ impl<'ty_arena: 'value_arena, 'value_arena> core::fmt::Debug for Value<'ty_arena
                 let bytes = self.as_bytes().unwrap();
                 escape_bytes(f, bytes, BytesQuoteStyle::default())
             }
+            #[cfg(feature = "bigint")]
+            Type::BigInt => {
+                let value = self.as_bigint().unwrap();
+                write!(f, "{}n", value)
+            }
+            #[cfg(feature = "decimal")]
+            Type::Decimal => {
+                let value = self.as_decimal().unwrap();
+                write!(f, "{}d", value)
+            }
+            #[cfg(not(feature = "bigint"))]
+            Type::BigInt => unreachable!("BigInt values require the bigint feature"),
+            #[cfg(not(feature = "decimal"))]
+            Type::Decimal => unreachable!("Decimal values require the decimal feature"),
             Type::Array(_) => {
                 let array = self.as_array().unwrap();
                 write!(f, "[")?;
@@ -391,7 +443,7 @@ impl<'ty_arena: 'value_arena, 'value_arena> core::fmt::Debug for Value<'ty_arena
             Type::Record(_) => {
                 let record = self.as_record().unwrap();
                 write!(f, "{{")?;
-                for (i, (field_name, field_value)) in record.iter().enumerate() {
+                for (i, (field_name, field_value)) in record.iter_display_order().enumerate() {
                     if i > 0 {
                         write!(f, ", ")?;
                     }
@@ -446,6 +498,16 @@ impl<'ty_arena: 'value_arena, 'value_arena> core::fmt::Display for Value<'ty_are
                 let s = self.as_str().unwrap();
                 write!(f, "{}", s)
             }
+            #[cfg(feature = "bigint")]
+            Type::BigInt => {
+                let value = self.as_bigint().unwrap();
+                write!(f, "{}", value)
+            }
+            #[cfg(feature = "decimal")]
+            Type::Decimal => {
+                let value = self.as_decimal().unwrap();
+                write!(f, "{}", value)
+            }
 
             // Complex types and Bytes: delegate to Debug
             _ => write!(f, "{:?}", self),
@@ -473,7 +535,90 @@ fn format_float(f: &mut core::fmt::Formatter<'_>, value: f64) -> core::fmt::Resu
     }
 }
 
+/// The top-level kind of a [`Value`], for cheap dispatch without formatting
+/// its full type.
+///
+/// Containers report only their own kind: an `Array[Map[Str, Int]]` is
+/// [`ValueKind::Array`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ValueKind {
+    Int,
+    Float,
+    Bool,
+    Str,
+    Bytes,
+    BigInt,
+    Decimal,
+    Array,
+    Map,
+    Record,
+    Option,
+    Function,
+    Symbol,
+    /// A value typed by an unresolved type variable (not expected at runtime).
+    TypeVar,
+}
+
+impl ValueKind {
+    /// The kind's name as written in Melbi type syntax.
+    pub fn name(self) -> &'static str {
+        match self {
+            ValueKind::Int => "Int",
+            ValueKind::Float => "Float",
+            ValueKind::Bool => "Bool",
+            ValueKind::Str => "Str",
+            ValueKind::Bytes => "Bytes",
+            ValueKind::BigInt => "BigInt",
+            ValueKind::Decimal => "Decimal",
+            ValueKind::Array => "Array",
+            ValueKind::Map => "Map",
+            ValueKind::Record => "Record",
+            ValueKind::Option => "Option",
+            ValueKind::Function => "Function",
+            ValueKind::Symbol => "Symbol",
+            ValueKind::TypeVar => "TypeVar",
+        }
+    }
+}
+
+impl core::fmt::Display for ValueKind {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
 impl<'ty_arena: 'value_arena, 'value_arena> Value<'ty_arena, 'value_arena> {
+    // ============================================================================
+    // Type Inspection
+    // ============================================================================
+
+    /// The top-level kind of this value, read from its type's discriminant.
+    pub fn kind(&self) -> ValueKind {
+        match self.ty {
+            Type::Int => ValueKind::Int,
+            Type::Float => ValueKind::Float,
+            Type::Bool => ValueKind::Bool,
+            Type::Str => ValueKind::Str,
+            Type::Bytes => ValueKind::Bytes,
+            Type::BigInt => ValueKind::BigInt,
+            Type::Decimal => ValueKind::Decimal,
+            Type::Array(_) => ValueKind::Array,
+            Type::Map(_, _) => ValueKind::Map,
+            Type::Record(_) => ValueKind::Record,
+            Type::Option(_) => ValueKind::Option,
+            Type::Function { .. } => ValueKind::Function,
+            Type::Symbol(_) => ValueKind::Symbol,
+            Type::TypeVar(_) => ValueKind::TypeVar,
+        }
+    }
+
+    /// The name of this value's top-level kind (e.g. `"Array"`), without
+    /// formatting its full type.
+    pub fn type_name(&self) -> &'static str {
+        self.kind().name()
+    }
+
     // ============================================================================
     // Raw Value Access
     // ============================================================================
@@ -583,6 +728,38 @@ impl<'ty_arena: 'value_arena, 'value_arena> Value<'ty_arena, 'value_arena> {
         }
     }
 
+    /// Create an arbitrary-precision integer value.
+    ///
+    /// Requires arena for allocation. Type is inferred from TypeManager.
+    #[cfg(feature = "bigint")]
+    pub fn bigint(
+        arena: &'value_arena bumpalo::Bump,
+        type_mgr: &'ty_arena TypeManager<'ty_arena>,
+        value: &num_bigint::BigInt,
+    ) -> Self {
+        Self {
+            ty: type_mgr.bigint(),
+            raw: RawValue::make_bigint(arena, value),
+            _phantom: core::marker::PhantomData,
+        }
+    }
+
+    /// Create a fixed-point decimal value.
+    ///
+    /// Requires arena for allocation. Type is inferred from TypeManager.
+    #[cfg(feature = "decimal")]
+    pub fn decimal(
+        arena: &'value_arena bumpalo::Bump,
+        type_mgr: &'ty_arena TypeManager<'ty_arena>,
+        value: crate::values::decimal::Decimal,
+    ) -> Self {
+        Self {
+            ty: type_mgr.decimal(),
+            raw: RawValue::make_decimal(arena, value),
+            _phantom: core::marker::PhantomData,
+        }
+    }
+
     /// Create an array value with runtime type validation.
     ///
     /// Type must be Array(elem_ty). All elements must match elem_ty.
@@ -703,6 +880,58 @@ impl<'ty_arena: 'value_arena, 'value_arena> Value<'ty_arena, 'value_arena> {
         ty: &'ty_arena Type<'ty_arena>,
         fields: &[(&'ty_arena str, Value<'ty_arena, 'value_arena>)],
     ) -> Result<Self, TypeError> {
+        let raw_values = Self::record_raw_values(ty, fields)?;
+        let data = RecordData::new_with(arena, &raw_values);
+
+        Ok(Self {
+            ty,
+            raw: data.as_raw_value(),
+            _phantom: core::marker::PhantomData,
+        })
+    }
+
+    /// Create a record value that remembers the order its fields were written in.
+    ///
+    /// Fields are validated and stored exactly as in [`Value::record`], so field
+    /// access, equality and hashing are unaffected. `field_order[i]` is the index
+    /// into `fields` of the i-th field in source order; it only changes the order
+    /// in which fields are displayed.
+    ///
+    /// Returns error if `field_order` is not a permutation of the field indices.
+    pub fn record_with_field_order(
+        arena: &'value_arena bumpalo::Bump,
+        ty: &'ty_arena Type<'ty_arena>,
+        fields: &[(&'ty_arena str, Value<'ty_arena, 'value_arena>)],
+        field_order: &[u32],
+    ) -> Result<Self, TypeError> {
+        let raw_values = Self::record_raw_values(ty, fields)?;
+
+        // Validate: field_order is a permutation of 0..fields.len()
+        if field_order.len() != fields.len() {
+            return Err(TypeError::Mismatch);
+        }
+        let mut seen = vec![false; fields.len()];
+        for &index in field_order {
+            match seen.get_mut(index as usize) {
+                Some(seen) if !*seen => *seen = true,
+                _ => return Err(TypeError::Mismatch),
+            }
+        }
+
+        let data = RecordData::new_with_field_order(arena, &raw_values, field_order);
+
+        Ok(Self {
+            ty,
+            raw: data.as_raw_value(),
+            _phantom: core::marker::PhantomData,
+        })
+    }
+
+    /// Validate record fields against `ty` and extract their raw values.
+    fn record_raw_values(
+        ty: &'ty_arena Type<'ty_arena>,
+        fields: &[(&'ty_arena str, Value<'ty_arena, 'value_arena>)],
+    ) -> Result<Vec<RawValue>, TypeError> {
         // Validate: ty must be Record(field_types)
         let Type::Record(field_types) = ty else {
             return Err(TypeError::Mismatch);
@@ -725,22 +954,17 @@ impl<'ty_arena: 'value_arena, 'value_arena> Value<'ty_arena, 'value_arena> {
         }
 
         // Extract raw values
-        let raw_values: Vec<RawValue> = fields.iter().map(|(_, v)| v.raw).collect();
-
-        // Allocate in arena
-        let data = RecordData::new_with(arena, &raw_values);
-
-        Ok(Self {
-            ty,
-            raw: data.as_raw_value(),
-            _phantom: core::marker::PhantomData,
-        })
+        Ok(fields.iter().map(|(_, v)| v.raw).collect())
     }
 
     /// Create a map value with runtime type validation.
     ///
     /// The map will store key-value pairs in sorted order by key for efficient
-    /// binary search lookups. Keys will be sorted using Value::cmp.
+    /// binary search lookups. Keys will be sorted using Value::cmp, and when a
+    /// key appears more than once the last value wins.
+    ///
+    /// This is the canonical map order: the evaluator and the VM both build
+    /// maps this way, so iteration order never depends on insertion order.
     ///
     /// # Arguments
     ///
@@ -803,8 +1027,9 @@ impl<'ty_arena: 'value_arena, 'value_arena> Value<'ty_arena, 'value_arena> {
         )> = Vec::new();
         for (key, value) in sorted_pairs {
             if let Some(last) = deduplicated.last() {
-                if last.0 == key {
-                    // Same key as previous - replace the value
+                if last.0.cmp(&key).is_eq() {
+                    // Same key as previous (by the ordering, so NaN keys
+                    // deduplicate too) - replace the value
                     deduplicated.pop();
                 }
             }
@@ -863,6 +1088,23 @@ impl<'ty_arena: 'value_arena, 'value_arena> Value<'ty_arena, 'value_arena> {
         })
     }
 
+    /// Create a value from any Rust type with a [`Bridge`] mapping.
+    ///
+    /// The type is derived from `T`, so this can't fail. Strings go through
+    /// [`typed::Str`](crate::values::typed::Str):
+    ///
+    /// ```ignore
+    /// let count = Value::from_rust(&arena, type_mgr, 42_i64);
+    /// let name = Value::from_rust(&arena, type_mgr, Str::from_str(&arena, "melbi"));
+    /// ```
+    pub fn from_rust<T: Bridge + 'value_arena>(
+        arena: &'value_arena bumpalo::Bump,
+        type_mgr: &'ty_arena TypeManager<'ty_arena>,
+        value: T,
+    ) -> Self {
+        Self::from_raw_unchecked(T::type_from(type_mgr), T::to_raw_value(arena, value))
+    }
+
     // ============================================================================
     // Dynamic Extraction API
     // ============================================================================
@@ -928,6 +1170,28 @@ impl<'ty_arena: 'value_arena, 'value_arena> Value<'ty_arena, 'value_arena> {
         }
     }
 
+    /// Extract arbitrary-precision integer value dynamically.
+    ///
+    /// Returns error if value is not a BigInt.
+    #[cfg(feature = "bigint")]
+    pub fn as_bigint(&self) -> Result<num_bigint::BigInt, TypeError> {
+        match self.ty {
+            Type::BigInt => Ok(self.raw.as_bigint_unchecked()),
+            _ => Err(TypeError::Mismatch),
+        }
+    }
+
+    /// Extract fixed-point decimal value dynamically.
+    ///
+    /// Returns error if value is not a Decimal.
+    #[cfg(feature = "decimal")]
+    pub fn as_decimal(&self) -> Result<crate::values::decimal::Decimal, TypeError> {
+        match self.ty {
+            Type::Decimal => Ok(self.raw.as_decimal_unchecked()),
+            _ => Err(TypeError::Mismatch),
+        }
+    }
+
     /// Get dynamic array view.
     ///
     /// Returns Array wrapper that allows iteration and indexing
@@ -1154,6 +1418,32 @@ impl<'ty_arena, 'value_arena> Record<'ty_arena, 'value_arena> {
             _phantom: core::marker::PhantomData,
         }
     }
+
+    /// Iterate over fields in the order they should be displayed.
+    ///
+    /// This is source order for records built with
+    /// [`Value::record_with_field_order`], and sorted order otherwise.
+    pub fn iter_display_order(
+        &self,
+    ) -> impl Iterator<Item = (&'ty_arena str, Value<'ty_arena, 'value_arena>)> + '_
+    where
+        'ty_arena: 'value_arena,
+    {
+        let field_order = self.data.field_order();
+        (0..self.len()).map(move |i| {
+            let index = field_order.map_or(i, |order| order[i] as usize);
+            let (field_name, field_ty) = self.field_types[index];
+            let raw = unsafe { self.data.get(index) };
+            (
+                field_name,
+                Value {
+                    ty: field_ty,
+                    raw,
+                    _phantom: core::marker::PhantomData,
+                },
+            )
+        })
+    }
 }
 
 /// Iterator over Record fields.
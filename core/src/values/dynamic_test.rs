@@ -10,6 +10,7 @@ use crate::{
     },
 };
 use bumpalo::Bump;
+use core::hash::BuildHasher;
 
 #[test]
 fn test_dynamic_int() {
@@ -1180,10 +1181,9 @@ fn test_float_zero_ordering() {
     let pos_zero = Value::float(type_mgr, 0.0);
     let neg_zero = Value::float(type_mgr, -0.0);
 
-    // Using total_cmp: -0.0 < +0.0 (they're NOT equal for total ordering)
-    // But they are equal for ==
-    assert_eq!(pos_zero, neg_zero); // == treats them as equal
-    assert_eq!(neg_zero.cmp(&pos_zero), Ordering::Less); // But Ord distinguishes them
+    // Ordering agrees with ==, so sorting and map keys treat them as one value
+    assert_eq!(pos_zero, neg_zero);
+    assert_eq!(neg_zero.cmp(&pos_zero), Ordering::Equal);
 }
 
 #[test]
@@ -1560,3 +1560,151 @@ fn test_record_builder_vs_manual() {
     // Both methods should produce equal records
     assert_eq!(with_builder, manual);
 }
+
+// ============================================================================
+// Kind and type name
+// ============================================================================
+
+fn identity<'types, 'arena>(
+    _ctx: &crate::values::function::FfiContext<'types, 'arena>,
+    args: &[Value<'types, 'arena>],
+) -> Result<Value<'types, 'arena>, crate::evaluator::ExecutionError> {
+    Ok(args[0])
+}
+
+#[test]
+fn test_kind_of_scalars() {
+    use crate::values::dynamic::ValueKind;
+
+    let arena = Bump::new();
+    let type_mgr = TypeManager::new(&arena);
+
+    let cases = [
+        (Value::int(type_mgr, 1), ValueKind::Int, "Int"),
+        (Value::float(type_mgr, 1.5), ValueKind::Float, "Float"),
+        (Value::bool(type_mgr, true), ValueKind::Bool, "Bool"),
+        (
+            Value::str(&arena, type_mgr.str(), "hi"),
+            ValueKind::Str,
+            "Str",
+        ),
+        (
+            Value::bytes(&arena, type_mgr.bytes(), b"hi"),
+            ValueKind::Bytes,
+            "Bytes",
+        ),
+    ];
+    for (value, kind, name) in cases {
+        assert_eq!(value.kind(), kind);
+        assert_eq!(value.type_name(), name);
+        assert_eq!(kind.to_string(), name);
+    }
+}
+
+#[test]
+fn test_kind_of_containers_is_top_level() {
+    use crate::values::{dynamic::ValueKind, function::NativeFunction};
+
+    let arena = Bump::new();
+    let type_mgr = TypeManager::new(&arena);
+    let int = type_mgr.int();
+
+    let inner_map_ty = type_mgr.map(type_mgr.str(), int);
+    let inner_map = Value::map(
+        &arena,
+        inner_map_ty,
+        &[(
+            Value::str(&arena, type_mgr.str(), "a"),
+            Value::int(type_mgr, 1),
+        )],
+    )
+    .unwrap();
+    let array = Value::array(&arena, type_mgr.array(inner_map_ty), &[inner_map]).unwrap();
+    assert_eq!(inner_map.kind(), ValueKind::Map);
+    assert_eq!(array.kind(), ValueKind::Array);
+    assert_eq!(array.type_name(), "Array");
+
+    let map = Value::map(
+        &arena,
+        type_mgr.map(int, type_mgr.array(inner_map_ty)),
+        &[(Value::int(type_mgr, 0), array)],
+    )
+    .unwrap();
+    assert_eq!(map.kind(), ValueKind::Map);
+    assert_eq!(map.type_name(), "Map");
+
+    let record_ty = type_mgr.record(vec![("items", array.ty)]);
+    let record = Value::record(&arena, record_ty, &[("items", array)]).unwrap();
+    assert_eq!(record.kind(), ValueKind::Record);
+    assert_eq!(record.type_name(), "Record");
+
+    let option_ty = type_mgr.option(record_ty);
+    let some = Value::optional(&arena, option_ty, Some(record)).unwrap();
+    let none = Value::optional(&arena, option_ty, None).unwrap();
+    assert_eq!(some.kind(), ValueKind::Option);
+    assert_eq!(none.kind(), ValueKind::Option);
+    assert_eq!(some.type_name(), "Option");
+
+    let function_ty = type_mgr.function(&[int], int);
+    let function = Value::function(&arena, NativeFunction::new(function_ty, identity)).unwrap();
+    assert_eq!(function.kind(), ValueKind::Function);
+    assert_eq!(function.type_name(), "Function");
+}
+
+#[test]
+fn test_float_equality_inside_containers() {
+    let arena = Bump::new();
+    let type_mgr = TypeManager::new(&arena);
+    let float_array = |value: f64| {
+        Value::array(
+            &arena,
+            type_mgr.array(type_mgr.float()),
+            &[Value::float(type_mgr, value)],
+        )
+        .unwrap()
+    };
+
+    // NaN is never equal, even to the same array
+    let nan_array = float_array(f64::NAN);
+    assert!(nan_array != nan_array);
+
+    // Signed zeros are equal, and hash and order the same way
+    let negative_zero = float_array(-0.0);
+    let positive_zero = float_array(0.0);
+    assert!(negative_zero == positive_zero);
+    assert_eq!(
+        negative_zero.cmp(&positive_zero),
+        core::cmp::Ordering::Equal
+    );
+    let hasher = hashbrown::DefaultHashBuilder::default();
+    assert_eq!(
+        hasher.hash_one(negative_zero),
+        hasher.hash_one(positive_zero)
+    );
+}
+
+#[test]
+fn test_float_map_keys_use_canonical_order() {
+    let arena = Bump::new();
+    let type_mgr = TypeManager::new(&arena);
+    let map_ty = type_mgr.map(type_mgr.float(), type_mgr.int());
+    let float = |value: f64| Value::float(type_mgr, value);
+    let int = |value: i64| Value::int(type_mgr, value);
+
+    let map = Value::map(
+        &arena,
+        map_ty,
+        &[
+            (float(0.0), int(1)),
+            (float(f64::NAN), int(2)),
+            (float(-f64::NAN), int(3)),
+        ],
+    )
+    .unwrap();
+    let map = map.as_map().unwrap();
+
+    // NaNs collapse into one key (last value wins), as do signed zeros
+    assert_eq!(map.len(), 2);
+    assert_eq!(map.get(&float(-0.0)).unwrap().as_int().unwrap(), 1);
+    assert_eq!(map.get(&float(f64::NAN)).unwrap().as_int().unwrap(), 3);
+}
@@ -4,10 +4,16 @@
 //! Supports native Rust functions, and will support closures, foreign language functions, etc.
 
 use super::dynamic::Value;
-use crate::evaluator::ExecutionError;
+use crate::evaluator::{
+    EvaluatorOptions, ExecutionError, ExecutionErrorKind, ResourceExceededError,
+};
+use crate::parser::Span;
 use crate::types::{Type, manager::TypeManager};
 use crate::values::binder::Binder;
+use crate::{String, ToString};
+use alloc::sync::Arc;
 use bumpalo::Bump;
+use core::cell::Cell;
 
 // ============================================================================
 // FFI Context
@@ -39,13 +45,126 @@ use bumpalo::Bump;
 pub struct FfiContext<'types, 'arena> {
     arena: &'arena Bump,
     type_mgr: &'types TypeManager<'types>,
+    call_depth: CallDepth,
+    on_function_call: Option<FunctionCallHook>,
+    fuel: Option<&'arena Fuel>,
+    preserve_record_field_order: bool,
 }
 
 impl<'types, 'arena> FfiContext<'types, 'arena> {
     /// Create a new FFI context with the given arena and type manager.
     #[inline]
     pub fn new(arena: &'arena Bump, type_mgr: &'types TypeManager<'types>) -> Self {
-        Self { arena, type_mgr }
+        Self {
+            arena,
+            type_mgr,
+            call_depth: CallDepth::default(),
+            on_function_call: None,
+            fuel: None,
+            preserve_record_field_order: false,
+        }
+    }
+
+    /// Set the depth of the call made with this context.
+    #[inline]
+    pub fn with_call_depth(mut self, call_depth: CallDepth) -> Self {
+        self.call_depth = call_depth;
+        self
+    }
+
+    /// Get the depth of the call made with this context.
+    ///
+    /// Lambdas run their body one level deeper, so that runaway recursion
+    /// stops with a stack overflow error.
+    #[inline]
+    pub fn call_depth(&self) -> CallDepth {
+        self.call_depth
+    }
+
+    /// Set the hook that observes native function calls made by the called
+    /// function.
+    #[inline]
+    pub fn with_function_call_hook(mut self, hook: Option<FunctionCallHook>) -> Self {
+        self.on_function_call = hook;
+        self
+    }
+
+    /// Get the hook that observes native function calls, if any.
+    ///
+    /// Lambdas pass it on, so calls made from their bodies are observed too.
+    #[inline]
+    pub fn function_call_hook(&self) -> Option<&FunctionCallHook> {
+        self.on_function_call.as_ref()
+    }
+
+    /// Report a call to the function named `name` to the function call hook,
+    /// failing if the hook rejects it.
+    ///
+    /// Functions with a global name report themselves when called, so calls
+    /// that native functions make (like `Math.Sqrt` in
+    /// `Array.Map(xs, Math.Sqrt)`) are reported like any other.
+    pub(crate) fn report_call(
+        &self,
+        name: &str,
+        argument_count: usize,
+    ) -> Result<(), ExecutionError> {
+        match &self.on_function_call {
+            Some(hook) => {
+                hook.before_call(name, argument_count)
+                    .map_err(|kind| ExecutionError {
+                        kind,
+                        // Runtimes point errors with no source at the call
+                        source: String::new(),
+                        span: Span(0..0),
+                    })
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// Set the fuel budget that the called function draws from.
+    #[inline]
+    pub fn with_fuel(mut self, fuel: Option<&'arena Fuel>) -> Self {
+        self.fuel = fuel;
+        self
+    }
+
+    /// Get the fuel budget of the run, if it has one.
+    #[inline]
+    pub fn fuel(&self) -> Option<&'arena Fuel> {
+        self.fuel
+    }
+
+    /// Set whether records built by the called function remember their
+    /// source field order, for display.
+    #[inline]
+    pub fn with_record_field_order(mut self, preserve: bool) -> Self {
+        self.preserve_record_field_order = preserve;
+        self
+    }
+
+    /// Whether records built by the called function remember their source
+    /// field order.
+    ///
+    /// Lambdas pass it on, so records built in their bodies display like the
+    /// ones built outside.
+    #[inline]
+    pub fn preserve_record_field_order(&self) -> bool {
+        self.preserve_record_field_order
+    }
+
+    /// Charge `cost` units of fuel for work done by a native function.
+    ///
+    /// Functions whose work grows with their input (like copying an array or
+    /// building a string) charge in proportion to it, so that one call over
+    /// large data costs as much as the expressions it replaces. Does nothing
+    /// when the run has no fuel budget.
+    #[inline]
+    pub fn charge(&self, cost: u64) -> Result<(), ResourceExceededError> {
+        match self.fuel {
+            Some(fuel) => fuel.consume(cost),
+            None => Ok(()),
+        }
     }
 
     /// Get the arena for allocating values.
@@ -61,6 +180,126 @@ impl<'types, 'arena> FfiContext<'types, 'arena> {
     }
 }
 
+/// Fuel budget of a run, shared by the runtime and the functions it calls.
+///
+/// The evaluator charges one unit per evaluated expression and the VM one per
+/// instruction it runs, and native functions charge more for expensive work
+/// with [`FfiContext::charge`].
+#[derive(Debug)]
+pub struct Fuel {
+    max_fuel: u64,
+    remaining: Cell<u64>,
+}
+
+impl Fuel {
+    /// Create a budget of `max_fuel` units.
+    pub fn new(max_fuel: u64) -> Self {
+        Self {
+            max_fuel,
+            remaining: Cell::new(max_fuel),
+        }
+    }
+
+    /// Deduct `cost` units, failing once the budget is used up.
+    pub fn consume(&self, cost: u64) -> Result<(), ResourceExceededError> {
+        match self.remaining.get().checked_sub(cost) {
+            Some(remaining) => {
+                self.remaining.set(remaining);
+                Ok(())
+            }
+            None => {
+                self.remaining.set(0);
+                Err(ResourceExceededError::OutOfFuel {
+                    max_fuel: self.max_fuel,
+                })
+            }
+        }
+    }
+
+    /// Units used so far.
+    pub fn used(&self) -> u64 {
+        self.max_fuel - self.remaining.get()
+    }
+}
+
+/// Hook called before each native function call, with the function's name
+/// (like `max` or `Math.Sqrt`) and the number of arguments.
+///
+/// The hook may capture state, for example to count calls. Returning an error
+/// rejects the call, failing the run with
+/// `ResourceExceededError::CallRejected`, which `otherwise` does not catch.
+///
+/// # Example
+///
+/// ```
+/// use melbi_core::values::FunctionCallHook;
+///
+/// let hook = FunctionCallHook::new(|name, _argument_count| {
+///     if name == "Math.Sqrt" {
+///         Err("not allowed here".to_string())
+///     } else {
+///         Ok(())
+///     }
+/// });
+/// ```
+#[derive(Clone)]
+pub struct FunctionCallHook(Arc<HookFn>);
+
+type HookFn = dyn Fn(&str, usize) -> Result<(), String> + Send + Sync;
+
+impl FunctionCallHook {
+    /// Wrap a closure as a hook.
+    pub fn new(hook: impl Fn(&str, usize) -> Result<(), String> + Send + Sync + 'static) -> Self {
+        Self(Arc::new(hook))
+    }
+
+    /// Report a call to `function`, failing if the hook rejects it.
+    pub fn before_call(
+        &self,
+        function: &str,
+        argument_count: usize,
+    ) -> Result<(), ExecutionErrorKind> {
+        (self.0)(function, argument_count).map_err(|reason| {
+            ResourceExceededError::CallRejected {
+                function: function.to_string(),
+                reason,
+            }
+            .into()
+        })
+    }
+}
+
+impl core::fmt::Debug for FunctionCallHook {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str("FunctionCallHook")
+    }
+}
+
+/// Lambda call nesting at a call, carried into the called function.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CallDepth {
+    /// Number of lambda calls the caller is nested in.
+    pub depth: usize,
+    /// Depth at which calls stop with a stack overflow error.
+    pub max_depth: usize,
+}
+
+impl CallDepth {
+    /// Whether the limit has been reached.
+    pub fn is_exceeded(&self) -> bool {
+        self.depth >= self.max_depth
+    }
+}
+
+impl Default for CallDepth {
+    fn default() -> Self {
+        Self {
+            depth: 0,
+            max_depth: EvaluatorOptions::default().max_depth,
+        }
+    }
+}
+
 // ============================================================================
 // Function Trait
 // ============================================================================
@@ -110,6 +349,80 @@ pub trait Function<'types, 'arena> {
         ctx: &FfiContext<'types, 'arena>,
         args: &[Value<'types, 'arena>],
     ) -> Result<Value<'types, 'arena>, ExecutionError>;
+
+    /// Whether this function is a Melbi lambda rather than a native function.
+    ///
+    /// Calls to lambdas are not reported to [`FunctionCallHook`]s.
+    fn is_lambda(&self) -> bool {
+        false
+    }
+
+    /// The global name of this function, like `max` or `Math.Sqrt`.
+    ///
+    /// Calls are reported to [`FunctionCallHook`]s under this name, whatever
+    /// the expression calls the function. Functions with no global name, like
+    /// those passed in as arguments, are not reported.
+    fn global_name(&self) -> Option<&str> {
+        None
+    }
+}
+
+/// A function bound to a global name, reporting that name to
+/// [`FunctionCallHook`]s.
+pub(crate) struct NamedFunction<'types, 'arena> {
+    name: &'arena str,
+    function: &'arena dyn Function<'types, 'arena>,
+}
+
+impl<'types, 'arena> NamedFunction<'types, 'arena> {
+    /// Name the functions in a global value: the value itself if it is a
+    /// function, or the fields of a package record, as `Package.Field`.
+    pub(crate) fn name_functions(
+        arena: &'arena Bump,
+        name: &str,
+        value: Value<'types, 'arena>,
+    ) -> Value<'types, 'arena> {
+        if let Ok(function) = value.as_function() {
+            if function.is_lambda() || function.global_name().is_some() {
+                return value;
+            }
+            let name = arena.alloc_str(name);
+            return Value::function(arena, NamedFunction { name, function })
+                .expect("Function values have function types");
+        }
+        if let Ok(record) = value.as_record() {
+            let fields: alloc::vec::Vec<_> = record
+                .iter()
+                .map(|(field, field_value)| {
+                    let qualified = alloc::format!("{}.{}", name, field);
+                    (field, Self::name_functions(arena, &qualified, field_value))
+                })
+                .collect();
+            return Value::record(arena, value.ty, &fields)
+                .expect("Fields come from a record of this type");
+        }
+        value
+    }
+}
+
+impl<'types, 'arena> Function<'types, 'arena> for NamedFunction<'types, 'arena> {
+    fn ty(&self) -> &'types Type<'types> {
+        self.function.ty()
+    }
+
+    #[allow(unsafe_code)]
+    unsafe fn call_unchecked(
+        &self,
+        ctx: &FfiContext<'types, 'arena>,
+        args: &[Value<'types, 'arena>],
+    ) -> Result<Value<'types, 'arena>, ExecutionError> {
+        ctx.report_call(self.name, args.len())?;
+        unsafe { self.function.call_unchecked(ctx, args) }
+    }
+
+    fn global_name(&self) -> Option<&str> {
+        Some(self.name)
+    }
 }
 
 /// Type alias for native FFI function pointers.
@@ -5,10 +5,12 @@
 use super::dynamic::Value;
 use super::function::{FfiContext, Function};
 use crate::analyzer::typed_expr::TypedExpr;
-use crate::evaluator::{Evaluator, EvaluatorOptions, ExecutionError};
+use crate::evaluator::{Evaluator, EvaluatorOptions, ExecutionError, ResourceExceededError};
 use crate::scope_stack::CompleteScope;
 use crate::types::{Type, traits::TypeView, unification::Unification};
+use alloc::string::ToString;
 use alloc::vec::Vec;
+use core::cell::OnceCell;
 
 /// A lambda function value.
 ///
@@ -20,9 +22,13 @@ use alloc::vec::Vec;
 /// Lambdas can capture variables from their enclosing scope. Captured variables are stored
 /// as a slice of (name, value) pairs and pushed onto the scope stack when the lambda is called.
 ///
-/// # Current Limitations
+/// # Recursion
 ///
-/// - No recursive lambdas (would require Y-combinator or named functions)
+/// Lambdas bound in the same `where` may call themselves and each other. They
+/// share the group's bindings, which are filled in once all lambdas exist and
+/// pushed above the captures when called. Each call runs its body one call
+/// deeper than the caller, so runaway recursion stops at `max_depth` nested
+/// calls, as in the VM.
 ///
 /// # Future Extensions
 ///
@@ -39,6 +45,9 @@ pub struct EvalLambda<'types, 'arena> {
 
     /// Captured variables from the enclosing scope
     captures: &'arena [(&'arena str, Value<'types, 'arena>)],
+
+    /// Lambdas of the same `where` that this one may call, sorted by name
+    recursive_bindings: Option<&'arena OnceCell<&'arena [(&'arena str, Value<'types, 'arena>)]>>,
 }
 
 impl<'types, 'arena> EvalLambda<'types, 'arena> {
@@ -66,8 +75,19 @@ impl<'types, 'arena> EvalLambda<'types, 'arena> {
             params,
             body,
             captures,
+            recursive_bindings: None,
         }
     }
+
+    /// Give the lambda access to the lambdas of its `where` group, once they
+    /// are all created.
+    pub fn with_recursive_bindings(
+        mut self,
+        bindings: &'arena OnceCell<&'arena [(&'arena str, Value<'types, 'arena>)]>,
+    ) -> Self {
+        self.recursive_bindings = Some(bindings);
+        self
+    }
 }
 
 impl<'types, 'arena> Function<'types, 'arena> for EvalLambda<'types, 'arena> {
@@ -84,6 +104,23 @@ impl<'types, 'arena> Function<'types, 'arena> for EvalLambda<'types, 'arena> {
         let arena = ctx.arena();
         let type_mgr = ctx.type_mgr();
 
+        let call_depth = ctx.call_depth();
+        if call_depth.is_exceeded() {
+            return Err(ExecutionError {
+                kind: ResourceExceededError::StackOverflow {
+                    depth: call_depth.depth,
+                    max_depth: call_depth.max_depth,
+                }
+                .into(),
+                source: self.body.ann.source.to_string(),
+                span: self
+                    .body
+                    .ann
+                    .span_of(self.body.expr)
+                    .expect("span not found"),
+            });
+        }
+
         // Build parameter bindings for the lambda call
         let mut param_bindings: Vec<_> = self
             .params
@@ -96,15 +133,21 @@ impl<'types, 'arena> Function<'types, 'arena> for EvalLambda<'types, 'arena> {
         param_bindings.sort_by_key(|(name, _)| *name);
 
         // Create an evaluator with the lambda body's TypedExpr
-        // Scope order: globals (empty) → captures → parameters
+        // Scope order: globals (empty) → captures → recursive bindings → parameters
         let mut evaluator = Evaluator::new(
-            EvaluatorOptions::default(),
+            EvaluatorOptions {
+                max_depth: call_depth.max_depth,
+                preserve_record_field_order: ctx.preserve_record_field_order(),
+                on_function_call: ctx.function_call_hook().cloned(),
+            },
             arena,
             type_mgr,
             self.body, // Pass the full TypedExpr for error context
             &[],       // No globals passed - they'll be accessed through normal scoping
             &[],       // We'll push captures and parameters manually
         );
+        evaluator.set_call_depth(call_depth.depth + 1);
+        evaluator.set_fuel(ctx.fuel());
 
         // Build monomorphization unification by unifying parameter types with argument types
         // This allows the evaluator to resolve type variables in polymorphic lambda bodies
@@ -126,6 +169,11 @@ impl<'types, 'arena> Function<'types, 'arena> for EvalLambda<'types, 'arena> {
             evaluator.push_scope(CompleteScope::from_sorted(self.captures));
         }
 
+        // Push the lambdas this one may call, shadowing captures of the same name
+        if let Some(bindings) = self.recursive_bindings.and_then(|cell| cell.get()) {
+            evaluator.push_scope(CompleteScope::from_sorted(bindings));
+        }
+
         // Push parameters scope
         let param_slice = arena.alloc_slice_copy(&param_bindings);
         evaluator.push_scope(CompleteScope::from_sorted(param_slice));
@@ -133,4 +181,8 @@ impl<'types, 'arena> Function<'types, 'arena> for EvalLambda<'types, 'arena> {
         // Evaluate the body expression (now with full error context)
         evaluator.eval()
     }
+
+    fn is_lambda(&self) -> bool {
+        true
+    }
 }
@@ -1,5 +1,7 @@
 pub mod binder;
 pub mod bytecode_lambda;
+#[cfg(feature = "decimal")]
+pub mod decimal;
 pub mod dynamic;
 pub mod from_raw;
 pub mod function;
@@ -10,7 +12,9 @@ pub mod typed;
 
 pub use bytecode_lambda::{BytecodeLambda, LambdaInstantiation};
 pub use from_raw::TypeError;
-pub use function::{FfiContext, Function, NativeFn, NativeFunction};
+pub use function::{
+    CallDepth, FfiContext, Fuel, Function, FunctionCallHook, NativeFn, NativeFunction,
+};
 pub use lambda::EvalLambda;
 pub(crate) use raw::{ArrayData, MapData, RawValue, RecordData};
 pub use type_class_traits::{Melbi, Numeric};
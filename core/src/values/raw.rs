@@ -64,6 +64,22 @@ impl RawValue {
         RawValue { float_value: value }
     }
 
+    /// Store an arbitrary-precision integer as minimal two's-complement
+    /// little-endian bytes, so equal values have identical bytes.
+    #[cfg(feature = "bigint")]
+    pub fn make_bigint(arena: &Bump, value: &num_bigint::BigInt) -> RawValue {
+        let data = arena.alloc_slice_copy(&value.to_signed_bytes_le());
+        Slice::new(arena, data).as_raw_value()
+    }
+
+    /// Store a decimal as its fixed-size encoding; decimals are normalized, so
+    /// equal values have identical bytes.
+    #[cfg(feature = "decimal")]
+    pub fn make_decimal(arena: &Bump, value: crate::values::decimal::Decimal) -> RawValue {
+        let data = arena.alloc_slice_copy(&value.to_bytes());
+        Slice::new(arena, data).as_raw_value()
+    }
+
     #[inline(always)]
     pub fn as_optional_unchecked(&self) -> Option<RawValue> {
         unsafe { self.option.map(|p| *p.as_ref()) }
@@ -89,6 +105,16 @@ impl RawValue {
         unsafe { (*self.slice).as_slice() }
     }
 
+    #[cfg(feature = "bigint")]
+    pub fn as_bigint_unchecked(self) -> num_bigint::BigInt {
+        num_bigint::BigInt::from_signed_bytes_le(self.as_bytes_unchecked())
+    }
+
+    #[cfg(feature = "decimal")]
+    pub fn as_decimal_unchecked(self) -> crate::values::decimal::Decimal {
+        crate::values::decimal::Decimal::from_bytes(self.as_bytes_unchecked())
+    }
+
     #[inline(always)]
     pub fn as_str_unchecked<'a>(self) -> &'a str {
         unsafe { core::str::from_utf8_unchecked(self.as_bytes_unchecked()) }
@@ -250,10 +276,15 @@ impl<'a> ArrayData<'a> {
 
 #[repr(C)]
 pub struct RecordDataRepr {
+    /// Number of fields. The top bit is set when the record carries a display
+    /// order, stored as `u32`s after the field values.
     _length: usize,
     _data: [RawValue; 0],
 }
 
+/// Set in `RecordDataRepr::_length` when a display order follows the values.
+const RECORD_FIELD_ORDER_FLAG: usize = 1 << (usize::BITS - 1);
+
 #[derive(Clone, Copy)]
 pub struct RecordData<'a> {
     ptr: *const RecordDataRepr,
@@ -264,12 +295,18 @@ impl<'a> RecordData<'a> {
     fn new_uninitialized_in(
         arena: &'a Bump,
         length: usize,
+        has_field_order: bool,
     ) -> (*mut RecordDataRepr, *mut RawValue) {
-        let (layout, data_offset) = Self::layout(length);
+        let (layout, data_offset, _) = Self::layout(length, has_field_order);
 
         unsafe {
             let ptr = arena.alloc_layout(layout).as_ptr();
-            core::ptr::write::<usize>(ptr as *mut usize, length);
+            let header = if has_field_order {
+                length | RECORD_FIELD_ORDER_FLAG
+            } else {
+                length
+            };
+            core::ptr::write::<usize>(ptr as *mut usize, header);
             let data = ptr.add(data_offset) as *mut RawValue;
             let record_data_ptr = ptr as *mut RecordDataRepr;
             (record_data_ptr, data)
@@ -277,7 +314,7 @@ impl<'a> RecordData<'a> {
     }
 
     pub fn new_with(arena: &'a Bump, values: &[RawValue]) -> RecordData<'a> {
-        let (rec, data_ptr) = Self::new_uninitialized_in(arena, values.len());
+        let (rec, data_ptr) = Self::new_uninitialized_in(arena, values.len(), false);
         for (i, &val) in values.iter().enumerate() {
             unsafe { core::ptr::write(data_ptr.add(i), val) };
         }
@@ -287,19 +324,68 @@ impl<'a> RecordData<'a> {
         }
     }
 
-    fn layout(n: usize) -> (core::alloc::Layout, usize) {
+    /// Create a record that remembers the order its fields should be displayed in.
+    ///
+    /// `values` are in sorted field order (as always); `field_order[i]` is the
+    /// index into `values` of the i-th field to display. The order is stored
+    /// after the values, so records created with [`RecordData::new_with`] pay
+    /// nothing for it.
+    pub fn new_with_field_order(
+        arena: &'a Bump,
+        values: &[RawValue],
+        field_order: &[u32],
+    ) -> RecordData<'a> {
+        debug_assert_eq!(values.len(), field_order.len());
+        let (rec, data_ptr) = Self::new_uninitialized_in(arena, values.len(), true);
+        let (_, _, order_offset) = Self::layout(values.len(), true);
+        unsafe {
+            for (i, &val) in values.iter().enumerate() {
+                core::ptr::write(data_ptr.add(i), val);
+            }
+            let order_ptr = (rec as *mut u8).add(order_offset) as *mut u32;
+            core::ptr::copy_nonoverlapping(field_order.as_ptr(), order_ptr, field_order.len());
+        }
+        RecordData {
+            ptr: rec,
+            _marker: core::marker::PhantomData,
+        }
+    }
+
+    /// Layout of a record with `n` fields, with the offsets of its values and
+    /// of its display order (only allocated if `has_field_order`).
+    fn layout(n: usize, has_field_order: bool) -> (core::alloc::Layout, usize, usize) {
         let record_data_layout = core::alloc::Layout::new::<usize>();
         let elements_layout = core::alloc::Layout::array::<RawValue>(n).unwrap();
         let (layout, data_offset) = record_data_layout.extend(elements_layout).unwrap();
-        (layout.pad_to_align(), data_offset)
+        let order_len = if has_field_order { n } else { 0 };
+        let order_layout = core::alloc::Layout::array::<u32>(order_len).unwrap();
+        let (layout, order_offset) = layout.extend(order_layout).unwrap();
+        (layout.pad_to_align(), data_offset, order_offset)
     }
 
-    pub fn length(&self) -> usize {
+    fn header(&self) -> usize {
         unsafe { (*self.ptr)._length }
     }
 
+    pub fn length(&self) -> usize {
+        self.header() & !RECORD_FIELD_ORDER_FLAG
+    }
+
+    /// Display order of the fields, if one was recorded at construction.
+    pub fn field_order(&self) -> Option<&'a [u32]> {
+        if self.header() & RECORD_FIELD_ORDER_FLAG == 0 {
+            return None;
+        }
+        let length = self.length();
+        let (_, _, order_offset) = Self::layout(length, true);
+        unsafe {
+            let order_ptr = (self.ptr as *const u8).add(order_offset) as *const u32;
+            Some(core::slice::from_raw_parts(order_ptr, length))
+        }
+    }
+
     pub(self) fn as_ptr(&self) -> *const RawValue {
-        let (_, data_offset) = Self::layout(self.length());
+        let (_, data_offset, _) = Self::layout(self.length(), false);
         unsafe { (self.ptr as *const u8).add(data_offset) as *const RawValue }
     }
 
@@ -6,6 +6,7 @@
 //! eliminating the need for runtime type checking or TypeManager.
 
 use crate::Vec;
+use alloc::collections::BTreeMap;
 use core::marker::PhantomData;
 use core::ops::Deref;
 
@@ -889,6 +890,99 @@ impl<'a, K: Bridge, V: Bridge> IntoIterator for &'a Map<'a, K, V> {
     }
 }
 
+// ============================================================================
+// Option and Vec - Owned Rust values converted to/from Melbi Option and Array
+// ============================================================================
+
+/// Converts between a Rust `Option` and a Melbi `Option`.
+///
+/// Unlike [`Optional`], the inner value is converted eagerly, so these nest
+/// freely with other owned types: `Option<Vec<i64>>` is an `Option[Array[Int]]`.
+impl<T: Bridge> RawConvertible for Option<T> {
+    fn to_raw_value(arena: &Bump, value: Self) -> RawValue {
+        RawValue::make_optional(arena, value.map(|inner| T::to_raw_value(arena, inner)))
+    }
+
+    unsafe fn from_raw_value(raw: RawValue) -> Self {
+        raw.as_optional_unchecked()
+            .map(|inner| unsafe { T::from_raw_value(inner) })
+    }
+}
+
+impl<T: Bridge> Bridge for Option<T> {
+    type Raw = *const RawValue;
+    fn type_from<'b>(type_mgr: &'b TypeManager<'b>) -> &'b Type<'b> {
+        type_mgr.option(T::type_from(type_mgr))
+    }
+}
+
+/// Converts between a Rust `Vec` and a Melbi `Array`, copying the elements.
+///
+/// Elements are converted eagerly, so `Vec<Option<i64>>` is an
+/// `Array[Option[Int]]`.
+impl<T: Bridge> RawConvertible for Vec<T> {
+    fn to_raw_value(arena: &Bump, value: Self) -> RawValue {
+        Array::from_iter(arena, value).as_raw_value()
+    }
+
+    unsafe fn from_raw_value(raw: RawValue) -> Self {
+        let array_data = ArrayData::from_raw_value(raw);
+        (0..array_data.length())
+            .map(|i| unsafe { T::from_raw_value(array_data.get_unchecked(i)) })
+            .collect()
+    }
+}
+
+impl<T: Bridge> Bridge for Vec<T> {
+    type Raw = ArrayData<'static>;
+    fn type_from<'b>(type_mgr: &'b TypeManager<'b>) -> &'b Type<'b> {
+        type_mgr.array(T::type_from(type_mgr))
+    }
+}
+
+// ============================================================================
+// BTreeMap - Owned Rust map converted to/from a Melbi Map
+// ============================================================================
+
+/// Converts between a Rust `BTreeMap` and a Melbi `Map`, copying the entries.
+///
+/// Melbi maps are sorted by key, so `K`'s `Ord` must agree with Melbi's key
+/// order. This holds for `i64`, `bool`, and `&[u8]`.
+impl<K: Bridge + Ord, V: Bridge> RawConvertible for BTreeMap<K, V> {
+    fn to_raw_value(arena: &Bump, value: Self) -> RawValue {
+        // BTreeMap iterates in key order without duplicates: already sorted.
+        let entries: Vec<MapEntry> = value
+            .into_iter()
+            .map(|(key, value)| MapEntry {
+                key: K::to_raw_value(arena, key),
+                value: V::to_raw_value(arena, value),
+            })
+            .collect();
+        MapData::new_with_sorted(arena, &entries).as_raw_value()
+    }
+
+    unsafe fn from_raw_value(raw: RawValue) -> Self {
+        let map_data = MapData::from_raw_value(raw);
+        (0..map_data.length())
+            .map(|i| unsafe {
+                (
+                    K::from_raw_value(map_data.get_key(i)),
+                    V::from_raw_value(map_data.get_value(i)),
+                )
+            })
+            .collect()
+    }
+}
+
+impl<K: Bridge + Ord, V: Bridge> Bridge for BTreeMap<K, V> {
+    type Raw = MapData<'static>;
+    fn type_from<'b>(type_mgr: &'b TypeManager<'b>) -> &'b Type<'b> {
+        let key_ty = K::type_from(type_mgr);
+        let value_ty = V::type_from(type_mgr);
+        type_mgr.map(key_ty, value_ty)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -4,6 +4,7 @@ use hashbrown::HashSet;
 
 use crate::{
     Vec,
+    parser::Span,
     types::Type,
     values::RawValue,
     vm::{FunctionAdapter, GenericAdapter, Instruction},
@@ -20,6 +21,32 @@ pub struct Code<'t> {
     pub max_stack_size: usize,
     /// Nested lambda bytecode (for closures).
     pub lambdas: Vec<LambdaCode<'t>>,
+    /// Source spans as `(index, span)` pairs sorted by index: instructions
+    /// from `index` up to the next pair belong to the expression at `span`.
+    /// Empty when the source is unknown (e.g., hand-written bytecode).
+    pub spans: Vec<(usize, Span)>,
+    /// Source field order of record literals as `(index, order)` pairs sorted
+    /// by the index of their `MakeRecord`, used when records are displayed in
+    /// source order. Only literals not written in sorted order have one.
+    pub record_field_orders: Vec<(usize, Vec<u32>)>,
+}
+
+impl Code<'_> {
+    /// Source span of the expression that the instruction at `index`
+    /// belongs to, used to locate runtime errors.
+    pub fn span_at(&self, index: usize) -> Option<Span> {
+        let end = self.spans.partition_point(|(start, _)| *start <= index);
+        end.checked_sub(1).map(|i| self.spans[i].1.clone())
+    }
+
+    /// Source field order of the record built by the `MakeRecord` at `index`,
+    /// if it differs from sorted order.
+    pub fn record_field_order_at(&self, index: usize) -> Option<&[u32]> {
+        self.record_field_orders
+            .binary_search_by_key(&index, |(start, _)| *start)
+            .ok()
+            .map(|i| self.record_field_orders[i].1.as_slice())
+    }
 }
 
 /// Bytecode for a lambda/closure, including its type and capture count.
@@ -28,6 +55,9 @@ pub struct LambdaCode<'t> {
     pub lambda_type: &'t Type<'t>,
     /// Number of captured values from the enclosing scope.
     pub num_captures: u32,
+    /// Number of lambdas in the recursive group this lambda belongs to, or 0
+    /// if it is not recursive.
+    pub num_recursive: u32,
     /// The kind of lambda (monomorphic or polymorphic).
     pub kind: LambdaKind<'t>,
 }
@@ -183,6 +213,9 @@ impl core::fmt::Debug for LambdaCode<'_> {
         writeln!(f, "LambdaCode {{")?;
         writeln!(f, "      type: {}", self.lambda_type)?;
         writeln!(f, "      num_captures: {}", self.num_captures)?;
+        if self.num_recursive > 0 {
+            writeln!(f, "      num_recursive: {}", self.num_recursive)?;
+        }
 
         match &self.kind {
             LambdaKind::Mono { code } => {
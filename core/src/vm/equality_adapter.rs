@@ -0,0 +1,65 @@
+//! Structural equality adapter for the VM.
+//!
+//! This adapter enables `==` and `!=` on types without a dedicated comparison
+//! instruction: arrays, records, maps, options, and other non-scalar values.
+//! Like [`ArrayContainsAdapter`](super::ArrayContainsAdapter), it stores the
+//! operand type so raw values can be compared as typed `Value`s, which gives
+//! the VM the same structural equality as the evaluator.
+
+use bumpalo::Bump;
+
+use crate::{
+    evaluator::ExecutionErrorKind,
+    parser::ComparisonOp,
+    types::Type,
+    values::{RawValue, dynamic::Value},
+    vm::GenericAdapter,
+};
+
+/// Adapter for structural equality (`left == right` / `left != right`).
+///
+/// Both operands must have the stored type.
+pub struct EqualityAdapter<'t> {
+    value_type: &'t Type<'t>,
+    op: ComparisonOp,
+}
+
+impl<'t> EqualityAdapter<'t> {
+    pub fn new(value_type: &'t Type<'t>, op: ComparisonOp) -> Self {
+        debug_assert!(matches!(op, ComparisonOp::Eq | ComparisonOp::Neq));
+        EqualityAdapter { value_type, op }
+    }
+}
+
+impl<'t> GenericAdapter for EqualityAdapter<'t> {
+    fn num_args(&self) -> usize {
+        2 // left and right
+    }
+
+    fn call(&self, _arena: &Bump, args: &[RawValue]) -> Result<RawValue, ExecutionErrorKind> {
+        let left = Value::from_raw_unchecked(self.value_type, args[0]);
+        let right = Value::from_raw_unchecked(self.value_type, args[1]);
+
+        let result = match self.op {
+            ComparisonOp::Eq => left == right,
+            ComparisonOp::Neq => left != right,
+            _ => unreachable!("EqualityAdapter only handles Eq/Neq"),
+        };
+
+        Ok(RawValue::make_bool(result))
+    }
+
+    fn name(&self) -> alloc::string::String {
+        let op_name = match self.op {
+            ComparisonOp::Eq => "==",
+            ComparisonOp::Neq => "!=",
+            _ => "?",
+        };
+        alloc::format!(
+            "Equality({} {} {})",
+            self.value_type,
+            op_name,
+            self.value_type
+        )
+    }
+}
@@ -2,7 +2,7 @@ use bumpalo::Bump;
 
 use crate::{
     Vec,
-    evaluator::ExecutionErrorKind,
+    evaluator::{ExecutionError, ExecutionErrorKind},
     types::{Type, manager::TypeManager},
     values::{RawValue, dynamic::Value, function::FfiContext},
     vm::GenericAdapter,
@@ -27,16 +27,22 @@ impl<'t> FunctionAdapter<'t> {
     pub fn param_types(&self) -> &[&'t Type<'t>] {
         &self.types
     }
-}
 
-impl<'t> GenericAdapter for FunctionAdapter<'t> {
-    fn num_args(&self) -> usize {
-        // +1 for the function itself (last element in args)
-        self.types.len() + 1
+    /// Get the type manager, for building the context of a call.
+    pub fn type_mgr(&self) -> &'t TypeManager<'t> {
+        self.type_mgr
     }
 
+    /// Call the function with `ctx`.
+    ///
+    /// Errors keep the location set by the function, if any: lambdas point at
+    /// the failing expression in their body.
     #[allow(unsafe_code)]
-    fn call(&self, arena: &Bump, args: &[RawValue]) -> Result<RawValue, ExecutionErrorKind> {
+    pub fn call_in<'a>(
+        &self,
+        ctx: &FfiContext<'t, 'a>,
+        args: &[RawValue],
+    ) -> Result<RawValue, ExecutionError> {
         debug_assert_eq!(args.len(), self.num_args());
 
         // Last element is the function, rest are arguments
@@ -51,16 +57,24 @@ impl<'t> GenericAdapter for FunctionAdapter<'t> {
             .map(|(arg, ty)| Value::from_raw_unchecked(ty, *arg))
             .collect();
 
-        let ctx = FfiContext::new(arena, self.type_mgr);
-
         unsafe {
-            let func_ref = func.as_function_unchecked();
-            func_ref
-                .call_unchecked(&ctx, typed_args.as_slice())
+            func.as_function_unchecked()
+                .call_unchecked(ctx, typed_args.as_slice())
                 .map(|value| value.as_raw())
-                .map_err(|e| e.kind)
         }
     }
+}
+
+impl<'t> GenericAdapter for FunctionAdapter<'t> {
+    fn num_args(&self) -> usize {
+        // +1 for the function itself (last element in args)
+        self.types.len() + 1
+    }
+
+    fn call(&self, arena: &Bump, args: &[RawValue]) -> Result<RawValue, ExecutionErrorKind> {
+        self.call_in(&FfiContext::new(arena, self.type_mgr), args)
+            .map_err(|e| e.kind)
+    }
 
     fn name(&self) -> alloc::string::String {
         if self.types.is_empty() {
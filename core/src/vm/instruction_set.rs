@@ -50,11 +50,15 @@ use crate::parser::ComparisonOp;
 /// - Can be safely transmuted to/from `[u8; 2]`
 #[repr(C, u8)]
 #[derive(Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
 pub enum Instruction {
     // ========================================================================
     // Special (0x00)
     // ========================================================================
-    /// Halt execution
+    /// Halt execution with an internal error
+    ///
+    /// Marks code that type checking makes unreachable, like the failure of
+    /// the last arm's pattern in an exhaustive `match`.
     ///
     /// Having Halt at 0x00 is a safety feature:
     /// - Uninitialized memory (zeros) will halt instead of executing garbage
@@ -131,6 +135,14 @@ pub enum Instruction {
 
     // 0x0D reserved (was StoreUpvalue, removed - captures are immutable)
 
+    /// Load a lambda of the current closure's recursive group
+    /// Operand: u8 index | Stack: [...] -> [..., closure]
+    ///
+    /// Lambdas bound in the same `where` that call each other cannot capture
+    /// each other by value, since they are created one after another. They
+    /// share the group instead, in binding order (see `BindRecursive`).
+    LoadRecursive(u8) = 0x0E,
+
     // 0x0F reserved
 
     // ========================================================================
@@ -158,7 +170,23 @@ pub enum Instruction {
     /// Stack: [..., a: Int, b: Int] -> [..., result: Bool]
     IntCmpOp(ComparisonOp) = 0x14,
 
-    // 0x15-0x1F reserved for future int operations
+    /// Arbitrary-precision integer binary operation (`bigint` feature)
+    ///
+    /// Same operand encoding as IntBinOp, without modulo.
+    ///
+    /// Stack: [..., a: BigInt, b: BigInt] -> [..., result: BigInt(|!)]
+    BigIntBinOp(u8) = 0x15,
+
+    /// Arbitrary-precision integer negation: -a (`bigint` feature)
+    /// Stack: [..., a: BigInt] -> [..., -a: BigInt]
+    NegBigInt = 0x16,
+
+    /// Arbitrary-precision integer comparison operation (`bigint` feature)
+    ///
+    /// Stack: [..., a: BigInt, b: BigInt] -> [..., result: Bool]
+    BigIntCmpOp(ComparisonOp) = 0x17,
+
+    // 0x18-0x1F reserved for future int operations
 
     // ========================================================================
     // Arithmetic - Float (0x20 - 0x2F)
@@ -184,7 +212,28 @@ pub enum Instruction {
     /// Stack: [..., a: Float, b: Float] -> [..., result: Bool]
     FloatCmpOp(ComparisonOp) = 0x22,
 
-    // 0x23-0x2F reserved for future float operations
+    // 0x23-0x27 reserved for future float operations
+
+    // ========================================================================
+    // Arithmetic - Decimal (0x28 - 0x2F)
+    // ========================================================================
+    /// Fixed-point decimal binary operation (`decimal` feature)
+    ///
+    /// Same operand encoding as FloatBinOp.
+    ///
+    /// Stack: [..., a: Decimal, b: Decimal] -> [..., result: Decimal(|!)]
+    DecimalBinOp(u8) = 0x28,
+
+    /// Fixed-point decimal negation: -a (`decimal` feature)
+    /// Stack: [..., a: Decimal] -> [..., -a: Decimal]
+    NegDecimal = 0x29,
+
+    /// Fixed-point decimal comparison operation (`decimal` feature)
+    ///
+    /// Stack: [..., a: Decimal, b: Decimal] -> [..., result: Bool]
+    DecimalCmpOp(ComparisonOp) = 0x2A,
+
+    // 0x2B-0x2F reserved for future decimal operations
 
     // ========================================================================
     // Logical Operations (0x30 - 0x37)
@@ -272,7 +321,15 @@ pub enum Instruction {
     /// The number of upvalues is stored in the FunctionConstant.
     MakeClosure(u8) = 0x50,
 
-    // 0x51-0x5F reserved for function operations
+    /// Complete a recursive group of closures
+    /// Operand: u8 count | Stack: [..., c1, ..., cN] -> [..., c1, ..., cN]
+    ///
+    /// Shares the N closures on top of the stack with each other, so that
+    /// `LoadRecursive` can load them. They must all be lambdas that load
+    /// recursive values, created by `MakeClosure` since the last group.
+    BindRecursive(u8) = 0x51,
+
+    // 0x52-0x5F reserved for function operations
 
     // ========================================================================
     // Array Operations (0x60 - 0x6F)
@@ -301,11 +358,22 @@ pub enum Instruction {
     /// Stack: [..., arr: Array[T], start: Int, end: Int] -> [..., slice: Array[T]!]
     ArraySlice = 0x65,
 
-    /// Append element to array (creates new array)
-    /// Stack: [..., array: Array[T], elem: T] -> [..., new_array: Array[T]]
+    /// Append element to the array under construction (see `ArrayStart`)
+    /// Stack: [..., elem: T] -> [...]
     ArrayAppend = 0x66,
 
-    // 0x67-0x6F reserved for array operations
+    /// Start building an array element by element
+    /// Stack: [...] -> [...]
+    ///
+    /// Appended elements are kept outside the value stack, so generated arrays
+    /// don't need a `max_stack_size` proportional to their length. Builders nest.
+    ArrayStart = 0x67,
+
+    /// Finish the array under construction
+    /// Stack: [...] -> [..., array: Array[T]]
+    ArrayFinish = 0x68,
+
+    // 0x69-0x6F reserved for array operations
 
     // ========================================================================
     // Map Operations (0x70 - 0x7F)
@@ -355,6 +423,12 @@ pub enum Instruction {
     /// Operand: u8 field index | Stack: [..., record] -> [..., value!]
     RecordGet(u8) = 0x81,
 
+    /// Get field by name computed at runtime
+    /// Stack: [..., record, field_names: Array[String], name: String] -> [..., value!]
+    ///
+    /// `field_names` lists the record's fields in storage order.
+    RecordGetDynamic = 0x82,
+
     /// Merge two records
     /// Stack: [..., rec1, rec2] -> [..., merged]
     RecordMerge = 0x83,
@@ -492,10 +566,14 @@ impl fmt::Debug for Instruction {
             // Binary operations - show operator as char
             Self::IntBinOp(op) => write!(f, "IntBinOp({})", *op as char),
             Self::FloatBinOp(op) => write!(f, "FloatBinOp({})", *op as char),
+            Self::BigIntBinOp(op) => write!(f, "BigIntBinOp({})", *op as char),
+            Self::DecimalBinOp(op) => write!(f, "DecimalBinOp({})", *op as char),
 
             // Comparisons - use ComparisonOp's Debug
             Self::IntCmpOp(op) => write!(f, "IntCmpOp({:?})", op),
             Self::FloatCmpOp(op) => write!(f, "FloatCmpOp({:?})", op),
+            Self::BigIntCmpOp(op) => write!(f, "BigIntCmpOp({:?})", op),
+            Self::DecimalCmpOp(op) => write!(f, "DecimalCmpOp({:?})", op),
             Self::StringCmpOp(op) => write!(f, "StringCmpOp({:?})", op),
             Self::BytesCmpOp(op) => write!(f, "BytesCmpOp({:?})", op),
 
@@ -512,8 +590,11 @@ impl fmt::Debug for Instruction {
             Self::LoadLocal(idx) => write!(f, "LoadLocal({})", idx),
             Self::StoreLocal(idx) => write!(f, "StoreLocal({})", idx),
             Self::LoadCapture(idx) => write!(f, "LoadCapture({})", idx),
+            Self::LoadRecursive(idx) => write!(f, "LoadRecursive({})", idx),
             Self::NegInt => write!(f, "NegInt"),
             Self::NegFloat => write!(f, "NegFloat"),
+            Self::NegBigInt => write!(f, "NegBigInt"),
+            Self::NegDecimal => write!(f, "NegDecimal"),
             Self::And => write!(f, "And"),
             Self::Or => write!(f, "Or"),
             Self::Not => write!(f, "Not"),
@@ -524,6 +605,7 @@ impl fmt::Debug for Instruction {
             Self::Return => write!(f, "Return"),
             Self::Call(argc) => write!(f, "Call({})", argc),
             Self::MakeClosure(idx) => write!(f, "MakeClosure({})", idx),
+            Self::BindRecursive(count) => write!(f, "BindRecursive({})", count),
             Self::MakeArray(count) => write!(f, "MakeArray({})", count),
             Self::ArrayLen => write!(f, "ArrayLen"),
             Self::ArrayGet => write!(f, "ArrayGet"),
@@ -531,6 +613,8 @@ impl fmt::Debug for Instruction {
             Self::ArrayConcat => write!(f, "ArrayConcat"),
             Self::ArraySlice => write!(f, "ArraySlice"),
             Self::ArrayAppend => write!(f, "ArrayAppend"),
+            Self::ArrayStart => write!(f, "ArrayStart"),
+            Self::ArrayFinish => write!(f, "ArrayFinish"),
             Self::MakeMap(count) => write!(f, "MakeMap({})", count),
             Self::MapLen => write!(f, "MapLen"),
             Self::MapGet => write!(f, "MapGet"),
@@ -541,6 +625,7 @@ impl fmt::Debug for Instruction {
             Self::MapValues => write!(f, "MapValues"),
             Self::MakeRecord(ty_idx) => write!(f, "MakeRecord({})", ty_idx),
             Self::RecordGet(idx) => write!(f, "RecordGet({})", idx),
+            Self::RecordGetDynamic => write!(f, "RecordGetDynamic"),
             Self::RecordMerge => write!(f, "RecordMerge"),
             Self::StringFormat(argc) => write!(f, "StringFormat({})", argc),
             Self::BytesGet => write!(f, "BytesGet"),
@@ -0,0 +1,123 @@
+//! Map adapter for the VM.
+//!
+//! The `MakeMap` and `MapGet` instructions compare keys as integers, which is
+//! only right for `Int` keys. This adapter stores the map type so keys of any
+//! type are compared as typed `Value`s, giving the VM the same map semantics
+//! as the evaluator.
+
+use bumpalo::Bump;
+
+use crate::{
+    Vec,
+    evaluator::{ExecutionErrorKind, RuntimeError},
+    format,
+    parser::ComparisonOp,
+    types::Type,
+    values::{RawValue, dynamic::Value},
+    vm::GenericAdapter,
+};
+
+/// Operation performed by a [`MapAdapter`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MapOp {
+    /// Build a map from `num_pairs` key-value pairs (`{k1: v1, ...}`).
+    Make { num_pairs: usize },
+    /// Look up a key (`map[key]`).
+    Get,
+    /// Check for a key (`key in map` / `key not in map`).
+    Contains(ComparisonOp),
+}
+
+/// Adapter for map operations on maps of any key type.
+pub struct MapAdapter<'t> {
+    map_type: &'t Type<'t>,
+    op: MapOp,
+}
+
+impl<'t> MapAdapter<'t> {
+    pub fn new(map_type: &'t Type<'t>, op: MapOp) -> Self {
+        debug_assert!(matches!(map_type, Type::Map(_, _)));
+        debug_assert!(!matches!(
+            op,
+            MapOp::Contains(op) if !matches!(op, ComparisonOp::In | ComparisonOp::NotIn)
+        ));
+        MapAdapter { map_type, op }
+    }
+
+    fn key_type(&self) -> &'t Type<'t> {
+        match self.map_type {
+            Type::Map(key_type, _) => key_type,
+            _ => unreachable!("MapAdapter type must be Map"),
+        }
+    }
+
+    fn value_type(&self) -> &'t Type<'t> {
+        match self.map_type {
+            Type::Map(_, value_type) => value_type,
+            _ => unreachable!("MapAdapter type must be Map"),
+        }
+    }
+}
+
+impl<'t> GenericAdapter for MapAdapter<'t> {
+    fn num_args(&self) -> usize {
+        match self.op {
+            MapOp::Make { num_pairs } => num_pairs * 2,
+            // Stack order matches the instructions: map then key for `Get`,
+            // key then map for `Contains`
+            MapOp::Get | MapOp::Contains(_) => 2,
+        }
+    }
+
+    fn call(&self, arena: &Bump, args: &[RawValue]) -> Result<RawValue, ExecutionErrorKind> {
+        match self.op {
+            MapOp::Make { .. } => {
+                let pairs: Vec<_> = args
+                    .chunks_exact(2)
+                    .map(|pair| {
+                        (
+                            Value::from_raw_unchecked(self.key_type(), pair[0]),
+                            Value::from_raw_unchecked(self.value_type(), pair[1]),
+                        )
+                    })
+                    .collect();
+                // `Value::map` keeps the last value for duplicate keys
+                let map = Value::map(arena, self.map_type, &pairs)
+                    .expect("Map pairs have the map's key and value types");
+                Ok(map.as_raw())
+            }
+            MapOp::Get => {
+                let map = Value::from_raw_unchecked(self.map_type, args[0]);
+                let key = Value::from_raw_unchecked(self.key_type(), args[1]);
+                match map.as_map().expect("MapAdapter type is Map").get(&key) {
+                    Some(value) => Ok(value.as_raw()),
+                    None => Err(RuntimeError::KeyNotFound {
+                        key_display: format!("{}", key),
+                    }
+                    .into()),
+                }
+            }
+            MapOp::Contains(op) => {
+                let key = Value::from_raw_unchecked(self.key_type(), args[0]);
+                let map = Value::from_raw_unchecked(self.map_type, args[1]);
+                let found = map
+                    .as_map()
+                    .expect("MapAdapter type is Map")
+                    .get(&key)
+                    .is_some();
+                Ok(RawValue::make_bool(found == (op == ComparisonOp::In)))
+            }
+        }
+    }
+
+    fn name(&self) -> alloc::string::String {
+        match self.op {
+            MapOp::Make { num_pairs } => format!("MakeMap({}, {})", self.map_type, num_pairs),
+            MapOp::Get => format!("MapGet({})", self.map_type),
+            MapOp::Contains(ComparisonOp::NotIn) => {
+                format!("MapContains({} not in {})", self.key_type(), self.map_type)
+            }
+            MapOp::Contains(_) => format!("MapContains({} in {})", self.key_type(), self.map_type),
+        }
+    }
+}
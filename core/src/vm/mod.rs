@@ -1,20 +1,28 @@
 mod array_contains_adapter;
 mod cast_adapter;
 mod code;
+mod equality_adapter;
 mod format_str_adapter;
 mod function_adapter;
 mod generic_adapter;
 mod instruction_set;
+mod map_adapter;
 mod stack;
+mod verify;
 mod vm;
 
 pub use array_contains_adapter::ArrayContainsAdapter;
 pub use cast_adapter::CastAdapter;
 pub use code::{Code, LambdaCode, LambdaKind};
+pub use equality_adapter::EqualityAdapter;
 pub use format_str_adapter::FormatStrAdapter;
 pub use function_adapter::FunctionAdapter;
 pub use generic_adapter::GenericAdapter;
 pub use instruction_set::Instruction;
+pub use map_adapter::{MapAdapter, MapOp};
+pub use verify::VerifyError;
 pub use vm::VM;
 
+#[cfg(test)]
+pub(crate) use stack::INLINE_CAPACITY as STACK_INLINE_CAPACITY;
 pub(crate) use stack::Stack;
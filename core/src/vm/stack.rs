@@ -1,39 +1,62 @@
 #![allow(dead_code)]
-use crate::Vec;
 use alloc::fmt;
+use smallvec::SmallVec;
 
-/// A stack data structure with maximum size enforcement in debug mode.
+use crate::evaluator::{ExecutionErrorKind, ResourceExceededError};
+
+/// Number of elements a [`Stack`] stores inline before spilling to the heap.
+///
+/// Most expressions need only a handful of stack slots, so their VM runs
+/// never allocate a stack buffer.
+pub const INLINE_CAPACITY: usize = 8;
+
+/// A stack data structure with maximum size enforcement.
 ///
-/// This stack is used by the VM for value storage during execution. The maximum
-/// size is only enforced in debug builds to catch stack overflow bugs during
-/// development, while maintaining zero overhead in release builds.
+/// This stack is used by the VM for value storage during execution. The
+/// compiler computes the maximum size each piece of bytecode needs; pushing
+/// beyond it means the bytecode is malformed (e.g., hand-built or corrupted),
+/// so [`Stack::push`] reports a stack overflow instead of growing.
+///
+/// Up to [`INLINE_CAPACITY`] elements are stored inline; deeper stacks move to
+/// a heap buffer.
 pub struct Stack<T> {
     /// The underlying storage for stack elements.
-    items: Vec<T>,
-    /// Maximum allowed stack size (enforced in debug mode only).
+    items: SmallVec<[T; INLINE_CAPACITY]>,
+    /// Maximum allowed stack size.
     max_size: usize,
 }
 
 impl<T> Stack<T> {
     pub fn new(max_size: usize) -> Self {
         // Pre-allocate a reasonable amount (min of max_size or 256)
-        // to avoid frequent reallocations during normal execution
+        // to avoid frequent reallocations during normal execution.
+        // Stacks that fit inline don't allocate at all.
         let initial_capacity = max_size.min(256);
 
         Self {
-            items: Vec::with_capacity(initial_capacity),
+            items: SmallVec::with_capacity(initial_capacity),
             max_size,
         }
     }
 
+    /// Returns true if the elements are stored inline rather than on the heap.
     #[inline]
-    pub fn push(&mut self, value: T) {
-        debug_assert!(
-            self.items.len() < self.max_size,
-            "Stack overflow: attempted to push beyond maximum size of {}",
-            self.max_size
-        );
+    pub fn is_inline(&self) -> bool {
+        !self.items.spilled()
+    }
+
+    /// Push a value, failing with a stack overflow if the stack is full.
+    #[inline]
+    pub fn push(&mut self, value: T) -> Result<(), ExecutionErrorKind> {
+        if self.items.len() >= self.max_size {
+            return Err(ResourceExceededError::StackOverflow {
+                depth: self.items.len() + 1,
+                max_depth: self.max_size,
+            }
+            .into());
+        }
         self.items.push(value);
+        Ok(())
     }
 
     #[inline]
@@ -115,8 +138,7 @@ impl<T: Clone> Stack<T> {
     #[inline]
     pub fn dup(&mut self) -> bool {
         if let Some(value) = self.peek().cloned() {
-            self.push(value);
-            true
+            self.push(value).is_ok()
         } else {
             false
         }
@@ -150,9 +172,9 @@ impl<T> core::ops::Index<usize> for Stack<T> {
     /// use melbi_core::vm::Stack;
     ///
     /// let mut stack = Stack::new(100);
-    /// stack.push(10);
-    /// stack.push(20);
-    /// stack.push(30);
+    /// stack.push(10).unwrap();
+    /// stack.push(20).unwrap();
+    /// stack.push(30).unwrap();
     ///
     /// assert_eq!(stack[0], 30); // Top
     /// assert_eq!(stack[1], 20); // Below top
@@ -186,9 +208,9 @@ impl<T> core::ops::IndexMut<usize> for Stack<T> {
     /// use melbi_core::vm::Stack;
     ///
     /// let mut stack = Stack::new(100);
-    /// stack.push(10);
-    /// stack.push(20);
-    /// stack.push(30);
+    /// stack.push(10).unwrap();
+    /// stack.push(20).unwrap();
+    /// stack.push(30).unwrap();
     ///
     /// stack[0] = 99; // Modify top
     /// assert_eq!(stack[0], 99);
@@ -209,6 +231,7 @@ impl<T> core::ops::IndexMut<usize> for Stack<T> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::Vec;
 
     #[test]
     fn test_new_stack() {
@@ -221,9 +244,9 @@ mod tests {
     #[test]
     fn test_push_pop() {
         let mut stack = Stack::new(100);
-        stack.push(1);
-        stack.push(2);
-        stack.push(3);
+        stack.push(1).unwrap();
+        stack.push(2).unwrap();
+        stack.push(3).unwrap();
 
         assert_eq!(stack.len(), 3);
         assert_eq!(stack.pop(), 3);
@@ -237,11 +260,11 @@ mod tests {
         let mut stack = Stack::new(100);
         assert_eq!(stack.peek(), None);
 
-        stack.push(42);
+        stack.push(42).unwrap();
         assert_eq!(stack.peek(), Some(&42));
         assert_eq!(stack.len(), 1); // Peek doesn't remove
 
-        stack.push(17);
+        stack.push(17).unwrap();
         assert_eq!(stack.peek(), Some(&17));
 
         // Clean up
@@ -251,7 +274,7 @@ mod tests {
     #[test]
     fn test_peek_mut() {
         let mut stack = Stack::new(100);
-        stack.push(42);
+        stack.push(42).unwrap();
 
         if let Some(top) = stack.peek_mut() {
             *top = 100;
@@ -263,9 +286,9 @@ mod tests {
     #[test]
     fn test_peek_at() {
         let mut stack = Stack::new(100);
-        stack.push(10);
-        stack.push(20);
-        stack.push(30);
+        stack.push(10).unwrap();
+        stack.push(20).unwrap();
+        stack.push(30).unwrap();
 
         assert_eq!(stack.peek_at(0), Some(&30));
         assert_eq!(stack.peek_at(1), Some(&20));
@@ -284,7 +307,7 @@ mod tests {
         assert!(!stack.dup());
 
         // Dup with value
-        stack.push(42);
+        stack.push(42).unwrap();
         assert!(stack.dup());
         assert_eq!(stack.len(), 2);
         assert_eq!(stack.pop(), 42);
@@ -294,9 +317,9 @@ mod tests {
     #[test]
     fn test_clear() {
         let mut stack = Stack::new(100);
-        stack.push(1);
-        stack.push(2);
-        stack.push(3);
+        stack.push(1).unwrap();
+        stack.push(2).unwrap();
+        stack.push(3).unwrap();
 
         stack.clear();
         assert_eq!(stack.len(), 0);
@@ -306,9 +329,9 @@ mod tests {
     #[test]
     fn test_iter() {
         let mut stack = Stack::new(100);
-        stack.push(1);
-        stack.push(2);
-        stack.push(3);
+        stack.push(1).unwrap();
+        stack.push(2).unwrap();
+        stack.push(3).unwrap();
 
         let items: Vec<_> = stack.iter().copied().collect();
         assert_eq!(items, vec![1, 2, 3]);
@@ -318,20 +341,51 @@ mod tests {
     }
 
     #[test]
-    #[should_panic(expected = "Stack overflow")]
-    #[cfg(debug_assertions)]
-    fn test_overflow_debug() {
+    fn test_overflow() {
         let mut stack = Stack::new(2);
-        stack.push(1);
-        stack.push(2);
-        stack.push(3); // Should panic in debug mode
+        stack.push(1).unwrap();
+        stack.push(2).unwrap();
+        assert_eq!(
+            stack.push(3),
+            Err(ExecutionErrorKind::ResourceExceeded(
+                ResourceExceededError::StackOverflow {
+                    depth: 3,
+                    max_depth: 2
+                }
+            ))
+        );
+        assert_eq!(stack.len(), 2);
+        assert!(!stack.dup());
+    }
+
+    #[test]
+    fn test_shallow_stack_stays_inline() {
+        let mut stack = Stack::new(INLINE_CAPACITY);
+        assert!(stack.is_inline());
+        for i in 0..INLINE_CAPACITY {
+            stack.push(i).unwrap();
+        }
+        assert!(stack.is_inline());
+        assert_eq!(stack[0], INLINE_CAPACITY - 1);
+    }
+
+    #[test]
+    fn test_deep_stack_spills_to_heap() {
+        let mut stack = Stack::new(100);
+        assert!(!stack.is_inline());
+        for i in 0..20 {
+            stack.push(i).unwrap();
+        }
+        assert_eq!(stack.top_n(3), &[17, 18, 19]);
+        stack.pop_n(19);
+        assert_eq!(stack.pop(), 0);
     }
 
     #[test]
     fn test_large_stack() {
         let mut stack = Stack::new(10000);
         for i in 0..1000 {
-            stack.push(i);
+            stack.push(i).unwrap();
         }
 
         assert_eq!(stack.len(), 1000);
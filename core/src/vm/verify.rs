@@ -0,0 +1,705 @@
+//! Static verification of bytecode.
+//!
+//! The VM trusts its bytecode: operands index directly into the constant
+//! pool, locals and adapters, and jumps move the instruction pointer without
+//! bounds checks. Bytecode from the compiler upholds these invariants, but
+//! hand-built or deserialized bytecode may not, so it must pass
+//! [`Code::verify`] before running.
+//!
+//! Verification is a single forward pass: every jump moves forward, so the
+//! stack depth on entry to an instruction is known once all instructions
+//! before it have been visited. All paths must reach an instruction with the
+//! same stack depth and the same number of arrays under construction.
+//!
+//! Verification checks the shape of the code, not the types of the values it
+//! works on: an `IntBinOp` on floats, or a `RecordGet` on a record without
+//! that field, passes. Only run bytecode whose values are well-typed, such as
+//! the compiler's output. (The VM does check `RecordGet` indices at runtime.)
+
+use alloc::boxed::Box;
+use core::fmt;
+
+use crate::{
+    Vec, vec,
+    vm::{Code, GenericAdapter, Instruction, LambdaKind},
+};
+
+/// Reason bytecode was rejected by [`Code::verify`].
+///
+/// `address` is the index of the offending instruction in `Code::instructions`.
+#[derive(Debug, PartialEq, Eq)]
+pub enum VerifyError {
+    /// `ConstLoad` refers past the end of the constant pool.
+    ConstantOutOfRange {
+        address: usize,
+        index: usize,
+        len: usize,
+    },
+
+    /// `LoadLocal`/`StoreLocal` refers to a slot not counted in `num_locals`.
+    LocalOutOfRange {
+        address: usize,
+        index: usize,
+        num_locals: usize,
+    },
+
+    /// `LoadCapture` refers past the captures of the enclosing lambda.
+    CaptureOutOfRange {
+        address: usize,
+        index: usize,
+        num_captures: usize,
+    },
+
+    /// `LoadRecursive` refers past the recursive group of the enclosing lambda.
+    RecursiveOutOfRange {
+        address: usize,
+        index: usize,
+        num_recursive: usize,
+    },
+
+    /// `Call`/`CallGenericAdapter` refers past the end of its adapter table.
+    AdapterOutOfRange {
+        address: usize,
+        index: usize,
+        len: usize,
+    },
+
+    /// `MakeClosure` refers past the end of `Code::lambdas`.
+    LambdaOutOfRange {
+        address: usize,
+        index: usize,
+        len: usize,
+    },
+
+    /// A jump lands past the last instruction.
+    JumpOutOfRange {
+        address: usize,
+        target: usize,
+        len: usize,
+    },
+
+    /// An instruction operand has no meaning (e.g., `MakeOption(2)`).
+    InvalidOperand { address: usize },
+
+    /// Execution can run past the last instruction without a `Return`.
+    MissingReturn { address: usize },
+
+    /// An instruction pops more values than are on the stack.
+    StackUnderflow { address: usize },
+
+    /// An instruction pushes beyond `max_stack_size`.
+    StackOverflow {
+        address: usize,
+        depth: usize,
+        max_stack_size: usize,
+    },
+
+    /// Two paths reach `target` with different stack depths or arrays under
+    /// construction; `address` is the instruction of the second path.
+    InconsistentJoin { address: usize, target: usize },
+
+    /// `ArrayAppend`/`ArrayFinish` runs with no array under construction, or
+    /// the code returns with one still open.
+    UnbalancedArray { address: usize },
+
+    /// A polymorphic lambda lists an instantiation that is not a monomorphic lambda.
+    InvalidInstantiation { index: usize },
+
+    /// The bytecode of `Code::lambdas[index]` is invalid.
+    InLambda {
+        index: usize,
+        error: Box<VerifyError>,
+    },
+}
+
+impl fmt::Display for VerifyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VerifyError::ConstantOutOfRange {
+                address,
+                index,
+                len,
+            } => write!(
+                f,
+                "instruction {}: constant {} out of range (pool has {})",
+                address, index, len
+            ),
+            VerifyError::LocalOutOfRange {
+                address,
+                index,
+                num_locals,
+            } => write!(
+                f,
+                "instruction {}: local {} out of range (code has {})",
+                address, index, num_locals
+            ),
+            VerifyError::CaptureOutOfRange {
+                address,
+                index,
+                num_captures,
+            } => write!(
+                f,
+                "instruction {}: capture {} out of range (lambda has {})",
+                address, index, num_captures
+            ),
+            VerifyError::RecursiveOutOfRange {
+                address,
+                index,
+                num_recursive,
+            } => write!(
+                f,
+                "instruction {}: recursive lambda {} out of range (group has {})",
+                address, index, num_recursive
+            ),
+            VerifyError::AdapterOutOfRange {
+                address,
+                index,
+                len,
+            } => write!(
+                f,
+                "instruction {}: adapter {} out of range (table has {})",
+                address, index, len
+            ),
+            VerifyError::LambdaOutOfRange {
+                address,
+                index,
+                len,
+            } => write!(
+                f,
+                "instruction {}: lambda {} out of range (code has {})",
+                address, index, len
+            ),
+            VerifyError::JumpOutOfRange {
+                address,
+                target,
+                len,
+            } => write!(
+                f,
+                "instruction {}: jump to {} is past the end ({} instructions)",
+                address, target, len
+            ),
+            VerifyError::InvalidOperand { address } => {
+                write!(f, "instruction {}: invalid operand", address)
+            }
+            VerifyError::MissingReturn { address } => {
+                write!(f, "instruction {}: execution runs past the end", address)
+            }
+            VerifyError::StackUnderflow { address } => {
+                write!(f, "instruction {}: stack underflow", address)
+            }
+            VerifyError::StackOverflow {
+                address,
+                depth,
+                max_stack_size,
+            } => write!(
+                f,
+                "instruction {}: stack depth {} exceeds max_stack_size {}",
+                address, depth, max_stack_size
+            ),
+            VerifyError::InconsistentJoin { address, target } => write!(
+                f,
+                "instruction {}: reaches instruction {} with a different stack",
+                address, target
+            ),
+            VerifyError::UnbalancedArray { address } => {
+                write!(f, "instruction {}: unbalanced array construction", address)
+            }
+            VerifyError::InvalidInstantiation { index } => {
+                write!(f, "lambda {} is not a monomorphic instantiation", index)
+            }
+            VerifyError::InLambda { index, error } => write!(f, "lambda {}: {}", index, error),
+        }
+    }
+}
+
+/// Machine state on entry to an instruction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct State {
+    /// Values on the stack.
+    depth: usize,
+    /// Arrays under construction by `ArrayStart`.
+    arrays: usize,
+}
+
+/// How control leaves an instruction.
+enum Flow {
+    /// Continue with the next instruction.
+    Next,
+    /// Continue with the next instruction, or jump to `target` with the
+    /// stack depth adjusted by `depth_change` relative to the fall-through.
+    Branch { target: usize, depth_change: isize },
+    /// Always jump to `target`.
+    Jump { target: usize },
+    /// Execution ends.
+    Stop,
+}
+
+impl Code<'_> {
+    /// Check that this bytecode is well-formed.
+    ///
+    /// Verifies that constant, local, capture, recursive, adapter and lambda
+    /// operands are in range, that jumps stay within the code, that the stack
+    /// never underflows or grows beyond `max_stack_size`, and that every
+    /// `ArrayStart` is matched by an `ArrayFinish`. Nested lambdas are verified
+    /// too. Top-level code is assumed to run without captures or a recursive
+    /// group. The types of values are not checked (see the module docs).
+    pub fn verify(&self) -> Result<(), VerifyError> {
+        self.verify_with_captures(0, 0)
+    }
+
+    fn verify_with_captures(
+        &self,
+        num_captures: usize,
+        num_recursive: usize,
+    ) -> Result<(), VerifyError> {
+        self.verify_instructions(num_captures, num_recursive)?;
+
+        for (index, lambda) in self.lambdas.iter().enumerate() {
+            let result = match &lambda.kind {
+                LambdaKind::Mono { code } => code.verify_with_captures(
+                    lambda.num_captures as usize,
+                    lambda.num_recursive as usize,
+                ),
+                LambdaKind::Poly { monos } => monos
+                    .iter()
+                    .map(|&mono| mono as usize)
+                    .find(|&mono| {
+                        !matches!(
+                            self.lambdas.get(mono).map(|lambda| &lambda.kind),
+                            Some(LambdaKind::Mono { .. })
+                        )
+                    })
+                    .map_or(Ok(()), |mono| {
+                        Err(VerifyError::InvalidInstantiation { index: mono })
+                    }),
+            };
+            result.map_err(|error| VerifyError::InLambda {
+                index,
+                error: Box::new(error),
+            })?;
+        }
+        Ok(())
+    }
+
+    fn verify_instructions(
+        &self,
+        num_captures: usize,
+        num_recursive: usize,
+    ) -> Result<(), VerifyError> {
+        let len = self.instructions.len();
+        // State on entry to each instruction; `None` while unreachable.
+        let mut states: Vec<Option<State>> = vec![None; len];
+        if len == 0 {
+            return Err(VerifyError::MissingReturn { address: 0 });
+        }
+        states[0] = Some(State {
+            depth: 0,
+            arrays: 0,
+        });
+
+        let mut wide_arg: usize = 0;
+        for address in 0..len {
+            let instruction = self.instructions[address];
+            let operand = |arg: u8| wide_arg | arg as usize;
+            let jump_target = |arg: u8| -> Result<usize, VerifyError> {
+                let target = address + 1 + operand(arg);
+                if target < len {
+                    Ok(target)
+                } else {
+                    Err(VerifyError::JumpOutOfRange {
+                        address,
+                        target,
+                        len,
+                    })
+                }
+            };
+            let check_range = |index: usize, limit: usize, error: VerifyError| {
+                if index < limit { Ok(()) } else { Err(error) }
+            };
+
+            use Instruction::*;
+            let (pops, pushes, flow) = match instruction {
+                WideArg(arg) => {
+                    wide_arg = operand(arg) << 8;
+                    if let Some(state) = states[address] {
+                        merge_state(&mut states, address, address + 1, state)?;
+                    }
+                    continue;
+                }
+
+                ConstLoad(arg) => {
+                    let index = operand(arg);
+                    let len = self.constants.len();
+                    check_range(
+                        index,
+                        len,
+                        VerifyError::ConstantOutOfRange {
+                            address,
+                            index,
+                            len,
+                        },
+                    )?;
+                    (0, 1, Flow::Next)
+                }
+                LoadLocal(arg) | StoreLocal(arg) => {
+                    let index = operand(arg);
+                    check_range(
+                        index,
+                        self.num_locals,
+                        VerifyError::LocalOutOfRange {
+                            address,
+                            index,
+                            num_locals: self.num_locals,
+                        },
+                    )?;
+                    if matches!(instruction, LoadLocal(_)) {
+                        (0, 1, Flow::Next)
+                    } else {
+                        (1, 0, Flow::Next)
+                    }
+                }
+                LoadCapture(arg) => {
+                    let index = operand(arg);
+                    check_range(
+                        index,
+                        num_captures,
+                        VerifyError::CaptureOutOfRange {
+                            address,
+                            index,
+                            num_captures,
+                        },
+                    )?;
+                    (0, 1, Flow::Next)
+                }
+                LoadRecursive(arg) => {
+                    let index = operand(arg);
+                    check_range(
+                        index,
+                        num_recursive,
+                        VerifyError::RecursiveOutOfRange {
+                            address,
+                            index,
+                            num_recursive,
+                        },
+                    )?;
+                    (0, 1, Flow::Next)
+                }
+                Call(arg) => {
+                    let index = operand(arg);
+                    let len = self.adapters.len();
+                    check_range(
+                        index,
+                        len,
+                        VerifyError::AdapterOutOfRange {
+                            address,
+                            index,
+                            len,
+                        },
+                    )?;
+                    (self.adapters[index].num_args(), 1, Flow::Next)
+                }
+                CallGenericAdapter(arg) => {
+                    let index = operand(arg);
+                    let len = self.generic_adapters.len();
+                    check_range(
+                        index,
+                        len,
+                        VerifyError::AdapterOutOfRange {
+                            address,
+                            index,
+                            len,
+                        },
+                    )?;
+                    (self.generic_adapters[index].num_args(), 1, Flow::Next)
+                }
+                MakeClosure(arg) => {
+                    let index = operand(arg);
+                    let len = self.lambdas.len();
+                    check_range(
+                        index,
+                        len,
+                        VerifyError::LambdaOutOfRange {
+                            address,
+                            index,
+                            len,
+                        },
+                    )?;
+                    (self.lambdas[index].num_captures as usize, 1, Flow::Next)
+                }
+                BindRecursive(arg) => (operand(arg), operand(arg), Flow::Next),
+
+                ConstInt(_) | ConstUInt(_) | ConstBool(_) | ArrayFinish => (0, 1, Flow::Next),
+                DupN(depth) => (depth as usize + 1, depth as usize + 2, Flow::Next),
+                Pop | ArrayAppend => (1, 0, Flow::Next),
+                Swap => (2, 2, Flow::Next),
+
+                NegInt | NegFloat | Not | ArrayLen | ArrayGetConst(_) | MapLen | MapKeys
+                | MapValues | RecordGet(_) | BytesGetConst(_) | StringToBytes | BytesToString => {
+                    (1, 1, Flow::Next)
+                }
+                NegBigInt => (1, 1, Flow::Next),
+                NegDecimal => (1, 1, Flow::Next),
+
+                IntBinOp(_) | IntCmpOp(_) | FloatBinOp(_) | FloatCmpOp(_) | And | Or | EqBool
+                | ArrayGet | ArrayConcat | MapGet | MapHas | MapRemove | RecordMerge
+                | StringCmpOp(_) | BytesGet | BytesCmpOp(_) | Eq | NotEq => (2, 1, Flow::Next),
+                BigIntBinOp(_) | BigIntCmpOp(_) => (2, 1, Flow::Next),
+                DecimalBinOp(_) | DecimalCmpOp(_) => (2, 1, Flow::Next),
+
+                ArraySlice | BytesSlice | MapInsert | RecordGetDynamic => (3, 1, Flow::Next),
+
+                MakeArray(arg) | MakeRecord(arg) => (operand(arg), 1, Flow::Next),
+                MakeMap(arg) => (operand(arg) * 2, 1, Flow::Next),
+                StringFormat(arg) => (operand(arg) + 1, 1, Flow::Next),
+                MakeOption(0) => (0, 1, Flow::Next),
+                MakeOption(1) => (1, 1, Flow::Next),
+                MakeOption(_) => return Err(VerifyError::InvalidOperand { address }),
+
+                JumpForward(arg) | PopOtherwiseAndJump(arg) => (
+                    0,
+                    0,
+                    Flow::Jump {
+                        target: jump_target(arg)?,
+                    },
+                ),
+                PushOtherwise(arg) => (
+                    0,
+                    0,
+                    Flow::Branch {
+                        target: jump_target(arg)?,
+                        depth_change: 0,
+                    },
+                ),
+                PopJumpIfFalse(arg) | PopJumpIfTrue(arg) | MatchNoneOrJump(arg) => (
+                    1,
+                    0,
+                    Flow::Branch {
+                        target: jump_target(arg)?,
+                        depth_change: 0,
+                    },
+                ),
+                // The inner value is only pushed when falling through
+                MatchSomeOrJump(arg) => (
+                    1,
+                    1,
+                    Flow::Branch {
+                        target: jump_target(arg)?,
+                        depth_change: -1,
+                    },
+                ),
+
+                Return => (1, 0, Flow::Stop),
+                Halt => (0, 0, Flow::Stop),
+
+                ArrayStart | PopOtherwise | Nop | Breakpoint(_) | CheckLimits | Trace(_)
+                | InlineCache(_) => (0, 0, Flow::Next),
+            };
+            wide_arg = 0;
+
+            // Unreachable instructions only need their operands checked
+            let Some(State { depth, arrays }) = states[address] else {
+                continue;
+            };
+            let depth = depth
+                .checked_sub(pops)
+                .ok_or(VerifyError::StackUnderflow { address })?
+                + pushes;
+            if depth > self.max_stack_size {
+                return Err(VerifyError::StackOverflow {
+                    address,
+                    depth,
+                    max_stack_size: self.max_stack_size,
+                });
+            }
+            let arrays = match instruction {
+                ArrayStart => arrays + 1,
+                ArrayAppend if arrays == 0 => return Err(VerifyError::UnbalancedArray { address }),
+                ArrayFinish => arrays
+                    .checked_sub(1)
+                    .ok_or(VerifyError::UnbalancedArray { address })?,
+                Return if arrays > 0 => {
+                    return Err(VerifyError::UnbalancedArray { address });
+                }
+                _ => arrays,
+            };
+            let state = State { depth, arrays };
+
+            match flow {
+                Flow::Next => merge_state(&mut states, address, address + 1, state)?,
+                Flow::Branch {
+                    target,
+                    depth_change,
+                } => {
+                    merge_state(&mut states, address, address + 1, state)?;
+                    let depth = depth.saturating_add_signed(depth_change);
+                    merge_state(&mut states, address, target, State { depth, ..state })?;
+                }
+                Flow::Jump { target } => merge_state(&mut states, address, target, state)?,
+                Flow::Stop => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Record that `target` is reached from `address` in the given state, which
+/// must match the state of other paths to `target`.
+fn merge_state(
+    states: &mut [Option<State>],
+    address: usize,
+    target: usize,
+    state: State,
+) -> Result<(), VerifyError> {
+    let Some(entry) = states.get_mut(target) else {
+        return Err(VerifyError::MissingReturn { address });
+    };
+    match *entry {
+        None => *entry = Some(state),
+        Some(existing) if existing == state => {}
+        Some(_) => return Err(VerifyError::InconsistentJoin { address, target }),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn code(instructions: Vec<Instruction>, max_stack_size: usize) -> Code<'static> {
+        Code {
+            constants: vec![],
+            adapters: vec![],
+            generic_adapters: vec![],
+            instructions,
+            num_locals: 1,
+            max_stack_size,
+            lambdas: vec![],
+            spans: vec![],
+            record_field_orders: vec![],
+        }
+    }
+
+    #[test]
+    fn test_accepts_branches() {
+        use Instruction::*;
+        // if true then 1 else 2
+        let code = code(
+            vec![
+                ConstBool(1),
+                PopJumpIfFalse(2),
+                ConstInt(1),
+                JumpForward(1),
+                ConstInt(2),
+                Return,
+            ],
+            1,
+        );
+        assert_eq!(code.verify(), Ok(()));
+    }
+
+    #[test]
+    fn test_rejects_bad_constant_index() {
+        use Instruction::*;
+        let code = code(vec![ConstInt(1), WideArg(1), ConstLoad(3), Return], 2);
+        assert_eq!(
+            code.verify(),
+            Err(VerifyError::ConstantOutOfRange {
+                address: 2,
+                index: 259,
+                len: 0
+            })
+        );
+    }
+
+    #[test]
+    fn test_rejects_jump_past_end() {
+        use Instruction::*;
+        let code = code(vec![ConstBool(1), PopJumpIfTrue(5), ConstInt(1), Return], 1);
+        assert_eq!(
+            code.verify(),
+            Err(VerifyError::JumpOutOfRange {
+                address: 1,
+                target: 7,
+                len: 4
+            })
+        );
+    }
+
+    #[test]
+    fn test_rejects_stack_errors() {
+        use Instruction::*;
+        let overflow = code(vec![ConstInt(1), ConstInt(2), IntBinOp(b'+'), Return], 1);
+        assert_eq!(
+            overflow.verify(),
+            Err(VerifyError::StackOverflow {
+                address: 1,
+                depth: 2,
+                max_stack_size: 1
+            })
+        );
+
+        let underflow = code(vec![ConstInt(1), IntBinOp(b'+'), Return], 2);
+        assert_eq!(
+            underflow.verify(),
+            Err(VerifyError::StackUnderflow { address: 1 })
+        );
+
+        // The paths join with one and two values, so `Swap` may underflow
+        let join = code(
+            vec![
+                ConstBool(1),
+                PopJumpIfFalse(1),
+                ConstInt(1),
+                ConstInt(2),
+                Swap,
+                Return,
+            ],
+            2,
+        );
+        assert_eq!(
+            join.verify(),
+            Err(VerifyError::InconsistentJoin {
+                address: 2,
+                target: 3
+            })
+        );
+    }
+
+    #[test]
+    fn test_rejects_unbalanced_arrays() {
+        use Instruction::*;
+        let balanced = code(
+            vec![ArrayStart, ConstInt(1), ArrayAppend, ArrayFinish, Return],
+            1,
+        );
+        assert_eq!(balanced.verify(), Ok(()));
+
+        let append = code(vec![ConstInt(1), ArrayAppend, ConstInt(2), Return], 1);
+        assert_eq!(
+            append.verify(),
+            Err(VerifyError::UnbalancedArray { address: 1 })
+        );
+
+        let finish = code(vec![ArrayFinish, Return], 1);
+        assert_eq!(
+            finish.verify(),
+            Err(VerifyError::UnbalancedArray { address: 0 })
+        );
+
+        let open = code(vec![ArrayStart, ConstInt(1), Return], 1);
+        assert_eq!(
+            open.verify(),
+            Err(VerifyError::UnbalancedArray { address: 2 })
+        );
+    }
+
+    #[test]
+    fn test_rejects_missing_return() {
+        use Instruction::*;
+        let code = code(vec![ConstInt(1), StoreLocal(0)], 1);
+        assert_eq!(
+            code.verify(),
+            Err(VerifyError::MissingReturn { address: 1 })
+        );
+    }
+}
@@ -1,6 +1,6 @@
 #![allow(unsafe_code)]
 
-use core::cmp::Ordering;
+use core::{cell::OnceCell, cmp::Ordering};
 
 use bumpalo::Bump;
 
@@ -8,16 +8,25 @@ use super::instruction_set::Instruction;
 
 use crate::{
     String, Vec,
-    evaluator::{ExecutionError, ExecutionErrorKind, RuntimeError},
+    evaluator::{ExecutionError, ExecutionErrorKind, InternalError, RuntimeError},
     format,
     parser::{ComparisonOp, Span},
-    values::{ArrayData, BytecodeLambda, LambdaInstantiation, MapData, RawValue, RecordData},
+    values::{
+        ArrayData, BytecodeLambda, CallDepth, FfiContext, Fuel, FunctionCallHook,
+        LambdaInstantiation, MapData, RawValue, RecordData,
+    },
     vm::{Code, GenericAdapter, LambdaKind, Stack},
 };
+#[cfg(any(feature = "bigint", feature = "decimal"))]
+use crate::{
+    evaluator::operators,
+    parser::{BinaryOp, UnaryOp},
+};
 
 struct OtherwiseBlock {
     fallback: *const Instruction,
     stack_size: usize,
+    array_builders: usize,
 }
 
 pub struct VM<'a, 'b, 'c> {
@@ -27,8 +36,26 @@ pub struct VM<'a, 'b, 'c> {
     stack: Stack<RawValue>,
     locals: Vec<RawValue>,
     otherwise_stack: Vec<OtherwiseBlock>,
+    /// Arrays under construction by `ArrayStart`/`ArrayAppend`/`ArrayFinish`
+    array_builders: Vec<Vec<RawValue>>,
     /// Captured values for the current closure (empty for top-level code)
     captures: &'a [RawValue],
+    /// Array of the lambdas in the current closure's recursive group (see
+    /// `LoadRecursive`)
+    recursive: Option<RawValue>,
+    /// Recursive group shared by closures made since the last `BindRecursive`
+    pending_recursive: Option<&'a OnceCell<RawValue>>,
+    /// Depth of the call running this code, passed on to called functions
+    call_depth: CallDepth,
+    /// Fuel budget of the run, shared with called functions
+    fuel: Option<&'a Fuel>,
+    /// Hook called before each native function call
+    on_function_call: Option<FunctionCallHook>,
+    /// Whether records remember their source field order, for display
+    preserve_record_field_order: bool,
+    /// Location of an error raised inside a called function, like a lambda
+    /// passed to a native function, which points more precisely than the call
+    callee_error_span: Option<Span>,
 }
 
 impl<'a, 'b, 'c> VM<'a, 'b, 'c> {
@@ -52,16 +79,81 @@ impl<'a, 'b, 'c> VM<'a, 'b, 'c> {
             stack: Stack::new(code.max_stack_size),
             locals,
             otherwise_stack: Vec::new(),
+            array_builders: Vec::new(),
             captures,
+            recursive: None,
+            pending_recursive: None,
+            call_depth: CallDepth::default(),
+            fuel: None,
+            on_function_call: None,
+            preserve_record_field_order: false,
+            callee_error_span: None,
         }
     }
 
+    /// Set the array of lambdas that `LoadRecursive` loads from.
+    pub fn with_recursive(mut self, recursive: Option<RawValue>) -> Self {
+        self.recursive = recursive;
+        self
+    }
+
+    /// Set the depth of the call running this code.
+    pub fn with_call_depth(mut self, call_depth: CallDepth) -> Self {
+        self.call_depth = call_depth;
+        self
+    }
+
+    /// Set the fuel budget that this code and the functions it calls draw from.
+    pub fn with_fuel(mut self, fuel: Option<&'a Fuel>) -> Self {
+        self.fuel = fuel;
+        self
+    }
+
+    /// Set the hook called before each native function call.
+    pub fn with_function_call_hook(mut self, hook: Option<FunctionCallHook>) -> Self {
+        self.on_function_call = hook;
+        self
+    }
+
+    /// Set whether records built by this code and the functions it calls
+    /// remember their source field order, for display.
+    pub fn with_record_field_order(mut self, preserve: bool) -> Self {
+        self.preserve_record_field_order = preserve;
+        self
+    }
+
     pub fn execute(arena: &'a Bump, code: &'b Code<'c>) -> Result<RawValue, ExecutionError> {
         let mut vm = VM::new(arena, code, Vec::new(), &[]);
         vm.run()
     }
 
+    /// Execute top-level code, stopping lambda calls nested deeper than
+    /// `max_depth` and runs that use up `fuel`.
+    pub fn execute_with_limits(
+        arena: &'a Bump,
+        code: &'b Code<'c>,
+        max_depth: usize,
+        fuel: Option<&'a Fuel>,
+    ) -> Result<RawValue, ExecutionError> {
+        let mut vm = VM::new(arena, code, Vec::new(), &[])
+            .with_call_depth(CallDepth {
+                depth: 0,
+                max_depth,
+            })
+            .with_fuel(fuel);
+        vm.run()
+    }
+
     pub fn run(&mut self) -> Result<RawValue, ExecutionError> {
+        // Jumps only go forward, so the code runs each instruction at most once
+        if let Some(fuel) = self.fuel {
+            fuel.consume(self.code.instructions.len() as u64)
+                .map_err(|error| ExecutionError {
+                    kind: error.into(),
+                    source: String::new(),
+                    span: Span(0..0),
+                })?;
+        }
         let result = self.run_control_loop();
         debug_assert!(self.stack.is_empty(), "Stack should be empty.");
         result
@@ -81,15 +173,24 @@ impl<'a, 'b, 'c> VM<'a, 'b, 'c> {
                             tracing::debug!(error = %runtime_error, "Handled by `otherwise` block");
                             self.ip = block.fallback;
                             self.stack.pop_n(self.stack.len() - block.stack_size);
+                            self.array_builders.truncate(block.array_builders);
+                            self.callee_error_span = None;
                             continue;
                         }
                     }
                     self.stack.clear();
-                    return Err(e).map_err(|e| ExecutionError {
+                    // `ip` still points at the failing instruction
+                    let index = unsafe { self.ip.offset_from(self.code.instructions.as_ptr()) };
+                    return Err(ExecutionError {
                         kind: e,
-                        // TODO: Add source and span information.
+                        // The bytecode doesn't keep the source; callers that
+                        // have it fill it in
                         source: String::new(),
-                        span: Span(0..0),
+                        span: self
+                            .callee_error_span
+                            .take()
+                            .or_else(|| self.code.span_at(index as usize))
+                            .unwrap_or(Span(0..0)),
                     });
                 }
                 Ok(()) => {
@@ -109,16 +210,16 @@ impl<'a, 'b, 'c> VM<'a, 'b, 'c> {
             match unsafe { *self.ip } {
                 ConstLoad(arg) => {
                     let index = wide_arg | arg as usize;
-                    self.stack.push(self.code.constants[index]);
+                    self.stack.push(self.code.constants[index])?;
                 }
                 ConstInt(value) => {
-                    self.stack.push(RawValue::make_int(value as i64));
+                    self.stack.push(RawValue::make_int(value as i64))?;
                 }
                 ConstUInt(value) => {
-                    self.stack.push(RawValue::make_int(value as i64));
+                    self.stack.push(RawValue::make_int(value as i64))?;
                 }
                 ConstBool(value) => {
-                    self.stack.push(RawValue::make_bool(value != 0));
+                    self.stack.push(RawValue::make_bool(value != 0))?;
                 }
                 WideArg(arg) => {
                     wide_arg |= arg as usize;
@@ -162,7 +263,7 @@ impl<'a, 'b, 'c> VM<'a, 'b, 'c> {
 
                     self.stack.push(RawValue::make_int(
                         a.as_int_unchecked().div_euclid(b.as_int_unchecked()),
-                    ));
+                    ))?;
                 }
                 IntBinOp(b'%') => {
                     let b = self.stack.pop();
@@ -177,7 +278,7 @@ impl<'a, 'b, 'c> VM<'a, 'b, 'c> {
 
                     self.stack.push(RawValue::make_int(
                         a.as_int_unchecked().rem_euclid(b.as_int_unchecked()),
-                    ));
+                    ))?;
                 }
                 IntBinOp(b'^') => {
                     let b = self.stack.pop().as_int_unchecked();
@@ -189,13 +290,13 @@ impl<'a, 'b, 'c> VM<'a, 'b, 'c> {
                     } else {
                         a.wrapping_pow(b as u32)
                     };
-                    self.stack.push(RawValue::make_int(result));
+                    self.stack.push(RawValue::make_int(result))?;
                 }
 
                 // Integer unary operations
                 NegInt => {
                     let a = self.stack.pop().as_int_unchecked();
-                    self.stack.push(RawValue::make_int(a.wrapping_neg()));
+                    self.stack.push(RawValue::make_int(a.wrapping_neg()))?;
                 }
 
                 // Integer comparisons
@@ -213,7 +314,55 @@ impl<'a, 'b, 'c> VM<'a, 'b, 'c> {
                             panic!("In/NotIn not valid for integers (type checker bug)")
                         }
                     };
-                    self.stack.push(RawValue::make_bool(result));
+                    self.stack.push(RawValue::make_bool(result))?;
+                }
+
+                // Arbitrary-precision integer operations
+                #[cfg(feature = "bigint")]
+                BigIntBinOp(op) => {
+                    let b = self.stack.pop().as_bigint_unchecked();
+                    let a = self.stack.pop().as_bigint_unchecked();
+                    let result = operators::eval_binary_bigint(binary_op_from_byte(op), &a, &b)?;
+                    self.stack
+                        .push(RawValue::make_bigint(self.arena, &result))?;
+                }
+                #[cfg(feature = "bigint")]
+                NegBigInt => {
+                    let a = self.stack.pop().as_bigint_unchecked();
+                    let result = operators::eval_unary_bigint(UnaryOp::Neg, &a);
+                    self.stack
+                        .push(RawValue::make_bigint(self.arena, &result))?;
+                }
+                #[cfg(feature = "bigint")]
+                BigIntCmpOp(op) => {
+                    let b = self.stack.pop().as_bigint_unchecked();
+                    let a = self.stack.pop().as_bigint_unchecked();
+                    let result = operators::eval_comparison_bigint(op, &a, &b);
+                    self.stack.push(RawValue::make_bool(result))?;
+                }
+
+                // Fixed-point decimal operations
+                #[cfg(feature = "decimal")]
+                DecimalBinOp(op) => {
+                    let b = self.stack.pop().as_decimal_unchecked();
+                    let a = self.stack.pop().as_decimal_unchecked();
+                    let result = operators::eval_binary_decimal(binary_op_from_byte(op), a, b)?;
+                    self.stack
+                        .push(RawValue::make_decimal(self.arena, result))?;
+                }
+                #[cfg(feature = "decimal")]
+                NegDecimal => {
+                    let a = self.stack.pop().as_decimal_unchecked();
+                    let result = operators::eval_unary_decimal(UnaryOp::Neg, a)?;
+                    self.stack
+                        .push(RawValue::make_decimal(self.arena, result))?;
+                }
+                #[cfg(feature = "decimal")]
+                DecimalCmpOp(op) => {
+                    let b = self.stack.pop().as_decimal_unchecked();
+                    let a = self.stack.pop().as_decimal_unchecked();
+                    let result = operators::eval_comparison_decimal(op, a, b);
+                    self.stack.push(RawValue::make_bool(result))?;
                 }
 
                 // Float binary operations
@@ -246,13 +395,13 @@ impl<'a, 'b, 'c> VM<'a, 'b, 'c> {
                     let a = self.stack.pop();
                     self.stack.push(RawValue::make_float(
                         a.as_float_unchecked().powf(b.as_float_unchecked()),
-                    ));
+                    ))?;
                 }
 
                 NegFloat => {
                     let a = self.stack.pop();
                     self.stack
-                        .push(RawValue::make_float(-a.as_float_unchecked()));
+                        .push(RawValue::make_float(-a.as_float_unchecked()))?;
                 }
 
                 // Float comparisons
@@ -270,7 +419,7 @@ impl<'a, 'b, 'c> VM<'a, 'b, 'c> {
                             panic!("In/NotIn not valid for floats (type checker bug)")
                         }
                     };
-                    self.stack.push(RawValue::make_bool(result));
+                    self.stack.push(RawValue::make_bool(result))?;
                 }
 
                 BytesGet => {
@@ -283,7 +432,7 @@ impl<'a, 'b, 'c> VM<'a, 'b, 'c> {
                         }
                         .into());
                     };
-                    self.stack.push(RawValue::make_int(bytes[index] as i64));
+                    self.stack.push(RawValue::make_int(bytes[index] as i64))?;
                 }
 
                 BytesGetConst(arg) => {
@@ -296,7 +445,7 @@ impl<'a, 'b, 'c> VM<'a, 'b, 'c> {
                         }
                         .into());
                     }
-                    self.stack.push(RawValue::make_int(bytes[index] as i64));
+                    self.stack.push(RawValue::make_int(bytes[index] as i64))?;
                 }
 
                 BytesCmpOp(op) => {
@@ -326,7 +475,7 @@ impl<'a, 'b, 'c> VM<'a, 'b, 'c> {
                             }
                         }
                     };
-                    self.stack.push(RawValue::make_bool(result));
+                    self.stack.push(RawValue::make_bool(result))?;
                 }
 
                 StringCmpOp(op) => {
@@ -350,7 +499,7 @@ impl<'a, 'b, 'c> VM<'a, 'b, 'c> {
                             }
                         }
                     };
-                    self.stack.push(RawValue::make_bool(result));
+                    self.stack.push(RawValue::make_bool(result))?;
                 }
 
                 // Logical operations
@@ -359,31 +508,32 @@ impl<'a, 'b, 'c> VM<'a, 'b, 'c> {
                     let a = self.stack.pop();
                     self.stack.push(RawValue::make_bool(
                         a.as_bool_unchecked() && b.as_bool_unchecked(),
-                    ));
+                    ))?;
                 }
                 Or => {
                     let b = self.stack.pop();
                     let a = self.stack.pop();
                     self.stack.push(RawValue::make_bool(
                         a.as_bool_unchecked() || b.as_bool_unchecked(),
-                    ));
+                    ))?;
                 }
                 Not => {
                     let a = self.stack.pop();
-                    self.stack.push(RawValue::make_bool(!a.as_bool_unchecked()));
+                    self.stack
+                        .push(RawValue::make_bool(!a.as_bool_unchecked()))?;
                 }
                 EqBool => {
                     let b = self.stack.pop();
                     let a = self.stack.pop();
                     self.stack.push(RawValue::make_bool(
                         a.as_bool_unchecked() == b.as_bool_unchecked(),
-                    ));
+                    ))?;
                 }
 
                 // Stack operations
                 DupN(depth) => {
                     let val = *self.stack.peek_at(depth as usize).unwrap();
-                    self.stack.push(val);
+                    self.stack.push(val)?;
                 }
                 Pop => {
                     self.stack.pop();
@@ -391,14 +541,14 @@ impl<'a, 'b, 'c> VM<'a, 'b, 'c> {
                 Swap => {
                     let b = self.stack.pop();
                     let a = self.stack.pop();
-                    self.stack.push(b);
-                    self.stack.push(a);
+                    self.stack.push(b)?;
+                    self.stack.push(a)?;
                 }
 
                 // Local variables
                 LoadLocal(arg) => {
                     let index = wide_arg | arg as usize;
-                    self.stack.push(self.locals[index]);
+                    self.stack.push(self.locals[index])?;
                 }
                 StoreLocal(arg) => {
                     let index = wide_arg | arg as usize;
@@ -430,7 +580,10 @@ impl<'a, 'b, 'c> VM<'a, 'b, 'c> {
                 }
 
                 Halt => {
-                    return Ok(());
+                    return Err(InternalError::InvariantViolation {
+                        message: String::from("reached Halt"),
+                    }
+                    .into());
                 }
                 Return => {
                     return Ok(());
@@ -446,6 +599,7 @@ impl<'a, 'b, 'c> VM<'a, 'b, 'c> {
                     self.otherwise_stack.push(OtherwiseBlock {
                         fallback: fallback_ip,
                         stack_size: self.stack.len(),
+                        array_builders: self.array_builders.len(),
                     });
                 }
 
@@ -475,7 +629,33 @@ impl<'a, 'b, 'c> VM<'a, 'b, 'c> {
                     let len = wide_arg | arg as usize;
                     let array = ArrayData::new_with(self.arena, self.stack.top_n(len));
                     self.stack.pop_n(len);
-                    self.stack.push(array.as_raw_value());
+                    self.stack.push(array.as_raw_value())?;
+                }
+
+                ArrayStart => {
+                    self.array_builders.push(Vec::new());
+                }
+
+                ArrayAppend => {
+                    let element = self.stack.pop();
+                    let Some(builder) = self.array_builders.last_mut() else {
+                        return Err(InternalError::InvariantViolation {
+                            message: String::from("ArrayAppend without ArrayStart"),
+                        }
+                        .into());
+                    };
+                    builder.push(element);
+                }
+
+                ArrayFinish => {
+                    let Some(elements) = self.array_builders.pop() else {
+                        return Err(InternalError::InvariantViolation {
+                            message: String::from("ArrayFinish without ArrayStart"),
+                        }
+                        .into());
+                    };
+                    let array = ArrayData::new_with(self.arena, &elements);
+                    self.stack.push(array.as_raw_value())?;
                 }
 
                 Call(arg) => {
@@ -485,13 +665,25 @@ impl<'a, 'b, 'c> VM<'a, 'b, 'c> {
                     let num_args = adapter.num_args();
                     let args = self.stack.top_n(num_args);
 
-                    let result = adapter.call(self.arena, args)?;
+                    let ctx = FfiContext::new(self.arena, adapter.type_mgr())
+                        .with_call_depth(self.call_depth)
+                        .with_fuel(self.fuel)
+                        .with_function_call_hook(self.on_function_call.clone())
+                        .with_record_field_order(self.preserve_record_field_order);
+                    let result = adapter.call_in(&ctx, args).map_err(|error| {
+                        // Native functions don't know the source, so their
+                        // errors point at the call
+                        if error.span != Span(0..0) {
+                            self.callee_error_span = Some(error.span);
+                        }
+                        error.kind
+                    })?;
 
                     // Pop arguments from stack after the call
                     self.stack.pop_n(num_args);
 
                     // Push the result
-                    self.stack.push(result);
+                    self.stack.push(result)?;
                 }
 
                 CallGenericAdapter(arg) => {
@@ -507,14 +699,25 @@ impl<'a, 'b, 'c> VM<'a, 'b, 'c> {
                     self.stack.pop_n(num_args);
 
                     // Push the result
-                    self.stack.push(result);
+                    self.stack.push(result)?;
                 }
 
                 // === Closure Operations ===
                 LoadCapture(arg) => {
                     let index = wide_arg | arg as usize;
                     let value = self.captures[index];
-                    self.stack.push(value);
+                    self.stack.push(value)?;
+                }
+
+                LoadRecursive(arg) => {
+                    let index = wide_arg | arg as usize;
+                    let group = ArrayData::from_raw_value(
+                        self.recursive
+                            .expect("LoadRecursive outside a recursive lambda"),
+                    );
+                    // Verified: index is within the lambda's recursive group
+                    let value = unsafe { group.get_unchecked(index) };
+                    self.stack.push(value)?;
                 }
 
                 MakeClosure(arg) => {
@@ -554,12 +757,29 @@ impl<'a, 'b, 'c> VM<'a, 'b, 'c> {
                     };
 
                     // Create BytecodeLambda with all instantiations
-                    let lambda =
+                    let mut lambda =
                         BytecodeLambda::new(lambda_code.lambda_type, instantiations, captures);
+                    if lambda_code.num_recursive > 0 {
+                        let arena = self.arena;
+                        let group = *self
+                            .pending_recursive
+                            .get_or_insert_with(|| arena.alloc(OnceCell::new()));
+                        lambda = lambda.with_recursive(group);
+                    }
                     let raw = RawValue::make_function(self.arena, lambda);
 
                     self.stack.pop_n(num_captures);
-                    self.stack.push(raw);
+                    self.stack.push(raw)?;
+                }
+
+                BindRecursive(arg) => {
+                    let count = wide_arg | arg as usize;
+                    let group = self
+                        .pending_recursive
+                        .take()
+                        .expect("BindRecursive without recursive closures");
+                    let closures = ArrayData::new_with(self.arena, self.stack.top_n(count));
+                    let _ = group.set(closures.as_raw_value());
                 }
 
                 // === Array Operations ===
@@ -578,7 +798,7 @@ impl<'a, 'b, 'c> VM<'a, 'b, 'c> {
                     };
 
                     let element = unsafe { array.get_unchecked(index) };
-                    self.stack.push(element);
+                    self.stack.push(element)?;
                 }
 
                 ArrayGetConst(arg) => {
@@ -597,10 +817,10 @@ impl<'a, 'b, 'c> VM<'a, 'b, 'c> {
                     }
 
                     let element = unsafe { array.get_unchecked(index) };
-                    self.stack.push(element);
+                    self.stack.push(element)?;
                 }
 
-                ArrayLen | ArrayConcat | ArraySlice | ArrayAppend => {
+                ArrayLen | ArrayConcat | ArraySlice => {
                     todo!("Other array operations")
                 }
 
@@ -626,7 +846,7 @@ impl<'a, 'b, 'c> VM<'a, 'b, 'c> {
                     }
 
                     match found {
-                        Some(value) => self.stack.push(value),
+                        Some(value) => self.stack.push(value)?,
                         None => {
                             // Format key for error message (simple int display for now)
                             let key_display = format!("{}", key.as_int_unchecked());
@@ -657,18 +877,32 @@ impl<'a, 'b, 'c> VM<'a, 'b, 'c> {
                         });
                     }
 
-                    // Sort entries by key (integer comparison for now)
+                    // Sort entries by key (integer comparison for now). The sort
+                    // is stable, so duplicate keys stay in source order.
                     // TODO: Proper multi-type key comparison
-                    entries.sort_by(|a, b| a.key.as_int_unchecked().cmp(&b.key.as_int_unchecked()));
+                    entries.sort_by_key(|entry| entry.key.as_int_unchecked());
+
+                    // Duplicate keys keep the last value, matching `Value::map`
+                    let mut deduplicated: Vec<MapEntry> = Vec::with_capacity(entries.len());
+                    for entry in entries {
+                        match deduplicated.last_mut() {
+                            Some(last)
+                                if last.key.as_int_unchecked() == entry.key.as_int_unchecked() =>
+                            {
+                                *last = entry;
+                            }
+                            _ => deduplicated.push(entry),
+                        }
+                    }
 
                     // Create the map
-                    let map = MapData::new_with_sorted(self.arena, &entries);
+                    let map = MapData::new_with_sorted(self.arena, &deduplicated);
 
                     // Pop the 2*N elements
                     self.stack.pop_n(num_values);
 
                     // Push the map result
-                    self.stack.push(map.as_raw_value());
+                    self.stack.push(map.as_raw_value())?;
                 }
 
                 MapHas => {
@@ -676,7 +910,7 @@ impl<'a, 'b, 'c> VM<'a, 'b, 'c> {
                     // For now, just push false (placeholder implementation)
                     let _map = self.stack.pop();
                     let _key = self.stack.pop();
-                    self.stack.push(RawValue::make_bool(false));
+                    self.stack.push(RawValue::make_bool(false))?;
                 }
 
                 MapLen | MapInsert | MapRemove | MapKeys | MapValues => {
@@ -688,11 +922,25 @@ impl<'a, 'b, 'c> VM<'a, 'b, 'c> {
                     // Stack: [..., val0, val1, ..., valN] -> [..., record]
                     let num_fields = wide_arg | arg as usize;
                     // Get the top N elements to create the record
-                    let record = RecordData::new_with(self.arena, self.stack.top_n(num_fields));
+                    let values = self.stack.top_n(num_fields);
+                    let field_order = self
+                        .preserve_record_field_order
+                        .then(|| {
+                            let index =
+                                unsafe { self.ip.offset_from(self.code.instructions.as_ptr()) };
+                            self.code.record_field_order_at(index as usize)
+                        })
+                        .flatten();
+                    let record = match field_order {
+                        Some(field_order) => {
+                            RecordData::new_with_field_order(self.arena, values, field_order)
+                        }
+                        None => RecordData::new_with(self.arena, values),
+                    };
                     // Pop the N elements that were used to create the record
                     self.stack.pop_n(num_fields);
                     // Push the record result
-                    self.stack.push(record.as_raw_value());
+                    self.stack.push(record.as_raw_value())?;
                 }
 
                 RecordGet(arg) => {
@@ -700,10 +948,37 @@ impl<'a, 'b, 'c> VM<'a, 'b, 'c> {
                     let index = wide_arg | arg as usize;
                     let record_raw = self.stack.pop();
                     let record = RecordData::from_raw_value(record_raw);
-                    debug_assert!(index < record.length());
+                    // Verified bytecode may still index past the record's fields
+                    if index >= record.length() {
+                        return Err(InternalError::InvariantViolation {
+                            message: format!("RecordGet({}) past the end of a record", index),
+                        }
+                        .into());
+                    }
 
                     let field_value = unsafe { record.get(index) };
-                    self.stack.push(field_value);
+                    self.stack.push(field_value)?;
+                }
+
+                RecordGetDynamic => {
+                    // Stack: [..., record, field_names, name] -> [..., field_value]
+                    let name = self.stack.pop().as_str_unchecked();
+                    let field_names = ArrayData::from_raw_value(self.stack.pop());
+                    let record = RecordData::from_raw_value(self.stack.pop());
+                    debug_assert!(field_names.length() == record.length());
+
+                    let index = (0..field_names.length()).find(|&i| {
+                        unsafe { field_names.get_unchecked(i) }.as_str_unchecked() == name
+                    });
+                    let Some(index) = index else {
+                        return Err(RuntimeError::KeyNotFound {
+                            key_display: String::from(name),
+                        }
+                        .into());
+                    };
+
+                    let field_value = unsafe { record.get(index) };
+                    self.stack.push(field_value)?;
                 }
 
                 RecordMerge => {
@@ -721,7 +996,7 @@ impl<'a, 'b, 'c> VM<'a, 'b, 'c> {
                         _ => panic!("Invalid MakeOption operand: {}", is_some),
                     };
                     self.stack
-                        .push(RawValue::make_optional(self.arena, option_value));
+                        .push(RawValue::make_optional(self.arena, option_value))?;
                 }
 
                 StringFormat(_) => {
@@ -739,7 +1014,7 @@ impl<'a, 'b, 'c> VM<'a, 'b, 'c> {
                     match option.as_optional_unchecked() {
                         // Some: push inner value and fall through
                         Some(inner) => {
-                            self.stack.push(inner);
+                            self.stack.push(inner)?;
                         }
                         // None: jump forward
                         None => {
@@ -773,6 +1048,19 @@ impl<'a, 'b, 'c> VM<'a, 'b, 'c> {
     }
 }
 
+/// Decode the operand of a `BigIntBinOp`/`DecimalBinOp` instruction.
+#[cfg(any(feature = "bigint", feature = "decimal"))]
+fn binary_op_from_byte(op: u8) -> BinaryOp {
+    match op {
+        b'+' => BinaryOp::Add,
+        b'-' => BinaryOp::Sub,
+        b'*' => BinaryOp::Mul,
+        b'/' => BinaryOp::Div,
+        b'^' => BinaryOp::Pow,
+        _ => panic!("Invalid binary operation operand: {}", op as char),
+    }
+}
+
 /// Calculate the index for an array or bytes value, supporting negative indices,
 /// and checking for out-of-bounds errors.
 fn calculate_index(mut index: i64, len: usize) -> Option<usize> {
@@ -789,6 +1077,7 @@ fn calculate_index(mut index: i64, len: usize) -> Option<usize> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::evaluator::ResourceExceededError;
 
     #[test]
     fn test_works() {
@@ -801,6 +1090,8 @@ mod tests {
             num_locals: 0,
             max_stack_size: 2,
             lambdas: vec![],
+            spans: vec![],
+            record_field_orders: vec![],
         };
         let arena = Bump::new();
         let mut vm = VM::new(&arena, &code, Vec::new(), &[]);
@@ -824,6 +1115,8 @@ mod tests {
             num_locals: 0,
             max_stack_size: 2,
             lambdas: vec![],
+            spans: vec![],
+            record_field_orders: vec![],
         };
         code.constants.resize(257, RawValue::make_int(0));
         code.constants[256] = RawValue::make_int(42);
@@ -850,6 +1143,8 @@ mod tests {
             num_locals: 0,
             max_stack_size: 2,
             lambdas: vec![],
+            spans: vec![],
+            record_field_orders: vec![],
         };
         let arena = Bump::new();
         let mut vm = VM::new(&arena, &code, Vec::new(), &[]);
@@ -869,6 +1164,8 @@ mod tests {
             num_locals: 0,
             max_stack_size: 2,
             lambdas: vec![],
+            spans: vec![],
+            record_field_orders: vec![],
         };
         let mut vm = VM::new(&arena, &code, Vec::new(), &[]);
         assert!(vm.run().unwrap().as_bool_unchecked());
@@ -886,6 +1183,8 @@ mod tests {
             num_locals: 0,
             max_stack_size: 2,
             lambdas: vec![],
+            spans: vec![],
+            record_field_orders: vec![],
         };
         let arena = Bump::new();
         let mut vm = VM::new(&arena, &code, Vec::new(), &[]);
@@ -905,6 +1204,8 @@ mod tests {
             num_locals: 0,
             max_stack_size: 2,
             lambdas: vec![],
+            spans: vec![],
+            record_field_orders: vec![],
         };
         let arena = Bump::new();
         let mut vm = VM::new(&arena, &code, Vec::new(), &[]);
@@ -919,6 +1220,8 @@ mod tests {
             num_locals: 0,
             max_stack_size: 2,
             lambdas: vec![],
+            spans: vec![],
+            record_field_orders: vec![],
         };
         let mut vm = VM::new(&arena, &code, Vec::new(), &[]);
         assert!(vm.run().unwrap().as_bool_unchecked());
@@ -932,6 +1235,8 @@ mod tests {
             num_locals: 0,
             max_stack_size: 1,
             lambdas: vec![],
+            spans: vec![],
+            record_field_orders: vec![],
         };
         let mut vm = VM::new(&arena, &code, Vec::new(), &[]);
         assert!(vm.run().unwrap().as_bool_unchecked());
@@ -950,6 +1255,8 @@ mod tests {
             num_locals: 0,
             max_stack_size: 2,
             lambdas: vec![],
+            spans: vec![],
+            record_field_orders: vec![],
         };
         let arena = Bump::new();
         let mut vm = VM::new(&arena, &code, Vec::new(), &[]);
@@ -964,6 +1271,8 @@ mod tests {
             num_locals: 0,
             max_stack_size: 2,
             lambdas: vec![],
+            spans: vec![],
+            record_field_orders: vec![],
         };
         let mut vm = VM::new(&arena, &code, Vec::new(), &[]);
         assert_eq!(vm.run().unwrap().as_int_unchecked(), -5);
@@ -989,6 +1298,8 @@ mod tests {
             num_locals: 1,
             max_stack_size: 2,
             lambdas: vec![],
+            spans: vec![],
+            record_field_orders: vec![],
         };
         let arena = Bump::new();
         let mut vm = VM::new(&arena, &code, Vec::new(), &[]);
@@ -1016,6 +1327,8 @@ mod tests {
             num_locals: 0,
             max_stack_size: 2,
             lambdas: vec![],
+            spans: vec![],
+            record_field_orders: vec![],
         };
         let arena = Bump::new();
         let mut vm = VM::new(&arena, &code, Vec::new(), &[]);
@@ -1041,6 +1354,8 @@ mod tests {
             num_locals: 0,
             max_stack_size: 2,
             lambdas: vec![],
+            spans: vec![],
+            record_field_orders: vec![],
         };
         let arena = Bump::new();
         let mut vm = VM::new(&arena, &code, Vec::new(), &[]);
@@ -1061,6 +1376,8 @@ mod tests {
             num_locals: 0,
             max_stack_size: 2,
             lambdas: vec![],
+            spans: vec![],
+            record_field_orders: vec![],
         };
         let mut vm = VM::new(&arena, &code, Vec::new(), &[]);
         assert_eq!(vm.run().unwrap().as_int_unchecked(), 42);
@@ -1079,6 +1396,8 @@ mod tests {
             num_locals: 0,
             max_stack_size: 1,
             lambdas: vec![],
+            spans: vec![],
+            record_field_orders: vec![],
         };
         let arena = Bump::new();
         let mut vm = VM::new(&arena, &code, Vec::new(), &[]);
@@ -1101,6 +1420,8 @@ mod tests {
             num_locals: 0,
             max_stack_size: 2,
             lambdas: vec![],
+            spans: vec![],
+            record_field_orders: vec![],
         };
         let arena = Bump::new();
         let mut vm = VM::new(&arena, &code, Vec::new(), &[]);
@@ -1121,6 +1442,8 @@ mod tests {
             num_locals: 0,
             max_stack_size: 2,
             lambdas: vec![],
+            spans: vec![],
+            record_field_orders: vec![],
         };
         let arena = Bump::new();
         let mut vm = VM::new(&arena, &code, Vec::new(), &[]);
@@ -1141,6 +1464,8 @@ mod tests {
             num_locals: 0,
             max_stack_size: 2,
             lambdas: vec![],
+            spans: vec![],
+            record_field_orders: vec![],
         };
         let arena = Bump::new();
         let mut vm = VM::new(&arena, &code, Vec::new(), &[]);
@@ -1161,6 +1486,8 @@ mod tests {
             num_locals: 0,
             max_stack_size: 2,
             lambdas: vec![],
+            spans: vec![],
+            record_field_orders: vec![],
         };
         let arena = Bump::new();
         let mut vm = VM::new(&arena, &code, Vec::new(), &[]);
@@ -1179,6 +1506,8 @@ mod tests {
             num_locals: 0,
             max_stack_size: 2,
             lambdas: vec![],
+            spans: vec![],
+            record_field_orders: vec![],
         };
         let arena = Bump::new();
         let mut vm = VM::new(&arena, &code, Vec::new(), &[]);
@@ -1206,6 +1535,8 @@ mod tests {
             num_locals: 0,
             max_stack_size: 2,
             lambdas: vec![],
+            spans: vec![],
+            record_field_orders: vec![],
         };
         let arena = Bump::new();
         let mut vm = VM::new(&arena, &code, Vec::new(), &[]);
@@ -1235,6 +1566,8 @@ mod tests {
             num_locals: 0,
             max_stack_size: 2,
             lambdas: vec![],
+            spans: vec![],
+            record_field_orders: vec![],
         };
         let arena = Bump::new();
         let mut vm = VM::new(&arena, &code, Vec::new(), &[]);
@@ -1253,6 +1586,8 @@ mod tests {
             num_locals: 0,
             max_stack_size: 2,
             lambdas: vec![],
+            spans: vec![],
+            record_field_orders: vec![],
         };
         let arena = Bump::new();
         let mut vm = VM::new(&arena, &code, Vec::new(), &[]);
@@ -1273,6 +1608,8 @@ mod tests {
             num_locals: 0,
             max_stack_size: 2,
             lambdas: vec![],
+            spans: vec![],
+            record_field_orders: vec![],
         };
         let arena = Bump::new();
         let mut vm = VM::new(&arena, &code, Vec::new(), &[]);
@@ -1292,6 +1629,8 @@ mod tests {
             num_locals: 0,
             max_stack_size: 2,
             lambdas: vec![],
+            spans: vec![],
+            record_field_orders: vec![],
         };
         let arena = Bump::new();
         let mut vm = VM::new(&arena, &code, Vec::new(), &[]);
@@ -1311,6 +1650,8 @@ mod tests {
             num_locals: 0,
             max_stack_size: 2,
             lambdas: vec![],
+            spans: vec![],
+            record_field_orders: vec![],
         };
         let arena = Bump::new();
         let mut vm = VM::new(&arena, &code, Vec::new(), &[]);
@@ -1329,6 +1670,8 @@ mod tests {
             num_locals: 0,
             max_stack_size: 2,
             lambdas: vec![],
+            spans: vec![],
+            record_field_orders: vec![],
         };
         let arena = Bump::new();
         let mut vm = VM::new(&arena, &code, Vec::new(), &[]);
@@ -1356,6 +1699,8 @@ mod tests {
             num_locals: 0,
             max_stack_size: 2,
             lambdas: vec![],
+            spans: vec![],
+            record_field_orders: vec![],
         };
         let arena = Bump::new();
         let mut vm = VM::new(&arena, &code, Vec::new(), &[]);
@@ -1386,6 +1731,8 @@ mod tests {
             num_locals: 0,
             max_stack_size: 2,
             lambdas: vec![],
+            spans: vec![],
+            record_field_orders: vec![],
         };
         let mut vm = VM::new(&arena, &code_div, Vec::new(), &[]);
         let quotient = vm.run().unwrap().as_int_unchecked();
@@ -1399,6 +1746,8 @@ mod tests {
             num_locals: 0,
             max_stack_size: 2,
             lambdas: vec![],
+            spans: vec![],
+            record_field_orders: vec![],
         };
         let mut vm = VM::new(&arena, &code_mod, Vec::new(), &[]);
         let remainder = vm.run().unwrap().as_int_unchecked();
@@ -1408,4 +1757,89 @@ mod tests {
         assert_eq!(quotient, -3);
         assert_eq!(remainder, 2);
     }
+
+    #[test]
+    fn test_push_beyond_max_stack_size_is_stack_overflow() {
+        use Instruction::*;
+        // Malformed bytecode: pushes two values but declares room for one
+        let code = Code {
+            constants: vec![],
+            adapters: vec![],
+            generic_adapters: vec![],
+            instructions: vec![ConstInt(1), ConstInt(2), IntBinOp(b'+'), Return],
+            num_locals: 0,
+            max_stack_size: 1,
+            lambdas: vec![],
+            spans: vec![],
+            record_field_orders: vec![],
+        };
+        let arena = Bump::new();
+        let error = VM::execute(&arena, &code).unwrap_err();
+        assert_eq!(
+            error.kind,
+            ExecutionErrorKind::ResourceExceeded(ResourceExceededError::StackOverflow {
+                depth: 2,
+                max_depth: 1
+            })
+        );
+    }
+
+    #[test]
+    fn test_unbalanced_array_builders_are_internal_errors() {
+        use Instruction::*;
+        for instructions in [
+            vec![ConstInt(1), ArrayAppend, Return],
+            vec![ArrayFinish, Return],
+        ] {
+            let code = Code {
+                constants: vec![],
+                adapters: vec![],
+                generic_adapters: vec![],
+                instructions,
+                num_locals: 0,
+                max_stack_size: 1,
+                lambdas: vec![],
+                spans: vec![],
+                record_field_orders: vec![],
+            };
+            let arena = Bump::new();
+            let error = VM::execute(&arena, &code).unwrap_err();
+            assert!(matches!(
+                error.kind,
+                ExecutionErrorKind::Internal(InternalError::InvariantViolation { .. })
+            ));
+        }
+    }
+
+    #[test]
+    fn test_array_append_bounded_stack() {
+        use Instruction::*;
+        let mut instructions = vec![ArrayStart];
+        for i in 0..1000 {
+            instructions.push(ConstInt((i % 100) as i8));
+            instructions.push(ArrayAppend);
+        }
+        instructions.push(ArrayFinish);
+        instructions.push(Return);
+        let code = Code {
+            constants: vec![],
+            adapters: vec![],
+            generic_adapters: vec![],
+            instructions,
+            num_locals: 0,
+            // Only one element is ever on the stack, unlike `MakeArray(1000)`
+            max_stack_size: 1,
+            lambdas: vec![],
+            spans: vec![],
+            record_field_orders: vec![],
+        };
+        let arena = Bump::new();
+        let mut vm = VM::new(&arena, &code, Vec::new(), &[]);
+        let array = ArrayData::from_raw_value(vm.run().unwrap());
+        assert_eq!(array.length(), 1000);
+        for i in 0..1000 {
+            let element = unsafe { array.get_unchecked(i) };
+            assert_eq!(element.as_int_unchecked(), (i % 100) as i64);
+        }
+    }
 }
@@ -5,9 +5,10 @@
 
 use bumpalo::Bump;
 use melbi_core::api::{
-    CompileOptions, CompileOptionsOverride, Engine, EngineOptions, EnvironmentBuilder,
+    CompileOptions, CompileOptionsOverride, Engine, EngineOptions, EnvironmentBuilder, Error,
+    RunOptions, Severity,
 };
-use melbi_core::evaluator::ExecutionError;
+use melbi_core::evaluator::{ExecutionError, ExecutionErrorKind, RuntimeError};
 use melbi_core::values::binder::{self, Binder};
 use melbi_core::values::dynamic::Value;
 use melbi_core::values::{FfiContext, NativeFunction};
@@ -297,14 +298,15 @@ fn test_complex_expression_with_multiple_operations() {
 
 #[test]
 fn test_engine_options_max_depth() {
-    use melbi_core::api::RunOptions;
+    use melbi_core::api::{RunOptions, RuntimeKind};
 
     let arena = Bump::new();
     let options = EngineOptions {
         default_compile_options: CompileOptions::default(),
         default_run_options: RunOptions {
             max_depth: 5,
-            max_iterations: None, // Unlimited
+            runtime: RuntimeKind::Evaluator,
+            ..RunOptions::default()
         },
     };
     let engine = Engine::new(options, &arena, |arena, type_mgr, env| {
@@ -342,6 +344,790 @@ fn test_engine_options_max_depth() {
         .expect("execution should succeed");
 }
 
+#[test]
+fn test_compile_options_deny_warnings() {
+    let arena = Bump::new();
+    let engine = Engine::new(Default::default(), &arena, |_arena, _type_mgr, env| env);
+    let source = "x * 2 where { x = 21, unused = 0 }";
+
+    // By default, warnings don't prevent compilation
+    let expr = engine
+        .compile(CompileOptionsOverride::default(), source, &[])
+        .expect("warnings should not fail compilation by default");
+    let val_arena = Bump::new();
+    let result = expr.run(Default::default(), &val_arena, &[]).unwrap();
+    assert_eq!(result.as_int().unwrap(), 42);
+
+    // With deny_warnings, the unused binding is a compilation error
+    let deny = CompileOptionsOverride {
+        deny_warnings: Some(true),
+        ..Default::default()
+    };
+    let Err(Error::Compilation { diagnostics, .. }) = engine.compile(deny, source, &[]) else {
+        panic!("expected compilation to fail with denied warnings");
+    };
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].severity, Severity::Warning);
+    assert!(diagnostics[0].message.contains("unused"));
+
+    // Warning-free expressions still compile with deny_warnings
+    assert!(engine.compile(deny, "x * 2 where { x = 21 }", &[]).is_ok());
+}
+
+#[test]
+fn test_compile_options_strict_inference() {
+    use melbi_core::stdlib::register_stdlib;
+
+    let arena = Bump::new();
+    let engine = Engine::new(Default::default(), &arena, register_stdlib);
+
+    // By default, an ambiguous empty array compiles and evaluates
+    let expr = engine
+        .compile(CompileOptionsOverride::default(), "[]", &[])
+        .expect("ambiguous types should compile by default");
+    let val_arena = Bump::new();
+    let result = expr.run(Default::default(), &val_arena, &[]).unwrap();
+    assert_eq!(result.as_array().unwrap().len(), 0);
+
+    // With strict_inference, the ambiguity is a compilation error
+    let strict = CompileOptionsOverride {
+        strict_inference: Some(true),
+        ..Default::default()
+    };
+    for (source, ambiguous) in [
+        ("[]", "[]"),
+        ("if [] == [] then 1 else 2", "[]"),
+        ("{ a = none }", "none"),
+        ("Array.Len([])", "[]"),
+    ] {
+        let Err(Error::Compilation { diagnostics, .. }) = engine.compile(strict, source, &[])
+        else {
+            panic!("expected {:?} to fail under strict inference", source);
+        };
+        assert_eq!(diagnostics[0].severity, Severity::Error);
+        assert_eq!(diagnostics[0].code.as_deref(), Some("E024"));
+        assert_eq!(&source[diagnostics[0].span.0.clone()], ambiguous);
+    }
+
+    // Fully determined expressions, including polymorphic lambdas, still compile
+    for source in [
+        "[] == [1]",
+        "f(1) + f(2) where { f = (x) => x }",
+        "coalesce([none, some 1])",
+        // Packages and their polymorphic functions are resolved by their arguments
+        "Array.Len([1, 2])",
+        "Array.Map([\"a\", \"bc\"], String.Len)",
+        "Array.Map([1, 2], (x) => x * 2)",
+        "[x + 1 for x in [1, 2] if x > 1]",
+        "{x: Math.Sqrt(x) for x in [4.0]}",
+    ] {
+        assert!(
+            engine.compile(strict, source, &[]).is_ok(),
+            "expected {:?} to compile under strict inference",
+            source
+        );
+    }
+}
+
+#[test]
+fn test_used_functions_excludes_local_lambdas() {
+    use melbi_core::stdlib::register_math_package;
+
+    let arena = Bump::new();
+    let engine = Engine::new(Default::default(), &arena, register_math_package);
+
+    // The local lambda `f` and the non-function `Math.PI` are not reported
+    let expr = engine
+        .compile(
+            CompileOptionsOverride::default(),
+            "f(Math.Sqrt(16.0)) + Math.PI where { f = (x) => x * 2.0 }",
+            &[],
+        )
+        .unwrap();
+    assert_eq!(expr.used_functions(), ["Math.Sqrt"]);
+
+    // A local binding shadowing a package hides it
+    let expr = engine
+        .compile(
+            CompileOptionsOverride::default(),
+            "Math.Sqrt(2.0) where { Math = { Sqrt = (x) => x } }",
+            &[],
+        )
+        .unwrap();
+    assert!(expr.used_functions().is_empty());
+
+    // Builtins are global functions too
+    let expr = engine
+        .compile(CompileOptionsOverride::default(), "max(1, 2)", &[])
+        .unwrap();
+    assert_eq!(expr.used_functions(), ["max"]);
+}
+
+#[test]
+fn test_used_functions_includes_packages_used_as_values() {
+    use melbi_core::stdlib::register_math_package;
+
+    let arena = Bump::new();
+    let engine = Engine::new(Default::default(), &arena, register_math_package);
+
+    // Any function of a package used other than through a field may be called
+    for source in [
+        "m.Floor(2.5) where { m = Math }",
+        "(if true then Math else Math).Floor(2.5)",
+        "{ a = Math }.a.Floor(2.5)",
+    ] {
+        let expr = engine
+            .compile(CompileOptionsOverride::default(), source, &[])
+            .unwrap();
+        let used = expr.used_functions();
+        assert!(
+            used.contains(&"Math.Floor") && used.contains(&"Math.Sqrt"),
+            "expected {:?} to use all of Math, got {:?}",
+            source,
+            used
+        );
+        assert!(!used.contains(&"Math.PI"));
+    }
+}
+
+#[test]
+fn test_on_function_call_observes_native_calls() {
+    use melbi_core::api::{RunOptionsOverride, RuntimeKind};
+    use melbi_core::stdlib::register_stdlib;
+    use melbi_core::values::FunctionCallHook;
+    use std::sync::{Arc, Mutex};
+
+    let calls = Arc::new(Mutex::new(Vec::new()));
+    let hook = FunctionCallHook::new({
+        let calls = calls.clone();
+        move |name, argument_count| {
+            calls
+                .lock()
+                .unwrap()
+                .push((name.to_string(), argument_count));
+            Ok(())
+        }
+    });
+
+    let arena = Bump::new();
+    let engine = Engine::new(Default::default(), &arena, register_stdlib);
+    for runtime in [RuntimeKind::Evaluator, RuntimeKind::Vm] {
+        let options = RunOptionsOverride {
+            runtime: Some(runtime),
+            on_function_call: Some(Some(hook.clone())),
+            ..Default::default()
+        };
+        let run = |source| {
+            let expr = engine
+                .compile(CompileOptionsOverride::default(), source, &[])
+                .unwrap();
+            let val_arena = Bump::new();
+            expr.run(options.clone(), &val_arena, &[]).unwrap();
+            core::mem::take(&mut *calls.lock().unwrap())
+        };
+
+        assert_eq!(
+            run("Math.Sqrt(Math.Abs(-4.0))"),
+            [("Math.Abs".to_string(), 1), ("Math.Sqrt".to_string(), 1)],
+            "{:?}",
+            runtime
+        );
+
+        // Calls from lambda bodies are observed, calls to lambdas are not
+        assert_eq!(
+            run("f(2.0) where { f = (x) => Math.Pow(x, 3.0) }"),
+            [("Math.Pow".to_string(), 2)],
+            "{:?}",
+            runtime
+        );
+
+        // Functions are reported by their own names, not the aliases they are called by
+        assert_eq!(
+            run("f(4.0) + g(1.0, 2.0) where { f = Math.Sqrt, g = max }"),
+            [("Math.Sqrt".to_string(), 1), ("max".to_string(), 2)],
+            "{:?}",
+            runtime
+        );
+        assert_eq!(
+            run("m.Sqrt(4.0) where { m = Math }"),
+            [("Math.Sqrt".to_string(), 1)],
+            "{:?}",
+            runtime
+        );
+
+        // Functions called by native functions are observed too
+        assert_eq!(
+            run("Array.Map([1.0, 4.0], Math.Sqrt)"),
+            [
+                ("Array.Map".to_string(), 2),
+                ("Math.Sqrt".to_string(), 1),
+                ("Math.Sqrt".to_string(), 1)
+            ],
+            "{:?}",
+            runtime
+        );
+        assert_eq!(
+            run("Array.Map([-1], abs)"),
+            [("Array.Map".to_string(), 2), ("abs".to_string(), 1)],
+            "{:?}",
+            runtime
+        );
+    }
+}
+
+#[test]
+fn test_on_function_call_can_reject_calls() {
+    use melbi_core::api::{RunOptionsOverride, RuntimeKind};
+    use melbi_core::stdlib::{register_math_package, register_stdlib};
+    use melbi_core::values::FunctionCallHook;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    // Allow two calls per run
+    let calls = Arc::new(AtomicUsize::new(0));
+    let hook = FunctionCallHook::new({
+        let calls = calls.clone();
+        move |_name, _argument_count| {
+            if calls.fetch_add(1, Ordering::Relaxed) < 2 {
+                Ok(())
+            } else {
+                Err("too many calls".to_string())
+            }
+        }
+    });
+
+    let arena = Bump::new();
+    let engine = Engine::new(Default::default(), &arena, register_math_package);
+    let expr = engine
+        .compile(
+            CompileOptionsOverride::default(),
+            "Math.Sqrt(Math.Sqrt(Math.Sqrt(16.0))) otherwise 0.0",
+            &[],
+        )
+        .unwrap();
+    for runtime in [RuntimeKind::Evaluator, RuntimeKind::Vm] {
+        calls.store(0, Ordering::Relaxed);
+        let options = RunOptionsOverride {
+            runtime: Some(runtime),
+            on_function_call: Some(Some(hook.clone())),
+            ..Default::default()
+        };
+        let val_arena = Bump::new();
+        match expr.run(options, &val_arena, &[]) {
+            Err(Error::ResourceExceeded(message)) => {
+                assert_eq!(message, "Call to Math.Sqrt rejected: too many calls")
+            }
+            other => panic!("expected a rejected call on {:?}, got {:?}", runtime, other),
+        }
+    }
+
+    // Calls made by native functions can be rejected too
+    let hook = FunctionCallHook::new(|name, _argument_count| {
+        if name == "Math.Sqrt" {
+            Err("not allowed".to_string())
+        } else {
+            Ok(())
+        }
+    });
+    let engine = Engine::new(Default::default(), &arena, register_stdlib);
+    let expr = engine
+        .compile(
+            CompileOptionsOverride::default(),
+            "Array.Map([4.0], Math.Sqrt) otherwise []",
+            &[],
+        )
+        .unwrap();
+    for runtime in [RuntimeKind::Evaluator, RuntimeKind::Vm] {
+        let options = RunOptionsOverride {
+            runtime: Some(runtime),
+            on_function_call: Some(Some(hook.clone())),
+            ..Default::default()
+        };
+        let val_arena = Bump::new();
+        match expr.run(options, &val_arena, &[]) {
+            Err(Error::ResourceExceeded(message)) => {
+                assert_eq!(message, "Call to Math.Sqrt rejected: not allowed")
+            }
+            other => panic!("expected a rejected call on {:?}, got {:?}", runtime, other),
+        }
+    }
+}
+
+#[test]
+fn test_max_fuel_charges_native_functions_for_their_work() {
+    use melbi_core::api::RunOptionsOverride;
+    use melbi_core::stdlib::register_array_package;
+
+    let arena = Bump::new();
+    let engine = Engine::new(Default::default(), &arena, register_array_package);
+    let type_mgr = engine.type_manager();
+    let array_ty = type_mgr.array(type_mgr.int());
+    let expr = engine
+        .compile(
+            CompileOptionsOverride::default(),
+            "Array.Reverse(xs) otherwise []",
+            &[("xs", array_ty)],
+        )
+        .unwrap();
+    let run = |len: i64, max_fuel: Option<u64>| {
+        let val_arena = Bump::new();
+        let elements: Vec<_> = (0..len).map(|i| Value::int(type_mgr, i)).collect();
+        let xs = Value::array(&val_arena, array_ty, &elements).unwrap();
+        let options = RunOptionsOverride {
+            max_fuel: Some(max_fuel),
+            ..Default::default()
+        };
+        expr.run(options, &val_arena, &[xs])
+            .map(|result| result.as_array().unwrap().len())
+    };
+
+    assert_eq!(run(50, Some(100)).unwrap(), 50);
+    assert_eq!(run(10_000, None).unwrap(), 10_000);
+
+    // The call is a handful of expressions, but reversing costs one unit per
+    // element, and running out of fuel is not caught by `otherwise`
+    let result = run(10_000, Some(100));
+    assert!(
+        matches!(result, Err(Error::ResourceExceeded(_))),
+        "reversing a large array should run out of fuel: {result:?}"
+    );
+}
+
+#[test]
+fn test_max_fuel_charges_sort_n_log_n() {
+    use melbi_core::api::{RunOptionsOverride, RuntimeKind};
+    use melbi_core::stdlib::register_array_package;
+
+    let arena = Bump::new();
+    let engine = Engine::new(Default::default(), &arena, register_array_package);
+    let type_mgr = engine.type_manager();
+    let array_ty = type_mgr.array(type_mgr.int());
+    let expr = engine
+        .compile(
+            CompileOptionsOverride::default(),
+            "Array.Sort(xs)",
+            &[("xs", array_ty)],
+        )
+        .unwrap();
+    let run = |len: i64, max_fuel: u64, runtime: RuntimeKind| {
+        let val_arena = Bump::new();
+        let elements: Vec<_> = (0..len).rev().map(|i| Value::int(type_mgr, i)).collect();
+        let xs = Value::array(&val_arena, array_ty, &elements).unwrap();
+        let options = RunOptionsOverride {
+            max_fuel: Some(Some(max_fuel)),
+            runtime: Some(runtime),
+            ..Default::default()
+        };
+        expr.run(options, &val_arena, &[xs])
+            .map(|result| result.as_array().unwrap().len())
+    };
+
+    // Sorting 1024 elements costs 1024 * 11 units, more than a linear pass
+    for runtime in [RuntimeKind::Evaluator, RuntimeKind::Vm] {
+        assert_eq!(run(1024, 1024 * 11 + 100, runtime).unwrap(), 1024);
+        let result = run(1024, 1024 * 10, runtime);
+        assert!(
+            matches!(result, Err(Error::ResourceExceeded(_))),
+            "sorting should cost n log n fuel: {result:?}"
+        );
+    }
+}
+
+#[test]
+fn test_max_fuel_limits_the_vm() {
+    use melbi_core::api::{RunOptionsOverride, RuntimeKind};
+
+    let arena = Bump::new();
+    let engine = Engine::new(Default::default(), &arena, |_, _, env| env);
+    let expr = engine
+        .compile(
+            CompileOptionsOverride::default(),
+            "f(f(f(1))) where { f = (x) => x * 2 + 1 }",
+            &[],
+        )
+        .unwrap();
+    let run = |max_fuel: u64| {
+        let options = RunOptionsOverride {
+            max_fuel: Some(Some(max_fuel)),
+            runtime: Some(RuntimeKind::Vm),
+            ..Default::default()
+        };
+        expr.run(options, &arena, &[])
+    };
+
+    assert_eq!(run(1000).unwrap().as_int().unwrap(), 15);
+    let result = run(5);
+    assert!(
+        matches!(result, Err(Error::ResourceExceeded(_))),
+        "the VM should run out of fuel: {result:?}"
+    );
+}
+
+#[test]
+#[allow(deprecated)]
+fn test_max_iterations_is_ignored() {
+    use melbi_core::api::{RunOptions, RunOptionsOverride, RuntimeKind};
+
+    let arena = Bump::new();
+    let options = EngineOptions {
+        default_run_options: RunOptions {
+            max_iterations: Some(1),
+            ..RunOptions::default()
+        },
+        ..Default::default()
+    };
+    let engine = Engine::new(options, &arena, |_, _, env| env);
+    let expr = engine
+        .compile(
+            CompileOptionsOverride::default(),
+            "f(f(f(1))) where { f = (x) => x * 2 + 1 }",
+            &[],
+        )
+        .unwrap();
+    for runtime in [RuntimeKind::Evaluator, RuntimeKind::Vm] {
+        let options = RunOptionsOverride {
+            max_iterations: Some(Some(1)),
+            runtime: Some(runtime),
+            ..Default::default()
+        };
+        assert_eq!(
+            expr.run(options, &arena, &[]).unwrap().as_int().unwrap(),
+            15
+        );
+    }
+}
+
+#[test]
+fn test_constant_fold_result() {
+    let arena = Bump::new();
+    let engine = Engine::new(Default::default(), &arena, |_, _, env| env);
+    let int_ty = engine.type_manager().int();
+    let params = [("x", int_ty)];
+    let compile = |source| {
+        engine
+            .compile(CompileOptionsOverride::default(), source, &params)
+            .unwrap()
+    };
+    let fold_int = |source| {
+        compile(source)
+            .constant_fold_result()
+            .unwrap()
+            .unwrap()
+            .as_int()
+            .unwrap()
+    };
+
+    assert_eq!(fold_int("2 + 3 * 4"), 14);
+
+    // Local bindings and matches over constants are still constant
+    assert_eq!(fold_int("x * 2 where { x = 5 }"), 10);
+    assert_eq!(fold_int("some 1 match { some y -> y, none -> 0 }"), 1);
+
+    // Parameters, calls, and lambdas are not, and are not evaluated
+    for source in ["x + 1", "max(1, 2)", "(x) => x + 1"] {
+        assert!(compile(source).constant_fold_result().is_none());
+    }
+
+    // The value is computed once and then returned as is
+    let expr = compile(r#"f"a{n}" where { n = 1 }"#);
+    let (first, second) = (
+        expr.constant_fold_result().unwrap().unwrap(),
+        expr.constant_fold_result().unwrap().unwrap(),
+    );
+    assert_eq!(first.as_str().unwrap(), "a1");
+    assert!(core::ptr::eq(
+        first.as_str().unwrap(),
+        second.as_str().unwrap()
+    ));
+
+    // Constants that fail to evaluate return the error, as running does
+    let expr = compile("1 / 0");
+    let error = expr.constant_fold_result().unwrap().unwrap_err();
+    assert!(
+        matches!(
+            error.kind,
+            ExecutionErrorKind::Runtime(RuntimeError::DivisionByZero {})
+        ),
+        "{error:?}"
+    );
+    let val_arena = Bump::new();
+    let result = expr.run(
+        Default::default(),
+        &val_arena,
+        &[Value::int(engine.type_manager(), 1)],
+    );
+    assert!(matches!(result, Err(Error::Runtime { .. })), "{result:?}");
+
+    // The engine's default run options bound the evaluation
+    let limited = Engine::new(
+        EngineOptions {
+            default_run_options: RunOptions {
+                max_fuel: Some(3),
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+        &arena,
+        |_, _, env| env,
+    );
+    let expr = limited
+        .compile(Default::default(), "a * a * a where { a = 1 + 2 }", &[])
+        .unwrap();
+    let error = expr.constant_fold_result().unwrap().unwrap_err();
+    assert!(
+        matches!(error.kind, ExecutionErrorKind::ResourceExceeded(_)),
+        "{error:?}"
+    );
+}
+
+#[test]
+fn test_compile_options_allowed_functions() {
+    use melbi_core::stdlib::register_math_package;
+
+    let arena = Bump::new();
+    let options = EngineOptions {
+        default_compile_options: CompileOptions {
+            allowed_functions: Some(["Math.Sqrt".to_string()].into_iter().collect()),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let engine = Engine::new(options, &arena, register_math_package);
+
+    // Allowed functions, non-function globals, and local lambdas compile
+    let expr = engine
+        .compile(
+            CompileOptionsOverride::default(),
+            "f(Math.Sqrt(16.0)) * Math.PI where { f = (x) => x + 1.0 }",
+            &[],
+        )
+        .expect("allowed call should compile");
+    let val_arena = Bump::new();
+    let result = expr.run(Default::default(), &val_arena, &[]).unwrap();
+    assert_eq!(result.as_float().unwrap(), 5.0 * std::f64::consts::PI);
+
+    // Each disallowed use gets a diagnostic naming the function
+    let source = "Math.Floor(Math.Sqrt(2.0)) + Math.Ceil(0.5) + max(1, 2)";
+    let Err(Error::Compilation { diagnostics, .. }) =
+        engine.compile(CompileOptionsOverride::default(), source, &[])
+    else {
+        panic!("expected disallowed calls to fail compilation");
+    };
+    let disallowed: Vec<_> = diagnostics
+        .iter()
+        .map(|diagnostic| &source[diagnostic.span.0.clone()])
+        .collect();
+    assert_eq!(disallowed, ["Math.Floor", "Math.Ceil", "max"]);
+    assert_eq!(diagnostics[0].severity, Severity::Error);
+    assert_eq!(diagnostics[0].code.as_deref(), Some("E026"));
+    assert!(diagnostics[0].message.contains("Math.Floor"));
+
+    // Comprehensions only use the functions called in them
+    let expr = engine
+        .compile(
+            CompileOptionsOverride::default(),
+            "[Math.Sqrt(x) for x in [1.0, 4.0] if x > 0.0]",
+            &[],
+        )
+        .expect("comprehension with allowed calls should compile");
+    assert_eq!(expr.used_functions(), ["Math.Sqrt"]);
+    let Err(Error::Compilation { diagnostics, .. }) = engine.compile(
+        CompileOptionsOverride::default(),
+        "{x: Math.Floor(x) for x in [1.5]}",
+        &[],
+    ) else {
+        panic!("expected a disallowed call in a comprehension to fail compilation");
+    };
+    assert_eq!(diagnostics.len(), 1);
+    assert!(diagnostics[0].message.contains("Math.Floor"));
+}
+
+#[test]
+fn test_reusable_value_arena_does_not_grow() {
+    use melbi_core::api::ReusableValueArena;
+
+    let arena = Bump::new();
+    let engine = Engine::new(Default::default(), &arena, |_arena, _type_mgr, env| env);
+    let int_ty = engine.type_manager().int();
+    let expr = engine
+        .compile(
+            Default::default(),
+            r#"{ items = [n, n * 2, n * 3], label = f"run {n}" }"#,
+            &[("n", int_ty)],
+        )
+        .unwrap();
+
+    let mut values = ReusableValueArena::new();
+    let mut warmed_up_bytes = 0;
+    for n in 0..1000 {
+        let run_arena = values.reset();
+        let args = [Value::int(engine.type_manager(), n)];
+        let result = expr.run(Default::default(), run_arena, &args).unwrap();
+
+        let record = result.as_record().unwrap();
+        let items = record.get("items").unwrap().as_array().unwrap();
+        assert_eq!(items.get(2).unwrap().as_int().unwrap(), n * 3);
+        assert_eq!(
+            record.get("label").unwrap().as_str().unwrap(),
+            format!("run {}", n)
+        );
+
+        if n == 10 {
+            warmed_up_bytes = values.allocated_bytes();
+        }
+    }
+    assert_eq!(values.allocated_bytes(), warmed_up_bytes);
+}
+
+#[test]
+fn test_optimize_drops_dead_branches_on_the_vm() {
+    use melbi_core::api::RunOptionsOverride;
+
+    let arena = Bump::new();
+    let engine = Engine::new(Default::default(), &arena, |_arena, _type_mgr, env| env);
+    let int_ty = engine.type_manager().int();
+    let dead_branch = vec!["a"; 50].join(" + ");
+    let source = format!("if true or false then 0 else {}", dead_branch);
+
+    // The VM charges fuel per instruction, so dropping the dead branch makes
+    // the run cheap enough for the budget
+    let options = RunOptionsOverride {
+        max_fuel: Some(Some(20)),
+        ..Default::default()
+    };
+    for (optimize, fits) in [(false, false), (true, true)] {
+        let expr = engine
+            .compile(
+                CompileOptionsOverride {
+                    optimize: Some(optimize),
+                    ..Default::default()
+                },
+                &source,
+                &[("a", int_ty)],
+            )
+            .unwrap();
+        let val_arena = Bump::new();
+        let result = expr.run(
+            options.clone(),
+            &val_arena,
+            &[Value::int(engine.type_manager(), 1)],
+        );
+        match result {
+            Ok(value) => {
+                assert!(fits, "optimize = {}", optimize);
+                assert_eq!(value.as_int().unwrap(), 0);
+            }
+            Err(Error::ResourceExceeded(_)) => assert!(!fits, "optimize = {}", optimize),
+            Err(error) => panic!("unexpected error: {:?}", error),
+        }
+    }
+}
+
+#[test]
+fn test_vm_compiles_bytecode_once() {
+    let arena = Bump::new();
+    let engine = Engine::new(Default::default(), &arena, |_arena, type_mgr, env| {
+        env.bind("x", Value::int(type_mgr, 0))
+    });
+    let type_mgr = engine.type_manager();
+    let expr = engine
+        .compile(
+            CompileOptionsOverride::default(),
+            "[x, y, 1]",
+            &[("x", type_mgr.int()), ("y", type_mgr.int())],
+        )
+        .unwrap();
+
+    // The VM is the default runtime, and arguments shadow globals
+    let run = |x: i64, y: i64| -> Vec<i64> {
+        let val_arena = Bump::new();
+        let args = [Value::int(type_mgr, x), Value::int(type_mgr, y)];
+        let result = expr.run(Default::default(), &val_arena, &args).unwrap();
+        let array = result.as_array().unwrap();
+        array.iter().map(|value| value.as_int().unwrap()).collect()
+    };
+    assert_eq!(run(2, 3), [2, 3, 1]);
+
+    // Later runs reuse the bytecode instead of compiling into the engine's arena
+    let compiled_bytes = arena.allocated_bytes();
+    assert_eq!(run(4, 5), [4, 5, 1]);
+    assert_eq!(arena.allocated_bytes(), compiled_bytes);
+}
+
+#[test]
+fn test_run_options_preserve_record_field_order() {
+    use melbi_core::api::{RunOptionsOverride, RuntimeKind};
+    use melbi_core::stdlib::register_stdlib;
+
+    let arena = Bump::new();
+    let engine = Engine::new(Default::default(), &arena, register_stdlib);
+    let expr = engine
+        .compile(
+            CompileOptionsOverride::default(),
+            "{ zeta = 1, alpha = { y = 2, x = 3 }, mid = 4 }",
+            &[],
+        )
+        .expect("compilation should succeed");
+
+    // Default: fields display sorted by name
+    let val_arena = Bump::new();
+    let sorted = expr
+        .run(Default::default(), &val_arena, &[])
+        .expect("execution should succeed");
+    assert_eq!(
+        format!("{}", sorted),
+        "{alpha = {x = 3, y = 2}, mid = 4, zeta = 1}"
+    );
+
+    // With the flag on: fields display in source order, on either runtime
+    for runtime in [RuntimeKind::Evaluator, RuntimeKind::Vm, RuntimeKind::Both] {
+        let preserve = RunOptionsOverride {
+            preserve_record_field_order: Some(true),
+            runtime: Some(runtime),
+            ..Default::default()
+        };
+        let in_source_order = expr
+            .run(preserve, &val_arena, &[])
+            .expect("execution should succeed");
+        assert_eq!(
+            format!("{}", in_source_order),
+            "{zeta = 1, alpha = {y = 2, x = 3}, mid = 4}",
+            "{runtime:?}"
+        );
+
+        // Storage is still sorted, so field access and equality are unaffected
+        let record = in_source_order.as_record().unwrap();
+        let names: Vec<&str> = record.iter().map(|(name, _)| name).collect();
+        assert_eq!(names, ["alpha", "mid", "zeta"]);
+        assert_eq!(record.get("mid").unwrap().as_int().unwrap(), 4);
+        assert_eq!(in_source_order, sorted);
+    }
+
+    // Records built in lambdas, including ones called by native functions
+    let expr = engine
+        .compile(
+            CompileOptionsOverride::default(),
+            "Array.Map([1], (n) => { b = n, a = f(n) }) where { f = (n) => { d = n, c = n } }",
+            &[],
+        )
+        .expect("compilation should succeed");
+    for runtime in [RuntimeKind::Evaluator, RuntimeKind::Vm] {
+        let preserve = RunOptionsOverride {
+            preserve_record_field_order: Some(true),
+            runtime: Some(runtime),
+            ..Default::default()
+        };
+        let result = expr
+            .run(preserve, &val_arena, &[])
+            .expect("execution should succeed");
+        assert_eq!(
+            format!("{}", result),
+            "[{b = 1, a = {d = 1, c = 1}}]",
+            "{runtime:?}"
+        );
+    }
+}
+
 #[test]
 fn test_error_duplicate_registration() {
     let arena = Bump::new();
@@ -391,3 +1177,434 @@ fn test_access_expression_metadata() {
     let return_type = expr.return_type();
     assert!(core::ptr::eq(return_type, int_ty));
 }
+
+#[test]
+fn test_input_schema_infers_free_variables() {
+    let arena = Bump::new();
+    let engine = Engine::new(Default::default(), &arena, |_arena, _type_mgr, env| env);
+    let options = CompileOptionsOverride {
+        infer_free_variables: Some(true),
+        ..Default::default()
+    };
+    let schema = |source| {
+        let expr = engine.compile(options, source, &[]).unwrap();
+        serde_json::to_string(&expr.input_schema()).unwrap()
+    };
+
+    assert_eq!(
+        schema("user.age + 1"),
+        r#"{"user":{"type":"Record","open":true,"fields":{"age":{"type":"Int"}}}}"#
+    );
+
+    // Bound names are not inputs; unconstrained inputs are `Any`
+    assert_eq!(
+        schema("if flag then [x] else items where { x = 1, unused = other }"),
+        r#"{"flag":{"type":"Bool"},"items":{"type":"Array","element":{"type":"Int"}},"other":{"type":"Any"}}"#
+    );
+
+    // Without the option, free variables are still an error
+    assert!(matches!(
+        engine.compile(Default::default(), "user.age + 1", &[]),
+        Err(Error::Compilation { .. })
+    ));
+}
+
+#[test]
+fn test_inferred_free_variables_are_parameters() {
+    let arena = Bump::new();
+    let engine = Engine::new(Default::default(), &arena, |_arena, _type_mgr, env| env);
+    let type_mgr = engine.type_manager();
+    let options = CompileOptionsOverride {
+        infer_free_variables: Some(true),
+        ..Default::default()
+    };
+
+    // Inferred inputs follow the declared parameters, in order of first use
+    let expr = engine
+        .compile(options, "x * scale + offset", &[("x", type_mgr.int())])
+        .unwrap();
+    let names: Vec<_> = expr.params().iter().map(|(name, _)| *name).collect();
+    assert_eq!(names, ["x", "scale", "offset"]);
+    assert_eq!(
+        serde_json::to_string(&expr.input_schema()).unwrap(),
+        r#"{"offset":{"type":"Int"},"scale":{"type":"Int"},"x":{"type":"Int"}}"#
+    );
+
+    let val_arena = Bump::new();
+    let args = [2, 10, 1].map(|n| Value::int(type_mgr, n));
+    let result = expr.run(Default::default(), &val_arena, &args).unwrap();
+    assert_eq!(result.as_int().unwrap(), 21);
+}
+
+#[test]
+fn test_bytecode_limits_are_compilation_errors() {
+    use melbi_core::api::{RunOptions, RuntimeKind};
+
+    // Jumping over the array takes more than 65535 instructions
+    let source = format!("if b then [{}] else []", vec!["x"; 70_000].join(", "));
+    let compile = |runtime| {
+        let arena = Bump::new();
+        let options = EngineOptions {
+            default_run_options: RunOptions {
+                runtime,
+                ..RunOptions::default()
+            },
+            ..Default::default()
+        };
+        let engine = Engine::new(options, &arena, |_, _, env| env);
+        let type_mgr = engine.type_manager();
+        let params = [("b", type_mgr.bool()), ("x", type_mgr.int())];
+        match engine.compile(Default::default(), &source, &params) {
+            Ok(_) => None,
+            Err(Error::Compilation { diagnostics, .. }) => Some(diagnostics[0].message.clone()),
+            Err(error) => panic!("unexpected error: {error:?}"),
+        }
+    };
+
+    for runtime in [RuntimeKind::Vm, RuntimeKind::Both] {
+        let message = compile(runtime).expect("expected a compilation error");
+        assert!(message.starts_with("Jump distance too large"), "{message}");
+    }
+    // The evaluator needs no bytecode
+    assert_eq!(compile(RuntimeKind::Evaluator), None);
+}
+
+#[test]
+fn test_run_options_runtime_selects_runtime() {
+    use melbi_core::api::{RunOptionsOverride, RuntimeKind};
+
+    let arena = Bump::new();
+    let engine = Engine::new(Default::default(), &arena, |_arena, type_mgr, env| {
+        env.bind("offset", Value::int(type_mgr, 100))
+    });
+
+    let int_ty = engine.type_manager().int();
+    let expr = engine
+        .compile(Default::default(), "x * 2 + offset", &[("x", int_ty)])
+        .expect("compilation should succeed");
+
+    for runtime in [RuntimeKind::Evaluator, RuntimeKind::Vm, RuntimeKind::Both] {
+        let val_arena = Bump::new();
+        let options = RunOptionsOverride {
+            runtime: Some(runtime),
+            ..Default::default()
+        };
+        let result = expr
+            .run(
+                options,
+                &val_arena,
+                &[Value::int(engine.type_manager(), 21)],
+            )
+            .unwrap_or_else(|err| panic!("{runtime:?} failed: {err}"));
+        assert_eq!(result.as_int().unwrap(), 142, "{runtime:?}");
+    }
+}
+
+#[test]
+fn test_recursive_where_lambda_runs_in_both_runtimes() {
+    use melbi_core::api::{RunOptionsOverride, RuntimeKind};
+
+    let arena = Bump::new();
+    let engine = Engine::new(Default::default(), &arena, |_arena, _type_mgr, env| env);
+
+    let int_ty = engine.type_manager().int();
+    let expr = engine
+        .compile(
+            Default::default(),
+            "factorial(n) where { factorial = (n) => if n <= 1 then 1 else n * factorial(n - 1) }",
+            &[("n", int_ty)],
+        )
+        .expect("compilation should succeed");
+
+    let val_arena = Bump::new();
+    let options = RunOptionsOverride {
+        runtime: Some(RuntimeKind::Both),
+        ..Default::default()
+    };
+    let result = expr
+        .run(options, &val_arena, &[Value::int(engine.type_manager(), 5)])
+        .unwrap();
+    assert_eq!(result.as_int().unwrap(), 120);
+
+    // Recursion deeper than the limit stops in both runtimes
+    let options = RunOptionsOverride {
+        runtime: Some(RuntimeKind::Both),
+        max_depth: Some(30),
+        ..Default::default()
+    };
+    let result = expr.run(
+        options,
+        &val_arena,
+        &[Value::int(engine.type_manager(), 1000)],
+    );
+    assert!(
+        matches!(result, Err(Error::ResourceExceeded(_))),
+        "both runtimes should stop with a stack overflow: {result:?}"
+    );
+}
+
+#[test]
+fn test_run_options_max_depth_default_limit_in_both_runtimes() {
+    use melbi_core::api::{RunOptions, RunOptionsOverride, RuntimeKind};
+    use melbi_core::stdlib::register_stdlib;
+
+    // Each nested call takes native stack, so give the thread room for the
+    // 1000 calls allowed by default (debug builds use larger frames)
+    std::thread::Builder::new()
+        .stack_size(64 * 1024 * 1024)
+        .spawn(|| {
+            let arena = Bump::new();
+            let engine = Engine::new(Default::default(), &arena, register_stdlib);
+            let max_depth = RunOptions::default().max_depth as i64;
+
+            let int_ty = engine.type_manager().int();
+            let sources = [
+                "f(n) where { f = (n) => if n == 0 then 0 else f(n - 1) }",
+                // Through a native function calling back into the lambda
+                "f(n) where { f = (n) => if n == 0 then 0 else Array.Map([n - 1], f)[0] }",
+            ];
+            for source in sources {
+                let expr = engine
+                    .compile(Default::default(), source, &[("n", int_ty)])
+                    .expect("compilation should succeed");
+
+                for runtime in [RuntimeKind::Evaluator, RuntimeKind::Vm] {
+                    let val_arena = Bump::new();
+                    let options = RunOptionsOverride {
+                        runtime: Some(runtime),
+                        ..Default::default()
+                    };
+
+                    // `f(n)` nests n + 1 calls
+                    let result = expr
+                        .run(
+                            options.clone(),
+                            &val_arena,
+                            &[Value::int(engine.type_manager(), max_depth - 1)],
+                        )
+                        .unwrap();
+                    assert_eq!(result.as_int().unwrap(), 0);
+
+                    for n in [max_depth, 100_000] {
+                        let result = expr.run(
+                            options.clone(),
+                            &val_arena,
+                            &[Value::int(engine.type_manager(), n)],
+                        );
+                        assert!(
+                            matches!(result, Err(Error::ResourceExceeded(_))),
+                            "{runtime:?} should stop at the default limit: {result:?}"
+                        );
+                    }
+                }
+            }
+        })
+        .unwrap()
+        .join()
+        .unwrap();
+}
+
+#[test]
+fn test_record_argument_with_extra_fields_in_both_runtimes() {
+    use melbi_core::api::{RunOptionsOverride, RuntimeKind};
+
+    let arena = Bump::new();
+    let engine = Engine::new(Default::default(), &arena, |_arena, _type_mgr, env| env);
+    let type_mgr = engine.type_manager();
+
+    let user_ty = type_mgr.record(vec![("age", type_mgr.int()), ("name", type_mgr.str())]);
+    let user_expr = engine
+        .compile(
+            Default::default(),
+            r#"f(user) where { f = (r) => (if true then r else { name = "a" }).name }"#,
+            &[("user", user_ty)],
+        )
+        .expect("compilation should succeed");
+    let nested_expr = engine
+        .compile(
+            Default::default(),
+            "g({ inner = { a = 2, b = 3 }, c = 4 }) \
+             where { g = (r) => (if true then r else { inner = { a = 1 } }).inner.a }",
+            &[],
+        )
+        .expect("compilation should succeed");
+
+    let val_arena = Bump::new();
+    let user = Value::record(
+        &val_arena,
+        user_ty,
+        &[
+            ("age", Value::int(type_mgr, 30)),
+            ("name", Value::str(&val_arena, type_mgr.str(), "bob")),
+        ],
+    )
+    .unwrap();
+
+    // The callee sees only the fields it expects, whichever runtime runs it
+    for runtime in [RuntimeKind::Evaluator, RuntimeKind::Vm, RuntimeKind::Both] {
+        let options = RunOptionsOverride {
+            runtime: Some(runtime),
+            ..Default::default()
+        };
+        let result = user_expr.run(options.clone(), &val_arena, &[user]).unwrap();
+        assert_eq!(result.as_str().unwrap(), "bob");
+        let result = nested_expr.run(options, &val_arena, &[]).unwrap();
+        assert_eq!(result.as_int().unwrap(), 2);
+    }
+}
+
+#[test]
+fn test_run_options_runtime_both_reports_matching_errors() {
+    use melbi_core::api::{RunOptionsOverride, RuntimeKind};
+
+    let arena = Bump::new();
+    let engine = Engine::new(Default::default(), &arena, |_arena, _type_mgr, env| env);
+
+    let int_ty = engine.type_manager().int();
+    let expr = engine
+        .compile(Default::default(), "10 / x", &[("x", int_ty)])
+        .expect("compilation should succeed");
+
+    let val_arena = Bump::new();
+    let options = RunOptionsOverride {
+        runtime: Some(RuntimeKind::Both),
+        ..Default::default()
+    };
+    let result = expr.run(options, &val_arena, &[Value::int(engine.type_manager(), 0)]);
+    assert!(
+        matches!(result, Err(Error::Runtime { .. })),
+        "both runtimes should fail with the same runtime error: {result:?}"
+    );
+}
+
+#[test]
+fn test_run_options_runtime_both_agrees_on_nan_and_functions() {
+    use melbi_core::api::{RunOptionsOverride, RuntimeKind};
+
+    let arena = Bump::new();
+    let engine = Engine::new(Default::default(), &arena, |_arena, _type_mgr, env| env);
+    let options = || RunOptionsOverride {
+        runtime: Some(RuntimeKind::Both),
+        ..Default::default()
+    };
+
+    for (source, expected) in [
+        ("0.0 / 0.0", "NaN"),
+        ("[0.0 / 0.0, 1.0]", "[nan, 1.]"),
+        ("{x = some (0.0 / 0.0)}", "{x = Some(nan)}"),
+    ] {
+        let expr = engine.compile(Default::default(), source, &[]).unwrap();
+        let val_arena = Bump::new();
+        let result = expr.run(options(), &val_arena, &[]);
+        assert_eq!(result.unwrap().to_string(), expected, "{source}");
+    }
+
+    let expr = engine
+        .compile(Default::default(), "(x) => x + 1", &[])
+        .unwrap();
+    let val_arena = Bump::new();
+    let result = expr.run(options(), &val_arena, &[]);
+    assert!(result.is_ok(), "{result:?}");
+}
+
+#[test]
+fn test_typeof_of_polymorphic_values_is_the_same_on_both_runtimes() {
+    use melbi_core::api::{RunOptionsOverride, RuntimeKind};
+
+    let arena = Bump::new();
+    let engine = Engine::new(Default::default(), &arena, |_arena, _type_mgr, env| env);
+    for optimize in [false, true] {
+        let compile_options = CompileOptionsOverride {
+            optimize: Some(optimize),
+            ..Default::default()
+        };
+        let expr = engine
+            .compile(
+                compile_options,
+                "{a = typeof(f), b = typeof(none), c = typeof((x) => x)} where { f = (x) => x }",
+                &[],
+            )
+            .unwrap();
+        let val_arena = Bump::new();
+        let options = RunOptionsOverride {
+            runtime: Some(RuntimeKind::Both),
+            ..Default::default()
+        };
+        let result = expr.run(options, &val_arena, &[]);
+        assert_eq!(
+            result.unwrap().to_string(),
+            r#"{a = "(a) => a", b = "Option[a]", c = "(a) => a"}"#,
+            "optimize: {optimize}"
+        );
+    }
+}
+
+#[test]
+fn test_native_function_errors_point_at_the_call() {
+    use melbi_core::api::{RunOptionsOverride, RuntimeKind};
+    use melbi_core::parser::Span;
+    use melbi_core::stdlib::register_array_package;
+
+    let arena = Bump::new();
+    let engine = Engine::new(Default::default(), &arena, register_array_package);
+    let source = "1 + Array.Len(Array.Range(0, 10, 0))";
+    let expr = engine
+        .compile(CompileOptionsOverride::default(), source, &[])
+        .unwrap();
+
+    let val_arena = Bump::new();
+    let options = RunOptionsOverride {
+        runtime: Some(RuntimeKind::Evaluator),
+        ..Default::default()
+    };
+    match expr.run(options, &val_arena, &[]) {
+        Err(Error::Runtime {
+            diagnostic,
+            source: error_source,
+            ..
+        }) => {
+            assert_eq!(diagnostic.span, Span(14..35));
+            assert_eq!(error_source, source);
+        }
+        result => panic!("expected a runtime error: {result:?}"),
+    }
+}
+
+#[test]
+fn test_errors_inside_callbacks_point_at_the_failing_expression() {
+    use melbi_core::api::{RunOptionsOverride, RuntimeKind};
+    use melbi_core::parser::Span;
+    use melbi_core::stdlib::register_array_package;
+
+    let arena = Bump::new();
+    let engine = Engine::new(Default::default(), &arena, register_array_package);
+
+    for (source, span) in [
+        ("[10 / x for x in [1, 0]]", Span(1..7)),
+        ("{x: 10 / x for x in [1, 0]}", Span(4..10)),
+        ("Array.Map([1, 0], (x) => 10 / x)", Span(25..31)),
+    ] {
+        let expr = engine
+            .compile(CompileOptionsOverride::default(), source, &[])
+            .unwrap();
+
+        for runtime in [RuntimeKind::Evaluator, RuntimeKind::Vm] {
+            let val_arena = Bump::new();
+            let options = RunOptionsOverride {
+                runtime: Some(runtime),
+                ..Default::default()
+            };
+            match expr.run(options, &val_arena, &[]) {
+                Err(Error::Runtime {
+                    diagnostic,
+                    source: error_source,
+                    ..
+                }) => {
+                    assert_eq!(diagnostic.span, span, "{source} on {runtime:?}");
+                    assert_eq!(error_source, source);
+                }
+                result => panic!("expected a runtime error: {result:?}"),
+            }
+        }
+    }
+}
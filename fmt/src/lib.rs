@@ -3,6 +3,7 @@
 // TODO: Also remove miette. Use Melbi's render_error function.
 
 use miette::{Diagnostic, Result, SourceOffset, SourceSpan};
+use std::ops::Range;
 use std::string::FromUtf8Error;
 use thiserror::Error;
 use topiary_core::{FormatterError, Operation, TopiaryQuery};
@@ -26,6 +27,9 @@ pub enum FormatError {
     #[error("idempotency violated")]
     Idempotency,
 
+    #[error("invalid range {start}..{end}")]
+    Range { start: usize, end: usize },
+
     #[error("UTF8 conversion error")]
     UTF8(#[from] FromUtf8Error),
 
@@ -33,6 +37,36 @@ pub enum FormatError {
     Unknown,
 }
 
+/// A formatter bug found by [`format_reporting_idempotency`]: formatting the output a second time
+/// changed it.
+#[derive(Error, Debug, Diagnostic, Clone, PartialEq, Eq)]
+#[error("idempotency violated")]
+#[diagnostic(code(melbi_format::idempotency))]
+pub struct IdempotencyWarning {
+    /// Output of the first formatting pass.
+    #[source_code]
+    pub first_pass: String,
+
+    /// First span of `first_pass` that the second pass changed.
+    #[label("changed by a second formatting pass")]
+    pub span: SourceSpan,
+
+    /// Text the second pass put in place of `span`.
+    pub second_pass_text: String,
+}
+
+/// How [`format_with_options`] treats the newline at the end of its output.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum FinalNewline {
+    /// End with a newline only if the input did.
+    #[default]
+    Preserve,
+    /// Always end with exactly one newline.
+    Always,
+    /// Never end with a newline.
+    Strip,
+}
+
 const QUERY: &str = include_str!("../../topiary-queries/queries/melbi.scm");
 
 /// Format Melbi source code.
@@ -45,6 +79,9 @@ const QUERY: &str = include_str!("../../topiary-queries/queries/melbi.scm");
 /// - `skip_idempotence`: skip check that AST of formatted source is identical to input. This is
 ///   intended for working around current formatter limitations.
 ///
+/// The output ends with a newline only if the input did; see [`format_with_options`] to change
+/// that.
+///
 /// # Examples
 ///
 /// ```
@@ -59,6 +96,74 @@ pub fn format(
     input: &str,
     skip_idempotence: bool,
     tolerate_parsing_errors: bool,
+) -> Result<String> {
+    format_with_options(
+        input,
+        skip_idempotence,
+        tolerate_parsing_errors,
+        FinalNewline::Preserve,
+    )
+}
+
+/// Format Melbi source code, choosing how the output ends.
+///
+/// Takes the same arguments as [`format`], plus `final_newline`: whether the output ends with a
+/// newline; see [`FinalNewline`].
+///
+/// # Examples
+///
+/// ```
+/// # use melbi_fmt::{format_with_options, FinalNewline};
+/// let source = "a   + b where{ a = 1, b = 2}";
+/// assert_eq!(
+///     format_with_options(source, false, false, FinalNewline::Always).unwrap(),
+///     "a + b where { a = 1, b = 2 }\n"
+/// );
+/// ```
+pub fn format_with_options(
+    input: &str,
+    skip_idempotence: bool,
+    tolerate_parsing_errors: bool,
+    final_newline: FinalNewline,
+) -> Result<String> {
+    let output = format_pass(input, skip_idempotence, tolerate_parsing_errors)?;
+    Ok(apply_final_newline(input, output, final_newline))
+}
+
+/// Format Melbi source code, reporting idempotency violations instead of failing on them.
+///
+/// Formats `input`, then formats the result a second time. If the second pass changes the text,
+/// the first-pass output is still returned, together with an [`IdempotencyWarning`] pointing at
+/// the first place where the two passes differ. Other errors are returned as by [`format`].
+///
+/// # Examples
+///
+/// ```
+/// # use melbi_fmt::{format_reporting_idempotency, FinalNewline};
+/// let (output, warning) =
+///     format_reporting_idempotency("1  +  2", false, FinalNewline::Preserve).unwrap();
+/// assert_eq!(output, "1 + 2");
+/// assert!(warning.is_none());
+/// ```
+pub fn format_reporting_idempotency(
+    input: &str,
+    tolerate_parsing_errors: bool,
+    final_newline: FinalNewline,
+) -> Result<(String, Option<IdempotencyWarning>)> {
+    let first_pass = format_pass(input, true, tolerate_parsing_errors)?;
+    let second_pass = format_pass(&first_pass, true, tolerate_parsing_errors)?;
+    let warning = idempotency_warning(&first_pass, &second_pass);
+    Ok((
+        apply_final_newline(input, first_pass, final_newline),
+        warning,
+    ))
+}
+
+/// Run the formatter once, returning its output as is.
+fn format_pass(
+    input: &str,
+    skip_idempotence: bool,
+    tolerate_parsing_errors: bool,
 ) -> Result<String> {
     let mut output = Vec::new();
 
@@ -127,14 +232,323 @@ pub fn format(
         })?;
     }
 
-    let output = String::from_utf8(output).map_err(FormatError::UTF8)?;
+    Ok(String::from_utf8(output).map_err(FormatError::UTF8)?)
+}
+
+fn apply_final_newline(input: &str, output: String, final_newline: FinalNewline) -> String {
+    // Final cleanup of result. By default, if we received an input not ending in a newline, also
+    // return an output without newline. We do not want to force a newline since we e.g., could be
+    // formatting input received from an editor and do not want to insert additional newlines.
+    match final_newline {
+        FinalNewline::Preserve if input.ends_with('\n') => output,
+        FinalNewline::Preserve | FinalNewline::Strip => output.trim_end().into(),
+        FinalNewline::Always => format!("{}\n", output.trim_end()),
+    }
+}
+
+/// Format only the part of `input` covering the byte range `start..end`.
+///
+/// Reformats the smallest expression enclosing the range that can be formatted on its own, and
+/// leaves the text around it byte-identical. Continuation lines of the formatted expression are
+/// indented to match the line it starts on. Since the rest of the input is not formatted, it may
+/// contain syntax errors.
+///
+/// # Examples
+///
+/// ```
+/// # use melbi_fmt::format_range;
+/// let source = "[1+2, 3+4]";
+/// assert_eq!(format_range(source, 1, 4).unwrap(), "[1 + 2, 3+4]");
+/// ```
+pub fn format_range(input: &str, start: usize, end: usize) -> Result<String> {
+    if start > end || !input.is_char_boundary(start) || !input.is_char_boundary(end) {
+        return Err(FormatError::Range { start, end }.into());
+    }
+
+    let mut parser = tree_sitter::Parser::new();
+    parser
+        .set_language(&tree_sitter_melbi::LANGUAGE.into())
+        .map_err(|_| FormatError::Unknown)?;
+    let tree = parser.parse(input, None).ok_or(FormatError::Unknown)?;
+
+    // Not every node is an expression (e.g. a `where` binding), so climb until one formats.
+    let mut node = tree.root_node().named_descendant_for_byte_range(start, end);
+    while let Some(current) = node {
+        let range = current.byte_range();
+        if let Ok(formatted) =
+            format_with_options(&input[range.clone()], false, false, FinalNewline::Strip)
+        {
+            let indent = line_indent(input, range.start);
+            return Ok(format!(
+                "{}{}{}",
+                &input[..range.start],
+                indent_continuation_lines(&formatted, indent),
+                &input[range.end..]
+            ));
+        }
+        node = current.parent();
+    }
+    Err(FormatError::Range { start, end }.into())
+}
+
+/// The leading whitespace of the line containing `offset`.
+fn line_indent(input: &str, offset: usize) -> &str {
+    let line_start = input[..offset].rfind('\n').map_or(0, |index| index + 1);
+    let line = &input[line_start..];
+    &line[..whitespace_len(line)]
+}
+
+fn indent_continuation_lines(text: &str, indent: &str) -> String {
+    text.lines()
+        .enumerate()
+        .map(|(index, line)| {
+            if index == 0 || line.is_empty() {
+                line.to_string()
+            } else {
+                format!("{indent}{line}")
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// A replacement of `range` (byte offsets into the input) with `new_text`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TextEdit {
+    pub range: Range<usize>,
+    pub new_text: String,
+}
+
+/// Format Melbi source code, returning the changes as edits to `input` instead of the whole
+/// formatted text.
+///
+/// Takes the same arguments as [`format_with_options`]. Edits are sorted and non-overlapping; applying them
+/// from last to first turns `input` into the formatted text. Whitespace-only changes produce one
+/// edit per changed whitespace run.
+///
+/// # Examples
+///
+/// ```
+/// # use melbi_fmt::{format_edits, FinalNewline, TextEdit};
+/// let edits = format_edits("a  + b", false, false, FinalNewline::Preserve).unwrap();
+/// assert_eq!(edits, [TextEdit { range: 1..3, new_text: " ".to_string() }]);
+/// ```
+pub fn format_edits(
+    input: &str,
+    skip_idempotence: bool,
+    tolerate_parsing_errors: bool,
+    final_newline: FinalNewline,
+) -> Result<Vec<TextEdit>> {
+    let output = format_with_options(
+        input,
+        skip_idempotence,
+        tolerate_parsing_errors,
+        final_newline,
+    )?;
+    Ok(diff_edits(input, &output))
+}
+
+/// Compute edits turning `input` into `output`.
+///
+/// Aligns the non-whitespace characters of both texts and emits an edit for each whitespace run
+/// that differs. Should the non-whitespace characters diverge, the rest of the input (up to the
+/// common suffix) is replaced in a single edit.
+fn diff_edits(input: &str, output: &str) -> Vec<TextEdit> {
+    let mut edits = Vec::new();
+    let (mut input_pos, mut output_pos) = (0, 0);
+    loop {
+        let input_space_end = input_pos + whitespace_len(&input[input_pos..]);
+        let output_space_end = output_pos + whitespace_len(&output[output_pos..]);
+        if input[input_pos..input_space_end] != output[output_pos..output_space_end] {
+            edits.push(TextEdit {
+                range: input_pos..input_space_end,
+                new_text: output[output_pos..output_space_end].to_string(),
+            });
+        }
+        input_pos = input_space_end;
+        output_pos = output_space_end;
+
+        match (
+            input[input_pos..].chars().next(),
+            output[output_pos..].chars().next(),
+        ) {
+            (None, None) => return edits,
+            (Some(input_char), Some(output_char)) if input_char == output_char => {
+                input_pos += input_char.len_utf8();
+                output_pos += output_char.len_utf8();
+            }
+            _ => {
+                let input_rest = &input[input_pos..];
+                let output_rest = &output[output_pos..];
+                let suffix_len = common_suffix_len(input_rest, output_rest);
+                edits.push(TextEdit {
+                    range: input_pos..input.len() - suffix_len,
+                    new_text: output_rest[..output_rest.len() - suffix_len].to_string(),
+                });
+                return edits;
+            }
+        }
+    }
+}
+
+/// Compare two formatting passes, describing the first difference between them.
+fn idempotency_warning(first_pass: &str, second_pass: &str) -> Option<IdempotencyWarning> {
+    let edit = diff_edits(first_pass, second_pass).into_iter().next()?;
+    Some(IdempotencyWarning {
+        first_pass: first_pass.to_string(),
+        span: (edit.range.start, edit.range.len()).into(),
+        second_pass_text: edit.new_text,
+    })
+}
+
+fn whitespace_len(text: &str) -> usize {
+    text.len() - text.trim_start().len()
+}
+
+fn common_suffix_len(left: &str, right: &str) -> usize {
+    left.chars()
+        .rev()
+        .zip(right.chars().rev())
+        .take_while(|(left_char, right_char)| left_char == right_char)
+        .map(|(left_char, _)| left_char.len_utf8())
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn format_with(input: &str, final_newline: FinalNewline) -> String {
+        format_with_options(input, false, false, final_newline).unwrap()
+    }
+
+    #[test]
+    fn test_final_newline_preserve() {
+        assert_eq!(format_with("1  +  2", FinalNewline::Preserve), "1 + 2");
+        assert_eq!(format_with("1  +  2\n", FinalNewline::Preserve), "1 + 2\n");
+    }
+
+    #[test]
+    fn test_final_newline_always() {
+        assert_eq!(format_with("1  +  2", FinalNewline::Always), "1 + 2\n");
+        assert_eq!(format_with("1  +  2\n", FinalNewline::Always), "1 + 2\n");
+    }
+
+    #[test]
+    fn test_final_newline_strip() {
+        assert_eq!(format_with("1  +  2", FinalNewline::Strip), "1 + 2");
+        assert_eq!(format_with("1  +  2\n", FinalNewline::Strip), "1 + 2");
+    }
+
+    #[test]
+    fn test_format_edits_single_space_fix() {
+        let edits = format_edits("1 +  2", false, false, FinalNewline::Preserve).unwrap();
+        assert_eq!(
+            edits,
+            [TextEdit {
+                range: 3..5,
+                new_text: " ".to_string()
+            }]
+        );
+    }
+
+    #[test]
+    fn test_format_edits_already_formatted() {
+        let source = "a + b where { a = 1, b = 2 }";
+        let edits = format_edits(source, false, false, FinalNewline::Preserve).unwrap();
+        assert!(edits.is_empty());
+    }
+
+    #[test]
+    fn test_diff_edits_insertions_and_deletions() {
+        let edits = diff_edits("f(a,b )", "f(a, b)");
+        assert_eq!(
+            edits,
+            [
+                TextEdit {
+                    range: 4..4,
+                    new_text: " ".to_string()
+                },
+                TextEdit {
+                    range: 5..6,
+                    new_text: String::new()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_diff_edits_diverging_text() {
+        // Only the differing part is replaced, keeping the common suffix
+        let edits = diff_edits("[1, 2,] ++ x", "[1, 2] ++ x");
+        assert_eq!(
+            edits,
+            [TextEdit {
+                range: 5..6,
+                new_text: String::new()
+            }]
+        );
+    }
+
+    #[test]
+    fn test_idempotency_warning_locates_divergence() {
+        // No input is currently known to trip the formatter, so compare two passes directly
+        let first_pass = "f(a, b)\n    + g(c)";
+        let second_pass = "f(a, b)\n        + g(c)";
+        let warning = idempotency_warning(first_pass, second_pass).unwrap();
+        assert_eq!(warning.span, SourceSpan::from((7, 5)));
+        assert_eq!(warning.second_pass_text, "\n        ");
+        assert_eq!(warning.first_pass, first_pass);
+
+        assert_eq!(idempotency_warning(first_pass, first_pass), None);
+    }
+
+    #[test]
+    fn test_format_reporting_idempotency_clean_input() {
+        let (output, warning) = format_reporting_idempotency(
+            "a  + b where{ a = 1, b = 2}\n",
+            false,
+            FinalNewline::Preserve,
+        )
+        .unwrap();
+        assert_eq!(output, "a + b where { a = 1, b = 2 }\n");
+        assert_eq!(warning, None);
+    }
+
+    #[test]
+    fn test_format_range_leaves_rest_untouched() {
+        let source = "f(a  +  b)  +  g(c+d)";
+        let start = source.find("c+d").unwrap();
+        assert_eq!(
+            format_range(source, start, start + 3).unwrap(),
+            "f(a  +  b)  +  g(c + d)"
+        );
+    }
+
+    #[test]
+    fn test_format_range_climbs_to_enclosing_expression() {
+        // A `where` binding is not an expression, so the whole `where` is formatted
+        let source = "[y where {x  =  1},  2]";
+        let start = source.find('x').unwrap();
+        let end = source.find('1').unwrap() + 1;
+        assert_eq!(
+            format_range(source, start, end).unwrap(),
+            "[y where { x = 1 },  2]"
+        );
+    }
+
+    #[test]
+    fn test_format_range_indents_continuation_lines() {
+        assert_eq!(
+            indent_continuation_lines("a\n\n  b", "    "),
+            "a\n\n      b"
+        );
+        assert_eq!(line_indent("x\n    foo(bar)", 10), "    ");
+    }
 
-    // Final cleanup of result. If we received an input not ending in a newline, also return an
-    // output without newline. We do not want to force a newline since we e.g., could be formatting
-    // input received from an editor and do not want to insert additional newlines.
-    if input.ends_with('\n') {
-        Ok(output)
-    } else {
-        Ok(output.trim_end().into())
+    #[test]
+    fn test_format_range_rejects_invalid_range() {
+        assert!(format_range("1 + 2", 3, 2).is_err());
+        assert!(format_range("1 + 2", 0, 10).is_err());
     }
 }
@@ -0,0 +1,245 @@
+//! Results of analyzing one version of a document, kept for editor queries.
+//!
+//! The typed expression lives in an arena that is dropped once analysis
+//! finishes, so everything hover, go-to-definition, and completion need is
+//! copied out of it. Queries then answer from this summary without parsing
+//! or type-checking the document again.
+
+use std::collections::HashSet;
+use std::ops::Range;
+
+use melbi_core::analyzer::{
+    self,
+    typed_expr::{Expr, ExprInner, TypedExpr},
+};
+use melbi_core::parser::{AnnotatedSource, Span};
+
+/// Summary of a type-checked document.
+#[derive(Debug)]
+pub struct Analysis {
+    /// Expressions that have a span, nested like the typed expression
+    root: Option<Node>,
+
+    /// Identifier spans paired with the spans of their bindings
+    definitions: Vec<(Span, Span)>,
+
+    /// Names bound by `where` and lambdas, in the order completion lists them
+    scopes: Vec<Scope>,
+}
+
+#[derive(Debug)]
+struct Node {
+    span: Range<usize>,
+    /// Hover text, or `None` for expressions without hover (literals, operators)
+    hover: Option<String>,
+    children: Vec<Node>,
+}
+
+#[derive(Debug)]
+struct Scope {
+    /// End of the expression that binds the names
+    end: usize,
+    names: Vec<String>,
+    /// How the names are bound, shown next to the completion
+    detail: &'static str,
+}
+
+impl Analysis {
+    pub fn new(typed: &TypedExpr<'_, '_>) -> Self {
+        let mut scopes = Vec::new();
+        collect_scopes(typed.expr, typed.ann, &mut scopes);
+        Self {
+            root: build_node(typed.expr, typed.ann),
+            definitions: analyzer::definitions(typed),
+            scopes,
+        }
+    }
+
+    /// Hover text for the most specific expression at `offset`
+    pub fn hover_at(&self, offset: usize) -> Option<&str> {
+        node_at(self.root.as_ref()?, offset)?.hover.as_deref()
+    }
+
+    /// Span of the binding that the identifier at `offset` refers to
+    pub fn definition_at(&self, offset: usize) -> Option<&Span> {
+        self.definitions
+            .iter()
+            .find(|(identifier, _)| identifier.0.contains(&offset))
+            .map(|(_, binding)| binding)
+    }
+
+    /// Names in scope at `offset`, without duplicates, each with how it is bound
+    pub fn names_in_scope(&self, offset: usize) -> Vec<(&str, &'static str)> {
+        let mut seen = HashSet::new();
+        self.scopes
+            .iter()
+            // A scope ending before the cursor cannot contain it
+            .filter(|scope| scope.end >= offset)
+            .flat_map(|scope| scope.names.iter().map(|name| (name.as_str(), scope.detail)))
+            .filter(|(name, _)| seen.insert(*name))
+            .collect()
+    }
+}
+
+/// Copy `expr` and its descendants that have a span.
+fn build_node<'types, 'arena>(
+    expr: &Expr<'types, 'arena>,
+    ann: &AnnotatedSource<'arena, Expr<'types, 'arena>>,
+) -> Option<Node> {
+    let span = ann.span_of(expr)?;
+
+    // Only show hover for identifiers and calls - not for literals or operators
+    let shows_hover = matches!(
+        &expr.1,
+        ExprInner::Ident(_)
+            | ExprInner::Call { .. }
+            | ExprInner::Field { .. }
+            | ExprInner::Lambda { .. }
+            | ExprInner::Where { .. }
+            | ExprInner::If { .. }
+    );
+    let hover = shows_hover.then(|| format!("```melbi\n{}\n```", expr.0));
+
+    let children = children(expr)
+        .into_iter()
+        .filter_map(|child| build_node(child, ann))
+        .collect();
+    Some(Node {
+        span: span.0,
+        hover,
+        children,
+    })
+}
+
+/// The most specific (smallest) node at `offset`.
+fn node_at(node: &Node, offset: usize) -> Option<&Node> {
+    if !node.span.contains(&offset) {
+        return None;
+    }
+    node.children
+        .iter()
+        .find_map(|child| node_at(child, offset))
+        .or(Some(node))
+}
+
+/// Direct subexpressions of `expr`, `where` bindings before the body.
+fn children<'types, 'arena>(expr: &Expr<'types, 'arena>) -> Vec<&'arena Expr<'types, 'arena>> {
+    match &expr.1 {
+        ExprInner::Binary { left, right, .. }
+        | ExprInner::Boolean { left, right, .. }
+        | ExprInner::Comparison { left, right, .. }
+        | ExprInner::Index {
+            value: left,
+            index: right,
+        }
+        | ExprInner::Otherwise {
+            primary: left,
+            fallback: right,
+        }
+        | ExprInner::Coalesce {
+            option: left,
+            default: right,
+        } => vec![*left, *right],
+        ExprInner::Unary { expr: inner, .. }
+        | ExprInner::Cast { expr: inner }
+        | ExprInner::Field { value: inner, .. }
+        | ExprInner::Lambda { body: inner, .. }
+        | ExprInner::Option { inner: Some(inner) } => vec![*inner],
+        ExprInner::Call { callable, args } => std::iter::once(*callable)
+            .chain(args.iter().copied())
+            .collect(),
+        ExprInner::If {
+            cond,
+            then_branch,
+            else_branch,
+        } => vec![*cond, *then_branch, *else_branch],
+        ExprInner::Where {
+            expr: inner,
+            bindings,
+        } => bindings
+            .iter()
+            .map(|(_, value)| *value)
+            .chain(std::iter::once(*inner))
+            .collect(),
+        ExprInner::Match { expr: inner, arms } => std::iter::once(*inner)
+            .chain(arms.iter().map(|arm| arm.body))
+            .collect(),
+        ExprInner::Record { fields } => fields.iter().map(|(_, value)| *value).collect(),
+        ExprInner::Map { elements } => elements
+            .iter()
+            .flat_map(|(key, value)| [*key, *value])
+            .collect(),
+        ExprInner::Array { elements } => elements.to_vec(),
+        ExprInner::FormatStr { exprs, .. } => exprs.to_vec(),
+        ExprInner::Constant(_) | ExprInner::Ident(_) | ExprInner::Option { inner: None } => {
+            Vec::new()
+        }
+    }
+}
+
+/// Collect the names bound by `where` bindings and lambdas around expressions
+/// that completion can reach from `expr`.
+fn collect_scopes<'types, 'arena>(
+    expr: &Expr<'types, 'arena>,
+    ann: &AnnotatedSource<'arena, Expr<'types, 'arena>>,
+    scopes: &mut Vec<Scope>,
+) {
+    let Some(span) = ann.span_of(expr) else {
+        return;
+    };
+
+    match &expr.1 {
+        // Binding values are not searched, only the body
+        ExprInner::Where {
+            bindings,
+            expr: inner,
+        } => {
+            scopes.push(Scope {
+                end: span.0.end,
+                names: bindings.iter().map(|(name, _)| name.to_string()).collect(),
+                detail: "(where binding)",
+            });
+            collect_scopes(inner, ann, scopes);
+        }
+        ExprInner::Lambda { params, body, .. } => {
+            scopes.push(Scope {
+                end: span.0.end,
+                names: params.iter().map(|param| param.to_string()).collect(),
+                detail: "(parameter)",
+            });
+            collect_scopes(body, ann, scopes);
+        }
+        ExprInner::Binary { left, right, .. }
+        | ExprInner::Boolean { left, right, .. }
+        | ExprInner::Otherwise {
+            primary: left,
+            fallback: right,
+        }
+        | ExprInner::Coalesce {
+            option: left,
+            default: right,
+        } => {
+            collect_scopes(left, ann, scopes);
+            collect_scopes(right, ann, scopes);
+        }
+        ExprInner::Unary { expr: inner, .. } => collect_scopes(inner, ann, scopes),
+        ExprInner::Call { callable, args } => {
+            collect_scopes(callable, ann, scopes);
+            for arg in args.iter() {
+                collect_scopes(arg, ann, scopes);
+            }
+        }
+        ExprInner::If {
+            cond,
+            then_branch,
+            else_branch,
+        } => {
+            collect_scopes(cond, ann, scopes);
+            collect_scopes(then_branch, ann, scopes);
+            collect_scopes(else_branch, ann, scopes);
+        }
+        _ => {
+            // For other expression types, we don't need to recurse for completion purposes
+        }
+    }
+}
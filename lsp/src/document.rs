@@ -1,6 +1,7 @@
 use bumpalo::Bump;
 use tower_lsp::lsp_types::*;
 
+use crate::analysis::Analysis;
 use crate::semantic_tokens as st;
 
 /// Represents the state of a document being edited
@@ -9,6 +10,9 @@ pub struct DocumentState {
     /// The source code
     pub source: String,
 
+    /// The client's version of the source
+    pub version: i32,
+
     /// Tree-sitter parse tree (if parsing succeeded)
     pub tree: Option<tree_sitter::Tree>,
 
@@ -17,24 +21,42 @@ pub struct DocumentState {
 
     /// Whether the document type-checked successfully
     pub type_checked: bool,
+
+    /// Results for editor queries, kept until the source changes
+    analysis: Option<Analysis>,
+
+    /// How many times the document was parsed and type-checked
+    pub analysis_count: usize,
 }
 
 impl DocumentState {
     pub fn new(source: String) -> Self {
         Self {
             source,
+            version: 0,
             tree: None,
             diagnostics: Vec::new(),
             type_checked: false,
+            analysis: None,
+            analysis_count: 0,
         }
     }
 
-    /// Update the document with new source code
-    pub fn update(&mut self, source: String) {
+    /// Update the document to a new version of its source code.
+    ///
+    /// Returns false, keeping the current analysis, if `version` is the one
+    /// already held.
+    pub fn update(&mut self, version: i32, source: String) -> bool {
+        if version == self.version {
+            return false;
+        }
         self.source = source;
+        self.version = version;
         self.tree = None;
         self.diagnostics.clear();
         self.type_checked = false;
+        self.analysis = None;
+        true
     }
 
     /// Parse and analyze the document, returning all diagnostics
@@ -119,10 +141,14 @@ impl DocumentState {
 
     /// Analyze the document for type errors
     fn type_check(&mut self) -> Vec<Diagnostic> {
-        use melbi_core::{analyzer, parser, types::manager::TypeManager};
+        use melbi_core::{
+            analyzer, api::EnvironmentBuilder, parser, stdlib, types::manager::TypeManager,
+            values::binder::Binder,
+        };
 
         // Create arena for this analysis
         let arena = Bump::new();
+        self.analysis_count += 1;
 
         // Parse with Pest
         let parsed = match parser::parse(&arena, &self.source) {
@@ -136,18 +162,31 @@ impl DocumentState {
         // Create type manager
         let type_manager = TypeManager::new(&arena);
 
-        // Analyze with empty globals and variables for now
-        // TODO: Add support for providing globals (stdlib functions)
-        let globals: &[(&str, &_)] = &[];
+        // Analyze against the standard library, so documents using it
+        // type-check and editor queries work on them
+        let environment =
+            stdlib::register_stdlib(&arena, type_manager, EnvironmentBuilder::new(&arena))
+                .build()
+                .expect("Environment should build successfully");
+        let globals: Vec<_> = environment
+            .iter()
+            .map(|(name, value)| (*name, value.ty))
+            .collect();
         let variables: &[(&str, &_)] = &[];
 
-        match analyzer::analyze(type_manager, &arena, parsed, globals, variables) {
-            Ok(_typed_expr) => {
+        match analyzer::analyze(type_manager, &arena, parsed, &globals, variables) {
+            Ok(typed_expr) => {
                 self.type_checked = true;
-                Vec::new()
+                self.analysis = Some(Analysis::new(typed_expr));
+                analyzer::lint(typed_expr)
+                    .into_iter()
+                    .chain(analyzer::performance_hints(typed_expr))
+                    .map(|warning| self.to_lsp_diagnostic(warning))
+                    .collect()
             }
             Err(e) => {
                 self.type_checked = false;
+                self.analysis = None;
                 vec![self.error_to_diagnostic(&e)]
             }
         }
@@ -156,8 +195,11 @@ impl DocumentState {
     /// Convert a Melbi TypeError to an LSP diagnostic
     fn error_to_diagnostic(&self, error: &melbi_core::analyzer::TypeError) -> Diagnostic {
         // Use the error's built-in to_diagnostic() method
-        let diag = error.to_diagnostic();
+        self.to_lsp_diagnostic(error.to_diagnostic())
+    }
 
+    /// Convert a Melbi diagnostic (error or lint warning) to an LSP diagnostic
+    fn to_lsp_diagnostic(&self, diag: melbi_core::api::Diagnostic) -> Diagnostic {
         // Convert Span to LSP Range
         let start_pos = self.offset_to_position(diag.span.0.start);
         let end_pos = self.offset_to_position(diag.span.0.end);
@@ -232,164 +274,28 @@ impl DocumentState {
 
     /// Get hover information at a position
     pub fn hover_at_position(&self, position: Position) -> Option<String> {
-        use melbi_core::{analyzer, parser, types::manager::TypeManager};
-
         // Only provide hover if type checking succeeded
-        if !self.type_checked {
-            return None;
-        }
-
-        // Convert position to byte offset
+        let analysis = self.analysis.as_ref()?;
         let offset = self.position_to_offset(position)?;
 
-        // Re-run analysis to get typed expression
-        // TODO: Cache the typed expression to avoid re-analysis
-        let arena = Bump::new();
-        let parsed = parser::parse(&arena, &self.source).ok()?;
-        let type_manager = TypeManager::new(&arena);
-        let globals: &[(&str, &_)] = &[];
-        let variables: &[(&str, &_)] = &[];
-
-        let typed_expr =
-            analyzer::analyze(type_manager, &arena, parsed, globals, variables).ok()?;
-
-        // Find the most specific expression at the cursor position
-        let expr_at_cursor = self.find_expr_at_offset(typed_expr.expr, typed_expr.ann, offset)?;
-
-        // Only show hover for identifiers and calls - not for literals or operators
-        use melbi_core::analyzer::typed_expr::ExprInner;
-        let should_show_hover = matches!(
-            &expr_at_cursor.1,
-            ExprInner::Ident(_)
-                | ExprInner::Call { .. }
-                | ExprInner::Field { .. }
-                | ExprInner::Lambda { .. }
-                | ExprInner::Where { .. }
-                | ExprInner::If { .. }
-        );
-
-        if !should_show_hover {
-            return None;
-        }
-
-        // Format the hover response
-        let type_str = format!("{}", expr_at_cursor.0);
-        let hover_text = format!("```melbi\n{}\n```", type_str);
-
-        // TODO: When documentation support is added, append it here:
-        // if let Some(doc) = get_documentation_for_expr(expr_at_cursor) {
-        //     hover_text.push_str("\n\n---\n\n");
-        //     hover_text.push_str(doc);
-        // }
-
-        Some(hover_text)
+        // TODO: When documentation support is added, append it here
+        analysis.hover_at(offset).map(str::to_string)
     }
 
-    /// Find the most specific (smallest) expression at the given offset
-    fn find_expr_at_offset<'types, 'arena>(
-        &self,
-        expr: &'arena melbi_core::analyzer::typed_expr::Expr<'types, 'arena>,
-        ann: &'arena melbi_core::parser::AnnotatedSource<
-            'arena,
-            melbi_core::analyzer::typed_expr::Expr<'types, 'arena>,
-        >,
-        offset: usize,
-    ) -> Option<&'arena melbi_core::analyzer::typed_expr::Expr<'types, 'arena>> {
-        use melbi_core::analyzer::typed_expr::ExprInner;
-
-        // Check if this expression's span contains the offset
-        let span = ann.span_of(expr)?;
-        if !span.0.contains(&offset) {
-            return None;
-        }
-
-        // Try to find a more specific child expression
-        // If we find one, return it; otherwise return this expression
-        let child = match &expr.1 {
-            ExprInner::Binary { left, right, .. } => self
-                .find_expr_at_offset(left, ann, offset)
-                .or_else(|| self.find_expr_at_offset(right, ann, offset)),
-            ExprInner::Boolean { left, right, .. } => self
-                .find_expr_at_offset(left, ann, offset)
-                .or_else(|| self.find_expr_at_offset(right, ann, offset)),
-            ExprInner::Comparison { left, right, .. } => self
-                .find_expr_at_offset(left, ann, offset)
-                .or_else(|| self.find_expr_at_offset(right, ann, offset)),
-            ExprInner::Unary { expr: inner, .. } => self.find_expr_at_offset(inner, ann, offset),
-            ExprInner::Call { callable, args, .. } => {
-                self.find_expr_at_offset(callable, ann, offset).or_else(|| {
-                    args.iter()
-                        .find_map(|arg| self.find_expr_at_offset(arg, ann, offset))
-                })
-            }
-            ExprInner::Option { inner } => match inner {
-                Some(inner) => self.find_expr_at_offset(inner, ann, offset),
-                None => None,
-            },
-            ExprInner::Index { value, index, .. } => self
-                .find_expr_at_offset(value, ann, offset)
-                .or_else(|| self.find_expr_at_offset(index, ann, offset)),
-            ExprInner::Field { value, .. } => self.find_expr_at_offset(value, ann, offset),
-            ExprInner::Cast { expr: inner, .. } => self.find_expr_at_offset(inner, ann, offset),
-            ExprInner::Lambda { body, .. } => self.find_expr_at_offset(body, ann, offset),
-            ExprInner::If {
-                cond,
-                then_branch,
-                else_branch,
-                ..
-            } => self
-                .find_expr_at_offset(cond, ann, offset)
-                .or_else(|| self.find_expr_at_offset(then_branch, ann, offset))
-                .or_else(|| self.find_expr_at_offset(else_branch, ann, offset)),
-            ExprInner::Where {
-                expr: inner,
-                bindings,
-                ..
-            } => {
-                // Check bindings first (they're more specific)
-                bindings
-                    .iter()
-                    .find_map(|(_, binding_expr)| {
-                        self.find_expr_at_offset(binding_expr, ann, offset)
-                    })
-                    .or_else(|| self.find_expr_at_offset(inner, ann, offset))
-            }
-            ExprInner::Otherwise {
-                primary, fallback, ..
-            } => self
-                .find_expr_at_offset(primary, ann, offset)
-                .or_else(|| self.find_expr_at_offset(fallback, ann, offset)),
-            ExprInner::Record { fields, .. } => fields
-                .iter()
-                .find_map(|(_, field_expr)| self.find_expr_at_offset(field_expr, ann, offset)),
-            ExprInner::Map { elements, .. } => elements.iter().find_map(|(key, value)| {
-                self.find_expr_at_offset(key, ann, offset)
-                    .or_else(|| self.find_expr_at_offset(value, ann, offset))
-            }),
-            ExprInner::Array { elements, .. } => elements
-                .iter()
-                .find_map(|elem| self.find_expr_at_offset(elem, ann, offset)),
-            ExprInner::FormatStr { exprs, .. } => exprs
-                .iter()
-                .find_map(|e| self.find_expr_at_offset(e, ann, offset)),
-            ExprInner::Match { expr: inner, arms } => {
-                self.find_expr_at_offset(inner, ann, offset).or_else(|| {
-                    arms.iter()
-                        .find_map(|arm| self.find_expr_at_offset(arm.body, ann, offset))
-                })
-            }
-            // Leaf nodes - no children to search
-            ExprInner::Constant(_) | ExprInner::Ident(_) => None,
-        };
+    /// Get the range of the binding that the identifier at a position refers to
+    pub fn definition_at_position(&self, position: Position) -> Option<Range> {
+        let analysis = self.analysis.as_ref()?;
+        let offset = self.position_to_offset(position)?;
 
-        // Return the most specific expression found
-        child.or(Some(expr))
+        let span = analysis.definition_at(offset)?;
+        Some(Range::new(
+            self.offset_to_position(span.0.start),
+            self.offset_to_position(span.0.end),
+        ))
     }
 
     /// Get completion items at a position
     pub fn completions_at_position(&self, position: Position) -> Vec<CompletionItem> {
-        use melbi_core::{analyzer, parser, types::manager::TypeManager};
-
         // Convert position to offset to check context
         let offset = match self.position_to_offset(position) {
             Some(o) => o,
@@ -411,24 +317,15 @@ impl DocumentState {
         let mut completions = self.get_keyword_completions();
 
         // If type checking succeeded, also provide scope-based completions
-        if self.type_checked {
-            // Re-run analysis to get scope information
-            // TODO: Cache the typed expression and scope stack to avoid re-analysis
-            let arena = Bump::new();
-            if let Ok(parsed) = parser::parse(&arena, &self.source) {
-                let type_manager = TypeManager::new(&arena);
-                let globals: &[(&str, &_)] = &[];
-                let variables: &[(&str, &_)] = &[];
-
-                if let Ok(typed_expr) =
-                    analyzer::analyze(type_manager, &arena, parsed, globals, variables)
-                {
-                    // Add variable completions from scope
-                    let scope_completions =
-                        self.collect_scope_completions(typed_expr.expr, typed_expr.ann, offset);
-                    completions.extend(scope_completions);
-                }
-            }
+        if let Some(analysis) = &self.analysis {
+            completions.extend(analysis.names_in_scope(offset).into_iter().map(
+                |(name, detail)| CompletionItem {
+                    label: name.to_string(),
+                    kind: Some(CompletionItemKind::VARIABLE),
+                    detail: Some(detail.to_string()),
+                    ..Default::default()
+                },
+            ));
         }
 
         completions
@@ -505,131 +402,6 @@ impl DocumentState {
         false
     }
 
-    /// Collect completion items from identifiers in scope
-    fn collect_scope_completions<'types, 'arena>(
-        &self,
-        expr: &'arena melbi_core::analyzer::typed_expr::Expr<'types, 'arena>,
-        ann: &'arena melbi_core::parser::AnnotatedSource<
-            'arena,
-            melbi_core::analyzer::typed_expr::Expr<'types, 'arena>,
-        >,
-        offset: usize,
-    ) -> Vec<CompletionItem> {
-        use std::collections::HashSet;
-
-        let mut completions = Vec::new();
-        let mut seen = HashSet::new();
-
-        // Walk the AST and collect all identifiers that are in scope at the cursor position
-        self.collect_identifiers_in_scope(expr, ann, offset, &mut completions, &mut seen);
-
-        completions
-    }
-
-    fn collect_identifiers_in_scope<'types, 'arena>(
-        &self,
-        expr: &'arena melbi_core::analyzer::typed_expr::Expr<'types, 'arena>,
-        ann: &'arena melbi_core::parser::AnnotatedSource<
-            'arena,
-            melbi_core::analyzer::typed_expr::Expr<'types, 'arena>,
-        >,
-        offset: usize,
-        completions: &mut Vec<CompletionItem>,
-        seen: &mut std::collections::HashSet<String>,
-    ) {
-        use melbi_core::analyzer::typed_expr::ExprInner;
-
-        // Check if this expression contains the cursor position
-        let span = match ann.span_of(expr) {
-            Some(s) => s,
-            None => return,
-        };
-
-        // Only collect from expressions that contain the cursor
-        if !span.0.contains(&offset) && span.0.end < offset {
-            return;
-        }
-
-        // Collect identifiers from where bindings (they're in scope for the rest of the expression)
-        if let ExprInner::Where {
-            bindings,
-            expr: inner,
-            ..
-        } = &expr.1
-        {
-            for (name, _) in *bindings {
-                if !seen.contains(*name) {
-                    seen.insert(name.to_string());
-                    completions.push(CompletionItem {
-                        label: name.to_string(),
-                        kind: Some(CompletionItemKind::VARIABLE),
-                        detail: Some("(where binding)".to_string()),
-                        ..Default::default()
-                    });
-                }
-            }
-            // Continue searching in the inner expression
-            self.collect_identifiers_in_scope(inner, ann, offset, completions, seen);
-        }
-
-        // Collect lambda parameters
-        if let ExprInner::Lambda { params, body, .. } = &expr.1 {
-            for param in *params {
-                if !seen.contains(*param) {
-                    seen.insert(param.to_string());
-                    completions.push(CompletionItem {
-                        label: param.to_string(),
-                        kind: Some(CompletionItemKind::VARIABLE),
-                        detail: Some("(parameter)".to_string()),
-                        ..Default::default()
-                    });
-                }
-            }
-            // Continue searching in the body
-            self.collect_identifiers_in_scope(body, ann, offset, completions, seen);
-        }
-
-        // Recursively search other expression types
-        match &expr.1 {
-            ExprInner::Binary { left, right, .. } => {
-                self.collect_identifiers_in_scope(left, ann, offset, completions, seen);
-                self.collect_identifiers_in_scope(right, ann, offset, completions, seen);
-            }
-            ExprInner::Boolean { left, right, .. } => {
-                self.collect_identifiers_in_scope(left, ann, offset, completions, seen);
-                self.collect_identifiers_in_scope(right, ann, offset, completions, seen);
-            }
-            ExprInner::Unary { expr: inner, .. } => {
-                self.collect_identifiers_in_scope(inner, ann, offset, completions, seen);
-            }
-            ExprInner::Call { callable, args, .. } => {
-                self.collect_identifiers_in_scope(callable, ann, offset, completions, seen);
-                for arg in *args {
-                    self.collect_identifiers_in_scope(arg, ann, offset, completions, seen);
-                }
-            }
-            ExprInner::If {
-                cond,
-                then_branch,
-                else_branch,
-                ..
-            } => {
-                self.collect_identifiers_in_scope(cond, ann, offset, completions, seen);
-                self.collect_identifiers_in_scope(then_branch, ann, offset, completions, seen);
-                self.collect_identifiers_in_scope(else_branch, ann, offset, completions, seen);
-            }
-            ExprInner::Otherwise {
-                primary, fallback, ..
-            } => {
-                self.collect_identifiers_in_scope(primary, ann, offset, completions, seen);
-                self.collect_identifiers_in_scope(fallback, ann, offset, completions, seen);
-            }
-            _ => {
-                // For other expression types, we don't need to recurse for completion purposes
-            }
-        }
-    }
-
     /// Get semantic tokens for the entire document
     pub fn semantic_tokens(&self) -> Option<Vec<SemanticToken>> {
         let tree = self.tree.as_ref()?;
@@ -777,4 +549,23 @@ impl DocumentState {
     pub fn format(&self) -> Option<String> {
         melbi_fmt::format(&self.source, false, true).ok()
     }
+
+    /// Format the document using melbi-fmt, returning minimal edits
+    pub fn format_edits(&self) -> Option<Vec<TextEdit>> {
+        let edits =
+            melbi_fmt::format_edits(&self.source, false, true, melbi_fmt::FinalNewline::Preserve)
+                .ok()?;
+        Some(
+            edits
+                .into_iter()
+                .map(|edit| TextEdit {
+                    range: Range {
+                        start: self.offset_to_position(edit.range.start),
+                        end: self.offset_to_position(edit.range.end),
+                    },
+                    new_text: edit.new_text,
+                })
+                .collect(),
+        )
+    }
 }
@@ -1,6 +1,8 @@
 // Library interface for the Melbi Language Server
 // This module exposes the core functionality for testing
 
+pub mod analysis;
 pub mod document;
 pub mod semantic_tokens;
+pub mod server;
 pub mod helpers;
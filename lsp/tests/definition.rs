@@ -0,0 +1,48 @@
+use melbi_lsp::document::DocumentState;
+use tower_lsp::lsp_types::*;
+
+#[test]
+fn test_definition_of_where_binding() {
+    let mut doc = DocumentState::new("x + 1 where { x = 42 }".to_string());
+    doc.analyze();
+
+    let range = doc.definition_at_position(Position::new(0, 0));
+    assert_eq!(
+        range,
+        Some(Range::new(Position::new(0, 14), Position::new(0, 15)))
+    );
+}
+
+#[test]
+fn test_definition_on_another_line() {
+    let mut doc = DocumentState::new("f(1) where {\n    f = (n) => n * 2,\n}".to_string());
+    doc.analyze();
+
+    // `n` in the body refers to the lambda parameter
+    let range = doc.definition_at_position(Position::new(1, 15));
+    assert_eq!(
+        range,
+        Some(Range::new(Position::new(1, 9), Position::new(1, 10)))
+    );
+}
+
+#[test]
+fn test_definition_of_literal() {
+    let mut doc = DocumentState::new("1 + 2".to_string());
+    doc.analyze();
+
+    assert!(doc.definition_at_position(Position::new(0, 0)).is_none());
+}
+
+#[test]
+fn test_definition_in_document_using_stdlib() {
+    let mut doc = DocumentState::new("Math.Floor(x) where { x = 1.5 }".to_string());
+    doc.analyze();
+
+    assert!(doc.type_checked);
+    let range = doc.definition_at_position(Position::new(0, 11));
+    assert_eq!(
+        range,
+        Some(Range::new(Position::new(0, 22), Position::new(0, 23)))
+    );
+}
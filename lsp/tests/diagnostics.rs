@@ -31,6 +31,17 @@ fn test_valid_program_no_errors() {
     assert!(doc.type_checked, "Valid program should type-check");
 }
 
+#[test]
+fn test_constant_condition_warning() {
+    let mut doc = DocumentState::new("if true then 1 else 2".to_string());
+    let diagnostics = doc.analyze();
+
+    assert_eq!(diagnostics.len(), 1, "Should warn about constant condition");
+    assert_eq!(diagnostics[0].severity, Some(DiagnosticSeverity::WARNING));
+    assert!(diagnostics[0].message.contains("always true"));
+    assert!(doc.type_checked, "Warnings should not fail type checking");
+}
+
 #[test]
 fn test_where_expression_type_checking() {
     let mut doc = DocumentState::new(
@@ -192,3 +192,22 @@ fn test_format_idempotent() {
         "Formatting should be idempotent"
     );
 }
+
+#[test]
+fn test_format_edits_are_minimal() {
+    let doc = DocumentState::new("1 +  2".to_string());
+    let edits = doc.format_edits().expect("Should format valid expression");
+
+    // Only the doubled space before `2` changes
+    assert_eq!(edits.len(), 1);
+    assert_eq!(edits[0].range.start.line, 0);
+    assert_eq!(edits[0].range.start.character, 3);
+    assert_eq!(edits[0].range.end.character, 5);
+    assert_eq!(edits[0].new_text, " ");
+}
+
+#[test]
+fn test_format_edits_already_formatted() {
+    let doc = DocumentState::new("1 + 2".to_string());
+    assert_eq!(doc.format_edits(), Some(vec![]));
+}
@@ -128,3 +128,32 @@ fn test_hover_on_nested_expression() {
     // Inner literal, so no hover
     assert!(hover.is_none());
 }
+
+#[test]
+fn test_hover_reuses_analysis_of_same_version() {
+    let mut doc = DocumentState::new("x + 1 where { x = 41 }".to_string());
+    doc.analyze();
+    assert_eq!(doc.analysis_count, 1);
+
+    // Neither hover parses or type-checks again
+    assert!(doc.hover_at_position(Position::new(0, 0)).unwrap().contains("Int"));
+    assert!(doc.hover_at_position(Position::new(0, 0)).unwrap().contains("Int"));
+    assert_eq!(doc.analysis_count, 1);
+}
+
+#[test]
+fn test_update_reanalyzes_only_new_versions() {
+    let mut doc = DocumentState::new("x where { x = 1 }".to_string());
+    doc.analyze();
+
+    // The version already held keeps its analysis
+    assert!(!doc.update(0, "x where { x = 1 }".to_string()));
+    assert!(doc.hover_at_position(Position::new(0, 0)).is_some());
+
+    // A new version drops it until the document is analyzed again
+    assert!(doc.update(1, r#"x where { x = "one" }"#.to_string()));
+    assert!(doc.hover_at_position(Position::new(0, 0)).is_none());
+    doc.analyze();
+    assert_eq!(doc.analysis_count, 2);
+    assert!(doc.hover_at_position(Position::new(0, 0)).unwrap().contains("Str"));
+}
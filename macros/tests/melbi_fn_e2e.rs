@@ -1,5 +1,8 @@
+use std::collections::BTreeMap;
+
 use bumpalo::Bump;
 use melbi_core::{
+    api::{Engine, EngineOptions},
     evaluator::{ExecutionError, ExecutionErrorKind, RuntimeError},
     types::manager::TypeManager,
     values::{
@@ -401,6 +404,45 @@ fn array_first<'value>(
     })
 }
 
+/// Function with BTreeMap parameter - converted from a Melbi Map
+#[melbi_fn(name = DeclSumMapValues)]
+fn sum_map_values(map: BTreeMap<i64, i64>) -> i64 {
+    map.values().sum()
+}
+
+/// Function returning BTreeMap - converted to a Melbi Map
+#[melbi_fn(name = DeclSquaresMap)]
+fn squares_map(n: i64) -> BTreeMap<i64, i64> {
+    (1..=n).map(|i| (i, i * i)).collect()
+}
+
+/// Function with nested Option<Vec<T>> parameter
+#[melbi_fn(name = DeclSumOrNegative)]
+fn sum_or_negative(values: Option<Vec<i64>>) -> i64 {
+    values.map_or(-1, |values| values.iter().sum())
+}
+
+/// Function returning nested Option<Vec<T>>
+#[melbi_fn(name = DeclNonEmpty)]
+fn non_empty(values: Vec<i64>) -> Option<Vec<i64>> {
+    (!values.is_empty()).then_some(values)
+}
+
+/// Function with nested Vec<Option<T>> parameter
+#[melbi_fn(name = DeclCountPresent)]
+fn count_present(values: Vec<Option<i64>>) -> i64 {
+    values.iter().flatten().count() as i64
+}
+
+/// Function returning nested Vec<Option<T>>
+#[melbi_fn(name = DeclPositiveOnly)]
+fn positive_only(values: Vec<i64>) -> Vec<Option<i64>> {
+    values
+        .into_iter()
+        .map(|value| (value > 0).then_some(value))
+        .collect()
+}
+
 // NOTE: Only a single lifetime is supported (e.g., 'a).
 // All values in Melbi FFI share the same arena/type_mgr lifetime.
 // The proc macro should reject functions with multiple lifetimes.
@@ -1001,6 +1043,138 @@ fn test_array_first_empty_error() {
     ));
 }
 
+// 16. OWNED RUST COLLECTIONS (BTreeMap, Option, Vec)
+// Tests functions called from Melbi source, with arguments and results
+// converted from and to owned Rust collections.
+
+/// Evaluate `source` with the owned-collection functions in scope, returning
+/// the result's debug representation.
+fn eval_with_collection_functions(source: &str) -> String {
+    let arena = Bump::new();
+    let engine = Engine::new(EngineOptions::default(), &arena, |arena, type_mgr, env| {
+        env.bind(
+            "SumMapValues",
+            Value::function(arena, DeclSumMapValues::new(type_mgr)).unwrap(),
+        )
+        .bind(
+            "SquaresMap",
+            Value::function(arena, DeclSquaresMap::new(type_mgr)).unwrap(),
+        )
+        .bind(
+            "SumOrNegative",
+            Value::function(arena, DeclSumOrNegative::new(type_mgr)).unwrap(),
+        )
+        .bind(
+            "NonEmpty",
+            Value::function(arena, DeclNonEmpty::new(type_mgr)).unwrap(),
+        )
+        .bind(
+            "CountPresent",
+            Value::function(arena, DeclCountPresent::new(type_mgr)).unwrap(),
+        )
+        .bind(
+            "PositiveOnly",
+            Value::function(arena, DeclPositiveOnly::new(type_mgr)).unwrap(),
+        )
+    });
+    let expr = engine
+        .compile(Default::default(), source, &[])
+        .expect("compilation should succeed");
+    let value_arena = Bump::new();
+    let result = expr
+        .run(Default::default(), &value_arena, &[])
+        .expect("execution should succeed");
+    format!("{:?}", result)
+}
+
+#[test]
+fn test_sum_map_values_function_type() {
+    let arena = Bump::new();
+    let ctx = TestCtx::new(&arena);
+    let int = ctx.type_mgr.int();
+    let expected = ctx.type_mgr.function(&[ctx.type_mgr.map(int, int)], int);
+    assert!(core::ptr::eq(
+        DeclSumMapValues::new(ctx.type_mgr).ty(),
+        expected
+    ));
+}
+
+#[test]
+fn test_sum_map_values_from_melbi_map() {
+    assert_eq!(
+        eval_with_collection_functions("SumMapValues({1: 10, 2: 20, 3: 30}) + SumMapValues({})"),
+        "60"
+    );
+}
+
+#[test]
+fn test_squares_map_to_melbi_map() {
+    assert_eq!(
+        eval_with_collection_functions("SquaresMap(3)[2] otherwise -1"),
+        "4"
+    );
+}
+
+#[test]
+fn test_nested_generics_function_types() {
+    let arena = Bump::new();
+    let ctx = TestCtx::new(&arena);
+    let int = ctx.type_mgr.int();
+    let option_of_array = ctx.type_mgr.option(ctx.type_mgr.array(int));
+    let array_of_option = ctx.type_mgr.array(ctx.type_mgr.option(int));
+    let int_array = ctx.type_mgr.array(int);
+    assert!(core::ptr::eq(
+        DeclSumOrNegative::new(ctx.type_mgr).ty(),
+        ctx.type_mgr.function(&[option_of_array], int)
+    ));
+    assert!(core::ptr::eq(
+        DeclNonEmpty::new(ctx.type_mgr).ty(),
+        ctx.type_mgr.function(&[int_array], option_of_array)
+    ));
+    assert!(core::ptr::eq(
+        DeclCountPresent::new(ctx.type_mgr).ty(),
+        ctx.type_mgr.function(&[array_of_option], int)
+    ));
+    assert!(core::ptr::eq(
+        DeclPositiveOnly::new(ctx.type_mgr).ty(),
+        ctx.type_mgr.function(&[int_array], array_of_option)
+    ));
+}
+
+#[test]
+fn test_option_of_vec_parameter() {
+    assert_eq!(
+        eval_with_collection_functions("SumOrNegative(some [1, 2, 3])"),
+        "6"
+    );
+    assert_eq!(eval_with_collection_functions("SumOrNegative(none)"), "-1");
+}
+
+#[test]
+fn test_option_of_vec_return() {
+    assert_eq!(
+        eval_with_collection_functions("NonEmpty([4, 5])"),
+        "Some([4, 5])"
+    );
+    assert_eq!(eval_with_collection_functions("NonEmpty([])"), "None");
+}
+
+#[test]
+fn test_vec_of_option_parameter() {
+    assert_eq!(
+        eval_with_collection_functions("CountPresent([some 1, none, some 3])"),
+        "2"
+    );
+}
+
+#[test]
+fn test_vec_of_option_return() {
+    assert_eq!(
+        eval_with_collection_functions("PositiveOnly([1, -2, 3])"),
+        "[Some(1), None, Some(3)]"
+    );
+}
+
 // ============================================================================
 // 15. DERIVED NAMES (no explicit name attribute)
 // ============================================================================
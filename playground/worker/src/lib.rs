@@ -1,9 +1,7 @@
 use bumpalo::Bump;
 use js_sys::JSON;
-use melbi_core::api::{
-    Diagnostic as CoreDiagnostic, Engine, EngineOptions, Error, RelatedInfo, Severity,
-};
-use melbi_core::parser::Span;
+use melbi_core::api::{Engine, EngineOptions, Error};
+use melbi_core::diagnostics::json::{JsonDiagnostic, Utf16Index};
 use melbi_core::stdlib;
 use melbi_core::values::dynamic::Value;
 use serde::Serialize;
@@ -97,29 +95,9 @@ impl<T> WorkerResponse<T> {
 pub struct WorkerError {
     kind: &'static str,
     message: String,
-    diagnostics: Option<Vec<DiagnosticPayload>>,
-}
-
-#[derive(Serialize)]
-pub struct DiagnosticPayload {
-    severity: &'static str,
-    message: String,
-    span: RangePayload,
-    help: Option<String>,
-    code: Option<String>,
-    related: Vec<RelatedInfoPayload>,
-}
-
-#[derive(Serialize)]
-pub struct RelatedInfoPayload {
-    span: RangePayload,
-    message: String,
-}
-
-#[derive(Serialize)]
-pub struct RangePayload {
-    start: usize,
-    end: usize,
+    /// Diagnostics in the `melbi_core::diagnostics::json` schema, with spans
+    /// in UTF-16 code units.
+    diagnostics: Option<Vec<JsonDiagnostic>>,
 }
 
 #[derive(Serialize)]
@@ -151,76 +129,50 @@ impl From<Error> for WorkerError {
                 message,
                 diagnostics: None,
             },
-            Error::Compilation { diagnostics, .. } => WorkerError {
-                kind: "compilation",
-                message: format!(
-                    "Compilation failed with {} diagnostic(s)",
-                    diagnostics.len()
-                ),
-                diagnostics: Some(
-                    diagnostics
-                        .into_iter()
-                        .map(DiagnosticPayload::from)
-                        .collect(),
-                ),
-            },
-            Error::Runtime { diagnostic, .. } => WorkerError {
-                kind: "runtime",
-                message: diagnostic.message.clone(),
-                diagnostics: Some(vec![DiagnosticPayload::from(diagnostic)]),
-            },
+            Error::Compilation {
+                diagnostics,
+                source,
+                ..
+            } => {
+                let index = Utf16Index::new(&source);
+                WorkerError {
+                    kind: "compilation",
+                    message: format!(
+                        "Compilation failed with {} diagnostic(s)",
+                        diagnostics.len()
+                    ),
+                    diagnostics: Some(
+                        diagnostics
+                            .iter()
+                            .map(|diag| index.diagnostic_to_json(diag))
+                            .collect(),
+                    ),
+                }
+            }
+            Error::Runtime {
+                diagnostic, source, ..
+            } => {
+                let index = Utf16Index::new(&source);
+                WorkerError {
+                    kind: "runtime",
+                    message: diagnostic.message.clone(),
+                    diagnostics: Some(vec![index.diagnostic_to_json(&diagnostic)]),
+                }
+            }
             Error::ResourceExceeded(message) => WorkerError {
                 kind: "resource_exceeded",
                 message,
                 diagnostics: None,
             },
+            Error::RuntimeMismatch { .. } => WorkerError {
+                kind: "runtime_mismatch",
+                message: err.to_string(),
+                diagnostics: None,
+            },
         }
     }
 }
 
-impl From<CoreDiagnostic> for DiagnosticPayload {
-    fn from(diag: CoreDiagnostic) -> Self {
-        Self {
-            severity: severity_to_str(diag.severity),
-            message: diag.message,
-            span: RangePayload::from(diag.span),
-            help: diag.help.get(0).map(|s| s.clone()),
-            code: diag.code,
-            related: diag
-                .related
-                .into_iter()
-                .map(RelatedInfoPayload::from)
-                .collect(),
-        }
-    }
-}
-
-impl From<RelatedInfo> for RelatedInfoPayload {
-    fn from(info: RelatedInfo) -> Self {
-        Self {
-            span: RangePayload::from(info.span),
-            message: info.message,
-        }
-    }
-}
-
-impl From<Span> for RangePayload {
-    fn from(span: Span) -> Self {
-        RangePayload {
-            start: span.0.start,
-            end: span.0.end,
-        }
-    }
-}
-
-fn severity_to_str(severity: Severity) -> &'static str {
-    match severity {
-        Severity::Error => "error",
-        Severity::Warning => "warning",
-        Severity::Info => "info",
-    }
-}
-
 fn to_js_value<T: Serialize>(value: &T) -> Result<JsValue, JsValue> {
     let serialized = serde_json::to_string(value)
         .map_err(|err| JsValue::from_str(&format!("serialization error: {}", err)))?;
@@ -244,4 +196,27 @@ mod tests {
             WorkerResponse::Err { error } => panic!("evaluation failed: {}", error.message),
         }
     }
+
+    #[test]
+    fn diagnostic_after_emoji_reports_utf16_offsets() {
+        let arena = Bump::new();
+        let engine = Engine::new(EngineOptions::default(), &arena, stdlib::register_stdlib);
+        // `"😀"` is 6 bytes but 4 UTF-16 units, so `true` starts at byte 9 / unit 7.
+        let source = "\"😀\" + true";
+        let Err(error) = engine.compile(Default::default(), source, &[]) else {
+            panic!("expected a type error");
+        };
+
+        let error = WorkerError::from(error);
+
+        let diagnostics = error.diagnostics.expect("expected diagnostics");
+        let diagnostic = serde_json::to_value(&diagnostics[0]).unwrap();
+        let span = &diagnostic["span"];
+        let start = span["start"].as_u64().unwrap() as usize;
+        let end = span["end"].as_u64().unwrap() as usize;
+        let js_source: Vec<u16> = source.encode_utf16().collect();
+        let highlighted = String::from_utf16(&js_source[start..end]).unwrap();
+        assert_eq!((start, end), (7, 11));
+        assert_eq!(highlighted, "true");
+    }
 }
@@ -5,17 +5,10 @@
 
 use crate::{Diagnostic, Error, Severity};
 use ariadne::{ColorGenerator, Label, Report, ReportKind, Source};
+use melbi_core::diagnostics::render;
 use std::io::Write;
 
-/// Character set for rendering error messages.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
-pub enum CharSet {
-    /// Use Unicode characters for rich visual output.
-    #[default]
-    Unicode,
-    /// Use ASCII-only characters for compatibility.
-    Ascii,
-}
+pub use melbi_core::diagnostics::render::CharSet;
 
 /// Configuration for error rendering.
 #[derive(Debug, Clone)]
@@ -114,12 +107,12 @@ pub fn render_error_to(
         Error::Runtime {
             diagnostic, source, ..
         } => render_diagnostics(source, &[diagnostic.clone()], writer, config, filename),
-        Error::ResourceExceeded(msg) => {
-            writeln!(writer, "Resource limit exceeded: {}", msg)
-        }
-        Error::Api(msg) => {
-            writeln!(writer, "API error: {}", msg)
-        }
+        // No source snippet to show, so the plain core renderer is enough.
+        Error::ResourceExceeded(_) | Error::Api(_) | Error::RuntimeMismatch { .. } => write!(
+            writer,
+            "{}",
+            render::render_error_to_string(error, Some(filename), config.charset)
+        ),
     }
 }
 
@@ -372,6 +365,30 @@ mod tests {
         );
     }
 
+    fn check_matches_core_renderer(source: &str, config: &RenderConfig) {
+        let arena = Bump::new();
+        let engine = Engine::new(EngineOptions::default(), &arena, |_, _, env| env);
+        let error = match engine.compile(Default::default(), source, &[]) {
+            Err(e) => e,
+            Ok(_) => panic!("Expected compilation error for source: {source}"),
+        };
+
+        let mut buf = Vec::new();
+        render_error_to(&error, &mut buf, config).unwrap();
+        let ariadne_output = String::from_utf8(buf).unwrap();
+        let core_output = render::render_error_to_string(&error, config.filename, config.charset);
+
+        assert_eq!(core_output, ariadne_output);
+    }
+
+    #[test]
+    fn test_core_renderer_matches_ariadne() {
+        for source in ["1 + + 2", "1 + true", "foo + 1", "{ x = 1"] {
+            check_matches_core_renderer(source, &UNICODE_CONFIG);
+            check_matches_core_renderer(source, &ASCII_CONFIG);
+        }
+    }
+
     #[test]
     fn test_charset_default_is_unicode() {
         assert_eq!(CharSet::default(), CharSet::Unicode);
@@ -67,6 +67,14 @@ impl<B: TyBuilder> Ty<B> {
     pub fn flags(&self) -> TyFlags {
         self.node().flags()
     }
+
+    /// Returns true if the type contains no type variables.
+    ///
+    /// O(1): reads the cached [`TyFlags::HAS_TYPE_VARS`] bit instead of
+    /// traversing the type.
+    pub fn is_concrete(&self) -> bool {
+        !self.flags().contains(TyFlags::HAS_TYPE_VARS)
+    }
 }
 
 // Implement Copy when TyHandle is Copy (e.g., for ArenaBuilder)
@@ -415,3 +423,31 @@ impl<'a, B: TyBuilder> IntoIterator for &'a FieldList<B> {
         self.0.iter()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{ArenaBuilder, BoxBuilder, ty};
+    use bumpalo::Bump;
+
+    #[test]
+    fn test_nested_concrete_type_is_concrete() {
+        let arena = Bump::new();
+        let b = ArenaBuilder::new(&arena);
+        let t = ty!(b, (Record[tags: Array[Str], scores: Map[Str, Float]], Int) => Array[Bool]);
+        assert!(t.is_concrete());
+
+        let b = BoxBuilder::new();
+        let t = ty!(b, Map[Str, Array[Array[Int]]]);
+        assert!(t.is_concrete());
+    }
+
+    #[test]
+    fn test_type_with_variable_is_not_concrete() {
+        let arena = Bump::new();
+        let b = ArenaBuilder::new(&arena);
+        assert!(!ty!(b, [a] => a).is_concrete());
+        assert!(!ty!(b, [a] => Record[items: Array[Map[Str, a]]]).is_concrete());
+        assert!(!ty!(b, [a] => (Int) => a).is_concrete());
+        assert!(!ty!(b, [a] => (a) => Int).is_concrete());
+    }
+}